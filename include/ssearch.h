@@ -0,0 +1,46 @@
+/*
+ * C API of ssearch (built with the `ffi` feature):
+ *
+ *   cargo rustc --release --lib --features ffi --crate-type cdylib
+ *
+ * Strings are UTF-8 and NUL-terminated. Strings returned by ssearch_search
+ * and ssearch_index belong to the caller and are freed with
+ * ssearch_string_free. Functions returning a pointer return NULL on failure;
+ * ssearch_last_error then describes the error.
+ */
+#ifndef SSEARCH_H
+#define SSEARCH_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct SsearchHandle SsearchHandle;
+
+/* Load config_path alone, or the global and project config when NULL,
+ * and connect to the vector store. */
+SsearchHandle *ssearch_init(const char *config_path);
+
+/* Search results as JSON: {"query", "results", "total", "duration_ms"}.
+ * limit 0 uses [search] default_limit; tags is NULL or "key:value,...". */
+char *ssearch_search(const SsearchHandle *handle, const char *query, uint32_t limit,
+                     const char *tags);
+
+/* Index the text files under path as JSON: {"documents_indexed",
+ * "documents_skipped", "chunks_created", "chunks_stored"}. */
+char *ssearch_index(const SsearchHandle *handle, const char *path, const char *tags);
+
+/* Error of the last failed call on this thread, owned by the library. */
+const char *ssearch_last_error(void);
+
+void ssearch_string_free(char *s);
+
+void ssearch_free(SsearchHandle *handle);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* SSEARCH_H */
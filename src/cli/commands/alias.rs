@@ -0,0 +1,256 @@
+//! Alias command: save searches under a name and run them again.
+
+use anyhow::{Context, Result};
+use clap::{Args, FromArgMatches, Subcommand};
+
+use super::collection::{target_config_path, update_config_file};
+use super::search::{SearchArgs, handle_search};
+use crate::cli::output::get_formatter;
+use crate::models::{Config, OutputFormat, SavedQuery};
+
+/// Alias subcommands.
+#[derive(Debug, Subcommand)]
+pub enum AliasCommand {
+    /// Save a search under a name
+    Add {
+        /// Alias name (letters, digits, '_' and '-')
+        #[arg(required = true)]
+        name: String,
+
+        /// Search query text
+        #[arg(required = true)]
+        query: String,
+
+        /// Filter by tags (e.g., "team:platform,env:prod")
+        #[arg(long, short = 't')]
+        tags: Option<String>,
+
+        /// Filter by source type (e.g., "local,confluence")
+        #[arg(long, short = 's')]
+        source: Option<String>,
+
+        /// Filter by programming language (e.g., "rust,python")
+        #[arg(long = "lang", value_name = "LANGS")]
+        languages: Option<String>,
+
+        /// Filter expression, as for `search --filter` (repeatable)
+        #[arg(long = "filter", value_name = "EXPR")]
+        filters: Vec<String>,
+
+        /// Project-relative path or glob, as for `search --path` (repeatable)
+        #[arg(long = "path", value_name = "PATH")]
+        paths: Vec<String>,
+
+        /// Only results updated since a date or age (e.g., "2024-01-01", "30d")
+        #[arg(long, value_name = "WHEN")]
+        since: Option<String>,
+
+        /// Maximum number of results to return
+        #[arg(long, short = 'n')]
+        limit: Option<u32>,
+
+        /// Minimum similarity score (0.0-1.0)
+        #[arg(long)]
+        min_score: Option<f32>,
+
+        /// Combine vector search with keyword matching
+        #[arg(long)]
+        hybrid: bool,
+
+        /// Collection to search instead of the active one
+        #[arg(long)]
+        collection: Option<String>,
+
+        /// Save to the global config instead of the project config
+        #[arg(long, short = 'g')]
+        global: bool,
+    },
+
+    /// List saved searches
+    List,
+
+    /// Delete a saved search
+    Remove {
+        #[arg(required = true)]
+        name: String,
+    },
+
+    /// Run a saved search; extra `search` options override the saved ones
+    Run {
+        #[arg(required = true)]
+        name: String,
+
+        /// Extra `search` options (e.g., `--limit 20 --hybrid`)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+}
+
+/// Handle the alias command.
+pub async fn handle_alias(cmd: AliasCommand, format: OutputFormat, verbose: bool) -> Result<()> {
+    let formatter = get_formatter(format);
+
+    match cmd {
+        AliasCommand::Add {
+            name,
+            query,
+            tags,
+            source,
+            languages,
+            filters,
+            paths,
+            since,
+            limit,
+            min_score,
+            hybrid,
+            collection,
+            global,
+        } => {
+            validate_name(&name)?;
+            if query.trim().is_empty() {
+                anyhow::bail!("search query cannot be empty");
+            }
+            let saved = SavedQuery {
+                query,
+                tags,
+                source,
+                languages,
+                filters,
+                paths,
+                since,
+                limit,
+                min_score,
+                hybrid,
+                collection,
+            };
+            // Parse once so a bad option fails now rather than on every run
+            search_args(&saved, &[])?;
+
+            let path = target_config_path(global)?;
+            update_config_file(&path, |partial| {
+                partial
+                    .aliases
+                    .get_or_insert_default()
+                    .insert(name.clone(), saved);
+            })?;
+            println!(
+                "{}",
+                formatter.format_message(&format!(
+                    "Saved alias '{}' (saved to {})",
+                    name,
+                    path.display()
+                ))
+            );
+        }
+        AliasCommand::List => {
+            let config = Config::load()?.config;
+            print!("{}", formatter.format_aliases(&config.aliases));
+        }
+        AliasCommand::Remove { name } => {
+            let resolved = Config::load()?;
+            let mut removed = false;
+            for path in [resolved.project_path, resolved.global_path]
+                .into_iter()
+                .flatten()
+            {
+                let partial = Config::load_partial(&path)?;
+                if partial
+                    .aliases
+                    .as_ref()
+                    .is_some_and(|a| a.contains_key(&name))
+                {
+                    update_config_file(&path, |partial| {
+                        if let Some(ref mut aliases) = partial.aliases {
+                            aliases.remove(&name);
+                        }
+                    })?;
+                    removed = true;
+                }
+            }
+            if !removed {
+                anyhow::bail!("no alias named '{}'", name);
+            }
+            println!(
+                "{}",
+                formatter.format_message(&format!("Removed alias '{}'", name))
+            );
+        }
+        AliasCommand::Run { name, args } => {
+            let config = Config::load()?.config;
+            let saved = config.aliases.get(&name).with_context(|| {
+                format!(
+                    "no alias named '{}'; list them with `ssearch alias list`",
+                    name
+                )
+            })?;
+            let search = search_args(saved, &args)?;
+            handle_search(search, format, verbose).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Parse a saved search into `search` arguments, `extra` options taking
+/// precedence over the saved ones.
+fn search_args(saved: &SavedQuery, extra: &[String]) -> Result<SearchArgs> {
+    let command = SearchArgs::augment_args(clap::Command::new("search")).args_override_self(true);
+    let argv = std::iter::once("search".to_string())
+        .chain(saved.search_options())
+        .chain(extra.iter().cloned())
+        .chain(["--".to_string(), saved.query.clone()]);
+    let matches = command
+        .try_get_matches_from(argv)
+        .context("invalid search options")?;
+    Ok(SearchArgs::from_arg_matches(&matches)?)
+}
+
+fn validate_name(name: &str) -> Result<()> {
+    let valid_chars = name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if name.is_empty() || !valid_chars {
+        anyhow::bail!(
+            "invalid alias name '{}': use letters, digits, '_' and '-'",
+            name
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alias_search_args() {
+        let saved = SavedQuery {
+            query: "kubernetes deployment".to_string(),
+            tags: Some("team:platform".to_string()),
+            filters: vec!["language=rust".to_string()],
+            limit: Some(5),
+            hybrid: true,
+            ..Default::default()
+        };
+        let args = search_args(&saved, &[]).unwrap();
+        assert_eq!(args.query, "kubernetes deployment");
+        assert_eq!(args.tags.as_deref(), Some("team:platform"));
+        assert_eq!(args.filters, vec!["language=rust"]);
+        assert_eq!(args.limit, Some(5));
+        assert!(args.hybrid);
+
+        let extra = ["--limit", "20", "--filter", "path~docs/**"].map(String::from);
+        let args = search_args(&saved, &extra).unwrap();
+        assert_eq!(args.limit, Some(20));
+        assert_eq!(args.filters.len(), 2);
+
+        assert!(search_args(&saved, &["--bogus".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_alias_names() {
+        assert!(validate_name("deploy-docs").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("deploy docs").is_err());
+    }
+}
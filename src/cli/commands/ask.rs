@@ -0,0 +1,196 @@
+use anyhow::{Context, Result};
+use clap::Args;
+use clap_complete::ArgValueCompleter;
+use std::time::Instant;
+
+use crate::cli::completion::{complete_sources, complete_tags};
+use crate::cli::output::get_formatter;
+use crate::models::{Answer, Config, OutputFormat, SearchFilter, SourceType, Tag, parse_tags};
+use crate::services::fusion::rescore;
+use crate::services::{
+    EmbeddingClient, LlmClient, SYSTEM_PROMPT, build_prompt, check_dimension, create_backend,
+};
+
+#[derive(Debug, Args)]
+pub struct AskArgs {
+    #[arg(required = true, help = "Question to answer from the indexed content")]
+    pub question: String,
+
+    #[arg(
+        long,
+        short = 'n',
+        help = "Number of chunks to pass to the LLM (default: [llm] context_chunks)"
+    )]
+    pub limit: Option<u32>,
+
+    #[arg(
+        long,
+        short = 't',
+        add = ArgValueCompleter::new(complete_tags),
+        help = "Filter by tags (e.g., 'source:confluence,space:common')"
+    )]
+    pub tags: Option<String>,
+
+    #[arg(
+        long,
+        short = 's',
+        add = ArgValueCompleter::new(complete_sources),
+        help = "Filter by source type (e.g., 'local,confluence,jira')"
+    )]
+    pub source: Option<String>,
+
+    #[arg(
+        long,
+        help = "Combine semantic similarity with keyword (BM25) matching for exact identifiers"
+    )]
+    pub hybrid: bool,
+
+    #[arg(
+        long,
+        conflicts_with = "no_rerank",
+        help = "Re-score top candidates with the cross-encoder reranker"
+    )]
+    pub rerank: bool,
+
+    #[arg(long, help = "Disable reranking even if enabled in config")]
+    pub no_rerank: bool,
+
+    #[arg(long, help = "LLM model to use instead of [llm] model")]
+    pub model: Option<String>,
+
+    #[arg(long, help = "Collection to search instead of the configured one")]
+    pub collection: Option<String>,
+
+    #[arg(
+        long,
+        help = "Search content indexed from every workspace, not just the current project"
+    )]
+    pub all_workspaces: bool,
+}
+
+pub async fn handle_ask(args: AskArgs, format: OutputFormat, verbose: bool) -> Result<()> {
+    let question = args.question.trim();
+    if question.is_empty() {
+        anyhow::bail!("question cannot be empty");
+    }
+
+    let config = Config::load_with_collection(args.collection.as_deref())?.config;
+    let formatter = get_formatter(format);
+    let start_time = Instant::now();
+
+    let limit = args.limit.unwrap_or(config.llm.context_chunks);
+    if limit == 0 {
+        anyhow::bail!("limit must be at least 1");
+    }
+
+    let tags: Vec<Tag> = args
+        .tags
+        .as_ref()
+        .map(|s| parse_tags(s))
+        .transpose()
+        .context("failed to parse tags")?
+        .unwrap_or_default();
+
+    let source_types: Vec<SourceType> = args
+        .source
+        .as_deref()
+        .map(|s| {
+            s.split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(|s| s.parse().unwrap())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let workspace = config.workspace().filter(|_| !args.all_workspaces);
+    let filter = SearchFilter::new()
+        .with_tags(tags)
+        .with_source_types(source_types)
+        .with_workspace(workspace);
+
+    let mut llm = LlmClient::new(&config.llm);
+    if let Some(model) = args.model {
+        llm = llm.with_model(model);
+    }
+
+    let rerank = !args.no_rerank && (args.rerank || config.search.rerank);
+    let candidates = if rerank {
+        limit.max(config.search.rerank_top_k)
+    } else {
+        limit
+    };
+
+    let embedding_client = EmbeddingClient::new(&config);
+    let vector_store = create_backend(&config.vector_store).await?;
+    check_dimension(vector_store.as_ref(), u64::from(config.embedding.dimension)).await?;
+
+    let query_embedding = embedding_client
+        .embed_query(question)
+        .await
+        .context("failed to generate query embedding")?;
+
+    let search_start = Instant::now();
+    let mut results = if args.hybrid {
+        vector_store
+            .hybrid_search(
+                query_embedding,
+                question,
+                u64::from(candidates),
+                0,
+                &filter,
+                None,
+            )
+            .await
+    } else {
+        vector_store
+            .search(query_embedding, u64::from(candidates), 0, &filter, None)
+            .await
+    }
+    .context("search failed")?;
+
+    if rerank && !results.is_empty() {
+        let documents: Vec<String> = results.iter().map(|r| r.content.clone()).collect();
+        let scores = embedding_client
+            .rerank(question, documents)
+            .await
+            .context("failed to rerank results")?;
+        results = rescore(results, &scores, limit as usize);
+    }
+    results.truncate(limit as usize);
+    let search_ms = search_start.elapsed().as_millis();
+
+    if results.is_empty() {
+        print!(
+            "{}",
+            formatter.format_message("No indexed content matches the question")
+        );
+        return Ok(());
+    }
+
+    if verbose {
+        eprintln!("Question: \"{question}\"");
+        eprintln!("  Sources: {} ({search_ms}ms)", results.len());
+        eprintln!("  Model: {} ({})", llm.model(), config.llm.provider);
+    }
+
+    let llm_start = Instant::now();
+    let prompt = build_prompt(question, &results);
+    let reply = llm.chat(SYSTEM_PROMPT, &prompt).await?;
+
+    if verbose {
+        eprintln!("  Generation: {}ms", llm_start.elapsed().as_millis());
+        eprintln!();
+    }
+
+    let answer = Answer {
+        question: question.to_string(),
+        answer: reply,
+        model: llm.model().to_string(),
+        sources: results,
+        duration_ms: start_time.elapsed().as_millis() as u64,
+    };
+    print!("{}", formatter.format_answer(&answer));
+
+    Ok(())
+}
@@ -0,0 +1,654 @@
+//! Collection command implementation.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::Subcommand;
+use indicatif::{ProgressBar, ProgressStyle};
+
+use crate::cli::output::{CollectionSummary, Formatter, format_bytes, get_formatter};
+use crate::models::{CollectionConfig, Config, Deleted, OutputFormat, PartialConfig, SearchFilter};
+use crate::services::{
+    EmbeddingClient, IndexPipeline, create_backend, create_backend_with_embedding_config,
+    resend_stored_chunks,
+};
+
+/// Collection subcommands.
+#[derive(Debug, Subcommand)]
+pub enum CollectionCommand {
+    /// Create a named collection
+    Create {
+        /// Collection name (letters, digits, '_' and '-')
+        #[arg(required = true)]
+        name: String,
+
+        /// Embedding model for this collection (defaults to [embedding] model_id)
+        #[arg(long)]
+        model: Option<String>,
+
+        /// Embedding dimension of the model
+        #[arg(long)]
+        dimension: Option<u32>,
+
+        /// Save to the global config instead of the project config
+        #[arg(long, short = 'g')]
+        global: bool,
+    },
+
+    /// List collections with their point counts
+    List,
+
+    /// Delete a collection and its indexed documents
+    Delete {
+        #[arg(required = true)]
+        name: String,
+
+        /// Skip confirmation prompt
+        #[arg(long, short = 'y')]
+        force: bool,
+    },
+
+    /// Back up a collection to the cache directory (Qdrant snapshot or PostgreSQL table copy)
+    Snapshot {
+        /// Collection to back up (defaults to the active one)
+        name: Option<String>,
+    },
+
+    /// Replace a collection's contents with a snapshot
+    Restore {
+        /// Collection to restore (defaults to the active one)
+        name: Option<String>,
+
+        /// Snapshot file to restore [default: the collection's latest snapshot]
+        #[arg(long, value_name = "FILE")]
+        from: Option<PathBuf>,
+
+        /// Skip confirmation prompt
+        #[arg(long, short = 'y')]
+        force: bool,
+    },
+
+    /// Copy a collection into a new one, e.g. after switching embedding models
+    Migrate {
+        /// Collection to migrate (defaults to the active one)
+        name: Option<String>,
+
+        /// Re-embed the stored content with the configured model instead of copying vectors
+        #[arg(long)]
+        re_embed: bool,
+
+        /// Name of the new collection [default: <name>_<dimension>]
+        #[arg(long, value_name = "NAME")]
+        to: Option<String>,
+
+        /// Make the new collection the active one once it is filled
+        #[arg(long = "use")]
+        activate: bool,
+
+        /// Save to the global config instead of the project config
+        #[arg(long, short = 'g')]
+        global: bool,
+    },
+
+    /// Make a collection the default for all commands
+    Use {
+        #[arg(required = true)]
+        name: String,
+
+        /// Save to the global config instead of the project config
+        #[arg(long, short = 'g')]
+        global: bool,
+    },
+}
+
+/// Handle the collection command.
+pub async fn handle_collection(
+    cmd: CollectionCommand,
+    format: OutputFormat,
+    _verbose: bool,
+) -> Result<()> {
+    let formatter = get_formatter(format);
+
+    match cmd {
+        CollectionCommand::Create {
+            name,
+            model,
+            dimension,
+            global,
+        } => {
+            let overrides = CollectionConfig {
+                model_id: model,
+                model_path: None,
+                dimension,
+            };
+            handle_create(formatter.as_ref(), &name, overrides, global).await
+        }
+        CollectionCommand::List => handle_list(formatter.as_ref()).await,
+        CollectionCommand::Delete { name, force } => {
+            handle_delete(formatter.as_ref(), &name, force).await
+        }
+        CollectionCommand::Snapshot { name } => {
+            handle_snapshot(formatter.as_ref(), name.as_deref()).await
+        }
+        CollectionCommand::Restore { name, from, force } => {
+            handle_restore(formatter.as_ref(), name.as_deref(), from, force).await
+        }
+        CollectionCommand::Migrate {
+            name,
+            re_embed,
+            to,
+            activate,
+            global,
+        } => {
+            let options = MigrateOptions {
+                re_embed,
+                to,
+                activate,
+                global,
+            };
+            handle_migrate(formatter.as_ref(), name.as_deref(), options).await
+        }
+        CollectionCommand::Use { name, global } => handle_use(formatter.as_ref(), &name, global),
+    }
+}
+
+async fn handle_create(
+    formatter: &dyn Formatter,
+    name: &str,
+    overrides: CollectionConfig,
+    global: bool,
+) -> Result<()> {
+    let mut config = Config::load_with_collection(Some(name))?.config;
+    config.vector_store.driver.validate_collection_name(name)?;
+
+    if config.collections.contains_key(name) {
+        anyhow::bail!("collection '{}' already exists", name);
+    }
+
+    if let Some(ref model_id) = overrides.model_id {
+        config.embedding.model_id = model_id.clone();
+        config.embedding.model_path = None;
+    }
+    if let Some(dimension) = overrides.dimension {
+        config.embedding.dimension = dimension;
+    }
+
+    let vector_store =
+        create_backend_with_embedding_config(&config.vector_store, &config.embedding).await?;
+    vector_store
+        .create_collection()
+        .await
+        .context("failed to create collection")?;
+
+    let path = target_config_path(global)?;
+    update_config_file(&path, |partial| {
+        partial
+            .collections
+            .get_or_insert_default()
+            .insert(name.to_string(), overrides);
+    })?;
+
+    println!(
+        "{}",
+        formatter.format_message(&format!(
+            "Created collection '{}' ({}, {}d) in {}",
+            name,
+            config.embedding.model_id,
+            config.embedding.dimension,
+            path.display()
+        ))
+    );
+    Ok(())
+}
+
+async fn handle_list(formatter: &dyn Formatter) -> Result<()> {
+    let config = Config::load()?.config;
+    let active = config.vector_store.collection.clone();
+
+    let mut names: Vec<String> = config.collections.keys().cloned().collect();
+    if !names.contains(&active) {
+        names.push(active.clone());
+        names.sort();
+    }
+
+    let mut collections = Vec::with_capacity(names.len());
+    for name in names {
+        let config = Config::load_with_collection(Some(&name))?.config;
+        let vector_store =
+            create_backend_with_embedding_config(&config.vector_store, &config.embedding).await?;
+        let info = vector_store
+            .get_collection_info()
+            .await
+            .context("failed to read collection info")?;
+
+        collections.push(CollectionSummary {
+            active: name == active,
+            name,
+            model_id: config.embedding.model_id,
+            dimension: config.embedding.dimension,
+            points: info.map(|i| i.points_count),
+        });
+    }
+
+    print!("{}", formatter.format_collections(&collections));
+    Ok(())
+}
+
+async fn handle_delete(formatter: &dyn Formatter, name: &str, force: bool) -> Result<()> {
+    let resolved = Config::load_with_collection(Some(name))?;
+    let config = &resolved.config;
+
+    if !force {
+        println!(
+            "This will delete collection '{}' and all of its documents. Continue? [y/N]",
+            name
+        );
+        let mut input = String::new();
+        std::io::stdin().read_line(&mut input)?;
+        if !input.trim().eq_ignore_ascii_case("y") {
+            println!("{}", formatter.format_message("Cancelled."));
+            return Ok(());
+        }
+    }
+
+    let vector_store =
+        create_backend_with_embedding_config(&config.vector_store, &config.embedding).await?;
+    vector_store
+        .delete_collection()
+        .await
+        .context("failed to delete collection")?;
+
+    for path in [resolved.project_path, resolved.global_path]
+        .into_iter()
+        .flatten()
+    {
+        let partial = Config::load_partial(&path)?;
+        if partial
+            .collections
+            .as_ref()
+            .is_some_and(|c| c.contains_key(name))
+        {
+            update_config_file(&path, |partial| {
+                if let Some(ref mut collections) = partial.collections {
+                    collections.remove(name);
+                }
+            })?;
+        }
+    }
+
+    println!(
+        "{}",
+        formatter.format_message(&format!("Deleted collection '{}'", name))
+    );
+
+    if Config::load()?.config.vector_store.collection == name {
+        println!(
+            "{}",
+            formatter.format_message(
+                "This was the active collection. Run 'ssearch collection use <name>' to switch."
+            )
+        );
+    }
+    Ok(())
+}
+
+async fn handle_snapshot(formatter: &dyn Formatter, name: Option<&str>) -> Result<()> {
+    let config = Config::load_with_collection(name)?.config;
+    let vector_store =
+        create_backend_with_embedding_config(&config.vector_store, &config.embedding).await?;
+    let collection = vector_store.collection().to_string();
+    if vector_store.get_collection_info().await?.is_none() {
+        anyhow::bail!("collection '{}' does not exist", collection);
+    }
+
+    let dir = Config::snapshots_dir(&collection)
+        .ok_or_else(|| anyhow::anyhow!("could not determine cache directory"))?;
+    std::fs::create_dir_all(&dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    let path = dir.join(format!(
+        "{}.{}",
+        chrono::Utc::now().format(SNAPSHOT_TIMESTAMP),
+        vector_store.snapshot_extension()
+    ));
+
+    if let Err(e) = vector_store.create_snapshot(&path).await {
+        let _ = std::fs::remove_file(&path);
+        return Err(e).context("failed to create snapshot");
+    }
+    let size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+
+    println!(
+        "{}",
+        formatter.format_message(&format!(
+            "Saved snapshot of '{}' to {} ({})",
+            collection,
+            path.display(),
+            format_bytes(size)
+        ))
+    );
+    Ok(())
+}
+
+async fn handle_restore(
+    formatter: &dyn Formatter,
+    name: Option<&str>,
+    from: Option<PathBuf>,
+    force: bool,
+) -> Result<()> {
+    let config = Config::load_with_collection(name)?.config;
+    let vector_store =
+        create_backend_with_embedding_config(&config.vector_store, &config.embedding).await?;
+    let collection = vector_store.collection().to_string();
+    let extension = vector_store.snapshot_extension();
+
+    let path = match from {
+        Some(path) => path,
+        None => Config::snapshots_dir(&collection)
+            .and_then(|dir| latest_snapshot(&dir, extension))
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "no snapshots of '{}'. Run 'ssearch collection snapshot' first.",
+                    collection
+                )
+            })?,
+    };
+    if path.extension().and_then(|e| e.to_str()) != Some(extension) {
+        anyhow::bail!(
+            "{} is not a .{} snapshot for the {} backend",
+            path.display(),
+            extension,
+            config.vector_store.driver
+        );
+    }
+
+    if !force {
+        println!(
+            "This will replace all documents in '{}' with {}. Continue? [y/N]",
+            collection,
+            path.display()
+        );
+        let mut input = String::new();
+        std::io::stdin().read_line(&mut input)?;
+        if !input.trim().eq_ignore_ascii_case("y") {
+            println!("{}", formatter.format_message("Cancelled."));
+            return Ok(());
+        }
+    }
+
+    vector_store
+        .restore_snapshot(&path)
+        .await
+        .with_context(|| format!("failed to restore {}", path.display()))?;
+    let points = vector_store
+        .get_collection_info()
+        .await?
+        .map(|info| info.points_count)
+        .unwrap_or(0);
+
+    println!(
+        "{}",
+        formatter.format_message(&format!(
+            "Restored '{}' from {} ({} chunks)",
+            collection,
+            path.display(),
+            points
+        ))
+    );
+    Ok(())
+}
+
+/// File stem of snapshots; sorts chronologically.
+const SNAPSHOT_TIMESTAMP: &str = "%Y%m%dT%H%M%SZ";
+
+/// Most recent snapshot in `dir` with the given extension.
+fn latest_snapshot(dir: &Path, extension: &str) -> Option<PathBuf> {
+    std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().and_then(|e| e.to_str()) == Some(extension))
+        .max()
+}
+
+/// Chunks read from the old collection per page.
+const MIGRATE_PAGE_SIZE: u32 = 256;
+
+struct MigrateOptions {
+    re_embed: bool,
+    to: Option<String>,
+    activate: bool,
+    global: bool,
+}
+
+/// Default name of the collection a migration fills.
+fn migration_target(source: &str, dimension: u32) -> String {
+    format!("{}_{}", source, dimension)
+}
+
+async fn handle_migrate(
+    formatter: &dyn Formatter,
+    name: Option<&str>,
+    options: MigrateOptions,
+) -> Result<()> {
+    let config = Config::load_with_collection(name)?.config;
+    let source = config.vector_store.collection.clone();
+    let dimension = config.embedding.dimension;
+
+    let source_store = create_backend(&config.vector_store).await?;
+    let Some(info) = source_store.get_collection_info().await? else {
+        anyhow::bail!("collection '{}' does not exist", source);
+    };
+    let stored = info.dimension.unwrap_or(u64::from(dimension));
+    if !options.re_embed && stored != u64::from(dimension) {
+        anyhow::bail!(
+            "'{}' stores {}-dimensional vectors but {} produces {}; pass --re-embed to re-embed its content",
+            source,
+            stored,
+            config.embedding.model_id,
+            dimension
+        );
+    }
+
+    let target = options
+        .to
+        .unwrap_or_else(|| migration_target(&source, dimension));
+    config
+        .vector_store
+        .driver
+        .validate_collection_name(&target)?;
+    if target == source {
+        anyhow::bail!("the new collection needs a name other than '{}'", source);
+    }
+    let mut target_config = config.clone();
+    target_config.vector_store.collection = target.clone();
+    let target_store =
+        create_backend_with_embedding_config(&target_config.vector_store, &config.embedding)
+            .await?;
+    if target_store
+        .get_collection_info()
+        .await?
+        .is_some_and(|info| info.points_count > 0)
+    {
+        anyhow::bail!(
+            "collection '{}' already has documents; pick another name with --to",
+            target
+        );
+    }
+    target_store
+        .create_collection()
+        .await
+        .context("failed to create collection")?;
+
+    let pb = ProgressBar::new(info.points_count);
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template(
+                "{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} chunks ({eta})",
+            )
+            .unwrap()
+            .progress_chars("#>-"),
+    );
+
+    let copied = if options.re_embed {
+        let embedding_client = EmbeddingClient::new(&config);
+        let (sender, pipeline) = IndexPipeline::new(
+            &embedding_client,
+            target_store.as_ref(),
+            config.embedding.batch_size as usize,
+        );
+        let retry_notice = |message: String| pb.println(message);
+        let embed_notice = |embedded: u64| pb.inc(embedded);
+        let pipeline = pipeline
+            .with_title_vectors(config.vector_store.title_vectors)
+            .with_max_retries(config.indexing.max_retries)
+            .with_retry_notice(&retry_notice)
+            .with_embed_notice(&embed_notice);
+
+        let all = SearchFilter::new().with_deleted(Deleted::Any);
+        let produce = resend_stored_chunks(source_store.as_ref(), &all, sender);
+        let (produced, stored) = tokio::join!(produce, pipeline.run());
+        let stored = stored?;
+        produced?;
+        stored.stored
+    } else {
+        let mut copied = 0;
+        let mut offset = None;
+        loop {
+            let page = source_store
+                .scroll_chunks(offset, MIGRATE_PAGE_SIZE)
+                .await
+                .context("failed to read chunks")?;
+            copied += page.chunks.len() as u64;
+            pb.inc(page.chunks.len() as u64);
+            if !page.chunks.is_empty() {
+                target_store
+                    .upsert_points(page.chunks)
+                    .await
+                    .context("failed to store chunks")?;
+            }
+            offset = page.next_offset;
+            if offset.is_none() {
+                break;
+            }
+        }
+        copied
+    };
+    pb.finish_and_clear();
+
+    // Pin the model, so the new collection keeps it if [embedding] changes again
+    let overrides = CollectionConfig {
+        model_id: Some(config.embedding.model_id.clone()),
+        model_path: config.embedding.model_path.clone(),
+        dimension: Some(dimension),
+    };
+    let path = target_config_path(options.global)?;
+    update_config_file(&path, |partial| {
+        partial
+            .collections
+            .get_or_insert_default()
+            .insert(target.clone(), overrides);
+        if options.activate {
+            partial.vector_store.get_or_insert_default().collection = Some(target.clone());
+        }
+    })?;
+
+    println!(
+        "{}",
+        formatter.format_message(&format!(
+            "Migrated {} chunks from '{}' ({}d) to '{}' ({}, {}d)",
+            copied, source, stored, target, config.embedding.model_id, dimension
+        ))
+    );
+    let next = if options.activate {
+        format!(
+            "Now using '{}'. Run 'ssearch collection delete {}' once you no longer need the old one.",
+            target, source
+        )
+    } else {
+        format!("Run 'ssearch collection use {}' to switch to it.", target)
+    };
+    println!("{}", formatter.format_message(&next));
+    Ok(())
+}
+
+fn handle_use(formatter: &dyn Formatter, name: &str, global: bool) -> Result<()> {
+    let config = Config::load()?.config;
+    config.vector_store.driver.validate_collection_name(name)?;
+
+    let path = target_config_path(global)?;
+    update_config_file(&path, |partial| {
+        partial.vector_store.get_or_insert_default().collection = Some(name.to_string());
+    })?;
+
+    println!(
+        "{}",
+        formatter.format_message(&format!(
+            "Using collection '{}' (saved to {})",
+            name,
+            path.display()
+        ))
+    );
+    Ok(())
+}
+
+/// Project config if there is one, otherwise the global config.
+pub(super) fn target_config_path(global: bool) -> Result<PathBuf> {
+    let project = if global {
+        None
+    } else {
+        Config::find_project_config()
+    };
+
+    project
+        .or_else(Config::global_path)
+        .ok_or_else(|| anyhow::anyhow!("could not determine config path"))
+}
+
+pub(super) fn update_config_file(
+    path: &Path,
+    update: impl FnOnce(&mut PartialConfig),
+) -> Result<()> {
+    let mut partial = if path.exists() {
+        Config::load_partial(path)?
+    } else {
+        PartialConfig::default()
+    };
+    update(&mut partial);
+    Config::save_partial(path, &partial)
+        .with_context(|| format!("failed to write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::VectorDriver;
+
+    #[test]
+    fn test_migration_target() {
+        assert_eq!(migration_target("docs", 1024), "docs_1024");
+        assert!(
+            VectorDriver::Qdrant
+                .validate_collection_name(&migration_target("work-docs", 384))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_latest_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(latest_snapshot(dir.path(), "pgcopy").is_none());
+        for name in [
+            "20240101T000000Z.pgcopy",
+            "20240301T120000Z.pgcopy",
+            "20240401T000000Z.snapshot",
+        ] {
+            std::fs::write(dir.path().join(name), b"").unwrap();
+        }
+
+        let latest = latest_snapshot(dir.path(), "pgcopy").unwrap();
+        assert!(latest.ends_with("20240301T120000Z.pgcopy"));
+        assert!(
+            latest_snapshot(dir.path(), "snapshot")
+                .unwrap()
+                .ends_with("20240401T000000Z.snapshot")
+        );
+    }
+}
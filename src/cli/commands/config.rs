@@ -1,9 +1,14 @@
 use anyhow::{Context, Result};
 use clap::Subcommand;
+use serde::Serialize;
 use std::process::Command;
 
 use crate::cli::output::get_formatter;
-use crate::models::{Config, ConfigSource, OutputFormat, ResolvedConfig};
+use crate::client::DaemonClient;
+use crate::models::{
+    Config, ConfigSource, OutputFormat, QueryExpansion, ResolvedConfig, VectorDriver,
+};
+use crate::services::create_backend_with_embedding_config;
 
 #[derive(Debug, Subcommand)]
 pub enum ConfigCommand {
@@ -15,7 +20,7 @@ pub enum ConfigCommand {
             help = "Create global config instead of project config"
         )]
         global: bool,
-        #[arg(long, short = 'f', help = "Force overwrite existing config")]
+        #[arg(long, help = "Force overwrite existing config")]
         force: bool,
     },
     #[command(about = "Show current configuration")]
@@ -37,6 +42,37 @@ pub enum ConfigCommand {
         )]
         global: bool,
     },
+    #[command(about = "Check the configuration for consistency")]
+    Validate {
+        #[arg(long, help = "Also connect to the vector store and ping the daemon")]
+        connect: bool,
+    },
+}
+
+/// Outcome of one `config validate` check.
+#[derive(Debug, Serialize)]
+struct ValidationCheck {
+    name: &'static str,
+    ok: bool,
+    detail: String,
+}
+
+impl ValidationCheck {
+    fn ok(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            ok: true,
+            detail: detail.into(),
+        }
+    }
+
+    fn failed(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            ok: false,
+            detail: detail.into(),
+        }
+    }
 }
 
 pub async fn handle_config(cmd: ConfigCommand, format: OutputFormat, _verbose: bool) -> Result<()> {
@@ -47,6 +83,118 @@ pub async fn handle_config(cmd: ConfigCommand, format: OutputFormat, _verbose: b
         ConfigCommand::Show { source } => handle_show(source, format),
         ConfigCommand::Path { all } => handle_path(all),
         ConfigCommand::Edit { global } => handle_edit(global, formatter.as_ref()),
+        ConfigCommand::Validate { connect } => handle_validate(connect, format).await,
+    }
+}
+
+async fn handle_validate(connect: bool, format: OutputFormat) -> Result<()> {
+    let config = Config::load()?.config;
+
+    let problems = config.problems();
+    let mut checks: Vec<ValidationCheck> = if problems.is_empty() {
+        vec![ValidationCheck::ok("config", "settings are consistent")]
+    } else {
+        problems
+            .into_iter()
+            .map(|p| ValidationCheck::failed("config", p))
+            .collect()
+    };
+    if connect {
+        checks.extend(check_vector_store(&config).await);
+        checks.push(check_daemon(&config).await);
+    }
+
+    if format == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(&checks)?);
+    } else {
+        for check in &checks {
+            let mark = if check.ok { "[OK]  " } else { "[FAIL]" };
+            println!("{} {}: {}", mark, check.name, check.detail);
+        }
+    }
+
+    let failed = checks.iter().filter(|c| !c.ok).count();
+    if failed > 0 {
+        anyhow::bail!("configuration check failed ({} problem(s))", failed);
+    }
+    Ok(())
+}
+
+/// Reachability of the vector store, and whether the collection's dimension
+/// matches the embedding model.
+async fn check_vector_store(config: &Config) -> Vec<ValidationCheck> {
+    let store_config = &config.vector_store;
+    let unreachable = |e: String| {
+        ValidationCheck::failed(
+            "vector_store",
+            format!(
+                "cannot reach {} at {}: {}; check [vector_store] url and that the server is running",
+                store_config.driver, store_config.url, e
+            ),
+        )
+    };
+    let store = match create_backend_with_embedding_config(store_config, &config.embedding).await {
+        Ok(store) => store,
+        Err(e) => return vec![unreachable(e.to_string())],
+    };
+    match store.health_check().await {
+        Ok(true) => {}
+        Ok(false) => return vec![unreachable("health check failed".to_string())],
+        Err(e) => return vec![unreachable(e.to_string())],
+    }
+
+    let mut checks = vec![ValidationCheck::ok(
+        "vector_store",
+        format!(
+            "connected to {} at {}",
+            store_config.driver, store_config.url
+        ),
+    )];
+    let collection = store_config.scoped_collection();
+    let expected = u64::from(config.embedding.dimension);
+    checks.push(match store.get_collection_info().await {
+        Ok(Some(info)) => match info.dimension {
+            Some(dimension) if dimension != expected => ValidationCheck::failed(
+                "collection",
+                format!(
+                    "\"{}\" was created with dimension {} but [embedding] dimension is {}; set dimension = {} or index into a new collection",
+                    collection, dimension, expected, dimension
+                ),
+            ),
+            _ => ValidationCheck::ok(
+                "collection",
+                format!("\"{}\" has {} points", collection, info.points_count),
+            ),
+        },
+        Ok(None) => ValidationCheck::ok(
+            "collection",
+            format!("\"{}\" does not exist yet; indexing creates it", collection),
+        ),
+        Err(e) => ValidationCheck::failed(
+            "collection",
+            format!("cannot read \"{}\": {}", collection, e),
+        ),
+    });
+    checks
+}
+
+/// The daemon starts on demand, so only a running daemon that does not
+/// answer is a problem.
+async fn check_daemon(config: &Config) -> ValidationCheck {
+    let client = DaemonClient::new(config);
+    if !client.is_running() {
+        return ValidationCheck::ok("daemon", "not running; started on first use");
+    }
+    match client.ping().await {
+        Ok(()) => ValidationCheck::ok("daemon", "responded to ping"),
+        Err(e) => ValidationCheck::failed(
+            "daemon",
+            format!(
+                "running at {} but not responding: {}; try `ssearch serve restart`",
+                config.socket_path().display(),
+                e
+            ),
+        ),
     }
 }
 
@@ -158,6 +306,36 @@ fn print_resolved_config(resolved: &ResolvedConfig, show_source: bool) {
         config.embedding.max_tokens,
         src(&sources.embedding_max_tokens)
     );
+    println!(
+        "sessions = {}{}",
+        config.embedding.sessions,
+        src(&sources.embedding_sessions)
+    );
+    println!(
+        "device = \"{}\"{}",
+        config.embedding.device,
+        src(&sources.embedding_device)
+    );
+    if let Some(threads) = config.embedding.threads {
+        println!("threads = {threads}");
+    }
+    let model_default = |set: &Option<String>| {
+        if show_source && set.is_none() {
+            "  # model default"
+        } else {
+            ""
+        }
+    };
+    println!(
+        "query_instruction = {:?}{}",
+        config.embedding.resolved_query_instruction(),
+        model_default(&config.embedding.query_instruction)
+    );
+    println!(
+        "document_instruction = {:?}{}",
+        config.embedding.resolved_document_instruction(),
+        model_default(&config.embedding.document_instruction)
+    );
     println!();
 
     println!("[vector_store]");
@@ -176,12 +354,40 @@ fn print_resolved_config(resolved: &ResolvedConfig, show_source: bool) {
         config.vector_store.collection,
         src(&sources.vector_store_collection)
     );
+    println!("quantization = \"{}\"", config.vector_store.quantization);
+    println!("title_vectors = {}", config.vector_store.title_vectors);
+    if config.vector_store.title_vectors {
+        println!("title_weight = {}", config.vector_store.title_weight);
+    }
+    if config.vector_store.driver == VectorDriver::PostgreSQL {
+        println!("index_type = \"{}\"", config.vector_store.index_type);
+        let tuning = [
+            ("hnsw_m", config.vector_store.hnsw_m),
+            (
+                "hnsw_ef_construction",
+                config.vector_store.hnsw_ef_construction,
+            ),
+            ("ef_search", config.vector_store.ef_search),
+            ("ivfflat_lists", config.vector_store.ivfflat_lists),
+            ("ivfflat_probes", config.vector_store.ivfflat_probes),
+        ];
+        for (key, value) in tuning {
+            if let Some(value) = value {
+                println!("{} = {}", key, value);
+            }
+        }
+    }
     if config.vector_store.api_key.is_some() {
         println!(
             "api_key = \"********\"{}",
             src(&sources.vector_store_api_key)
         );
     }
+    if config.vector_store.encryption_key.is_some() {
+        println!("encryption_key = \"********\"");
+    } else if let Some(ref command) = config.vector_store.encryption_key_command {
+        println!("encryption_key_command = \"{}\"", command);
+    }
     println!();
 
     println!("[indexing]");
@@ -200,6 +406,42 @@ fn print_resolved_config(resolved: &ResolvedConfig, show_source: bool) {
         config.indexing.chunk_overlap,
         src(&sources.indexing_chunk_overlap)
     );
+    println!(
+        "strategy = \"{}\"{}",
+        config.indexing.strategy,
+        src(&sources.indexing_strategy)
+    );
+    println!("dedup = {}", config.indexing.dedup);
+    println!("redact_secrets = {}", config.indexing.redact_secrets);
+    println!("tokenizer = \"{}\"", config.indexing.tokenizer);
+    if let Some(ref path) = config.indexing.tokenizer_path {
+        println!("tokenizer_path = \"{}\"", path.display());
+    }
+    println!("max_retries = {}", config.indexing.max_retries);
+    println!("summaries = \"{}\"", config.indexing.summaries);
+    println!("pii = \"{}\"", config.indexing.pii);
+    println!("min_chunk_length = {}", config.indexing.min_chunk_length);
+    println!(
+        "boilerplate_min_files = {}",
+        config.indexing.boilerplate_min_files
+    );
+    println!("filler = \"{}\"", config.indexing.filler);
+    for (key, entry) in config.indexing.overrides.iter() {
+        let mut fields = Vec::new();
+        if let Some(size) = entry.chunk_size {
+            fields.push(format!("chunk_size = {size}"));
+        }
+        if let Some(overlap) = entry.chunk_overlap {
+            fields.push(format!("chunk_overlap = {overlap}"));
+        }
+        println!("overrides.{key} = {{ {} }}", fields.join(", "));
+    }
+    for (source, age) in &config.indexing.retention.max_age {
+        println!("retention.max_age.{source} = \"{age}\"");
+    }
+    if let Some(max_points) = config.indexing.retention.max_points {
+        println!("retention.max_points = {max_points}");
+    }
     if !config.indexing.exclude_patterns.is_empty() {
         if show_source {
             println!(
@@ -230,6 +472,31 @@ fn print_resolved_config(resolved: &ResolvedConfig, show_source: bool) {
     if let Some(score) = config.search.default_min_score {
         println!("default_min_score = {score}");
     }
+    println!(
+        "rerank = {}{}",
+        config.search.rerank,
+        src(&sources.search_rerank)
+    );
+    if config.search.rerank {
+        println!("rerank_model_id = \"{}\"", config.search.rerank_model_id);
+        println!("rerank_top_k = {}", config.search.rerank_top_k);
+    }
+    if config.search.recency_weight > 0.0 {
+        println!("recency_weight = {}", config.search.recency_weight);
+        println!(
+            "recency_half_life_days = {}",
+            config.search.recency_half_life_days
+        );
+    }
+    if let Some(threshold) = config.search.dedup_threshold {
+        println!("dedup_threshold = {threshold}");
+    }
+    if config.search.expansion != QueryExpansion::Off {
+        println!("expansion = \"{}\"", config.search.expansion);
+    }
+    if !config.search.keyword_fallback {
+        println!("keyword_fallback = false");
+    }
     println!();
 
     println!("[daemon]");
@@ -246,6 +513,36 @@ fn print_resolved_config(resolved: &ResolvedConfig, show_source: bool) {
     if !show_source {
         println!("socket_path = \"{}\"", config.socket_path().display());
     }
+    if let Some(ref addr) = config.daemon.http_addr {
+        println!("http_addr = \"{addr}\"");
+    }
+    println!("log_format = \"{}\"", config.daemon.log_format);
+    if config.daemon.auth_token.is_some() {
+        println!("auth_token = \"********\"");
+    }
+    if let Some(rate) = config.daemon.rate_limit {
+        println!("rate_limit = {rate}");
+    }
+    if let Some(rate) = config.daemon.client_rate_limit {
+        println!("client_rate_limit = {rate}");
+    }
+    if let Some(burst) = config.daemon.rate_limit_burst {
+        println!("rate_limit_burst = {burst}");
+    }
+    if config.daemon.preload {
+        println!("preload = true");
+    }
+    println!();
+
+    println!("[llm]");
+    println!("provider = \"{}\"", config.llm.provider);
+    println!("url = \"{}\"", config.llm.base_url());
+    println!("model = \"{}\"", config.llm.model);
+    if config.llm.api_key.is_some() {
+        println!("api_key = \"********\"");
+    }
+    println!("context_chunks = {}", config.llm.context_chunks);
+    println!("timeout_secs = {}", config.llm.timeout_secs);
     println!();
 
     println!("[metrics]");
@@ -259,6 +556,17 @@ fn print_resolved_config(resolved: &ResolvedConfig, show_source: bool) {
         config.metrics.retention_days,
         src(&sources.metrics_retention_days)
     );
+    println!("history = {}", config.metrics.history);
+    if let Some(ref addr) = config.metrics.http_addr {
+        println!("http_addr = \"{addr}\"");
+    }
+    println!();
+
+    println!("[workspace]");
+    println!("isolate = {}", config.workspace.isolate);
+    if let Some(ref name) = config.workspace.name {
+        println!("name = \"{name}\"");
+    }
 }
 
 fn format_source(source: &ConfigSource) -> &'static str {
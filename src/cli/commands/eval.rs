@@ -0,0 +1,179 @@
+//! Eval command: score the index against labeled queries and compare runs.
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::Args;
+use clap_complete::ArgValueCompleter;
+
+use crate::cli::completion::complete_tags;
+use crate::cli::output::get_formatter;
+use crate::models::{Config, OutputFormat, SearchFilter, parse_tags};
+use crate::services::fusion::rescore;
+use crate::services::{
+    DEFAULT_EVAL_K, EVAL_CANDIDATE_FACTOR, EmbeddingClient, EvalRun, QueryEval, check_dimension,
+    create_backend, parse_judgments,
+};
+
+#[derive(Debug, Args)]
+pub struct EvalArgs {
+    #[arg(
+        required = true,
+        value_name = "JUDGMENTS",
+        help = "JSONL file of labeled queries: {\"query\": \"...\", \"relevant\": [\"src/auth.rs\"]} or graded {\"relevant\": {\"src/auth.rs\": 2}}"
+    )]
+    pub judgments: PathBuf,
+
+    #[arg(
+        short = 'k',
+        value_name = "K",
+        default_value_t = DEFAULT_EVAL_K,
+        help = "Documents scored per query (recall@k, nDCG@k)"
+    )]
+    pub k: usize,
+
+    #[arg(
+        long,
+        short = 't',
+        add = ArgValueCompleter::new(complete_tags),
+        help = "Filter by tags (e.g., 'source:confluence,space:common')"
+    )]
+    pub tags: Option<String>,
+
+    #[arg(
+        long,
+        help = "Combine semantic similarity with keyword (BM25) matching for exact identifiers"
+    )]
+    pub hybrid: bool,
+
+    #[arg(
+        long,
+        conflicts_with = "no_rerank",
+        help = "Re-score top candidates with the cross-encoder reranker"
+    )]
+    pub rerank: bool,
+
+    #[arg(long, help = "Disable reranking even if enabled in config")]
+    pub no_rerank: bool,
+
+    #[arg(long, help = "Collection to evaluate instead of the configured one")]
+    pub collection: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Save this run's scores to FILE for a later --baseline"
+    )]
+    pub save: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Compare with a run saved by --save, showing per-metric and per-query changes"
+    )]
+    pub baseline: Option<PathBuf>,
+}
+
+pub async fn handle_eval(args: EvalArgs, format: OutputFormat, verbose: bool) -> Result<()> {
+    if args.k == 0 {
+        anyhow::bail!("k must be at least 1");
+    }
+    let config = Config::load_with_collection(args.collection.as_deref())?.config;
+    let formatter = get_formatter(format);
+
+    let text = std::fs::read_to_string(&args.judgments)
+        .with_context(|| format!("failed to read {}", args.judgments.display()))?;
+    let judgments = parse_judgments(&text)
+        .with_context(|| format!("invalid judgments in {}", args.judgments.display()))?;
+    // Read before searching so a bad path doesn't waste a run
+    let baseline: Option<EvalRun> = match args.baseline {
+        Some(ref path) => {
+            let saved = std::fs::read_to_string(path)
+                .with_context(|| format!("failed to read {}", path.display()))?;
+            Some(serde_json::from_str(&saved).with_context(|| {
+                format!("{} is not a run saved by 'eval --save'", path.display())
+            })?)
+        }
+        None => None,
+    };
+
+    let tags = args
+        .tags
+        .as_ref()
+        .map(|s| parse_tags(s))
+        .transpose()
+        .context("failed to parse tags")?
+        .unwrap_or_default();
+    let filter = SearchFilter::new()
+        .with_tags(tags)
+        .with_workspace(config.workspace());
+    let rerank = !args.no_rerank && (args.rerank || config.search.rerank);
+    let candidates = args.k.saturating_mul(EVAL_CANDIDATE_FACTOR);
+    let pool = if rerank {
+        candidates.max(config.search.rerank_top_k as usize)
+    } else {
+        candidates
+    } as u64;
+
+    let embedding_client = EmbeddingClient::new(&config);
+    let vector_store = create_backend(&config.vector_store).await?;
+    check_dimension(vector_store.as_ref(), u64::from(config.embedding.dimension)).await?;
+
+    let embeddings = embedding_client
+        .embed_queries(judgments.iter().map(|j| j.query.clone()).collect())
+        .await
+        .context("failed to generate query embeddings")?;
+
+    let mut queries = Vec::with_capacity(judgments.len());
+    for (judgment, embedding) in judgments.iter().zip(embeddings) {
+        let mut ranking = if args.hybrid {
+            vector_store
+                .hybrid_search(embedding, &judgment.query, pool, 0, &filter, None)
+                .await
+        } else {
+            vector_store.search(embedding, pool, 0, &filter, None).await
+        }
+        .context("search failed")?;
+        if rerank && !ranking.is_empty() {
+            let documents = ranking.iter().map(|r| r.content.clone()).collect();
+            let scores = embedding_client
+                .rerank(&judgment.query, documents)
+                .await
+                .context("failed to rerank results")?;
+            ranking = rescore(ranking, &scores, candidates);
+        }
+        let eval = QueryEval::score(judgment, &ranking, args.k);
+        if verbose {
+            eprintln!(
+                "{:.3} nDCG  {:.3} recall  \"{}\"",
+                eval.ndcg, eval.recall, eval.query
+            );
+        }
+        queries.push(eval);
+    }
+
+    let mut modes = Vec::new();
+    if args.hybrid {
+        modes.push("hybrid".to_string());
+    }
+    if rerank {
+        modes.push("rerank".to_string());
+    }
+    let run = EvalRun::new(
+        args.k,
+        config.vector_store.scoped_collection(),
+        config.embedding.model_id.clone(),
+        modes,
+        queries,
+    );
+
+    if let Some(ref path) = args.save {
+        std::fs::write(path, serde_json::to_string_pretty(&run)?)
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        if verbose {
+            eprintln!("Saved run to {}", path.display());
+        }
+    }
+    print!("{}", formatter.format_eval(&run, baseline.as_ref()));
+    Ok(())
+}
@@ -0,0 +1,105 @@
+//! Export command implementation.
+
+use anyhow::{Context, Result};
+use clap::Args;
+use indicatif::{ProgressBar, ProgressStyle};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+use std::time::Instant;
+
+use crate::cli::output::get_formatter;
+use crate::models::{Config, OutputFormat};
+use crate::services::create_backend;
+
+/// Chunks read from the vector store per request
+const EXPORT_PAGE_SIZE: u32 = 256;
+
+#[derive(Debug, Args)]
+pub struct ExportArgs {
+    /// Output JSONL file (use - for stdout)
+    #[arg(required = true)]
+    pub file: PathBuf,
+}
+
+pub async fn handle_export(args: ExportArgs, format: OutputFormat, verbose: bool) -> Result<()> {
+    let config = Config::load()?.config;
+    let formatter = get_formatter(format);
+    let start_time = Instant::now();
+
+    let vector_store = create_backend(&config.vector_store).await?;
+    let Some(info) = vector_store.get_collection_info().await? else {
+        println!(
+            "{}",
+            formatter.format_message("Collection not found. Run 'ssearch index' first.")
+        );
+        return Ok(());
+    };
+
+    let to_stdout = args.file.to_string_lossy() == "-";
+    let mut writer: Box<dyn Write> = if to_stdout {
+        Box::new(BufWriter::new(io::stdout().lock()))
+    } else {
+        let file = File::create(&args.file)
+            .with_context(|| format!("failed to create {}", args.file.display()))?;
+        Box::new(BufWriter::new(file))
+    };
+
+    let pb = if to_stdout {
+        ProgressBar::hidden()
+    } else {
+        ProgressBar::new(info.points_count)
+    };
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template(
+                "{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} ({eta})",
+            )
+            .unwrap()
+            .progress_chars("#>-"),
+    );
+
+    let mut exported = 0u64;
+    let mut offset = None;
+    loop {
+        let page = vector_store
+            .scroll_chunks(offset, EXPORT_PAGE_SIZE)
+            .await
+            .context("failed to read collection")?;
+
+        for chunk in &page.chunks {
+            serde_json::to_writer(&mut writer, chunk).context("failed to serialize chunk")?;
+            writer.write_all(b"\n").context("failed to write export")?;
+        }
+        exported += page.chunks.len() as u64;
+        pb.inc(page.chunks.len() as u64);
+
+        offset = page.next_offset;
+        if offset.is_none() || page.chunks.is_empty() {
+            break;
+        }
+    }
+
+    writer.flush().context("failed to write export")?;
+    pb.finish_and_clear();
+
+    if !to_stdout {
+        if verbose {
+            println!(
+                "Exported collection '{}' in {}ms",
+                vector_store.collection(),
+                start_time.elapsed().as_millis()
+            );
+        }
+        println!(
+            "{}",
+            formatter.format_message(&format!(
+                "Exported {} chunks to {}",
+                exported,
+                args.file.display()
+            ))
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,154 @@
+//! Health command: deep checks of the daemon, vector store, disk and external CLIs.
+
+use std::time::Instant;
+
+use anyhow::Result;
+
+use super::source::external_clis;
+use crate::cli::output::get_formatter;
+use crate::client::DaemonClient;
+use crate::error::AppError;
+use crate::models::{Config, OutputFormat};
+use crate::services::{
+    EmbeddingClient, HealthCheck, HealthReport, available_space, collection_check, create_backend,
+    disk_space_check, embedding_check,
+};
+
+/// Text embedded to measure the model's output dimension.
+const PROBE_TEXT: &str = "health check";
+
+pub async fn handle_health(format: OutputFormat, _verbose: bool) -> Result<()> {
+    let config = Config::load()?.config;
+    let formatter = get_formatter(format);
+
+    let mut report = HealthReport::default();
+    let model_dimension = check_daemon(&config, &mut report).await;
+    check_vector_store(&config, model_dimension, &mut report).await;
+    report.push(check_disk_space());
+    for cli in external_clis() {
+        let name = format!("cli:{}", cli.name);
+        report.push(if cli.available {
+            let version = cli.version.as_deref().and_then(|v| v.lines().next());
+            HealthCheck::pass(name, version.unwrap_or("installed"))
+        } else {
+            HealthCheck::warn(name, format!("not installed ({})", cli.description))
+        });
+    }
+
+    print!("{}", formatter.format_health(&report));
+
+    match report.failed() {
+        0 => Ok(()),
+        failed => Err(AppError::Unhealthy(failed).into()),
+    }
+}
+
+/// Ping the daemon and embed a probe text, returning the dimension the
+/// active collection's model produces.
+async fn check_daemon(config: &Config, report: &mut HealthReport) -> Option<usize> {
+    let client = DaemonClient::new(config);
+    if !client.is_running() {
+        report.push(if config.daemon.auto_start {
+            HealthCheck::warn(
+                "daemon",
+                "not running; it starts automatically on the first search or index",
+            )
+        } else {
+            HealthCheck::fail(
+                "daemon",
+                "not running and [daemon] auto_start is off; start it with `ssearch serve`",
+            )
+        });
+        return None;
+    }
+
+    let started = Instant::now();
+    if let Err(e) = client.ping().await {
+        report.push(HealthCheck::fail("daemon", format!("ping failed: {e}")));
+        return None;
+    }
+    let latency_ms = started.elapsed().as_millis();
+    if let Err(e) = client.hello().await {
+        report.push(HealthCheck::fail("daemon", e.to_string()));
+        return None;
+    }
+    report.push(match client.status().await {
+        Ok(status) if status.ready => HealthCheck::pass(
+            "daemon",
+            format!("{} loaded, ping {latency_ms}ms", status.embedding_model),
+        ),
+        Ok(status) => HealthCheck::warn(
+            "daemon",
+            format!("{} still warming up", status.embedding_model),
+        ),
+        Err(e) => HealthCheck::fail("daemon", format!("status failed: {e}")),
+    });
+
+    let model_id = &config.embedding.model_id;
+    match EmbeddingClient::new(config).embed_query(PROBE_TEXT).await {
+        Ok(embedding) => {
+            report.push(embedding_check(
+                model_id,
+                embedding.len(),
+                config.embedding.dimension,
+            ));
+            Some(embedding.len())
+        }
+        Err(e) => {
+            report.push(HealthCheck::fail(
+                "embedding",
+                format!("{model_id} failed to embed: {e}"),
+            ));
+            None
+        }
+    }
+}
+
+async fn check_vector_store(
+    config: &Config,
+    model_dimension: Option<usize>,
+    report: &mut HealthReport,
+) {
+    let driver = &config.vector_store.driver;
+    let store = match create_backend(&config.vector_store).await {
+        Ok(store) => store,
+        Err(e) => {
+            report.push(HealthCheck::fail("vector_store", format!("{driver}: {e}")));
+            return;
+        }
+    };
+    match store.health_check().await {
+        Ok(true) => report.push(HealthCheck::pass(
+            "vector_store",
+            format!("{driver} at {}", config.vector_store.url),
+        )),
+        Ok(false) => {
+            report.push(HealthCheck::fail(
+                "vector_store",
+                format!("{driver} at {} is not responding", config.vector_store.url),
+            ));
+            return;
+        }
+        Err(e) => {
+            report.push(HealthCheck::fail("vector_store", format!("{driver}: {e}")));
+            return;
+        }
+    }
+
+    let collection = config.vector_store.scoped_collection();
+    let expected = model_dimension.map_or(config.embedding.dimension as u64, |d| d as u64);
+    report.push(match store.get_collection_info().await {
+        Ok(info) => collection_check(&collection, info.as_ref(), expected),
+        Err(e) => HealthCheck::fail("collection", format!("'{collection}': {e}")),
+    });
+}
+
+fn check_disk_space() -> HealthCheck {
+    let Some(dir) = Config::cache_dir() else {
+        return HealthCheck::warn("disk", "no home directory for the model cache");
+    };
+    match available_space(&dir) {
+        Ok(available) => disk_space_check(&dir, available),
+        Err(e) => HealthCheck::warn("disk", format!("{}: {e}", dir.display())),
+    }
+}
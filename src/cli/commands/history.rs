@@ -0,0 +1,84 @@
+//! History command implementation.
+
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand};
+
+use crate::cli::output::get_formatter;
+use crate::models::{Config, OutputFormat};
+use crate::services::{DEFAULT_POOR_SCORE, MetricsStore};
+
+#[derive(Debug, Args)]
+pub struct HistoryArgs {
+    #[command(subcommand)]
+    pub command: Option<HistoryCommand>,
+
+    /// Number of recent searches to show
+    #[arg(long, short = 'n', default_value_t = 20)]
+    pub limit: u32,
+
+    /// Only show searches that returned no results
+    #[arg(long)]
+    pub zero: bool,
+}
+
+/// History subcommands.
+#[derive(Debug, Subcommand)]
+pub enum HistoryCommand {
+    /// Summarize recent searches and list queries with zero or poor results
+    Stats {
+        /// Days of history to include
+        #[arg(long, short = 'd', default_value_t = 7)]
+        days: u32,
+
+        /// Top score below which a search counts as poor
+        #[arg(long, value_name = "SCORE", default_value_t = DEFAULT_POOR_SCORE)]
+        poor_score: f32,
+    },
+
+    /// Delete all recorded searches
+    Clear,
+}
+
+/// Handle the history command.
+pub async fn handle_history(args: HistoryArgs, format: OutputFormat, _verbose: bool) -> Result<()> {
+    let formatter = get_formatter(format);
+    let config = Config::load()?.config;
+
+    let path = Config::metrics_db_path()
+        .ok_or_else(|| anyhow::anyhow!("could not determine cache directory"))?;
+    if !path.exists() {
+        println!("{}", formatter.format_message("No searches recorded."));
+        return Ok(());
+    }
+    let store = MetricsStore::open(&path).context("failed to open metrics database")?;
+
+    if !(config.metrics.enabled && config.metrics.history) && args.command.is_none() {
+        eprintln!("Search history is disabled in [metrics]; showing earlier searches");
+    }
+
+    match args.command {
+        None => {
+            let entries = store
+                .recent_searches(args.limit, args.zero)
+                .context("failed to read search history")?;
+            print!("{}", formatter.format_search_history(&entries));
+        }
+        Some(HistoryCommand::Stats { days, poor_score }) => {
+            let stats = store
+                .search_stats(days, poor_score)
+                .context("failed to read search history")?;
+            print!("{}", formatter.format_search_stats(&stats));
+        }
+        Some(HistoryCommand::Clear) => {
+            let removed = store
+                .clear_searches()
+                .context("failed to clear search history")?;
+            println!(
+                "{}",
+                formatter.format_message(&format!("Removed {} searches from history", removed))
+            );
+        }
+    }
+
+    Ok(())
+}
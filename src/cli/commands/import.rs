@@ -2,16 +2,28 @@
 
 use anyhow::{Context, Result};
 use clap::Args;
+use clap_complete::ArgValueCompleter;
 use serde::Deserialize;
+use std::collections::HashSet;
 use std::io::{self, Read};
 use std::path::{Path, PathBuf};
 use std::time::Instant;
+use tokio_stream::StreamExt;
 
+use crate::cli::completion::complete_tags;
 use crate::cli::output::{IndexStats, get_formatter};
 use crate::models::{
-    Config, Document, DocumentMetadata, OutputFormat, Source, SourceType, Tag, parse_tags,
+    Config, Document, DocumentChunk, DocumentMetadata, OutputFormat, Source, SourceType, Tag,
+    WORKSPACE_ATTRIBUTE, parse_tags,
 };
-use crate::services::{EmbeddingClient, TextChunker, create_backend, process_batch};
+use crate::services::{
+    CsvColumns, DEFAULT_ROWS_PER_CHUNK, EmbeddingClient, ImportFormat, IndexPipeline, PiiOutcome,
+    RowTemplate, Table, TextChunker, check_dimension, create_backend_with_dimension,
+    create_backend_with_embedding_config, csv_documents, markdown_document, markdown_files,
+    obsidian_documents, openapi_documents, screen_pii, table_documents,
+};
+use crate::sources::{DEFAULT_MAX_PAGES, DataSource, DocumentStream, SyncOptions, WebSource};
+use crate::utils::file::detect_language;
 
 #[derive(Debug, Args)]
 pub struct ImportArgs {
@@ -20,16 +32,71 @@ pub struct ImportArgs {
     pub file: Option<PathBuf>,
 
     /// Tags to apply to imported documents (comma-separated, format: key:value)
-    #[arg(long, short = 't')]
+    #[arg(long, short = 't', add = ArgValueCompleter::new(complete_tags))]
     pub tags: Option<String>,
 
-    /// Source name for imported documents
-    #[arg(long, default_value = "custom")]
-    pub source: String,
+    /// Source name for imported documents [default: custom, or obsidian with --from obsidian]
+    #[arg(long)]
+    pub source: Option<String>,
+
+    /// Input layout: json, csv, markdown (a file or directory of notes),
+    /// obsidian (a vault directory) or table (CSV or Parquet rows, several per document)
+    #[arg(long, value_name = "FORMAT", default_value = "json")]
+    pub from: ImportFormat,
+
+    /// CSV column holding the document text
+    #[arg(long, value_name = "COLUMN", default_value = "content")]
+    pub content_column: String,
+
+    /// CSV column holding the document title
+    #[arg(long, value_name = "COLUMN")]
+    pub title_column: Option<String>,
+
+    /// CSV column holding a file path, used as the document location
+    #[arg(long, value_name = "COLUMN")]
+    pub path_column: Option<String>,
+
+    /// CSV column holding a URL, used as the document location
+    #[arg(long, value_name = "COLUMN")]
+    pub url_column: Option<String>,
+
+    /// CSV columns whose values become `<column>:<value>` tags (comma-separated)
+    #[arg(long, value_name = "COLUMNS", value_delimiter = ',')]
+    pub tag_columns: Vec<String>,
+
+    /// With --from table, the text of each row with `{column}` placeholders
+    /// (e.g., '{name} ({type}): {description}'); `column: value` pairs when absent
+    #[arg(long, value_name = "TEMPLATE")]
+    pub row_template: Option<String>,
+
+    /// With --from table, rows stored together as one document
+    #[arg(long, value_name = "N", default_value_t = DEFAULT_ROWS_PER_CHUNK)]
+    pub rows_per_chunk: usize,
 
     /// Only validate the import file without indexing
     #[arg(long)]
     pub validate_only: bool,
+
+    /// Restore chunks with stored embeddings from an `ssearch export` file (no re-embedding)
+    #[arg(long, conflicts_with = "from")]
+    pub with_vectors: bool,
+
+    /// Treat the file as an OpenAPI/Swagger spec (JSON or YAML) and index one
+    /// document per endpoint, tagged `api-path:` and `api-method:`
+    #[arg(long, conflicts_with_all = ["with_vectors", "from"])]
+    pub openapi: bool,
+
+    /// Fetch web pages and index their readable text (repeatable)
+    #[arg(long, conflicts_with_all = ["file", "with_vectors", "openapi", "from"])]
+    pub url: Vec<String>,
+
+    /// Link levels to follow from the --url pages, staying under their directory
+    #[arg(long, default_value = "0", requires = "url")]
+    pub depth: u32,
+
+    /// Maximum pages to fetch with --url
+    #[arg(long, requires = "url")]
+    pub max_pages: Option<u32>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -55,7 +122,24 @@ pub async fn handle_import(args: ImportArgs, format: OutputFormat, verbose: bool
         Vec::new()
     };
 
+    if !args.url.is_empty() {
+        return import_urls(&config, tags, &args, format, verbose).await;
+    }
+
+    if args.from != ImportFormat::Json {
+        return import_formatted(&config, &tags, &args, format, verbose).await;
+    }
+
     let input = read_input(args.file.as_deref())?;
+
+    if args.openapi {
+        return import_openapi(&config, &input, &tags, &args, format, verbose).await;
+    }
+
+    if args.with_vectors {
+        return import_chunks(&config, &input, &tags, &args, format, verbose).await;
+    }
+
     let import_docs = parse_import_documents(&input)?;
 
     if import_docs.is_empty() {
@@ -82,96 +166,500 @@ pub async fn handle_import(args: ImportArgs, format: OutputFormat, verbose: bool
     }
 
     let embedding_client = EmbeddingClient::new(&config);
-    let vector_store = create_backend(&config.vector_store).await?;
+    let vector_store =
+        create_backend_with_embedding_config(&config.vector_store, &config.embedding).await?;
+    check_dimension(vector_store.as_ref(), u64::from(config.embedding.dimension)).await?;
     vector_store.create_collection().await?;
 
-    let chunker = TextChunker::new(&config.indexing);
+    let chunker = TextChunker::from_config(&config);
 
     let mut stats = IndexStats {
         files_scanned: import_docs.len() as u64,
+        filler: config.indexing.filler,
         ..Default::default()
     };
 
-    let batch_size = config.embedding.batch_size as usize;
-    let mut pending_chunks = Vec::new();
-    let mut pending_texts = Vec::new();
+    let (sender, pipeline) = IndexPipeline::new(
+        &embedding_client,
+        vector_store.as_ref(),
+        config.embedding.batch_size as usize,
+    );
+    let pipeline = pipeline
+        .with_dedup(config.indexing.dedup)
+        .with_redaction(config.indexing.redact_secrets)
+        .with_workspace(config.workspace().map(String::from))
+        .with_title_vectors(config.vector_store.title_vectors)
+        .with_max_retries(config.indexing.max_retries);
+
+    let produce = async {
+        let sender = sender;
+        for import_doc in import_docs {
+            if import_doc.content.is_empty() {
+                stats.files_skipped += 1;
+                continue;
+            }
 
-    for import_doc in import_docs {
-        if import_doc.content.is_empty() {
-            stats.files_skipped += 1;
-            continue;
-        }
+            let checksum = {
+                use sha2::{Digest, Sha256};
+                let hash = Sha256::digest(import_doc.content.as_bytes());
+                hex::encode(hash)
+            };
+
+            // Parse source_type - never fails, defaults to Other("custom")
+            let source_type: SourceType = import_doc
+                .source_type
+                .as_deref()
+                .map(|s| s.parse().unwrap())
+                .unwrap_or_else(|| SourceType::Other("custom".to_string()));
+
+            // Location: url > path > checksum
+            let location = import_doc
+                .url
+                .clone()
+                .or_else(|| import_doc.path.clone())
+                .unwrap_or_else(|| checksum.clone());
+
+            let source = Source::new(source_type, location, import_doc.url.clone());
+
+            let metadata = DocumentMetadata {
+                filename: None,
+                extension: None,
+                language: import_doc
+                    .path
+                    .as_deref()
+                    .and_then(|path| detect_language(Path::new(path))),
+                title: import_doc.title.clone(),
+                path: import_doc.path.clone(),
+                size_bytes: import_doc.content.len() as u64,
+            };
+
+            let mut doc_tags = tags.clone();
+            for tag_str in &import_doc.tags {
+                if let Ok(tag) = tag_str.parse::<Tag>()
+                    && !doc_tags.iter().any(|t| t.to_string() == tag.to_string())
+                {
+                    doc_tags.push(tag);
+                }
+            }
+
+            let mut document =
+                Document::new(import_doc.content, source, doc_tags, checksum, metadata);
+            match screen_pii(config.indexing.pii, &mut document) {
+                PiiOutcome::Clean => {}
+                PiiOutcome::Flagged(_) => stats.files_pii_flagged += 1,
+                PiiOutcome::Skipped(_) => {
+                    sender.replace(document.id.clone()).await?;
+                    stats.files_pii_skipped += 1;
+                    continue;
+                }
+            }
+
+            let (chunks, short) = chunker.chunk_screened(&document);
+            stats.chunks_short += short;
+            stats.chunks_created += chunks.len() as u64;
+            stats.files_indexed += 1;
 
-        let checksum = {
-            use sha2::{Digest, Sha256};
-            let hash = Sha256::digest(import_doc.content.as_bytes());
-            hex::encode(hash)
-        };
-
-        // Parse source_type - never fails, defaults to Other("custom")
-        let source_type: SourceType = import_doc
-            .source_type
-            .as_deref()
-            .map(|s| s.parse().unwrap())
-            .unwrap_or_else(|| SourceType::Other("custom".to_string()));
-
-        // Location: url > path > checksum
-        let location = import_doc
-            .url
-            .clone()
-            .or_else(|| import_doc.path.clone())
-            .unwrap_or_else(|| checksum.clone());
-
-        let source = Source::new(source_type, location, import_doc.url.clone());
-
-        let metadata = DocumentMetadata {
-            filename: None,
-            extension: None,
-            language: None,
-            title: import_doc.title.clone(),
-            path: import_doc.path.clone(),
-            size_bytes: import_doc.content.len() as u64,
-        };
-
-        let mut doc_tags = tags.clone();
-        for tag_str in &import_doc.tags {
-            if let Ok(tag) = tag_str.parse::<Tag>()
-                && !doc_tags.iter().any(|t| t.to_string() == tag.to_string())
-            {
-                doc_tags.push(tag);
+            for chunk in chunks {
+                sender.send(chunk).await?;
             }
         }
+        Ok::<(), anyhow::Error>(())
+    };
+
+    let (produced, stored) = tokio::join!(produce, pipeline.run());
+    let stored = stored?;
+    produced?;
+    stats.chunks_deduplicated = stored.deduplicated;
+    stats.secrets_redacted = stored.redacted;
+
+    stats.duration_ms = start_time.elapsed().as_millis() as u64;
+    print!("{}", formatter.format_index_stats(&stats));
+
+    Ok(())
+}
+
+/// Fetch pages (and the links under them, up to `--depth`) and index them by URL.
+async fn import_urls(
+    config: &Config,
+    tags: Vec<Tag>,
+    args: &ImportArgs,
+    format: OutputFormat,
+    verbose: bool,
+) -> Result<()> {
+    let formatter = get_formatter(format);
+    let start_time = Instant::now();
 
-        let document = Document::new(import_doc.content, source, doc_tags, checksum, metadata);
+    let source = WebSource::new();
+    if !source.check_available()? {
+        anyhow::bail!(
+            "Required CLI is not installed.\n{}",
+            source.install_instructions()
+        );
+    }
 
-        let chunks = chunker.chunk(&document);
-        stats.chunks_created += chunks.len() as u64;
-        stats.files_indexed += 1;
+    let options = SyncOptions {
+        query: Some(args.url.join(",")),
+        tags,
+        limit: Some(args.max_pages.unwrap_or(DEFAULT_MAX_PAGES)),
+        depth: args.depth,
+        ..Default::default()
+    };
+    let mut documents = DataSource::sync_stream(&source, options);
 
-        for chunk in chunks {
-            pending_texts.push(chunk.content.clone());
-            pending_chunks.push(chunk);
+    if verbose {
+        println!("Fetching {} page(s), depth {}", args.url.len(), args.depth);
+    }
+
+    if args.validate_only {
+        let mut count = 0u64;
+        while let Some(document) = documents.next().await {
+            let document = document.context("failed to fetch pages")?;
+            if verbose {
+                println!("  {}", document.source.location);
+            }
+            count += 1;
         }
+        println!(
+            "{}",
+            formatter.format_message(&format!(
+                "Validation successful: {} pages ready for import",
+                count
+            ))
+        );
+        return Ok(());
+    }
+
+    let mut stats = index_documents(config, documents, verbose).await?;
+
+    if stats.files_scanned == 0 {
+        println!(
+            "{}",
+            formatter.format_message("No readable pages found at the given URLs.")
+        );
+        return Ok(());
+    }
+
+    stats.duration_ms = start_time.elapsed().as_millis() as u64;
+    print!("{}", formatter.format_index_stats(&stats));
 
-        if pending_texts.len() >= batch_size {
-            process_batch(
-                &embedding_client,
-                vector_store.as_ref(),
-                &mut pending_chunks,
-                &mut pending_texts,
+    Ok(())
+}
+
+/// Parse an OpenAPI/Swagger spec and index one document per endpoint.
+async fn import_openapi(
+    config: &Config,
+    input: &str,
+    tags: &[Tag],
+    args: &ImportArgs,
+    format: OutputFormat,
+    verbose: bool,
+) -> Result<()> {
+    let formatter = get_formatter(format);
+    let start_time = Instant::now();
+
+    let location = match args.file.as_deref() {
+        Some(path) if path.to_string_lossy() != "-" => path.display().to_string(),
+        _ => "stdin".to_string(),
+    };
+    let documents =
+        openapi_documents(input, &location, tags).context("failed to parse OpenAPI spec")?;
+
+    if documents.is_empty() {
+        println!(
+            "{}",
+            formatter.format_message("No endpoints found in spec.")
+        );
+        return Ok(());
+    }
+
+    if verbose || args.validate_only {
+        println!("Found {} endpoints to import", documents.len());
+    }
+
+    if args.validate_only {
+        println!(
+            "{}",
+            formatter.format_message(&format!(
+                "Validation successful: {} endpoints ready for import",
+                documents.len()
+            ))
+        );
+        return Ok(());
+    }
+
+    let documents: DocumentStream = Box::pin(tokio_stream::iter(documents.into_iter().map(Ok)));
+    let mut stats = index_documents(config, documents, verbose).await?;
+
+    stats.duration_ms = start_time.elapsed().as_millis() as u64;
+    print!("{}", formatter.format_index_stats(&stats));
+
+    Ok(())
+}
+
+/// Import CSV rows, Markdown notes or an Obsidian vault through their adapter.
+async fn import_formatted(
+    config: &Config,
+    tags: &[Tag],
+    args: &ImportArgs,
+    format: OutputFormat,
+    verbose: bool,
+) -> Result<()> {
+    let formatter = get_formatter(format);
+    let start_time = Instant::now();
+
+    let source_type: SourceType = match (&args.source, args.from) {
+        (Some(source), _) => source.parse().unwrap(),
+        (None, ImportFormat::Obsidian) => SourceType::Other("obsidian".to_string()),
+        (None, _) => SourceType::Other("custom".to_string()),
+    };
+    let path = args.file.as_deref().filter(|p| p.to_string_lossy() != "-");
+    let location = path.map_or_else(|| "stdin".to_string(), |p| p.display().to_string());
+
+    let documents = match args.from {
+        ImportFormat::Csv => {
+            let columns = CsvColumns {
+                content: args.content_column.clone(),
+                title: args.title_column.clone(),
+                path: args.path_column.clone(),
+                url: args.url_column.clone(),
+                tags: args.tag_columns.clone(),
+            };
+            let input = read_input(path)?;
+            csv_documents(&input, &location, &columns, &source_type, tags)
+                .context("failed to parse CSV")?
+        }
+        ImportFormat::Markdown => match path {
+            Some(dir) if dir.is_dir() => {
+                let mut documents = Vec::new();
+                for file in markdown_files(dir).context("failed to list Markdown files")? {
+                    let input = std::fs::read_to_string(&file)
+                        .with_context(|| format!("failed to read {}", file.display()))?;
+                    let location = file.display().to_string();
+                    documents.push(markdown_document(&input, &location, &source_type, tags));
+                }
+                documents
+            }
+            _ => {
+                let input = read_input(path)?;
+                vec![markdown_document(&input, &location, &source_type, tags)]
+            }
+        },
+        ImportFormat::Obsidian => {
+            let vault = path.context("--from obsidian needs the vault directory")?;
+            obsidian_documents(vault, &source_type, tags).context("failed to read vault")?
+        }
+        ImportFormat::Table => {
+            // Parquet needs random access, so only CSV can come from stdin
+            let table = match path {
+                Some(path) => Table::read(path),
+                None => Table::from_csv(&read_input(None)?),
+            }
+            .context("failed to read table")?;
+            let template = RowTemplate::parse(args.row_template.as_deref(), &table)?;
+            if verbose {
+                println!(
+                    "Read {} rows of {} columns",
+                    table.rows.len(),
+                    table.columns.len()
+                );
+            }
+            table_documents(
+                &table,
+                &location,
+                &template,
+                args.rows_per_chunk,
+                &source_type,
+                tags,
             )
-            .await?;
+        }
+        ImportFormat::Json => unreachable!("JSON imports are handled by handle_import"),
+    };
+    let documents: Vec<Document> = documents
+        .into_iter()
+        .filter(|d| !d.content.trim().is_empty())
+        .collect();
+
+    if documents.is_empty() {
+        println!(
+            "{}",
+            formatter.format_message("No documents found in input.")
+        );
+        return Ok(());
+    }
+
+    if verbose || args.validate_only {
+        println!(
+            "Found {} {} documents to import",
+            documents.len(),
+            args.from
+        );
+    }
+
+    if args.validate_only {
+        println!(
+            "{}",
+            formatter.format_message(&format!(
+                "Validation successful: {} documents ready for import",
+                documents.len()
+            ))
+        );
+        return Ok(());
+    }
+
+    let documents: DocumentStream = Box::pin(tokio_stream::iter(documents.into_iter().map(Ok)));
+    let mut stats = index_documents(config, documents, verbose).await?;
+
+    stats.duration_ms = start_time.elapsed().as_millis() as u64;
+    print!("{}", formatter.format_index_stats(&stats));
+
+    Ok(())
+}
+
+/// Chunk, embed, and store documents as they arrive.
+async fn index_documents(
+    config: &Config,
+    mut documents: DocumentStream,
+    verbose: bool,
+) -> Result<IndexStats> {
+    let embedding_client = EmbeddingClient::new(config);
+    let vector_store =
+        create_backend_with_embedding_config(&config.vector_store, &config.embedding).await?;
+    check_dimension(vector_store.as_ref(), u64::from(config.embedding.dimension)).await?;
+    vector_store.create_collection().await?;
+
+    let chunker = TextChunker::from_config(config);
+    let mut stats = IndexStats {
+        filler: config.indexing.filler,
+        ..Default::default()
+    };
+
+    let (sender, pipeline) = IndexPipeline::new(
+        &embedding_client,
+        vector_store.as_ref(),
+        config.embedding.batch_size as usize,
+    );
+    let pipeline = pipeline
+        .with_dedup(config.indexing.dedup)
+        .with_redaction(config.indexing.redact_secrets)
+        .with_workspace(config.workspace().map(String::from))
+        .with_title_vectors(config.vector_store.title_vectors)
+        .with_max_retries(config.indexing.max_retries);
+
+    let produce = async {
+        let sender = sender;
+        while let Some(document) = documents.next().await {
+            let mut document = document?;
+            stats.files_scanned += 1;
+            if verbose {
+                println!("  {}", document.source.location);
+            }
+            match screen_pii(config.indexing.pii, &mut document) {
+                PiiOutcome::Clean => {}
+                PiiOutcome::Flagged(_) => stats.files_pii_flagged += 1,
+                PiiOutcome::Skipped(kinds) => {
+                    if verbose {
+                        println!("    skipped: contains PII ({})", kinds.join(", "));
+                    }
+                    sender.replace(document.id.clone()).await?;
+                    stats.files_pii_skipped += 1;
+                    continue;
+                }
+            }
+
+            let (chunks, short) = chunker.chunk_screened(&document);
+            stats.chunks_short += short;
+            stats.chunks_created += chunks.len() as u64;
+            stats.files_indexed += 1;
+
+            for chunk in chunks {
+                sender.send(chunk).await?;
+            }
+        }
+        Ok::<(), anyhow::Error>(())
+    };
+
+    let (produced, stored) = tokio::join!(produce, pipeline.run());
+    let stored = stored?;
+    produced.context("failed to fetch documents")?;
+    stats.chunks_deduplicated = stored.deduplicated;
+    stats.secrets_redacted = stored.redacted;
+
+    Ok(stats)
+}
+
+/// Restore exported chunks, upserting their stored embeddings as-is.
+async fn import_chunks(
+    config: &Config,
+    input: &str,
+    tags: &[Tag],
+    args: &ImportArgs,
+    format: OutputFormat,
+    verbose: bool,
+) -> Result<()> {
+    let formatter = get_formatter(format);
+    let start_time = Instant::now();
+
+    let mut chunks = parse_exported_chunks(input)?;
+    if chunks.is_empty() {
+        println!("{}", formatter.format_message("No chunks found in input."));
+        return Ok(());
+    }
+
+    let dimension = chunks[0].dense_vector.len();
+    let documents: HashSet<&str> = chunks.iter().map(|c| c.document_id.as_str()).collect();
+
+    if verbose || args.validate_only {
+        println!(
+            "Found {} chunks from {} documents ({} dimensions)",
+            chunks.len(),
+            documents.len(),
+            dimension
+        );
+    }
+
+    if args.validate_only {
+        println!(
+            "{}",
+            formatter.format_message(&format!(
+                "Validation successful: {} chunks ready for import",
+                chunks.len()
+            ))
+        );
+        return Ok(());
+    }
+
+    let mut stats = IndexStats {
+        files_scanned: documents.len() as u64,
+        files_indexed: documents.len() as u64,
+        chunks_created: chunks.len() as u64,
+        ..Default::default()
+    };
+
+    for chunk in &mut chunks {
+        for tag in tags {
+            if !chunk.tags.contains(tag) {
+                chunk.tags.push(tag.clone());
+            }
+        }
+        if let Some(workspace) = config.workspace() {
+            chunk
+                .attributes
+                .insert(WORKSPACE_ATTRIBUTE.to_string(), workspace.to_string());
         }
     }
 
-    if !pending_texts.is_empty() {
-        process_batch(
-            &embedding_client,
-            vector_store.as_ref(),
-            &mut pending_chunks,
-            &mut pending_texts,
-        )
-        .await?;
+    let vector_store =
+        create_backend_with_dimension(&config.vector_store, dimension as u64).await?;
+    check_dimension(vector_store.as_ref(), dimension as u64).await?;
+    vector_store.create_collection().await?;
+
+    let batch_size = (config.embedding.batch_size as usize).max(1);
+    while !chunks.is_empty() {
+        let batch: Vec<DocumentChunk> = chunks.drain(..batch_size.min(chunks.len())).collect();
+        vector_store
+            .upsert_points(batch)
+            .await
+            .context("failed to upsert chunks")?;
     }
 
     stats.duration_ms = start_time.elapsed().as_millis() as u64;
@@ -180,6 +668,36 @@ pub async fn handle_import(args: ImportArgs, format: OutputFormat, verbose: bool
     Ok(())
 }
 
+fn parse_exported_chunks(input: &str) -> Result<Vec<DocumentChunk>> {
+    let mut chunks: Vec<DocumentChunk> = Vec::new();
+
+    for (i, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let chunk: DocumentChunk = serde_json::from_str(line)
+            .context(format!("failed to parse chunk at line {}", i + 1))?;
+
+        if chunk.dense_vector.is_empty() {
+            anyhow::bail!("chunk at line {} has no embedding", i + 1);
+        }
+        if let Some(first) = chunks.first()
+            && first.dense_vector.len() != chunk.dense_vector.len()
+        {
+            anyhow::bail!(
+                "chunk at line {} has {} dimensions, expected {}",
+                i + 1,
+                chunk.dense_vector.len(),
+                first.dense_vector.len()
+            );
+        }
+        chunks.push(chunk);
+    }
+
+    Ok(chunks)
+}
+
 fn read_input(file: Option<&Path>) -> Result<String> {
     match file {
         Some(path) if path.to_string_lossy() != "-" => {
@@ -219,3 +737,54 @@ fn parse_import_documents(input: &str) -> Result<Vec<ImportDocument>> {
 
     Ok(documents)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk_line(id: &str, vector: &[f32]) -> String {
+        let document = Document::new(
+            "fn main() {}".to_string(),
+            Source::local(format!("/src/{id}.rs")),
+            vec![],
+            "checksum".to_string(),
+            DocumentMetadata::default(),
+        );
+        let mut chunk = DocumentChunk::from_document(
+            &document,
+            document.content.clone(),
+            0,
+            1,
+            0,
+            12,
+            Some(1),
+            Some(1),
+        );
+        chunk.dense_vector = vector.to_vec();
+        serde_json::to_string(&chunk).unwrap()
+    }
+
+    #[test]
+    fn test_parse_exported_chunks_roundtrip() {
+        let input = format!(
+            "{}\n\n{}\n",
+            chunk_line("a", &[0.1, 0.2]),
+            chunk_line("b", &[0.3, 0.4])
+        );
+        let chunks = parse_exported_chunks(&input).unwrap();
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[1].dense_vector, vec![0.3, 0.4]);
+        assert_eq!(chunks[0].source.location, "/src/a.rs");
+    }
+
+    #[test]
+    fn test_parse_exported_chunks_rejects_mismatched_dimensions() {
+        let input = format!(
+            "{}\n{}\n",
+            chunk_line("a", &[0.1, 0.2]),
+            chunk_line("b", &[0.3])
+        );
+        assert!(parse_exported_chunks(&input).is_err());
+        assert!(parse_exported_chunks(&chunk_line("a", &[])).is_err());
+    }
+}
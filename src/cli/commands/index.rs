@@ -2,28 +2,56 @@
 
 use anyhow::{Context, Result};
 use clap::Subcommand;
+use clap_complete::ArgValueCompleter;
 use indicatif::{ProgressBar, ProgressStyle};
+use rayon::prelude::*;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::IsTerminal;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use std::time::Instant;
-use walkdir::WalkDir;
+use tokio::sync::mpsc;
 
+use crate::cli::completion::{complete_sources, complete_tags};
 use crate::cli::output::{IndexStats, get_formatter};
 use crate::models::{
-    Config, Document, DocumentMetadata, OutputFormat, Source, SourceType, Tag, parse_tags,
+    Config, Deleted, Document, DocumentChunk, DocumentMetadata, FillerPolicy, OutputFormat,
+    PathPattern, PiiPolicy, RELATIVE_PATH_ATTRIBUTE, SearchFilter, Source, SourceType, SummaryMode,
+    Tag, TagTemplate, parse_tags,
+};
+use crate::services::{
+    BoilerplateCounter, EmbeddingClient, IndexJournal, IndexPipeline, IndexService,
+    IndexedDocument, JournalState, PiiOutcome, SUMMARY_INPUT_CHARS, Summarizer, TextChunker,
+    VectorStore, check_dimension, create_backend, create_backend_with_embedding_config,
+    extractive_summary, prune, resend_stored_chunks, screen_boilerplate, screen_pii, summary_chunk,
+    truncate_chars,
+};
+use crate::utils::GitRepo;
+use crate::utils::file::{
+    calculate_checksum, is_text_file, read_file_content, read_text_limited, walk_files,
 };
-use crate::services::{EmbeddingClient, TextChunker, create_backend, process_batch};
-use crate::utils::file::{calculate_checksum, is_text_file, read_file_content};
+
+/// Source type and default label of documents indexed with `index add --stdin`.
+const STDIN_SOURCE: &str = "stdin";
 
 #[derive(Debug, Subcommand)]
 pub enum IndexCommand {
     /// Add files or directories to the search index
     Add {
-        /// Path to directory or file to index
-        #[arg(required = true)]
-        path: PathBuf,
+        /// Files, directories or glob patterns to index (e.g., src/ docs/ '**/*.md')
+        #[arg(required_unless_present = "stdin", conflicts_with = "stdin")]
+        paths: Vec<String>,
+
+        /// Index text piped on stdin as one document (e.g., `kubectl logs app | ssearch index add --stdin`)
+        #[arg(long, conflicts_with_all = ["git", "no_ignore", "resume", "exclude", "summarize"])]
+        stdin: bool,
+
+        /// Label of the --stdin document; indexing the same label again replaces it
+        #[arg(long, value_name = "LABEL", conflicts_with = "paths")]
+        name: Option<String>,
 
         /// Tags to apply to indexed documents (comma-separated, format: key:value)
-        #[arg(long, short = 't')]
+        #[arg(long, short = 't', add = ArgValueCompleter::new(complete_tags))]
         tags: Option<String>,
 
         /// File patterns to exclude (can be specified multiple times)
@@ -33,13 +61,65 @@ pub enum IndexCommand {
         /// Show what would be indexed without actually indexing
         #[arg(long)]
         dry_run: bool,
+
+        /// Re-index all files, even unchanged ones (e.g., after changing tags or chunk settings)
+        #[arg(long)]
+        full: bool,
+
+        /// Index a git working tree: respect .gitignore and record commit, branch, and author
+        #[arg(long)]
+        git: bool,
+
+        /// Also index files excluded by .gitignore, .ignore and .ssearchignore
+        #[arg(long, conflicts_with = "git")]
+        no_ignore: bool,
+
+        /// Continue an interrupted run from its checkpoint in .ssearch/state/
+        #[arg(long, conflicts_with = "dry_run")]
+        resume: bool,
+
+        /// Store a summary chunk per document (extractive, llm); overrides [indexing] summaries
+        #[arg(
+            long,
+            value_name = "MODE",
+            num_args = 0..=1,
+            require_equals = true,
+            default_missing_value = "extractive"
+        )]
+        summarize: Option<SummaryMode>,
+
+        /// Chunk size in tokens for every file, replacing [indexing] chunk_size and its
+        /// per-type overrides (add --full to re-chunk files already indexed)
+        #[arg(long, value_name = "TOKENS", value_parser = clap::value_parser!(u32).range(1..))]
+        chunk_size: Option<u32>,
+
+        /// Collection to use instead of the configured one
+        #[arg(long)]
+        collection: Option<String>,
     },
 
-    /// Delete indexed documents by path
+    /// Delete indexed documents by path, or every chunk matching tags and sources
     Delete {
-        /// Path to file or directory to remove from index
-        #[arg(required = true)]
-        path: PathBuf,
+        /// Path to file or directory to remove from index; with --tags or --source,
+        /// a path prefix that chunks must also match
+        #[arg(required_unless_present_any = ["tags", "source"])]
+        path: Option<PathBuf>,
+
+        /// Only delete chunks carrying all of these tags (e.g., 'project:foo,team:core')
+        #[arg(long, short = 't', add = ArgValueCompleter::new(complete_tags))]
+        tags: Option<String>,
+
+        /// Only delete chunks from these source types (e.g., 'jira,confluence')
+        #[arg(long, short = 's', add = ArgValueCompleter::new(complete_sources))]
+        source: Option<String>,
+
+        /// With --tags, --source or --soft, also delete matches from other workspaces
+        #[arg(long)]
+        all_workspaces: bool,
+
+        /// Hide the matches from searches instead of removing them; undo with `index restore`
+        #[arg(long)]
+        soft: bool,
 
         /// Show what would be deleted without actually deleting
         #[arg(long)]
@@ -48,6 +128,127 @@ pub enum IndexCommand {
         /// Skip confirmation prompt
         #[arg(long, short = 'y')]
         force: bool,
+
+        /// Collection to use instead of the configured one
+        #[arg(long)]
+        collection: Option<String>,
+    },
+
+    /// Bring back documents removed with `index delete --soft`
+    Restore {
+        /// Path to a file or directory to restore; with --tags or --source, a path
+        /// prefix that chunks must also match
+        #[arg(required_unless_present_any = ["tags", "source"])]
+        path: Option<PathBuf>,
+
+        /// Only restore chunks carrying all of these tags (e.g., 'project:foo,team:core')
+        #[arg(long, short = 't', add = ArgValueCompleter::new(complete_tags))]
+        tags: Option<String>,
+
+        /// Only restore chunks from these source types (e.g., 'jira,confluence')
+        #[arg(long, short = 's', add = ArgValueCompleter::new(complete_sources))]
+        source: Option<String>,
+
+        /// Also restore matches from other workspaces
+        #[arg(long)]
+        all_workspaces: bool,
+
+        /// Show what would be restored without restoring anything
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Collection to use instead of the configured one
+        #[arg(long)]
+        collection: Option<String>,
+    },
+
+    /// Show what the index holds: documents and chunks per source, tags, size and index times
+    List {
+        /// Collection to use instead of the configured one
+        #[arg(long)]
+        collection: Option<String>,
+    },
+
+    /// List the stored chunks of one document with their positions, line ranges and token estimates
+    Inspect {
+        /// Indexed file path, or a document ID from search output
+        #[arg(long, short = 'd', value_name = "PATH_OR_ID")]
+        document: String,
+
+        /// Print each chunk's full text instead of its first and last lines
+        #[arg(long)]
+        content: bool,
+
+        /// Collection to use instead of the configured one
+        #[arg(long)]
+        collection: Option<String>,
+    },
+
+    /// Remove chunks of local files that were deleted or changed since they were indexed
+    Gc {
+        /// Show what would be removed without deleting anything
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Collection to use instead of the configured one
+        #[arg(long)]
+        collection: Option<String>,
+    },
+
+    /// Remove chunks past their [indexing.retention] max_age, then the least recently
+    /// updated until at most max_points remain
+    Prune {
+        /// Show what would be removed without deleting anything
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Collection to use instead of the configured one
+        #[arg(long)]
+        collection: Option<String>,
+    },
+
+    /// Compare local files with the index: unindexed, stale and orphaned documents
+    Verify {
+        /// File, directory or glob pattern to check
+        path: String,
+
+        /// File patterns to exclude (can be specified multiple times)
+        #[arg(long, short = 'e')]
+        exclude: Vec<String>,
+
+        /// Index unindexed and stale files and remove orphaned documents
+        #[arg(long)]
+        repair: bool,
+
+        /// Collection to use instead of the configured one
+        #[arg(long)]
+        collection: Option<String>,
+    },
+
+    /// Re-embed stored chunks with the current model, without fetching their sources again
+    Reembed {
+        /// Only re-embed chunks carrying all of these tags (e.g., 'project:foo,team:core')
+        #[arg(long, short = 't', add = ArgValueCompleter::new(complete_tags))]
+        tags: Option<String>,
+
+        /// Only re-embed chunks from these source types (e.g., 'jira,confluence')
+        #[arg(long, short = 's', add = ArgValueCompleter::new(complete_sources))]
+        source: Option<String>,
+
+        /// Store the new embeddings in this collection, using its model, instead of in place
+        #[arg(long, value_name = "COLLECTION")]
+        into: Option<String>,
+
+        /// Collection to use instead of the configured one
+        #[arg(long)]
+        collection: Option<String>,
+    },
+
+    /// Rebuild the vector index with the current tuning options (pgvector), e.g. after bulk loads
+    Reindex {
+        /// Collection to use instead of the configured one
+        #[arg(long)]
+        collection: Option<String>,
     },
 
     /// Clear all indexed documents
@@ -55,35 +256,174 @@ pub enum IndexCommand {
         /// Skip confirmation prompt
         #[arg(long, short = 'y')]
         force: bool,
+
+        /// Collection to use instead of the configured one
+        #[arg(long)]
+        collection: Option<String>,
     },
 }
 
 pub async fn handle_index(cmd: IndexCommand, format: OutputFormat, verbose: bool) -> Result<()> {
     match cmd {
         IndexCommand::Add {
-            path,
+            stdin: true,
+            name,
+            tags,
+            dry_run,
+            full,
+            chunk_size,
+            collection,
+            ..
+        } => {
+            let options = StdinOptions {
+                name: name.unwrap_or_else(|| STDIN_SOURCE.to_string()),
+                dry_run,
+                full,
+                chunk_size,
+                collection,
+            };
+            handle_add_stdin(options, tags, format, verbose).await
+        }
+        IndexCommand::Add {
+            paths,
             tags,
             exclude,
             dry_run,
-        } => handle_add(path, tags, exclude, dry_run, format, verbose).await,
+            full,
+            git,
+            no_ignore,
+            resume,
+            summarize,
+            chunk_size,
+            collection,
+            ..
+        } => {
+            let options = AddOptions {
+                dry_run,
+                full,
+                git,
+                no_ignore,
+                resume,
+                summarize,
+                chunk_size,
+                collection,
+            };
+            handle_add(paths, tags, exclude, options, format, verbose).await
+        }
+        IndexCommand::Delete {
+            path,
+            tags: None,
+            source: None,
+            soft: false,
+            dry_run,
+            force,
+            collection,
+            ..
+        } => {
+            // clap requires a path when no filter is given
+            let path = path.unwrap_or_default();
+            handle_delete(path, dry_run, force, collection, format, verbose).await
+        }
         IndexCommand::Delete {
             path,
+            tags,
+            source,
+            all_workspaces,
+            soft,
             dry_run,
             force,
-        } => handle_delete(path, dry_run, force, format, verbose).await,
-        IndexCommand::Clear { force } => handle_clear(force, format, verbose).await,
+            collection,
+        } => {
+            let selection = ChunkSelection {
+                path,
+                tags,
+                source,
+                all_workspaces,
+            };
+            handle_delete_matching(selection, soft, dry_run, force, collection, format).await
+        }
+        IndexCommand::Restore {
+            path,
+            tags,
+            source,
+            all_workspaces,
+            dry_run,
+            collection,
+        } => {
+            let selection = ChunkSelection {
+                path,
+                tags,
+                source,
+                all_workspaces,
+            };
+            handle_restore(selection, dry_run, collection, format).await
+        }
+        IndexCommand::List { collection } => handle_list(collection, format).await,
+        IndexCommand::Inspect {
+            document,
+            content,
+            collection,
+        } => handle_inspect(&document, content, collection, format).await,
+        IndexCommand::Gc {
+            dry_run,
+            collection,
+        } => handle_gc(dry_run, collection, format, verbose).await,
+        IndexCommand::Prune {
+            dry_run,
+            collection,
+        } => handle_prune(dry_run, collection, format).await,
+        IndexCommand::Verify {
+            path,
+            exclude,
+            repair,
+            collection,
+        } => handle_verify(&path, exclude, repair, collection, format, verbose).await,
+        IndexCommand::Reembed {
+            tags,
+            source,
+            into,
+            collection,
+        } => {
+            let options = ReembedOptions {
+                tags,
+                source,
+                into,
+                collection,
+            };
+            handle_reembed(options, format).await
+        }
+        IndexCommand::Reindex { collection } => handle_reindex(collection, format).await,
+        IndexCommand::Clear { force, collection } => {
+            handle_clear(force, collection, format, verbose).await
+        }
     }
 }
 
+struct AddOptions {
+    dry_run: bool,
+    full: bool,
+    git: bool,
+    no_ignore: bool,
+    resume: bool,
+    /// Summary mode, when overriding `[indexing] summaries`
+    summarize: Option<SummaryMode>,
+    /// Tokens per chunk for every file, when overriding `[indexing]`
+    chunk_size: Option<u32>,
+    collection: Option<String>,
+}
+
 async fn handle_add(
-    path: PathBuf,
+    paths: Vec<String>,
     tags: Option<String>,
     exclude: Vec<String>,
-    dry_run: bool,
+    options: AddOptions,
     format: OutputFormat,
     verbose: bool,
 ) -> Result<()> {
-    let config = Config::load()?.config;
+    let mut config = Config::load_with_collection(options.collection.as_deref())?.config;
+    if let Some(chunk_size) = options.chunk_size {
+        config.indexing.set_chunk_size(chunk_size);
+    }
     let formatter = get_formatter(format);
     let start_time = Instant::now();
 
@@ -92,13 +432,65 @@ async fn handle_add(
     } else {
         Vec::new()
     };
+    let tag_templates = config
+        .tag_templates(&SourceType::Local)
+        .context("invalid [tags] template")?;
 
-    let path = path.canonicalize().context("invalid path")?;
-    if !path.exists() {
-        anyhow::bail!("path does not exist: {}", path.display());
-    }
+    let targets = paths
+        .iter()
+        .map(|arg| IndexTarget::parse(arg))
+        .collect::<Result<Vec<_>>>()?;
+    let roots: Vec<PathBuf> = targets.iter().map(IndexTarget::key).collect();
 
-    let files = collect_files(&path, &exclude, &config.indexing.exclude_patterns)?;
+    let AddOptions {
+        dry_run,
+        full,
+        git,
+        no_ignore,
+        resume,
+        summarize,
+        collection,
+        ..
+    } = options;
+    let summarizer = Summarizer::new(summarize.unwrap_or(config.indexing.summaries), &config.llm);
+
+    // Files from every target, merged so overlapping targets index each file once
+    let mut files = Vec::new();
+    let mut git_attributes: HashMap<PathBuf, BTreeMap<String, String>> = HashMap::new();
+    for target in &targets {
+        if git {
+            let base = target.base();
+            let repo = GitRepo::discover(base).context("not a git repository")?;
+            let authors = repo.last_authors(base)?;
+            if verbose {
+                println!(
+                    "Git repository: {} ({} @ {})",
+                    repo.root.display(),
+                    repo.branch,
+                    &repo.commit[..repo.commit.len().min(12)]
+                );
+            }
+            for file in repo.list_files(base)? {
+                if !target.contains(&file) {
+                    continue;
+                }
+                let mut attributes = BTreeMap::from([
+                    ("commit".to_string(), repo.commit.clone()),
+                    ("branch".to_string(), repo.branch.clone()),
+                ]);
+                if let Some(author) = authors.get(&file) {
+                    attributes.insert("author".to_string(), author.clone());
+                }
+                git_attributes.insert(file.clone(), attributes);
+                files.push(file);
+            }
+        } else {
+            files.extend(target.files(!no_ignore)?);
+        }
+    }
+    files.sort();
+    files.dedup();
+    let files = filter_excluded(files, &exclude, &config.indexing.exclude_patterns);
 
     if files.is_empty() {
         println!("{}", formatter.format_message("No files found to index."));
@@ -120,11 +512,57 @@ async fn handle_add(
         return Ok(());
     }
 
-    let embedding_client = EmbeddingClient::new(&config);
-    let vector_store = create_backend(&config.vector_store).await?;
+    let service = IndexService::from_config(&config).await?;
+    let vector_store = service.store();
+    check_dimension(vector_store, u64::from(config.embedding.dimension)).await?;
     vector_store.create_collection().await?;
 
-    let chunker = TextChunker::new(&config.indexing);
+    // Checkpoint journal, so a failed run can continue with --resume
+    let journal_path = Config::state_dir()
+        .map(|dir| IndexJournal::path_for(&dir, &roots, &config.vector_store.collection))
+        .context("cannot locate the .ssearch/state directory")?;
+    let checkpoint = if resume {
+        let checkpoint = IndexJournal::replay(&journal_path)?;
+        match checkpoint {
+            Some(ref state) if verbose => println!(
+                "Resuming: {} documents already indexed by the interrupted run",
+                state.completed.len()
+            ),
+            Some(_) => {}
+            None => println!(
+                "{}",
+                formatter.format_message("No interrupted run to resume; indexing from the start.")
+            ),
+        }
+        checkpoint
+    } else {
+        None
+    };
+    let journal = Mutex::new(IndexJournal::open(
+        &journal_path,
+        &roots,
+        &config.vector_store.collection,
+        checkpoint.is_some(),
+    )?);
+
+    // Documents already indexed under the targets, keyed by document ID
+    let existing: HashMap<String, IndexedDocument> = vector_store
+        .list_documents(&SourceType::Local)
+        .await?
+        .into_iter()
+        .filter(|doc| {
+            let location = Path::new(&doc.location);
+            targets.iter().any(|target| target.contains(location))
+        })
+        .map(|doc| (doc.document_id.clone(), doc))
+        .collect();
+
+    if verbose && !existing.is_empty() {
+        println!(
+            "{} documents already indexed under these paths",
+            existing.len()
+        );
+    }
 
     let pb = ProgressBar::new(files.len() as u64);
     pb.set_style(
@@ -138,84 +576,347 @@ async fn handle_add(
 
     let mut stats = IndexStats {
         files_scanned: files.len() as u64,
+        filler: config.indexing.filler,
         ..Default::default()
     };
 
-    let batch_size = config.embedding.batch_size as usize;
-    let mut pending_chunks = Vec::new();
-    let mut pending_texts = Vec::new();
-
-    for file_path in &files {
-        pb.inc(1);
-
-        if !is_text_file(file_path) {
-            stats.files_skipped += 1;
-            continue;
+    let mut seen_ids: HashSet<String> = HashSet::new();
+    let (sender, pipeline) = service.pipeline();
+    let retry_notice = |message: String| pb.println(message);
+    let record_stored = |document_ids: &[String]| {
+        let mut journal = journal.lock().unwrap_or_else(|e| e.into_inner());
+        if let Err(e) = journal.stored(document_ids) {
+            pb.println(format!("Warning: {}", e));
         }
+    };
+    let pipeline = pipeline
+        .with_retry_notice(&retry_notice)
+        .with_stored_notice(&record_stored);
 
-        let content = match read_file_content(file_path, config.indexing.max_file_size) {
-            Ok(c) => c,
-            Err(e) => {
-                if verbose {
-                    pb.println(format!("Skipping {}: {}", file_path.display(), e));
+    // Read, checksum and chunk files across cores while earlier batches are
+    // embedded and upserted; the bounded queue keeps workers from running ahead
+    let mut planner = FilePlanner {
+        chunker: service.chunker().clone(),
+        tags,
+        tag_templates,
+        project_root: Config::project_root().and_then(|root| root.canonicalize().ok()),
+        max_file_size: config.indexing.max_file_size,
+        full,
+        indexed: existing.clone(),
+        store: service.shared_store(),
+        workspace: config.workspace().map(String::from),
+        checkpoint,
+        git_attributes,
+        summaries: summarizer.mode() != SummaryMode::Off,
+        pii: config.indexing.pii,
+        boilerplate: HashSet::new(),
+        filler: config.indexing.filler,
+    };
+    // Counted over every file up front, so the first copies are screened too
+    let min_files = config.indexing.boilerplate_min_files;
+    if min_files > 0 {
+        let counter = files
+            .par_iter()
+            .fold(BoilerplateCounter::default, |mut counter, file| {
+                counter.add_file(&planner.chunks(file));
+                counter
+            })
+            .reduce(BoilerplateCounter::default, BoilerplateCounter::merge);
+        planner.boilerplate = counter.boilerplate(min_files);
+    }
+    let planner = Arc::new(planner);
+    let (prepared_tx, prepared_rx) = mpsc::channel(PREPARED_QUEUE_SIZE);
+    let workers = tokio::task::spawn_blocking(move || {
+        // Fails, stopping the workers, once the consumer is gone
+        let _ = files.par_iter().try_for_each_with(prepared_tx, |tx, file| {
+            tx.blocking_send(planner.prepare(file))
+        });
+    });
+
+    let produce = async {
+        let sender = sender;
+        let mut prepared_rx = prepared_rx;
+        while let Some(prepared) = prepared_rx.recv().await {
+            pb.inc(1);
+            let (document_id, checksum, replace, mut chunks, summary_input) = match prepared {
+                PreparedFile::Skipped { path, reason } => {
+                    if verbose && let Some(reason) = reason {
+                        pb.println(format!("Skipping {}: {}", path.display(), reason));
+                    }
+                    stats.files_skipped += 1;
+                    continue;
+                }
+                PreparedFile::PiiSkipped {
+                    path,
+                    document_id,
+                    kinds,
+                } => {
+                    if verbose {
+                        pb.println(format!(
+                            "Skipping {}: contains PII ({})",
+                            path.display(),
+                            kinds.join(", ")
+                        ));
+                    }
+                    // Drop any copy indexed before the file gained personal data
+                    if existing.contains_key(&document_id) {
+                        sender.replace(document_id).await?;
+                    }
+                    stats.files_pii_skipped += 1;
+                    continue;
+                }
+                PreparedFile::Unchanged { document_id } => {
+                    seen_ids.insert(document_id);
+                    stats.files_unchanged += 1;
+                    continue;
+                }
+                PreparedFile::Changed {
+                    document_id,
+                    checksum,
+                    replace,
+                    chunks,
+                    summary_input,
+                    pii_flagged,
+                    short_chunks,
+                    boilerplate_chunks,
+                } => {
+                    if pii_flagged {
+                        stats.files_pii_flagged += 1;
+                    }
+                    stats.chunks_short += short_chunks;
+                    stats.chunks_boilerplate += boilerplate_chunks;
+                    (document_id, checksum, replace, chunks, summary_input)
+                }
+            };
+            if let Some(input) = summary_input {
+                let summary = match summarizer.summarize(&input).await {
+                    Ok(summary) => summary,
+                    Err(e) => {
+                        pb.println(format!(
+                            "Warning: {} summary failed, using an extractive one: {}",
+                            document_id, e
+                        ));
+                        extractive_summary(&input)
+                    }
+                };
+                if !summary.is_empty() {
+                    chunks.extend(summary_chunk(&chunks, summary));
                 }
-                stats.files_skipped += 1;
-                continue;
             }
-        };
+            seen_ids.insert(document_id.clone());
+            if replace {
+                // Changed content may produce fewer chunks; drop the old ones first
+                sender.replace(document_id.clone()).await?;
+            }
+            stats.chunks_created += chunks.len() as u64;
+            stats.files_indexed += 1;
 
-        if content.is_empty() {
-            stats.files_skipped += 1;
-            continue;
+            journal
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .pending(&document_id, &checksum)?;
+            for chunk in chunks {
+                sender.send(chunk).await?;
+            }
+            sender.complete(document_id).await?;
         }
+        Ok::<(), anyhow::Error>(())
+    };
 
-        let checksum = calculate_checksum(&content);
-        let source = Source::local(file_path.to_string_lossy().to_string());
-        let metadata = DocumentMetadata {
-            filename: file_path
-                .file_name()
-                .map(|n| n.to_string_lossy().to_string()),
-            extension: file_path
-                .extension()
-                .map(|e| e.to_string_lossy().to_string()),
-            language: detect_language(file_path),
-            title: None,
-            path: Some(file_path.to_string_lossy().to_string()),
-            size_bytes: content.len() as u64,
-        };
-
-        let document = Document::new(content, source, tags.clone(), checksum, metadata);
-        let chunks = chunker.chunk(&document);
-        stats.chunks_created += chunks.len() as u64;
-        stats.files_indexed += 1;
+    let (produced, stored) = tokio::join!(produce, pipeline.run());
+    workers.await.context("file reading task failed")?;
+    if produced.is_err() || stored.is_err() {
+        let collection_arg = collection
+            .map(|c| format!(" --collection {}", c))
+            .unwrap_or_default();
+        let target_args: Vec<String> = targets.iter().map(ToString::to_string).collect();
+        pb.println(format!(
+            "Indexing stopped. Run 'ssearch index add {}{} --resume' to continue.",
+            target_args.join(" "),
+            collection_arg
+        ));
+    }
+    let stored = stored?;
+    produced?;
+    stats.chunks_deduplicated = stored.deduplicated;
+    stats.secrets_redacted = stored.redacted;
 
-        for chunk in chunks {
-            pending_texts.push(chunk.content.clone());
-            pending_chunks.push(chunk);
+    let removed_ids = removed_documents(existing, &seen_ids);
+    if !removed_ids.is_empty() {
+        if verbose {
+            pb.println(format!(
+                "Removing {} deleted files from index",
+                removed_ids.len()
+            ));
         }
+        vector_store.delete_by_document_ids(&removed_ids).await?;
+        stats.files_removed = removed_ids.len() as u64;
+    }
+    journal
+        .into_inner()
+        .unwrap_or_else(|e| e.into_inner())
+        .finish()?;
 
-        if pending_texts.len() >= batch_size {
-            process_batch(
-                &embedding_client,
-                vector_store.as_ref(),
-                &mut pending_chunks,
-                &mut pending_texts,
-            )
-            .await?;
+    pb.finish_and_clear();
+    stats.duration_ms = start_time.elapsed().as_millis() as u64;
+    print!("{}", formatter.format_index_stats(&stats));
+
+    Ok(())
+}
+
+/// IDs of the indexed documents that were not seen in this run and whose
+/// files no longer exist.
+fn removed_documents(
+    mut existing: HashMap<String, IndexedDocument>,
+    seen_ids: &HashSet<String>,
+) -> Vec<String> {
+    existing.retain(|id, doc| !seen_ids.contains(id) && !Path::new(&doc.location).exists());
+    existing.into_keys().collect()
+}
+
+/// Options for `index add --stdin`.
+struct StdinOptions {
+    /// Label the document is stored under
+    name: String,
+    dry_run: bool,
+    full: bool,
+    chunk_size: Option<u32>,
+    collection: Option<String>,
+}
+
+/// Index text piped on stdin as a single `stdin` document named by its label.
+async fn handle_add_stdin(
+    options: StdinOptions,
+    tags: Option<String>,
+    format: OutputFormat,
+    verbose: bool,
+) -> Result<()> {
+    let mut config = Config::load_with_collection(options.collection.as_deref())?.config;
+    if let Some(chunk_size) = options.chunk_size {
+        config.indexing.set_chunk_size(chunk_size);
+    }
+    let formatter = get_formatter(format);
+    let start_time = Instant::now();
+
+    let tags: Vec<Tag> = if let Some(ref tag_str) = tags {
+        parse_tags(tag_str).context("failed to parse tags")?
+    } else {
+        Vec::new()
+    };
+    let source_type = SourceType::Other(STDIN_SOURCE.to_string());
+    let tag_templates = config
+        .tag_templates(&source_type)
+        .context("invalid [tags] template")?;
+
+    let stdin = std::io::stdin();
+    if stdin.is_terminal() {
+        anyhow::bail!(
+            "--stdin expects piped input (e.g., `kubectl logs app | ssearch index add --stdin`)"
+        );
+    }
+    let content = read_text_limited(stdin.lock(), config.indexing.max_file_size)
+        .context("failed to read stdin")?;
+    if content.trim().is_empty() {
+        println!("{}", formatter.format_message("Nothing to index on stdin."));
+        return Ok(());
+    }
+
+    let checksum = calculate_checksum(&content);
+    let source = Source::new(
+        source_type.clone(),
+        format!("{}:{}", STDIN_SOURCE, options.name),
+        None,
+    );
+    let metadata = DocumentMetadata {
+        title: Some(options.name.clone()),
+        size_bytes: content.len() as u64,
+        ..DocumentMetadata::default()
+    };
+    let mut document = Document::new(content, source, tags, checksum, metadata);
+    document.add_template_tags(&tag_templates, &BTreeMap::new());
+
+    let mut stats = IndexStats {
+        files_scanned: 1,
+        filler: config.indexing.filler,
+        ..Default::default()
+    };
+    match screen_pii(config.indexing.pii, &mut document) {
+        PiiOutcome::Clean => {}
+        PiiOutcome::Flagged(_) => stats.files_pii_flagged += 1,
+        PiiOutcome::Skipped(kinds) => {
+            println!(
+                "{}",
+                formatter.format_message(&format!(
+                    "Not indexing stdin: contains PII ({})",
+                    kinds.join(", ")
+                ))
+            );
+            return Ok(());
         }
     }
 
-    if !pending_texts.is_empty() {
-        process_batch(
-            &embedding_client,
-            vector_store.as_ref(),
-            &mut pending_chunks,
-            &mut pending_texts,
-        )
-        .await?;
+    if options.dry_run {
+        let (chunks, _) = TextChunker::from_config(&config).chunk_screened(&document);
+        println!(
+            "{}",
+            formatter.format_message(&format!(
+                "Dry run: Would index {} bytes from stdin as '{}' ({} chunks)",
+                document.metadata.size_bytes,
+                options.name,
+                chunks.len()
+            ))
+        );
+        return Ok(());
     }
 
-    pb.finish_and_clear();
+    let service = IndexService::from_config(&config).await?;
+    let vector_store = service.store();
+    check_dimension(vector_store, u64::from(config.embedding.dimension)).await?;
+    vector_store.create_collection().await?;
+
+    let indexed = vector_store
+        .list_documents(&source_type)
+        .await?
+        .into_iter()
+        .find(|doc| doc.document_id == document.id);
+    if let Some(ref indexed) = indexed
+        && !options.full
+        && indexed.checksum == vector_store.stored_checksum(&document.checksum)
+    {
+        stats.files_unchanged = 1;
+        stats.duration_ms = start_time.elapsed().as_millis() as u64;
+        print!("{}", formatter.format_index_stats(&stats));
+        return Ok(());
+    }
+    if verbose {
+        println!(
+            "Indexing {} bytes from stdin as '{}'",
+            document.metadata.size_bytes, options.name
+        );
+    }
+
+    let (chunks, short) = service.chunker().chunk_screened(&document);
+    stats.chunks_short = short;
+    stats.chunks_created = chunks.len() as u64;
+    let (sender, pipeline) = service.pipeline();
+    let produce = async {
+        let sender = sender;
+        if indexed.is_some() {
+            // The new text may produce fewer chunks; drop the old ones first
+            sender.replace(document.id.clone()).await?;
+        }
+        for chunk in chunks {
+            sender.send(chunk).await?;
+        }
+        Ok::<(), anyhow::Error>(())
+    };
+    let (produced, stored) = tokio::join!(produce, pipeline.run());
+    let stored = stored?;
+    produced?;
+
+    stats.files_indexed = 1;
+    stats.chunks_deduplicated = stored.deduplicated;
+    stats.secrets_redacted = stored.redacted;
     stats.duration_ms = start_time.elapsed().as_millis() as u64;
     print!("{}", formatter.format_index_stats(&stats));
 
@@ -226,10 +927,11 @@ async fn handle_delete(
     path: PathBuf,
     dry_run: bool,
     force: bool,
+    collection: Option<String>,
     format: OutputFormat,
     verbose: bool,
 ) -> Result<()> {
-    let config = Config::load()?.config;
+    let config = Config::load_with_collection(collection.as_deref())?.config;
     let formatter = get_formatter(format);
 
     let path = path.canonicalize().context("invalid path")?;
@@ -268,7 +970,7 @@ async fn handle_delete(
     let files = if path.is_file() {
         vec![path.clone()]
     } else {
-        collect_files(&path, &[], &[])?
+        collect_files(&path, &[], &[], false)?
     };
 
     if files.is_empty() {
@@ -298,104 +1000,1371 @@ async fn handle_delete(
     Ok(())
 }
 
-async fn handle_clear(force: bool, format: OutputFormat, verbose: bool) -> Result<()> {
-    let config = Config::load()?.config;
-    let formatter = get_formatter(format);
-
-    if verbose {
-        println!("Clearing all indexed documents...");
-    }
-
-    if !force {
-        println!("This will delete ALL indexed documents. Continue? [y/N]");
-        let mut input = String::new();
-        std::io::stdin().read_line(&mut input)?;
-        if !input.trim().eq_ignore_ascii_case("y") {
-            println!("{}", formatter.format_message("Cancelled."));
-            return Ok(());
-        }
-    }
+/// Comma-separated `--source` types.
+fn parse_source_types(source: Option<&str>) -> Vec<SourceType> {
+    source
+        .map(|s| {
+            s.split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(|s| s.parse().unwrap())
+                .collect()
+        })
+        .unwrap_or_default()
+}
 
-    let vector_store = create_backend(&config.vector_store).await?;
-    vector_store.clear_collection().await?;
+/// Chunks selected by `index delete` or `index restore` with tags, sources
+/// or a path prefix.
+struct ChunkSelection {
+    path: Option<PathBuf>,
+    tags: Option<String>,
+    source: Option<String>,
+    all_workspaces: bool,
+}
 
-    println!(
-        "{}",
-        formatter.format_message("All indexed documents have been cleared.")
-    );
+impl ChunkSelection {
+    fn filter(&self, config: &Config) -> Result<SearchFilter> {
+        let tags = self
+            .tags
+            .as_deref()
+            .map(parse_tags)
+            .transpose()
+            .context("failed to parse tags")?
+            .unwrap_or_default();
+        let source_types = parse_source_types(self.source.as_deref());
+        // Deleted files are still indexed, so the prefix need not exist on disk
+        let paths = match self.path {
+            Some(ref path) => {
+                let path = path
+                    .canonicalize()
+                    .or_else(|_| std::path::absolute(path))
+                    .context("invalid path")?;
+                let mut prefix = path.to_string_lossy().to_string();
+                if path.is_dir() && !prefix.ends_with('/') {
+                    prefix.push('/');
+                }
+                vec![PathPattern::new(prefix)]
+            }
+            None => Vec::new(),
+        };
+        let workspace = config.workspace().filter(|_| !self.all_workspaces);
 
-    Ok(())
+        Ok(SearchFilter::new()
+            .with_tags(tags)
+            .with_source_types(source_types)
+            .with_paths(paths)
+            .with_workspace(workspace))
+    }
 }
 
-fn collect_files(
-    path: &PathBuf,
-    exclude: &[String],
-    default_exclude: &[String],
-) -> Result<Vec<PathBuf>> {
-    let mut files = Vec::new();
+async fn handle_delete_matching(
+    selection: ChunkSelection,
+    soft: bool,
+    dry_run: bool,
+    force: bool,
+    collection: Option<String>,
+    format: OutputFormat,
+) -> Result<()> {
+    let config = Config::load_with_collection(collection.as_deref())?.config;
+    let formatter = get_formatter(format);
+    let filter = selection.filter(&config)?;
+    // A hard delete also removes chunks that were already soft-deleted
+    let (filter, action) = if soft {
+        (filter, "soft-delete")
+    } else {
+        (filter.with_deleted(Deleted::Any), "delete")
+    };
+
+    let vector_store =
+        create_backend_with_embedding_config(&config.vector_store, &config.embedding).await?;
+    let count = vector_store.count_by_filter(&filter).await?;
+    if count == 0 {
+        println!(
+            "{}",
+            formatter.format_message(&format!("No chunks match '{}'.", filter))
+        );
+        return Ok(());
+    }
+
+    if dry_run {
+        println!(
+            "{}",
+            formatter.format_message(&format!(
+                "Dry run: Would {} {} chunk(s) matching '{}'",
+                action, count, filter
+            ))
+        );
+        return Ok(());
+    }
+
+    if !force {
+        println!(
+            "This will {} {} chunk(s) matching '{}'. Continue? [y/N]",
+            action, count, filter
+        );
+        let mut input = String::new();
+        std::io::stdin().read_line(&mut input)?;
+        if !input.trim().eq_ignore_ascii_case("y") {
+            println!("{}", formatter.format_message("Cancelled."));
+            return Ok(());
+        }
+    }
+
+    let message = if soft {
+        let deleted = vector_store
+            .soft_delete_by_filter(&filter, chrono::Utc::now())
+            .await?;
+        format!(
+            "Soft-deleted {} chunk(s); bring them back with 'ssearch index restore'",
+            deleted
+        )
+    } else {
+        let deleted = vector_store.delete_by_filter(&filter).await?;
+        format!("Deleted {} chunk(s) from index", deleted)
+    };
+    println!("{}", formatter.format_message(&message));
+
+    Ok(())
+}
+
+async fn handle_restore(
+    selection: ChunkSelection,
+    dry_run: bool,
+    collection: Option<String>,
+    format: OutputFormat,
+) -> Result<()> {
+    let config = Config::load_with_collection(collection.as_deref())?.config;
+    let formatter = get_formatter(format);
+    let selected = selection.filter(&config)?;
+    let filter = selected.clone().with_deleted(Deleted::Only);
+
+    let vector_store =
+        create_backend_with_embedding_config(&config.vector_store, &config.embedding).await?;
+    let count = vector_store.count_by_filter(&filter).await?;
+    if count == 0 {
+        println!(
+            "{}",
+            formatter.format_message(&format!("No soft-deleted chunks match '{}'.", selected))
+        );
+        return Ok(());
+    }
+
+    if dry_run {
+        println!(
+            "{}",
+            formatter.format_message(&format!(
+                "Dry run: Would restore {} chunk(s) matching '{}'",
+                count, selected
+            ))
+        );
+        return Ok(());
+    }
+
+    let restored = vector_store.restore_by_filter(&filter).await?;
+    println!(
+        "{}",
+        formatter.format_message(&format!("Restored {} chunk(s)", restored))
+    );
+
+    Ok(())
+}
+
+async fn handle_list(collection: Option<String>, format: OutputFormat) -> Result<()> {
+    let config = Config::load_with_collection(collection.as_deref())?.config;
+    let formatter = get_formatter(format);
+
+    let vector_store =
+        create_backend_with_embedding_config(&config.vector_store, &config.embedding).await?;
+    if vector_store.get_collection_info().await?.is_none() {
+        println!(
+            "{}",
+            formatter.format_message("Collection not found. Run 'ssearch index' first.")
+        );
+        return Ok(());
+    }
+
+    let inventory = vector_store
+        .inventory()
+        .await
+        .context("failed to read index inventory")?;
+
+    print!(
+        "{}",
+        formatter.format_inventory(vector_store.collection(), &inventory)
+    );
+    Ok(())
+}
+
+async fn handle_inspect(
+    document: &str,
+    show_content: bool,
+    collection: Option<String>,
+    format: OutputFormat,
+) -> Result<()> {
+    let config = Config::load_with_collection(collection.as_deref())?.config;
+    let formatter = get_formatter(format);
+
+    let vector_store = create_backend(&config.vector_store).await?;
+    if vector_store.get_collection_info().await?.is_none() {
+        println!(
+            "{}",
+            formatter.format_message("Collection not found. Run 'ssearch index' first.")
+        );
+        return Ok(());
+    }
+
+    let chunks = document_chunks(vector_store.as_ref(), document).await?;
+
+    print!(
+        "{}",
+        formatter.format_document_chunks(&chunks, show_content)
+    );
+    Ok(())
+}
+
+/// Stored chunks of `document`, an indexed file path or a document ID.
+async fn document_chunks(
+    vector_store: &dyn VectorStore,
+    document: &str,
+) -> Result<Vec<DocumentChunk>> {
+    // A local file is stored under the ID of its canonical path
+    let mut candidates = Vec::new();
+    if let Ok(path) = Path::new(document).canonicalize() {
+        candidates.push(Document::generate_id(&Source::local(
+            path.to_string_lossy().to_string(),
+        )));
+    }
+    candidates.push(document.to_string());
+
+    for document_id in candidates {
+        let chunks = vector_store
+            .scroll_by_document(&document_id)
+            .await
+            .context("failed to read document chunks")?;
+        if !chunks.is_empty() {
+            return Ok(chunks);
+        }
+    }
+    Ok(Vec::new())
+}
+
+/// Why an indexed local document no longer matches its file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StaleReason {
+    Missing,
+    Changed,
+}
+
+/// Check an indexed document against its file on disk, comparing checksums
+/// in the form `store` keeps them.
+///
+/// Files that can no longer be read as text (e.g. grown past `max_file_size`)
+/// are left alone, as `index add` would skip rather than remove them.
+fn stale_reason(
+    doc: &IndexedDocument,
+    store: &dyn VectorStore,
+    max_file_size: u64,
+) -> Option<StaleReason> {
+    let path = Path::new(&doc.location);
+    if !path.exists() {
+        return Some(StaleReason::Missing);
+    }
+    let content = read_file_content(path, max_file_size).ok()?;
+    (store.stored_checksum(&calculate_checksum(&content)) != doc.checksum)
+        .then_some(StaleReason::Changed)
+}
+
+async fn handle_gc(
+    dry_run: bool,
+    collection: Option<String>,
+    format: OutputFormat,
+    verbose: bool,
+) -> Result<()> {
+    let config = Config::load_with_collection(collection.as_deref())?.config;
+    let formatter = get_formatter(format);
 
-    if path.is_file() {
-        files.push(path.clone());
-        return Ok(files);
+    let vector_store = create_backend(&config.vector_store).await?;
+    if vector_store.get_collection_info().await?.is_none() {
+        println!(
+            "{}",
+            formatter.format_message("Collection not found. Run 'ssearch index' first.")
+        );
+        return Ok(());
     }
 
-    for entry in WalkDir::new(path).follow_links(false) {
-        let entry = entry.context("failed to read directory entry")?;
-        let entry_path = entry.path();
+    let documents = vector_store
+        .list_documents(&SourceType::Local)
+        .await
+        .context("failed to list indexed documents")?;
+    let scanned = documents.len();
 
-        if !entry_path.is_file() {
+    let (mut missing, mut changed, mut chunks) = (0, 0, 0);
+    let mut stale_ids = Vec::new();
+    for doc in documents {
+        let Some(reason) = stale_reason(&doc, vector_store.as_ref(), config.indexing.max_file_size)
+        else {
             continue;
+        };
+        match reason {
+            StaleReason::Missing => missing += 1,
+            StaleReason::Changed => changed += 1,
         }
+        if verbose || dry_run {
+            let label = match reason {
+                StaleReason::Missing => "missing",
+                StaleReason::Changed => "changed",
+            };
+            println!("  {} ({}, {} chunks)", doc.location, label, doc.chunk_count);
+        }
+        chunks += doc.chunk_count;
+        stale_ids.push(doc.document_id);
+    }
+
+    if stale_ids.is_empty() {
+        println!(
+            "{}",
+            formatter.format_message(&format!(
+                "Checked {} documents; nothing to collect.",
+                scanned
+            ))
+        );
+        return Ok(());
+    }
+
+    let summary = format!(
+        "{} documents ({} missing, {} changed), {} chunks",
+        stale_ids.len(),
+        missing,
+        changed,
+        chunks
+    );
+    if dry_run {
+        println!(
+            "{}",
+            formatter.format_message(&format!("Dry run: Would remove {}", summary))
+        );
+        return Ok(());
+    }
+
+    vector_store.delete_by_document_ids(&stale_ids).await?;
+    println!(
+        "{}",
+        formatter.format_message(&format!(
+            "Removed {} from {} indexed documents",
+            summary, scanned
+        ))
+    );
+    if changed > 0 {
+        println!(
+            "{}",
+            formatter.format_message("Run 'ssearch index add' to re-index changed files.")
+        );
+    }
+
+    Ok(())
+}
+
+async fn handle_prune(
+    dry_run: bool,
+    collection: Option<String>,
+    format: OutputFormat,
+) -> Result<()> {
+    let config = Config::load_with_collection(collection.as_deref())?.config;
+    let formatter = get_formatter(format);
+    let retention = &config.indexing.retention;
+    if retention.is_empty() {
+        println!(
+            "{}",
+            formatter.format_message(
+                "No retention configured. Set [indexing.retention] max_age or max_points."
+            )
+        );
+        return Ok(());
+    }
+    let max_ages = retention.max_ages()?;
+
+    let vector_store = create_backend(&config.vector_store).await?;
+    if vector_store.get_collection_info().await?.is_none() {
+        println!(
+            "{}",
+            formatter.format_message("Collection not found. Run 'ssearch index' first.")
+        );
+        return Ok(());
+    }
 
-        let path_str = entry_path.to_string_lossy();
-        let mut excluded = false;
+    let report = prune(
+        vector_store.as_ref(),
+        &max_ages,
+        retention.max_points,
+        chrono::Utc::now(),
+        dry_run,
+    )
+    .await
+    .context("failed to prune the index")?;
 
-        for pattern in exclude.iter().chain(default_exclude.iter()) {
-            if glob::Pattern::new(pattern)
-                .map(|p| p.matches(&path_str))
-                .unwrap_or(false)
+    for expired in report.expired.iter().filter(|e| e.chunks > 0) {
+        println!(
+            "  {}: {} chunks not updated since {}",
+            expired.source_type,
+            expired.chunks,
+            expired.cutoff.format("%Y-%m-%d")
+        );
+    }
+    if let (Some(cutoff), Some(max_points)) = (report.budget_cutoff, retention.max_points) {
+        println!(
+            "  over max_points {}: {} chunks not updated since {}",
+            max_points,
+            report.over_budget,
+            cutoff.format("%Y-%m-%d")
+        );
+    }
+
+    let message = if report.removed() == 0 {
+        format!("Checked {} chunks; nothing to prune.", report.chunks_before)
+    } else if dry_run {
+        format!(
+            "Dry run: Would remove {} of {} chunks",
+            report.removed(),
+            report.chunks_before
+        )
+    } else {
+        format!(
+            "Removed {} of {} chunks ({} remain)",
+            report.removed(),
+            report.chunks_before,
+            report.chunks_after()
+        )
+    };
+    println!("{}", formatter.format_message(&message));
+    if let Some(max_points) = retention.max_points
+        && report.chunks_after() > max_points
+    {
+        eprintln!(
+            "Warning: {} chunks remain, over max_points {}; chunks without an update time are kept",
+            report.chunks_after(),
+            max_points
+        );
+    }
+
+    Ok(())
+}
+
+/// Differences between local files and their indexed documents.
+#[derive(Debug, Default)]
+struct Verification {
+    /// Files on disk that have no indexed document
+    unindexed: Vec<PathBuf>,
+    /// Indexed documents whose file changed since
+    stale: Vec<IndexedDocument>,
+    /// Indexed documents whose file was deleted
+    orphaned: Vec<IndexedDocument>,
+}
+
+impl Verification {
+    fn is_clean(&self) -> bool {
+        self.unindexed.is_empty() && self.stale.is_empty() && self.orphaned.is_empty()
+    }
+}
+
+/// Compare `files` on disk with the `documents` indexed for them.
+///
+/// Files `index add` would skip (binary, empty or unreadable) don't count as unindexed.
+fn verify_files(
+    files: &[PathBuf],
+    documents: Vec<IndexedDocument>,
+    store: &dyn VectorStore,
+    max_file_size: u64,
+) -> Verification {
+    let mut on_disk: HashMap<String, &PathBuf> = files
+        .iter()
+        .map(|file| {
+            let source = Source::local(file.to_string_lossy().to_string());
+            (Document::generate_id(&source), file)
+        })
+        .collect();
+
+    let mut verification = Verification::default();
+    for doc in documents {
+        on_disk.remove(&doc.document_id);
+        match stale_reason(&doc, store, max_file_size) {
+            Some(StaleReason::Missing) => verification.orphaned.push(doc),
+            Some(StaleReason::Changed) => verification.stale.push(doc),
+            None => {}
+        }
+    }
+
+    verification.unindexed = on_disk
+        .into_values()
+        .filter(|file| {
+            is_text_file(file)
+                && read_file_content(file, max_file_size).is_ok_and(|c| !c.is_empty())
+        })
+        .cloned()
+        .collect();
+    verification.unindexed.sort();
+    verification
+        .stale
+        .sort_by(|a, b| a.location.cmp(&b.location));
+    verification
+        .orphaned
+        .sort_by(|a, b| a.location.cmp(&b.location));
+    verification
+}
+
+async fn handle_verify(
+    path: &str,
+    exclude: Vec<String>,
+    repair: bool,
+    collection: Option<String>,
+    format: OutputFormat,
+    verbose: bool,
+) -> Result<()> {
+    let config = Config::load_with_collection(collection.as_deref())?.config;
+    let formatter = get_formatter(format);
+
+    let target = IndexTarget::parse(path)?;
+    let files = filter_excluded(
+        target.files(true)?,
+        &exclude,
+        &config.indexing.exclude_patterns,
+    );
+
+    let vector_store = create_backend(&config.vector_store).await?;
+    let documents = if vector_store.get_collection_info().await?.is_some() {
+        vector_store
+            .list_documents(&SourceType::Local)
+            .await
+            .context("failed to list indexed documents")?
+            .into_iter()
+            .filter(|doc| target.contains(Path::new(&doc.location)))
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let indexed = documents.len();
+
+    let verification = verify_files(
+        &files,
+        documents,
+        vector_store.as_ref(),
+        config.indexing.max_file_size,
+    );
+    if verification.is_clean() {
+        println!(
+            "{}",
+            formatter.format_message(&format!(
+                "Verified {} files and {} indexed documents under {}; the index is up to date.",
+                files.len(),
+                indexed,
+                target
+            ))
+        );
+        return Ok(());
+    }
+
+    if verbose || !repair {
+        for file in &verification.unindexed {
+            println!("  {} (unindexed)", file.display());
+        }
+        for doc in &verification.stale {
+            println!("  {} (stale, {} chunks)", doc.location, doc.chunk_count);
+        }
+        for doc in &verification.orphaned {
+            println!("  {} (orphaned, {} chunks)", doc.location, doc.chunk_count);
+        }
+    }
+
+    let summary = format!(
+        "{} unindexed, {} stale, {} orphaned",
+        verification.unindexed.len(),
+        verification.stale.len(),
+        verification.orphaned.len()
+    );
+    if !repair {
+        println!(
+            "{}",
+            formatter.format_message(&format!(
+                "Checked {} files and {} indexed documents: {}. Run with --repair to fix.",
+                files.len(),
+                indexed,
+                summary
+            ))
+        );
+        return Ok(());
+    }
+
+    if !verification.orphaned.is_empty() {
+        let orphaned_ids: Vec<String> = verification
+            .orphaned
+            .iter()
+            .map(|doc| doc.document_id.clone())
+            .collect();
+        vector_store.delete_by_document_ids(&orphaned_ids).await?;
+    }
+
+    // Stale documents are replaced by `index add`, as their checksums differ
+    let reindex: Vec<String> = verification
+        .unindexed
+        .iter()
+        .map(|file| file.to_string_lossy().into_owned())
+        .chain(verification.stale.iter().map(|doc| doc.location.clone()))
+        .collect();
+    if !reindex.is_empty() {
+        let options = AddOptions {
+            dry_run: false,
+            full: false,
+            git: false,
+            no_ignore: false,
+            resume: false,
+            summarize: None,
+            chunk_size: None,
+            collection,
+        };
+        handle_add(reindex, None, Vec::new(), options, format, verbose).await?;
+    }
+
+    println!(
+        "{}",
+        formatter.format_message(&format!("Repaired {}", summary))
+    );
+    Ok(())
+}
+
+struct ReembedOptions {
+    tags: Option<String>,
+    source: Option<String>,
+    /// Collection to write to instead of the one read from
+    into: Option<String>,
+    collection: Option<String>,
+}
+
+async fn handle_reembed(options: ReembedOptions, format: OutputFormat) -> Result<()> {
+    let config = Config::load_with_collection(options.collection.as_deref())?.config;
+    let formatter = get_formatter(format);
+    let start_time = Instant::now();
+
+    let tags = options
+        .tags
+        .as_deref()
+        .map(parse_tags)
+        .transpose()
+        .context("failed to parse tags")?
+        .unwrap_or_default();
+    // Soft-deleted chunks are re-embedded too, and stay soft-deleted
+    let filter = SearchFilter::new()
+        .with_tags(tags)
+        .with_source_types(parse_source_types(options.source.as_deref()))
+        .with_deleted(Deleted::Any);
+
+    let source_store = create_backend(&config.vector_store).await?;
+    if source_store.get_collection_info().await?.is_none() {
+        anyhow::bail!("collection '{}' does not exist", source_store.collection());
+    }
+    let count = source_store.count_by_filter(&filter).await?;
+    if count == 0 {
+        println!(
+            "{}",
+            formatter.format_message(&format!("No chunks match '{}'.", filter))
+        );
+        return Ok(());
+    }
+
+    // The embeddings are made with the model of the collection they are written to
+    let target_config = match options.into {
+        Some(ref name) => Config::load_with_collection(Some(name))?.config,
+        None => config.clone(),
+    };
+    let target_store =
+        create_backend_with_embedding_config(&target_config.vector_store, &target_config.embedding)
+            .await?;
+    check_dimension(
+        target_store.as_ref(),
+        u64::from(target_config.embedding.dimension),
+    )
+    .await?;
+    target_store.create_collection().await?;
+
+    let pb = ProgressBar::new(count);
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template(
+                "{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} chunks ({eta})",
+            )
+            .unwrap()
+            .progress_chars("#>-"),
+    );
+
+    let embedding_client = EmbeddingClient::new(&target_config);
+    let (sender, pipeline) = IndexPipeline::new(
+        &embedding_client,
+        target_store.as_ref(),
+        target_config.embedding.batch_size as usize,
+    );
+    let retry_notice = |message: String| pb.println(message);
+    let embed_notice = |embedded: u64| pb.inc(embedded);
+    let pipeline = pipeline
+        .with_redaction(target_config.indexing.redact_secrets)
+        .with_title_vectors(target_config.vector_store.title_vectors)
+        .with_max_retries(target_config.indexing.max_retries)
+        .with_retry_notice(&retry_notice)
+        .with_embed_notice(&embed_notice);
+
+    let produce = resend_stored_chunks(source_store.as_ref(), &filter, sender);
+    let (produced, stored) = tokio::join!(produce, pipeline.run());
+    let stored = stored?;
+    produced?;
+    pb.finish_and_clear();
+
+    println!(
+        "{}",
+        formatter.format_message(&format!(
+            "Re-embedded {} chunk(s) into '{}' with {} ({}d) in {:.1}s",
+            stored.stored,
+            target_store.collection(),
+            target_config.embedding.model_id,
+            target_config.embedding.dimension,
+            start_time.elapsed().as_secs_f64()
+        ))
+    );
+    if stored.redacted > 0 {
+        println!(
+            "{}",
+            formatter.format_message(&format!("Redacted {} secret(s)", stored.redacted))
+        );
+    }
+    Ok(())
+}
+
+async fn handle_reindex(collection: Option<String>, format: OutputFormat) -> Result<()> {
+    let config = Config::load_with_collection(collection.as_deref())?.config;
+    let formatter = get_formatter(format);
+
+    let vector_store = create_backend(&config.vector_store).await?;
+    let start = Instant::now();
+    let rebuilt = vector_store
+        .rebuild_index()
+        .await
+        .context("failed to rebuild the vector index")?;
+
+    let message = if rebuilt {
+        format!(
+            "Rebuilt {} index on '{}' in {:.1}s.",
+            config.vector_store.index_type,
+            vector_store.collection(),
+            start.elapsed().as_secs_f64()
+        )
+    } else {
+        format!(
+            "The {} backend maintains its index automatically; nothing to rebuild.",
+            config.vector_store.driver
+        )
+    };
+    println!("{}", formatter.format_message(&message));
+    Ok(())
+}
+
+async fn handle_clear(
+    force: bool,
+    collection: Option<String>,
+    format: OutputFormat,
+    verbose: bool,
+) -> Result<()> {
+    let config = Config::load_with_collection(collection.as_deref())?.config;
+    let formatter = get_formatter(format);
+
+    if verbose {
+        println!("Clearing all indexed documents...");
+    }
+
+    if !force {
+        println!(
+            "This will delete ALL indexed documents (back up first with 'ssearch collection snapshot'). Continue? [y/N]"
+        );
+        let mut input = String::new();
+        std::io::stdin().read_line(&mut input)?;
+        if !input.trim().eq_ignore_ascii_case("y") {
+            println!("{}", formatter.format_message("Cancelled."));
+            return Ok(());
+        }
+    }
+
+    let vector_store = create_backend(&config.vector_store).await?;
+    vector_store.clear_collection().await?;
+
+    println!(
+        "{}",
+        formatter.format_message("All indexed documents have been cleared.")
+    );
+
+    Ok(())
+}
+
+/// Files read and chunked ahead of the embedding pipeline.
+const PREPARED_QUEUE_SIZE: usize = 64;
+
+/// Turns files into chunks on worker threads.
+struct FilePlanner {
+    chunker: TextChunker,
+    tags: Vec<Tag>,
+    /// `[tags]` templates for local files
+    tag_templates: Vec<TagTemplate>,
+    /// Root that `relative_path` attributes are relative to
+    project_root: Option<PathBuf>,
+    max_file_size: u64,
+    /// Re-index files whose checksum is unchanged
+    full: bool,
+    /// Documents already indexed under the targets, by ID
+    indexed: HashMap<String, IndexedDocument>,
+    /// Store `indexed` came from, whose checksum form new checksums are compared in
+    store: Arc<dyn VectorStore>,
+    /// Workspace chunks are stamped with; unchanged files stamped otherwise
+    /// (e.g. indexed before the project had a config) are indexed again
+    workspace: Option<String>,
+    /// What an interrupted run finished, when resuming
+    checkpoint: Option<JournalState>,
+    git_attributes: HashMap<PathBuf, BTreeMap<String, String>>,
+    /// Hand the leading text of changed files to the summarizer
+    summaries: bool,
+    pii: PiiPolicy,
+    /// Content hashes of chunks repeated across `[indexing] boilerplate_min_files` files
+    boilerplate: HashSet<String>,
+    filler: FillerPolicy,
+}
+
+/// Outcome of reading one file.
+enum PreparedFile {
+    /// Binary, unreadable or empty; `reason` is shown with --verbose
+    Skipped {
+        path: PathBuf,
+        reason: Option<String>,
+    },
+    /// Left out by `[indexing] pii = "skip"`; `kinds` names the personal data found
+    PiiSkipped {
+        path: PathBuf,
+        document_id: String,
+        kinds: Vec<&'static str>,
+    },
+    Unchanged {
+        document_id: String,
+    },
+    Changed {
+        document_id: String,
+        checksum: String,
+        /// Drop the document's stored chunks before adding the new ones
+        replace: bool,
+        chunks: Vec<DocumentChunk>,
+        /// Text to write the document's summary chunk from
+        summary_input: Option<String>,
+        /// Tagged `pii:true`
+        pii_flagged: bool,
+        /// Short and boilerplate chunks skipped or flagged
+        short_chunks: u64,
+        boilerplate_chunks: u64,
+    },
+}
+
+impl FilePlanner {
+    fn prepare(&self, file_path: &Path) -> PreparedFile {
+        let skipped = |reason: Option<String>| PreparedFile::Skipped {
+            path: file_path.to_path_buf(),
+            reason,
+        };
+        if !is_text_file(file_path) {
+            return skipped(None);
+        }
+
+        let content = match read_file_content(file_path, self.max_file_size) {
+            Ok(c) if c.is_empty() => return skipped(None),
+            Ok(c) => c,
+            Err(e) => return skipped(Some(e.to_string())),
+        };
+
+        let checksum = calculate_checksum(&content);
+        let source = Source::local(file_path.to_string_lossy().to_string());
+        let document_id = Document::generate_id(&source);
+
+        if let Some(ref state) = self.checkpoint
+            && state.is_completed(&document_id, &checksum)
+        {
+            return PreparedFile::Unchanged { document_id };
+        }
+        // The interrupted run may have stored only some of its chunks
+        let interrupted = self
+            .checkpoint
+            .as_ref()
+            .is_some_and(|state| state.pending.contains(&document_id));
+
+        let replace = match self.indexed.get(&document_id) {
+            Some(indexed)
+                if !self.full
+                    && !interrupted
+                    && indexed.checksum == self.store.stored_checksum(&checksum)
+                    && indexed.workspace == self.workspace =>
             {
-                excluded = true;
-                break;
+                return PreparedFile::Unchanged { document_id };
             }
+            Some(_) => true,
+            None => false,
+        };
+
+        let metadata = DocumentMetadata::for_file(file_path, &content);
+        let mut document = Document::new(
+            content,
+            source,
+            self.tags.clone(),
+            checksum.clone(),
+            metadata,
+        );
+        if let Some(attributes) = self.git_attributes.get(file_path) {
+            document = document.with_attributes(attributes.clone());
+        }
+        if let Some(relative) = self
+            .project_root
+            .as_ref()
+            .and_then(|root| file_path.strip_prefix(root).ok())
+        {
+            document.attributes.insert(
+                RELATIVE_PATH_ATTRIBUTE.to_string(),
+                relative.to_string_lossy().into_owned(),
+            );
+        }
+        document.add_template_tags(&self.tag_templates, &BTreeMap::new());
+        let pii_flagged = match screen_pii(self.pii, &mut document) {
+            PiiOutcome::Clean => false,
+            PiiOutcome::Flagged(_) => true,
+            PiiOutcome::Skipped(kinds) => {
+                return PreparedFile::PiiSkipped {
+                    path: file_path.to_path_buf(),
+                    document_id,
+                    kinds,
+                };
+            }
+        };
+        let summary_input = self
+            .summaries
+            .then(|| truncate_chars(&document.content, SUMMARY_INPUT_CHARS).to_string());
+        let (mut chunks, short_chunks) = self.chunker.chunk_screened(&document);
+        let boilerplate_chunks = screen_boilerplate(&mut chunks, &self.boilerplate, self.filler);
+        PreparedFile::Changed {
+            document_id,
+            checksum,
+            replace,
+            chunks,
+            summary_input,
+            pii_flagged,
+            short_chunks,
+            boilerplate_chunks,
+        }
+    }
+
+    /// Chunks of a readable text file, as [`FilePlanner::prepare`] cuts them,
+    /// for counting boilerplate before anything is indexed.
+    fn chunks(&self, file_path: &Path) -> Vec<DocumentChunk> {
+        if !is_text_file(file_path) {
+            return Vec::new();
+        }
+        let Ok(content) = read_file_content(file_path, self.max_file_size) else {
+            return Vec::new();
+        };
+        let checksum = calculate_checksum(&content);
+        let source = Source::local(file_path.to_string_lossy().to_string());
+        let metadata = DocumentMetadata::for_file(file_path, &content);
+        let document = Document::new(content, source, Vec::new(), checksum, metadata);
+        self.chunker.chunk(&document)
+    }
+}
+
+/// A file, directory or glob pattern given to `index add`.
+#[derive(Debug)]
+enum IndexTarget {
+    /// An existing file or directory, canonicalized
+    Path(PathBuf),
+    /// Pattern over absolute paths, matching files below `base`
+    Glob {
+        pattern: glob::Pattern,
+        base: PathBuf,
+    },
+}
+
+/// `*` stays within one path component, as in a shell; `**` crosses them.
+const GLOB_OPTIONS: glob::MatchOptions = glob::MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+fn has_wildcard(component: &str) -> bool {
+    component.contains(['*', '?', '['])
+}
+
+impl IndexTarget {
+    /// Treat `arg` as a glob when it has wildcards and isn't an existing path.
+    fn parse(arg: &str) -> Result<Self> {
+        let path = Path::new(arg);
+        if path.exists() || !has_wildcard(arg) {
+            let path = path
+                .canonicalize()
+                .with_context(|| format!("invalid path: {}", arg))?;
+            return Ok(Self::Path(path));
+        }
+
+        // Walk from the deepest directory before the first wildcard
+        let mut base = if path.is_absolute() {
+            PathBuf::new()
+        } else {
+            std::env::current_dir()?
+        };
+        let mut components = path.components().peekable();
+        while let Some(component) =
+            components.next_if(|c| !has_wildcard(&c.as_os_str().to_string_lossy()))
+        {
+            base.push(component);
+        }
+        let base = base.canonicalize().unwrap_or(base);
+        let rest: Vec<String> = components
+            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+            .collect();
+
+        let escaped = glob::Pattern::escape(&base.to_string_lossy());
+        let pattern = glob::Pattern::new(&format!(
+            "{}/{}",
+            escaped.trim_end_matches('/'),
+            rest.join("/")
+        ))
+        .with_context(|| format!("invalid glob pattern: {}", arg))?;
+        Ok(Self::Glob { pattern, base })
+    }
+
+    /// Directory (or file) the target's files live under.
+    fn base(&self) -> &Path {
+        match self {
+            Self::Path(path) => path,
+            Self::Glob { base, .. } => base,
+        }
+    }
+
+    /// Whether an indexed location belongs to this target.
+    fn contains(&self, location: &Path) -> bool {
+        match self {
+            Self::Path(path) => location.starts_with(path),
+            Self::Glob { pattern, .. } => pattern.matches_path_with(location, GLOB_OPTIONS),
+        }
+    }
+
+    /// Files selected by the target, before exclude patterns.
+    fn files(&self, respect_ignore: bool) -> Result<Vec<PathBuf>> {
+        match self {
+            Self::Path(path) => collect_files(path, &[], &[], respect_ignore),
+            Self::Glob { base, .. } if !base.exists() => Ok(Vec::new()),
+            Self::Glob { base, .. } => Ok(collect_files(base, &[], &[], respect_ignore)?
+                .into_iter()
+                .filter(|file| self.contains(file))
+                .collect()),
+        }
+    }
+
+    /// Identifies the target in checkpoint journals.
+    fn key(&self) -> PathBuf {
+        match self {
+            Self::Path(path) => path.clone(),
+            Self::Glob { pattern, .. } => PathBuf::from(pattern.as_str()),
+        }
+    }
+}
+
+impl std::fmt::Display for IndexTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Path(path) => write!(f, "{}", path.display()),
+            Self::Glob { pattern, .. } => write!(f, "'{}'", pattern),
+        }
+    }
+}
+
+/// Files under `path` minus exclude patterns, honoring ignore files when `respect_ignore`.
+fn collect_files(
+    path: &Path,
+    exclude: &[String],
+    default_exclude: &[String],
+    respect_ignore: bool,
+) -> Result<Vec<PathBuf>> {
+    let files = walk_files(path, respect_ignore).context("failed to read directory entry")?;
+    Ok(filter_excluded(files, exclude, default_exclude))
+}
+
+fn filter_excluded(
+    files: Vec<PathBuf>,
+    exclude: &[String],
+    default_exclude: &[String],
+) -> Vec<PathBuf> {
+    let patterns: Vec<glob::Pattern> = exclude
+        .iter()
+        .chain(default_exclude.iter())
+        .filter_map(|p| glob::Pattern::new(p).ok())
+        .collect();
+
+    files
+        .into_iter()
+        .filter(|file| {
+            let path_str = file.to_string_lossy();
+            !patterns.iter().any(|p| p.matches(&path_str))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::MemoryBackend;
+
+    fn indexed(path: &Path, content: &str) -> IndexedDocument {
+        IndexedDocument {
+            document_id: "doc".to_string(),
+            location: path.to_string_lossy().to_string(),
+            checksum: calculate_checksum(content),
+            chunk_count: 1,
+            workspace: None,
+        }
+    }
+
+    #[test]
+    fn test_stale_reason() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.md");
+        std::fs::write(&path, "original").unwrap();
+        let doc = indexed(&path, "original");
+        let store = MemoryBackend::new("stale");
+
+        assert_eq!(stale_reason(&doc, &store, 1024), None);
+
+        std::fs::write(&path, "edited").unwrap();
+        assert_eq!(stale_reason(&doc, &store, 1024), Some(StaleReason::Changed));
+        // Unreadable files are kept
+        assert_eq!(stale_reason(&doc, &store, 1), None);
+
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(stale_reason(&doc, &store, 1024), Some(StaleReason::Missing));
+    }
+
+    #[test]
+    fn test_verify_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |name: &str, content: &str| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, content).unwrap();
+            path
+        };
+        let document = |path: &Path, content: &str| IndexedDocument {
+            document_id: Document::generate_id(&Source::local(path.to_string_lossy().to_string())),
+            ..indexed(path, content)
+        };
+
+        let current = write("current.md", "same");
+        let changed = write("changed.md", "edited");
+        let new = write("new.md", "fresh");
+        let empty = write("empty.txt", "");
+        let deleted = dir.path().join("deleted.md");
+        let store = MemoryBackend::new("verify");
+
+        let files = vec![current.clone(), changed.clone(), new.clone(), empty];
+        let documents = vec![
+            document(&current, "same"),
+            document(&changed, "original"),
+            document(&deleted, "gone"),
+        ];
+        let verification = verify_files(&files, documents, &store, 1024);
+
+        assert_eq!(verification.unindexed, vec![new]);
+        assert_eq!(verification.stale.len(), 1);
+        assert_eq!(verification.stale[0].location, changed.to_string_lossy());
+        assert_eq!(verification.orphaned.len(), 1);
+        assert_eq!(verification.orphaned[0].location, deleted.to_string_lossy());
+        assert!(!verification.is_clean());
+
+        assert!(
+            verify_files(&files[..1], vec![document(&current, "same")], &store, 1024).is_clean()
+        );
+    }
+
+    #[test]
+    fn test_file_planner_prepare() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.md");
+        std::fs::write(&path, "# Notes\n\nSome content").unwrap();
+        std::fs::write(dir.path().join("empty.txt"), "").unwrap();
+        let document_id = Document::generate_id(&Source::local(path.to_string_lossy().to_string()));
+
+        let mut planner = FilePlanner {
+            chunker: TextChunker::new(&crate::models::IndexingConfig::default()),
+            tags: Vec::new(),
+            tag_templates: vec!["kind:{extension}".parse().unwrap()],
+            project_root: Some(dir.path().to_path_buf()),
+            max_file_size: 1024,
+            full: false,
+            indexed: HashMap::new(),
+            store: Arc::new(MemoryBackend::new("planner")),
+            workspace: None,
+            checkpoint: None,
+            git_attributes: HashMap::new(),
+            summaries: false,
+            pii: PiiPolicy::Ignore,
+            boilerplate: HashSet::new(),
+            filler: FillerPolicy::Skip,
+        };
+
+        let PreparedFile::Changed {
+            replace, chunks, ..
+        } = planner.prepare(&path)
+        else {
+            panic!("new file should be indexed");
+        };
+        assert!(!replace);
+        assert!(!chunks.is_empty());
+        assert_eq!(chunks[0].tags[0].to_string(), "kind:md");
+        assert_eq!(chunks[0].attributes[RELATIVE_PATH_ATTRIBUTE], "notes.md");
+
+        let mut indexed = indexed(&path, "# Notes\n\nSome content");
+        indexed.document_id = document_id.clone();
+        planner.indexed.insert(document_id.clone(), indexed);
+        assert!(matches!(
+            planner.prepare(&path),
+            PreparedFile::Unchanged { .. }
+        ));
+        // Unchanged, but not stamped with the workspace being indexed
+        planner.workspace = Some("/work/api".to_string());
+        assert!(matches!(
+            planner.prepare(&path),
+            PreparedFile::Changed { replace: true, .. }
+        ));
+        planner.indexed.get_mut(&document_id).unwrap().workspace = planner.workspace.clone();
+        assert!(matches!(
+            planner.prepare(&path),
+            PreparedFile::Unchanged { .. }
+        ));
+        planner.full = true;
+        assert!(matches!(
+            planner.prepare(&path),
+            PreparedFile::Changed { replace: true, .. }
+        ));
+        planner.full = false;
+        std::fs::write(&path, "# Notes\n\nEdited content").unwrap();
+        assert!(matches!(
+            planner.prepare(&path),
+            PreparedFile::Changed { replace: true, .. }
+        ));
+        assert!(matches!(
+            planner.prepare(&dir.path().join("empty.txt")),
+            PreparedFile::Skipped { reason: None, .. }
+        ));
+
+        let mut counter = BoilerplateCounter::default();
+        counter.add_file(&planner.chunks(&path));
+        counter.add_file(&planner.chunks(&path));
+        planner.boilerplate = counter.boilerplate(2);
+        let PreparedFile::Changed {
+            chunks,
+            boilerplate_chunks,
+            ..
+        } = planner.prepare(&path)
+        else {
+            panic!("changed file should be indexed");
+        };
+        assert!(chunks.is_empty());
+        assert_eq!(boilerplate_chunks, 1);
+    }
+
+    #[tokio::test]
+    async fn test_document_chunks() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().canonicalize().unwrap().join("guide.md");
+        let content = "Rotate the signing keys every quarter.";
+        std::fs::write(&path, content).unwrap();
+        let indexed = crate::testing::document(&path.to_string_lossy(), content);
+        let other = crate::testing::document("docs/other.md", "Page the on-call engineer.");
+        let embedder = crate::testing::MockEmbeddingProvider::default();
+        let store = crate::testing::MockVectorStore::default();
+        crate::testing::index(&embedder, &store, &[indexed.clone(), other])
+            .await
+            .unwrap();
+
+        // By the file's path, relative or not, or by document ID
+        let relative = dir.path().join(".").join("guide.md");
+        for document in [path.to_string_lossy(), relative.to_string_lossy()] {
+            let chunks = document_chunks(&store, &document).await.unwrap();
+            assert_eq!(chunks.len(), 1);
+            assert_eq!(chunks[0].document_id, indexed.id);
         }
+        let chunks = document_chunks(&store, &indexed.id).await.unwrap();
+        assert_eq!(chunks[0].content, content);
+        assert!(
+            document_chunks(&store, "docs/missing.md")
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_removed_documents() {
+        let dir = tempfile::tempdir().unwrap();
+        let kept = dir.path().join("kept.md");
+        let excluded = dir.path().join("excluded.md");
+        std::fs::write(&kept, "kept").unwrap();
+        std::fs::write(&excluded, "excluded").unwrap();
+        let existing: HashMap<String, IndexedDocument> = [
+            ("kept", kept),
+            ("excluded", excluded),
+            ("deleted", dir.path().join("deleted.md")),
+        ]
+        .into_iter()
+        .map(|(id, path)| {
+            let mut document = indexed(&path, id);
+            document.document_id = id.to_string();
+            (id.to_string(), document)
+        })
+        .collect();
+
+        // Files skipped by this run (e.g. excluded) stay indexed while they exist
+        let seen = HashSet::from(["kept".to_string()]);
+        assert_eq!(removed_documents(existing, &seen), vec!["deleted"]);
+        assert!(removed_documents(HashMap::new(), &seen).is_empty());
+    }
+
+    #[test]
+    fn test_index_targets() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        std::fs::create_dir_all(root.join("docs/guide")).unwrap();
+        std::fs::write(root.join("README.md"), "readme").unwrap();
+        std::fs::write(root.join("docs/intro.md"), "intro").unwrap();
+        std::fs::write(root.join("docs/guide/setup.md"), "setup").unwrap();
+        std::fs::write(root.join("docs/notes.txt"), "notes").unwrap();
 
-        if !excluded {
-            files.push(entry_path.to_path_buf());
-        }
-    }
-
-    Ok(files)
-}
-
-fn detect_language(path: &Path) -> Option<String> {
-    path.extension().and_then(|ext| {
-        let ext = ext.to_string_lossy().to_lowercase();
-        match ext.as_str() {
-            "rs" => Some("rust"),
-            "py" => Some("python"),
-            "js" | "jsx" => Some("javascript"),
-            "ts" | "tsx" => Some("typescript"),
-            "go" => Some("go"),
-            "java" => Some("java"),
-            "kt" | "kts" => Some("kotlin"),
-            "c" | "h" => Some("c"),
-            "cpp" | "hpp" | "cc" | "cxx" => Some("cpp"),
-            "rb" => Some("ruby"),
-            "php" => Some("php"),
-            "swift" => Some("swift"),
-            "scala" => Some("scala"),
-            "sh" | "bash" => Some("shell"),
-            "sql" => Some("sql"),
-            "html" | "htm" => Some("html"),
-            "css" | "scss" | "sass" => Some("css"),
-            "json" => Some("json"),
-            "yaml" | "yml" => Some("yaml"),
-            "toml" => Some("toml"),
-            "xml" => Some("xml"),
-            "md" | "markdown" => Some("markdown"),
-            _ => None,
-        }
-        .map(String::from)
-    })
+        let dir_target = IndexTarget::parse(&root.join("docs").to_string_lossy()).unwrap();
+        assert!(matches!(dir_target, IndexTarget::Path(_)));
+        assert_eq!(dir_target.files(true).unwrap().len(), 3);
+
+        let all_md = IndexTarget::parse(&format!("{}/**/*.md", root.display())).unwrap();
+        assert_eq!(all_md.base(), root);
+        let mut files = all_md.files(true).unwrap();
+        files.sort();
+        assert_eq!(
+            files,
+            vec![
+                root.join("README.md"),
+                root.join("docs/guide/setup.md"),
+                root.join("docs/intro.md"),
+            ]
+        );
+
+        // A single `*` doesn't cross directories
+        let top_md = IndexTarget::parse(&format!("{}/docs/*.md", root.display())).unwrap();
+        assert_eq!(top_md.base(), root.join("docs"));
+        assert!(top_md.contains(&root.join("docs/intro.md")));
+        assert!(!top_md.contains(&root.join("docs/guide/setup.md")));
+
+        let missing = IndexTarget::parse(&format!("{}/nope/*.md", root.display())).unwrap();
+        assert!(missing.files(true).unwrap().is_empty());
+        assert!(IndexTarget::parse(&root.join("nope").to_string_lossy()).is_err());
+    }
 }
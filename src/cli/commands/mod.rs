@@ -1,23 +1,46 @@
+mod alias;
+mod ask;
+mod collection;
 mod config;
+mod eval;
+mod export;
+mod health;
+mod history;
 mod import;
 mod index;
+mod query;
 mod search;
 mod serve;
 mod source;
 mod status;
 mod tags;
 
+pub use alias::AliasCommand;
+pub use ask::AskArgs;
+pub use collection::CollectionCommand;
 pub use config::ConfigCommand;
+pub use eval::EvalArgs;
+pub use export::ExportArgs;
+pub use history::{HistoryArgs, HistoryCommand};
 pub use import::ImportArgs;
 pub use index::IndexCommand;
+pub use query::QueryArgs;
 pub use search::SearchArgs;
-pub use serve::ServeArgs;
+pub use serve::{LogArgs, ServeArgs};
 pub use source::SourceCommand;
 pub use tags::TagsCommand;
 
+pub use alias::handle_alias;
+pub use ask::handle_ask;
+pub use collection::handle_collection;
 pub use config::handle_config;
+pub use eval::handle_eval;
+pub use export::handle_export;
+pub use health::handle_health;
+pub use history::handle_history;
 pub use import::handle_import;
 pub use index::handle_index;
+pub use query::handle_query;
 pub use search::handle_search;
 pub use serve::handle_serve;
 pub use source::handle_source;
@@ -0,0 +1,187 @@
+//! One-shot search over a directory, without a persistent index.
+
+use anyhow::{Context, Result};
+use clap::Args;
+use indicatif::{ProgressBar, ProgressStyle};
+use std::path::PathBuf;
+use std::time::Instant;
+
+use crate::cli::output::get_formatter;
+use crate::error::SearchError;
+use crate::models::{Config, OutputFormat, SearchFilter, SearchResults};
+use crate::services::{EmbeddingClient, IndexPipeline, MemoryBackend, TextChunker, VectorStore};
+use crate::sources::LocalSource;
+
+#[derive(Debug, Args)]
+pub struct QueryArgs {
+    #[arg(required = true, help = "Search query text")]
+    pub query: String,
+
+    #[arg(
+        long,
+        short = 'p',
+        value_name = "DIR",
+        default_value = ".",
+        help = "Directory or file to search"
+    )]
+    pub path: PathBuf,
+
+    #[arg(long, short = 'n', help = "Maximum number of results to return")]
+    pub limit: Option<u32>,
+
+    #[arg(
+        long,
+        short = 'e',
+        help = "File patterns to exclude (can be specified multiple times)"
+    )]
+    pub exclude: Vec<String>,
+
+    #[arg(
+        long,
+        help = "Also search files excluded by .gitignore, .ignore and .ssearchignore"
+    )]
+    pub no_ignore: bool,
+
+    #[arg(long, help = "Minimum similarity score threshold (0.0-1.0)")]
+    pub min_score: Option<f32>,
+
+    #[arg(
+        long,
+        help = "Combine semantic similarity with keyword (BM25) matching for exact identifiers"
+    )]
+    pub hybrid: bool,
+}
+
+/// Index `path` into a transient in-memory store, search it, and discard it.
+pub async fn handle_query(args: QueryArgs, format: OutputFormat, verbose: bool) -> Result<()> {
+    let query = args.query.trim();
+    if query.is_empty() {
+        anyhow::bail!("search query cannot be empty");
+    }
+
+    let config = Config::load()?.config;
+    let formatter = get_formatter(format);
+    let start_time = Instant::now();
+
+    let limit = args.limit.unwrap_or(config.search.default_limit);
+    if limit == 0 {
+        anyhow::bail!("limit must be at least 1");
+    }
+    let min_score = args.min_score.or(config.search.default_min_score);
+
+    let path = args.path.canonicalize().context("invalid path")?;
+    let mut exclude = args.exclude;
+    exclude.extend(config.indexing.exclude_patterns.iter().cloned());
+    let source = LocalSource::new(path, exclude, config.indexing.max_file_size)
+        .with_ignore_files(!args.no_ignore);
+    let files = source.collect_files()?;
+    if files.is_empty() {
+        println!("{}", formatter.format_message("No files found to search."));
+        return Ok(());
+    }
+
+    let embedding_client = EmbeddingClient::new(&config);
+    let mut vector_store = MemoryBackend::new("query");
+    if config.vector_store.title_vectors {
+        vector_store = vector_store.with_title_weight(config.vector_store.title_weight);
+    }
+    let chunker = TextChunker::from_config(&config);
+
+    let pb = ProgressBar::new(files.len() as u64);
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template(
+                "{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} ({eta})",
+            )
+            .unwrap()
+            .progress_chars("#>-"),
+    );
+
+    let (sender, pipeline) = IndexPipeline::new(
+        &embedding_client,
+        &vector_store,
+        config.embedding.batch_size as usize,
+    );
+    let retry_notice = |message: String| pb.println(message);
+    let pipeline = pipeline
+        .with_redaction(config.indexing.redact_secrets)
+        .with_title_vectors(config.vector_store.title_vectors)
+        .with_max_retries(config.indexing.max_retries)
+        .with_retry_notice(&retry_notice);
+    let produce = async {
+        let sender = sender;
+        for file_path in &files {
+            pb.inc(1);
+            let document = match source.read_document(file_path, Vec::new()) {
+                Ok(document) if !document.content.is_empty() => document,
+                Ok(_) => continue,
+                Err(e) => {
+                    if verbose {
+                        pb.println(format!("Skipping {}: {}", file_path.display(), e));
+                    }
+                    continue;
+                }
+            };
+            for chunk in chunker.chunk(&document) {
+                sender.send(chunk).await?;
+            }
+        }
+        Ok::<(), anyhow::Error>(())
+    };
+    let (produced, stored) = tokio::join!(produce, pipeline.run());
+    stored?;
+    produced?;
+    pb.finish_and_clear();
+    let index_ms = start_time.elapsed().as_millis();
+
+    let search_start = Instant::now();
+    let query_embedding = embedding_client
+        .embed_query(query)
+        .await
+        .context("failed to generate query embedding")?;
+    let filter = SearchFilter::new();
+    let results = if args.hybrid {
+        vector_store
+            .hybrid_search(
+                query_embedding,
+                query,
+                u64::from(limit),
+                0,
+                &filter,
+                min_score,
+            )
+            .await
+    } else {
+        vector_store
+            .search(query_embedding, u64::from(limit), 0, &filter, min_score)
+            .await
+    }
+    .context("search failed")?;
+    let search_ms = search_start.elapsed().as_millis();
+
+    if verbose {
+        let chunks = vector_store
+            .get_collection_info()
+            .await?
+            .map_or(0, |info| info.points_count);
+        eprintln!(
+            "Indexed {} files ({} chunks) in memory",
+            files.len(),
+            chunks
+        );
+        eprintln!("Timing:");
+        eprintln!("  Index: {index_ms}ms");
+        eprintln!("  Search: {search_ms}ms");
+        eprintln!();
+    }
+
+    let duration_ms = start_time.elapsed().as_millis() as u64;
+    let total = results.len() as u64;
+    let search_results = SearchResults::new(query.to_string(), results, total, duration_ms);
+    print!("{}", formatter.format_search_results(&search_results));
+
+    if total == 0 {
+        return Err(SearchError::NoResults.into());
+    }
+    Ok(())
+}
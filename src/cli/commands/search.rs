@@ -1,22 +1,103 @@
 use anyhow::{Context, Result};
+use chrono::Utc;
 use clap::Args;
+use clap_complete::ArgValueCompleter;
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::Instant;
 
-use crate::cli::output::get_formatter;
-use crate::models::{Config, OutputFormat, SearchResults, SourceType, Tag, parse_tags};
-use crate::services::{EmbeddingClient, create_backend};
+use tokio::task::{JoinError, JoinSet};
+
+use crate::cli::completion::{complete_sources, complete_tags};
+use crate::cli::open::OpenTarget;
+use crate::cli::output::{MarkdownFormatter, SearchReport, get_formatter};
+use crate::error::{SearchError, VectorStoreError};
+use crate::models::{
+    Config, DocumentChunk, FilterExpr, OutputFormat, PathPattern, QueryExpansion, RangeOp,
+    SavedQuery, SearchFilter, SearchResult, SearchResults, SourceType, Tag, TimeBound, TimeField,
+    parse_datetime, parse_tags,
+};
+use crate::services::fusion::explain_scores;
+use crate::services::{
+    EmbeddingClient, MemoryBackend, MetricsStore, SearchOptions, SearchRecord, SearchService,
+    VectorStore, check_dimension, create_backend, expand_context, expand_query,
+};
+use crate::utils::file::normalize_language;
+
+/// Number of recent searches whose top scores `--explain` ranks against.
+const EXPLAIN_HISTORY: u32 = 500;
+
+/// Vector searches `--batch` keeps in flight at once.
+const BATCH_CONCURRENCY: usize = 8;
 
 #[derive(Debug, Args)]
 pub struct SearchArgs {
-    #[arg(required = true, help = "Search query text")]
+    #[arg(
+        required_unless_present = "batch",
+        default_value = "",
+        hide_default_value = true,
+        help = "Search query text"
+    )]
     pub query: String,
 
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = [
+            "query", "offset", "page_size", "report", "refine", "explain", "expand", "rerank",
+            "recency", "dedup", "summaries_only", "open",
+        ],
+        help = "Run every query in FILE ('-' for stdin) with one embedding round trip: one query per line, or JSONL with per-query filters ({\"query\": ..., \"tags\": ..., \"limit\": ...}); results are grouped per query"
+    )]
+    pub batch: Option<PathBuf>,
+
     #[arg(long, short = 'n', help = "Maximum number of results to return")]
     pub limit: Option<u32>,
 
+    #[arg(
+        long,
+        value_name = "N",
+        conflicts_with = "limit",
+        help = "Results per page, for use with --offset (same as --limit)"
+    )]
+    pub page_size: Option<u32>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 0,
+        help = "Skip the first N results, to page through large result sets"
+    )]
+    pub offset: u32,
+
+    #[arg(
+        long,
+        short = 'o',
+        value_name = "FILE",
+        help = "Write results to a file instead of stdout"
+    )]
+    pub output: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Also write a standalone Markdown report (query, filters, full chunk contents with links) to FILE"
+    )]
+    pub report: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with = "summaries_only",
+        help = "Re-rank and filter the results saved by an earlier 'search -f json' instead of the whole index"
+    )]
+    pub refine: Option<PathBuf>,
+
     #[arg(
         long,
         short = 't',
+        add = ArgValueCompleter::new(complete_tags),
         help = "Filter by tags (e.g., 'source:confluence,space:common')"
     )]
     pub tags: Option<String>,
@@ -24,35 +105,174 @@ pub struct SearchArgs {
     #[arg(
         long,
         short = 's',
+        add = ArgValueCompleter::new(complete_sources),
         help = "Filter by source type (e.g., 'local,confluence,jira')"
     )]
     pub source: Option<String>,
 
+    #[arg(
+        long = "lang",
+        value_name = "LANGS",
+        help = "Filter by language (e.g., 'rust,python'); extensions like 'rs' also work"
+    )]
+    pub languages: Option<String>,
+
+    #[arg(
+        long = "filter",
+        value_name = "EXPR",
+        help = "Filter expression: 'language=rust', 'path~src/**/*.rs', 'symbol~handle_', 'created_at>=2024-01-01'; repeatable"
+    )]
+    pub filters: Vec<String>,
+
+    #[arg(
+        long = "path",
+        value_name = "GLOB",
+        help = "Only files under a project-relative path or glob (e.g., 'src/services/**'); repeatable"
+    )]
+    pub paths: Vec<String>,
+
+    #[arg(
+        long,
+        value_name = "WHEN",
+        help = "Only documents updated since a date or age (e.g., '30d', '2w', '2024-01-01')"
+    )]
+    pub since: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "WHEN",
+        help = "Only documents updated before a date or age (e.g., '2024-06-01', '1y')"
+    )]
+    pub until: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "WEIGHT",
+        help = "Boost recently updated documents (0.0-1.0, overrides [search] recency_weight)"
+    )]
+    pub recency: Option<f32>,
+
+    #[arg(
+        long,
+        value_name = "THRESHOLD",
+        help = "Drop results this similar to a higher-ranked one (0.0-1.0, overrides [search] dedup_threshold)"
+    )]
+    pub dedup: Option<f32>,
+
     #[arg(long, help = "Minimum similarity score threshold (0.0-1.0)")]
     pub min_score: Option<f32>,
+
+    #[arg(
+        long,
+        short = 'C',
+        value_name = "N",
+        help = "Include N neighboring chunks before and after each result"
+    )]
+    pub context: Option<u32>,
+
+    #[arg(
+        long,
+        help = "Combine semantic similarity with keyword (BM25) matching for exact identifiers"
+    )]
+    pub hybrid: bool,
+
+    #[arg(
+        long,
+        help = "Don't fall back to keyword matching when no result reaches the minimum score"
+    )]
+    pub no_keyword_fallback: bool,
+
+    #[arg(
+        long,
+        help = "Explain each score: raw similarity, percentile against recent searches, matched tags and the hybrid breakdown"
+    )]
+    pub explain: bool,
+
+    #[arg(
+        long,
+        value_name = "MODE",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "heuristic",
+        conflicts_with = "no_expand",
+        help = "Also search reformulations of the query and fuse the rankings (heuristic, llm)"
+    )]
+    pub expand: Option<QueryExpansion>,
+
+    #[arg(long, help = "Disable query expansion even if enabled in config")]
+    pub no_expand: bool,
+
+    #[arg(
+        long,
+        conflicts_with = "no_rerank",
+        help = "Re-score top candidates with the cross-encoder reranker"
+    )]
+    pub rerank: bool,
+
+    #[arg(long, help = "Disable reranking even if enabled in config")]
+    pub no_rerank: bool,
+
+    #[arg(
+        long,
+        help = "Match document summaries first, then search only the chunks of those documents"
+    )]
+    pub summaries_only: bool,
+
+    #[arg(long, help = "Collection to search instead of the configured one")]
+    pub collection: Option<String>,
+
+    #[arg(
+        long,
+        help = "Search content indexed from every workspace, not just the current project"
+    )]
+    pub all_workspaces: bool,
+
+    #[arg(
+        long,
+        value_name = "N",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "0",
+        help = "Open result N: local files in $EDITOR at the matched line, other sources in the browser; without N, asks which"
+    )]
+    pub open: Option<u64>,
 }
 
 pub async fn handle_search(args: SearchArgs, format: OutputFormat, verbose: bool) -> Result<()> {
+    if let Some(ref path) = args.batch {
+        return handle_batch(&args, path, format, verbose).await;
+    }
+
     let query = args.query.trim();
     if query.is_empty() {
         anyhow::bail!("search query cannot be empty");
     }
 
-    let config = Config::load()?.config;
+    let config = Config::load_with_collection(args.collection.as_deref())?.config;
     let formatter = get_formatter(format);
     let start_time = Instant::now();
 
-    let limit = args.limit.unwrap_or(config.search.default_limit);
-    if limit == 0 {
-        anyhow::bail!("limit must be at least 1");
-    }
-
+    let limit = args
+        .page_size
+        .or(args.limit)
+        .unwrap_or(config.search.default_limit);
+    let offset = args.offset;
     let min_score = args.min_score.or(config.search.default_min_score);
-    if let Some(score) = min_score
-        && !(0.0..=1.0).contains(&score)
-    {
-        anyhow::bail!("min_score must be between 0.0 and 1.0");
-    }
+    let recency_weight = args.recency.unwrap_or(config.search.recency_weight);
+    let dedup_threshold = args.dedup.or(config.search.dedup_threshold);
+    let rerank = !args.no_rerank && (args.rerank || config.search.rerank);
+    let options = SearchOptions::from_config(&config.search)
+        .with_limit(limit)
+        .with_offset(offset)
+        .with_min_score(min_score)
+        .with_hybrid(args.hybrid)
+        .with_rerank(rerank)
+        .with_recency(recency_weight)
+        .with_dedup_threshold(dedup_threshold)
+        .with_keyword_fallback(!args.no_keyword_fallback && config.search.keyword_fallback)
+        .with_summaries_only(args.summaries_only)
+        .with_explain(args.explain);
+    options.validate()?;
 
     let tags: Vec<Tag> = args
         .tags
@@ -62,21 +282,29 @@ pub async fn handle_search(args: SearchArgs, format: OutputFormat, verbose: bool
         .context("failed to parse tags")?
         .unwrap_or_default();
 
-    let source_types: Vec<SourceType> = args
-        .source
-        .as_deref()
-        .map(|s| {
-            s.split(',')
-                .map(str::trim)
-                .filter(|s| !s.is_empty())
-                .map(|s| s.parse().unwrap())
-                .collect()
-        })
-        .unwrap_or_default();
+    let source_types = parse_source_types(args.source.as_deref());
+    let languages = parse_languages(args.languages.as_deref());
+    let expressions =
+        parse_expressions(&args.filters, args.since.as_deref(), args.until.as_deref())?;
+
+    let workspace = config.workspace().filter(|_| !args.all_workspaces);
+
+    let expansion = if args.no_expand {
+        QueryExpansion::Off
+    } else {
+        args.expand.unwrap_or(config.search.expansion)
+    };
+    let queries = expand_query(query, expansion, &config.llm)
+        .await
+        .context("failed to expand query")?;
+    let expanded = queries.len() > 1;
 
     if verbose {
         eprintln!("Query: \"{query}\"");
         eprintln!("  Limit: {limit}");
+        if offset > 0 {
+            eprintln!("  Offset: {offset}");
+        }
         if !tags.is_empty() {
             let tag_strs: Vec<String> = tags.iter().map(ToString::to_string).collect();
             eprintln!("  Tags: {}", tag_strs.join(", "));
@@ -85,50 +313,613 @@ pub async fn handle_search(args: SearchArgs, format: OutputFormat, verbose: bool
             let source_strs: Vec<String> = source_types.iter().map(ToString::to_string).collect();
             eprintln!("  Sources: {}", source_strs.join(", "));
         }
+        if !languages.is_empty() {
+            eprintln!("  Languages: {}", languages.join(", "));
+        }
+        if !expressions.is_empty() {
+            let filter_strs: Vec<String> = expressions.iter().map(ToString::to_string).collect();
+            eprintln!("  Filters: {}", filter_strs.join(", "));
+        }
+        if let Some(workspace) = workspace {
+            eprintln!("  Workspace: {workspace}");
+        }
+        if let Some(ref path) = args.refine {
+            eprintln!("  Refining: {}", path.display());
+        }
         if let Some(score) = min_score {
             eprintln!("  Min score: {score:.3}");
         }
+        if args.hybrid {
+            eprintln!("  Mode: hybrid");
+        }
+        if recency_weight > 0.0 {
+            eprintln!(
+                "  Recency: {recency_weight:.2} (half-life {}d)",
+                config.search.recency_half_life_days
+            );
+        }
+        if let Some(threshold) = dedup_threshold {
+            eprintln!("  Dedup threshold: {threshold:.2}");
+        }
+        if expanded {
+            eprintln!("  Expansions ({expansion}):");
+            for expansion in &queries[1..] {
+                eprintln!("    {expansion}");
+            }
+        }
+    }
+
+    // Listed under Filters in a --report
+    let mut described = Vec::new();
+    if args.report.is_some() {
+        let join = |items: Vec<String>| items.join(", ");
+        if !tags.is_empty() {
+            described.push(format!(
+                "Tags: {}",
+                join(tags.iter().map(ToString::to_string).collect())
+            ));
+        }
+        if !source_types.is_empty() {
+            let sources = source_types.iter().map(ToString::to_string).collect();
+            described.push(format!("Sources: {}", join(sources)));
+        }
+        if !languages.is_empty() {
+            described.push(format!("Languages: {}", languages.join(", ")));
+        }
+        for expression in &expressions {
+            described.push(format!("Filter: {expression}"));
+        }
+        for path in &args.paths {
+            described.push(format!("Path: {path}"));
+        }
+        if let Some(workspace) = workspace {
+            described.push(format!("Workspace: {workspace}"));
+        }
+        if let Some(score) = min_score {
+            described.push(format!("Min score: {score:.3}"));
+        }
+        let mut modes = Vec::new();
+        if args.hybrid {
+            modes.push("hybrid".to_string());
+        }
+        if expanded {
+            modes.push(format!("expanded ({expansion})"));
+        }
+        if rerank {
+            modes.push("reranked".to_string());
+        }
+        if recency_weight > 0.0 {
+            modes.push(format!("recency {recency_weight:.2}"));
+        }
+        if args.summaries_only {
+            modes.push("summaries only".to_string());
+        }
+        if !modes.is_empty() {
+            described.push(format!("Mode: {}", modes.join(", ")));
+        }
+    }
+
+    let filter = SearchFilter::new()
+        .with_tags(tags.clone())
+        .with_source_types(source_types)
+        .with_languages(languages)
+        .with_expressions(expressions)
+        .with_relative_paths(args.paths.iter().map(PathPattern::new).collect())
+        .with_workspace(workspace);
+    let options = options
+        .with_expansions(queries[1..].to_vec())
+        .with_filter(filter);
+
+    let embedding_client = Arc::new(EmbeddingClient::new(&config));
+    let vector_store: Arc<dyn VectorStore> = Arc::from(create_backend(&config.vector_store).await?);
+    check_dimension(vector_store.as_ref(), u64::from(config.embedding.dimension)).await?;
+    // Rankings come from the earlier results when refining; context still comes from the index
+    let search_store: Arc<dyn VectorStore> = match args.refine {
+        Some(ref path) => {
+            Arc::new(refine_store(path, vector_store.as_ref(), &embedding_client).await?)
+        }
+        None => Arc::clone(&vector_store),
+    };
+    let service = SearchService::new(embedding_client, search_store, config.search.clone());
+    let run = service.run(query, &options).await?;
+    if verbose {
+        if args.summaries_only {
+            eprintln!("Summaries matched {} documents", run.summary_documents);
+        }
+        match run.keyword_fallback {
+            Some(Ok(matches)) => {
+                eprintln!("No semantic results; keyword fallback found {matches} matches");
+            }
+            // Collections indexed before keyword support can't fall back
+            Some(Err(ref e)) => eprintln!("Keyword fallback unavailable: {e}"),
+            None => {}
+        }
+        if dedup_threshold.is_some() {
+            eprintln!("Suppressed {} near-duplicate results", run.suppressed);
+        }
+    }
+    let mut results = run.results;
+
+    let context_start = Instant::now();
+    if let Some(radius) = args.context {
+        expand_context(vector_store.as_ref(), &mut results, radius)
+            .await
+            .context("failed to fetch neighboring chunks")?;
+    }
+    let context_ms = context_start.elapsed().as_millis();
+
+    if verbose {
+        let total_ms = start_time.elapsed().as_millis();
+        eprintln!("Timing:");
+        eprintln!("  Embedding: {}ms", run.embed_ms);
+        eprintln!("  Search: {}ms", run.search_ms);
+        if rerank {
+            eprintln!("  Rerank: {}ms", run.rerank_ms);
+        }
+        if args.context.is_some() {
+            eprintln!("  Context: {context_ms}ms");
+        }
+        eprintln!("  Total: {total_ms}ms");
+        eprintln!();
+    }
+
+    let history = MetricsStore::open_history(&config);
+    if let Some((dense, keyword)) = run.explained {
+        // Compare against earlier searches only, so read before recording this one
+        let top_scores: Vec<f32> = history
+            .as_ref()
+            .and_then(|h| h.recent_searches(EXPLAIN_HISTORY, false).ok())
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|entry| entry.search.top_score)
+            .collect();
+        explain_scores(&mut results, &dense, keyword.as_deref(), &tags, &top_scores);
+    }
+
+    let duration_ms = start_time.elapsed().as_millis() as u64;
+    if let Some(history) = history {
+        history.record_search(&SearchRecord::new(
+            query,
+            &run.filter,
+            &results,
+            duration_ms,
+        ));
+    }
+    let total = results.len() as u64;
+    let search_results = SearchResults::new(query.to_string(), results, total, duration_ms)
+        .with_offset(u64::from(offset));
+
+    if let Some(ref path) = args.output {
+        // Keep terminal styling out of the file
+        console::set_colors_enabled(false);
+        std::fs::write(path, formatter.format_search_results(&search_results))
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        if verbose {
+            eprintln!("Wrote {} results to {}", total, path.display());
+        }
+    } else {
+        print!("{}", formatter.format_search_results(&search_results));
+    }
+    if let Some(ref path) = args.report {
+        let report = SearchReport {
+            results: &search_results,
+            filters: described,
+            collection: config.vector_store.scoped_collection(),
+            generated_at: Utc::now(),
+        };
+        std::fs::write(path, MarkdownFormatter.format_report(&report))
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        if verbose {
+            eprintln!("Wrote report to {}", path.display());
+        }
+    }
+
+    if total == 0 {
+        return Err(SearchError::NoResults.into());
+    }
+    if let Some(number) = args.open {
+        open_result(&search_results, number)?;
+    }
+    Ok(())
+}
+
+/// One query of a `--batch` run, with the command line's options applied.
+struct BatchQuery {
+    text: String,
+    filter: SearchFilter,
+    limit: u32,
+    min_score: Option<f32>,
+    hybrid: bool,
+}
+
+impl BatchQuery {
+    /// Options given for the query replace the command line's; filters and
+    /// paths are added to them.
+    fn new(args: &SearchArgs, config: &Config, saved: SavedQuery) -> Result<Self> {
+        if saved.collection.is_some() {
+            anyhow::bail!("a batch searches one collection; use --collection instead");
+        }
+        let text = saved.query.trim().to_string();
+        if text.is_empty() {
+            anyhow::bail!("search query cannot be empty");
+        }
+        let limit = saved
+            .limit
+            .or(args.limit)
+            .unwrap_or(config.search.default_limit);
+        if limit == 0 {
+            anyhow::bail!("limit must be at least 1");
+        }
+        let min_score = saved
+            .min_score
+            .or(args.min_score)
+            .or(config.search.default_min_score);
+        if let Some(score) = min_score
+            && !(0.0..=1.0).contains(&score)
+        {
+            anyhow::bail!("min_score must be between 0.0 and 1.0");
+        }
+
+        let tags = saved
+            .tags
+            .as_deref()
+            .or(args.tags.as_deref())
+            .map(parse_tags)
+            .transpose()
+            .context("failed to parse tags")?
+            .unwrap_or_default();
+        let source = saved.source.as_deref().or(args.source.as_deref());
+        let languages = saved.languages.as_deref().or(args.languages.as_deref());
+        let filters: Vec<String> = args.filters.iter().chain(&saved.filters).cloned().collect();
+        let since = saved.since.as_deref().or(args.since.as_deref());
+        let paths = args.paths.iter().chain(&saved.paths).map(PathPattern::new);
+        let workspace = config.workspace().filter(|_| !args.all_workspaces);
+        let filter = SearchFilter::new()
+            .with_tags(tags)
+            .with_source_types(parse_source_types(source))
+            .with_languages(parse_languages(languages))
+            .with_expressions(parse_expressions(&filters, since, args.until.as_deref())?)
+            .with_relative_paths(paths.collect())
+            .with_workspace(workspace);
+
+        Ok(Self {
+            text,
+            filter,
+            limit,
+            min_score,
+            hybrid: args.hybrid || saved.hybrid,
+        })
+    }
+}
+
+/// Run every query of a `--batch` file: the queries are embedded in one
+/// daemon request and searched concurrently.
+async fn handle_batch(
+    args: &SearchArgs,
+    path: &Path,
+    format: OutputFormat,
+    verbose: bool,
+) -> Result<()> {
+    let config = Config::load_with_collection(args.collection.as_deref())?.config;
+    let formatter = get_formatter(format);
+    let start_time = Instant::now();
+
+    let text = if path == Path::new("-") {
+        std::io::read_to_string(std::io::stdin()).context("failed to read queries from stdin")?
+    } else {
+        std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?
+    };
+    let queries = parse_batch(&text)?
+        .into_iter()
+        .map(|(line, saved)| {
+            BatchQuery::new(args, &config, saved)
+                .with_context(|| format!("{}:{}", path.display(), line))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if queries.is_empty() {
+        anyhow::bail!("no queries in {}", path.display());
     }
 
     let embedding_client = EmbeddingClient::new(&config);
-    let vector_store = create_backend(&config.vector_store).await?;
+    let vector_store: Arc<dyn VectorStore> = create_backend(&config.vector_store).await?.into();
+    check_dimension(vector_store.as_ref(), u64::from(config.embedding.dimension)).await?;
 
     let embed_start = Instant::now();
-    let query_embedding = embedding_client
-        .embed_query(query)
+    let embeddings = embedding_client
+        .embed_queries(queries.iter().map(|q| q.text.clone()).collect())
         .await
-        .context("failed to generate query embedding")?;
+        .context("failed to generate query embeddings")?;
     let embed_ms = embed_start.elapsed().as_millis();
 
     let search_start = Instant::now();
-    let mut results = vector_store
-        .search(
-            query_embedding,
-            u64::from(limit),
-            &tags,
-            &source_types,
-            min_score,
-        )
-        .await
-        .context("search failed")?;
+    let mut rankings: Vec<(Vec<SearchResult>, u64)> = vec![Default::default(); queries.len()];
+    let mut in_flight = JoinSet::new();
+    for (index, (query, embedding)) in queries.iter().zip(embeddings).enumerate() {
+        if in_flight.len() >= BATCH_CONCURRENCY {
+            let (index, ranking) = finish_batch_search(in_flight.join_next().await)?;
+            rankings[index] = ranking;
+        }
+        let store = Arc::clone(&vector_store);
+        let text = query.text.clone();
+        let filter = query.filter.clone();
+        let (limit, min_score, hybrid) = (u64::from(query.limit), query.min_score, query.hybrid);
+        in_flight.spawn(async move {
+            let started = Instant::now();
+            let ranking = if hybrid {
+                store
+                    .hybrid_search(embedding, &text, limit, 0, &filter, min_score)
+                    .await
+            } else {
+                store.search(embedding, limit, 0, &filter, min_score).await
+            };
+            (index, ranking, started.elapsed().as_millis() as u64)
+        });
+    }
+    while let Some(joined) = in_flight.join_next().await {
+        let (index, ranking) = finish_batch_search(Some(joined))?;
+        rankings[index] = ranking;
+    }
     let search_ms = search_start.elapsed().as_millis();
 
-    results.truncate(limit as usize);
+    let history = MetricsStore::open_history(&config);
+    let mut batch = Vec::with_capacity(queries.len());
+    for (query, (mut results, duration_ms)) in queries.iter().zip(rankings) {
+        if let Some(radius) = args.context {
+            expand_context(vector_store.as_ref(), &mut results, radius)
+                .await
+                .context("failed to fetch neighboring chunks")?;
+        }
+        if let Some(ref history) = history {
+            history.record_search(&SearchRecord::new(
+                &query.text,
+                &query.filter,
+                &results,
+                duration_ms,
+            ));
+        }
+        let total = results.len() as u64;
+        batch.push(SearchResults::new(
+            query.text.clone(),
+            results,
+            total,
+            duration_ms,
+        ));
+    }
 
     if verbose {
-        let total_ms = start_time.elapsed().as_millis();
+        eprintln!("Batch: {} queries", queries.len());
         eprintln!("Timing:");
         eprintln!("  Embedding: {embed_ms}ms");
         eprintln!("  Search: {search_ms}ms");
-        eprintln!("  Total: {total_ms}ms");
+        eprintln!("  Total: {}ms", start_time.elapsed().as_millis());
         eprintln!();
     }
 
-    let duration_ms = start_time.elapsed().as_millis() as u64;
-    let total = results.len() as u64;
-    let search_results = SearchResults::new(query.to_string(), results, total, duration_ms);
-
-    print!("{}", formatter.format_search_results(&search_results));
+    if let Some(ref path) = args.output {
+        // Keep terminal styling out of the file
+        console::set_colors_enabled(false);
+        std::fs::write(path, formatter.format_batch_results(&batch))
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        if verbose {
+            eprintln!("Wrote {} result sets to {}", batch.len(), path.display());
+        }
+    } else {
+        print!("{}", formatter.format_batch_results(&batch));
+    }
 
+    if batch.iter().all(SearchResults::is_empty) {
+        return Err(SearchError::NoResults.into());
+    }
     Ok(())
 }
+
+/// A finished `--batch` search: query index, ranking and duration in ms.
+type BatchSearch = (usize, Result<Vec<SearchResult>, VectorStoreError>, u64);
+
+fn finish_batch_search(
+    joined: Option<Result<BatchSearch, JoinError>>,
+) -> Result<(usize, (Vec<SearchResult>, u64))> {
+    let (index, ranking, duration_ms) = joined
+        .context("no search in flight")?
+        .context("search task failed")?;
+    Ok((index, (ranking.context("search failed")?, duration_ms)))
+}
+
+/// Queries of a `--batch` file with their line numbers: a JSON object per
+/// line (the fields of an `[aliases.NAME]` entry) or plain query text.
+/// Blank lines and lines starting with `#` are skipped.
+fn parse_batch(text: &str) -> Result<Vec<(usize, SavedQuery)>> {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(number, line)| {
+            let saved = if line.starts_with('{') {
+                serde_json::from_str(line)
+                    .with_context(|| format!("line {number}: invalid query object"))?
+            } else {
+                SavedQuery {
+                    query: line.to_string(),
+                    ..Default::default()
+                }
+            };
+            Ok((number, saved))
+        })
+        .collect()
+}
+
+fn parse_source_types(source: Option<&str>) -> Vec<SourceType> {
+    source
+        .map(|s| {
+            s.split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(|s| s.parse().unwrap())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn parse_languages(languages: Option<&str>) -> Vec<String> {
+    languages
+        .map(|s| {
+            s.split(',')
+                .filter(|s| !s.trim().is_empty())
+                .map(normalize_language)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// `--filter` expressions plus the `--since`/`--until` bounds on the update time.
+fn parse_expressions(
+    filters: &[String],
+    since: Option<&str>,
+    until: Option<&str>,
+) -> Result<Vec<FilterExpr>> {
+    let mut expressions: Vec<FilterExpr> = filters
+        .iter()
+        .map(|f| f.parse())
+        .collect::<Result<_, _>>()?;
+    if let Some(since) = since {
+        expressions.push(FilterExpr::Time(TimeBound::new(
+            TimeField::UpdatedAt,
+            RangeOp::Gte,
+            parse_datetime(since)?,
+        )));
+    }
+    if let Some(until) = until {
+        expressions.push(FilterExpr::Time(TimeBound::new(
+            TimeField::UpdatedAt,
+            RangeOp::Lt,
+            parse_datetime(until)?,
+        )));
+    }
+    Ok(expressions)
+}
+
+/// Open the result numbered `number` as listed (counting past the page's
+/// offset), asking for the number when it is 0.
+fn open_result(results: &SearchResults, number: u64) -> Result<()> {
+    let first = results.offset + 1;
+    let last = results.offset + results.results.len() as u64;
+    let number = if number == 0 {
+        if !std::io::stdin().is_terminal() {
+            anyhow::bail!("--open without a result number needs a terminal to ask");
+        }
+        eprint!("Open which result? [{first}-{last}] ");
+        std::io::stderr().flush()?;
+        let mut input = String::new();
+        std::io::stdin().read_line(&mut input)?;
+        let input = input.trim();
+        if input.is_empty() {
+            return Ok(());
+        }
+        input
+            .parse()
+            .with_context(|| format!("not a result number: {input}"))?
+    } else {
+        number
+    };
+    if !(first..=last).contains(&number) {
+        anyhow::bail!("no result {number} on this page (results {first}-{last})");
+    }
+
+    let result = &results.results[(number - first) as usize];
+    let target = OpenTarget::for_result(result).with_context(|| {
+        format!(
+            "result {number} ({}) has no file or URL to open",
+            result.location
+        )
+    })?;
+    target.open()
+}
+
+/// An in-memory store holding the results saved by an earlier `search -f json`.
+///
+/// Vectors come from the index; results whose chunks have since been
+/// re-indexed or deleted are embedded again from their saved content.
+async fn refine_store(
+    path: &Path,
+    vector_store: &dyn VectorStore,
+    embedding_client: &EmbeddingClient,
+) -> Result<MemoryBackend> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    let previous: SearchResults = serde_json::from_str(&text).with_context(|| {
+        format!(
+            "{} is not saved search output (use 'search -f json')",
+            path.display()
+        )
+    })?;
+
+    let chunk_ids: Vec<String> = previous
+        .results
+        .iter()
+        .map(|r| r.chunk_id.clone())
+        .collect();
+    let mut vectors = vector_store
+        .get_vectors(&chunk_ids)
+        .await
+        .context("failed to fetch result vectors")?;
+    let missing: Vec<String> = previous
+        .results
+        .iter()
+        .filter(|r| !vectors.contains_key(&r.chunk_id))
+        .map(|r| r.content.clone())
+        .collect();
+    if !missing.is_empty() {
+        let embeddings = embedding_client
+            .embed_batch(missing)
+            .await
+            .context("failed to embed earlier results")?;
+        let mut embeddings = embeddings.into_iter();
+        for result in &previous.results {
+            if !vectors.contains_key(&result.chunk_id)
+                && let Some(embedding) = embeddings.next()
+            {
+                vectors.insert(result.chunk_id.clone(), embedding);
+            }
+        }
+    }
+
+    let chunks = previous
+        .results
+        .into_iter()
+        .map(|r| {
+            let vector = vectors.remove(&r.chunk_id).unwrap_or_default();
+            DocumentChunk::from_search_result(r, vector)
+        })
+        .collect();
+    let store = MemoryBackend::new("refine");
+    store.upsert_points(chunks).await?;
+    Ok(store)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_batch() {
+        let text = "\
+# deploy questions
+how do we deploy
+
+{\"query\": \"retry policy\", \"tags\": \"team:platform\", \"limit\": 3, \"filters\": [\"language=rust\"]}
+";
+        let queries = parse_batch(text).unwrap();
+        assert_eq!(queries.len(), 2);
+        assert_eq!(queries[0].0, 2);
+        assert_eq!(queries[0].1.query, "how do we deploy");
+        let (line, saved) = &queries[1];
+        assert_eq!(*line, 4);
+        assert_eq!(saved.tags.as_deref(), Some("team:platform"));
+        assert_eq!(saved.limit, Some(3));
+        assert_eq!(saved.filters, vec!["language=rust"]);
+
+        let error = parse_batch("ok\n{\"tags\": \"x\"}").unwrap_err();
+        assert!(error.to_string().contains("line 2"));
+    }
+}
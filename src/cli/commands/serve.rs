@@ -1,15 +1,55 @@
-use anyhow::Result;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
 use clap::{Args, Subcommand};
 
+use crate::cli::output::get_formatter;
 use crate::client::{DaemonClient, stop_daemon};
-use crate::models::Config;
-use crate::server::run_daemon;
+use crate::logging::LOG_ENV;
+use crate::models::{Config, DEFAULT_METRICS_RETENTION_DAYS, Device, LogFormat, OutputFormat};
+use crate::server::activation::{LAUNCHD_LABEL, SERVICE_NAME, launchd_plist, systemd_units};
+use crate::server::protocol::ReloadModelRequest;
+use crate::server::{run_daemon, run_editor, run_lsp, run_mcp};
+use crate::services::{MetricsStore, RequestRecord, StatsPeriod};
+
+/// How long `serve restart` waits for the old daemon to drain and release its socket.
+const RESTART_WAIT: Duration = Duration::from_secs(35);
 
 #[derive(Debug, Args)]
 pub struct ServeArgs {
     #[command(subcommand)]
     pub command: Option<ServeCommand>,
 
+    /// Also serve the HTTP API on this address (e.g., 127.0.0.1:8765)
+    #[arg(long, value_name = "ADDR")]
+    pub http: Option<String>,
+
+    /// Serve Prometheus metrics on this address (e.g., 127.0.0.1:9464)
+    #[arg(long, value_name = "ADDR")]
+    pub metrics: Option<String>,
+
+    /// Inference device, overriding [embedding] device (cpu, cuda, coreml, directml)
+    #[arg(long, value_name = "DEVICE")]
+    pub device: Option<Device>,
+
+    /// Also load the reranker and connect to the vector store before reporting ready
+    #[arg(long)]
+    pub preload: bool,
+
+    /// Serve the Model Context Protocol over stdio for AI coding agents
+    #[arg(long, conflicts_with_all = ["http", "metrics"])]
+    pub mcp: bool,
+
+    /// Serve JSON-RPC over stdio for editor plugins (search, indexBuffer, status)
+    #[arg(long, conflicts_with_all = ["http", "metrics", "mcp"])]
+    pub stdio: bool,
+
+    /// Serve the Language Server Protocol over stdio (experimental): semantic
+    /// matches as workspace symbols and through `ssearch/semanticSearch`
+    #[arg(long, conflicts_with_all = ["http", "metrics", "mcp", "stdio"])]
+    pub lsp: bool,
+
     #[arg(long, hide = true)]
     pub daemon: bool,
 
@@ -20,29 +60,123 @@ pub struct ServeArgs {
 #[derive(Debug, Subcommand)]
 pub enum ServeCommand {
     Stop,
+    /// Restart once in-flight requests finish, running the current binary
     Restart,
+    /// Swap the running daemon's embedding model without restarting it
+    Reload(ReloadArgs),
+    /// Show request counts, success rate, batch size and latency history
+    Stats(StatsArgs),
+    /// Install a systemd user service (launchd agent on macOS) that starts
+    /// the daemon on demand through socket activation
+    InstallService(InstallServiceArgs),
 }
 
-pub async fn handle_serve(args: ServeArgs) -> Result<()> {
-    let config = Config::load()?.config;
+#[derive(Debug, Args)]
+pub struct InstallServiceArgs {
+    /// Print the service definitions instead of installing them
+    #[arg(long, conflicts_with = "uninstall")]
+    pub print: bool,
+
+    /// Stop and remove an installed service
+    #[arg(long)]
+    pub uninstall: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct StatsArgs {
+    /// Days of history to include
+    #[arg(long, short = 'd', default_value_t = DEFAULT_METRICS_RETENTION_DAYS)]
+    pub days: u32,
+
+    /// Group by week instead of by day
+    #[arg(long)]
+    pub weekly: bool,
+
+    /// Export the raw request rows as CSV to this file (- for stdout)
+    #[arg(long, value_name = "FILE")]
+    pub csv: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct ReloadArgs {
+    /// Model to load (HuggingFace model ID)
+    #[arg(long, value_name = "ID")]
+    pub model: String,
+
+    /// Embedding dimension; defaults to the daemon's current one
+    #[arg(long, value_name = "N")]
+    pub dimension: Option<u32>,
+
+    /// Local model directory, instead of the models directory
+    #[arg(long, value_name = "PATH")]
+    pub model_path: Option<PathBuf>,
+}
+
+/// Logging flags given to `ssearch serve`, forwarded to a background daemon.
+#[derive(Debug, Clone, Default)]
+pub struct LogArgs {
+    pub level: Option<String>,
+    pub format: Option<LogFormat>,
+}
+
+pub async fn handle_serve(args: ServeArgs, format: OutputFormat, log_args: LogArgs) -> Result<()> {
+    let mut config = Config::load()?.config;
+
+    if let Some(addr) = args.http {
+        addr.parse::<std::net::SocketAddr>()
+            .map_err(|e| anyhow::anyhow!("invalid HTTP address '{}': {}", addr, e))?;
+        config.daemon.http_addr = Some(addr);
+    }
+
+    if let Some(addr) = args.metrics {
+        addr.parse::<std::net::SocketAddr>()
+            .map_err(|e| anyhow::anyhow!("invalid metrics address '{}': {}", addr, e))?;
+        config.metrics.http_addr = Some(addr);
+    }
+
+    if let Some(device) = args.device {
+        config.embedding.device = device;
+    }
+
+    if args.preload {
+        config.daemon.preload = true;
+    }
+
+    if args.mcp {
+        run_mcp(config).await?;
+        return Ok(());
+    }
+
+    if args.stdio {
+        run_editor(config).await?;
+        return Ok(());
+    }
+
+    if args.lsp {
+        run_lsp(config).await?;
+        return Ok(());
+    }
 
     if args.daemon {
         return run_daemon_mode(config).await;
     }
 
     if args.foreground {
-        eprintln!("Starting daemon in foreground mode...");
+        tracing::info!("starting daemon in foreground mode");
         return run_daemon_mode(config).await;
     }
 
     match args.command {
         Some(ServeCommand::Stop) => handle_stop(&config),
-        Some(ServeCommand::Restart) => handle_restart(&config).await,
-        None => handle_start(&config),
+        Some(ServeCommand::Restart) => handle_restart(&config, &log_args).await,
+        Some(ServeCommand::Reload(reload)) => handle_reload(&config, reload).await,
+        Some(ServeCommand::Stats(stats)) => handle_stats(&config, stats, format),
+        Some(ServeCommand::InstallService(install)) => handle_install_service(&config, install),
+        None => handle_start(&config, &log_args),
     }
 }
 
-fn handle_start(config: &Config) -> Result<()> {
+fn handle_start(config: &Config, log_args: &LogArgs) -> Result<()> {
     let client = DaemonClient::new(config);
 
     if client.is_running() {
@@ -52,15 +186,47 @@ fn handle_start(config: &Config) -> Result<()> {
 
     let exe = std::env::current_exe()?;
 
-    std::process::Command::new(&exe)
-        .args(["serve", "--daemon"])
+    let mut command = std::process::Command::new(&exe);
+    command.args(["serve", "--daemon"]);
+    if let Some(ref addr) = config.daemon.http_addr {
+        command.args(["--http", addr]);
+    }
+    if let Some(ref addr) = config.metrics.http_addr {
+        command.args(["--metrics", addr]);
+    }
+    command.args(["--device", &config.embedding.device.to_string()]);
+    if config.daemon.preload {
+        command.arg("--preload");
+    }
+    if let Some(ref level) = log_args.level {
+        command.env(LOG_ENV, level);
+    }
+    if let Some(format) = log_args.format {
+        command.args(["--log-format", &format.to_string()]);
+    }
+
+    let log_path = config.log_path();
+    let log_file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&log_path)
+        .with_context(|| format!("failed to open log file {}", log_path.display()))?;
+
+    command
         .stdin(std::process::Stdio::null())
         .stdout(std::process::Stdio::null())
-        .stderr(std::process::Stdio::null())
+        .stderr(log_file)
         .spawn()?;
 
     println!("Daemon started");
     println!("Socket: {}", config.socket_path().display());
+    println!("Logs: {}", log_path.display());
+    if let Some(ref addr) = config.daemon.http_addr {
+        println!("HTTP: http://{}", addr);
+    }
+    if let Some(ref addr) = config.metrics.http_addr {
+        println!("Metrics: http://{}/metrics", addr);
+    }
     Ok(())
 }
 
@@ -78,10 +244,224 @@ fn handle_stop(config: &Config) -> Result<()> {
     }
 }
 
-async fn handle_restart(config: &Config) -> Result<()> {
-    let _ = stop_daemon(config);
-    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
-    handle_start(config)
+/// Stop the daemon once its in-flight requests finish, then start the
+/// current binary in its place.
+async fn handle_restart(config: &Config, log_args: &LogArgs) -> Result<()> {
+    let client = DaemonClient::new(config);
+    let mut previous = None;
+    if client.is_running() {
+        // Daemons that predate the handshake still stop on request
+        previous = client.hello().await.ok();
+        if client.shutdown().await.is_err() {
+            let _ = stop_daemon(config);
+        }
+    }
+
+    // A socket-activated daemon is started again by its service manager on
+    // the next connection; otherwise wait for the socket and spawn one
+    let activated = previous.as_ref().is_some_and(|p| p.socket_activated);
+    if !activated {
+        client
+            .wait_for_release(RESTART_WAIT)
+            .await
+            .context("daemon did not release its socket")?;
+        handle_start(config, log_args)?;
+    }
+
+    let start = std::time::Instant::now();
+    let current = loop {
+        client
+            .wait_for_ready()
+            .await
+            .context("restarted daemon did not become ready")?;
+        let hello = client.hello().await?;
+        // The old daemon may still answer while it drains
+        if previous.as_ref().is_none_or(|p| p.pid != hello.pid) || start.elapsed() > RESTART_WAIT {
+            break hello;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    };
+    match previous {
+        Some(previous) if previous.daemon_version != current.daemon_version => println!(
+            "Daemon upgraded: {} -> {}",
+            previous.daemon_version, current.daemon_version
+        ),
+        _ => println!("Daemon version: {}", current.daemon_version),
+    }
+    Ok(())
+}
+
+fn handle_install_service(config: &Config, args: InstallServiceArgs) -> Result<()> {
+    let exe = std::env::current_exe()?;
+    let socket = config.socket_path();
+    let launchd = cfg!(target_os = "macos");
+    let files = if launchd {
+        let agents = dirs::home_dir()
+            .ok_or_else(|| anyhow::anyhow!("could not determine home directory"))?
+            .join("Library/LaunchAgents");
+        vec![launchd_plist(&agents, &exe, &socket, &config.log_path())]
+    } else {
+        let units = dirs::config_dir()
+            .ok_or_else(|| anyhow::anyhow!("could not determine config directory"))?
+            .join("systemd/user");
+        systemd_units(&units, &exe, &socket)
+    };
+
+    if args.print {
+        for file in &files {
+            println!("# {}\n{}", file.path.display(), file.contents);
+        }
+        return Ok(());
+    }
+
+    let uid = nix::unistd::getuid().to_string();
+    if args.uninstall {
+        if launchd {
+            let target = format!("gui/{}/{}", uid, LAUNCHD_LABEL);
+            let _ = run_service_manager("launchctl", &["bootout", &target]);
+        } else {
+            let socket_unit = format!("{}.socket", SERVICE_NAME);
+            let service_unit = format!("{}.service", SERVICE_NAME);
+            let _ = run_service_manager(
+                "systemctl",
+                &["--user", "disable", "--now", &socket_unit, &service_unit],
+            );
+        }
+        for file in &files {
+            if file.path.exists() {
+                std::fs::remove_file(&file.path)
+                    .with_context(|| format!("failed to remove {}", file.path.display()))?;
+                println!("Removed {}", file.path.display());
+            }
+        }
+        if !launchd {
+            run_service_manager("systemctl", &["--user", "daemon-reload"])?;
+        }
+        return Ok(());
+    }
+
+    // The service manager must own the socket, so stop a daemon the client spawned
+    if DaemonClient::new(config).is_running() {
+        let _ = stop_daemon(config);
+    }
+
+    for file in &files {
+        if let Some(parent) = file.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&file.path, &file.contents)
+            .with_context(|| format!("failed to write {}", file.path.display()))?;
+        println!("Wrote {}", file.path.display());
+    }
+
+    if launchd {
+        let domain = format!("gui/{}", uid);
+        let plist = files[0].path.to_string_lossy();
+        // Replace an agent loaded by an earlier install
+        let _ = run_service_manager(
+            "launchctl",
+            &["bootout", &format!("{}/{}", domain, LAUNCHD_LABEL)],
+        );
+        run_service_manager("launchctl", &["bootstrap", &domain, &plist])?;
+    } else {
+        let socket_unit = format!("{}.socket", SERVICE_NAME);
+        run_service_manager("systemctl", &["--user", "daemon-reload"])?;
+        run_service_manager("systemctl", &["--user", "enable", "--now", &socket_unit])?;
+    }
+
+    println!(
+        "Service installed; the daemon starts on the first connection to {}",
+        socket.display()
+    );
+    Ok(())
+}
+
+/// Run `systemctl`/`launchctl`, failing with its stderr.
+fn run_service_manager(program: &str, args: &[&str]) -> Result<()> {
+    let output = std::process::Command::new(program)
+        .args(args)
+        .output()
+        .with_context(|| format!("failed to run {}", program))?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "{} {} failed: {}",
+            program,
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+fn handle_stats(config: &Config, args: StatsArgs, format: OutputFormat) -> Result<()> {
+    let formatter = get_formatter(format);
+    let path = Config::metrics_db_path()
+        .ok_or_else(|| anyhow::anyhow!("could not determine cache directory"))?;
+    if !path.exists() {
+        println!(
+            "{}",
+            formatter.format_message("No daemon requests recorded.")
+        );
+        return Ok(());
+    }
+    if !config.metrics.enabled {
+        eprintln!("Metrics are disabled in [metrics]; showing earlier requests");
+    }
+    let store = MetricsStore::open(&path).context("failed to open metrics database")?;
+
+    if let Some(csv) = args.csv {
+        let requests = store
+            .requests(args.days)
+            .context("failed to read daemon metrics")?;
+        let mut out = String::new();
+        out.push_str(RequestRecord::CSV_HEADER);
+        out.push('\n');
+        for request in &requests {
+            out.push_str(&request.to_csv());
+            out.push('\n');
+        }
+        if csv.as_os_str() == "-" {
+            print!("{}", out);
+        } else {
+            std::fs::write(&csv, out)
+                .with_context(|| format!("failed to write {}", csv.display()))?;
+            eprintln!("Exported {} requests to {}", requests.len(), csv.display());
+        }
+        return Ok(());
+    }
+
+    let period = if args.weekly {
+        StatsPeriod::Weekly
+    } else {
+        StatsPeriod::Daily
+    };
+    let stats = store
+        .daemon_stats(args.days, period)
+        .context("failed to read daemon metrics")?;
+    print!("{}", formatter.format_daemon_stats(&stats));
+    Ok(())
+}
+
+async fn handle_reload(config: &Config, args: ReloadArgs) -> Result<()> {
+    let client = DaemonClient::new(config);
+    if !client.is_running() {
+        anyhow::bail!("daemon is not running; start it with `ssearch serve`");
+    }
+
+    let reloaded = client
+        .reload_model(ReloadModelRequest {
+            model_id: args.model,
+            dimension: args.dimension,
+            model_path: args.model_path,
+        })
+        .await
+        .context("failed to reload embedding model")?;
+
+    println!(
+        "Reloaded embedding model: {} -> {} (dim {})",
+        reloaded.previous_model_id, reloaded.model_id, reloaded.dimension
+    );
+    Ok(())
 }
 
 async fn run_daemon_mode(config: Config) -> Result<()> {
@@ -1,14 +1,22 @@
 //! Source command implementation for external data sources.
 
 use anyhow::{Context, Result};
+use chrono::Utc;
 use clap::Subcommand;
+use clap_complete::ArgValueCompleter;
 use indicatif::{ProgressBar, ProgressStyle};
+use std::collections::BTreeMap;
 use std::process::Command;
 use std::time::Instant;
-
-use crate::cli::output::{CliInfo, IndexStats, SourceInfo, get_formatter};
-use crate::models::{Config, OutputFormat, SourceType, Tag, parse_tags};
-use crate::services::{EmbeddingClient, TextChunker, create_backend, process_batch};
+use tokio_stream::StreamExt;
+
+use crate::cli::completion::complete_tags;
+use crate::cli::output::{CliInfo, Formatter, IndexStats, SourceInfo, get_formatter};
+use crate::models::{Config, OutputFormat, SourceType, SyncJob, Tag, parse_tags};
+use crate::services::{
+    EmbeddingClient, IndexPipeline, PiiOutcome, TextChunker, check_dimension, create_backend,
+    create_backend_with_embedding_config, screen_pii,
+};
 use crate::sources::{SyncOptions, get_data_source};
 
 #[derive(Debug, Subcommand)]
@@ -18,20 +26,23 @@ pub enum SourceCommand {
 
     /// Sync data from an external source
     Sync {
-        /// Source type (jira, confluence, figma)
-        #[arg(required = true)]
-        source: String,
+        /// Source type (jira, confluence, figma, github, slack, gdrive, mail, web)
+        #[arg(required_unless_present_any = ["all_configured", "schedule"])]
+        source: Option<String>,
 
-        /// Source-specific query (e.g., JQL for Jira, CQL for Confluence)
+        /// Source-specific query (e.g., JQL for Jira, CQL for Confluence, search for GitHub,
+        /// export directory for Slack, file URL or Drive search for Google Drive,
+        /// mbox path or IMAP folder URL for mail, start URLs for web)
         #[arg(long, short = 'q')]
         query: Option<String>,
 
-        /// Project key (Jira) or space key (Confluence) - syncs all items
+        /// Project key (Jira), space key (Confluence), owner/repo (GitHub), channels (Slack),
+        /// or folder IDs (Google Drive)
         #[arg(long, short = 'p')]
         project: Option<String>,
 
         /// Tags to apply to synced documents
-        #[arg(long, short = 't')]
+        #[arg(long, short = 't', add = ArgValueCompleter::new(complete_tags))]
         tags: Option<String>,
 
         /// Maximum items to sync (ignored with --all)
@@ -45,11 +56,34 @@ pub enum SourceCommand {
         /// Exclude pages under these ancestor IDs (Confluence only, comma-separated)
         #[arg(long)]
         exclude_ancestor: Option<String>,
+
+        /// Also index page attachments (PDF, text; images with the `ocr` feature)
+        /// as documents tagged `parent-page:<id>` (Confluence only)
+        #[arg(long)]
+        attachments: bool,
+
+        /// Link levels to follow from the start pages, staying under their directory (web only)
+        #[arg(long, default_value = "0")]
+        depth: u32,
+
+        /// Run every [[sync]] job from the config instead of a single source
+        #[arg(
+            long,
+            conflicts_with_all = ["source", "query", "project", "tags", "limit", "all", "exclude_ancestor", "attachments", "depth"]
+        )]
+        all_configured: bool,
+
+        /// Keep running [[sync]] jobs from the config, each every `interval`
+        #[arg(
+            long,
+            conflicts_with_all = ["source", "query", "project", "tags", "limit", "all", "exclude_ancestor", "attachments", "depth"]
+        )]
+        schedule: bool,
     },
 
     /// Delete all indexed documents from a source type
     Delete {
-        /// Source type to delete (jira, confluence, figma)
+        /// Source type to delete (jira, confluence, figma, github, slack)
         #[arg(required = true)]
         source: String,
 
@@ -68,6 +102,13 @@ pub async fn handle_source(cmd: SourceCommand, format: OutputFormat, verbose: bo
 
     match cmd {
         SourceCommand::List => handle_list(formatter.as_ref(), verbose),
+        SourceCommand::Sync { schedule: true, .. } => {
+            handle_schedule(formatter.as_ref(), &config, verbose).await
+        }
+        SourceCommand::Sync {
+            all_configured: true,
+            ..
+        } => handle_configured(formatter.as_ref(), &config, verbose).await,
         SourceCommand::Sync {
             source,
             query,
@@ -76,20 +117,24 @@ pub async fn handle_source(cmd: SourceCommand, format: OutputFormat, verbose: bo
             limit,
             all,
             exclude_ancestor,
+            attachments,
+            depth,
+            ..
         } => {
-            handle_sync(
-                formatter.as_ref(),
-                &config,
-                &source,
+            let job = SyncJob {
+                source: source.unwrap_or_default().parse().unwrap(),
                 query,
                 project,
                 tags,
-                limit,
-                all,
-                exclude_ancestor,
-                verbose,
-            )
-            .await
+                limit: if all { None } else { Some(limit) },
+                exclude_ancestors: exclude_ancestor
+                    .map(|s| s.split(',').map(|id| id.trim().to_string()).collect())
+                    .unwrap_or_default(),
+                attachments,
+                depth,
+                interval: None,
+            };
+            handle_sync(formatter.as_ref(), &config, &job, verbose).await
         }
         SourceCommand::Delete { source, force } => {
             handle_delete(formatter.as_ref(), &config, &source, force, verbose).await
@@ -98,7 +143,7 @@ pub async fn handle_source(cmd: SourceCommand, format: OutputFormat, verbose: bo
     }
 }
 
-fn handle_list(formatter: &dyn crate::cli::output::Formatter, _verbose: bool) -> Result<()> {
+fn handle_list(formatter: &dyn Formatter, _verbose: bool) -> Result<()> {
     let source_defs: &[(&str, &str, &str)] = &[
         ("jira", "Jira issues via atlassian-cli", "atlassian-cli"),
         (
@@ -107,6 +152,27 @@ fn handle_list(formatter: &dyn crate::cli::output::Formatter, _verbose: bool) ->
             "atlassian-cli",
         ),
         ("figma", "Figma designs via figma-cli", "figma-cli"),
+        (
+            "github",
+            "GitHub issues, pull requests, and discussions via gh",
+            "gh",
+        ),
+        (
+            "slack",
+            "Slack channel threads via export or API token",
+            "curl",
+        ),
+        ("gdrive", "Google Docs and Sheets via the Drive API", "curl"),
+        (
+            "mail",
+            "Email threads from mbox files or an IMAP folder",
+            "curl",
+        ),
+        (
+            "web",
+            "Web pages as readable text, optionally crawled",
+            "curl",
+        ),
     ];
 
     let sources: Vec<SourceInfo> = source_defs
@@ -122,13 +188,20 @@ fn handle_list(formatter: &dyn crate::cli::output::Formatter, _verbose: bool) ->
     Ok(())
 }
 
-fn handle_status(formatter: &dyn crate::cli::output::Formatter, _verbose: bool) -> Result<()> {
-    let cli_defs: &[(&str, &str)] = &[
-        ("atlassian-cli", "For Jira and Confluence integration"),
-        ("figma-cli", "For Figma design integration"),
-    ];
-
-    let clis: Vec<CliInfo> = cli_defs
+/// External CLIs the sources shell out to, with what each is for.
+const EXTERNAL_CLIS: &[(&str, &str)] = &[
+    ("atlassian-cli", "For Jira and Confluence integration"),
+    ("figma-cli", "For Figma design integration"),
+    ("gh", "For GitHub integration"),
+    (
+        "curl",
+        "For Slack (SLACK_TOKEN) Google Drive (GOOGLE_DRIVE_TOKEN), and IMAP integration",
+    ),
+];
+
+/// Whether each external CLI is installed, and its version if so.
+pub(super) fn external_clis() -> Vec<CliInfo> {
+    EXTERNAL_CLIS
         .iter()
         .map(|&(name, desc)| {
             let available = check_cli_available(name);
@@ -149,28 +222,95 @@ fn handle_status(formatter: &dyn crate::cli::output::Formatter, _verbose: bool)
                 version,
             }
         })
-        .collect();
+        .collect()
+}
 
-    print!("{}", formatter.format_cli_status(&clis));
+fn handle_status(formatter: &dyn Formatter, _verbose: bool) -> Result<()> {
+    print!("{}", formatter.format_cli_status(&external_clis()));
     Ok(())
 }
 
-#[allow(clippy::too_many_arguments)]
+/// Run every `[[sync]]` job once, continuing past failures.
+async fn handle_configured(
+    formatter: &dyn Formatter,
+    config: &Config,
+    verbose: bool,
+) -> Result<()> {
+    if config.sync.is_empty() {
+        anyhow::bail!("no [[sync]] jobs configured");
+    }
+
+    let mut failed = 0;
+    for job in &config.sync {
+        if let Err(e) = handle_sync(formatter, config, job, verbose).await {
+            eprint!(
+                "{}",
+                formatter.format_error(&format!("{}: {:#}", job.label(), e))
+            );
+            failed += 1;
+        }
+    }
+    if failed > 0 {
+        anyhow::bail!("{} of {} sync jobs failed", failed, config.sync.len());
+    }
+    Ok(())
+}
+
+/// Run `[[sync]]` jobs on their intervals until interrupted.
+///
+/// Every job runs at startup; jobs without an `interval` run only then.
+async fn handle_schedule(formatter: &dyn Formatter, config: &Config, verbose: bool) -> Result<()> {
+    let jobs = config
+        .sync
+        .iter()
+        .map(|job| Ok((job, job.interval()?)))
+        .collect::<Result<Vec<_>>>()?;
+    if jobs.iter().all(|(_, interval)| interval.is_none()) {
+        anyhow::bail!("no [[sync]] job has an interval to schedule");
+    }
+
+    let mut next_runs = vec![Some(tokio::time::Instant::now()); jobs.len()];
+    loop {
+        for ((job, interval), next_run) in jobs.iter().zip(next_runs.iter_mut()) {
+            if next_run.is_none_or(|at| at > tokio::time::Instant::now()) {
+                continue;
+            }
+            println!(
+                "[{}] Running sync: {}",
+                Utc::now().format("%Y-%m-%d %H:%M UTC"),
+                job.label()
+            );
+            // A failed run is retried at the next interval rather than stopping the schedule
+            if let Err(e) = handle_sync(formatter, config, job, verbose).await {
+                eprint!(
+                    "{}",
+                    formatter.format_error(&format!("{}: {:#}", job.label(), e))
+                );
+            }
+            *next_run = interval.map(|interval| tokio::time::Instant::now() + interval);
+        }
+
+        let Some(wake) = next_runs.iter().flatten().min().copied() else {
+            return Ok(());
+        };
+        if verbose {
+            let wait = wake.saturating_duration_since(tokio::time::Instant::now());
+            println!("Next sync in {}s", wait.as_secs());
+        }
+        tokio::time::sleep_until(wake).await;
+    }
+}
+
 async fn handle_sync(
-    formatter: &dyn crate::cli::output::Formatter,
+    formatter: &dyn Formatter,
     config: &Config,
-    source: &str,
-    query: Option<String>,
-    project: Option<String>,
-    tags: Option<String>,
-    limit: u32,
-    all: bool,
-    exclude_ancestor: Option<String>,
+    job: &SyncJob,
     verbose: bool,
 ) -> Result<()> {
     let start_time = Instant::now();
 
-    let source_type: SourceType = source.parse().unwrap();
+    let source_type = job.source.clone();
+    let source = source_type.to_string();
 
     if source_type.cli_command().is_none() {
         anyhow::bail!(
@@ -182,26 +322,57 @@ async fn handle_sync(
     let data_source = get_data_source(source_type.clone())
         .ok_or_else(|| anyhow::anyhow!("no implementation found for source: {}", source))?;
 
-    if !data_source.check_available()? {
+    if !data_source.check_available().await? {
         anyhow::bail!(
             "Required CLI is not installed.\n{}",
             data_source.install_instructions()
         );
     }
 
-    if project.is_some() && !matches!(source_type, SourceType::Jira | SourceType::Confluence) {
-        anyhow::bail!("--project option is only available for Jira and Confluence sources");
+    let project = job.project.clone();
+    let query = job.query.clone();
+    let attachments = job.attachments;
+    let depth = job.depth;
+    let limit = job.limit;
+
+    if project.is_some()
+        && !matches!(
+            source_type,
+            SourceType::Jira
+                | SourceType::Confluence
+                | SourceType::GitHub
+                | SourceType::Slack
+                | SourceType::GoogleDrive
+        )
+    {
+        anyhow::bail!(
+            "--project option is only available for Jira, Confluence, GitHub, Slack, and Google Drive sources"
+        );
     }
 
-    let tags: Vec<Tag> = if let Some(ref tag_str) = tags {
+    if attachments && source_type != SourceType::Confluence {
+        anyhow::bail!("--attachments option is only available for the Confluence source");
+    }
+
+    if depth > 0 && source_type != SourceType::Web {
+        anyhow::bail!("--depth option is only available for the web source");
+    }
+
+    let tags: Vec<Tag> = if let Some(ref tag_str) = job.tags {
         parse_tags(tag_str).context("failed to parse tags")?
     } else {
         Vec::new()
     };
 
-    let exclude_ancestors: Vec<String> = exclude_ancestor
-        .map(|s| s.split(',').map(|id| id.trim().to_string()).collect())
-        .unwrap_or_default();
+    let exclude_ancestors = job.exclude_ancestors.clone();
+
+    let tag_templates = config
+        .tag_templates(&source_type)
+        .context("invalid [tags] template")?;
+    let template_vars: BTreeMap<String, String> = project
+        .iter()
+        .map(|p| ("project".to_string(), p.clone()))
+        .collect();
 
     println!("Syncing from {} source...", data_source.name());
     if verbose {
@@ -211,100 +382,120 @@ async fn handle_sync(
         if let Some(ref q) = query {
             println!("  Query: {}", q);
         }
-        if !all {
+        if let Some(limit) = limit {
             println!("  Limit: {}", limit);
         }
         if !exclude_ancestors.is_empty() {
             println!("  Excluding ancestors: {:?}", exclude_ancestors);
         }
+        if attachments {
+            println!("  Including attachments");
+        }
+        if depth > 0 {
+            println!("  Depth: {}", depth);
+        }
     }
 
     let sync_options = SyncOptions {
         query,
         project,
         tags,
-        limit: if all { None } else { Some(limit) },
+        limit,
         exclude_ancestors,
+        attachments,
+        depth,
     };
 
-    let documents = data_source
-        .sync(sync_options)
-        .context("failed to sync from external source")?;
-
-    if documents.is_empty() {
-        println!(
-            "{}",
-            formatter.format_message("No documents found from source.")
-        );
-        return Ok(());
-    }
-
-    println!("Fetched {} documents, indexing...", documents.len());
-
     let embedding_client = EmbeddingClient::new(config);
-    let vector_store = create_backend(&config.vector_store).await?;
+    let vector_store =
+        create_backend_with_embedding_config(&config.vector_store, &config.embedding).await?;
+    check_dimension(vector_store.as_ref(), u64::from(config.embedding.dimension)).await?;
     vector_store.create_collection().await?;
 
-    let chunker = TextChunker::new(&config.indexing);
+    let chunker = TextChunker::from_config(config);
+
+    // Documents are chunked and embedded while the source is still fetching
+    let mut documents = data_source.sync_stream(sync_options);
 
-    let pb = ProgressBar::new(documents.len() as u64);
+    let pb = ProgressBar::new_spinner();
     pb.set_style(
-        ProgressStyle::default_bar()
-            .template(
-                "{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} ({eta})",
-            )
-            .unwrap()
-            .progress_chars("#>-"),
+        ProgressStyle::default_spinner()
+            .template("{spinner:.green} [{elapsed_precise}] {pos} documents {msg}")
+            .unwrap(),
     );
+    pb.enable_steady_tick(std::time::Duration::from_millis(100));
 
     let mut stats = IndexStats {
-        files_scanned: documents.len() as u64,
+        filler: config.indexing.filler,
         ..Default::default()
     };
 
-    let batch_size = config.embedding.batch_size as usize;
-    let mut pending_chunks = Vec::new();
-    let mut pending_texts = Vec::new();
-
-    for document in &documents {
-        pb.inc(1);
+    let (sender, pipeline) = IndexPipeline::new(
+        &embedding_client,
+        vector_store.as_ref(),
+        config.embedding.batch_size as usize,
+    );
+    let retry_notice = |message: String| pb.println(message);
+    let pipeline = pipeline
+        .with_dedup(config.indexing.dedup)
+        .with_redaction(config.indexing.redact_secrets)
+        .with_workspace(config.workspace().map(String::from))
+        .with_title_vectors(config.vector_store.title_vectors)
+        .with_max_retries(config.indexing.max_retries)
+        .with_retry_notice(&retry_notice);
+
+    let produce = async {
+        let sender = sender;
+        while let Some(document) = documents.next().await {
+            let mut document = document.context("failed to sync from external source")?;
+            pb.inc(1);
+            stats.files_scanned += 1;
+
+            if document.content.is_empty() {
+                stats.files_skipped += 1;
+                continue;
+            }
 
-        if document.content.is_empty() {
-            stats.files_skipped += 1;
-            continue;
+            document.add_template_tags(&tag_templates, &template_vars);
+            match screen_pii(config.indexing.pii, &mut document) {
+                PiiOutcome::Clean => {}
+                PiiOutcome::Flagged(_) => stats.files_pii_flagged += 1,
+                PiiOutcome::Skipped(_) => {
+                    // Drop any copy synced before the document gained personal data
+                    sender.replace(document.id.clone()).await?;
+                    stats.files_pii_skipped += 1;
+                    continue;
+                }
+            }
+            let (chunks, short) = chunker.chunk_screened(&document);
+            stats.chunks_short += short;
+            stats.chunks_created += chunks.len() as u64;
+            stats.files_indexed += 1;
+            pb.set_message(format!("({} chunks)", stats.chunks_created));
+
+            for chunk in chunks {
+                sender.send(chunk).await?;
+            }
         }
+        Ok::<(), anyhow::Error>(())
+    };
 
-        let chunks = chunker.chunk(document);
-        stats.chunks_created += chunks.len() as u64;
-        stats.files_indexed += 1;
-
-        for chunk in chunks {
-            pending_texts.push(chunk.content.clone());
-            pending_chunks.push(chunk);
-        }
+    let (produced, stored) = tokio::join!(produce, pipeline.run());
+    let stored = stored?;
+    produced?;
+    stats.chunks_deduplicated = stored.deduplicated;
+    stats.secrets_redacted = stored.redacted;
 
-        if pending_texts.len() >= batch_size {
-            process_batch(
-                &embedding_client,
-                vector_store.as_ref(),
-                &mut pending_chunks,
-                &mut pending_texts,
-            )
-            .await?;
-        }
-    }
+    pb.finish_and_clear();
 
-    if !pending_texts.is_empty() {
-        process_batch(
-            &embedding_client,
-            vector_store.as_ref(),
-            &mut pending_chunks,
-            &mut pending_texts,
-        )
-        .await?;
+    if stats.files_scanned == 0 {
+        println!(
+            "{}",
+            formatter.format_message("No documents found from source.")
+        );
+        return Ok(());
     }
 
-    pb.finish_and_clear();
     stats.duration_ms = start_time.elapsed().as_millis() as u64;
     print!("{}", formatter.format_index_stats(&stats));
 
@@ -312,7 +503,7 @@ async fn handle_sync(
 }
 
 async fn handle_delete(
-    formatter: &dyn crate::cli::output::Formatter,
+    formatter: &dyn Formatter,
     config: &Config,
     source: &str,
     force: bool,
@@ -12,19 +12,22 @@ pub async fn handle_status(format: OutputFormat, _verbose: bool) -> Result<()> {
     let client = DaemonClient::new(&config);
     let daemon_running = client.is_running();
 
-    let (daemon_status, embedding_model, idle_secs, metrics) = if daemon_running {
-        match client.status().await {
-            Ok(status) => (
-                true,
-                Some(status.embedding_model),
-                Some(status.idle_secs),
-                status.metrics,
-            ),
-            Err(_) => (false, None, None, None),
-        }
-    } else {
-        (false, None, None, None)
-    };
+    let (daemon_status, daemon_ready, embedding_model, loaded_models, idle_secs, metrics) =
+        if daemon_running {
+            match client.status().await {
+                Ok(status) => (
+                    true,
+                    status.ready,
+                    Some(status.embedding_model),
+                    status.loaded_models,
+                    Some(status.idle_secs),
+                    status.metrics,
+                ),
+                Err(_) => (false, false, None, Vec::new(), None, None),
+            }
+        } else {
+            (false, false, None, Vec::new(), None, None)
+        };
 
     let (vector_store_connected, vector_store_points) =
         if let Ok(store) = create_backend(&config.vector_store).await {
@@ -46,13 +49,15 @@ pub async fn handle_status(format: OutputFormat, _verbose: bool) -> Result<()> {
 
     let status = StatusInfo {
         daemon_running: daemon_status,
+        daemon_ready,
         daemon_idle_secs: idle_secs,
         embedding_model,
+        loaded_models,
         vector_store_driver: config.vector_store.driver.to_string(),
         vector_store_url: config.vector_store.url.clone(),
         vector_store_connected,
         vector_store_points,
-        collection: config.vector_store.collection.clone(),
+        collection: config.vector_store.scoped_collection(),
         metrics,
     };
 
@@ -2,7 +2,9 @@
 
 use anyhow::{Context, Result};
 use clap::Subcommand;
+use clap_complete::ArgValueCompleter;
 
+use crate::cli::completion::complete_tags;
 use crate::cli::output::get_formatter;
 use crate::models::{Config, OutputFormat, Tag};
 use crate::services::create_backend;
@@ -16,7 +18,7 @@ pub enum TagsCommand {
     /// Delete documents by tag
     Delete {
         /// Tag to delete (format: key:value)
-        #[arg(required = true)]
+        #[arg(required = true, add = ArgValueCompleter::new(complete_tags))]
         tag: String,
 
         /// Show what would be deleted without actually deleting
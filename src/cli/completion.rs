@@ -0,0 +1,156 @@
+//! Dynamic shell completion.
+//!
+//! Completion runs through `clap_complete`'s `COMPLETE=<shell> ssearch`
+//! protocol: the registration script printed by `ssearch completions <shell>`
+//! calls back into the binary on every TAB, so tag values can be looked up in
+//! the vector store.
+
+use std::ffi::OsStr;
+use std::time::Duration;
+
+use clap_complete::engine::CompletionCandidate;
+use clap_complete::env::Shells;
+
+use crate::models::{Config, SourceType};
+use crate::services::create_backend;
+
+/// Environment variable that switches the binary into completion mode.
+pub const COMPLETE_ENV: &str = "COMPLETE";
+
+/// Give up on the vector store so a TAB never hangs on an unreachable backend.
+const LOOKUP_TIMEOUT: Duration = Duration::from_secs(2);
+
+const BUILTIN_SOURCES: [SourceType; 9] = [
+    SourceType::Local,
+    SourceType::Jira,
+    SourceType::Confluence,
+    SourceType::Figma,
+    SourceType::GitHub,
+    SourceType::Slack,
+    SourceType::GoogleDrive,
+    SourceType::Mail,
+    SourceType::Web,
+];
+
+/// Print the script that registers dynamic completion for `shell`.
+pub fn write_registration(shell: &str, buf: &mut dyn std::io::Write) -> std::io::Result<()> {
+    let shells = Shells::builtins();
+    let completer = shells.completer(shell).ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("unsupported shell: {}", shell),
+        )
+    })?;
+    completer.write_registration(COMPLETE_ENV, "ssearch", "ssearch", "ssearch", buf)
+}
+
+/// Complete a comma-separated `key:value` tag list from the indexed tags.
+pub fn complete_tags(current: &OsStr) -> Vec<CompletionCandidate> {
+    let tags = stored_tags();
+    complete_list(
+        current,
+        tags.iter().map(|(tag, count)| (tag.as_str(), *count)),
+    )
+}
+
+/// Complete a comma-separated source type list from the built-in sources
+/// and `source:` tags in the index.
+pub fn complete_sources(current: &OsStr) -> Vec<CompletionCandidate> {
+    let tags = stored_tags();
+    let mut sources: Vec<String> = BUILTIN_SOURCES.iter().map(ToString::to_string).collect();
+    for (tag, _) in &tags {
+        if let Some(source) = tag.strip_prefix("source:")
+            && !sources.iter().any(|s| s == source)
+        {
+            sources.push(source.to_string());
+        }
+    }
+    complete_list(current, sources.iter().map(|s| (s.as_str(), 0)))
+}
+
+/// Candidates for the last item of a comma-separated list, skipping items
+/// already given. A non-zero count is shown as help.
+fn complete_list<'a>(
+    current: &OsStr,
+    values: impl Iterator<Item = (&'a str, u64)>,
+) -> Vec<CompletionCandidate> {
+    let current = current.to_string_lossy();
+    let (done, partial) = match current.rfind(',') {
+        Some(i) => current.split_at(i + 1),
+        None => ("", current.as_ref()),
+    };
+    let given: Vec<&str> = done.split(',').map(str::trim).collect();
+
+    values
+        .filter(|(value, _)| value.starts_with(partial) && !given.contains(value))
+        .map(|(value, count)| {
+            let help = (count > 0).then(|| format!("{} chunks", count).into());
+            CompletionCandidate::new(format!("{}{}", done, value)).help(help)
+        })
+        .collect()
+}
+
+/// Tags in the active collection, or none if the store can't be reached.
+fn stored_tags() -> Vec<(String, u64)> {
+    let Ok(resolved) = Config::load() else {
+        return Vec::new();
+    };
+    let config = resolved.config;
+
+    // Completion runs before the CLI's runtime is usable, so use a private one
+    std::thread::spawn(move || {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .ok()?;
+        runtime.block_on(async {
+            tokio::time::timeout(LOOKUP_TIMEOUT, async {
+                let store = create_backend(&config.vector_store).await.ok()?;
+                store.list_all_tags().await.ok()
+            })
+            .await
+            .ok()
+            .flatten()
+        })
+    })
+    .join()
+    .ok()
+    .flatten()
+    .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(candidates: Vec<CompletionCandidate>) -> Vec<String> {
+        candidates
+            .iter()
+            .map(|c| c.get_value().to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_complete_list() {
+        let tags = [("project:api", 10), ("project:web", 4), ("space:common", 2)];
+
+        let candidates = complete_list(OsStr::new("proj"), tags.into_iter());
+        assert_eq!(values(candidates), vec!["project:api", "project:web"]);
+
+        let candidates = complete_list(OsStr::new("project:api,"), tags.into_iter());
+        assert_eq!(
+            values(candidates),
+            vec!["project:api,project:web", "project:api,space:common"]
+        );
+
+        assert!(complete_list(OsStr::new("team"), tags.into_iter()).is_empty());
+    }
+
+    #[test]
+    fn test_write_registration() {
+        let mut buf = Vec::new();
+        write_registration("bash", &mut buf).unwrap();
+        assert!(String::from_utf8(buf).unwrap().contains("ssearch"));
+        assert!(write_registration("tcsh", &mut Vec::new()).is_err());
+    }
+}
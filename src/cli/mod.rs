@@ -1,9 +1,11 @@
 pub mod commands;
+pub mod completion;
+pub mod open;
 pub mod output;
 
 use clap::{Parser, Subcommand};
 
-use crate::models::OutputFormat;
+use crate::models::{LogFormat, OutputFormat};
 
 #[derive(Debug, Parser)]
 #[command(name = "ssearch")]
@@ -21,6 +23,23 @@ pub struct Cli {
     #[arg(long, short = 'v', global = true, help = "Enable verbose output")]
     pub verbose: bool,
 
+    #[arg(
+        long,
+        global = true,
+        env = "SSEARCH_LOG",
+        value_name = "LEVEL",
+        help = "Log level (error, warn, info, debug, trace) or filter directives like 'ssearch=debug,sqlx=info'"
+    )]
+    pub log_level: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "FORMAT",
+        help = "Log format: text or json (serve defaults to [daemon] log_format)"
+    )]
+    pub log_format: Option<LogFormat>,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -30,6 +49,9 @@ pub enum Commands {
     /// Check infrastructure status
     Status,
 
+    /// Run deep health checks (daemon, model dimension, vector store, disk, external CLIs)
+    Health,
+
     /// Manage search index (add, delete, clear)
     #[command(subcommand)]
     Index(commands::IndexCommand),
@@ -37,6 +59,18 @@ pub enum Commands {
     /// Search indexed content
     Search(commands::SearchArgs),
 
+    /// Answer a question from indexed content with an LLM, citing sources
+    Ask(commands::AskArgs),
+
+    /// Search a directory directly, indexing it in memory for this query only
+    Query(commands::QueryArgs),
+
+    /// Review recent searches and query statistics
+    History(commands::HistoryArgs),
+
+    /// Score search quality against labeled queries (recall@k, MRR, nDCG) and compare runs
+    Eval(commands::EvalArgs),
+
     /// Manage configuration
     #[command(subcommand)]
     Config(commands::ConfigCommand),
@@ -45,13 +79,105 @@ pub enum Commands {
     #[command(subcommand)]
     Tags(commands::TagsCommand),
 
-    /// Import data from JSON/JSONL files
+    /// Manage named collections (create, list, delete, use)
+    #[command(subcommand)]
+    Collection(commands::CollectionCommand),
+
+    /// Save searches under a name and run them again (add, list, remove, run)
+    #[command(subcommand)]
+    Alias(commands::AliasCommand),
+
+    /// Import data from JSON/JSONL files or web pages (--url)
     Import(commands::ImportArgs),
 
+    /// Export the indexed collection with embeddings to a JSONL file
+    Export(commands::ExportArgs),
+
     /// Manage external data sources
     #[command(subcommand)]
     Source(commands::SourceCommand),
 
     /// Manage ML daemon server
     Serve(commands::ServeArgs),
+
+    /// Print the shell completion script (e.g., `source <(ssearch completions zsh)`)
+    Completions {
+        /// Shell to complete for
+        shell: clap_complete::Shell,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_cli_definition() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_search_paging_args() {
+        let cli = Cli::try_parse_from(["ssearch", "search", "retry", "--page-size", "20"])
+            .expect("page size parses");
+        let Commands::Search(args) = cli.command else {
+            panic!("expected search");
+        };
+        assert_eq!(args.page_size, Some(20));
+        assert_eq!(args.offset, 0);
+
+        assert!(
+            Cli::try_parse_from(["ssearch", "search", "q", "-n", "5", "--page-size", "20"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_search_open_args() {
+        let open = |args: &[&str]| {
+            let cli = Cli::try_parse_from(args).expect("search parses");
+            let Commands::Search(args) = cli.command else {
+                panic!("expected search");
+            };
+            args.open
+        };
+        assert_eq!(open(&["ssearch", "search", "q", "--open=3"]), Some(3));
+        assert_eq!(open(&["ssearch", "search", "q", "--open"]), Some(0));
+        assert_eq!(open(&["ssearch", "search", "q"]), None);
+        // Without `=`, the next word stays the query
+        assert_eq!(open(&["ssearch", "search", "--open", "q"]), Some(0));
+    }
+
+    #[test]
+    fn test_search_batch_args() {
+        let cli = Cli::try_parse_from(["ssearch", "search", "--batch", "queries.txt", "-n", "3"])
+            .expect("batch parses without a query");
+        let Commands::Search(args) = cli.command else {
+            panic!("expected search");
+        };
+        assert_eq!(
+            args.batch.as_deref(),
+            Some(std::path::Path::new("queries.txt"))
+        );
+        assert_eq!(args.query, "");
+
+        assert!(Cli::try_parse_from(["ssearch", "search"]).is_err());
+        assert!(Cli::try_parse_from(["ssearch", "search", "q", "--batch", "queries.txt"]).is_err());
+        assert!(
+            Cli::try_parse_from(["ssearch", "search", "--batch", "queries.txt", "--rerank"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_index_add_stdin_args() {
+        assert!(
+            Cli::try_parse_from(["ssearch", "index", "add", "--stdin", "--name", "logs"]).is_ok()
+        );
+        // Paths are required without --stdin and refused with it
+        assert!(Cli::try_parse_from(["ssearch", "index", "add"]).is_err());
+        assert!(Cli::try_parse_from(["ssearch", "index", "add", ".", "--stdin"]).is_err());
+        assert!(Cli::try_parse_from(["ssearch", "index", "add", ".", "--name", "logs"]).is_err());
+    }
 }
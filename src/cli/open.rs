@@ -0,0 +1,176 @@
+//! Opening a search result's source (`search --open`): local files in the
+//! editor at the matched line, everything else in the browser.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{Context, Result, bail};
+
+use crate::models::{SearchResult, SourceType};
+
+/// Editors that take `path:line` instead of `+line path`.
+const COLON_LINE_EDITORS: &[&str] = &["subl", "zed", "hx", "helix"];
+
+/// Editors that take `--goto path:line`.
+const GOTO_EDITORS: &[&str] = &["code", "code-insiders", "codium", "cursor"];
+
+/// Where a result leads.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OpenTarget {
+    File { path: PathBuf, line: Option<u32> },
+    Url(String),
+}
+
+impl OpenTarget {
+    /// The file behind a local result, else the source URL; `None` when the
+    /// result has neither (e.g. text indexed from stdin).
+    pub fn for_result(result: &SearchResult) -> Option<Self> {
+        if result.source.source_type == SourceType::Local {
+            return Some(Self::File {
+                path: PathBuf::from(&result.source.location),
+                line: result.line_start,
+            });
+        }
+        result
+            .source
+            .url
+            .clone()
+            .or_else(|| {
+                let location = &result.source.location;
+                (location.starts_with("https://") || location.starts_with("http://"))
+                    .then(|| location.clone())
+            })
+            .map(Self::Url)
+    }
+
+    /// Open the target, waiting for a terminal editor to exit.
+    pub fn open(&self) -> Result<()> {
+        match self {
+            Self::File { path, line } => {
+                if !path.exists() {
+                    bail!("{} no longer exists", path.display());
+                }
+                let editor = std::env::var("EDITOR")
+                    .unwrap_or_else(|_| std::env::var("VISUAL").unwrap_or_else(|_| "vim".into()));
+                let mut words = editor.split_whitespace();
+                let program = words.next().context("$EDITOR is empty")?;
+                let mut args: Vec<String> = words.map(String::from).collect();
+                args.extend(editor_args(program, path, *line));
+                Command::new(program)
+                    .args(&args)
+                    .status()
+                    .with_context(|| format!("failed to open editor: {}", editor))?;
+            }
+            Self::Url(url) => {
+                let (program, args) = browser_command(url);
+                Command::new(&program)
+                    .args(&args)
+                    .status()
+                    .with_context(|| format!("failed to open browser: {}", program))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Arguments that open `path` at `line` in `editor`.
+fn editor_args(editor: &str, path: &Path, line: Option<u32>) -> Vec<String> {
+    let path = path.to_string_lossy().into_owned();
+    let Some(line) = line else {
+        return vec![path];
+    };
+    let name = Path::new(editor)
+        .file_name()
+        .map_or_else(|| editor.to_string(), |n| n.to_string_lossy().into_owned());
+    if GOTO_EDITORS.contains(&name.as_str()) {
+        vec!["--goto".to_string(), format!("{}:{}", path, line)]
+    } else if COLON_LINE_EDITORS.contains(&name.as_str()) {
+        vec![format!("{}:{}", path, line)]
+    } else {
+        vec![format!("+{}", line), path]
+    }
+}
+
+/// `$BROWSER`, else the platform's opener.
+fn browser_command(url: &str) -> (String, Vec<String>) {
+    if let Ok(browser) = std::env::var("BROWSER")
+        && !browser.trim().is_empty()
+    {
+        return (browser, vec![url.to_string()]);
+    }
+    if cfg!(target_os = "macos") {
+        ("open".to_string(), vec![url.to_string()])
+    } else if cfg!(windows) {
+        let args = ["/C", "start", "", url].map(String::from).to_vec();
+        ("cmd".to_string(), args)
+    } else {
+        ("xdg-open".to_string(), vec![url.to_string()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Source;
+
+    fn result(source: Source, line_start: Option<u32>) -> SearchResult {
+        SearchResult {
+            chunk_id: "c".to_string(),
+            document_id: "d".to_string(),
+            chunk_index: 0,
+            score: 0.9,
+            content: "content".to_string(),
+            location: source.location.clone(),
+            source,
+            tags: Vec::new(),
+            line_start,
+            line_end: None,
+            attributes: Default::default(),
+            updated_at: None,
+            context_before: Vec::new(),
+            context_after: Vec::new(),
+            explanation: None,
+            keyword_match: false,
+        }
+    }
+
+    #[test]
+    fn test_open_targets() {
+        let local = result(Source::local("/src/lib.rs"), Some(42));
+        assert_eq!(
+            OpenTarget::for_result(&local),
+            Some(OpenTarget::File {
+                path: PathBuf::from("/src/lib.rs"),
+                line: Some(42),
+            })
+        );
+
+        let url = "https://wiki.example.com/pages/1";
+        let page = result(
+            Source::new(SourceType::Confluence, "page-1", Some(url.to_string())),
+            None,
+        );
+        assert_eq!(
+            OpenTarget::for_result(&page),
+            Some(OpenTarget::Url(url.to_string()))
+        );
+
+        let stdin = result(
+            Source::new(SourceType::Other("stdin".into()), "stdin:logs", None),
+            None,
+        );
+        assert_eq!(OpenTarget::for_result(&stdin), None);
+    }
+
+    #[test]
+    fn test_editor_args() {
+        let path = Path::new("/src/lib.rs");
+        assert_eq!(editor_args("vim", path, Some(7)), vec!["+7", "/src/lib.rs"]);
+        assert_eq!(
+            editor_args("/usr/bin/code", path, Some(7)),
+            vec!["--goto", "/src/lib.rs:7"]
+        );
+        assert_eq!(editor_args("zed", path, Some(7)), vec!["/src/lib.rs:7"]);
+        assert_eq!(editor_args("nano", path, None), vec!["/src/lib.rs"]);
+    }
+}
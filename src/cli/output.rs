@@ -1,15 +1,39 @@
+use std::collections::BTreeMap;
 use std::fmt::Write as FmtWrite;
 
-use crate::models::{OutputFormat, SearchResults};
-use crate::services::MetricsSummary;
+use console::style;
+
+use crate::models::{
+    Answer, DocumentChunk, FillerPolicy, LANGUAGE_ATTRIBUTE, OutputFormat, RELATIVE_PATH_ATTRIBUTE,
+    RankedScore, SavedQuery, ScoreExplanation, SearchResult, SearchResults, SourceType,
+};
+use crate::services::snippet::Snippet;
+use crate::services::{
+    CheckStatus, DaemonStats, EvalRun, HealthReport, IndexInventory, MetricsSummary, QueryStats,
+    SearchHistoryEntry, SearchStats, StatsPeriod, estimate_tokens,
+};
+
+/// Length of the content snippet shown for each search result, in characters.
+const SNIPPET_CHARS: usize = 200;
 
 pub trait Formatter {
     fn format_search_results(&self, results: &SearchResults) -> String;
+    fn format_batch_results(&self, batch: &[SearchResults]) -> String;
+    fn format_answer(&self, answer: &Answer) -> String;
     fn format_status(&self, status: &StatusInfo) -> String;
+    fn format_health(&self, report: &HealthReport) -> String;
+    fn format_eval(&self, run: &EvalRun, baseline: Option<&EvalRun>) -> String;
     fn format_index_stats(&self, stats: &IndexStats) -> String;
     fn format_tags(&self, tags: &[(String, u64)]) -> String;
+    fn format_aliases(&self, aliases: &BTreeMap<String, SavedQuery>) -> String;
+    fn format_inventory(&self, collection: &str, inventory: &IndexInventory) -> String;
+    fn format_collections(&self, collections: &[CollectionSummary]) -> String;
     fn format_sources(&self, sources: &[SourceInfo]) -> String;
     fn format_cli_status(&self, clis: &[CliInfo]) -> String;
+    fn format_search_history(&self, entries: &[SearchHistoryEntry]) -> String;
+    fn format_search_stats(&self, stats: &SearchStats) -> String;
+    fn format_daemon_stats(&self, stats: &DaemonStats) -> String;
+    fn format_document_chunks(&self, chunks: &[DocumentChunk], show_content: bool) -> String;
     fn format_message(&self, message: &str) -> String;
     fn format_error(&self, error: &str) -> String;
 }
@@ -17,8 +41,12 @@ pub trait Formatter {
 #[derive(Debug, Clone)]
 pub struct StatusInfo {
     pub daemon_running: bool,
+    /// Models warmed up; false while a freshly started daemon warms up
+    pub daemon_ready: bool,
     pub daemon_idle_secs: Option<u64>,
     pub embedding_model: Option<String>,
+    /// Collection-bound models the daemon has loaded besides the default
+    pub loaded_models: Vec<String>,
     pub vector_store_driver: String,
     pub vector_store_url: String,
     pub vector_store_connected: bool,
@@ -32,10 +60,31 @@ pub struct IndexStats {
     pub files_scanned: u64,
     pub files_indexed: u64,
     pub files_skipped: u64,
+    pub files_unchanged: u64,
+    pub files_removed: u64,
+    pub files_pii_flagged: u64,
+    pub files_pii_skipped: u64,
     pub chunks_created: u64,
+    pub chunks_deduplicated: u64,
+    /// Chunks under `[indexing] min_chunk_length`, skipped or flagged per `filler`
+    pub chunks_short: u64,
+    /// Chunks repeated across `[indexing] boilerplate_min_files` files
+    pub chunks_boilerplate: u64,
+    pub filler: FillerPolicy,
+    pub secrets_redacted: u64,
     pub duration_ms: u64,
 }
 
+impl IndexStats {
+    /// `skipped` or `flagged`, for the filler chunk counts.
+    fn filler_outcome(&self) -> &'static str {
+        match self.filler {
+            FillerPolicy::Skip => "skipped",
+            FillerPolicy::Flag => "flagged",
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SourceInfo {
     pub name: String,
@@ -43,6 +92,16 @@ pub struct SourceInfo {
     pub available: bool,
 }
 
+#[derive(Debug, Clone)]
+pub struct CollectionSummary {
+    pub name: String,
+    pub active: bool,
+    pub model_id: String,
+    pub dimension: u32,
+    /// `None` when the collection hasn't been created in the vector store
+    pub points: Option<u64>,
+}
+
 #[derive(Debug, Clone)]
 pub struct CliInfo {
     pub name: String,
@@ -51,9 +110,163 @@ pub struct CliInfo {
     pub version: Option<String>,
 }
 
+/// A search written out by `search --report`.
+#[derive(Debug, Clone)]
+pub struct SearchReport<'a> {
+    pub results: &'a SearchResults,
+    /// Filters and modes in effect, e.g. `Tags: team:a`
+    pub filters: Vec<String>,
+    pub collection: String,
+    pub generated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Human-readable byte size, e.g. `12.3 MB`.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+fn format_time(time: Option<chrono::DateTime<chrono::Utc>>) -> String {
+    time.map_or_else(
+        || "-".to_string(),
+        |t| t.format("%Y-%m-%d %H:%M UTC").to_string(),
+    )
+}
+
+/// Share of `part` in `total` as a percentage.
+fn percent(part: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 * 100.0 / total as f64
+    }
+}
+
+fn format_batch(batch: Option<f64>) -> String {
+    batch.map_or_else(|| "-".to_string(), |b| format!("{:.1}", b))
+}
+
+fn period_label(stats: &DaemonStats) -> &'static str {
+    match stats.period {
+        StatsPeriod::Daily => "daily",
+        StatsPeriod::Weekly => "weekly",
+    }
+}
+
+/// Line range of a chunk, e.g. `10-42`.
+fn format_lines(chunk: &DocumentChunk) -> String {
+    match (chunk.line_start, chunk.line_end) {
+        (Some(start), Some(end)) if end > start => format!("{}-{}", start, end),
+        (Some(start), _) => start.to_string(),
+        _ => "-".to_string(),
+    }
+}
+
+/// First and last non-blank lines of a chunk, where its boundaries fall.
+fn chunk_edges(content: &str) -> (String, Option<String>) {
+    let clip = |line: &str| {
+        let line = line.trim();
+        match line.char_indices().nth(SNIPPET_CHARS / 2) {
+            Some((i, _)) => format!("{}...", &line[..i]),
+            None => line.to_string(),
+        }
+    };
+    let mut lines = content.lines().filter(|l| !l.trim().is_empty());
+    let first = lines.next().map(clip).unwrap_or_default();
+    (first, lines.next_back().map(clip))
+}
+
+/// Human-readable lines of a `--explain` score breakdown.
+fn explain_lines(explanation: &ScoreExplanation) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut raw = format!("Raw score: {}", format_score(explanation.raw_score));
+    if let Some(percentile) = explanation.percentile {
+        write!(raw, " (p{:.0} of recent searches)", percentile).unwrap();
+    }
+    lines.push(raw);
+    // Only hybrid searches carry a per-ranking breakdown
+    if explanation.dense.is_some() || explanation.keyword.is_some() {
+        let rank = |r: Option<RankedScore>| {
+            r.map_or_else(
+                || "-".to_string(),
+                |r| format!("#{} ({:.3})", r.rank, r.score),
+            )
+        };
+        lines.push(format!(
+            "Dense: {}, Keyword: {}",
+            rank(explanation.dense),
+            rank(explanation.keyword)
+        ));
+    }
+    if !explanation.matched_tags.is_empty() {
+        let tags: Vec<String> = explanation
+            .matched_tags
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        lines.push(format!("Matched filters: {}", tags.join(", ")));
+    }
+    lines
+}
+
+/// An eval run's mean scores, labeled by metric.
+fn eval_metrics(run: &EvalRun) -> [(String, f64); 3] {
+    [
+        (format!("recall@{}", run.k), run.recall),
+        ("MRR".to_string(), run.mrr),
+        (format!("nDCG@{}", run.k), run.ndcg),
+    ]
+}
+
+/// What produced an eval run: collection, model and search modes.
+fn eval_setup(run: &EvalRun) -> String {
+    let mut setup = format!("{}, {}", run.collection, run.model_id);
+    for mode in &run.modes {
+        write!(setup, ", {}", mode).unwrap();
+    }
+    setup
+}
+
+/// A saved search's options as they'd be typed, values with spaces quoted.
+fn format_alias_options(alias: &SavedQuery) -> String {
+    alias
+        .search_options()
+        .into_iter()
+        .map(|arg| {
+            if arg.contains(char::is_whitespace) {
+                format!("'{}'", arg)
+            } else {
+                arg
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn format_score(score: Option<f32>) -> String {
+    score.map_or_else(|| "-".to_string(), |s| format!("{:.3}", s))
+}
+
 pub struct TextFormatter;
 
 impl Formatter for TextFormatter {
+    fn format_batch_results(&self, batch: &[SearchResults]) -> String {
+        batch
+            .iter()
+            .map(|results| self.format_search_results(results))
+            .collect::<Vec<_>>()
+            .join(&format!("{}\n", "=".repeat(60)))
+    }
+
     fn format_search_results(&self, results: &SearchResults) -> String {
         if results.is_empty() {
             return format!("No results found for: {}\n", results.query);
@@ -69,22 +282,60 @@ impl Formatter for TextFormatter {
         .unwrap();
 
         for (i, result) in results.results.iter().enumerate() {
-            writeln!(output, "{}. [Score: {:.3}]", i + 1, result.score).unwrap();
-            writeln!(output, "   Location: {}", result.location).unwrap();
+            writeln!(
+                output,
+                "{}. [Score: {:.3}{}]",
+                results.offset + i as u64 + 1,
+                result.score,
+                if result.keyword_match {
+                    ", keyword match"
+                } else {
+                    ""
+                }
+            )
+            .unwrap();
+            match result.symbol() {
+                Some(symbol) => {
+                    writeln!(output, "   Location: {} (in {})", result.location, symbol).unwrap()
+                }
+                None => writeln!(output, "   Location: {}", result.location).unwrap(),
+            }
             if !result.tags.is_empty() {
                 let tags: Vec<String> = result.tags.iter().map(ToString::to_string).collect();
                 writeln!(output, "   Tags: {}", tags.join(", ")).unwrap();
             }
+            if let Some(ref explanation) = result.explanation {
+                for line in explain_lines(explanation) {
+                    writeln!(output, "   {}", line).unwrap();
+                }
+            }
             writeln!(output, "   ---").unwrap();
 
-            let preview: String = result.content.chars().take(200).collect();
-            let preview = if result.content.chars().count() > 200 {
-                format!("{}...", preview)
+            // Matched terms are styled only when stdout is a color terminal
+            let highlight = |term: &str| style(term).bold().yellow().to_string();
+            if result.context_before.is_empty() && result.context_after.is_empty() {
+                let preview = Snippet::extract(&result.content, &results.query, SNIPPET_CHARS)
+                    .render(highlight);
+                for line in preview.lines() {
+                    writeln!(output, "   {}", line).unwrap();
+                }
             } else {
-                preview
-            };
-            for line in preview.lines() {
-                writeln!(output, "   {}", line).unwrap();
+                // With --context, show the whole passage and dim the neighbors
+                for chunk in &result.context_before {
+                    for line in chunk.lines() {
+                        writeln!(output, "   {}", style(line).dim()).unwrap();
+                    }
+                }
+                let content =
+                    Snippet::extract(&result.content, &results.query, usize::MAX).render(highlight);
+                for line in content.lines() {
+                    writeln!(output, "   {}", line).unwrap();
+                }
+                for chunk in &result.context_after {
+                    for line in chunk.lines() {
+                        writeln!(output, "   {}", style(line).dim()).unwrap();
+                    }
+                }
             }
             writeln!(output).unwrap();
         }
@@ -92,15 +343,32 @@ impl Formatter for TextFormatter {
         output
     }
 
+    fn format_answer(&self, answer: &Answer) -> String {
+        let mut output = String::new();
+        writeln!(output, "{}\n", answer.answer).unwrap();
+        writeln!(output, "Sources:").unwrap();
+        for (i, source) in answer.sources.iter().enumerate() {
+            writeln!(
+                output,
+                "  [{}] {} ({:.3})",
+                i + 1,
+                source.citation(),
+                source.score
+            )
+            .unwrap();
+        }
+        output
+    }
+
     fn format_status(&self, status: &StatusInfo) -> String {
         let mut output = String::new();
         writeln!(output, "Status").unwrap();
         writeln!(output, "------").unwrap();
 
-        let daemon_status = if status.daemon_running {
-            "[RUNNING]"
-        } else {
-            "[STOPPED]"
+        let daemon_status = match (status.daemon_running, status.daemon_ready) {
+            (true, true) => "[RUNNING]",
+            (true, false) => "[WARMING UP]",
+            (false, _) => "[STOPPED]",
         };
         writeln!(output, "ML Daemon:     {}", daemon_status).unwrap();
 
@@ -108,6 +376,9 @@ impl Formatter for TextFormatter {
             if let Some(ref model) = status.embedding_model {
                 writeln!(output, "  Embedding:   {}", model).unwrap();
             }
+            for model in &status.loaded_models {
+                writeln!(output, "  Also Loaded: {}", model).unwrap();
+            }
             if let Some(idle) = status.daemon_idle_secs {
                 writeln!(output, "  Idle:        {}s", idle).unwrap();
             }
@@ -141,6 +412,117 @@ impl Formatter for TextFormatter {
         output
     }
 
+    fn format_health(&self, report: &HealthReport) -> String {
+        let mut output = String::new();
+        writeln!(output, "Health").unwrap();
+        writeln!(output, "------").unwrap();
+        let width = report
+            .checks
+            .iter()
+            .map(|check| check.name.len())
+            .max()
+            .unwrap_or(0);
+        for check in &report.checks {
+            writeln!(
+                output,
+                "[{}] {:<width$}  {}",
+                check.status.to_string().to_uppercase(),
+                check.name,
+                check.message
+            )
+            .unwrap();
+        }
+        writeln!(output).unwrap();
+        writeln!(
+            output,
+            "Overall: {}",
+            report.status().to_string().to_uppercase()
+        )
+        .unwrap();
+        output
+    }
+
+    fn format_eval(&self, run: &EvalRun, baseline: Option<&EvalRun>) -> String {
+        let mut output = String::new();
+        writeln!(
+            output,
+            "Evaluation: {} queries, k={} ({})",
+            run.queries.len(),
+            run.k,
+            eval_setup(run)
+        )
+        .unwrap();
+        writeln!(output, "----------").unwrap();
+        let baseline_metrics = baseline.map(eval_metrics);
+        for (i, (name, value)) in eval_metrics(run).into_iter().enumerate() {
+            match baseline_metrics {
+                Some(ref before) => {
+                    let change = value - before[i].1;
+                    let change = if change > 0.0 {
+                        style(format!("{:+.3}", change)).green()
+                    } else if change < 0.0 {
+                        style(format!("{:+.3}", change)).red()
+                    } else {
+                        style(format!("{:+.3}", change)).dim()
+                    };
+                    writeln!(
+                        output,
+                        "  {:<10} {:.3}  (baseline {:.3}, {})",
+                        name, value, before[i].1, change
+                    )
+                    .unwrap()
+                }
+                None => writeln!(output, "  {:<10} {:.3}", name, value).unwrap(),
+            }
+        }
+
+        writeln!(output, "\nQueries").unwrap();
+        for query in &run.queries {
+            writeln!(
+                output,
+                "  {:.3} nDCG  {:.2} recall  {:.2} RR  \"{}\"",
+                query.ndcg, query.recall, query.reciprocal_rank, query.query
+            )
+            .unwrap();
+            if !query.missed.is_empty() {
+                writeln!(
+                    output,
+                    "        {}",
+                    style(format!("missed: {}", query.missed.join(", "))).dim()
+                )
+                .unwrap();
+            }
+        }
+
+        if let Some(baseline) = baseline {
+            let deltas = run.compare(baseline);
+            let changed: Vec<_> = deltas.iter().filter(|d| d.change() != 0.0).collect();
+            writeln!(
+                output,
+                "\nChanges vs baseline ({}, {})",
+                baseline.created_at.format("%Y-%m-%d %H:%M"),
+                eval_setup(baseline)
+            )
+            .unwrap();
+            for delta in &changed {
+                let change = format!("{:+.3}", delta.change());
+                let change = if delta.change() > 0.0 {
+                    style(change).green()
+                } else {
+                    style(change).red()
+                };
+                writeln!(
+                    output,
+                    "  {}  {:.3} -> {:.3}  \"{}\"",
+                    change, delta.before, delta.after, delta.query
+                )
+                .unwrap();
+            }
+            writeln!(output, "  {} unchanged", deltas.len() - changed.len()).unwrap();
+        }
+        output
+    }
+
     fn format_index_stats(&self, stats: &IndexStats) -> String {
         let mut output = String::new();
         writeln!(output, "Indexing Complete").unwrap();
@@ -148,7 +530,43 @@ impl Formatter for TextFormatter {
         writeln!(output, "Files scanned: {}", stats.files_scanned).unwrap();
         writeln!(output, "Files indexed: {}", stats.files_indexed).unwrap();
         writeln!(output, "Files skipped: {}", stats.files_skipped).unwrap();
+        if stats.files_unchanged > 0 {
+            writeln!(output, "Files unchanged: {}", stats.files_unchanged).unwrap();
+        }
+        if stats.files_removed > 0 {
+            writeln!(output, "Files removed: {}", stats.files_removed).unwrap();
+        }
+        if stats.files_pii_flagged > 0 {
+            writeln!(output, "Files flagged for PII: {}", stats.files_pii_flagged).unwrap();
+        }
+        if stats.files_pii_skipped > 0 {
+            writeln!(output, "Files skipped for PII: {}", stats.files_pii_skipped).unwrap();
+        }
         writeln!(output, "Chunks created: {}", stats.chunks_created).unwrap();
+        if stats.chunks_deduplicated > 0 {
+            writeln!(
+                output,
+                "Duplicate chunks skipped: {}",
+                stats.chunks_deduplicated
+            )
+            .unwrap();
+        }
+        if stats.chunks_short > 0 {
+            let outcome = stats.filler_outcome();
+            writeln!(output, "Short chunks {}: {}", outcome, stats.chunks_short).unwrap();
+        }
+        if stats.chunks_boilerplate > 0 {
+            let outcome = stats.filler_outcome();
+            writeln!(
+                output,
+                "Boilerplate chunks {}: {}",
+                outcome, stats.chunks_boilerplate
+            )
+            .unwrap();
+        }
+        if stats.secrets_redacted > 0 {
+            writeln!(output, "Secrets redacted: {}", stats.secrets_redacted).unwrap();
+        }
         writeln!(output, "Duration: {}ms", stats.duration_ms).unwrap();
         output
     }
@@ -167,6 +585,88 @@ impl Formatter for TextFormatter {
         output
     }
 
+    fn format_aliases(&self, aliases: &BTreeMap<String, SavedQuery>) -> String {
+        if aliases.is_empty() {
+            return "No aliases saved.\n".to_string();
+        }
+
+        let mut output = String::new();
+        writeln!(output, "Aliases").unwrap();
+        writeln!(output, "-------").unwrap();
+        for (name, alias) in aliases {
+            let options = format_alias_options(alias);
+            if options.is_empty() {
+                writeln!(output, "  {}: \"{}\"", name, alias.query).unwrap();
+            } else {
+                writeln!(output, "  {}: \"{}\" {}", name, alias.query, options).unwrap();
+            }
+        }
+        output
+    }
+
+    fn format_inventory(&self, collection: &str, inventory: &IndexInventory) -> String {
+        let mut output = String::new();
+        writeln!(output, "Index Inventory").unwrap();
+        writeln!(output, "---------------").unwrap();
+        writeln!(output, "Collection:    {}", collection).unwrap();
+        writeln!(output, "Documents:     {}", inventory.total_documents()).unwrap();
+        writeln!(output, "Chunks:        {}", inventory.total_chunks()).unwrap();
+        writeln!(
+            output,
+            "Storage:       ~{}",
+            format_bytes(inventory.storage_bytes)
+        )
+        .unwrap();
+        writeln!(
+            output,
+            "Last indexed:  {}",
+            format_time(inventory.last_indexed())
+        )
+        .unwrap();
+
+        if !inventory.sources.is_empty() {
+            writeln!(output, "\nSources").unwrap();
+            for s in &inventory.sources {
+                writeln!(
+                    output,
+                    "  {:<12} {} documents, {} chunks, last indexed {}",
+                    s.source_type,
+                    s.documents,
+                    s.chunks,
+                    format_time(s.last_indexed)
+                )
+                .unwrap();
+            }
+        }
+
+        if !inventory.tags.is_empty() {
+            writeln!(output, "\nTags").unwrap();
+            for (tag, documents) in &inventory.tags {
+                writeln!(output, "  {} ({} documents)", tag, documents).unwrap();
+            }
+        }
+        output
+    }
+
+    fn format_collections(&self, collections: &[CollectionSummary]) -> String {
+        let mut output = String::new();
+        writeln!(output, "Collections").unwrap();
+        writeln!(output, "-----------").unwrap();
+        for c in collections {
+            let marker = if c.active { "*" } else { " " };
+            let points = c
+                .points
+                .map_or_else(|| "not created".to_string(), |n| format!("{} points", n));
+            writeln!(
+                output,
+                "{} {} ({}) - {} [{}d]",
+                marker, c.name, points, c.model_id, c.dimension
+            )
+            .unwrap();
+        }
+        output
+    }
+
     fn format_sources(&self, sources: &[SourceInfo]) -> String {
         let mut output = String::new();
         writeln!(output, "Available Data Sources").unwrap();
@@ -196,6 +696,160 @@ impl Formatter for TextFormatter {
         output
     }
 
+    fn format_search_history(&self, entries: &[SearchHistoryEntry]) -> String {
+        if entries.is_empty() {
+            return "No searches recorded.\n".to_string();
+        }
+
+        let mut output = String::new();
+        writeln!(output, "Search History").unwrap();
+        writeln!(output, "--------------").unwrap();
+        for entry in entries {
+            let search = &entry.search;
+            writeln!(
+                output,
+                "{}  {:>3} results  top {:>5}  {:>5}ms  \"{}\"",
+                entry.timestamp,
+                search.result_count,
+                format_score(search.top_score),
+                search.duration_ms,
+                search.query
+            )
+            .unwrap();
+            if let Some(ref filters) = search.filters {
+                writeln!(output, "{:>21}filters: {}", "", filters).unwrap();
+            }
+        }
+        output
+    }
+
+    fn format_search_stats(&self, stats: &SearchStats) -> String {
+        let mut output = String::new();
+        writeln!(output, "Search Statistics (last {} days)", stats.days).unwrap();
+        writeln!(output, "-------------------------------").unwrap();
+        writeln!(output, "Searches:      {}", stats.total_searches).unwrap();
+        writeln!(
+            output,
+            "Zero results:  {} ({:.1}%)",
+            stats.zero_result_searches,
+            percent(stats.zero_result_searches, stats.total_searches)
+        )
+        .unwrap();
+        writeln!(
+            output,
+            "Poor results:  {} ({:.1}%, top score < {})",
+            stats.poor_result_searches,
+            percent(stats.poor_result_searches, stats.total_searches),
+            stats.poor_score
+        )
+        .unwrap();
+        writeln!(output, "Avg duration:  {}ms", stats.avg_duration_ms).unwrap();
+
+        let sections: [(&str, &[QueryStats]); 3] = [
+            ("Top queries", &stats.top_queries),
+            ("Zero-result queries", &stats.zero_result_queries),
+            ("Poor queries", &stats.poor_queries),
+        ];
+        for (title, queries) in sections {
+            if queries.is_empty() {
+                continue;
+            }
+            writeln!(output, "\n{}:", title).unwrap();
+            for query in queries {
+                writeln!(
+                    output,
+                    "  {:>4}x  top {:>5}  \"{}\"",
+                    query.count,
+                    format_score(query.avg_top_score),
+                    query.query
+                )
+                .unwrap();
+            }
+        }
+        output
+    }
+
+    fn format_document_chunks(&self, chunks: &[DocumentChunk], show_content: bool) -> String {
+        let Some(first) = chunks.first() else {
+            return "No indexed chunks found for this document.\n".to_string();
+        };
+
+        let mut output = String::new();
+        writeln!(output, "Document Chunks").unwrap();
+        writeln!(output, "---------------").unwrap();
+        writeln!(output, "Location:  {}", first.source.location).unwrap();
+        writeln!(output, "Document:  {}", first.document_id).unwrap();
+        writeln!(output, "Chunks:    {}", chunks.len()).unwrap();
+
+        for chunk in chunks {
+            write!(
+                output,
+                "\n  #{:<3} lines {:<11} ~{} tokens, {} chars",
+                chunk.chunk_index,
+                format_lines(chunk),
+                estimate_tokens(&chunk.content),
+                chunk.content.chars().count()
+            )
+            .unwrap();
+            if let Some(symbol) = chunk.symbol() {
+                write!(output, "  ({})", symbol).unwrap();
+            }
+            writeln!(output).unwrap();
+
+            if show_content {
+                for line in chunk.content.lines() {
+                    writeln!(output, "       | {}", line).unwrap();
+                }
+            } else {
+                let (head, tail) = chunk_edges(&chunk.content);
+                writeln!(output, "       | {}", head).unwrap();
+                if let Some(tail) = tail {
+                    writeln!(output, "       | ...").unwrap();
+                    writeln!(output, "       | {}", tail).unwrap();
+                }
+            }
+        }
+        output
+    }
+
+    fn format_daemon_stats(&self, stats: &DaemonStats) -> String {
+        if stats.rows.is_empty() {
+            return format!("No daemon requests in the last {} days.\n", stats.days);
+        }
+
+        let mut output = String::new();
+        writeln!(
+            output,
+            "Daemon Statistics (last {} days, {})",
+            stats.days,
+            period_label(stats)
+        )
+        .unwrap();
+        writeln!(output, "----------------------------------------").unwrap();
+        writeln!(
+            output,
+            "{:<10}  {:<6}  {:>8}  {:>7}  {:>6}  {:>7}  {:>7}  {:>7}",
+            "Period", "Kind", "Requests", "Success", "Batch", "p50", "p95", "p99"
+        )
+        .unwrap();
+        for row in &stats.rows {
+            writeln!(
+                output,
+                "{:<10}  {:<6}  {:>8}  {:>6.1}%  {:>6}  {:>5}ms  {:>5}ms  {:>5}ms",
+                row.period,
+                row.kind,
+                row.requests,
+                row.success_rate,
+                format_batch(row.avg_batch_size),
+                row.p50_ms,
+                row.p95_ms,
+                row.p99_ms
+            )
+            .unwrap();
+        }
+        output
+    }
+
     fn format_message(&self, message: &str) -> String {
         format!("{}\n", message)
     }
@@ -225,6 +879,24 @@ impl Formatter for JsonFormatter {
         }
     }
 
+    fn format_batch_results(&self, batch: &[SearchResults]) -> String {
+        let json = serde_json::json!({"queries": batch});
+
+        if self.pretty {
+            serde_json::to_string_pretty(&json).unwrap()
+        } else {
+            serde_json::to_string(&json).unwrap()
+        }
+    }
+
+    fn format_answer(&self, answer: &Answer) -> String {
+        if self.pretty {
+            serde_json::to_string_pretty(answer).unwrap()
+        } else {
+            serde_json::to_string(answer).unwrap()
+        }
+    }
+
     fn format_status(&self, status: &StatusInfo) -> String {
         let metrics = status.metrics.as_ref().map(|m| {
             serde_json::json!({
@@ -237,8 +909,10 @@ impl Formatter for JsonFormatter {
         let json = serde_json::json!({
             "daemon": {
                 "running": status.daemon_running,
+                "ready": status.daemon_running && status.daemon_ready,
                 "idle_secs": status.daemon_idle_secs,
                 "embedding_model": status.embedding_model,
+                "loaded_models": status.loaded_models,
                 "metrics": metrics,
             },
             "vector_store": {
@@ -257,12 +931,56 @@ impl Formatter for JsonFormatter {
         }
     }
 
-    fn format_index_stats(&self, stats: &IndexStats) -> String {
+    fn format_health(&self, report: &HealthReport) -> String {
         let json = serde_json::json!({
-            "files_scanned": stats.files_scanned,
-            "files_indexed": stats.files_indexed,
+            "status": report.status(),
+            "checks": report.checks,
+        });
+
+        if self.pretty {
+            serde_json::to_string_pretty(&json).unwrap()
+        } else {
+            serde_json::to_string(&json).unwrap()
+        }
+    }
+
+    fn format_eval(&self, run: &EvalRun, baseline: Option<&EvalRun>) -> String {
+        let mut json = serde_json::json!({"run": run});
+        if let Some(baseline) = baseline {
+            json["baseline"] = serde_json::json!({
+                "created_at": baseline.created_at,
+                "collection": baseline.collection,
+                "model_id": baseline.model_id,
+                "modes": baseline.modes,
+                "recall": baseline.recall,
+                "mrr": baseline.mrr,
+                "ndcg": baseline.ndcg,
+            });
+            json["changes"] = serde_json::json!(run.compare(baseline));
+        }
+
+        if self.pretty {
+            serde_json::to_string_pretty(&json).unwrap()
+        } else {
+            serde_json::to_string(&json).unwrap()
+        }
+    }
+
+    fn format_index_stats(&self, stats: &IndexStats) -> String {
+        let json = serde_json::json!({
+            "files_scanned": stats.files_scanned,
+            "files_indexed": stats.files_indexed,
             "files_skipped": stats.files_skipped,
+            "files_unchanged": stats.files_unchanged,
+            "files_removed": stats.files_removed,
+            "files_pii_flagged": stats.files_pii_flagged,
+            "files_pii_skipped": stats.files_pii_skipped,
             "chunks_created": stats.chunks_created,
+            "chunks_deduplicated": stats.chunks_deduplicated,
+            "chunks_short": stats.chunks_short,
+            "chunks_boilerplate": stats.chunks_boilerplate,
+            "filler": stats.filler.to_string(),
+            "secrets_redacted": stats.secrets_redacted,
             "duration_ms": stats.duration_ms,
         });
 
@@ -288,6 +1006,75 @@ impl Formatter for JsonFormatter {
         }
     }
 
+    fn format_aliases(&self, aliases: &BTreeMap<String, SavedQuery>) -> String {
+        let json = serde_json::json!({"aliases": aliases});
+
+        if self.pretty {
+            serde_json::to_string_pretty(&json).unwrap()
+        } else {
+            serde_json::to_string(&json).unwrap()
+        }
+    }
+
+    fn format_inventory(&self, collection: &str, inventory: &IndexInventory) -> String {
+        let sources: Vec<serde_json::Value> = inventory
+            .sources
+            .iter()
+            .map(|s| {
+                serde_json::json!({
+                    "source_type": s.source_type,
+                    "documents": s.documents,
+                    "chunks": s.chunks,
+                    "last_indexed": s.last_indexed,
+                })
+            })
+            .collect();
+        let tags: Vec<serde_json::Value> = inventory
+            .tags
+            .iter()
+            .map(|(tag, documents)| serde_json::json!({"tag": tag, "documents": documents}))
+            .collect();
+
+        let json = serde_json::json!({
+            "collection": collection,
+            "documents": inventory.total_documents(),
+            "chunks": inventory.total_chunks(),
+            "storage_bytes": inventory.storage_bytes,
+            "last_indexed": inventory.last_indexed(),
+            "sources": sources,
+            "tags": tags,
+        });
+
+        if self.pretty {
+            serde_json::to_string_pretty(&json).unwrap()
+        } else {
+            serde_json::to_string(&json).unwrap()
+        }
+    }
+
+    fn format_collections(&self, collections: &[CollectionSummary]) -> String {
+        let collections_array: Vec<serde_json::Value> = collections
+            .iter()
+            .map(|c| {
+                serde_json::json!({
+                    "name": c.name,
+                    "active": c.active,
+                    "model_id": c.model_id,
+                    "dimension": c.dimension,
+                    "points": c.points,
+                })
+            })
+            .collect();
+
+        let json = serde_json::json!({"collections": collections_array});
+
+        if self.pretty {
+            serde_json::to_string_pretty(&json).unwrap()
+        } else {
+            serde_json::to_string(&json).unwrap()
+        }
+    }
+
     fn format_sources(&self, sources: &[SourceInfo]) -> String {
         let sources_array: Vec<serde_json::Value> = sources
             .iter()
@@ -331,6 +1118,64 @@ impl Formatter for JsonFormatter {
         }
     }
 
+    fn format_search_history(&self, entries: &[SearchHistoryEntry]) -> String {
+        let json = serde_json::json!({"searches": entries});
+        if self.pretty {
+            serde_json::to_string_pretty(&json).unwrap()
+        } else {
+            serde_json::to_string(&json).unwrap()
+        }
+    }
+
+    fn format_search_stats(&self, stats: &SearchStats) -> String {
+        if self.pretty {
+            serde_json::to_string_pretty(stats).unwrap()
+        } else {
+            serde_json::to_string(stats).unwrap()
+        }
+    }
+
+    fn format_document_chunks(&self, chunks: &[DocumentChunk], show_content: bool) -> String {
+        let chunks_array: Vec<serde_json::Value> = chunks
+            .iter()
+            .map(|c| {
+                let mut json = serde_json::json!({
+                    "chunk_id": c.id,
+                    "chunk_index": c.chunk_index,
+                    "line_start": c.line_start,
+                    "line_end": c.line_end,
+                    "tokens": estimate_tokens(&c.content),
+                    "chars": c.content.chars().count(),
+                    "symbol": c.symbol(),
+                });
+                if show_content {
+                    json["content"] = serde_json::Value::String(c.content.clone());
+                }
+                json
+            })
+            .collect();
+
+        let json = serde_json::json!({
+            "document_id": chunks.first().map(|c| c.document_id.as_str()),
+            "location": chunks.first().map(|c| c.source.location.as_str()),
+            "chunks": chunks_array,
+        });
+
+        if self.pretty {
+            serde_json::to_string_pretty(&json).unwrap()
+        } else {
+            serde_json::to_string(&json).unwrap()
+        }
+    }
+
+    fn format_daemon_stats(&self, stats: &DaemonStats) -> String {
+        if self.pretty {
+            serde_json::to_string_pretty(stats).unwrap()
+        } else {
+            serde_json::to_string(stats).unwrap()
+        }
+    }
+
     fn format_message(&self, message: &str) -> String {
         serde_json::json!({"message": message}).to_string()
     }
@@ -342,7 +1187,131 @@ impl Formatter for JsonFormatter {
 
 pub struct MarkdownFormatter;
 
+impl MarkdownFormatter {
+    /// A standalone report of a search, with every result's full content,
+    /// for pasting into tickets and design docs.
+    pub fn format_report(&self, report: &SearchReport<'_>) -> String {
+        let results = report.results;
+        let mut output = String::new();
+        writeln!(output, "# Search report: {}\n", results.query).unwrap();
+        writeln!(output, "| | |\n|---|---|").unwrap();
+        writeln!(
+            output,
+            "| Query | `{}` |",
+            results.query.replace('|', "\\|")
+        )
+        .unwrap();
+        writeln!(output, "| Collection | `{}` |", report.collection).unwrap();
+        let filters = if report.filters.is_empty() {
+            "none".to_string()
+        } else {
+            report.filters.join("<br>").replace('|', "\\|")
+        };
+        writeln!(output, "| Filters | {} |", filters).unwrap();
+        writeln!(
+            output,
+            "| Generated | {} |",
+            format_time(Some(report.generated_at))
+        )
+        .unwrap();
+        let shown = match results.results.len() as u64 {
+            0 => "none".to_string(),
+            n => format!("{}-{}", results.offset + 1, results.offset + n),
+        };
+        writeln!(
+            output,
+            "| Results | {} of {} ({}ms) |\n",
+            shown, results.total, results.duration_ms
+        )
+        .unwrap();
+
+        for (i, result) in results.results.iter().enumerate() {
+            writeln!(
+                output,
+                "## {}. {}\n",
+                results.offset + i as u64 + 1,
+                report_title(result)
+            )
+            .unwrap();
+            writeln!(output, "- **Source:** {}", report_link(result)).unwrap();
+            let score = if result.keyword_match {
+                format!("{:.3} (keyword match)", result.score)
+            } else {
+                format!("{:.3}", result.score)
+            };
+            writeln!(output, "- **Score:** {}", score).unwrap();
+            if let Some(symbol) = result.symbol() {
+                writeln!(output, "- **Symbol:** `{}`", symbol).unwrap();
+            }
+            if result.updated_at.is_some() {
+                writeln!(output, "- **Updated:** {}", format_time(result.updated_at)).unwrap();
+            }
+            if !result.tags.is_empty() {
+                let tags: Vec<String> = result.tags.iter().map(|t| format!("`{}`", t)).collect();
+                writeln!(output, "- **Tags:** {}", tags.join(", ")).unwrap();
+            }
+            writeln!(output).unwrap();
+
+            let mut content = result.context_before.clone();
+            content.push(result.content.clone());
+            content.extend(result.context_after.iter().cloned());
+            let content = content.join("\n");
+            let fence = code_fence(&content);
+            let language = result
+                .attributes
+                .get(LANGUAGE_ATTRIBUTE)
+                .map_or("", String::as_str);
+            writeln!(output, "{}{}", fence, language).unwrap();
+            writeln!(output, "{}", content.trim_end()).unwrap();
+            writeln!(output, "{}\n", fence).unwrap();
+        }
+        output
+    }
+}
+
+/// Heading of a result in a report: its project-relative path or location
+/// and line range.
+fn report_title(result: &SearchResult) -> String {
+    let location = result
+        .attributes
+        .get(RELATIVE_PATH_ATTRIBUTE)
+        .unwrap_or(&result.location);
+    match (result.line_start, result.line_end) {
+        (Some(start), Some(end)) if end > start => format!("{}:{}-{}", location, start, end),
+        (Some(start), _) => format!("{}:{}", location, start),
+        _ => location.clone(),
+    }
+}
+
+/// Markdown link to a result's source: its URL, a `file://` link for local
+/// files, else the bare location.
+fn report_link(result: &SearchResult) -> String {
+    let citation = result.citation().replace(['[', ']'], "");
+    if let Some(ref url) = result.source.url {
+        return format!("[{}]({})", citation, url);
+    }
+    if result.source.source_type == SourceType::Local {
+        let url = format!("file://{}", result.location.replace(' ', "%20"));
+        return format!("[{}]({})", citation, url);
+    }
+    format!("`{}`", citation)
+}
+
+/// A backtick fence longer than any backtick run in `content`.
+fn code_fence(content: &str) -> String {
+    let longest = content.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    "`".repeat(longest.max(2) + 1)
+}
+
 impl Formatter for MarkdownFormatter {
+    fn format_batch_results(&self, batch: &[SearchResults]) -> String {
+        batch
+            .iter()
+            .map(|results| self.format_search_results(results))
+            .collect::<Vec<_>>()
+            .join("\n---\n\n")
+    }
+
     fn format_search_results(&self, results: &SearchResults) -> String {
         if results.is_empty() {
             return format!("## No results found\n\nQuery: `{}`\n", results.query);
@@ -359,31 +1328,99 @@ impl Formatter for MarkdownFormatter {
         .unwrap();
 
         for (i, result) in results.results.iter().enumerate() {
-            writeln!(output, "### {}. Score: {:.3}\n", i + 1, result.score).unwrap();
-            writeln!(output, "**Location:** `{}`\n", result.location).unwrap();
+            writeln!(
+                output,
+                "### {}. Score: {:.3}{}\n",
+                results.offset + i as u64 + 1,
+                result.score,
+                if result.keyword_match {
+                    " (keyword match)"
+                } else {
+                    ""
+                }
+            )
+            .unwrap();
+            match result.symbol() {
+                Some(symbol) => writeln!(
+                    output,
+                    "**Location:** `{}` (in `{}`)\n",
+                    result.location, symbol
+                )
+                .unwrap(),
+                None => writeln!(output, "**Location:** `{}`\n", result.location).unwrap(),
+            }
             if !result.tags.is_empty() {
                 let tags: Vec<String> = result.tags.iter().map(|t| format!("`{}`", t)).collect();
                 writeln!(output, "**Tags:** {}\n", tags.join(", ")).unwrap();
             }
+            if let Some(ref explanation) = result.explanation {
+                for line in explain_lines(explanation) {
+                    writeln!(output, "- {}", line).unwrap();
+                }
+                writeln!(output).unwrap();
+            }
+            let snippet = Snippet::extract(&result.content, &results.query, SNIPPET_CHARS)
+                .render(|term| format!("**{}**", term));
+            for line in snippet.lines().filter(|l| !l.trim().is_empty()) {
+                writeln!(output, "> {}", line.trim()).unwrap();
+            }
+            writeln!(output).unwrap();
             writeln!(output, "```").unwrap();
+            for chunk in &result.context_before {
+                writeln!(output, "{}", chunk).unwrap();
+            }
             writeln!(output, "{}", result.content).unwrap();
+            for chunk in &result.context_after {
+                writeln!(output, "{}", chunk).unwrap();
+            }
             writeln!(output, "```\n").unwrap();
         }
 
         output
     }
 
+    fn format_answer(&self, answer: &Answer) -> String {
+        let mut output = String::new();
+        writeln!(output, "## Answer\n").unwrap();
+        writeln!(output, "**Question:** {}\n", answer.question).unwrap();
+        writeln!(output, "{}\n", answer.answer).unwrap();
+        writeln!(output, "### Sources\n").unwrap();
+        for (i, source) in answer.sources.iter().enumerate() {
+            writeln!(
+                output,
+                "- **[{}]** `{}` (score {:.3})",
+                i + 1,
+                source.citation(),
+                source.score
+            )
+            .unwrap();
+        }
+        output
+    }
+
     fn format_status(&self, status: &StatusInfo) -> String {
         let mut output = String::new();
         writeln!(output, "## Status\n").unwrap();
 
-        let daemon_status = if status.daemon_running { "✅" } else { "❌" };
+        let daemon_status = match (status.daemon_running, status.daemon_ready) {
+            (true, true) => "✅",
+            (true, false) => "⏳",
+            (false, _) => "❌",
+        };
         writeln!(output, "### ML Daemon {}\n", daemon_status).unwrap();
 
         if status.daemon_running {
             if let Some(ref model) = status.embedding_model {
                 writeln!(output, "- **Embedding:** {}", model).unwrap();
             }
+            if !status.loaded_models.is_empty() {
+                writeln!(
+                    output,
+                    "- **Also Loaded:** {}",
+                    status.loaded_models.join(", ")
+                )
+                .unwrap();
+            }
             if let Some(ref m) = status.metrics {
                 writeln!(output, "- **Requests:** {}", m.total_requests).unwrap();
                 writeln!(output, "- **Avg Latency:** {}ms", m.avg_latency_ms).unwrap();
@@ -412,6 +1449,111 @@ impl Formatter for MarkdownFormatter {
         output
     }
 
+    fn format_health(&self, report: &HealthReport) -> String {
+        let icon = |status: CheckStatus| match status {
+            CheckStatus::Pass => "✅",
+            CheckStatus::Warn => "⚠️",
+            CheckStatus::Fail => "❌",
+        };
+        let mut output = String::new();
+        writeln!(output, "## Health {}\n", icon(report.status())).unwrap();
+        writeln!(output, "| Check | Status | Details |").unwrap();
+        writeln!(output, "|-------|--------|---------|").unwrap();
+        for check in &report.checks {
+            writeln!(
+                output,
+                "| {} | {} {} | {} |",
+                check.name,
+                icon(check.status),
+                check.status,
+                check.message.replace('|', "\\|")
+            )
+            .unwrap();
+        }
+        output
+    }
+
+    fn format_eval(&self, run: &EvalRun, baseline: Option<&EvalRun>) -> String {
+        let mut output = String::new();
+        writeln!(output, "## Evaluation\n").unwrap();
+        writeln!(
+            output,
+            "{} queries, k = {} ({})\n",
+            run.queries.len(),
+            run.k,
+            eval_setup(run)
+        )
+        .unwrap();
+        match baseline {
+            Some(baseline) => {
+                writeln!(output, "| Metric | Score | Baseline | Change |").unwrap();
+                writeln!(output, "|--------|-------|----------|--------|").unwrap();
+                for ((name, value), (_, before)) in
+                    eval_metrics(run).into_iter().zip(eval_metrics(baseline))
+                {
+                    writeln!(
+                        output,
+                        "| {} | {:.3} | {:.3} | {:+.3} |",
+                        name,
+                        value,
+                        before,
+                        value - before
+                    )
+                    .unwrap();
+                }
+            }
+            None => {
+                writeln!(output, "| Metric | Score |").unwrap();
+                writeln!(output, "|--------|-------|").unwrap();
+                for (name, value) in eval_metrics(run) {
+                    writeln!(output, "| {} | {:.3} |", name, value).unwrap();
+                }
+            }
+        }
+
+        writeln!(output, "\n### Queries\n").unwrap();
+        writeln!(output, "| Query | nDCG | Recall | RR | Missed |").unwrap();
+        writeln!(output, "|-------|------|--------|----|--------|").unwrap();
+        for query in &run.queries {
+            let missed: Vec<String> = query.missed.iter().map(|m| format!("`{}`", m)).collect();
+            writeln!(
+                output,
+                "| {} | {:.3} | {:.2} | {:.2} | {} |",
+                query.query.replace('|', "\\|"),
+                query.ndcg,
+                query.recall,
+                query.reciprocal_rank,
+                missed.join(", ")
+            )
+            .unwrap();
+        }
+
+        if let Some(baseline) = baseline {
+            let changed: Vec<_> = run
+                .compare(baseline)
+                .into_iter()
+                .filter(|d| d.change() != 0.0)
+                .collect();
+            if !changed.is_empty() {
+                writeln!(output, "\n### Changes vs Baseline\n").unwrap();
+                writeln!(output, "| Query | Baseline nDCG | nDCG | Change |").unwrap();
+                writeln!(output, "|-------|---------------|------|--------|").unwrap();
+                for delta in changed {
+                    writeln!(
+                        output,
+                        "| {} | {:.3} | {:.3} | {:+.3} |",
+                        delta.query.replace('|', "\\|"),
+                        delta.before,
+                        delta.after,
+                        delta.change()
+                    )
+                    .unwrap();
+                }
+            }
+        }
+        output
+    }
+
     fn format_index_stats(&self, stats: &IndexStats) -> String {
         let mut output = String::new();
         writeln!(output, "## Indexing Complete\n").unwrap();
@@ -420,7 +1562,58 @@ impl Formatter for MarkdownFormatter {
         writeln!(output, "| Files scanned | {} |", stats.files_scanned).unwrap();
         writeln!(output, "| Files indexed | {} |", stats.files_indexed).unwrap();
         writeln!(output, "| Files skipped | {} |", stats.files_skipped).unwrap();
+        if stats.files_unchanged > 0 {
+            writeln!(output, "| Files unchanged | {} |", stats.files_unchanged).unwrap();
+        }
+        if stats.files_removed > 0 {
+            writeln!(output, "| Files removed | {} |", stats.files_removed).unwrap();
+        }
+        if stats.files_pii_flagged > 0 {
+            writeln!(
+                output,
+                "| Files flagged for PII | {} |",
+                stats.files_pii_flagged
+            )
+            .unwrap();
+        }
+        if stats.files_pii_skipped > 0 {
+            writeln!(
+                output,
+                "| Files skipped for PII | {} |",
+                stats.files_pii_skipped
+            )
+            .unwrap();
+        }
         writeln!(output, "| Chunks created | {} |", stats.chunks_created).unwrap();
+        if stats.chunks_deduplicated > 0 {
+            writeln!(
+                output,
+                "| Duplicate chunks skipped | {} |",
+                stats.chunks_deduplicated
+            )
+            .unwrap();
+        }
+        if stats.chunks_short > 0 {
+            let outcome = stats.filler_outcome();
+            writeln!(
+                output,
+                "| Short chunks {} | {} |",
+                outcome, stats.chunks_short
+            )
+            .unwrap();
+        }
+        if stats.chunks_boilerplate > 0 {
+            let outcome = stats.filler_outcome();
+            writeln!(
+                output,
+                "| Boilerplate chunks {} | {} |",
+                outcome, stats.chunks_boilerplate
+            )
+            .unwrap();
+        }
+        if stats.secrets_redacted > 0 {
+            writeln!(output, "| Secrets redacted | {} |", stats.secrets_redacted).unwrap();
+        }
         writeln!(output, "| Duration | {}ms |", stats.duration_ms).unwrap();
         output
     }
@@ -440,6 +1633,96 @@ impl Formatter for MarkdownFormatter {
         output
     }
 
+    fn format_aliases(&self, aliases: &BTreeMap<String, SavedQuery>) -> String {
+        if aliases.is_empty() {
+            return "## Aliases\n\n*No aliases saved.*\n".to_string();
+        }
+
+        let mut output = String::new();
+        writeln!(output, "## Aliases\n").unwrap();
+        writeln!(output, "| Alias | Query | Options |").unwrap();
+        writeln!(output, "|-------|-------|---------|").unwrap();
+        for (name, alias) in aliases {
+            let options = format_alias_options(alias);
+            let options = if options.is_empty() {
+                "-".to_string()
+            } else {
+                format!("`{}`", options)
+            };
+            writeln!(output, "| `{}` | {} | {} |", name, alias.query, options).unwrap();
+        }
+        output
+    }
+
+    fn format_inventory(&self, collection: &str, inventory: &IndexInventory) -> String {
+        let mut output = String::new();
+        writeln!(output, "## Index: `{}`\n", collection).unwrap();
+        writeln!(output, "- **Documents:** {}", inventory.total_documents()).unwrap();
+        writeln!(output, "- **Chunks:** {}", inventory.total_chunks()).unwrap();
+        writeln!(
+            output,
+            "- **Storage:** ~{}",
+            format_bytes(inventory.storage_bytes)
+        )
+        .unwrap();
+        writeln!(
+            output,
+            "- **Last indexed:** {}\n",
+            format_time(inventory.last_indexed())
+        )
+        .unwrap();
+
+        if !inventory.sources.is_empty() {
+            writeln!(output, "### Sources\n").unwrap();
+            writeln!(output, "| Source | Documents | Chunks | Last indexed |").unwrap();
+            writeln!(output, "|--------|-----------|--------|--------------|").unwrap();
+            for s in &inventory.sources {
+                writeln!(
+                    output,
+                    "| `{}` | {} | {} | {} |",
+                    s.source_type,
+                    s.documents,
+                    s.chunks,
+                    format_time(s.last_indexed)
+                )
+                .unwrap();
+            }
+            writeln!(output).unwrap();
+        }
+
+        if !inventory.tags.is_empty() {
+            writeln!(output, "### Tags\n").unwrap();
+            writeln!(output, "| Tag | Documents |").unwrap();
+            writeln!(output, "|-----|-----------|").unwrap();
+            for (tag, documents) in &inventory.tags {
+                writeln!(output, "| `{}` | {} |", tag, documents).unwrap();
+            }
+        }
+        output
+    }
+
+    fn format_collections(&self, collections: &[CollectionSummary]) -> String {
+        let mut output = String::new();
+        writeln!(output, "## Collections\n").unwrap();
+        writeln!(output, "| Collection | Points | Model | Dimension |").unwrap();
+        writeln!(output, "|------------|--------|-------|-----------|").unwrap();
+        for c in collections {
+            let name = if c.active {
+                format!("**`{}`** (active)", c.name)
+            } else {
+                format!("`{}`", c.name)
+            };
+            let points = c.points.map_or_else(|| "-".to_string(), |n| n.to_string());
+            writeln!(
+                output,
+                "| {} | {} | `{}` | {} |",
+                name, points, c.model_id, c.dimension
+            )
+            .unwrap();
+        }
+        output
+    }
+
     fn format_sources(&self, sources: &[SourceInfo]) -> String {
         let mut output = String::new();
         writeln!(output, "## Available Data Sources\n").unwrap();
@@ -472,6 +1755,165 @@ impl Formatter for MarkdownFormatter {
         output
     }
 
+    fn format_search_history(&self, entries: &[SearchHistoryEntry]) -> String {
+        if entries.is_empty() {
+            return "## Search History\n\n*No searches recorded.*\n".to_string();
+        }
+
+        let mut output = String::new();
+        writeln!(output, "## Search History\n").unwrap();
+        writeln!(
+            output,
+            "| Time (UTC) | Query | Filters | Results | Top Score | Duration |"
+        )
+        .unwrap();
+        writeln!(
+            output,
+            "|------------|-------|---------|---------|-----------|----------|"
+        )
+        .unwrap();
+        for entry in entries {
+            let search = &entry.search;
+            writeln!(
+                output,
+                "| {} | `{}` | {} | {} | {} | {}ms |",
+                entry.timestamp,
+                search.query,
+                search.filters.as_deref().unwrap_or("-"),
+                search.result_count,
+                format_score(search.top_score),
+                search.duration_ms
+            )
+            .unwrap();
+        }
+        output
+    }
+
+    fn format_search_stats(&self, stats: &SearchStats) -> String {
+        let mut output = String::new();
+        writeln!(output, "## Search Statistics (last {} days)\n", stats.days).unwrap();
+        writeln!(output, "- **Searches:** {}", stats.total_searches).unwrap();
+        writeln!(
+            output,
+            "- **Zero results:** {} ({:.1}%)",
+            stats.zero_result_searches,
+            percent(stats.zero_result_searches, stats.total_searches)
+        )
+        .unwrap();
+        writeln!(
+            output,
+            "- **Poor results:** {} ({:.1}%, top score < {})",
+            stats.poor_result_searches,
+            percent(stats.poor_result_searches, stats.total_searches),
+            stats.poor_score
+        )
+        .unwrap();
+        writeln!(output, "- **Avg duration:** {}ms", stats.avg_duration_ms).unwrap();
+
+        let sections: [(&str, &[QueryStats]); 3] = [
+            ("Top Queries", &stats.top_queries),
+            ("Zero-Result Queries", &stats.zero_result_queries),
+            ("Poor Queries", &stats.poor_queries),
+        ];
+        for (title, queries) in sections {
+            if queries.is_empty() {
+                continue;
+            }
+            writeln!(output, "\n### {}\n", title).unwrap();
+            writeln!(output, "| Query | Count | Avg Top Score | Last Searched |").unwrap();
+            writeln!(output, "|-------|-------|---------------|---------------|").unwrap();
+            for query in queries {
+                writeln!(
+                    output,
+                    "| `{}` | {} | {} | {} |",
+                    query.query,
+                    query.count,
+                    format_score(query.avg_top_score),
+                    query.last_searched
+                )
+                .unwrap();
+            }
+        }
+        output
+    }
+
+    fn format_document_chunks(&self, chunks: &[DocumentChunk], show_content: bool) -> String {
+        let Some(first) = chunks.first() else {
+            return "## Document Chunks\n\n*No indexed chunks found for this document.*\n"
+                .to_string();
+        };
+
+        let mut output = String::new();
+        writeln!(output, "## Chunks of `{}`\n", first.source.location).unwrap();
+        writeln!(output, "- **Document:** `{}`", first.document_id).unwrap();
+        writeln!(output, "- **Chunks:** {}\n", chunks.len()).unwrap();
+        writeln!(output, "| # | Lines | Tokens | Chars | Symbol |").unwrap();
+        writeln!(output, "|---|-------|--------|-------|--------|").unwrap();
+        for chunk in chunks {
+            writeln!(
+                output,
+                "| {} | {} | ~{} | {} | {} |",
+                chunk.chunk_index,
+                format_lines(chunk),
+                estimate_tokens(&chunk.content),
+                chunk.content.chars().count(),
+                chunk
+                    .symbol()
+                    .map_or_else(|| "-".to_string(), |s| format!("`{}`", s))
+            )
+            .unwrap();
+        }
+
+        if show_content {
+            for chunk in chunks {
+                writeln!(output, "\n### Chunk {}\n", chunk.chunk_index).unwrap();
+                writeln!(output, "```\n{}\n```", chunk.content.trim_end()).unwrap();
+            }
+        }
+        output
+    }
+
+    fn format_daemon_stats(&self, stats: &DaemonStats) -> String {
+        let mut output = String::new();
+        writeln!(
+            output,
+            "## Daemon Statistics (last {} days, {})\n",
+            stats.days,
+            period_label(stats)
+        )
+        .unwrap();
+        if stats.rows.is_empty() {
+            writeln!(output, "*No daemon requests recorded.*").unwrap();
+            return output;
+        }
+        writeln!(
+            output,
+            "| Period | Kind | Requests | Success | Avg Batch | p50 | p95 | p99 |"
+        )
+        .unwrap();
+        writeln!(
+            output,
+            "|--------|------|----------|---------|-----------|-----|-----|-----|"
+        )
+        .unwrap();
+        for row in &stats.rows {
+            writeln!(
+                output,
+                "| {} | {} | {} | {:.1}% | {} | {}ms | {}ms | {}ms |",
+                row.period,
+                row.kind,
+                row.requests,
+                row.success_rate,
+                format_batch(row.avg_batch_size),
+                row.p50_ms,
+                row.p95_ms,
+                row.p99_ms
+            )
+            .unwrap();
+        }
+        output
+    }
+
     fn format_message(&self, message: &str) -> String {
         format!("> {}\n", message)
     }
@@ -488,3 +1930,61 @@ pub fn get_formatter(format: OutputFormat) -> Box<dyn Formatter> {
         OutputFormat::Markdown => Box::new(MarkdownFormatter),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::search_result;
+
+    #[test]
+    fn test_format_report() {
+        let local = search_result("/work/api/src/auth.rs", "let token = refresh()?;\n", 0.91);
+        let mut page = search_result("Runbook", "Run ```deploy``` to roll out.", 0.5);
+        page.source.source_type = SourceType::Confluence;
+        page.source.url = Some("https://wiki.example.com/runbook".to_string());
+        page.keyword_match = true;
+        let mut ticket = search_result("OPS-12", "Rollback failed", 0.4);
+        ticket.source.source_type = SourceType::Jira;
+        let results = SearchResults::new(
+            "token | refresh".to_string(),
+            vec![local, page, ticket],
+            25,
+            12,
+        )
+        .with_offset(10);
+        let generated_at = chrono::DateTime::parse_from_rfc3339("2026-01-02T03:04:05Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        let report = MarkdownFormatter.format_report(&SearchReport {
+            results: &results,
+            filters: vec!["Tags: team:core".to_string(), "Mode: hybrid".to_string()],
+            collection: "docs".to_string(),
+            generated_at,
+        });
+
+        assert!(report.starts_with("# Search report: token | refresh\n"));
+        assert!(report.contains("| Query | `token \\| refresh` |"));
+        assert!(report.contains("| Filters | Tags: team:core<br>Mode: hybrid |"));
+        assert!(report.contains("| Results | 11-13 of 25 (12ms) |"));
+
+        // Numbered from the page's offset, each linked to its source
+        assert!(report.contains("## 11. /work/api/src/auth.rs:1\n"));
+        assert!(report.contains("[/work/api/src/auth.rs:1](file:///work/api/src/auth.rs)"));
+        assert!(report.contains("## 12. Runbook:1\n"));
+        assert!(report.contains("[Runbook:1](https://wiki.example.com/runbook)"));
+        assert!(report.contains("- **Score:** 0.500 (keyword match)"));
+        assert!(report.contains("- **Source:** `OPS-12:1`"));
+        // Fences outrun backticks in the content
+        assert!(report.contains("````\nRun ```deploy``` to roll out.\n````"));
+
+        let empty = SearchResults::new("nothing".to_string(), Vec::new(), 0, 1);
+        let report = MarkdownFormatter.format_report(&SearchReport {
+            results: &empty,
+            filters: Vec::new(),
+            collection: "docs".to_string(),
+            generated_at,
+        });
+        assert!(report.contains("| Filters | none |"));
+        assert!(report.contains("| Results | none of 0 (1ms) |"));
+    }
+}
@@ -4,16 +4,42 @@ use std::time::Duration;
 
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::UnixStream;
+use tokio::sync::OnceCell;
 
 use crate::error::DaemonError;
 use crate::models::Config;
 use crate::server::protocol::{
-    EmbedRequest, Request, Response, StatusResponse, decode_length, encode_message,
+    AppendTextsRequest, BATCH_FRAME_BYTES, BeginBatchRequest, BusyResponse, EmbedRequest,
+    ErrorCode, HelloRequest, HelloResponse, MetricsResponse, ModelReloadedResponse, ModelSpec,
+    Priority, ProgressResponse, ReloadModelRequest, Request, RequestEnvelope, RerankRequest,
+    Response, StatusResponse, decode_length, encode_message, split_frames,
 };
 
+/// Times a rate-limited request is sent again before the client gives up.
+const BUSY_RETRIES: u32 = 6;
+
+/// First wait after a `Busy` response that suggests less; doubled on each retry.
+const BUSY_INITIAL_DELAY: Duration = Duration::from_millis(50);
+
+/// Longest wait between `Busy` retries.
+const BUSY_MAX_DELAY: Duration = Duration::from_secs(5);
+
+/// Wait before retry `attempt` (0-based): the daemon's suggestion or the backoff, whichever is longer.
+fn busy_delay(busy: &BusyResponse, attempt: u32) -> Duration {
+    let backoff = BUSY_INITIAL_DELAY.saturating_mul(1 << attempt.min(16));
+    busy.retry_after().max(backoff).min(BUSY_MAX_DELAY)
+}
+
+/// Receives `(done, total)` texts as the daemon works through a long embed batch.
+pub type EmbedProgress<'a> = &'a (dyn Fn(usize, usize) + Send + Sync);
+
 pub struct DaemonClient {
     socket_path: PathBuf,
     auto_start: bool,
+    /// Sent with every request when `[daemon] auth_token` is set
+    auth_token: Option<String>,
+    /// Daemon's answer to the version handshake, once made
+    hello: OnceCell<HelloResponse>,
 }
 
 impl DaemonClient {
@@ -21,6 +47,8 @@ impl DaemonClient {
         Self {
             socket_path: config.socket_path(),
             auto_start: config.daemon.auto_start,
+            auth_token: config.daemon.auth_token.clone(),
+            hello: OnceCell::new(),
         }
     }
 
@@ -30,16 +58,16 @@ impl DaemonClient {
     }
 
     pub async fn ensure_running(&self) -> Result<(), DaemonError> {
-        if self.is_running() {
-            return Ok(());
-        }
-
-        if !self.auto_start {
-            return Err(DaemonError::NotRunning);
+        if !self.is_running() {
+            if !self.auto_start {
+                return Err(DaemonError::NotRunning);
+            }
+            self.spawn_daemon()?;
+            self.wait_for_ready().await?;
         }
 
-        self.spawn_daemon()?;
-        self.wait_for_ready().await
+        self.hello.get_or_try_init(|| self.hello()).await?;
+        Ok(())
     }
 
     fn spawn_daemon(&self) -> Result<(), DaemonError> {
@@ -56,13 +84,14 @@ impl DaemonClient {
         Ok(())
     }
 
-    async fn wait_for_ready(&self) -> Result<(), DaemonError> {
+    /// Wait until a newly spawned daemon answers and has warmed up its models.
+    pub async fn wait_for_ready(&self) -> Result<(), DaemonError> {
         let max_wait = Duration::from_secs(60);
         let check_interval = Duration::from_millis(100);
         let start = std::time::Instant::now();
 
         while start.elapsed() < max_wait {
-            if self.is_running() && self.ping().await.is_ok() {
+            if self.is_running() && self.status().await.is_ok_and(|status| status.ready) {
                 return Ok(());
             }
             tokio::time::sleep(check_interval).await;
@@ -79,15 +108,71 @@ impl DaemonClient {
 
     async fn send_request(&self, request: Request) -> Result<Response, DaemonError> {
         let mut stream = self.connect().await?;
+        self.exchange_with_backoff(&mut stream, request, None).await
+    }
 
-        let encoded =
-            encode_message(&request).map_err(|e| DaemonError::ProtocolError(e.to_string()))?;
+    /// [`Self::exchange`], waiting and sending the request again while the
+    /// daemon answers `Busy`.
+    async fn exchange_with_backoff(
+        &self,
+        stream: &mut UnixStream,
+        request: Request,
+        progress: Option<EmbedProgress<'_>>,
+    ) -> Result<Response, DaemonError> {
+        let encoded = self.encode(request)?;
+        let mut attempt = 0;
+        loop {
+            match Self::exchange_frame(stream, &encoded, progress).await? {
+                Response::Busy(busy) if attempt < BUSY_RETRIES => {
+                    tokio::time::sleep(busy_delay(&busy, attempt)).await;
+                    attempt += 1;
+                }
+                Response::Busy(busy) => return Err(busy.into()),
+                response => return Ok(response),
+            }
+        }
+    }
+
+    /// Write one request frame and read its response on an open connection.
+    async fn exchange(
+        &self,
+        stream: &mut UnixStream,
+        request: Request,
+    ) -> Result<Response, DaemonError> {
+        let encoded = self.encode(request)?;
+        Self::exchange_frame(stream, &encoded, None).await
+    }
 
+    fn encode(&self, request: Request) -> Result<Vec<u8>, DaemonError> {
+        let envelope = RequestEnvelope::new(request, self.auth_token.clone());
+        encode_message(&envelope).map_err(|e| DaemonError::ProtocolError(e.to_string()))
+    }
+
+    /// Send an encoded request and read the response, passing any `Progress`
+    /// frames ahead of it to `progress`.
+    async fn exchange_frame(
+        stream: &mut UnixStream,
+        encoded: &[u8],
+        progress: Option<EmbedProgress<'_>>,
+    ) -> Result<Response, DaemonError> {
         stream
-            .write_all(&encoded)
+            .write_all(encoded)
             .await
             .map_err(|e| DaemonError::SocketError(e.to_string()))?;
 
+        loop {
+            match Self::read_response(stream).await? {
+                Response::Progress(ProgressResponse { done, total }) => {
+                    if let Some(progress) = progress {
+                        progress(done, total);
+                    }
+                }
+                response => return Ok(response),
+            }
+        }
+    }
+
+    async fn read_response(stream: &mut UnixStream) -> Result<Response, DaemonError> {
         let mut len_buf = [0u8; 4];
         stream
             .read_exact(&mut len_buf)
@@ -108,7 +193,7 @@ impl DaemonClient {
     pub async fn ping(&self) -> Result<(), DaemonError> {
         match self.send_request(Request::Ping).await? {
             Response::Pong => Ok(()),
-            Response::Error(e) => Err(DaemonError::ProtocolError(e.message)),
+            Response::Error(e) => Err(e.into()),
             _ => Err(DaemonError::ProtocolError(
                 "unexpected response".to_string(),
             )),
@@ -118,17 +203,82 @@ impl DaemonClient {
     pub async fn status(&self) -> Result<StatusResponse, DaemonError> {
         match self.send_request(Request::Status).await? {
             Response::Status(s) => Ok(s),
-            Response::Error(e) => Err(DaemonError::ProtocolError(e.message)),
+            Response::Error(e) => Err(e.into()),
             _ => Err(DaemonError::ProtocolError(
                 "unexpected response".to_string(),
             )),
         }
     }
 
+    pub async fn metrics(&self) -> Result<MetricsResponse, DaemonError> {
+        match self.send_request(Request::Metrics).await? {
+            Response::Metrics(m) => Ok(m),
+            Response::Error(e) => Err(e.into()),
+            _ => Err(DaemonError::ProtocolError(
+                "unexpected response".to_string(),
+            )),
+        }
+    }
+
+    /// Exchange protocol versions, failing with [`DaemonError::VersionMismatch`]
+    /// when the daemon was built from an incompatible version.
+    pub async fn hello(&self) -> Result<HelloResponse, DaemonError> {
+        let outdated = |detail: &str| {
+            DaemonError::VersionMismatch(format!(
+                "the running daemon predates ssearch {} ({}); restart it with `ssearch serve restart`",
+                env!("CARGO_PKG_VERSION"),
+                detail
+            ))
+        };
+        match self
+            .send_request(Request::Hello(HelloRequest::current()))
+            .await
+        {
+            Ok(Response::Hello(hello)) => Ok(hello),
+            Ok(Response::Error(e)) if e.code == ErrorCode::VersionMismatch => {
+                Err(DaemonError::VersionMismatch(e.message))
+            }
+            // Daemons without the handshake reject it as an unknown request
+            Ok(Response::Error(e)) if e.code == ErrorCode::InvalidRequest => {
+                Err(outdated(&e.message))
+            }
+            Ok(Response::Error(e)) => Err(e.into()),
+            Ok(_) => Err(outdated("unexpected response")),
+            Err(DaemonError::ProtocolError(e)) => Err(outdated(&e)),
+            Err(e) => Err(e),
+        }
+    }
+
     pub async fn shutdown(&self) -> Result<(), DaemonError> {
         match self.send_request(Request::Shutdown).await? {
             Response::ShutdownAck => Ok(()),
-            Response::Error(e) => Err(DaemonError::ProtocolError(e.message)),
+            Response::Error(e) => Err(e.into()),
+            _ => Err(DaemonError::ProtocolError(
+                "unexpected response".to_string(),
+            )),
+        }
+    }
+
+    /// Wait until a stopping daemon has released its socket.
+    pub async fn wait_for_release(&self, max_wait: Duration) -> Result<(), DaemonError> {
+        let start = std::time::Instant::now();
+        while self.is_running() {
+            if start.elapsed() > max_wait {
+                return Err(DaemonError::Timeout);
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        Ok(())
+    }
+
+    /// Ask a running daemon to swap its default embedding model.
+    pub async fn reload_model(
+        &self,
+        request: ReloadModelRequest,
+    ) -> Result<ModelReloadedResponse, DaemonError> {
+        match self.send_request(Request::ReloadModel(request)).await? {
+            Response::ModelReloaded(r) => Ok(r),
+            Response::Error(e) => Err(e.into()),
             _ => Err(DaemonError::ProtocolError(
                 "unexpected response".to_string(),
             )),
@@ -139,14 +289,125 @@ impl DaemonClient {
         &self,
         texts: Vec<String>,
         is_query: bool,
+        model: Option<ModelSpec>,
+        instruction: Option<String>,
+    ) -> Result<Vec<Vec<f32>>, DaemonError> {
+        self.embed_reporting(texts, is_query, model, instruction, None)
+            .await
+    }
+
+    /// [`Self::embed`], asking the daemon to report progress through
+    /// `progress` while it embeds the batch.
+    pub async fn embed_with_progress(
+        &self,
+        texts: Vec<String>,
+        is_query: bool,
+        model: Option<ModelSpec>,
+        instruction: Option<String>,
+        progress: EmbedProgress<'_>,
+    ) -> Result<Vec<Vec<f32>>, DaemonError> {
+        self.embed_reporting(texts, is_query, model, instruction, Some(progress))
+            .await
+    }
+
+    async fn embed_reporting(
+        &self,
+        texts: Vec<String>,
+        is_query: bool,
+        model: Option<ModelSpec>,
+        instruction: Option<String>,
+        progress: Option<EmbedProgress<'_>>,
     ) -> Result<Vec<Vec<f32>>, DaemonError> {
         self.ensure_running().await?;
 
-        let request = Request::Embed(EmbedRequest { texts, is_query });
+        let total_bytes: usize = texts.iter().map(String::len).sum();
+        if total_bytes > BATCH_FRAME_BYTES {
+            return self
+                .embed_streamed(texts, is_query, model, instruction, progress)
+                .await;
+        }
 
-        match self.send_request(request).await? {
+        let request = Request::Embed(EmbedRequest {
+            texts,
+            is_query,
+            model,
+            instruction,
+            priority: Some(Priority::for_query(is_query)),
+            progress: progress.is_some(),
+        });
+
+        let mut stream = self.connect().await?;
+        match self
+            .exchange_with_backoff(&mut stream, request, progress)
+            .await?
+        {
+            Response::Embed(r) => Ok(r.embeddings),
+            Response::Error(e) => Err(e.into()),
+            _ => Err(DaemonError::ProtocolError(
+                "unexpected response".to_string(),
+            )),
+        }
+    }
+
+    /// Embed a batch too large for one frame by streaming it over a single connection.
+    async fn embed_streamed(
+        &self,
+        texts: Vec<String>,
+        is_query: bool,
+        model: Option<ModelSpec>,
+        instruction: Option<String>,
+        progress: Option<EmbedProgress<'_>>,
+    ) -> Result<Vec<Vec<f32>>, DaemonError> {
+        let mut stream = self.connect().await?;
+
+        let begin = Request::BeginBatch(BeginBatchRequest {
+            is_query,
+            model,
+            instruction,
+            priority: Some(Priority::for_query(is_query)),
+            progress: progress.is_some(),
+        });
+        Self::expect_ack(self.exchange(&mut stream, begin).await?)?;
+
+        for frame in split_frames(texts, BATCH_FRAME_BYTES) {
+            let append = Request::AppendTexts(AppendTextsRequest { texts: frame });
+            Self::expect_ack(self.exchange(&mut stream, append).await?)?;
+        }
+
+        match self
+            .exchange_with_backoff(&mut stream, Request::FinishBatch, progress)
+            .await?
+        {
             Response::Embed(r) => Ok(r.embeddings),
-            Response::Error(e) => Err(DaemonError::ProtocolError(e.message)),
+            Response::Error(e) => Err(e.into()),
+            _ => Err(DaemonError::ProtocolError(
+                "unexpected response".to_string(),
+            )),
+        }
+    }
+
+    fn expect_ack(response: Response) -> Result<(), DaemonError> {
+        match response {
+            Response::BatchAck(_) => Ok(()),
+            Response::Error(e) => Err(e.into()),
+            _ => Err(DaemonError::ProtocolError(
+                "unexpected response".to_string(),
+            )),
+        }
+    }
+
+    pub async fn rerank(
+        &self,
+        query: String,
+        documents: Vec<String>,
+    ) -> Result<Vec<f32>, DaemonError> {
+        self.ensure_running().await?;
+
+        let request = Request::Rerank(RerankRequest { query, documents });
+
+        match self.send_request(request).await? {
+            Response::Rerank(r) => Ok(r.scores),
+            Response::Error(e) => Err(e.into()),
             _ => Err(DaemonError::ProtocolError(
                 "unexpected response".to_string(),
             )),
@@ -180,3 +441,62 @@ pub fn stop_daemon(config: &Config) -> Result<(), DaemonError> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use tokio::net::UnixListener;
+
+    use super::*;
+
+    /// Answer `Status` requests on `socket`, reporting ready from the `ready_after`th one on.
+    fn fake_daemon(socket: &std::path::Path, ready_after: usize) -> Arc<AtomicUsize> {
+        let listener = UnixListener::bind(socket).unwrap();
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&requests);
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let counter = Arc::clone(&counter);
+                tokio::spawn(async move {
+                    // `is_running` probes connect without sending anything
+                    let mut len = [0u8; 4];
+                    if stream.read_exact(&mut len).await.is_err() {
+                        return;
+                    }
+                    let mut request = vec![0u8; decode_length(&len)];
+                    stream.read_exact(&mut request).await.unwrap();
+                    let served = counter.fetch_add(1, Ordering::SeqCst) + 1;
+                    let response = Response::Status(StatusResponse {
+                        running: true,
+                        embedding_model: "org/model".to_string(),
+                        loaded_models: Vec::new(),
+                        idle_secs: 0,
+                        requests_served: served as u64,
+                        metrics: None,
+                        ready: served >= ready_after,
+                    });
+                    let encoded = encode_message(&response).unwrap();
+                    stream.write_all(&encoded).await.unwrap();
+                });
+            }
+        });
+        requests
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_ready() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = Config::default();
+        config.daemon.socket_path = Some(dir.path().join("daemon.sock"));
+        let client = DaemonClient::new(&config);
+        assert!(!client.is_running());
+
+        // Answering pings isn't enough; the daemon must report its models warm
+        let requests = fake_daemon(&config.socket_path(), 3);
+        client.wait_for_ready().await.unwrap();
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+        assert!(client.status().await.unwrap().ready);
+    }
+}
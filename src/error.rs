@@ -1,5 +1,7 @@
+use serde::Serialize;
 use thiserror::Error;
 
+use crate::server::protocol::ErrorCode;
 use crate::utils::retry::Retryable;
 
 #[derive(Debug, Error)]
@@ -30,6 +32,16 @@ pub enum ModelError {
 
     #[error("download error: {0}")]
     DownloadError(String),
+
+    #[error(
+        "model {model_id} produces {dimension}-dimensional embeddings but collection '{collection}' stores {expected}; re-index into a new collection to switch models"
+    )]
+    DimensionMismatch {
+        model_id: String,
+        dimension: u64,
+        collection: String,
+        expected: u64,
+    },
 }
 
 #[derive(Debug, Error)]
@@ -55,16 +67,27 @@ pub enum DaemonError {
     #[error("timeout")]
     Timeout,
 
+    /// Client and daemon were built from incompatible versions.
+    #[error("{0}")]
+    VersionMismatch(String),
+
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
+
+    /// The daemon answered with an error response.
+    #[error("{message}")]
+    Remote { code: ErrorCode, message: String },
 }
 
 impl Retryable for DaemonError {
     fn is_retryable(&self) -> bool {
-        matches!(
-            self,
-            DaemonError::ConnectionFailed(_) | DaemonError::Timeout | DaemonError::NotRunning
-        )
+        match self {
+            DaemonError::ConnectionFailed(_) | DaemonError::Timeout | DaemonError::NotRunning => {
+                true
+            }
+            DaemonError::Remote { code, .. } => code.is_retryable(),
+            _ => false,
+        }
     }
 }
 
@@ -117,6 +140,24 @@ pub enum VectorStoreError {
 
     #[error("unsupported backend: {0}")]
     UnsupportedBackend(String),
+
+    #[error("invalid tenant configuration: {0}")]
+    InvalidTenant(String),
+
+    #[error("snapshot error: {0}")]
+    SnapshotError(String),
+
+    #[error("encryption error: {0}")]
+    EncryptionError(String),
+
+    #[error(
+        "collection '{collection}' stores {stored}-dimensional vectors but the configured model produces {expected}; run `ssearch collection migrate --re-embed` to re-embed it"
+    )]
+    DimensionMismatch {
+        collection: String,
+        stored: u64,
+        expected: u64,
+    },
 }
 
 impl Retryable for VectorStoreError {
@@ -130,7 +171,11 @@ impl Retryable for VectorStoreError {
                     || msg_lower.contains("unavailable")
             }
             VectorStoreError::PgVectorExtensionError(_)
-            | VectorStoreError::UnsupportedBackend(_) => false,
+            | VectorStoreError::UnsupportedBackend(_)
+            | VectorStoreError::InvalidTenant(_)
+            | VectorStoreError::SnapshotError(_)
+            | VectorStoreError::EncryptionError(_)
+            | VectorStoreError::DimensionMismatch { .. } => false,
             VectorStoreError::CollectionError(msg)
             | VectorStoreError::UpsertError(msg)
             | VectorStoreError::SearchError(msg)
@@ -154,6 +199,12 @@ pub enum IndexError {
     #[error("chunking error: {0}")]
     ChunkError(String),
 
+    #[error("git error: {0}")]
+    GitError(String),
+
+    #[error("checkpoint journal error: {0}")]
+    JournalError(String),
+
     #[error("embedding error: {0}")]
     EmbeddingError(#[from] EmbeddingError),
 
@@ -208,6 +259,12 @@ pub enum ImportError {
     #[error("JSON parse error: {0}")]
     JsonParseError(#[from] serde_json::Error),
 
+    #[error("YAML parse error: {0}")]
+    YamlParseError(#[from] serde_yaml::Error),
+
+    #[error("CSV parse error: {0}")]
+    CsvParseError(#[from] csv::Error),
+
     #[error("validation error: {0}")]
     ValidationError(String),
 
@@ -225,6 +282,28 @@ pub enum SearchError {
 
     #[error("invalid query: {0}")]
     InvalidQuery(String),
+
+    /// The search ran but matched nothing; results were already printed.
+    #[error("no results")]
+    NoResults,
+}
+
+#[derive(Debug, Error)]
+pub enum EvalError {
+    #[error("line {line}: {message}")]
+    InvalidJudgment { line: usize, message: String },
+
+    #[error("no judgments found")]
+    NoJudgments,
+}
+
+#[derive(Debug, Error)]
+pub enum LlmError {
+    #[error("LLM request failed: {0}")]
+    RequestFailed(String),
+
+    #[error("invalid LLM response: {0}")]
+    InvalidResponse(String),
 }
 
 #[derive(Debug, Error)]
@@ -253,6 +332,219 @@ pub enum AppError {
     #[error("model error: {0}")]
     Model(#[from] ModelError),
 
+    #[error("LLM error: {0}")]
+    Llm(#[from] LlmError),
+
+    #[error("{0} health check(s) failed")]
+    Unhealthy(usize),
+
     #[error("{0}")]
     Other(String),
 }
+
+/// Process exit status, so scripts can tell "no results" from "store down".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExitStatus {
+    /// Finished; searches found results
+    Success = 0,
+    /// A search ran but matched nothing
+    NoResults = 1,
+    /// Missing, unreadable or invalid configuration
+    Config = 2,
+    /// The vector store, daemon, model or LLM endpoint couldn't be reached
+    Unavailable = 3,
+    /// Invalid arguments, filters, tags or input files
+    InvalidInput = 4,
+    /// Anything else
+    Failure = 5,
+}
+
+impl ExitStatus {
+    pub fn code(self) -> u8 {
+        self as u8
+    }
+
+    /// Classify an error by the first typed error in its chain.
+    pub fn of(error: &anyhow::Error) -> Self {
+        error
+            .chain()
+            .find_map(|cause| {
+                if let Some(e) = cause.downcast_ref::<AppError>() {
+                    Some(Self::from(e))
+                } else if let Some(e) = cause.downcast_ref::<ConfigError>() {
+                    Some(Self::from(e))
+                } else if let Some(e) = cause.downcast_ref::<VectorStoreError>() {
+                    Some(Self::from(e))
+                } else if let Some(e) = cause.downcast_ref::<EmbeddingError>() {
+                    Some(Self::from(e))
+                } else if let Some(e) = cause.downcast_ref::<DaemonError>() {
+                    Some(Self::from(e))
+                } else if let Some(e) = cause.downcast_ref::<ModelError>() {
+                    Some(Self::from(e))
+                } else if let Some(e) = cause.downcast_ref::<SearchError>() {
+                    Some(Self::from(e))
+                } else if let Some(e) = cause.downcast_ref::<IndexError>() {
+                    Some(Self::from(e))
+                } else if let Some(e) = cause.downcast_ref::<ImportError>() {
+                    Some(Self::from(e))
+                } else if let Some(e) = cause.downcast_ref::<SourceError>() {
+                    Some(Self::from(e))
+                } else if cause.is::<TagError>() || cause.is::<EvalError>() {
+                    Some(ExitStatus::InvalidInput)
+                } else if cause.is::<LlmError>() {
+                    Some(ExitStatus::Unavailable)
+                } else {
+                    None
+                }
+            })
+            .unwrap_or(ExitStatus::Failure)
+    }
+}
+
+impl From<&ConfigError> for ExitStatus {
+    fn from(_: &ConfigError) -> Self {
+        ExitStatus::Config
+    }
+}
+
+impl From<&VectorStoreError> for ExitStatus {
+    fn from(error: &VectorStoreError) -> Self {
+        match error {
+            VectorStoreError::InvalidTenant(_)
+            | VectorStoreError::UnsupportedBackend(_)
+            | VectorStoreError::EncryptionError(_)
+            | VectorStoreError::DimensionMismatch { .. } => ExitStatus::Config,
+            VectorStoreError::PgVectorExtensionError(_) => ExitStatus::Unavailable,
+            e if e.is_retryable() => ExitStatus::Unavailable,
+            _ => ExitStatus::Failure,
+        }
+    }
+}
+
+impl From<&DaemonError> for ExitStatus {
+    fn from(error: &DaemonError) -> Self {
+        match error {
+            DaemonError::NotRunning
+            | DaemonError::ConnectionFailed(_)
+            | DaemonError::SocketError(_)
+            | DaemonError::SpawnError(_)
+            | DaemonError::Timeout => ExitStatus::Unavailable,
+            DaemonError::Remote { code, .. } => match code {
+                ErrorCode::ModelNotLoaded | ErrorCode::Overloaded => ExitStatus::Unavailable,
+                ErrorCode::InvalidRequest => ExitStatus::InvalidInput,
+                ErrorCode::Unauthorized | ErrorCode::DimensionMismatch => ExitStatus::Config,
+                _ => ExitStatus::Failure,
+            },
+            _ => ExitStatus::Failure,
+        }
+    }
+}
+
+impl From<&ModelError> for ExitStatus {
+    fn from(error: &ModelError) -> Self {
+        match error {
+            ModelError::NotFound(_) | ModelError::DimensionMismatch { .. } => ExitStatus::Config,
+            ModelError::DownloadError(_) => ExitStatus::Unavailable,
+            _ => ExitStatus::Failure,
+        }
+    }
+}
+
+impl From<&EmbeddingError> for ExitStatus {
+    fn from(error: &EmbeddingError) -> Self {
+        match error {
+            EmbeddingError::DaemonError(e) => e.into(),
+            EmbeddingError::ModelError(e) => e.into(),
+            EmbeddingError::InvalidResponse(_) => ExitStatus::Failure,
+        }
+    }
+}
+
+impl From<&SearchError> for ExitStatus {
+    fn from(error: &SearchError) -> Self {
+        match error {
+            SearchError::EmbeddingError(e) => e.into(),
+            SearchError::VectorStoreError(e) => e.into(),
+            SearchError::InvalidQuery(_) => ExitStatus::InvalidInput,
+            SearchError::NoResults => ExitStatus::NoResults,
+        }
+    }
+}
+
+impl From<&IndexError> for ExitStatus {
+    fn from(error: &IndexError) -> Self {
+        match error {
+            IndexError::EmbeddingError(e) => e.into(),
+            IndexError::VectorStoreError(e) => e.into(),
+            IndexError::NoFilesFound => ExitStatus::InvalidInput,
+            _ => ExitStatus::Failure,
+        }
+    }
+}
+
+impl From<&ImportError> for ExitStatus {
+    fn from(error: &ImportError) -> Self {
+        match error {
+            ImportError::IoError(_) => ExitStatus::Failure,
+            _ => ExitStatus::InvalidInput,
+        }
+    }
+}
+
+impl From<&SourceError> for ExitStatus {
+    fn from(error: &SourceError) -> Self {
+        match error {
+            SourceError::CliNotFound(_) | SourceError::UnsupportedSource(_) => ExitStatus::Config,
+            _ => ExitStatus::Failure,
+        }
+    }
+}
+
+impl From<&AppError> for ExitStatus {
+    fn from(error: &AppError) -> Self {
+        match error {
+            AppError::Tag(_) => ExitStatus::InvalidInput,
+            AppError::Config(e) => e.into(),
+            AppError::Index(e) => e.into(),
+            AppError::Search(e) => e.into(),
+            AppError::Source(e) => e.into(),
+            AppError::Import(e) => e.into(),
+            AppError::Daemon(e) => e.into(),
+            AppError::Model(e) => e.into(),
+            AppError::Llm(_) | AppError::Unhealthy(_) => ExitStatus::Unavailable,
+            AppError::Other(_) => ExitStatus::Failure,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_exit_status() {
+        let error = anyhow::Error::from(VectorStoreError::ConnectionError("refused".into()));
+        assert_eq!(ExitStatus::of(&error), ExitStatus::Unavailable);
+
+        let error: anyhow::Result<()> =
+            Err(ConfigError::ValidationError("bad".into())).context("failed to load config");
+        assert_eq!(ExitStatus::of(&error.unwrap_err()), ExitStatus::Config);
+
+        let error = anyhow::Error::from(SearchError::NoResults);
+        assert_eq!(ExitStatus::of(&error), ExitStatus::NoResults);
+        assert_eq!(ExitStatus::of(&error).code(), 1);
+
+        let error = anyhow::Error::from(EmbeddingError::DaemonError(DaemonError::NotRunning));
+        assert_eq!(ExitStatus::of(&error), ExitStatus::Unavailable);
+
+        let error = anyhow::Error::from(AppError::Unhealthy(2));
+        assert_eq!(ExitStatus::of(&error), ExitStatus::Unavailable);
+
+        assert_eq!(
+            ExitStatus::of(&anyhow::anyhow!("collection does not exist")),
+            ExitStatus::Failure
+        );
+    }
+}
@@ -0,0 +1,283 @@
+//! C ABI for editors and non-Rust tools, behind the `ffi` feature.
+//!
+//! Build a shared library with
+//! `cargo rustc --release --lib --features ffi --crate-type cdylib`; the
+//! declarations are in `include/ssearch.h`. Strings are UTF-8 and
+//! NUL-terminated. Returned strings are owned by the caller and freed with
+//! [`ssearch_string_free`]; on failure functions return NULL and
+//! [`ssearch_last_error`] says why.
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_char};
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::path::Path;
+use std::ptr;
+use std::sync::Arc;
+
+use anyhow::{Context, Result, anyhow};
+use tokio::runtime::Runtime;
+
+use crate::models::{Config, SearchFilter, Tag, parse_tags};
+use crate::services::{
+    EmbeddingClient, EmbeddingProvider, IndexService, SearchService, TextChunker, VectorStore,
+    create_backend_with_embedding_config,
+};
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// A loaded configuration with its runtime and services, from [`ssearch_init`].
+pub struct SsearchHandle {
+    runtime: Runtime,
+    search: SearchService,
+    index: IndexService,
+}
+
+fn set_last_error(message: String) {
+    let message = CString::new(message.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|error| *error.borrow_mut() = Some(message));
+}
+
+fn panic_message(panic: Box<dyn Any + Send>) -> String {
+    let message = panic
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| panic.downcast_ref::<String>().cloned())
+        .unwrap_or_default();
+    format!("panicked: {message}")
+}
+
+/// Run `f`, recording an error or panic as the last error instead of
+/// letting it cross the C boundary.
+fn guard<T>(f: impl FnOnce() -> Result<T>) -> Option<T> {
+    let message = match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(value)) => return Some(value),
+        Ok(Err(e)) => format!("{e:#}"),
+        Err(panic) => panic_message(panic),
+    };
+    set_last_error(message);
+    None
+}
+
+/// Borrow a string argument; NULL is `None`.
+///
+/// # Safety
+///
+/// `ptr` is NULL or a NUL-terminated string that outlives `'a`.
+unsafe fn optional_str<'a>(ptr: *const c_char, name: &str) -> Result<Option<&'a str>> {
+    if ptr.is_null() {
+        return Ok(None);
+    }
+    unsafe { CStr::from_ptr(ptr) }
+        .to_str()
+        .map(Some)
+        .with_context(|| format!("{name} is not valid UTF-8"))
+}
+
+/// # Safety
+///
+/// As [`optional_str`].
+unsafe fn required_str<'a>(ptr: *const c_char, name: &str) -> Result<&'a str> {
+    unsafe { optional_str(ptr, name) }?.ok_or_else(|| anyhow!("{name} is NULL"))
+}
+
+/// # Safety
+///
+/// `ptr` is NULL or a live handle from [`ssearch_init`].
+unsafe fn handle<'a>(ptr: *const SsearchHandle) -> Result<&'a SsearchHandle> {
+    unsafe { ptr.as_ref() }.context("handle is NULL")
+}
+
+/// Parse a comma-separated tag list argument; NULL is no tags.
+///
+/// # Safety
+///
+/// As [`optional_str`].
+unsafe fn tag_list(tags: *const c_char) -> Result<Vec<Tag>> {
+    match unsafe { optional_str(tags, "tags") }? {
+        Some(tags) => parse_tags(tags).context("invalid tags"),
+        None => Ok(Vec::new()),
+    }
+}
+
+fn into_c_string(json: String) -> Result<*mut c_char> {
+    Ok(CString::new(json)?.into_raw())
+}
+
+/// Load the configuration and connect to the vector store.
+///
+/// `config_path` names a `config.toml` to use alone; NULL loads the global
+/// and nearest project configuration as the CLI does. Returns NULL on
+/// failure.
+///
+/// # Safety
+///
+/// `config_path` is NULL or a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ssearch_init(config_path: *const c_char) -> *mut SsearchHandle {
+    guard(|| {
+        let config = match unsafe { optional_str(config_path, "config_path") }? {
+            Some(path) => Config::load_file(Path::new(path))
+                .with_context(|| format!("failed to load {path}"))?,
+            None => Config::load()?.config,
+        };
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .context("failed to start runtime")?;
+        let store: Arc<dyn VectorStore> = Arc::from(runtime.block_on(
+            create_backend_with_embedding_config(&config.vector_store, &config.embedding),
+        )?);
+        let embedder: Arc<dyn EmbeddingProvider> = Arc::new(EmbeddingClient::new(&config));
+        let search = SearchService::new(embedder.clone(), store.clone(), config.search.clone());
+        let index = IndexService::new(embedder, store, TextChunker::from_config(&config))
+            .with_config(&config);
+        Ok(Box::into_raw(Box::new(SsearchHandle {
+            runtime,
+            search,
+            index,
+        })))
+    })
+    .unwrap_or(ptr::null_mut())
+}
+
+/// Search for `query` and return the results as JSON (`query`, `results`,
+/// `total`, `duration_ms`).
+///
+/// `limit` 0 uses `[search] default_limit`; `tags` is NULL or a
+/// comma-separated list such as `"team:infra,source:jira"`. Returns NULL on
+/// failure.
+///
+/// # Safety
+///
+/// `handle` is a live handle from [`ssearch_init`]; `query` and `tags` are
+/// NULL or NUL-terminated strings.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ssearch_search(
+    handle: *const SsearchHandle,
+    query: *const c_char,
+    limit: u32,
+    tags: *const c_char,
+) -> *mut c_char {
+    guard(|| {
+        let handle = unsafe { self::handle(handle) }?;
+        let query = unsafe { required_str(query, "query") }?;
+        let mut options = handle
+            .search
+            .options()
+            .with_filter(SearchFilter::new().with_tags(unsafe { tag_list(tags) }?));
+        if limit > 0 {
+            options = options.with_limit(limit);
+        }
+        let results = handle
+            .runtime
+            .block_on(handle.search.search(query, &options))?;
+        into_c_string(serde_json::to_string(&results)?)
+    })
+    .unwrap_or(ptr::null_mut())
+}
+
+/// Index the text files under `path` (or the file itself), tagged with
+/// `tags`, and return a JSON summary (`documents_indexed`,
+/// `documents_skipped`, `chunks_created`, `chunks_stored`).
+///
+/// `tags` is NULL or a comma-separated list. Returns NULL on failure.
+///
+/// # Safety
+///
+/// `handle` is a live handle from [`ssearch_init`]; `path` and `tags` are
+/// NULL or NUL-terminated strings.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ssearch_index(
+    handle: *const SsearchHandle,
+    path: *const c_char,
+    tags: *const c_char,
+) -> *mut c_char {
+    guard(|| {
+        let handle = unsafe { self::handle(handle) }?;
+        let path = unsafe { required_str(path, "path") }?;
+        let tags = unsafe { tag_list(tags) }?;
+        let summary = handle
+            .runtime
+            .block_on(handle.index.index_path(Path::new(path), &tags))?;
+        into_c_string(serde_json::to_string(&summary)?)
+    })
+    .unwrap_or(ptr::null_mut())
+}
+
+/// The error of the last call on this thread that returned NULL, or NULL.
+///
+/// The string is owned by the library and valid until the next failing
+/// call on the same thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn ssearch_last_error() -> *const c_char {
+    LAST_ERROR.with(|error| error.borrow().as_ref().map_or(ptr::null(), |e| e.as_ptr()))
+}
+
+/// Free a string returned by [`ssearch_search`] or [`ssearch_index`].
+///
+/// # Safety
+///
+/// `s` is NULL or a string from this library that hasn't been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ssearch_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(unsafe { CString::from_raw(s) });
+    }
+}
+
+/// Release a handle from [`ssearch_init`].
+///
+/// # Safety
+///
+/// `handle` is NULL or a handle that hasn't been freed, and no call using
+/// it is in progress.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ssearch_free(handle: *mut SsearchHandle) {
+    if !handle.is_null() {
+        drop(unsafe { Box::from_raw(handle) });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn last_error() -> String {
+        let error = ssearch_last_error();
+        assert!(!error.is_null());
+        unsafe { CStr::from_ptr(error) }
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[test]
+    fn test_errors_become_last_error() {
+        let missing = CString::new("/nonexistent/ssearch/config.toml").unwrap();
+        let handle = unsafe { ssearch_init(missing.as_ptr()) };
+        assert!(handle.is_null());
+        assert!(last_error().contains("/nonexistent/ssearch/config.toml"));
+
+        let query = CString::new("token refresh").unwrap();
+        let results = unsafe { ssearch_search(ptr::null(), query.as_ptr(), 5, ptr::null()) };
+        assert!(results.is_null());
+        assert_eq!(last_error(), "handle is NULL");
+
+        let invalid = CString::new("no-colon").unwrap();
+        assert!(unsafe { tag_list(invalid.as_ptr()) }.is_err());
+        let tags = CString::new("team:infra, lang:rust").unwrap();
+        assert_eq!(unsafe { tag_list(tags.as_ptr()) }.unwrap().len(), 2);
+        assert!(unsafe { tag_list(ptr::null()) }.unwrap().is_empty());
+
+        assert!(guard(|| -> Result<()> { panic!("boom") }).is_none());
+        assert_eq!(last_error(), "panicked: boom");
+
+        // NULL is accepted by both release functions
+        unsafe {
+            ssearch_string_free(ptr::null_mut());
+            ssearch_free(ptr::null_mut());
+        }
+    }
+}
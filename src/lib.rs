@@ -1,12 +1,17 @@
 pub mod cli;
 pub mod client;
 pub mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod logging;
 pub mod models;
 pub mod server;
 pub mod services;
 pub mod sources;
+#[cfg(any(test, feature = "test-util"))]
+pub mod testing;
 pub mod utils;
 
 pub use cli::{Cli, Commands};
-pub use error::AppError;
+pub use error::{AppError, ExitStatus};
 pub use models::{Config, OutputFormat};
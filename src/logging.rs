@@ -0,0 +1,75 @@
+//! Tracing subscriber setup for the CLI and daemon.
+
+use std::io::IsTerminal;
+
+use tracing::Level;
+use tracing_subscriber::EnvFilter;
+use tracing_subscriber::filter::ParseError;
+use tracing_subscriber::fmt::format::FmtSpan;
+
+use crate::models::LogFormat;
+
+/// Environment variable holding the log level or filter directives.
+pub const LOG_ENV: &str = "SSEARCH_LOG";
+
+/// Dependencies stay at warn; this crate logs progress at info.
+const DEFAULT_DIRECTIVES: &str = "warn,ssearch=info";
+
+/// Turn a `--log-level` value into filter directives.
+///
+/// A bare level (`debug`, `trace`, `off`, ...) applies to this crate only so
+/// dependencies don't flood the output; anything else is passed through as
+/// `EnvFilter` directives (e.g. `ssearch=debug,sqlx=info`).
+pub fn directives(level: Option<&str>, verbose: bool) -> String {
+    match level.map(str::trim).filter(|l| !l.is_empty()) {
+        Some(level) if level.eq_ignore_ascii_case("off") || level.parse::<Level>().is_ok() => {
+            format!("warn,ssearch={}", level.to_lowercase())
+        }
+        Some(directives) => directives.to_string(),
+        None if verbose => "warn,ssearch=debug".to_string(),
+        None => DEFAULT_DIRECTIVES.to_string(),
+    }
+}
+
+/// Install the global subscriber writing to stderr.
+///
+/// Span close events are logged, so every request span reports its duration.
+pub fn init(level: Option<&str>, verbose: bool, format: LogFormat) -> Result<(), ParseError> {
+    let filter = EnvFilter::try_new(directives(level, verbose))?;
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .with_span_events(FmtSpan::CLOSE);
+
+    // A global subscriber may already be installed (e.g. by tests); keep it
+    let _ = match format {
+        LogFormat::Text => builder
+            .with_ansi(std::io::stderr().is_terminal())
+            .with_target(false)
+            .try_init(),
+        LogFormat::Json => builder
+            .json()
+            .with_current_span(true)
+            .with_span_list(false)
+            .try_init(),
+    };
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_directives() {
+        assert_eq!(directives(None, false), DEFAULT_DIRECTIVES);
+        assert_eq!(directives(None, true), "warn,ssearch=debug");
+        assert_eq!(directives(Some("DEBUG"), false), "warn,ssearch=debug");
+        assert_eq!(directives(Some("off"), true), "warn,ssearch=off");
+        assert_eq!(
+            directives(Some("ssearch=trace,sqlx=info"), false),
+            "ssearch=trace,sqlx=info"
+        );
+        assert!(EnvFilter::try_new(directives(Some("info"), false)).is_ok());
+    }
+}
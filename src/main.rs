@@ -1,15 +1,21 @@
 use std::path::Path;
+use std::process::ExitCode;
 
-use anyhow::Result;
-use clap::Parser;
+use anyhow::{Context, Result};
+use clap::{CommandFactory, Parser};
+use clap_complete::CompleteEnv;
 use tokio::signal;
 
+use ssearch::ExitStatus;
 use ssearch::cli::commands::{
-    handle_config, handle_import, handle_index, handle_search, handle_serve, handle_source,
-    handle_status, handle_tags,
+    LogArgs, handle_alias, handle_ask, handle_collection, handle_config, handle_eval,
+    handle_export, handle_health, handle_history, handle_import, handle_index, handle_query,
+    handle_search, handle_serve, handle_source, handle_status, handle_tags,
 };
+use ssearch::cli::completion::{COMPLETE_ENV, write_registration};
 use ssearch::cli::{Cli, Commands};
-use ssearch::models::Config;
+use ssearch::logging;
+use ssearch::models::{Config, LogFormat, OutputFormat};
 
 /// Detect ONNX Runtime library path and set ORT_DYLIB_PATH if not already set.
 /// Must be called before any ort code runs.
@@ -56,56 +62,140 @@ fn detect_and_set_ort_path() {
 }
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> ExitCode {
     detect_and_set_ort_path();
-
-    let cli = Cli::parse();
+    CompleteEnv::with_factory(Cli::command)
+        .var(COMPLETE_ENV)
+        .complete();
+
+    let cli = match Cli::try_parse() {
+        Ok(cli) => cli,
+        Err(e) => {
+            let _ = e.print();
+            // --help and --version also arrive as errors, on stdout
+            return if e.use_stderr() {
+                ExitCode::from(ExitStatus::InvalidInput.code())
+            } else {
+                ExitCode::SUCCESS
+            };
+        }
+    };
     let resolved = Config::load().unwrap_or_default();
     let format = cli.format.unwrap_or(resolved.config.search.default_format);
     let verbose = cli.verbose;
 
-    tokio::select! {
-        result = run_command(cli.command, format, verbose) => {
-            result?;
-        }
+    // Only the daemon defaults to the configured format; CLI output stays readable
+    let log_format = cli.log_format.unwrap_or(match cli.command {
+        Commands::Serve(_) => resolved.config.daemon.log_format,
+        _ => LogFormat::Text,
+    });
+    if let Err(e) = logging::init(cli.log_level.as_deref(), verbose, log_format)
+        .context("invalid --log-level or SSEARCH_LOG")
+    {
+        return report_error(&e, format);
+    }
+
+    let log_args = LogArgs {
+        level: cli.log_level,
+        format: cli.log_format,
+    };
+
+    let result = tokio::select! {
+        result = run_command(cli.command, format, verbose, log_args) => result,
         _ = shutdown_signal() => {
-            eprintln!("\nReceived shutdown signal, cleaning up...");
+            tracing::info!("received shutdown signal, cleaning up");
             tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+            Ok(())
         }
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => report_error(&e, format),
     }
+}
 
-    Ok(())
+/// Print an error, as JSON on stderr with `--format json`, and map it to
+/// its documented exit status.
+fn report_error(error: &anyhow::Error, format: OutputFormat) -> ExitCode {
+    let status = ExitStatus::of(error);
+    // An empty result list was already printed; the status alone says it
+    if status != ExitStatus::NoResults {
+        if format == OutputFormat::Json {
+            let causes: Vec<String> = error.chain().skip(1).map(|c| c.to_string()).collect();
+            let body = serde_json::json!({
+                "error": {
+                    "kind": status,
+                    "code": status.code(),
+                    "message": error.to_string(),
+                    "causes": causes,
+                }
+            });
+            eprintln!("{}", body);
+        } else {
+            eprintln!("Error: {:?}", error);
+        }
+    }
+    ExitCode::from(status.code())
 }
 
 async fn run_command(
     command: Commands,
     format: ssearch::models::OutputFormat,
     verbose: bool,
+    log_args: LogArgs,
 ) -> Result<()> {
     match command {
         Commands::Status => {
             handle_status(format, verbose).await?;
         }
+        Commands::Health => {
+            handle_health(format, verbose).await?;
+        }
         Commands::Index(cmd) => {
             handle_index(cmd, format, verbose).await?;
         }
         Commands::Search(args) => {
             handle_search(args, format, verbose).await?;
         }
+        Commands::Ask(args) => {
+            handle_ask(args, format, verbose).await?;
+        }
+        Commands::Query(args) => {
+            handle_query(args, format, verbose).await?;
+        }
+        Commands::History(args) => {
+            handle_history(args, format, verbose).await?;
+        }
+        Commands::Eval(args) => {
+            handle_eval(args, format, verbose).await?;
+        }
         Commands::Config(cmd) => {
             handle_config(cmd, format, verbose).await?;
         }
         Commands::Tags(cmd) => {
             handle_tags(cmd, format, verbose).await?;
         }
+        Commands::Collection(cmd) => {
+            handle_collection(cmd, format, verbose).await?;
+        }
+        Commands::Alias(cmd) => {
+            handle_alias(cmd, format, verbose).await?;
+        }
         Commands::Import(args) => {
             handle_import(args, format, verbose).await?;
         }
+        Commands::Export(args) => {
+            handle_export(args, format, verbose).await?;
+        }
         Commands::Source(cmd) => {
             handle_source(cmd, format, verbose).await?;
         }
         Commands::Serve(args) => {
-            handle_serve(args).await?;
+            handle_serve(args, format, log_args).await?;
+        }
+        Commands::Completions { shell } => {
+            write_registration(&shell.to_string(), &mut std::io::stdout())?;
         }
     }
 
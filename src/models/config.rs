@@ -1,17 +1,82 @@
+use chrono::TimeDelta;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fmt;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
-use super::search::OutputFormat;
+use super::search::{OutputFormat, parse_age};
+use super::source::SourceType;
+use super::tag::TagTemplate;
+use crate::error::{ConfigError, TagError};
+use crate::utils::text::MIN_CONTENT_LENGTH;
 
 pub const DEFAULT_QDRANT_URL: &str = "http://localhost:16334";
 pub const DEFAULT_COLLECTION: &str = "semantic_search";
 pub const DEFAULT_EMBEDDING_MODEL: &str = "JunyeongAI/qwen3-embedding-0.6b-onnx";
 pub const DEFAULT_EMBEDDING_DIMENSION: u32 = 1024;
 pub const DEFAULT_MAX_TOKENS: u32 = 2048;
+
+/// Instruction prefixes of known instruction-tuned embedding models:
+/// (model name prefix, query instruction, document instruction).
+/// Names are matched case-insensitively, ignoring the `org/` part of the ID.
+const MODEL_INSTRUCTIONS: &[(&str, &str, &str)] = &[
+    (
+        "qwen3-embedding",
+        "Instruct: Given a search query, retrieve relevant passages\nQuery: ",
+        "",
+    ),
+    ("multilingual-e5-", "query: ", "passage: "),
+    ("e5-", "query: ", "passage: "),
+    (
+        "bge-small-en",
+        "Represent this sentence for searching relevant passages: ",
+        "",
+    ),
+    (
+        "bge-base-en",
+        "Represent this sentence for searching relevant passages: ",
+        "",
+    ),
+    (
+        "bge-large-en",
+        "Represent this sentence for searching relevant passages: ",
+        "",
+    ),
+    (
+        "mxbai-embed-large",
+        "Represent this sentence for searching relevant passages: ",
+        "",
+    ),
+    ("nomic-embed-text", "search_query: ", "search_document: "),
+    (
+        "snowflake-arctic-embed",
+        "Represent this sentence for searching relevant passages: ",
+        "",
+    ),
+];
+
+/// Default (query, document) instructions for a model; empty for models not in the registry.
+fn model_instructions(model_id: &str) -> (&'static str, &'static str) {
+    let name = model_id
+        .rsplit('/')
+        .next()
+        .unwrap_or(model_id)
+        .to_ascii_lowercase();
+    MODEL_INSTRUCTIONS
+        .iter()
+        .find(|(prefix, _, _)| name.starts_with(prefix))
+        .map_or(("", ""), |&(_, query, document)| (query, document))
+}
+pub const DEFAULT_RERANK_MODEL: &str = "cross-encoder/ms-marco-MiniLM-L-6-v2";
+pub const DEFAULT_RERANK_TOP_K: u32 = 50;
+pub const DEFAULT_RECENCY_HALF_LIFE_DAYS: f32 = 30.0;
 pub const DEFAULT_IDLE_TIMEOUT_SECS: u64 = 600;
 pub const DEFAULT_METRICS_RETENTION_DAYS: u32 = 30;
+pub const DEFAULT_LLM_MODEL: &str = "llama3.1";
+
+/// Attribute key that records which workspace a chunk was indexed from.
+pub const WORKSPACE_ATTRIBUTE: &str = "workspace";
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -31,6 +96,33 @@ impl fmt::Display for VectorDriver {
     }
 }
 
+impl VectorDriver {
+    /// Collection names become Qdrant collection or PostgreSQL table names,
+    /// so only letters, digits, `_` and `-` (not `-` on PostgreSQL) are allowed.
+    pub fn validate_collection_name(self, name: &str) -> Result<(), crate::error::ConfigError> {
+        let valid_chars = name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+        if name.is_empty() || !valid_chars {
+            return Err(crate::error::ConfigError::ValidationError(format!(
+                "invalid collection name '{}': use letters, digits, '_' and '-'",
+                name
+            )));
+        }
+
+        if self == VectorDriver::PostgreSQL
+            && (name.contains('-') || name.starts_with(|c: char| c.is_ascii_digit()))
+        {
+            return Err(crate::error::ConfigError::ValidationError(format!(
+                "invalid collection name '{}' for PostgreSQL: use letters, digits and '_', starting with a letter",
+                name
+            )));
+        }
+
+        Ok(())
+    }
+}
+
 impl FromStr for VectorDriver {
     type Err = String;
 
@@ -43,6 +135,411 @@ impl FromStr for VectorDriver {
     }
 }
 
+/// How documents are split into chunks before embedding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChunkStrategy {
+    /// Pick markdown or code splitting from the document's language
+    #[default]
+    Auto,
+    /// Fixed-size character windows with overlap
+    Chars,
+    /// Split at markdown headings
+    Markdown,
+    /// Split at function/class boundaries using tree-sitter
+    Code,
+    /// Split at sentence ends and paragraph breaks
+    Sentence,
+}
+
+impl fmt::Display for ChunkStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChunkStrategy::Auto => write!(f, "auto"),
+            ChunkStrategy::Chars => write!(f, "chars"),
+            ChunkStrategy::Markdown => write!(f, "markdown"),
+            ChunkStrategy::Code => write!(f, "code"),
+            ChunkStrategy::Sentence => write!(f, "sentence"),
+        }
+    }
+}
+
+impl FromStr for ChunkStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "auto" => Ok(ChunkStrategy::Auto),
+            "chars" => Ok(ChunkStrategy::Chars),
+            "markdown" | "md" => Ok(ChunkStrategy::Markdown),
+            "code" => Ok(ChunkStrategy::Code),
+            "sentence" | "sentences" => Ok(ChunkStrategy::Sentence),
+            _ => Err(format!("unknown chunk strategy: {}", s)),
+        }
+    }
+}
+
+/// How chunk sizes are measured in tokens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChunkTokenizer {
+    /// Approximate 4 characters per token
+    #[default]
+    Estimate,
+    /// Count with the embedding model's tokenizer and split at token boundaries
+    Model,
+}
+
+impl fmt::Display for ChunkTokenizer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChunkTokenizer::Estimate => write!(f, "estimate"),
+            ChunkTokenizer::Model => write!(f, "model"),
+        }
+    }
+}
+
+impl FromStr for ChunkTokenizer {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "estimate" => Ok(ChunkTokenizer::Estimate),
+            "model" => Ok(ChunkTokenizer::Model),
+            _ => Err(format!("unknown chunk tokenizer: {}", s)),
+        }
+    }
+}
+
+/// How `index add` writes a per-document summary chunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SummaryMode {
+    /// No summary chunks
+    #[default]
+    Off,
+    /// Title and the leading sentences of the document
+    Extractive,
+    /// Summary written by the `[llm]` model
+    Llm,
+}
+
+impl fmt::Display for SummaryMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SummaryMode::Off => write!(f, "off"),
+            SummaryMode::Extractive => write!(f, "extractive"),
+            SummaryMode::Llm => write!(f, "llm"),
+        }
+    }
+}
+
+impl FromStr for SummaryMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "off" | "none" => Ok(SummaryMode::Off),
+            "extractive" => Ok(SummaryMode::Extractive),
+            "llm" => Ok(SummaryMode::Llm),
+            _ => Err(format!("unknown summary mode: {}", s)),
+        }
+    }
+}
+
+/// What indexing does with documents containing personal data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PiiPolicy {
+    /// Index documents as they are
+    #[default]
+    Ignore,
+    /// Tag documents with personal data `pii:true`
+    Flag,
+    /// Leave documents with personal data out of the index
+    Skip,
+}
+
+impl fmt::Display for PiiPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PiiPolicy::Ignore => write!(f, "ignore"),
+            PiiPolicy::Flag => write!(f, "flag"),
+            PiiPolicy::Skip => write!(f, "skip"),
+        }
+    }
+}
+
+impl FromStr for PiiPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "ignore" | "off" => Ok(PiiPolicy::Ignore),
+            "flag" | "tag" => Ok(PiiPolicy::Flag),
+            "skip" => Ok(PiiPolicy::Skip),
+            _ => Err(format!("unknown pii policy: {}", s)),
+        }
+    }
+}
+
+/// What indexing does with short and boilerplate chunks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FillerPolicy {
+    /// Leave them out of the index
+    #[default]
+    Skip,
+    /// Index them tagged `filler:short` or `filler:boilerplate`
+    Flag,
+}
+
+impl fmt::Display for FillerPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FillerPolicy::Skip => write!(f, "skip"),
+            FillerPolicy::Flag => write!(f, "flag"),
+        }
+    }
+}
+
+impl FromStr for FillerPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "skip" => Ok(FillerPolicy::Skip),
+            "flag" | "tag" => Ok(FillerPolicy::Flag),
+            _ => Err(format!("unknown filler policy: {}", s)),
+        }
+    }
+}
+
+/// Compressed vector representation kept alongside the full-precision vectors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Quantization {
+    /// Full-precision float32 vectors only
+    #[default]
+    None,
+    /// Qdrant scalar int8 quantization; pgvector halfvec index
+    Int8,
+    /// 1 bit per dimension; best with high-dimensional models
+    Binary,
+}
+
+impl Quantization {
+    /// Candidates fetched per requested result before rescoring with full vectors.
+    pub fn oversampling(self) -> u64 {
+        match self {
+            Quantization::None => 1,
+            Quantization::Int8 => 2,
+            Quantization::Binary => 4,
+        }
+    }
+}
+
+impl fmt::Display for Quantization {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Quantization::None => write!(f, "none"),
+            Quantization::Int8 => write!(f, "int8"),
+            Quantization::Binary => write!(f, "binary"),
+        }
+    }
+}
+
+impl FromStr for Quantization {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "none" => Ok(Quantization::None),
+            "int8" | "scalar" => Ok(Quantization::Int8),
+            "binary" | "bit" => Ok(Quantization::Binary),
+            _ => Err(format!("unknown quantization: {}", s)),
+        }
+    }
+}
+
+/// Approximate nearest-neighbour index built by pgvector.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnnIndex {
+    /// Navigable small-world graph; good recall, can be built on an empty table
+    #[default]
+    Hnsw,
+    /// Inverted lists; faster to build, but should be rebuilt after bulk loads
+    Ivfflat,
+}
+
+impl fmt::Display for AnnIndex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnnIndex::Hnsw => write!(f, "hnsw"),
+            AnnIndex::Ivfflat => write!(f, "ivfflat"),
+        }
+    }
+}
+
+impl FromStr for AnnIndex {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "hnsw" => Ok(AnnIndex::Hnsw),
+            "ivfflat" | "ivf" => Ok(AnnIndex::Ivfflat),
+            _ => Err(format!("unknown index type: {}", s)),
+        }
+    }
+}
+
+/// Log line encoding written to stderr.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Human-readable lines
+    #[default]
+    Text,
+    /// One JSON object per event, for log collectors
+    Json,
+}
+
+impl fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LogFormat::Text => write!(f, "text"),
+            LogFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            _ => Err(format!("unknown log format: {}", s)),
+        }
+    }
+}
+
+/// How `search --expand` reformulates the query before searching.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QueryExpansion {
+    /// Search the query as written
+    #[default]
+    Off,
+    /// Abbreviation expansion and phrasing templates
+    Heuristic,
+    /// Reformulations written by the `[llm]` model
+    Llm,
+}
+
+impl fmt::Display for QueryExpansion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryExpansion::Off => write!(f, "off"),
+            QueryExpansion::Heuristic => write!(f, "heuristic"),
+            QueryExpansion::Llm => write!(f, "llm"),
+        }
+    }
+}
+
+impl FromStr for QueryExpansion {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "off" | "none" => Ok(QueryExpansion::Off),
+            "heuristic" => Ok(QueryExpansion::Heuristic),
+            "llm" => Ok(QueryExpansion::Llm),
+            _ => Err(format!("unknown query expansion mode: {}", s)),
+        }
+    }
+}
+
+/// Chat API dialect of the LLM endpoint `ssearch ask` calls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LlmProvider {
+    /// Local Ollama server (`POST /api/chat`)
+    #[default]
+    Ollama,
+    /// OpenAI or any compatible server (`POST /chat/completions`)
+    OpenAi,
+}
+
+impl LlmProvider {
+    /// Base URL used when `[llm] url` is unset.
+    pub fn default_url(self) -> &'static str {
+        match self {
+            LlmProvider::Ollama => "http://localhost:11434",
+            LlmProvider::OpenAi => "https://api.openai.com/v1",
+        }
+    }
+}
+
+impl fmt::Display for LlmProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LlmProvider::Ollama => write!(f, "ollama"),
+            LlmProvider::OpenAi => write!(f, "openai"),
+        }
+    }
+}
+
+impl FromStr for LlmProvider {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "ollama" => Ok(LlmProvider::Ollama),
+            "openai" => Ok(LlmProvider::OpenAi),
+            _ => Err(format!("unknown LLM provider: {}", s)),
+        }
+    }
+}
+
+/// Hardware used for ONNX inference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Device {
+    #[default]
+    Cpu,
+    Cuda,
+    CoreMl,
+    DirectMl,
+}
+
+impl fmt::Display for Device {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Device::Cpu => write!(f, "cpu"),
+            Device::Cuda => write!(f, "cuda"),
+            Device::CoreMl => write!(f, "coreml"),
+            Device::DirectMl => write!(f, "directml"),
+        }
+    }
+}
+
+impl FromStr for Device {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "cpu" => Ok(Device::Cpu),
+            "cuda" | "gpu" => Ok(Device::Cuda),
+            "coreml" => Ok(Device::CoreMl),
+            "directml" | "dml" => Ok(Device::DirectMl),
+            _ => Err(format!("unknown device: {}", s)),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum ConfigSource {
     #[default]
@@ -69,16 +566,20 @@ pub struct ConfigSources {
     pub embedding_dimension: ConfigSource,
     pub embedding_batch_size: ConfigSource,
     pub embedding_max_tokens: ConfigSource,
+    pub embedding_sessions: ConfigSource,
+    pub embedding_device: ConfigSource,
     pub vector_store_driver: ConfigSource,
     pub vector_store_url: ConfigSource,
     pub vector_store_collection: ConfigSource,
     pub vector_store_api_key: ConfigSource,
     pub indexing_chunk_size: ConfigSource,
     pub indexing_chunk_overlap: ConfigSource,
+    pub indexing_strategy: ConfigSource,
     pub indexing_exclude_patterns: ConfigSource,
     pub indexing_max_file_size: ConfigSource,
     pub search_default_limit: ConfigSource,
     pub search_default_format: ConfigSource,
+    pub search_rerank: ConfigSource,
     pub daemon_idle_timeout: ConfigSource,
     pub daemon_auto_start: ConfigSource,
     pub metrics_enabled: ConfigSource,
@@ -112,6 +613,29 @@ pub struct Config {
 
     #[serde(default)]
     pub metrics: MetricsConfig,
+
+    #[serde(default)]
+    pub workspace: WorkspaceConfig,
+
+    #[serde(default)]
+    pub llm: LlmConfig,
+
+    /// Named collections with per-collection overrides
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub collections: BTreeMap<String, CollectionConfig>,
+
+    /// Recurring source syncs (`[[sync]]`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sync: Vec<SyncJob>,
+
+    /// Default tag templates per source type (`[tags]`), e.g.
+    /// `jira = ["team:{jira-project}"]`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tags: BTreeMap<String, Vec<String>>,
+
+    /// Saved searches (`[aliases.<name>]`), run with `alias run <name>`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub aliases: BTreeMap<String, SavedQuery>,
 }
 
 impl Config {
@@ -143,43 +667,295 @@ impl Config {
         }
     }
 
-    pub fn project_config_dir() -> Option<PathBuf> {
-        std::env::current_dir()
-            .ok()
-            .map(|p| p.join(Self::CONFIG_DIR))
+    pub fn project_config_dir() -> Option<PathBuf> {
+        std::env::current_dir()
+            .ok()
+            .map(|p| p.join(Self::CONFIG_DIR))
+    }
+
+    /// Directory containing the nearest `.ssearch/`, or the current directory.
+    pub fn project_root() -> Option<PathBuf> {
+        Self::find_project_config()
+            .and_then(|p| p.parent()?.parent().map(Path::to_path_buf))
+            .or_else(|| std::env::current_dir().ok())
+    }
+
+    /// `.ssearch/state/` of the nearest project, or of the current directory.
+    pub fn state_dir() -> Option<PathBuf> {
+        Self::find_project_config()
+            .and_then(|p| p.parent().map(Path::to_path_buf))
+            .or_else(Self::project_config_dir)
+            .map(|p| p.join("state"))
+    }
+
+    pub fn load() -> Result<ResolvedConfig, crate::error::ConfigError> {
+        Self::load_with_collection(None)
+    }
+
+    /// Load configuration, switching to `collection` (e.g. from `--collection`) when given.
+    ///
+    /// Overrides from the active collection's `[collections.<name>]` entry are applied last.
+    pub fn load_with_collection(
+        collection: Option<&str>,
+    ) -> Result<ResolvedConfig, crate::error::ConfigError> {
+        dotenvy::dotenv().ok();
+
+        let mut config = Config::default();
+        let mut sources = ConfigSources::default();
+
+        let global_path = Self::global_path();
+        if let Some(ref path) = global_path
+            && path.exists()
+        {
+            let partial = Self::load_partial(path)?;
+            Self::merge_partial(&mut config, &mut sources, &partial, ConfigSource::Global);
+        }
+
+        let project_path = Self::find_project_config();
+        if let Some(ref path) = project_path {
+            let partial = Self::load_partial(path)?;
+            Self::merge_partial(&mut config, &mut sources, &partial, ConfigSource::Project);
+        }
+
+        Self::apply_env_overrides(&mut config, &mut sources);
+
+        if config.workspace.name.is_none() {
+            config.workspace.name = project_path.as_deref().and_then(Self::project_workspace);
+        }
+
+        if let Some(name) = collection {
+            // Interpolated into SQL as a table name by the pgvector backend
+            config.vector_store.driver.validate_collection_name(name)?;
+            config.vector_store.collection = name.to_string();
+        }
+        config.apply_collection_overrides();
+
+        Ok(ResolvedConfig {
+            config,
+            sources,
+            project_path,
+            global_path: global_path.filter(|p| p.exists()),
+        })
+    }
+
+    /// Load configuration from `path` alone, ignoring the global and project
+    /// files; environment overrides still apply.
+    pub fn load_file(path: &Path) -> Result<Config, crate::error::ConfigError> {
+        dotenvy::dotenv().ok();
+
+        let mut config = Config::default();
+        let mut sources = ConfigSources::default();
+        let partial = Self::load_partial(path)?;
+        Self::merge_partial(&mut config, &mut sources, &partial, ConfigSource::Project);
+        Self::apply_env_overrides(&mut config, &mut sources);
+        config.apply_collection_overrides();
+        Ok(config)
+    }
+
+    /// Workspace that indexing stamps and searches filter on, if isolation applies.
+    pub fn workspace(&self) -> Option<&str> {
+        if self.workspace.isolate {
+            self.workspace.name.as_deref()
+        } else {
+            None
+        }
+    }
+
+    /// Parsed `[tags]` templates for documents from `source`.
+    pub fn tag_templates(&self, source: &SourceType) -> Result<Vec<TagTemplate>, TagError> {
+        self.tags
+            .get(&source.to_string())
+            .map(|templates| templates.iter().map(|t| t.parse()).collect())
+            .unwrap_or_else(|| Ok(Vec::new()))
+    }
+
+    /// Inconsistencies in the resolved configuration, each phrased as the
+    /// setting to fix. Empty when the configuration is usable.
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        let embedding = &self.embedding;
+        if embedding.dimension == 0 {
+            problems.push("[embedding] dimension must be greater than 0".to_string());
+        }
+        if embedding.batch_size == 0 {
+            problems.push("[embedding] batch_size must be greater than 0".to_string());
+        }
+
+        let indexing = &self.indexing;
+        if indexing.chunk_size == 0 {
+            problems.push("[indexing] chunk_size must be greater than 0".to_string());
+        } else if indexing.chunk_overlap >= indexing.chunk_size {
+            problems.push(format!(
+                "[indexing] chunk_overlap ({}) must be smaller than chunk_size ({})",
+                indexing.chunk_overlap, indexing.chunk_size
+            ));
+        }
+        for (key, entry) in indexing.overrides.iter() {
+            let size = entry.chunk_size.unwrap_or(indexing.chunk_size);
+            if size == 0 {
+                problems.push(format!(
+                    "[indexing.overrides] {key}: chunk_size must be greater than 0"
+                ));
+            } else if let Some(overlap) = entry.chunk_overlap
+                && overlap >= size
+            {
+                problems.push(format!(
+                    "[indexing.overrides] {key}: chunk_overlap ({overlap}) must be smaller than chunk_size ({size})"
+                ));
+            }
+        }
+        if indexing.boilerplate_min_files == 1 {
+            problems
+                .push("[indexing] boilerplate_min_files must be 0 (off) or at least 2".to_string());
+        }
+        if let Err(e) = indexing.retention.max_ages() {
+            problems.push(format!("[indexing.retention] {}", e));
+        }
+        if indexing.retention.max_points == Some(0) {
+            problems.push("[indexing.retention] max_points must be greater than 0".to_string());
+        }
+
+        let url = &self.vector_store.url;
+        let schemes: &[&str] = match self.vector_store.driver {
+            VectorDriver::Qdrant => &["http", "https"],
+            VectorDriver::PostgreSQL => &["postgres", "postgresql"],
+        };
+        let scheme = url.split_once("://").map(|(scheme, _)| scheme);
+        if !scheme.is_some_and(|s| schemes.contains(&s.to_ascii_lowercase().as_str())) {
+            problems.push(format!(
+                "[vector_store] url \"{}\" must start with {} for the {} driver",
+                url,
+                schemes
+                    .iter()
+                    .map(|s| format!("{}://", s))
+                    .collect::<Vec<_>>()
+                    .join(" or "),
+                self.vector_store.driver
+            ));
+        }
+
+        let vs = &self.vector_store;
+        if vs.upsert_batch_size == 0 {
+            problems.push("[vector_store] upsert_batch_size must be greater than 0".to_string());
+        }
+        if vs.upsert_parallelism == 0 {
+            problems.push("[vector_store] upsert_parallelism must be greater than 0".to_string());
+        }
+        if !(0.0..=1.0).contains(&vs.title_weight) {
+            problems.push("[vector_store] title_weight must be between 0.0 and 1.0".to_string());
+        }
+        if vs.encrypts() && vs.driver != VectorDriver::Qdrant {
+            // PostgreSQL's keyword index is generated from the stored content
+            problems.push(
+                "[vector_store] content encryption is only supported by the qdrant driver"
+                    .to_string(),
+            );
+        }
+
+        // Ranges accepted by pgvector
+        let ann_options = [
+            ("hnsw_m", vs.hnsw_m, 2..=100),
+            ("hnsw_ef_construction", vs.hnsw_ef_construction, 4..=1000),
+            ("ef_search", vs.ef_search, 1..=1000),
+            ("ivfflat_lists", vs.ivfflat_lists, 1..=32768),
+            ("ivfflat_probes", vs.ivfflat_probes, 1..=32768),
+        ];
+        for (key, value, range) in ann_options {
+            if let Some(value) = value
+                && !range.contains(&value)
+            {
+                problems.push(format!(
+                    "[vector_store] {} ({}) must be between {} and {}",
+                    key,
+                    value,
+                    range.start(),
+                    range.end()
+                ));
+            }
+        }
+        if let Some(ef_construction) = vs.hnsw_ef_construction
+            && ef_construction < 2 * vs.hnsw_m.unwrap_or(16)
+        {
+            problems.push(format!(
+                "[vector_store] hnsw_ef_construction ({}) must be at least twice hnsw_m ({})",
+                ef_construction,
+                vs.hnsw_m.unwrap_or(16)
+            ));
+        }
+
+        for (source, templates) in &self.tags {
+            for template in templates {
+                if let Err(e) = template.parse::<TagTemplate>() {
+                    problems.push(format!("[tags] {}: {}", source, e));
+                }
+            }
+        }
+
+        let search = &self.search;
+        if search.default_limit == 0 {
+            problems.push("[search] default_limit must be greater than 0".to_string());
+        }
+        let fractions = [
+            ("default_min_score", search.default_min_score),
+            ("recency_weight", Some(search.recency_weight)),
+            ("dedup_threshold", search.dedup_threshold),
+        ];
+        for (key, value) in fractions {
+            if let Some(value) = value
+                && !(0.0..=1.0).contains(&value)
+            {
+                problems.push(format!(
+                    "[search] {} ({}) must be between 0.0 and 1.0",
+                    key, value
+                ));
+            }
+        }
+
+        let daemon = &self.daemon;
+        let rates = [
+            ("rate_limit", daemon.rate_limit),
+            ("client_rate_limit", daemon.client_rate_limit),
+            ("rate_limit_burst", daemon.rate_limit_burst),
+        ];
+        for (key, value) in rates {
+            if let Some(value) = value
+                && !(value > 0.0 && value.is_finite())
+            {
+                problems.push(format!(
+                    "[daemon] {} ({}) must be greater than 0",
+                    key, value
+                ));
+            }
+        }
+        problems
+    }
+
+    /// Workspace name for a project config: the directory containing `.ssearch/`.
+    fn project_workspace(config_path: &Path) -> Option<String> {
+        let root = config_path.parent()?.parent()?;
+        Some(root.to_string_lossy().into_owned())
     }
 
-    pub fn load() -> Result<ResolvedConfig, crate::error::ConfigError> {
-        dotenvy::dotenv().ok();
-
-        let mut config = Config::default();
-        let mut sources = ConfigSources::default();
-
-        let global_path = Self::global_path();
-        if let Some(ref path) = global_path
-            && path.exists()
-        {
-            let partial = Self::load_partial(path)?;
-            Self::merge_partial(&mut config, &mut sources, &partial, ConfigSource::Global);
+    /// Apply the active collection's embedding overrides, if it has any.
+    fn apply_collection_overrides(&mut self) {
+        let Some(collection) = self.collections.get(&self.vector_store.collection) else {
+            return;
+        };
+        if let Some(ref model_id) = collection.model_id {
+            // Explicit instructions belong to the `[embedding]` model, not this one
+            if *model_id != self.embedding.model_id {
+                self.embedding.query_instruction = None;
+                self.embedding.document_instruction = None;
+            }
+            self.embedding.model_id = model_id.clone();
+            self.embedding.model_path = collection.model_path.clone();
         }
-
-        let project_path = Self::find_project_config();
-        if let Some(ref path) = project_path {
-            let partial = Self::load_partial(path)?;
-            Self::merge_partial(&mut config, &mut sources, &partial, ConfigSource::Project);
+        if let Some(dimension) = collection.dimension {
+            self.embedding.dimension = dimension;
         }
-
-        Self::apply_env_overrides(&mut config, &mut sources);
-
-        Ok(ResolvedConfig {
-            config,
-            sources,
-            project_path,
-            global_path: global_path.filter(|p| p.exists()),
-        })
     }
 
-    fn load_partial(path: &Path) -> Result<PartialConfig, crate::error::ConfigError> {
+    pub fn load_partial(path: &Path) -> Result<PartialConfig, crate::error::ConfigError> {
         let content = std::fs::read_to_string(path)?;
         let partial: PartialConfig = toml::from_str(&content)?;
         Ok(partial)
@@ -208,9 +984,51 @@ impl Config {
                 config.embedding.max_tokens = v;
                 sources.embedding_max_tokens = source;
             }
+            if let Some(v) = emb.sessions {
+                config.embedding.sessions = v;
+                sources.embedding_sessions = source;
+            }
+            if let Some(v) = emb.device {
+                config.embedding.device = v;
+                sources.embedding_device = source;
+            }
+            if emb.threads.is_some() {
+                config.embedding.threads = emb.threads;
+            }
             if emb.model_path.is_some() {
                 config.embedding.model_path = emb.model_path.clone();
             }
+            if emb.query_instruction.is_some() {
+                config.embedding.query_instruction = emb.query_instruction.clone();
+            }
+            if emb.document_instruction.is_some() {
+                config.embedding.document_instruction = emb.document_instruction.clone();
+            }
+        }
+
+        if let Some(ref collections) = partial.collections {
+            for (name, collection) in collections {
+                config.collections.insert(name.clone(), collection.clone());
+            }
+        }
+
+        // A project's [tags] entry for a source replaces the global one
+        if let Some(ref tags) = partial.tags {
+            for (source, templates) in tags {
+                config.tags.insert(source.clone(), templates.clone());
+            }
+        }
+
+        // A project's alias replaces the global one of the same name
+        if let Some(ref aliases) = partial.aliases {
+            for (name, alias) in aliases {
+                config.aliases.insert(name.clone(), alias.clone());
+            }
+        }
+
+        // A project's [[sync]] list replaces the global one
+        if let Some(ref sync) = partial.sync {
+            config.sync = sync.clone();
         }
 
         if let Some(ref vs) = partial.vector_store {
@@ -233,12 +1051,63 @@ impl Config {
             if vs.schema.is_some() {
                 config.vector_store.schema = vs.schema.clone();
             }
+            if vs.rest_url.is_some() {
+                config.vector_store.rest_url = vs.rest_url.clone();
+            }
             if let Some(v) = vs.pool_max {
                 config.vector_store.pool_max = v;
             }
             if let Some(v) = vs.pool_acquire_timeout {
                 config.vector_store.pool_acquire_timeout = v;
             }
+            if let Some(v) = vs.upsert_batch_size {
+                config.vector_store.upsert_batch_size = v;
+            }
+            if let Some(v) = vs.upsert_wait {
+                config.vector_store.upsert_wait = v;
+            }
+            if let Some(v) = vs.upsert_parallelism {
+                config.vector_store.upsert_parallelism = v;
+            }
+            if let Some(v) = vs.quantization {
+                config.vector_store.quantization = v;
+            }
+            if let Some(v) = vs.title_vectors {
+                config.vector_store.title_vectors = v;
+            }
+            if let Some(v) = vs.title_weight {
+                config.vector_store.title_weight = v;
+            }
+            if let Some(v) = vs.index_type {
+                config.vector_store.index_type = v;
+            }
+            if vs.hnsw_m.is_some() {
+                config.vector_store.hnsw_m = vs.hnsw_m;
+            }
+            if vs.hnsw_ef_construction.is_some() {
+                config.vector_store.hnsw_ef_construction = vs.hnsw_ef_construction;
+            }
+            if vs.ef_search.is_some() {
+                config.vector_store.ef_search = vs.ef_search;
+            }
+            if vs.ivfflat_lists.is_some() {
+                config.vector_store.ivfflat_lists = vs.ivfflat_lists;
+            }
+            if vs.ivfflat_probes.is_some() {
+                config.vector_store.ivfflat_probes = vs.ivfflat_probes;
+            }
+            if vs.tenant_prefix.is_some() {
+                config.vector_store.tenant_prefix = vs.tenant_prefix.clone();
+            }
+            if let Some(ref v) = vs.tenant_tags {
+                config.vector_store.tenant_tags = v.clone();
+            }
+            if vs.encryption_key.is_some() {
+                config.vector_store.encryption_key = vs.encryption_key.clone();
+            }
+            if vs.encryption_key_command.is_some() {
+                config.vector_store.encryption_key_command = vs.encryption_key_command.clone();
+            }
         }
 
         if let Some(ref idx) = partial.indexing {
@@ -250,6 +1119,10 @@ impl Config {
                 config.indexing.chunk_overlap = v;
                 sources.indexing_chunk_overlap = source;
             }
+            if let Some(v) = idx.strategy {
+                config.indexing.strategy = v;
+                sources.indexing_strategy = source;
+            }
             if let Some(ref v) = idx.exclude_patterns {
                 config.indexing.exclude_patterns = v.clone();
                 sources.indexing_exclude_patterns = source;
@@ -258,6 +1131,50 @@ impl Config {
                 config.indexing.max_file_size = v;
                 sources.indexing_max_file_size = source;
             }
+            if let Some(v) = idx.dedup {
+                config.indexing.dedup = v;
+            }
+            if let Some(v) = idx.redact_secrets {
+                config.indexing.redact_secrets = v;
+            }
+            if let Some(v) = idx.pii {
+                config.indexing.pii = v;
+            }
+            if let Some(v) = idx.min_chunk_length {
+                config.indexing.min_chunk_length = v;
+            }
+            if let Some(v) = idx.boilerplate_min_files {
+                config.indexing.boilerplate_min_files = v;
+            }
+            if let Some(v) = idx.filler {
+                config.indexing.filler = v;
+            }
+            if let Some(v) = idx.tokenizer {
+                config.indexing.tokenizer = v;
+            }
+            if idx.tokenizer_path.is_some() {
+                config.indexing.tokenizer_path = idx.tokenizer_path.clone();
+            }
+            if let Some(v) = idx.max_retries {
+                config.indexing.max_retries = v;
+            }
+            if let Some(v) = idx.summaries {
+                config.indexing.summaries = v;
+            }
+            // A project's entry for a file type replaces the global one
+            if let Some(ref overrides) = idx.overrides {
+                for (key, entry) in overrides.iter() {
+                    config.indexing.overrides.insert(key, *entry);
+                }
+            }
+            // ... and likewise for a source type's max_age
+            if let Some(ref retention) = idx.retention {
+                let merged = &mut config.indexing.retention;
+                merged.max_age.extend(retention.max_age.clone());
+                if retention.max_points.is_some() {
+                    merged.max_points = retention.max_points;
+                }
+            }
         }
 
         if let Some(ref s) = partial.search {
@@ -272,6 +1189,34 @@ impl Config {
             if s.default_min_score.is_some() {
                 config.search.default_min_score = s.default_min_score;
             }
+            if let Some(v) = s.rerank {
+                config.search.rerank = v;
+                sources.search_rerank = source;
+            }
+            if let Some(ref v) = s.rerank_model_id {
+                config.search.rerank_model_id = v.clone();
+            }
+            if s.rerank_model_path.is_some() {
+                config.search.rerank_model_path = s.rerank_model_path.clone();
+            }
+            if let Some(v) = s.rerank_top_k {
+                config.search.rerank_top_k = v;
+            }
+            if let Some(v) = s.recency_weight {
+                config.search.recency_weight = v;
+            }
+            if let Some(v) = s.recency_half_life_days {
+                config.search.recency_half_life_days = v;
+            }
+            if s.dedup_threshold.is_some() {
+                config.search.dedup_threshold = s.dedup_threshold;
+            }
+            if let Some(v) = s.expansion {
+                config.search.expansion = v;
+            }
+            if let Some(v) = s.keyword_fallback {
+                config.search.keyword_fallback = v;
+            }
         }
 
         if let Some(ref d) = partial.daemon {
@@ -286,6 +1231,27 @@ impl Config {
             if d.socket_path.is_some() {
                 config.daemon.socket_path = d.socket_path.clone();
             }
+            if d.http_addr.is_some() {
+                config.daemon.http_addr = d.http_addr.clone();
+            }
+            if let Some(v) = d.log_format {
+                config.daemon.log_format = v;
+            }
+            if d.auth_token.is_some() {
+                config.daemon.auth_token = d.auth_token.clone();
+            }
+            if d.rate_limit.is_some() {
+                config.daemon.rate_limit = d.rate_limit;
+            }
+            if d.client_rate_limit.is_some() {
+                config.daemon.client_rate_limit = d.client_rate_limit;
+            }
+            if d.rate_limit_burst.is_some() {
+                config.daemon.rate_limit_burst = d.rate_limit_burst;
+            }
+            if let Some(v) = d.preload {
+                config.daemon.preload = v;
+            }
         }
 
         if let Some(ref m) = partial.metrics {
@@ -297,6 +1263,42 @@ impl Config {
                 config.metrics.retention_days = v;
                 sources.metrics_retention_days = source;
             }
+            if let Some(v) = m.history {
+                config.metrics.history = v;
+            }
+            if let Some(ref v) = m.http_addr {
+                config.metrics.http_addr = Some(v.clone());
+            }
+        }
+
+        if let Some(ref w) = partial.workspace {
+            if let Some(v) = w.isolate {
+                config.workspace.isolate = v;
+            }
+            if let Some(ref v) = w.name {
+                config.workspace.name = Some(v.clone());
+            }
+        }
+
+        if let Some(ref l) = partial.llm {
+            if let Some(v) = l.provider {
+                config.llm.provider = v;
+            }
+            if l.url.is_some() {
+                config.llm.url = l.url.clone();
+            }
+            if let Some(ref v) = l.model {
+                config.llm.model = v.clone();
+            }
+            if l.api_key.is_some() {
+                config.llm.api_key = l.api_key.clone();
+            }
+            if let Some(v) = l.context_chunks {
+                config.llm.context_chunks = v;
+            }
+            if let Some(v) = l.timeout_secs {
+                config.llm.timeout_secs = v;
+            }
         }
     }
 
@@ -323,6 +1325,18 @@ impl Config {
             config.embedding.max_tokens = tokens;
             sources.embedding_max_tokens = ConfigSource::Env;
         }
+        if let Ok(v) = std::env::var("SSEARCH_EMBEDDING_SESSIONS")
+            && let Ok(sessions) = v.parse()
+        {
+            config.embedding.sessions = sessions;
+            sources.embedding_sessions = ConfigSource::Env;
+        }
+        if let Ok(v) = std::env::var("SSEARCH_DEVICE")
+            && let Ok(device) = v.parse()
+        {
+            config.embedding.device = device;
+            sources.embedding_device = ConfigSource::Env;
+        }
         if let Ok(v) = std::env::var("SSEARCH_VECTOR_DRIVER")
             && let Ok(driver) = v.parse()
         {
@@ -344,6 +1358,9 @@ impl Config {
             config.vector_store.api_key = Some(v);
             sources.vector_store_api_key = ConfigSource::Env;
         }
+        if let Ok(v) = std::env::var("SSEARCH_ENCRYPTION_KEY") {
+            config.vector_store.encryption_key = Some(v);
+        }
         if let Ok(v) = std::env::var("SSEARCH_CHUNK_SIZE")
             && let Ok(size) = v.parse()
         {
@@ -356,6 +1373,12 @@ impl Config {
             config.indexing.chunk_overlap = overlap;
             sources.indexing_chunk_overlap = ConfigSource::Env;
         }
+        if let Ok(v) = std::env::var("SSEARCH_CHUNK_STRATEGY")
+            && let Ok(strategy) = v.parse()
+        {
+            config.indexing.strategy = strategy;
+            sources.indexing_strategy = ConfigSource::Env;
+        }
         if let Ok(v) = std::env::var("SSEARCH_MAX_FILE_SIZE")
             && let Ok(size) = v.parse()
         {
@@ -374,6 +1397,10 @@ impl Config {
             config.search.default_format = fmt;
             sources.search_default_format = ConfigSource::Env;
         }
+        if let Ok(v) = std::env::var("SSEARCH_RERANK") {
+            config.search.rerank = v.eq_ignore_ascii_case("true") || v == "1";
+            sources.search_rerank = ConfigSource::Env;
+        }
         if let Ok(v) = std::env::var("SSEARCH_DAEMON_TIMEOUT")
             && let Ok(timeout) = v.parse()
         {
@@ -384,6 +1411,16 @@ impl Config {
             config.daemon.auto_start = v.eq_ignore_ascii_case("true") || v == "1";
             sources.daemon_auto_start = ConfigSource::Env;
         }
+        if let Ok(v) = std::env::var("SSEARCH_DAEMON_HTTP_ADDR")
+            && !v.is_empty()
+        {
+            config.daemon.http_addr = Some(v);
+        }
+        if let Ok(v) = std::env::var("SSEARCH_DAEMON_AUTH_TOKEN")
+            && !v.is_empty()
+        {
+            config.daemon.auth_token = Some(v);
+        }
         if let Ok(v) = std::env::var("SSEARCH_METRICS_ENABLED") {
             config.metrics.enabled = v.eq_ignore_ascii_case("true") || v == "1";
             sources.metrics_enabled = ConfigSource::Env;
@@ -394,6 +1431,26 @@ impl Config {
             config.metrics.retention_days = days;
             sources.metrics_retention_days = ConfigSource::Env;
         }
+        if let Ok(v) = std::env::var("SSEARCH_LLM_PROVIDER")
+            && let Ok(provider) = v.parse()
+        {
+            config.llm.provider = provider;
+        }
+        if let Ok(v) = std::env::var("SSEARCH_LLM_URL")
+            && !v.is_empty()
+        {
+            config.llm.url = Some(v);
+        }
+        if let Ok(v) = std::env::var("SSEARCH_LLM_MODEL")
+            && !v.is_empty()
+        {
+            config.llm.model = v;
+        }
+        if let Ok(v) = std::env::var("SSEARCH_LLM_API_KEY")
+            && !v.is_empty()
+        {
+            config.llm.api_key = Some(v);
+        }
     }
 
     pub fn init_project() -> Result<PathBuf, crate::error::ConfigError> {
@@ -473,9 +1530,19 @@ impl Config {
         self.socket_path().with_extension("pid")
     }
 
+    /// Where a background daemon started by `ssearch serve` writes its logs.
+    pub fn log_path(&self) -> PathBuf {
+        self.socket_path().with_extension("log")
+    }
+
     pub fn metrics_db_path() -> Option<PathBuf> {
         Self::cache_dir().map(|p| p.join("metrics.db"))
     }
+
+    /// Where `collection snapshot` writes backups of a collection.
+    pub fn snapshots_dir(collection: &str) -> Option<PathBuf> {
+        Self::cache_dir().map(|p| p.join("snapshots").join(collection))
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -486,6 +1553,25 @@ pub struct PartialConfig {
     pub search: Option<PartialSearchConfig>,
     pub daemon: Option<PartialDaemonConfig>,
     pub metrics: Option<PartialMetricsConfig>,
+    pub workspace: Option<PartialWorkspaceConfig>,
+    pub llm: Option<PartialLlmConfig>,
+    pub collections: Option<BTreeMap<String, CollectionConfig>>,
+    pub sync: Option<Vec<SyncJob>>,
+    pub tags: Option<BTreeMap<String, Vec<String>>>,
+    pub aliases: Option<BTreeMap<String, SavedQuery>>,
+}
+
+/// Per-collection settings; unset fields fall back to `[embedding]`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct CollectionConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_id: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_path: Option<PathBuf>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dimension: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -495,6 +1581,11 @@ pub struct PartialEmbeddingConfig {
     pub dimension: Option<u32>,
     pub batch_size: Option<u32>,
     pub max_tokens: Option<u32>,
+    pub sessions: Option<u32>,
+    pub threads: Option<u32>,
+    pub device: Option<Device>,
+    pub query_instruction: Option<String>,
+    pub document_instruction: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -504,8 +1595,25 @@ pub struct PartialVectorStoreConfig {
     pub collection: Option<String>,
     pub schema: Option<String>,
     pub api_key: Option<String>,
+    pub rest_url: Option<String>,
     pub pool_max: Option<u32>,
     pub pool_acquire_timeout: Option<u32>,
+    pub upsert_batch_size: Option<u32>,
+    pub upsert_wait: Option<bool>,
+    pub upsert_parallelism: Option<u32>,
+    pub quantization: Option<Quantization>,
+    pub title_vectors: Option<bool>,
+    pub title_weight: Option<f32>,
+    pub index_type: Option<AnnIndex>,
+    pub hnsw_m: Option<u32>,
+    pub hnsw_ef_construction: Option<u32>,
+    pub ef_search: Option<u32>,
+    pub ivfflat_lists: Option<u32>,
+    pub ivfflat_probes: Option<u32>,
+    pub tenant_prefix: Option<String>,
+    pub tenant_tags: Option<Vec<String>>,
+    pub encryption_key: Option<String>,
+    pub encryption_key_command: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -514,6 +1622,19 @@ pub struct PartialIndexingConfig {
     pub max_file_size: Option<u64>,
     pub chunk_size: Option<u32>,
     pub chunk_overlap: Option<u32>,
+    pub strategy: Option<ChunkStrategy>,
+    pub dedup: Option<bool>,
+    pub redact_secrets: Option<bool>,
+    pub pii: Option<PiiPolicy>,
+    pub min_chunk_length: Option<u32>,
+    pub boilerplate_min_files: Option<u32>,
+    pub filler: Option<FillerPolicy>,
+    pub tokenizer: Option<ChunkTokenizer>,
+    pub tokenizer_path: Option<PathBuf>,
+    pub max_retries: Option<u32>,
+    pub summaries: Option<SummaryMode>,
+    pub overrides: Option<ChunkOverrides>,
+    pub retention: Option<RetentionConfig>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -521,6 +1642,15 @@ pub struct PartialSearchConfig {
     pub default_limit: Option<u32>,
     pub default_format: Option<OutputFormat>,
     pub default_min_score: Option<f32>,
+    pub rerank: Option<bool>,
+    pub rerank_model_id: Option<String>,
+    pub rerank_model_path: Option<PathBuf>,
+    pub rerank_top_k: Option<u32>,
+    pub recency_weight: Option<f32>,
+    pub recency_half_life_days: Option<f32>,
+    pub dedup_threshold: Option<f32>,
+    pub expansion: Option<QueryExpansion>,
+    pub keyword_fallback: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -528,12 +1658,37 @@ pub struct PartialDaemonConfig {
     pub idle_timeout_secs: Option<u64>,
     pub auto_start: Option<bool>,
     pub socket_path: Option<PathBuf>,
+    pub http_addr: Option<String>,
+    pub log_format: Option<LogFormat>,
+    pub auth_token: Option<String>,
+    pub rate_limit: Option<f64>,
+    pub client_rate_limit: Option<f64>,
+    pub rate_limit_burst: Option<f64>,
+    pub preload: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct PartialMetricsConfig {
     pub enabled: Option<bool>,
     pub retention_days: Option<u32>,
+    pub history: Option<bool>,
+    pub http_addr: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct PartialWorkspaceConfig {
+    pub isolate: Option<bool>,
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct PartialLlmConfig {
+    pub provider: Option<LlmProvider>,
+    pub url: Option<String>,
+    pub model: Option<String>,
+    pub api_key: Option<String>,
+    pub context_chunks: Option<u32>,
+    pub timeout_secs: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -553,6 +1708,42 @@ pub struct EmbeddingConfig {
     /// Maximum tokens per text for embedding (truncation limit)
     #[serde(default = "default_max_tokens")]
     pub max_tokens: u32,
+
+    /// ONNX sessions loaded by the daemon; each holds its own copy of the model
+    #[serde(default = "default_sessions")]
+    pub sessions: u32,
+
+    /// Intra-op threads per session (default: available cores / sessions)
+    #[serde(default)]
+    pub threads: Option<u32>,
+
+    /// Execution provider for inference; falls back to CPU when unavailable
+    #[serde(default)]
+    pub device: Device,
+
+    /// Prefix for search queries (default: the model's, from the instruction registry)
+    #[serde(default)]
+    pub query_instruction: Option<String>,
+
+    /// Prefix for indexed documents (default: the model's, from the instruction registry)
+    #[serde(default)]
+    pub document_instruction: Option<String>,
+}
+
+impl EmbeddingConfig {
+    /// `query_instruction`, or the model's default.
+    pub fn resolved_query_instruction(&self) -> &str {
+        self.query_instruction
+            .as_deref()
+            .unwrap_or_else(|| model_instructions(&self.model_id).0)
+    }
+
+    /// `document_instruction`, or the model's default.
+    pub fn resolved_document_instruction(&self) -> &str {
+        self.document_instruction
+            .as_deref()
+            .unwrap_or_else(|| model_instructions(&self.model_id).1)
+    }
 }
 
 fn default_embedding_model() -> String {
@@ -571,6 +1762,10 @@ fn default_max_tokens() -> u32 {
     DEFAULT_MAX_TOKENS
 }
 
+fn default_sessions() -> u32 {
+    1
+}
+
 impl Default for EmbeddingConfig {
     fn default() -> Self {
         Self {
@@ -579,6 +1774,11 @@ impl Default for EmbeddingConfig {
             dimension: default_embedding_dimension(),
             batch_size: default_batch_size(),
             max_tokens: default_max_tokens(),
+            sessions: default_sessions(),
+            threads: None,
+            device: Device::default(),
+            query_instruction: None,
+            document_instruction: None,
         }
     }
 }
@@ -600,11 +1800,84 @@ pub struct VectorStoreConfig {
     #[serde(default)]
     pub api_key: Option<String>,
 
+    /// Qdrant REST endpoint for snapshot transfers; defaults to the gRPC `url`
+    /// with the port below it (16334 → 16333)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rest_url: Option<String>,
+
     #[serde(default = "default_pool_max")]
     pub pool_max: u32,
 
     #[serde(default = "default_pool_acquire_timeout")]
     pub pool_acquire_timeout: u32,
+
+    /// Points per upsert request (Qdrant) or rows per multi-row insert
+    /// (PostgreSQL, where 1 inserts row by row)
+    #[serde(default = "default_upsert_batch_size")]
+    pub upsert_batch_size: u32,
+
+    /// Wait for each Qdrant upsert batch to be applied; with `false` only the
+    /// final batch waits, as a barrier for the ones before it
+    #[serde(default = "default_upsert_wait")]
+    pub upsert_wait: bool,
+
+    /// Qdrant upsert batches in flight at once
+    #[serde(default = "default_upsert_parallelism")]
+    pub upsert_parallelism: u32,
+
+    /// Applied when a collection is created; existing collections keep their setting
+    #[serde(default)]
+    pub quantization: Quantization,
+
+    /// Store a second vector per chunk embedding its file name and heading or
+    /// symbol; Qdrant collections need re-creating to add it
+    #[serde(default)]
+    pub title_vectors: bool,
+
+    /// Share of the title similarity in the score of collections with title vectors
+    #[serde(default = "default_title_weight")]
+    pub title_weight: f32,
+
+    /// pgvector ANN index; changes apply on `index reindex`
+    #[serde(default)]
+    pub index_type: AnnIndex,
+
+    /// pgvector HNSW connections per node (`m`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hnsw_m: Option<u32>,
+
+    /// pgvector HNSW candidate list size while building (`ef_construction`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hnsw_ef_construction: Option<u32>,
+
+    /// pgvector HNSW candidate list size per query (`hnsw.ef_search`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ef_search: Option<u32>,
+
+    /// pgvector IVFFlat inverted list count (`lists`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ivfflat_lists: Option<u32>,
+
+    /// pgvector IVFFlat lists probed per query (`ivfflat.probes`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ivfflat_probes: Option<u32>,
+
+    /// Prepended as `<prefix>_` to the collection or table name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant_prefix: Option<String>,
+
+    /// Tags (`key:value`) added to every stored chunk; searches, listings and
+    /// deletes only see chunks that carry all of them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tenant_tags: Vec<String>,
+
+    /// Base64 AES-256 key that chunk content is encrypted with before it's stored (Qdrant)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encryption_key: Option<String>,
+
+    /// Shell command printing the encryption key, e.g. a keychain lookup
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encryption_key_command: Option<String>,
 }
 
 fn default_qdrant_url() -> String {
@@ -623,6 +1896,22 @@ fn default_pool_acquire_timeout() -> u32 {
     30
 }
 
+fn default_upsert_batch_size() -> u32 {
+    500
+}
+
+fn default_upsert_wait() -> bool {
+    true
+}
+
+fn default_upsert_parallelism() -> u32 {
+    1
+}
+
+fn default_title_weight() -> f32 {
+    0.3
+}
+
 impl Default for VectorStoreConfig {
     fn default() -> Self {
         Self {
@@ -631,34 +1920,239 @@ impl Default for VectorStoreConfig {
             collection: default_collection(),
             schema: None,
             api_key: None,
+            rest_url: None,
             pool_max: default_pool_max(),
             pool_acquire_timeout: default_pool_acquire_timeout(),
+            upsert_batch_size: default_upsert_batch_size(),
+            upsert_wait: default_upsert_wait(),
+            upsert_parallelism: default_upsert_parallelism(),
+            quantization: Quantization::default(),
+            title_vectors: false,
+            title_weight: default_title_weight(),
+            index_type: AnnIndex::default(),
+            hnsw_m: None,
+            hnsw_ef_construction: None,
+            ef_search: None,
+            ivfflat_lists: None,
+            ivfflat_probes: None,
+            tenant_prefix: None,
+            tenant_tags: Vec::new(),
+            encryption_key: None,
+            encryption_key_command: None,
         }
     }
 }
 
 impl VectorStoreConfig {
+    /// Whether chunk content is encrypted before it's stored.
+    pub fn encrypts(&self) -> bool {
+        self.encryption_key.is_some() || self.encryption_key_command.is_some()
+    }
+
+    /// Collection name with the tenant prefix applied.
+    pub fn scoped_collection(&self) -> String {
+        match self.tenant_prefix.as_deref() {
+            Some(prefix) if !prefix.is_empty() => format!("{}_{}", prefix, self.collection),
+            _ => self.collection.clone(),
+        }
+    }
+
+    /// Qdrant REST endpoint: `rest_url`, or the gRPC URL with the port below it.
+    pub fn qdrant_rest_url(&self) -> String {
+        let url = match self.rest_url {
+            Some(ref url) => url.clone(),
+            None => match url::Url::parse(&self.url) {
+                Ok(mut parsed) => {
+                    if let Some(port) = parsed.port() {
+                        let _ = parsed.set_port(Some(port.saturating_sub(1)));
+                    }
+                    parsed.to_string()
+                }
+                Err(_) => self.url.clone(),
+            },
+        };
+        url.trim_end_matches('/').to_string()
+    }
+
     pub fn qualified_table_name(&self) -> String {
         match &self.schema {
-            Some(schema) => format!("{}.{}", schema, self.collection),
-            None => self.collection.clone(),
+            Some(schema) => format!("{}.{}", schema, self.scoped_collection()),
+            None => self.scoped_collection(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexingConfig {
+    #[serde(default = "default_exclude_patterns")]
+    pub exclude_patterns: Vec<String>,
+
+    #[serde(default = "default_max_file_size")]
+    pub max_file_size: u64,
+
+    #[serde(default = "default_chunk_size")]
+    pub chunk_size: u32,
+
+    #[serde(default = "default_chunk_overlap")]
+    pub chunk_overlap: u32,
+
+    #[serde(default)]
+    pub strategy: ChunkStrategy,
+
+    /// Skip chunks whose content is already stored for another document.
+    ///
+    /// Off by default: the skipped content is only stored with the first
+    /// document, so it drops out of the index when that document is deleted
+    /// or changed until the others are re-indexed.
+    #[serde(default)]
+    pub dedup: bool,
+
+    /// How chunk sizes are measured
+    #[serde(default)]
+    pub tokenizer: ChunkTokenizer,
+
+    /// tokenizer.json to count with instead of the embedding model's
+    #[serde(default)]
+    pub tokenizer_path: Option<PathBuf>,
+
+    /// Retries of a failed embedding or vector store write before indexing aborts
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+
+    /// Per-document summary chunks for `search --summaries-only`
+    #[serde(default)]
+    pub summaries: SummaryMode,
+
+    /// Replace AWS keys, tokens and private keys in chunks with placeholders before storing
+    #[serde(default = "default_redact_secrets")]
+    pub redact_secrets: bool,
+
+    /// Tag or skip documents containing emails, phone numbers or national IDs
+    #[serde(default)]
+    pub pii: PiiPolicy,
+
+    /// Non-whitespace characters below which a chunk of a multi-chunk document is filler
+    #[serde(default = "default_min_chunk_length")]
+    pub min_chunk_length: u32,
+
+    /// Files a chunk must appear in verbatim during one `index add` to count
+    /// as boilerplate (license headers, generated banners); 0 turns detection off
+    #[serde(default)]
+    pub boilerplate_min_files: u32,
+
+    /// Skip or tag short and boilerplate chunks
+    #[serde(default)]
+    pub filler: FillerPolicy,
+
+    /// Chunk sizes per language or extension (`[indexing.overrides] rust = { chunk_size = 2000 }`)
+    #[serde(default, skip_serializing_if = "ChunkOverrides::is_empty")]
+    pub overrides: ChunkOverrides,
+
+    /// What `index prune` removes
+    #[serde(default, skip_serializing_if = "RetentionConfig::is_empty")]
+    pub retention: RetentionConfig,
+}
+
+/// `[indexing.retention]`: how old and how large the index may grow.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct RetentionConfig {
+    /// Longest a document may go without an update, per source type
+    /// (`[indexing.retention.max_age] jira = "90d"`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub max_age: BTreeMap<String, String>,
+
+    /// Most chunks the collection may hold; the least recently updated go first
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_points: Option<u64>,
+}
+
+impl RetentionConfig {
+    pub fn is_empty(&self) -> bool {
+        self.max_age.is_empty() && self.max_points.is_none()
+    }
+
+    /// Parsed `max_age` entries, by source type.
+    pub fn max_ages(&self) -> Result<Vec<(SourceType, TimeDelta)>, ConfigError> {
+        self.max_age
+            .iter()
+            .map(|(source, age)| {
+                let Ok(source_type) = source.parse::<SourceType>();
+                let age = parse_age(age).filter(|age| *age > TimeDelta::zero()).ok_or_else(|| {
+                    ConfigError::ValidationError(format!(
+                        "invalid retention max_age for {source} '{age}': expected an age like 90d"
+                    ))
+                })?;
+                Ok((source_type, age))
+            })
+            .collect()
+    }
+}
+
+/// Chunk size and overlap in tokens for one file type; unset fields follow `[indexing]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct ChunkOverride {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chunk_size: Option<u32>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chunk_overlap: Option<u32>,
+}
+
+/// `[indexing.overrides]`, keyed by lowercase extension (`md`) or language (`rust`).
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(from = "BTreeMap<String, ChunkOverride>")]
+pub struct ChunkOverrides(BTreeMap<String, ChunkOverride>);
+
+impl From<BTreeMap<String, ChunkOverride>> for ChunkOverrides {
+    fn from(entries: BTreeMap<String, ChunkOverride>) -> Self {
+        let mut overrides = Self::default();
+        for (key, entry) in entries {
+            overrides.insert(&key, entry);
         }
+        overrides
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct IndexingConfig {
-    #[serde(default = "default_exclude_patterns")]
-    pub exclude_patterns: Vec<String>,
+impl ChunkOverrides {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
 
-    #[serde(default = "default_max_file_size")]
-    pub max_file_size: u64,
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &ChunkOverride)> {
+        self.0.iter()
+    }
 
-    #[serde(default = "default_chunk_size")]
-    pub chunk_size: u32,
+    /// Add or replace the entry for `key`.
+    pub fn insert(&mut self, key: &str, chunk_override: ChunkOverride) {
+        self.0.insert(key.to_lowercase(), chunk_override);
+    }
 
-    #[serde(default = "default_chunk_overlap")]
-    pub chunk_overlap: u32,
+    /// The entry for a document's extension, else for its language.
+    pub fn get(&self, extension: Option<&str>, language: Option<&str>) -> Option<&ChunkOverride> {
+        [extension, language]
+            .into_iter()
+            .flatten()
+            .find_map(|key| self.0.get(&key.to_lowercase()))
+    }
+
+    /// Chunk and overlap sizes in tokens for a document, given the configured
+    /// `(chunk_size, chunk_overlap)`. An override without an overlap keeps
+    /// the configured one, capped at half its chunk size.
+    pub fn sizes(
+        &self,
+        defaults: (u32, u32),
+        extension: Option<&str>,
+        language: Option<&str>,
+    ) -> (u32, u32) {
+        let Some(entry) = self.get(extension, language) else {
+            return defaults;
+        };
+        let size = entry.chunk_size.unwrap_or(defaults.0);
+        let overlap = entry
+            .chunk_overlap
+            .unwrap_or_else(|| defaults.1.min(size / 2));
+        (size, overlap)
+    }
 }
 
 fn default_exclude_patterns() -> Vec<String> {
@@ -692,6 +2186,18 @@ fn default_chunk_overlap() -> u32 {
     500
 }
 
+fn default_min_chunk_length() -> u32 {
+    MIN_CONTENT_LENGTH as u32
+}
+
+fn default_max_retries() -> u32 {
+    3
+}
+
+fn default_redact_secrets() -> bool {
+    true
+}
+
 impl Default for IndexingConfig {
     fn default() -> Self {
         Self {
@@ -699,10 +2205,33 @@ impl Default for IndexingConfig {
             max_file_size: default_max_file_size(),
             chunk_size: default_chunk_size(),
             chunk_overlap: default_chunk_overlap(),
+            strategy: ChunkStrategy::default(),
+            dedup: false,
+            tokenizer: ChunkTokenizer::default(),
+            tokenizer_path: None,
+            max_retries: default_max_retries(),
+            summaries: SummaryMode::default(),
+            redact_secrets: default_redact_secrets(),
+            pii: PiiPolicy::default(),
+            min_chunk_length: default_min_chunk_length(),
+            boilerplate_min_files: 0,
+            filler: FillerPolicy::default(),
+            overrides: ChunkOverrides::default(),
+            retention: RetentionConfig::default(),
         }
     }
 }
 
+impl IndexingConfig {
+    /// Use `chunk_size` tokens for every file (`index add --chunk-size`),
+    /// dropping the per-type overrides and shrinking the overlap to fit.
+    pub fn set_chunk_size(&mut self, chunk_size: u32) {
+        self.chunk_size = chunk_size;
+        self.chunk_overlap = self.chunk_overlap.min(chunk_size / 2);
+        self.overrides = ChunkOverrides::default();
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchConfig {
     #[serde(default = "default_limit")]
@@ -713,18 +2242,77 @@ pub struct SearchConfig {
 
     #[serde(default)]
     pub default_min_score: Option<f32>,
+
+    /// Re-score top candidates with a cross-encoder model
+    #[serde(default)]
+    pub rerank: bool,
+
+    #[serde(default = "default_rerank_model")]
+    pub rerank_model_id: String,
+
+    #[serde(default)]
+    pub rerank_model_path: Option<PathBuf>,
+
+    /// Number of vector store candidates passed to the reranker
+    #[serde(default = "default_rerank_top_k")]
+    pub rerank_top_k: u32,
+
+    /// Share of the score decided by document age (0.0 disables the boost)
+    #[serde(default)]
+    pub recency_weight: f32,
+
+    /// Age at which the recency boost halves
+    #[serde(default = "default_recency_half_life_days")]
+    pub recency_half_life_days: f32,
+
+    /// Drop results this similar (cosine) to a higher-ranked result
+    #[serde(default)]
+    pub dedup_threshold: Option<f32>,
+
+    /// Search reformulations of the query as well and fuse the rankings
+    #[serde(default)]
+    pub expansion: QueryExpansion,
+
+    /// Run a keyword search when no semantic result reaches the minimum score
+    #[serde(default = "default_keyword_fallback")]
+    pub keyword_fallback: bool,
 }
 
 fn default_limit() -> u32 {
     10
 }
 
+fn default_rerank_model() -> String {
+    DEFAULT_RERANK_MODEL.to_string()
+}
+
+fn default_rerank_top_k() -> u32 {
+    DEFAULT_RERANK_TOP_K
+}
+
+fn default_recency_half_life_days() -> f32 {
+    DEFAULT_RECENCY_HALF_LIFE_DAYS
+}
+
+fn default_keyword_fallback() -> bool {
+    true
+}
+
 impl Default for SearchConfig {
     fn default() -> Self {
         Self {
             default_limit: default_limit(),
             default_format: OutputFormat::Text,
             default_min_score: None,
+            rerank: false,
+            rerank_model_id: default_rerank_model(),
+            rerank_model_path: None,
+            rerank_top_k: default_rerank_top_k(),
+            recency_weight: 0.0,
+            recency_half_life_days: default_recency_half_life_days(),
+            dedup_threshold: None,
+            expansion: QueryExpansion::Off,
+            keyword_fallback: default_keyword_fallback(),
         }
     }
 }
@@ -739,6 +2327,35 @@ pub struct DaemonConfig {
 
     #[serde(default)]
     pub socket_path: Option<PathBuf>,
+
+    /// Address for the optional HTTP API (e.g., "127.0.0.1:8765")
+    #[serde(default)]
+    pub http_addr: Option<String>,
+
+    /// Log format for `ssearch serve` unless `--log-format` is given
+    #[serde(default)]
+    pub log_format: LogFormat,
+
+    /// Shared secret clients must send with every socket request, and as
+    /// `Authorization: Bearer` with every HTTP API request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_token: Option<String>,
+
+    /// Texts per second embedded or reranked for all clients together
+    #[serde(default)]
+    pub rate_limit: Option<f64>,
+
+    /// Texts per second for each client (socket process or HTTP address)
+    #[serde(default)]
+    pub client_rate_limit: Option<f64>,
+
+    /// Texts a client may send at once before the rate applies; one second's worth when unset
+    #[serde(default)]
+    pub rate_limit_burst: Option<f64>,
+
+    /// Also load the reranker and connect to the vector store at startup
+    #[serde(default)]
+    pub preload: bool,
 }
 
 fn default_idle_timeout() -> u64 {
@@ -759,6 +2376,13 @@ impl Default for DaemonConfig {
             idle_timeout_secs: default_idle_timeout(),
             auto_start: default_auto_start(),
             socket_path: None,
+            http_addr: None,
+            log_format: LogFormat::default(),
+            auth_token: None,
+            rate_limit: None,
+            client_rate_limit: None,
+            rate_limit_burst: None,
+            preload: false,
         }
     }
 }
@@ -770,12 +2394,24 @@ pub struct MetricsConfig {
 
     #[serde(default = "default_retention_days")]
     pub retention_days: u32,
+
+    /// Record executed searches for `ssearch history`
+    #[serde(default = "default_history_enabled")]
+    pub history: bool,
+
+    /// Address to serve Prometheus metrics on (e.g., "127.0.0.1:9464")
+    #[serde(default)]
+    pub http_addr: Option<String>,
 }
 
 fn default_metrics_enabled() -> bool {
     true
 }
 
+fn default_history_enabled() -> bool {
+    true
+}
+
 fn default_retention_days() -> u32 {
     DEFAULT_METRICS_RETENTION_DAYS
 }
@@ -785,6 +2421,254 @@ impl Default for MetricsConfig {
         Self {
             enabled: default_metrics_enabled(),
             retention_days: default_retention_days(),
+            history: default_history_enabled(),
+            http_addr: None,
+        }
+    }
+}
+
+/// LLM endpoint that `ssearch ask` sends retrieved chunks to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LlmConfig {
+    #[serde(default)]
+    pub provider: LlmProvider,
+
+    /// Base URL of the API; the provider's default when unset
+    #[serde(default)]
+    pub url: Option<String>,
+
+    #[serde(default = "default_llm_model")]
+    pub model: String,
+
+    /// Sent as a bearer token (required by OpenAI, optional for local servers)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+
+    /// Search results included in the prompt
+    #[serde(default = "default_context_chunks")]
+    pub context_chunks: u32,
+
+    #[serde(default = "default_llm_timeout")]
+    pub timeout_secs: u64,
+}
+
+fn default_llm_model() -> String {
+    DEFAULT_LLM_MODEL.to_string()
+}
+
+fn default_context_chunks() -> u32 {
+    5
+}
+
+fn default_llm_timeout() -> u64 {
+    120
+}
+
+impl LlmConfig {
+    /// Base URL requests go to, without a trailing slash.
+    pub fn base_url(&self) -> &str {
+        self.url
+            .as_deref()
+            .unwrap_or(self.provider.default_url())
+            .trim_end_matches('/')
+    }
+}
+
+impl Default for LlmConfig {
+    fn default() -> Self {
+        Self {
+            provider: LlmProvider::default(),
+            url: None,
+            model: default_llm_model(),
+            api_key: None,
+            context_chunks: default_context_chunks(),
+            timeout_secs: default_llm_timeout(),
+        }
+    }
+}
+
+/// A search saved by `alias add`; unset fields follow `[search]`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct SavedQuery {
+    pub query: String,
+
+    /// Tag filter (e.g., "team:platform,env:prod")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<String>,
+
+    /// Source type filter (e.g., "local,confluence")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+
+    /// Language filter (e.g., "rust,python")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub languages: Option<String>,
+
+    /// Filter expressions, as for `search --filter`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub filters: Vec<String>,
+
+    /// Project-relative paths or globs, as for `search --path`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub paths: Vec<String>,
+
+    /// Date or age, as for `search --since`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_score: Option<f32>,
+
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub hybrid: bool,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collection: Option<String>,
+}
+
+impl SavedQuery {
+    /// The `search` options the alias stands for, without the query.
+    pub fn search_options(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        let mut push = |flag: &str, value: String| {
+            args.push(flag.to_string());
+            args.push(value);
+        };
+        if let Some(ref tags) = self.tags {
+            push("--tags", tags.clone());
+        }
+        if let Some(ref source) = self.source {
+            push("--source", source.clone());
+        }
+        if let Some(ref languages) = self.languages {
+            push("--lang", languages.clone());
+        }
+        for filter in &self.filters {
+            push("--filter", filter.clone());
+        }
+        for path in &self.paths {
+            push("--path", path.clone());
+        }
+        if let Some(ref since) = self.since {
+            push("--since", since.clone());
+        }
+        if let Some(limit) = self.limit {
+            push("--limit", limit.to_string());
+        }
+        if let Some(min_score) = self.min_score {
+            push("--min-score", min_score.to_string());
+        }
+        if let Some(ref collection) = self.collection {
+            push("--collection", collection.clone());
+        }
+        if self.hybrid {
+            args.push("--hybrid".to_string());
+        }
+        args
+    }
+}
+
+/// A source sync run by `source sync --all-configured` and, every
+/// `interval`, by `source sync --schedule`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyncJob {
+    pub source: SourceType,
+
+    /// Source-specific query (JQL, CQL, GitHub search, Slack export directory, page URLs)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+
+    /// Project key, space key, owner/repo, or channels
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+
+    /// Tags applied to synced documents (e.g., "space:eng,team:core")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<String>,
+
+    /// Maximum items to sync; every item when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+
+    /// Exclude pages under these ancestor IDs (Confluence)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude_ancestors: Vec<String>,
+
+    /// Also index page attachments (Confluence)
+    #[serde(default)]
+    pub attachments: bool,
+
+    /// Link levels to follow from the start pages (Web)
+    #[serde(default)]
+    pub depth: u32,
+
+    /// How often `--schedule` reruns the job (e.g., "30m", "6h", "1d")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interval: Option<String>,
+}
+
+impl SyncJob {
+    /// Label used in progress output, e.g. `confluence ENG`.
+    pub fn label(&self) -> String {
+        match self.project.as_deref().or(self.query.as_deref()) {
+            Some(scope) => format!("{} {}", self.source, scope),
+            None => self.source.to_string(),
+        }
+    }
+
+    /// Parsed `interval`, or `None` for jobs that only run on demand.
+    pub fn interval(&self) -> Result<Option<std::time::Duration>, ConfigError> {
+        self.interval.as_deref().map(parse_interval).transpose()
+    }
+}
+
+/// Parse an interval such as `90s`, `30m`, `6h`, or `1d`.
+fn parse_interval(s: &str) -> Result<std::time::Duration, ConfigError> {
+    let invalid = || {
+        ConfigError::ValidationError(format!(
+            "invalid sync interval '{}': expected a number with s, m, h, or d (e.g., 6h)",
+            s
+        ))
+    };
+    let unit_start = s.find(|c: char| !c.is_ascii_digit()).ok_or_else(invalid)?;
+    let (amount, unit) = s.split_at(unit_start);
+    let amount: u64 = amount.parse().map_err(|_| invalid())?;
+    let secs = match unit {
+        "s" => Some(amount),
+        "m" => amount.checked_mul(60),
+        "h" => amount.checked_mul(3_600),
+        "d" => amount.checked_mul(86_400),
+        _ => None,
+    }
+    .filter(|&secs| secs > 0)
+    .ok_or_else(invalid)?;
+    Ok(std::time::Duration::from_secs(secs))
+}
+
+/// Per-project isolation of indexed content.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceConfig {
+    /// Stamp indexed chunks with the workspace and search only the current one
+    #[serde(default = "default_workspace_isolate")]
+    pub isolate: bool,
+
+    /// Workspace name; defaults to the project root of `.ssearch/config.toml`
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+fn default_workspace_isolate() -> bool {
+    true
+}
+
+impl Default for WorkspaceConfig {
+    fn default() -> Self {
+        Self {
+            isolate: default_workspace_isolate(),
+            name: None,
         }
     }
 }
@@ -798,6 +2682,41 @@ mod tests {
         let config = Config::default();
         assert_eq!(config.embedding.model_id, DEFAULT_EMBEDDING_MODEL);
         assert_eq!(config.vector_store.url, DEFAULT_QDRANT_URL);
+        // Deduplicated content would go with the document that owns it
+        assert!(!config.indexing.dedup);
+        let indexing: IndexingConfig = toml::from_str("").unwrap();
+        assert!(!indexing.dedup);
+    }
+
+    #[test]
+    fn test_validate_collection_name() {
+        let qdrant = VectorDriver::Qdrant;
+        let postgres = VectorDriver::PostgreSQL;
+        assert!(qdrant.validate_collection_name("work-docs").is_ok());
+        assert!(postgres.validate_collection_name("codebase_v2").is_ok());
+        assert!(postgres.validate_collection_name("work-docs").is_err());
+        assert!(postgres.validate_collection_name("2024_notes").is_err());
+        assert!(qdrant.validate_collection_name("").is_err());
+        assert!(qdrant.validate_collection_name("a/b").is_err());
+
+        let injected = Config::load_with_collection(Some("docs; DROP TABLE documents"));
+        assert!(matches!(
+            injected,
+            Err(crate::error::ConfigError::ValidationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_workspace_resolution() {
+        let mut config = Config::default();
+        assert_eq!(config.workspace(), None);
+
+        config.workspace.name =
+            Config::project_workspace(Path::new("/work/api/.ssearch/config.toml"));
+        assert_eq!(config.workspace(), Some("/work/api"));
+
+        config.workspace.isolate = false;
+        assert_eq!(config.workspace(), None);
     }
 
     #[test]
@@ -805,6 +2724,346 @@ mod tests {
         let config = DaemonConfig::default();
         assert_eq!(config.idle_timeout_secs, DEFAULT_IDLE_TIMEOUT_SECS);
         assert!(config.auto_start);
+        assert!(!config.preload);
+
+        let config: Config = toml::from_str("[daemon]\npreload = true\n").unwrap();
+        assert!(config.daemon.preload);
+    }
+
+    #[test]
+    fn test_search_config_default() {
+        let config = SearchConfig::default();
+        assert!(!config.rerank);
+        assert_eq!(config.rerank_model_id, DEFAULT_RERANK_MODEL);
+        assert_eq!(config.rerank_top_k, DEFAULT_RERANK_TOP_K);
+    }
+
+    #[test]
+    fn test_embedding_sessions_config() {
+        let config: Config = toml::from_str("[embedding]\nmodel_id = \"m\"\n").unwrap();
+        assert_eq!(config.embedding.sessions, 1);
+        assert_eq!(config.embedding.threads, None);
+
+        let mut config = Config::default();
+        let mut sources = ConfigSources::default();
+        let partial = PartialConfig {
+            embedding: Some(PartialEmbeddingConfig {
+                sessions: Some(4),
+                threads: Some(2),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        Config::merge_partial(&mut config, &mut sources, &partial, ConfigSource::Global);
+
+        assert_eq!(config.embedding.sessions, 4);
+        assert_eq!(config.embedding.threads, Some(2));
+        assert_eq!(sources.embedding_sessions, ConfigSource::Global);
+    }
+
+    #[test]
+    fn test_device_parse() {
+        assert_eq!("cpu".parse::<Device>().unwrap(), Device::Cpu);
+        assert_eq!("CUDA".parse::<Device>().unwrap(), Device::Cuda);
+        assert_eq!("coreml".parse::<Device>().unwrap(), Device::CoreMl);
+        assert_eq!("dml".parse::<Device>().unwrap(), Device::DirectMl);
+        assert!("tpu".parse::<Device>().is_err());
+
+        let config: Config = toml::from_str("[embedding]\ndevice = \"directml\"\n").unwrap();
+        assert_eq!(config.embedding.device, Device::DirectMl);
+        assert_eq!(config.embedding.device.to_string(), "directml");
+    }
+
+    #[test]
+    fn test_quantization_parse() {
+        assert_eq!("int8".parse::<Quantization>().unwrap(), Quantization::Int8);
+        assert_eq!(
+            "Binary".parse::<Quantization>().unwrap(),
+            Quantization::Binary
+        );
+        assert!("pq".parse::<Quantization>().is_err());
+        assert_eq!(
+            Config::default().vector_store.quantization,
+            Quantization::None
+        );
+
+        let config: Config = toml::from_str("[vector_store]\nquantization = \"binary\"\n").unwrap();
+        assert_eq!(config.vector_store.quantization, Quantization::Binary);
+        assert_eq!(config.vector_store.quantization.oversampling(), 4);
+    }
+
+    #[test]
+    fn test_tenant_scoping() {
+        let config: Config = toml::from_str(
+            "[vector_store]\ncollection = \"docs\"\nschema = \"search\"\ntenant_prefix = \"team_a\"\ntenant_tags = [\"team:a\"]\n",
+        )
+        .unwrap();
+        assert_eq!(config.vector_store.scoped_collection(), "team_a_docs");
+        assert_eq!(
+            config.vector_store.qualified_table_name(),
+            "search.team_a_docs"
+        );
+        assert_eq!(config.vector_store.tenant_tags, vec!["team:a".to_string()]);
+        assert_eq!(
+            Config::default().vector_store.scoped_collection(),
+            DEFAULT_COLLECTION
+        );
+    }
+
+    #[test]
+    fn test_problems() {
+        assert!(Config::default().problems().is_empty());
+
+        let config: Config = toml::from_str(
+            "[indexing]\nchunk_size = 100\nchunk_overlap = 100\n\n[vector_store]\ndriver = \"postgresql\"\nurl = \"http://localhost:6334\"\n\n[search]\nrecency_weight = 1.5\n",
+        )
+        .unwrap();
+        let problems = config.problems();
+        assert_eq!(problems.len(), 3);
+        assert!(problems[0].contains("chunk_overlap (100)"));
+        assert!(problems[1].contains("postgres:// or postgresql://"));
+        assert!(problems[2].contains("recency_weight"));
+    }
+
+    #[test]
+    fn test_filler_config() {
+        let indexing = IndexingConfig::default();
+        assert_eq!(indexing.min_chunk_length, 50);
+        assert_eq!(indexing.boilerplate_min_files, 0);
+        assert_eq!(indexing.filler, FillerPolicy::Skip);
+        assert_eq!("tag".parse::<FillerPolicy>(), Ok(FillerPolicy::Flag));
+        assert!("drop".parse::<FillerPolicy>().is_err());
+
+        let config: Config = toml::from_str(
+            "[indexing]
+boilerplate_min_files = 1
+filler = \"flag\"
+",
+        )
+        .unwrap();
+        assert_eq!(config.indexing.filler, FillerPolicy::Flag);
+        let problems = config.problems();
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].contains("boilerplate_min_files"));
+    }
+
+    #[test]
+    fn test_retention_config() {
+        let mut config = Config::default();
+        let mut sources = ConfigSources::default();
+        let global: PartialConfig = toml::from_str(
+            "[indexing.retention]\nmax_points = 100000\n\n[indexing.retention.max_age]\njira = \"90d\"\nslack = \"2w\"\n",
+        )
+        .unwrap();
+        let project: PartialConfig =
+            toml::from_str("[indexing.retention.max_age]\njira = \"1y\"\n").unwrap();
+        Config::merge_partial(&mut config, &mut sources, &global, ConfigSource::Global);
+        Config::merge_partial(&mut config, &mut sources, &project, ConfigSource::Project);
+
+        let retention = &config.indexing.retention;
+        assert_eq!(retention.max_points, Some(100_000));
+        assert_eq!(
+            retention.max_ages().unwrap(),
+            vec![
+                (SourceType::Jira, TimeDelta::days(365)),
+                (SourceType::Slack, TimeDelta::weeks(2)),
+            ]
+        );
+        assert!(config.problems().is_empty());
+
+        config
+            .indexing
+            .retention
+            .max_age
+            .insert("web".to_string(), "soon".to_string());
+        let problems = config.problems();
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].contains("max_age for web 'soon'"));
+    }
+
+    #[test]
+    fn test_qdrant_rest_url() {
+        let mut config = VectorStoreConfig::default();
+        assert_eq!(config.qdrant_rest_url(), "http://localhost:16333");
+        config.url = "https://qdrant.example.com".to_string();
+        assert_eq!(config.qdrant_rest_url(), "https://qdrant.example.com");
+        config.rest_url = Some("http://rest:6333/".to_string());
+        assert_eq!(config.qdrant_rest_url(), "http://rest:6333");
+    }
+
+    #[test]
+    fn test_ann_index_options() {
+        assert_eq!("ivf".parse::<AnnIndex>().unwrap(), AnnIndex::Ivfflat);
+        assert!("flat".parse::<AnnIndex>().is_err());
+
+        let config: Config = toml::from_str(
+            "[vector_store]\nindex_type = \"ivfflat\"\nivfflat_lists = 200\nivfflat_probes = 10\n",
+        )
+        .unwrap();
+        assert_eq!(config.vector_store.index_type, AnnIndex::Ivfflat);
+        assert_eq!(config.vector_store.ivfflat_lists, Some(200));
+        assert!(config.problems().is_empty());
+
+        let config: Config = toml::from_str(
+            "[vector_store]\nhnsw_m = 32\nhnsw_ef_construction = 40\nef_search = 0\n",
+        )
+        .unwrap();
+        let problems = config.problems();
+        assert_eq!(problems.len(), 2);
+        assert!(problems[0].contains("ef_search (0)"));
+        assert!(problems[1].contains("twice hnsw_m (32)"));
+    }
+
+    #[test]
+    fn test_encryption_requires_qdrant() {
+        let config: Config =
+            toml::from_str("[vector_store]\nencryption_key_command = \"pass show ssearch\"\n")
+                .unwrap();
+        assert!(config.vector_store.encrypts());
+        assert!(config.problems().is_empty());
+
+        let config: Config = toml::from_str(
+            "[vector_store]\ndriver = \"postgresql\"\nurl = \"postgres://localhost/db\"\nencryption_key = \"a2V5\"\n",
+        )
+        .unwrap();
+        assert_eq!(config.problems().len(), 1);
+        assert!(config.problems()[0].contains("only supported by the qdrant driver"));
+    }
+
+    #[test]
+    fn test_tag_templates() {
+        let mut config = Config::default();
+        let mut sources = ConfigSources::default();
+        for toml in [
+            "[tags]\njira = [\"team:core\"]\nlocal = [\"kind:code\"]\n",
+            "[tags]\njira = [\"team:{jira-project}\", \"kind:ticket\"]\n",
+        ] {
+            let partial: PartialConfig = toml::from_str(toml).unwrap();
+            Config::merge_partial(&mut config, &mut sources, &partial, ConfigSource::Project);
+        }
+
+        let jira = config.tag_templates(&SourceType::Jira).unwrap();
+        assert_eq!(jira.len(), 2);
+        assert_eq!(jira[0].to_string(), "team:{jira-project}");
+        assert_eq!(config.tag_templates(&SourceType::Local).unwrap().len(), 1);
+        assert!(config.tag_templates(&SourceType::Slack).unwrap().is_empty());
+
+        config
+            .tags
+            .insert("web".to_string(), vec!["{site".to_string()]);
+        assert!(config.tag_templates(&SourceType::Web).is_err());
+        assert!(config.problems()[0].starts_with("[tags] web:"));
+    }
+
+    #[test]
+    fn test_collection_overrides() {
+        let mut config = Config::default();
+        let mut sources = ConfigSources::default();
+        let partial: PartialConfig = toml::from_str(
+            "[vector_store]\ncollection = \"codebase\"\n\n[collections.codebase]\nmodel_id = \"small-model\"\ndimension = 384\n\n[collections.notes]\n",
+        )
+        .unwrap();
+        Config::merge_partial(&mut config, &mut sources, &partial, ConfigSource::Project);
+
+        let mut codebase = config.clone();
+        codebase.apply_collection_overrides();
+        assert_eq!(codebase.embedding.model_id, "small-model");
+        assert_eq!(codebase.embedding.dimension, 384);
+
+        let mut notes = config;
+        notes.vector_store.collection = "notes".to_string();
+        notes.apply_collection_overrides();
+        assert_eq!(notes.embedding.model_id, DEFAULT_EMBEDDING_MODEL);
+        assert_eq!(notes.embedding.dimension, DEFAULT_EMBEDDING_DIMENSION);
+    }
+
+    #[test]
+    fn test_aliases() {
+        let mut config = Config::default();
+        let mut sources = ConfigSources::default();
+        let global: PartialConfig = toml::from_str(
+            "[aliases.deploy]\nquery = \"deploy steps\"\n\n[aliases.errors]\nquery = \"error handling\"\nhybrid = true\n",
+        )
+        .unwrap();
+        Config::merge_partial(&mut config, &mut sources, &global, ConfigSource::Global);
+        let project: PartialConfig = toml::from_str(
+            "[aliases.deploy]\nquery = \"kubernetes deployment\"\ntags = \"team:platform\"\nlimit = 5\n",
+        )
+        .unwrap();
+        Config::merge_partial(&mut config, &mut sources, &project, ConfigSource::Project);
+
+        assert_eq!(config.aliases.len(), 2);
+        let deploy = &config.aliases["deploy"];
+        assert_eq!(deploy.query, "kubernetes deployment");
+        assert_eq!(
+            deploy.search_options(),
+            vec!["--tags", "team:platform", "--limit", "5"]
+        );
+        assert_eq!(config.aliases["errors"].search_options(), vec!["--hybrid"]);
+
+        let saved = toml::to_string(&project).unwrap();
+        assert!(saved.contains("[aliases.deploy]"));
+        assert!(!saved.contains("hybrid"));
+    }
+
+    #[test]
+    fn test_embedding_instructions() {
+        let mut config = Config::default();
+        assert!(
+            config
+                .embedding
+                .resolved_query_instruction()
+                .starts_with("Instruct: ")
+        );
+        assert_eq!(config.embedding.resolved_document_instruction(), "");
+
+        assert_eq!(
+            model_instructions("intfloat/multilingual-e5-large"),
+            ("query: ", "passage: ")
+        );
+        assert_eq!(
+            model_instructions("nomic-ai/Nomic-Embed-Text-v1.5"),
+            ("search_query: ", "search_document: ")
+        );
+        assert_eq!(model_instructions("BAAI/bge-m3"), ("", ""));
+
+        let mut sources = ConfigSources::default();
+        let partial: PartialConfig = toml::from_str(
+            "[embedding]\nquery_instruction = \"Q: \"\ndocument_instruction = \"\"\n\n[vector_store]\ncollection = \"e5\"\n\n[collections.e5]\nmodel_id = \"intfloat/e5-base-v2\"\n",
+        )
+        .unwrap();
+        Config::merge_partial(&mut config, &mut sources, &partial, ConfigSource::Project);
+        assert_eq!(config.embedding.resolved_query_instruction(), "Q: ");
+        assert_eq!(config.embedding.resolved_document_instruction(), "");
+
+        // A collection with its own model uses that model's defaults
+        config.apply_collection_overrides();
+        assert_eq!(config.embedding.resolved_query_instruction(), "query: ");
+        assert_eq!(
+            config.embedding.resolved_document_instruction(),
+            "passage: "
+        );
+    }
+
+    #[test]
+    fn test_upsert_config() {
+        let config = VectorStoreConfig::default();
+        assert!(config.upsert_wait);
+        assert_eq!(config.upsert_parallelism, 1);
+
+        let config: Config = toml::from_str(
+            "[vector_store]\nupsert_batch_size = 100\nupsert_wait = false\nupsert_parallelism = 4\n",
+        )
+        .unwrap();
+        assert_eq!(config.vector_store.upsert_batch_size, 100);
+        assert!(!config.vector_store.upsert_wait);
+        assert_eq!(config.vector_store.upsert_parallelism, 4);
+        assert!(config.problems().is_empty());
+
+        let config: Config = toml::from_str("[vector_store]\nupsert_parallelism = 0\n").unwrap();
+        let problems = config.problems();
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].contains("upsert_parallelism"));
     }
 
     #[test]
@@ -826,6 +3085,96 @@ mod tests {
         assert_eq!(sources.embedding_model_id, ConfigSource::Project);
     }
 
+    #[test]
+    fn test_chunk_overrides() {
+        let mut config: Config = toml::from_str(
+            "[indexing]\nchunk_size = 6000\nchunk_overlap = 500\n\n[indexing.overrides]\nrust = { chunk_size = 2000 }\nMD = { chunk_size = 8000, chunk_overlap = 0 }\ntoml = { chunk_size = 600 }\n",
+        )
+        .unwrap();
+        let overrides = &config.indexing.overrides;
+        assert_eq!(
+            overrides.sizes((6000, 500), Some("rs"), Some("rust")),
+            (2000, 500)
+        );
+        assert_eq!(overrides.sizes((6000, 500), Some("md"), None), (8000, 0));
+        assert_eq!(overrides.sizes((6000, 500), Some("toml"), None), (600, 300));
+        assert_eq!(
+            overrides.sizes((6000, 500), Some("py"), Some("python")),
+            (6000, 500)
+        );
+        assert!(config.problems().is_empty());
+
+        // A project entry replaces the global one for its type only
+        let partial: PartialConfig =
+            toml::from_str("[indexing.overrides]\nrust = { chunk_size = 1000 }\n").unwrap();
+        let mut sources = ConfigSources::default();
+        Config::merge_partial(&mut config, &mut sources, &partial, ConfigSource::Project);
+        let overrides = &config.indexing.overrides;
+        assert_eq!(
+            overrides.get(None, Some("rust")).unwrap().chunk_size,
+            Some(1000)
+        );
+        assert!(overrides.get(Some("md"), None).is_some());
+
+        config.indexing.set_chunk_size(800);
+        assert_eq!(config.indexing.chunk_overlap, 400);
+        assert!(config.indexing.overrides.is_empty());
+
+        let config: Config = toml::from_str(
+            "[indexing.overrides]\nrust = { chunk_size = 0 }\nmd = { chunk_size = 100, chunk_overlap = 100 }\n",
+        )
+        .unwrap();
+        let problems = config.problems();
+        assert_eq!(problems.len(), 2);
+        assert!(problems[0].contains("md: chunk_overlap (100)"));
+        assert!(problems[1].contains("rust: chunk_size"));
+    }
+
+    #[test]
+    fn test_llm_config() {
+        let mut config: LlmConfig = toml::from_str("").unwrap();
+        assert_eq!(config.provider, LlmProvider::Ollama);
+        assert_eq!(config.base_url(), "http://localhost:11434");
+        assert_eq!(config.context_chunks, 5);
+
+        config = toml::from_str("provider = \"openai\"\nmodel = \"gpt-4o-mini\"").unwrap();
+        assert_eq!(config.base_url(), "https://api.openai.com/v1");
+        config.url = Some("http://llm.internal:8000/v1/".to_string());
+        assert_eq!(config.base_url(), "http://llm.internal:8000/v1");
+
+        assert_eq!(
+            "openai".parse::<LlmProvider>().unwrap(),
+            LlmProvider::OpenAi
+        );
+        assert!("claude".parse::<LlmProvider>().is_err());
+    }
+
+    #[test]
+    fn test_sync_jobs() {
+        let config: Config = toml::from_str(
+            "[[sync]]\nsource = \"confluence\"\nproject = \"ENG\"\ntags = \"space:eng\"\ninterval = \"6h\"\n\n\
+             [[sync]]\nsource = \"jira\"\nquery = \"project = OPS\"\nlimit = 50\n",
+        )
+        .unwrap();
+        assert_eq!(config.sync.len(), 2);
+        assert_eq!(config.sync[0].source, SourceType::Confluence);
+        assert_eq!(config.sync[0].label(), "confluence ENG");
+        assert_eq!(
+            config.sync[0].interval().unwrap(),
+            Some(std::time::Duration::from_secs(6 * 3_600))
+        );
+        assert_eq!(config.sync[1].limit, Some(50));
+        assert_eq!(config.sync[1].interval().unwrap(), None);
+
+        assert_eq!(
+            parse_interval("30m").unwrap(),
+            std::time::Duration::from_secs(1_800)
+        );
+        assert!(parse_interval("0h").is_err());
+        assert!(parse_interval("6").is_err());
+        assert!(parse_interval("1w").is_err());
+    }
+
     #[test]
     fn test_config_source_display() {
         assert_eq!(format!("{}", ConfigSource::Default), "default");
@@ -1,7 +1,66 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::Path;
 
+use super::config::WORKSPACE_ATTRIBUTE;
+use super::search::SearchResult;
 use super::source::Source;
-use super::tag::Tag;
+use super::tag::{Tag, TagTemplate};
+use crate::utils::file::detect_language;
+
+/// Attribute key holding a chunk's language, from [`DocumentMetadata::language`].
+pub const LANGUAGE_ATTRIBUTE: &str = "language";
+
+/// Attribute key holding a local file's path relative to the project root,
+/// matched by `search --path`.
+pub const RELATIVE_PATH_ATTRIBUTE: &str = "relative_path";
+
+/// Tag key naming what kind of text a chunk holds.
+pub const CHUNK_TYPE_TAG: &str = "chunk_type";
+
+/// [`CHUNK_TYPE_TAG`] value of a document's summary chunk.
+pub const SUMMARY_CHUNK_TYPE: &str = "summary";
+
+/// The `chunk_type:summary` tag carried by summary chunks.
+pub fn summary_tag() -> Tag {
+    Tag {
+        key: CHUNK_TYPE_TAG.to_string(),
+        value: SUMMARY_CHUNK_TYPE.to_string(),
+    }
+}
+
+/// The `pii:true` tag added to documents flagged by `[indexing] pii = "flag"`.
+pub fn pii_tag() -> Tag {
+    Tag {
+        key: "pii".to_string(),
+        value: "true".to_string(),
+    }
+}
+
+/// Tag key marking chunks kept by `[indexing] filler = "flag"`.
+pub const FILLER_TAG: &str = "filler";
+
+/// Value of [`FILLER_TAG`] for chunks shorter than `[indexing] min_chunk_length`.
+pub const SHORT_FILLER: &str = "short";
+
+/// Value of [`FILLER_TAG`] for chunks repeated in `[indexing] boilerplate_min_files` files.
+pub const BOILERPLATE_FILLER: &str = "boilerplate";
+
+/// The `filler:<kind>` tag added to flagged short or boilerplate chunks.
+pub fn filler_tag(kind: &str) -> Tag {
+    Tag {
+        key: FILLER_TAG.to_string(),
+        value: kind.to_string(),
+    }
+}
+
+/// Attribute key holding the name of the code symbol enclosing a chunk.
+pub const SYMBOL_ATTRIBUTE: &str = "symbol";
+
+/// Attribute key holding the symbol's kind (`fn`, `struct`, `class`, ...).
+pub const SYMBOL_KIND_ATTRIBUTE: &str = "symbol_kind";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Document {
@@ -11,6 +70,9 @@ pub struct Document {
     pub tags: Vec<Tag>,
     pub checksum: String,
     pub metadata: DocumentMetadata,
+    /// Filterable key-value attributes (e.g., git commit, branch, author)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub attributes: BTreeMap<String, String>,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -25,6 +87,20 @@ pub struct DocumentMetadata {
     pub size_bytes: u64,
 }
 
+impl DocumentMetadata {
+    /// Metadata of a local file at `path` holding `content`.
+    pub fn for_file(path: &Path, content: &str) -> Self {
+        Self {
+            filename: path.file_name().map(|n| n.to_string_lossy().to_string()),
+            extension: path.extension().map(|e| e.to_string_lossy().to_string()),
+            language: detect_language(path),
+            title: None,
+            path: Some(path.to_string_lossy().to_string()),
+            size_bytes: content.len() as u64,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DocumentChunk {
     pub id: String,
@@ -38,13 +114,91 @@ pub struct DocumentChunk {
     pub line_end: Option<u32>,
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub dense_vector: Vec<f32>,
+    /// Embedding of [`DocumentChunk::title_text`], with `[vector_store] title_vectors`
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub title_vector: Vec<f32>,
     pub source: Source,
     pub tags: Vec<Tag>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub attributes: BTreeMap<String, String>,
     pub checksum: String,
     pub created_at: String,
+    /// Empty for chunks exported before updated_at was tracked
+    #[serde(default)]
+    pub updated_at: String,
+    /// When the chunk was soft-deleted; such chunks are left out of searches
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<String>,
 }
 
 impl Document {
+    /// Attach filterable attributes to the document.
+    #[must_use]
+    pub fn with_attributes(mut self, attributes: BTreeMap<String, String>) -> Self {
+        self.attributes = attributes;
+        self
+    }
+
+    /// Add the tags rendered from `templates`, skipping those whose
+    /// placeholders have no value and tags the document already has.
+    ///
+    /// Placeholders are looked up in `vars`, then `source`, `title`,
+    /// `filename`, `extension` and `language`, then the document's
+    /// attributes, then the keys of its existing tags.
+    pub fn add_template_tags(
+        &mut self,
+        templates: &[TagTemplate],
+        vars: &BTreeMap<String, String>,
+    ) {
+        let lookup = |name: &str| {
+            if let Some(value) = vars.get(name) {
+                return Some(value.clone());
+            }
+            let metadata = &self.metadata;
+            let builtin = match name {
+                "source" => Some(self.source.source_type.to_string()),
+                "title" => metadata.title.clone(),
+                "filename" => metadata.filename.clone(),
+                "extension" => metadata.extension.clone(),
+                "language" => metadata.language.clone(),
+                _ => None,
+            };
+            builtin
+                .or_else(|| self.attributes.get(name).cloned())
+                .or_else(|| {
+                    self.tags
+                        .iter()
+                        .find(|t| t.key == name)
+                        .map(|t| t.value.clone())
+                })
+        };
+        let rendered: Vec<Tag> = templates.iter().filter_map(|t| t.render(lookup)).collect();
+        for tag in rendered {
+            if !self.tags.contains(&tag) {
+                self.tags.push(tag);
+            }
+        }
+    }
+
+    /// Use the source system's timestamps instead of the indexing time.
+    ///
+    /// A missing `updated_at` falls back to `created_at`.
+    #[must_use]
+    pub fn with_timestamps(
+        mut self,
+        created_at: Option<DateTime<Utc>>,
+        updated_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        if let Some(created_at) = created_at {
+            self.created_at = created_at.to_rfc3339();
+            self.updated_at = self.created_at.clone();
+        }
+        if let Some(updated_at) = updated_at {
+            self.updated_at = updated_at.to_rfc3339();
+        }
+        self
+    }
+
     pub fn generate_id(source: &Source) -> String {
         use sha2::{Digest, Sha256};
         let input = format!("{}:{}", source.source_type, source.location);
@@ -68,6 +222,7 @@ impl Document {
             tags,
             checksum,
             metadata,
+            attributes: BTreeMap::new(),
             created_at: now.clone(),
             updated_at: now,
         }
@@ -75,12 +230,58 @@ impl Document {
 }
 
 impl DocumentChunk {
+    /// Whether this is the document's summary rather than a piece of its text.
+    pub fn is_summary(&self) -> bool {
+        self.tags.contains(&summary_tag())
+    }
+
+    /// Enclosing code symbol, e.g. `fn handle_search`.
+    pub fn symbol(&self) -> Option<String> {
+        let name = self.attributes.get(SYMBOL_ATTRIBUTE)?;
+        Some(match self.attributes.get(SYMBOL_KIND_ATTRIBUTE) {
+            Some(kind) => format!("{} {}", kind, name),
+            None => name.clone(),
+        })
+    }
+
+    /// Text embedded as the chunk's title vector: the file or page name,
+    /// followed by the markdown heading the chunk starts with or its symbol.
+    pub fn title_text(&self) -> String {
+        let location = self.source.location.trim_end_matches('/');
+        let name = location.rsplit('/').next().unwrap_or(location);
+        let heading = self
+            .content
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .filter(|line| line.starts_with('#'))
+            .map(|line| line.trim_start_matches('#').trim().to_string());
+        match heading.or_else(|| self.symbol()) {
+            Some(heading) if !heading.is_empty() => format!("{} - {}", name, heading),
+            _ => name.to_string(),
+        }
+    }
+
     pub fn generate_id(document_id: &str, chunk_index: u32) -> String {
         use uuid::Uuid;
         let name = format!("{}:{}", document_id, chunk_index);
         Uuid::new_v5(&Uuid::NAMESPACE_OID, name.as_bytes()).to_string()
     }
 
+    /// SHA-256 of the chunk text, used to detect the same content in other documents.
+    ///
+    /// Chunks from different workspaces never share a hash, so deduplication
+    /// stays within one workspace.
+    pub fn content_hash(&self) -> String {
+        let mut hasher = Sha256::new();
+        if let Some(workspace) = self.attributes.get(WORKSPACE_ATTRIBUTE) {
+            hasher.update(workspace.as_bytes());
+            hasher.update([0]);
+        }
+        hasher.update(self.content.as_bytes());
+        hex::encode(hasher.finalize())
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub fn from_document(
         document: &Document,
@@ -93,6 +294,21 @@ impl DocumentChunk {
         line_end: Option<u32>,
     ) -> Self {
         let id = Self::generate_id(&document.id, chunk_index);
+
+        // Language and extension are filterable like any other attribute
+        let mut attributes = document.attributes.clone();
+        let metadata = [
+            (LANGUAGE_ATTRIBUTE, &document.metadata.language),
+            ("extension", &document.metadata.extension),
+        ];
+        for (key, value) in metadata {
+            if let Some(value) = value {
+                attributes
+                    .entry(key.to_string())
+                    .or_insert_with(|| value.to_lowercase());
+            }
+        }
+
         Self {
             id,
             document_id: document.id.clone(),
@@ -104,17 +320,62 @@ impl DocumentChunk {
             line_start,
             line_end,
             dense_vector: Vec::new(),
+            title_vector: Vec::new(),
             source: document.source.clone(),
             tags: document.tags.clone(),
+            attributes,
             checksum: document.checksum.clone(),
             created_at: document.created_at.clone(),
+            updated_at: document.updated_at.clone(),
+            deleted_at: None,
+        }
+    }
+
+    /// Rebuild the chunk behind a search result, e.g. from saved `-f json` output.
+    ///
+    /// Offsets and the checksum aren't part of a result and are left empty.
+    pub fn from_search_result(result: SearchResult, dense_vector: Vec<f32>) -> Self {
+        let updated_at = result
+            .updated_at
+            .map(|t| t.to_rfc3339())
+            .unwrap_or_default();
+        Self {
+            id: result.chunk_id,
+            document_id: result.document_id,
+            content: result.content,
+            chunk_index: result.chunk_index,
+            total_chunks: 0,
+            start_offset: 0,
+            end_offset: 0,
+            line_start: result.line_start,
+            line_end: result.line_end,
+            dense_vector,
+            title_vector: Vec::new(),
+            source: result.source,
+            tags: result.tags,
+            attributes: result.attributes,
+            checksum: String::new(),
+            created_at: updated_at.clone(),
+            updated_at,
+            deleted_at: None,
         }
     }
 }
 
+/// Parse a timestamp reported by a source system.
+///
+/// Accepts RFC 3339 and the `+0900`-style offsets used by Jira.
+pub fn parse_timestamp(s: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(s)
+        .or_else(|_| DateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f%z"))
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::SourceType;
 
     #[test]
     fn test_document_generate_id() {
@@ -134,6 +395,35 @@ mod tests {
         assert_ne!(id, id3);
     }
 
+    #[test]
+    fn test_chunk_content_hash() {
+        let doc = Document::new(
+            "same text".to_string(),
+            Source::local("/a.md"),
+            vec![],
+            "checksum".to_string(),
+            DocumentMetadata::default(),
+        );
+        let other = Document::new(
+            "same text".to_string(),
+            Source::local("/b.md"),
+            vec![],
+            "checksum".to_string(),
+            DocumentMetadata::default(),
+        );
+        let a = DocumentChunk::from_document(&doc, "same text".to_string(), 0, 1, 0, 9, None, None);
+        let b =
+            DocumentChunk::from_document(&other, "same text".to_string(), 0, 1, 0, 9, None, None);
+        assert_ne!(a.id, b.id);
+        assert_eq!(a.content_hash(), b.content_hash());
+        assert_eq!(a.content_hash().len(), 64);
+
+        let mut c = b.clone();
+        c.attributes
+            .insert(WORKSPACE_ATTRIBUTE.to_string(), "/work/api".to_string());
+        assert_ne!(a.content_hash(), c.content_hash());
+    }
+
     #[test]
     fn test_document_new() {
         let source = Source::local("/test.rs");
@@ -147,4 +437,128 @@ mod tests {
         assert!(!doc.id.is_empty());
         assert!(!doc.created_at.is_empty());
     }
+
+    #[test]
+    fn test_add_template_tags() {
+        let mut doc = Document::new(
+            "content".to_string(),
+            Source::new(SourceType::Jira, "PLAT-1", None),
+            vec!["jira-project:plat".parse().unwrap()],
+            "checksum".to_string(),
+            DocumentMetadata::default(),
+        );
+        let templates: Vec<TagTemplate> = ["team:{jira-project}", "from:{source}", "owner:{owner}"]
+            .iter()
+            .map(|t| t.parse().unwrap())
+            .collect();
+        doc.add_template_tags(&templates, &BTreeMap::new());
+        let tags: Vec<String> = doc.tags.iter().map(ToString::to_string).collect();
+        assert_eq!(tags, vec!["jira-project:plat", "team:plat", "from:jira"]);
+
+        let vars = BTreeMap::from([("owner".to_string(), "Ana".to_string())]);
+        doc.add_template_tags(&templates, &vars);
+        assert_eq!(doc.tags.len(), 4);
+        assert_eq!(doc.tags[3].to_string(), "owner:ana");
+    }
+
+    #[test]
+    fn test_chunk_metadata_attributes() {
+        let metadata = DocumentMetadata {
+            extension: Some("RS".to_string()),
+            language: Some("rust".to_string()),
+            ..Default::default()
+        };
+        let doc = Document::new(
+            "fn main() {}".to_string(),
+            Source::local("/src/main.rs"),
+            vec![],
+            "checksum".to_string(),
+            metadata,
+        );
+        let chunk =
+            DocumentChunk::from_document(&doc, doc.content.clone(), 0, 1, 0, 12, None, None);
+        assert_eq!(
+            chunk.attributes.get("language").map(String::as_str),
+            Some("rust")
+        );
+        assert_eq!(
+            chunk.attributes.get("extension").map(String::as_str),
+            Some("rs")
+        );
+    }
+
+    #[test]
+    fn test_chunk_symbol() {
+        let doc = Document::new(
+            "fn main() {}".to_string(),
+            Source::local("/src/main.rs"),
+            vec![],
+            "checksum".to_string(),
+            DocumentMetadata::default(),
+        );
+        let mut chunk =
+            DocumentChunk::from_document(&doc, doc.content.clone(), 0, 1, 0, 12, None, None);
+        assert_eq!(chunk.symbol(), None);
+
+        chunk
+            .attributes
+            .insert(SYMBOL_ATTRIBUTE.to_string(), "main".to_string());
+        assert_eq!(chunk.symbol().as_deref(), Some("main"));
+        chunk
+            .attributes
+            .insert(SYMBOL_KIND_ATTRIBUTE.to_string(), "fn".to_string());
+        assert_eq!(chunk.symbol().as_deref(), Some("fn main"));
+    }
+
+    #[test]
+    fn test_chunk_title_text() {
+        let doc = Document::new(
+            String::new(),
+            Source::local("/docs/retry.md"),
+            vec![],
+            "checksum".to_string(),
+            DocumentMetadata::default(),
+        );
+        let chunk = |content: &str| {
+            DocumentChunk::from_document(&doc, content.to_string(), 0, 1, 0, 0, None, None)
+        };
+
+        assert_eq!(
+            chunk("\n## Backoff policy\nDelays double.").title_text(),
+            "retry.md - Backoff policy"
+        );
+        assert_eq!(chunk("Delays double.").title_text(), "retry.md");
+
+        let mut code = chunk("fn with_retry() {}");
+        code.attributes
+            .insert(SYMBOL_ATTRIBUTE.to_string(), "with_retry".to_string());
+        code.attributes
+            .insert(SYMBOL_KIND_ATTRIBUTE.to_string(), "fn".to_string());
+        assert_eq!(code.title_text(), "retry.md - fn with_retry");
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        assert_eq!(
+            parse_timestamp("2024-03-01T09:30:00.000+0900").map(|t| t.to_rfc3339()),
+            Some("2024-03-01T00:30:00+00:00".to_string())
+        );
+        assert!(parse_timestamp("2024-03-01T00:30:00Z").is_some());
+        assert!(parse_timestamp("yesterday").is_none());
+    }
+
+    #[test]
+    fn test_with_timestamps() {
+        let created = parse_timestamp("2024-01-01T00:00:00Z");
+        let doc = Document::new(
+            "content".to_string(),
+            Source::local("/test.rs"),
+            vec![],
+            "checksum".to_string(),
+            DocumentMetadata::default(),
+        )
+        .with_timestamps(created, None);
+        assert_eq!(doc.created_at, "2024-01-01T00:00:00+00:00");
+        assert_eq!(doc.updated_at, doc.created_at);
+    }
 }
@@ -5,12 +5,23 @@ mod source;
 mod tag;
 
 pub use config::{
+    AnnIndex, ChunkOverride, ChunkOverrides, ChunkStrategy, ChunkTokenizer, CollectionConfig,
     Config, ConfigSource, ConfigSources, DEFAULT_COLLECTION, DEFAULT_EMBEDDING_DIMENSION,
     DEFAULT_EMBEDDING_MODEL, DEFAULT_IDLE_TIMEOUT_SECS, DEFAULT_METRICS_RETENTION_DAYS,
-    DEFAULT_QDRANT_URL, DaemonConfig, EmbeddingConfig, IndexingConfig, MetricsConfig,
-    PartialConfig, ResolvedConfig, SearchConfig, VectorDriver, VectorStoreConfig,
+    DEFAULT_QDRANT_URL, DaemonConfig, Device, EmbeddingConfig, FillerPolicy, IndexingConfig,
+    LlmConfig, LlmProvider, LogFormat, MetricsConfig, PartialConfig, PiiPolicy, Quantization,
+    QueryExpansion, ResolvedConfig, RetentionConfig, SavedQuery, SearchConfig, SummaryMode,
+    SyncJob, VectorDriver, VectorStoreConfig, WORKSPACE_ATTRIBUTE, WorkspaceConfig,
+};
+pub use document::{
+    BOILERPLATE_FILLER, CHUNK_TYPE_TAG, Document, DocumentChunk, DocumentMetadata, FILLER_TAG,
+    LANGUAGE_ATTRIBUTE, RELATIVE_PATH_ATTRIBUTE, SHORT_FILLER, SUMMARY_CHUNK_TYPE,
+    SYMBOL_ATTRIBUTE, SYMBOL_KIND_ATTRIBUTE, filler_tag, parse_timestamp, pii_tag, summary_tag,
+};
+pub use search::{
+    Answer, AttributeFilter, Deleted, FilterExpr, OutputFormat, PathPattern, RangeOp, RankedScore,
+    ScoreExplanation, SearchFilter, SearchQuery, SearchResult, SearchResults, TimeBound, TimeField,
+    attribute_payload, parse_attribute_payload, parse_datetime,
 };
-pub use document::{Document, DocumentChunk, DocumentMetadata};
-pub use search::{OutputFormat, SearchQuery, SearchResult, SearchResults};
 pub use source::{Source, SourceType};
-pub use tag::{Tag, parse_tags};
+pub use tag::{Tag, TagTemplate, parse_tags};
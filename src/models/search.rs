@@ -1,9 +1,19 @@
 //! Search-related models for queries and results.
 
+use chrono::{DateTime, NaiveDate, NaiveTime, TimeDelta, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
 
+use super::config::WORKSPACE_ATTRIBUTE;
+use super::document::{
+    LANGUAGE_ATTRIBUTE, RELATIVE_PATH_ATTRIBUTE, SYMBOL_ATTRIBUTE, SYMBOL_KIND_ATTRIBUTE,
+    summary_tag,
+};
 use super::source::{Source, SourceType};
 use super::tag::Tag;
+use crate::error::SearchError;
 
 /// Output format for search results.
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
@@ -48,8 +58,13 @@ pub struct SearchQuery {
     pub limit: u32,
     pub tags: Vec<Tag>,
     pub source_types: Vec<SourceType>,
+    #[serde(default)]
+    pub attributes: Vec<AttributeFilter>,
     pub format: OutputFormat,
     pub min_score: Option<f32>,
+    /// Fuse keyword relevance with semantic similarity
+    #[serde(default)]
+    pub hybrid: bool,
 }
 
 impl Default for SearchQuery {
@@ -59,8 +74,10 @@ impl Default for SearchQuery {
             limit: 10,
             tags: Vec::new(),
             source_types: Vec::new(),
+            attributes: Vec::new(),
             format: OutputFormat::Text,
             min_score: None,
+            hybrid: false,
         }
     }
 }
@@ -95,6 +112,21 @@ impl SearchQuery {
         self
     }
 
+    /// Add attribute filters.
+    #[must_use]
+    pub fn with_attributes(mut self, attributes: Vec<AttributeFilter>) -> Self {
+        self.attributes = attributes;
+        self
+    }
+
+    /// Vector store filter for this query.
+    pub fn filter(&self) -> SearchFilter {
+        SearchFilter::new()
+            .with_tags(self.tags.clone())
+            .with_source_types(self.source_types.clone())
+            .with_attributes(self.attributes.clone())
+    }
+
     /// Set the output format.
     #[must_use]
     pub fn with_format(mut self, format: OutputFormat) -> Self {
@@ -108,12 +140,558 @@ impl SearchQuery {
         self.min_score = Some(min_score);
         self
     }
+
+    /// Enable hybrid (semantic + keyword) search.
+    #[must_use]
+    pub fn with_hybrid(mut self, hybrid: bool) -> Self {
+        self.hybrid = hybrid;
+        self
+    }
+}
+
+/// Exact-match filter on a document attribute (e.g., `branch:main`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AttributeFilter {
+    pub key: String,
+    pub value: String,
+}
+
+impl AttributeFilter {
+    pub fn new(key: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            value: value.into(),
+        }
+    }
+
+    /// Format as stored in the vector store payload (`key=value`).
+    pub fn to_payload_string(&self) -> String {
+        attribute_payload(&self.key, &self.value)
+    }
+}
+
+impl FromStr for AttributeFilter {
+    type Err = SearchError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (key, value) = s
+            .split_once(':')
+            .map(|(k, v)| (k.trim(), v.trim()))
+            .filter(|(k, v)| !k.is_empty() && !v.is_empty())
+            .ok_or_else(|| {
+                SearchError::InvalidQuery(format!("filter must be key:value, got '{}'", s))
+            })?;
+        Ok(Self::new(key, value))
+    }
+}
+
+impl fmt::Display for AttributeFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.key, self.value)
+    }
+}
+
+/// Format a document attribute as stored in the vector store payload.
+pub fn attribute_payload(key: &str, value: &str) -> String {
+    format!("{}={}", key, value)
+}
+
+/// Parse a payload attribute string (`key=value`) back into its parts.
+pub fn parse_attribute_payload(s: &str) -> Option<(String, String)> {
+    s.split_once('=')
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+}
+
+/// Comparison operator of a range filter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RangeOp {
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+}
+
+impl RangeOp {
+    /// Operator as written in filters and SQL.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RangeOp::Gt => ">",
+            RangeOp::Gte => ">=",
+            RangeOp::Lt => "<",
+            RangeOp::Lte => "<=",
+        }
+    }
+}
+
+/// Document timestamp a [`TimeBound`] applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeField {
+    /// When the document was created in its source (or first indexed)
+    CreatedAt,
+    /// When the document was last modified in its source (or indexed)
+    UpdatedAt,
+}
+
+impl TimeField {
+    /// Payload field and column name.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TimeField::CreatedAt => "created_at",
+            TimeField::UpdatedAt => "updated_at",
+        }
+    }
+}
+
+/// Bound on a document timestamp (e.g., `created_at>=2024-01-01`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeBound {
+    pub field: TimeField,
+    pub op: RangeOp,
+    pub value: DateTime<Utc>,
+}
+
+impl TimeBound {
+    pub fn new(field: TimeField, op: RangeOp, value: DateTime<Utc>) -> Self {
+        Self { field, op, value }
+    }
+}
+
+/// Glob over source locations (e.g., `src/**/*.rs`).
+///
+/// `*` and `?` stay within a path segment, `**` spans segments. A pattern
+/// without wildcards matches as a prefix, and relative patterns may start at
+/// any directory so they work against absolute indexed paths.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct PathPattern(String);
+
+impl PathPattern {
+    pub fn new(pattern: impl Into<String>) -> Self {
+        Self(pattern.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Equivalent anchored regex, valid for both the `regex` crate and PostgreSQL `~`.
+    pub fn to_regex(&self) -> String {
+        let anchor = if self.0.starts_with('/') {
+            "^"
+        } else {
+            "^(.*/)?"
+        };
+        glob_regex(&self.0, anchor)
+    }
+
+    /// Regex over the `relative_path=...` attribute payload, with the pattern
+    /// anchored at the project root ([`RELATIVE_PATH_ATTRIBUTE`]).
+    pub fn to_relative_regex(&self) -> String {
+        let pattern = self.0.trim_start_matches("./").trim_start_matches('/');
+        let anchor = format!("^{}=", regex::escape(RELATIVE_PATH_ATTRIBUTE));
+        glob_regex(pattern, &anchor)
+    }
+}
+
+/// Translate a glob into a regex starting with `anchor`; a pattern without
+/// wildcards matches as a prefix.
+fn glob_regex(pattern: &str, anchor: &str) -> String {
+    let mut pattern = pattern.to_string();
+    if !pattern.contains(['*', '?']) {
+        pattern.push_str("**");
+    }
+
+    let mut regex = String::from(anchor);
+    let mut rest = pattern.as_str();
+    while let Some(c) = rest.chars().next() {
+        if let Some(after) = rest.strip_prefix("**/") {
+            regex.push_str("(.*/)?");
+            rest = after;
+            continue;
+        }
+        if let Some(after) = rest.strip_prefix("**") {
+            regex.push_str(".*");
+            rest = after;
+            continue;
+        }
+        match c {
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            _ => regex.push_str(&regex::escape(&c.to_string())),
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+    regex.push('$');
+    regex
+}
+
+impl fmt::Display for PathPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// A parsed `--filter` expression.
+///
+/// - `key=value` / `key:value`: exact match on a document attribute
+///   (`branch`, `author`, `language`, `extension`, ...)
+/// - `path~glob`: source location matches a [`PathPattern`]
+/// - `symbol~text`: the enclosing code symbol's name contains `text`
+/// - `created_at>=date`, `updated_at<date` (also `>`, `<=`): see [`parse_datetime`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterExpr {
+    Attribute(AttributeFilter),
+    Path(PathPattern),
+    Symbol(String),
+    Time(TimeBound),
+}
+
+impl FromStr for FilterExpr {
+    type Err = SearchError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        const OPERATORS: [&str; 7] = [">=", "<=", ">", "<", "~", "=", ":"];
+
+        let invalid = |reason: &str| SearchError::InvalidQuery(format!("{reason}, got '{s}'"));
+
+        let s = s.trim();
+        let key_end = s
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.'))
+            .unwrap_or(s.len());
+        let (key, rest) = s.split_at(key_end);
+        let rest = rest.trim_start();
+        let op = OPERATORS
+            .iter()
+            .find(|op| rest.starts_with(**op))
+            .ok_or_else(|| invalid("filter must be key=value, path~glob or created_at>=date"))?;
+        let value = rest[op.len()..].trim();
+        if key.is_empty() || value.is_empty() {
+            return Err(invalid(
+                "filter must be key=value, path~glob or created_at>=date",
+            ));
+        }
+
+        match (key, *op) {
+            ("path", "~" | "=" | ":") => Ok(Self::Path(PathPattern::new(value))),
+            ("symbol", "~") => Ok(Self::Symbol(value.to_string())),
+            ("created_at" | "created" | "updated_at" | "updated", _) => {
+                let field = if key.starts_with("created") {
+                    TimeField::CreatedAt
+                } else {
+                    TimeField::UpdatedAt
+                };
+                let op = match *op {
+                    ">" => RangeOp::Gt,
+                    ">=" => RangeOp::Gte,
+                    "<" => RangeOp::Lt,
+                    "<=" => RangeOp::Lte,
+                    _ => return Err(invalid("timestamps take >, >=, < or <=")),
+                };
+                Ok(Self::Time(TimeBound::new(
+                    field,
+                    op,
+                    parse_datetime(value)?,
+                )))
+            }
+            (_, "=" | ":") => Ok(Self::Attribute(AttributeFilter::new(key, value))),
+            (_, "~") => Err(invalid("'~' is only supported for path and symbol")),
+            _ => Err(invalid(
+                "range comparisons are only supported for created_at and updated_at",
+            )),
+        }
+    }
+}
+
+impl fmt::Display for FilterExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FilterExpr::Attribute(attribute) => write!(f, "{}={}", attribute.key, attribute.value),
+            FilterExpr::Path(pattern) => write!(f, "path~{}", pattern),
+            FilterExpr::Symbol(text) => write!(f, "symbol~{}", text),
+            FilterExpr::Time(bound) => write!(
+                f,
+                "{}{}{}",
+                bound.field.as_str(),
+                bound.op.as_str(),
+                bound.value.to_rfc3339()
+            ),
+        }
+    }
+}
+
+/// Parse an RFC 3339 timestamp, a `YYYY-MM-DD` date (midnight UTC), or an
+/// age relative to now (`12h`, `30d`, `2w`, `1y`).
+pub fn parse_datetime(s: &str) -> Result<DateTime<Utc>, SearchError> {
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(s) {
+        return Ok(datetime.with_timezone(&Utc));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return Ok(date.and_time(NaiveTime::MIN).and_utc());
+    }
+    if let Some(age) = parse_age(s) {
+        return Ok(Utc::now() - age);
+    }
+    Err(SearchError::InvalidQuery(format!(
+        "invalid date '{}': expected YYYY-MM-DD, RFC 3339 or an age like 30d",
+        s
+    )))
+}
+
+/// Parse an age such as `12h`, `30d`, `2w` or `1y`.
+pub(crate) fn parse_age(s: &str) -> Option<TimeDelta> {
+    let unit_start = s.find(|c: char| !c.is_ascii_digit())?;
+    let (amount, unit) = s.split_at(unit_start);
+    let amount: i64 = amount.parse().ok()?;
+    match unit {
+        "h" => TimeDelta::try_hours(amount),
+        "d" => TimeDelta::try_days(amount),
+        "w" => TimeDelta::try_weeks(amount),
+        "y" => TimeDelta::try_days(amount.checked_mul(365)?),
+        _ => None,
+    }
+}
+
+/// Which chunks a [`SearchFilter`] matches by their soft-delete state.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Deleted {
+    /// Only live chunks (searches, counts, listings)
+    #[default]
+    Live,
+    /// Only soft-deleted chunks (restore)
+    Only,
+    /// Both (hard deletes, pruning and re-embedding)
+    Any,
+}
+
+impl Deleted {
+    pub fn is_live(&self) -> bool {
+        *self == Deleted::Live
+    }
+
+    /// Whether a chunk with this soft-delete state matches.
+    pub fn matches(self, is_deleted: bool) -> bool {
+        match self {
+            Deleted::Live => !is_deleted,
+            Deleted::Only => is_deleted,
+            Deleted::Any => true,
+        }
+    }
+}
+
+/// Filters applied to vector store searches. All conditions must match.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SearchFilter {
+    /// Documents must carry every tag
+    pub tags: Vec<Tag>,
+    /// Documents must come from one of these sources (any if empty)
+    pub source_types: Vec<SourceType>,
+    /// Documents must match every attribute
+    pub attributes: Vec<AttributeFilter>,
+    /// Documents must be in one of these languages
+    #[serde(default)]
+    pub languages: Vec<String>,
+    /// Source locations must match every pattern
+    #[serde(default)]
+    pub paths: Vec<PathPattern>,
+    /// Paths relative to the project root must match every pattern (`--path`)
+    #[serde(default)]
+    pub relative_paths: Vec<PathPattern>,
+    /// Enclosing symbol names must contain every one of these
+    #[serde(default)]
+    pub symbols: Vec<String>,
+    /// Document timestamps must satisfy every bound
+    #[serde(default)]
+    pub time_bounds: Vec<TimeBound>,
+    /// Chunks must belong to one of these documents, when set; an empty list matches nothing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub document_ids: Option<Vec<String>>,
+    /// Soft-delete state chunks must be in; live only by default
+    #[serde(default, skip_serializing_if = "Deleted::is_live")]
+    pub deleted: Deleted,
+}
+
+impl SearchFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn with_tags(mut self, tags: Vec<Tag>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    #[must_use]
+    pub fn with_source_types(mut self, source_types: Vec<SourceType>) -> Self {
+        self.source_types = source_types;
+        self
+    }
+
+    #[must_use]
+    pub fn with_attributes(mut self, attributes: Vec<AttributeFilter>) -> Self {
+        self.attributes = attributes;
+        self
+    }
+
+    #[must_use]
+    pub fn with_languages(mut self, languages: Vec<String>) -> Self {
+        self.languages = languages;
+        self
+    }
+
+    /// Payload strings matching any of the filter's languages.
+    pub fn language_payloads(&self) -> Vec<String> {
+        self.languages
+            .iter()
+            .map(|language| attribute_payload(LANGUAGE_ATTRIBUTE, language))
+            .collect()
+    }
+
+    #[must_use]
+    pub fn with_paths(mut self, paths: Vec<PathPattern>) -> Self {
+        self.paths = paths;
+        self
+    }
+
+    #[must_use]
+    pub fn with_relative_paths(mut self, paths: Vec<PathPattern>) -> Self {
+        self.relative_paths = paths;
+        self
+    }
+
+    #[must_use]
+    pub fn with_time_bounds(mut self, bounds: Vec<TimeBound>) -> Self {
+        self.time_bounds = bounds;
+        self
+    }
+
+    #[must_use]
+    pub fn with_document_ids(mut self, document_ids: Vec<String>) -> Self {
+        self.document_ids = Some(document_ids);
+        self
+    }
+
+    #[must_use]
+    pub fn with_deleted(mut self, deleted: Deleted) -> Self {
+        self.deleted = deleted;
+        self
+    }
+
+    /// Add parsed `--filter` expressions to the filter.
+    #[must_use]
+    pub fn with_expressions(mut self, expressions: Vec<FilterExpr>) -> Self {
+        for expression in expressions {
+            match expression {
+                FilterExpr::Attribute(attribute) => self.attributes.push(attribute),
+                FilterExpr::Path(pattern) => self.paths.push(pattern),
+                FilterExpr::Symbol(text) => self.symbols.push(text),
+                FilterExpr::Time(bound) => self.time_bounds.push(bound),
+            }
+        }
+        self
+    }
+
+    /// Restrict results to chunks indexed in `workspace`, if one is given.
+    #[must_use]
+    pub fn with_workspace(mut self, workspace: Option<&str>) -> Self {
+        if let Some(workspace) = workspace {
+            self.attributes
+                .push(AttributeFilter::new(WORKSPACE_ATTRIBUTE, workspace));
+        }
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+            && self.source_types.is_empty()
+            && self.attributes.is_empty()
+            && self.languages.is_empty()
+            && self.paths.is_empty()
+            && self.relative_paths.is_empty()
+            && self.symbols.is_empty()
+            && self.time_bounds.is_empty()
+            && self.document_ids.is_none()
+            && self.deleted.is_live()
+    }
+
+    /// Whether a chunk's enclosing symbol contains every `symbol~` substring.
+    pub fn matches_symbol(&self, attributes: &BTreeMap<String, String>) -> bool {
+        self.symbols.is_empty()
+            || attributes
+                .get(SYMBOL_ATTRIBUTE)
+                .is_some_and(|name| self.symbols.iter().all(|s| name.contains(s.as_str())))
+    }
+}
+
+/// Space-separated conditions, e.g. `tags=space:common sources=jira language=rust`.
+impl fmt::Display for SearchFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |items: Vec<String>| items.join(",");
+        let mut parts = Vec::new();
+        if !self.tags.is_empty() {
+            parts.push(format!(
+                "tags={}",
+                join(self.tags.iter().map(ToString::to_string).collect())
+            ));
+        }
+        if !self.source_types.is_empty() {
+            parts.push(format!(
+                "sources={}",
+                join(self.source_types.iter().map(ToString::to_string).collect())
+            ));
+        }
+        if !self.languages.is_empty() {
+            parts.push(format!("languages={}", join(self.languages.clone())));
+        }
+        parts.extend(
+            self.attributes
+                .iter()
+                .map(|a| FilterExpr::Attribute(a.clone()).to_string()),
+        );
+        parts.extend(
+            self.paths
+                .iter()
+                .map(|p| FilterExpr::Path(p.clone()).to_string()),
+        );
+        parts.extend(
+            self.relative_paths
+                .iter()
+                .map(|p| format!("relative_path~{}", p)),
+        );
+        parts.extend(
+            self.symbols
+                .iter()
+                .map(|s| FilterExpr::Symbol(s.clone()).to_string()),
+        );
+        parts.extend(
+            self.time_bounds
+                .iter()
+                .map(|b| FilterExpr::Time(*b).to_string()),
+        );
+        if let Some(ref document_ids) = self.document_ids {
+            parts.push(format!("documents={}", join(document_ids.clone())));
+        }
+        match self.deleted {
+            Deleted::Live => {}
+            Deleted::Only => parts.push("deleted".to_string()),
+            Deleted::Any => parts.push("including deleted".to_string()),
+        }
+        f.write_str(&parts.join(" "))
+    }
 }
 
 /// A single search result.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchResult {
     pub chunk_id: String,
+    #[serde(default)]
+    pub document_id: String,
+    /// Position of the chunk within its document
+    #[serde(default)]
+    pub chunk_index: u32,
     pub score: f32,
     pub content: String,
     pub source: Source,
@@ -121,6 +699,51 @@ pub struct SearchResult {
     pub location: String,
     pub line_start: Option<u32>,
     pub line_end: Option<u32>,
+    /// Document attributes (e.g., git commit, branch, author)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub attributes: BTreeMap<String, String>,
+    /// When the document was last modified in its source
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime<Utc>>,
+    /// Content of the preceding chunks of the document, oldest first (`--context`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub context_before: Vec<String>,
+    /// Content of the following chunks of the document (`--context`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub context_after: Vec<String>,
+    /// How the score came about (`--explain`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub explanation: Option<ScoreExplanation>,
+    /// Found by the keyword fallback, so `score` is a keyword rank rather than a similarity
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub keyword_match: bool,
+}
+
+/// Breakdown of a result's score, reported by `search --explain`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScoreExplanation {
+    /// Cosine similarity to the original query, before fusion, reranking or recency
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_score: Option<f32>,
+    /// Percentage of recent searches whose top score was at or below `raw_score`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub percentile: Option<f32>,
+    /// Tag filters the result satisfied
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub matched_tags: Vec<Tag>,
+    /// Position in the dense (semantic) ranking of a hybrid search
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dense: Option<RankedScore>,
+    /// Position in the keyword (BM25) ranking of a hybrid search
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keyword: Option<RankedScore>,
+}
+
+/// A result's 1-based rank and score within one ranking.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RankedScore {
+    pub rank: u32,
+    pub score: f32,
 }
 
 /// Collection of search results.
@@ -135,6 +758,10 @@ pub struct SearchResults {
     /// Total matches (before limit)
     pub total: u64,
 
+    /// Number of results skipped before this page
+    #[serde(default)]
+    pub offset: u64,
+
     /// Query execution time in milliseconds
     pub duration_ms: u64,
 }
@@ -146,10 +773,18 @@ impl SearchResults {
             query,
             results,
             total,
+            offset: 0,
             duration_ms,
         }
     }
 
+    /// Mark these results as a page starting after `offset` results.
+    #[must_use]
+    pub fn with_offset(mut self, offset: u64) -> Self {
+        self.offset = offset;
+        self
+    }
+
     /// Check if there are no results.
     pub fn is_empty(&self) -> bool {
         self.results.is_empty()
@@ -161,6 +796,44 @@ impl SearchResults {
     }
 }
 
+impl SearchResult {
+    /// Whether the result is a document summary chunk.
+    pub fn is_summary(&self) -> bool {
+        self.tags.contains(&summary_tag())
+    }
+
+    /// Enclosing code symbol, e.g. `fn handle_search`.
+    pub fn symbol(&self) -> Option<String> {
+        let name = self.attributes.get(SYMBOL_ATTRIBUTE)?;
+        Some(match self.attributes.get(SYMBOL_KIND_ATTRIBUTE) {
+            Some(kind) => format!("{} {}", kind, name),
+            None => name.clone(),
+        })
+    }
+
+    /// Location with its line range, e.g. `src/lib.rs:10-42`.
+    pub fn citation(&self) -> String {
+        match (self.line_start, self.line_end) {
+            (Some(start), Some(end)) if end > start => {
+                format!("{}:{}-{}", self.location, start, end)
+            }
+            (Some(start), _) => format!("{}:{}", self.location, start),
+            _ => self.location.clone(),
+        }
+    }
+}
+
+/// An LLM answer to a question, with the search results it was given.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Answer {
+    pub question: String,
+    /// Answer text, citing sources as `[n]` (1-based index into `sources`)
+    pub answer: String,
+    pub model: String,
+    pub sources: Vec<SearchResult>,
+    pub duration_ms: u64,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -180,12 +853,187 @@ mod tests {
         let query = SearchQuery::new("authentication")
             .with_limit(20)
             .with_min_score(0.5)
-            .with_format(OutputFormat::Json);
+            .with_format(OutputFormat::Json)
+            .with_hybrid(true);
 
         assert_eq!(query.query, "authentication");
         assert_eq!(query.limit, 20);
         assert_eq!(query.min_score, Some(0.5));
         assert_eq!(query.format, OutputFormat::Json);
+        assert!(query.hybrid);
+    }
+
+    #[test]
+    fn test_filter_expr_parse() {
+        let expr: FilterExpr = "language=rust".parse().unwrap();
+        assert_eq!(
+            expr,
+            FilterExpr::Attribute(AttributeFilter::new("language", "rust"))
+        );
+        let expr: FilterExpr = "branch:main".parse().unwrap();
+        assert_eq!(
+            expr,
+            FilterExpr::Attribute(AttributeFilter::new("branch", "main"))
+        );
+
+        let expr: FilterExpr = "path~src/**/*.rs".parse().unwrap();
+        assert_eq!(expr, FilterExpr::Path(PathPattern::new("src/**/*.rs")));
+
+        let expr: FilterExpr = "created_at >= 2024-01-01".parse().unwrap();
+        let FilterExpr::Time(bound) = expr else {
+            panic!("expected time bound");
+        };
+        assert_eq!(bound.field, TimeField::CreatedAt);
+        assert_eq!(bound.op, RangeOp::Gte);
+        assert_eq!(bound.value.to_rfc3339(), "2024-01-01T00:00:00+00:00");
+
+        let expr: FilterExpr = "created<2024-06-01T12:00:00+09:00".parse().unwrap();
+        let FilterExpr::Time(bound) = expr else {
+            panic!("expected time bound");
+        };
+        assert_eq!(bound.op, RangeOp::Lt);
+        assert_eq!(bound.value.to_rfc3339(), "2024-06-01T03:00:00+00:00");
+
+        assert!("branch~main".parse::<FilterExpr>().is_err());
+        assert!("size>10".parse::<FilterExpr>().is_err());
+        assert!("created_at=2024-01-01".parse::<FilterExpr>().is_err());
+        assert!("created_at>=yesterday".parse::<FilterExpr>().is_err());
+
+        let expr: FilterExpr = "updated_at>=7d".parse().unwrap();
+        let FilterExpr::Time(bound) = expr else {
+            panic!("expected time bound");
+        };
+        assert_eq!(bound.field, TimeField::UpdatedAt);
+        let age = Utc::now() - bound.value;
+        assert!((age - TimeDelta::days(7)).num_seconds().abs() < 60);
+        assert!("path~".parse::<FilterExpr>().is_err());
+        assert!("=rust".parse::<FilterExpr>().is_err());
+
+        let expr: FilterExpr = "symbol~handle_".parse().unwrap();
+        assert_eq!(expr, FilterExpr::Symbol("handle_".to_string()));
+        let filter = SearchFilter::default().with_expressions(vec![expr]);
+        let attributes =
+            BTreeMap::from([(SYMBOL_ATTRIBUTE.to_string(), "handle_search".to_string())]);
+        assert!(filter.matches_symbol(&attributes));
+        assert!(!filter.matches_symbol(&BTreeMap::new()));
+        assert_eq!(filter.to_string(), "symbol~handle_");
+    }
+
+    #[test]
+    fn test_parse_datetime() {
+        assert_eq!(
+            parse_datetime("2024-01-01").unwrap().to_rfc3339(),
+            "2024-01-01T00:00:00+00:00"
+        );
+        let since = parse_datetime("30d").unwrap();
+        let age = Utc::now() - since;
+        assert!((age - TimeDelta::days(30)).num_seconds().abs() < 60);
+        assert!(parse_datetime("2w").is_ok());
+        assert!(parse_datetime("1y").is_ok());
+        assert!(parse_datetime("30").is_err());
+        assert!(parse_datetime("30m").is_err());
+        assert!(parse_datetime("d").is_err());
+    }
+
+    #[test]
+    fn test_path_pattern_regex() {
+        let matches = |pattern: &str, path: &str| {
+            regex::Regex::new(&PathPattern::new(pattern).to_regex())
+                .unwrap()
+                .is_match(path)
+        };
+
+        assert!(matches("src/**/*.rs", "/home/me/app/src/cli/mod.rs"));
+        assert!(matches("src/**/*.rs", "/home/me/app/src/main.rs"));
+        assert!(!matches("src/**/*.rs", "/home/me/app/src/main.py"));
+        assert!(matches("*.md", "/home/me/app/docs/README.md"));
+        assert!(!matches("src/*.rs", "/home/me/app/src/cli/mod.rs"));
+        assert!(matches("/home/me/app/src", "/home/me/app/src/cli/mod.rs"));
+        assert!(!matches(
+            "/home/me/app/src",
+            "/other/home/me/app/src/main.rs"
+        ));
+        assert!(matches("PROJ-", "PROJ-123"));
+        assert!(matches("file?.txt", "/tmp/file1.txt"));
+        assert!(!matches("file?.txt", "/tmp/file12.txt"));
+
+        let relative = |pattern: &str, path: &str| {
+            regex::Regex::new(&PathPattern::new(pattern).to_relative_regex())
+                .unwrap()
+                .is_match(&attribute_payload(RELATIVE_PATH_ATTRIBUTE, path))
+        };
+        assert!(relative("src/services/**", "src/services/fusion.rs"));
+        assert!(relative(
+            "./src/services/",
+            "src/services/vector_store/mod.rs"
+        ));
+        assert!(!relative("src/**", "vendor/lib/src/main.rs"));
+        assert!(!relative("services", "src/services/fusion.rs"));
+    }
+
+    #[test]
+    fn test_attribute_filter_parse() {
+        let filter: AttributeFilter = "branch:main".parse().unwrap();
+        assert_eq!(filter.key, "branch");
+        assert_eq!(filter.value, "main");
+        assert_eq!(filter.to_payload_string(), "branch=main");
+
+        let filter: AttributeFilter = "branch:feature/x:y".parse().unwrap();
+        assert_eq!(filter.value, "feature/x:y");
+
+        assert!("branch".parse::<AttributeFilter>().is_err());
+        assert!(":main".parse::<AttributeFilter>().is_err());
+    }
+
+    #[test]
+    fn test_attribute_payload_roundtrip() {
+        let payload = attribute_payload("author", "Jane Doe");
+        assert_eq!(
+            parse_attribute_payload(&payload),
+            Some(("author".to_string(), "Jane Doe".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_search_query_filter() {
+        let query = SearchQuery::new("q")
+            .with_attributes(vec![AttributeFilter::new("branch", "main")])
+            .with_source_types(vec![SourceType::Local]);
+        let filter = query.filter();
+        assert_eq!(filter.attributes.len(), 1);
+        assert_eq!(filter.source_types, vec![SourceType::Local]);
+        assert!(SearchFilter::new().is_empty());
+    }
+
+    #[test]
+    fn test_search_filter_display() {
+        let filter = SearchFilter::new()
+            .with_tags(vec![Tag::new("space", "common").unwrap()])
+            .with_source_types(vec![SourceType::Jira, SourceType::Local])
+            .with_expressions(vec![
+                "language=rust".parse().unwrap(),
+                "path~src/**".parse().unwrap(),
+            ]);
+        assert_eq!(
+            filter.to_string(),
+            "tags=space:common sources=jira,local language=rust path~src/**"
+        );
+        assert_eq!(SearchFilter::new().to_string(), "");
+    }
+
+    #[test]
+    fn test_search_result_context_serialization() {
+        let json = r#"{"chunk_id": "c1", "score": 0.9, "content": "body",
+            "source": {"source_type": "local", "location": "/a.md", "url": null},
+            "tags": [], "location": "/a.md", "line_start": null, "line_end": null}"#;
+        let mut result: SearchResult = serde_json::from_str(json).unwrap();
+        assert_eq!(result.chunk_index, 0);
+        assert!(result.context_before.is_empty());
+        assert!(!serde_json::to_string(&result).unwrap().contains("context_"));
+
+        result.context_after = vec!["next".to_string()];
+        let value = serde_json::to_value(&result).unwrap();
+        assert_eq!(value["context_after"][0], "next");
     }
 
     #[test]
@@ -18,7 +18,17 @@ pub enum SourceType {
     Confluence,
     /// Figma designs
     Figma,
-    /// Any other source type (e.g., "notion", "slack", "github")
+    /// GitHub issues, pull requests, and discussions
+    GitHub,
+    /// Slack channel threads
+    Slack,
+    /// Google Docs and Sheets from Google Drive
+    GoogleDrive,
+    /// Email threads from mbox files or IMAP
+    Mail,
+    /// Web pages
+    Web,
+    /// Any other source type (e.g., "notion", "linear")
     Other(String),
 }
 
@@ -30,6 +40,10 @@ impl SourceType {
         match self {
             SourceType::Jira | SourceType::Confluence => Some("atlassian"),
             SourceType::Figma => Some("figma"),
+            SourceType::GitHub => Some("gh"),
+            SourceType::Slack | SourceType::GoogleDrive | SourceType::Mail | SourceType::Web => {
+                Some("curl")
+            }
             _ => None,
         }
     }
@@ -42,6 +56,11 @@ impl fmt::Display for SourceType {
             SourceType::Jira => write!(f, "jira"),
             SourceType::Confluence => write!(f, "confluence"),
             SourceType::Figma => write!(f, "figma"),
+            SourceType::GitHub => write!(f, "github"),
+            SourceType::Slack => write!(f, "slack"),
+            SourceType::GoogleDrive => write!(f, "gdrive"),
+            SourceType::Mail => write!(f, "mail"),
+            SourceType::Web => write!(f, "web"),
             SourceType::Other(s) => write!(f, "{}", s),
         }
     }
@@ -56,6 +75,11 @@ impl std::str::FromStr for SourceType {
             "jira" => SourceType::Jira,
             "confluence" => SourceType::Confluence,
             "figma" => SourceType::Figma,
+            "github" => SourceType::GitHub,
+            "slack" => SourceType::Slack,
+            "gdrive" | "google-drive" => SourceType::GoogleDrive,
+            "mail" | "email" => SourceType::Mail,
+            "web" => SourceType::Web,
             other => SourceType::Other(other.to_string()),
         })
     }
@@ -129,6 +153,11 @@ mod tests {
         assert_eq!(SourceType::Jira.to_string(), "jira");
         assert_eq!(SourceType::Confluence.to_string(), "confluence");
         assert_eq!(SourceType::Figma.to_string(), "figma");
+        assert_eq!(SourceType::GitHub.to_string(), "github");
+        assert_eq!(SourceType::Slack.to_string(), "slack");
+        assert_eq!(SourceType::GoogleDrive.to_string(), "gdrive");
+        assert_eq!(SourceType::Mail.to_string(), "mail");
+        assert_eq!(SourceType::Web.to_string(), "web");
         assert_eq!(
             SourceType::Other("notion".to_string()).to_string(),
             "notion"
@@ -147,9 +176,10 @@ mod tests {
             "notion".parse::<SourceType>().unwrap(),
             SourceType::Other("notion".to_string())
         );
+        assert_eq!("GitHub".parse::<SourceType>().unwrap(), SourceType::GitHub);
         assert_eq!(
-            "GitHub".parse::<SourceType>().unwrap(),
-            SourceType::Other("github".to_string())
+            "Notion".parse::<SourceType>().unwrap(),
+            SourceType::Other("notion".to_string())
         );
     }
 
@@ -163,7 +193,13 @@ mod tests {
         assert_eq!(parsed, jira);
 
         // Other types
-        let slack = SourceType::Other("slack".to_string());
+        let notion = SourceType::Other("notion".to_string());
+        let json = serde_json::to_string(&notion).unwrap();
+        assert_eq!(json, "\"notion\"");
+        let parsed: SourceType = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, notion);
+
+        let slack = SourceType::Slack;
         let json = serde_json::to_string(&slack).unwrap();
         assert_eq!(json, "\"slack\"");
         let parsed: SourceType = serde_json::from_str(&json).unwrap();
@@ -175,6 +211,9 @@ mod tests {
         assert_eq!(SourceType::Jira.cli_command(), Some("atlassian"));
         assert_eq!(SourceType::Confluence.cli_command(), Some("atlassian"));
         assert_eq!(SourceType::Figma.cli_command(), Some("figma"));
+        assert_eq!(SourceType::GitHub.cli_command(), Some("gh"));
+        assert_eq!(SourceType::Slack.cli_command(), Some("curl"));
+        assert_eq!(SourceType::GoogleDrive.cli_command(), Some("curl"));
         assert_eq!(SourceType::Local.cli_command(), None);
         assert_eq!(SourceType::Other("notion".to_string()).cli_command(), None);
     }
@@ -5,6 +5,7 @@ use std::fmt;
 use std::str::FromStr;
 
 use crate::error::TagError;
+use crate::utils::text::slug;
 
 /// Key-value pair for document classification and filtering.
 ///
@@ -109,6 +110,69 @@ impl FromStr for Tag {
     }
 }
 
+/// A tag with `{name}` placeholders, e.g. `team:{jira-project}`, filled in
+/// per document by [`TagTemplate::render`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagTemplate(String);
+
+impl TagTemplate {
+    /// Placeholder names in order of appearance.
+    pub fn placeholders(&self) -> Vec<&str> {
+        self.0
+            .split('{')
+            .skip(1)
+            .filter_map(|part| part.split_once('}').map(|(name, _)| name))
+            .collect()
+    }
+
+    /// Fill in placeholders from `lookup`; substituted values are lowercased
+    /// and reduced to valid tag characters.
+    ///
+    /// Returns `None` when a placeholder has no value.
+    pub fn render(&self, lookup: impl Fn(&str) -> Option<String>) -> Option<Tag> {
+        let mut rendered = self.0.clone();
+        for name in self.placeholders() {
+            let value = slug(&lookup(name)?);
+            if value.is_empty() {
+                return None;
+            }
+            rendered = rendered.replace(&format!("{{{}}}", name), &value);
+        }
+        rendered.parse().ok()
+    }
+}
+
+impl FromStr for TagTemplate {
+    type Err = TagError;
+
+    /// Parse a template, checking that it forms a valid tag once filled in.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let template = Self(s.trim().to_string());
+        let opens = template.0.matches('{').count();
+        let closes = template.0.matches('}').count();
+        let names = template.placeholders();
+        if opens != closes || names.len() != opens || names.iter().any(|n| n.is_empty()) {
+            return Err(TagError::ParseError(format!(
+                "unbalanced placeholder in tag template '{}'",
+                s
+            )));
+        }
+        template.render(|_| Some("x".to_string())).ok_or_else(|| {
+            TagError::ParseError(format!(
+                "tag template '{}' does not form a 'key:value' tag",
+                s
+            ))
+        })?;
+        Ok(template)
+    }
+}
+
+impl fmt::Display for TagTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
 /// Parse multiple tags from a comma-separated string.
 ///
 /// # Example
@@ -176,4 +240,21 @@ mod tests {
         let tag = Tag::new("version", "1.0.0").unwrap();
         assert_eq!(tag.value, "1.0.0");
     }
+
+    #[test]
+    fn test_tag_template() {
+        let template: TagTemplate = "team:{jira-project}".parse().unwrap();
+        assert_eq!(template.placeholders(), vec!["jira-project"]);
+        let tag = template
+            .render(|name| (name == "jira-project").then(|| "Core Platform".to_string()))
+            .unwrap();
+        assert_eq!(tag.to_string(), "team:core-platform");
+        assert!(template.render(|_| None).is_none());
+
+        let fixed: TagTemplate = "kind:ticket".parse().unwrap();
+        assert_eq!(fixed.render(|_| None).unwrap().to_string(), "kind:ticket");
+
+        assert!("team:{project".parse::<TagTemplate>().is_err());
+        assert!("team-{project}".parse::<TagTemplate>().is_err());
+    }
 }
@@ -0,0 +1,233 @@
+//! Socket activation and the service definitions that set it up.
+//!
+//! `ssearch serve install-service` writes a systemd user socket and service
+//! (or a launchd agent on macOS) that own the daemon socket. The service
+//! manager starts `ssearch serve --daemon` on the first connection and hands
+//! it the listening socket, which [`activated_listener`] picks up.
+
+use std::path::{Path, PathBuf};
+
+use tokio::net::UnixListener;
+
+/// Name of the systemd units and the launchd job label suffix.
+pub const SERVICE_NAME: &str = "ssearch";
+
+/// launchd job label, also the plist's file stem.
+pub const LAUNCHD_LABEL: &str = "com.github.junyeong-ai.ssearch";
+
+/// Name of the launchd socket entry passed to `launch_activate_socket`.
+const LAUNCHD_SOCKET_NAME: &str = "Listeners";
+
+/// The listening socket handed over by systemd or launchd, if the daemon
+/// was socket-activated.
+pub fn activated_listener() -> std::io::Result<Option<UnixListener>> {
+    let Some(listener) = platform_listener()? else {
+        return Ok(None);
+    };
+    listener.set_nonblocking(true)?;
+    UnixListener::from_std(listener).map(Some)
+}
+
+/// systemd passes sockets from fd 3 on, announced by `LISTEN_PID`/`LISTEN_FDS`.
+#[cfg(not(target_os = "macos"))]
+fn platform_listener() -> std::io::Result<Option<std::os::unix::net::UnixListener>> {
+    use std::os::fd::FromRawFd;
+
+    const SD_LISTEN_FDS_START: i32 = 3;
+
+    let for_us = std::env::var("LISTEN_PID")
+        .ok()
+        .and_then(|pid| pid.parse::<u32>().ok())
+        .is_some_and(|pid| pid == std::process::id());
+    let fds = std::env::var("LISTEN_FDS")
+        .ok()
+        .and_then(|n| n.parse::<i32>().ok())
+        .unwrap_or(0);
+    if !for_us || fds < 1 {
+        return Ok(None);
+    }
+
+    // SAFETY: systemd guarantees fd 3 is an open listening socket owned by
+    // this process when LISTEN_PID matches; nothing else takes ownership of it.
+    let listener = unsafe { std::os::unix::net::UnixListener::from_raw_fd(SD_LISTEN_FDS_START) };
+    Ok(Some(listener))
+}
+
+#[cfg(target_os = "macos")]
+fn platform_listener() -> std::io::Result<Option<std::os::unix::net::UnixListener>> {
+    use std::ffi::CString;
+    use std::os::fd::FromRawFd;
+
+    use nix::libc::{c_char, c_int, c_void, free, size_t};
+
+    unsafe extern "C" {
+        fn launch_activate_socket(
+            name: *const c_char,
+            fds: *mut *mut c_int,
+            cnt: *mut size_t,
+        ) -> c_int;
+    }
+
+    let name = CString::new(LAUNCHD_SOCKET_NAME).expect("socket name has no NUL");
+    let mut fds: *mut c_int = std::ptr::null_mut();
+    let mut count: size_t = 0;
+    // SAFETY: launchd fills `fds` with a malloc'd array of `count` descriptors
+    let status = unsafe { launch_activate_socket(name.as_ptr(), &mut fds, &mut count) };
+    // Not started by launchd, or the job has no such socket
+    if status != 0 || fds.is_null() {
+        return Ok(None);
+    }
+
+    // SAFETY: `fds` holds `count` descriptors; the array is ours to free
+    let descriptors = unsafe { std::slice::from_raw_parts(fds, count) }.to_vec();
+    unsafe { free(fds as *mut c_void) };
+
+    Ok(descriptors.first().map(|&fd| {
+        // SAFETY: launchd hands over ownership of its listening sockets
+        unsafe { std::os::unix::net::UnixListener::from_raw_fd(fd) }
+    }))
+}
+
+/// A service definition file and where it is installed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceFile {
+    pub path: PathBuf,
+    pub contents: String,
+}
+
+/// systemd user units: a socket that owns the daemon socket and the service
+/// it activates.
+pub fn systemd_units(unit_dir: &Path, exe: &Path, socket: &Path) -> Vec<ServiceFile> {
+    let socket_unit = format!(
+        "[Unit]\n\
+         Description=semantic-search-cli embedding daemon socket\n\
+         \n\
+         [Socket]\n\
+         ListenStream={socket}\n\
+         SocketMode=0600\n\
+         RemoveOnStop=true\n\
+         \n\
+         [Install]\n\
+         WantedBy=sockets.target\n",
+        socket = socket.display()
+    );
+    let service_unit = format!(
+        "[Unit]\n\
+         Description=semantic-search-cli embedding daemon\n\
+         Requires={name}.socket\n\
+         After={name}.socket\n\
+         \n\
+         [Service]\n\
+         Type=simple\n\
+         ExecStart={exe} serve --daemon\n\
+         Restart=on-failure\n",
+        name = SERVICE_NAME,
+        exe = exe.display()
+    );
+    vec![
+        ServiceFile {
+            path: unit_dir.join(format!("{}.socket", SERVICE_NAME)),
+            contents: socket_unit,
+        },
+        ServiceFile {
+            path: unit_dir.join(format!("{}.service", SERVICE_NAME)),
+            contents: service_unit,
+        },
+    ]
+}
+
+/// launchd agent that listens on the daemon socket and starts the daemon on demand.
+pub fn launchd_plist(agent_dir: &Path, exe: &Path, socket: &Path, log: &Path) -> ServiceFile {
+    let contents = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{label}</string>
+    <key>ProgramArguments</key>
+    <array>
+        <string>{exe}</string>
+        <string>serve</string>
+        <string>--daemon</string>
+    </array>
+    <key>Sockets</key>
+    <dict>
+        <key>{socket_name}</key>
+        <dict>
+            <key>SockPathName</key>
+            <string>{socket}</string>
+            <key>SockPathMode</key>
+            <integer>384</integer>
+        </dict>
+    </dict>
+    <key>StandardErrorPath</key>
+    <string>{log}</string>
+</dict>
+</plist>
+"#,
+        label = LAUNCHD_LABEL,
+        exe = xml_escape(&exe.display().to_string()),
+        socket_name = LAUNCHD_SOCKET_NAME,
+        socket = xml_escape(&socket.display().to_string()),
+        log = xml_escape(&log.display().to_string()),
+    );
+    ServiceFile {
+        path: agent_dir.join(format!("{}.plist", LAUNCHD_LABEL)),
+        contents,
+    }
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_service_definitions() {
+        let exe = Path::new("/usr/local/bin/ssearch");
+        let socket = Path::new("/tmp/ssearch.sock");
+
+        let units = systemd_units(Path::new("/home/me/.config/systemd/user"), exe, socket);
+        assert_eq!(
+            units[0].path,
+            Path::new("/home/me/.config/systemd/user/ssearch.socket")
+        );
+        assert!(
+            units[0]
+                .contents
+                .contains("ListenStream=/tmp/ssearch.sock\n")
+        );
+        assert!(
+            units[1]
+                .contents
+                .contains("ExecStart=/usr/local/bin/ssearch serve --daemon\n")
+        );
+        assert!(units[1].contents.contains("Requires=ssearch.socket\n"));
+
+        let plist = launchd_plist(
+            Path::new("/Users/me/Library/LaunchAgents"),
+            exe,
+            socket,
+            Path::new("/tmp/a&b.log"),
+        );
+        assert!(plist.path.ends_with("com.github.junyeong-ai.ssearch.plist"));
+        assert!(
+            plist.contents.contains(
+                "<key>SockPathName</key>\n            <string>/tmp/ssearch.sock</string>"
+            )
+        );
+        assert!(plist.contents.contains("<string>/tmp/a&amp;b.log</string>"));
+    }
+
+    #[test]
+    fn test_not_activated_without_listen_fds() {
+        // Test processes are never started by a service manager
+        assert!(activated_listener().unwrap().is_none());
+    }
+}
@@ -0,0 +1,238 @@
+//! Editor integration server over stdio (`ssearch serve --stdio`).
+//!
+//! A plugin (e.g. Neovim's `vim.system`) spawns it as a child process and
+//! exchanges newline-delimited JSON-RPC 2.0 with it, so the plugin never
+//! manages the daemon socket. Methods:
+//!
+//! - `search`: the `POST /search` body; returns the search results
+//! - `indexBuffer`: `{"path", "content", "tags"?}`; indexes unsaved buffer
+//!   content as the file at `path`, replacing what `index add` stored for it
+//! - `status`: daemon and vector store status
+//!
+//! Failures are JSON-RPC errors with code [`REQUEST_FAILED`].
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{Instrument, info_span, warn};
+
+use crate::models::{
+    Config, Document, DocumentMetadata, RELATIVE_PATH_ATTRIBUTE, Source, SourceType, Tag,
+    parse_tags,
+};
+use crate::server::stdio::{RpcError, RpcHandler, StdioContext, failed, params, serve_stdio};
+use crate::utils::file::calculate_checksum;
+
+/// Error code of a request that was understood but failed (JSON-RPC server error range)
+pub const REQUEST_FAILED: i32 = -32000;
+
+/// Params of `indexBuffer`.
+#[derive(Debug, Deserialize)]
+struct BufferRequest {
+    /// File the buffer belongs to; relative paths resolve against the server's directory
+    path: PathBuf,
+    content: String,
+    /// Tags added to the document (format: key:value)
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+/// Result of `indexBuffer`.
+#[derive(Debug, Serialize)]
+struct BufferIndexed {
+    document_id: String,
+    location: String,
+    chunks_created: u64,
+    chunks_stored: u64,
+    duration_ms: u64,
+}
+
+pub struct EditorServer {
+    context: StdioContext,
+}
+
+#[async_trait]
+impl RpcHandler for EditorServer {
+    async fn call(&self, method: &str, params: Value) -> Result<Value, RpcError> {
+        let span = info_span!("request", transport = "stdio", method = %method);
+        let result = async {
+            match method {
+                "search" => {
+                    let results = self.context.search(self::params(params)?).await;
+                    serde_json::to_value(results.map_err(failed(REQUEST_FAILED))?)
+                        .map_err(failed(REQUEST_FAILED))
+                }
+                "indexBuffer" => {
+                    let indexed = self.index_buffer(self::params(params)?).await;
+                    serde_json::to_value(indexed.map_err(failed(REQUEST_FAILED))?)
+                        .map_err(failed(REQUEST_FAILED))
+                }
+                "status" => Ok(self.context.status().await),
+                other => Err(RpcError::method_not_found(other)),
+            }
+        }
+        .instrument(span.clone())
+        .await;
+        if let Err(ref error) = result {
+            span.in_scope(|| warn!(error = %error.message, "request failed"));
+        }
+        result
+    }
+}
+
+impl EditorServer {
+    pub fn new(config: Config) -> Self {
+        Self {
+            context: StdioContext::new(config),
+        }
+    }
+
+    /// Serve requests from stdin until it is closed.
+    pub async fn run(&self) -> std::io::Result<()> {
+        serve_stdio(self, "Editor").await
+    }
+
+    async fn index_buffer(&self, req: BufferRequest) -> Result<BufferIndexed, String> {
+        let start = Instant::now();
+        let config = &self.context.config;
+        if req.content.trim().is_empty() {
+            return Err("buffer is empty".to_string());
+        }
+        let tags = parse_tags(&req.tags.join(",")).map_err(|e| format!("invalid tags: {}", e))?;
+        let templates = config
+            .tag_templates(&SourceType::Local)
+            .map_err(|e| format!("invalid [tags] template: {}", e))?;
+
+        let mut document = buffer_document(&req.path, req.content, tags, Config::project_root())?;
+        document.add_template_tags(&templates, &BTreeMap::new());
+        let document_id = document.id.clone();
+        let location = document.source.location.clone();
+
+        let summary = self
+            .context
+            .index_service()
+            .await?
+            .index_documents(vec![document])
+            .await
+            .map_err(|e| format!("{:#}", e))?;
+        Ok(BufferIndexed {
+            document_id,
+            location,
+            chunks_created: summary.chunks_created,
+            chunks_stored: summary.chunks_stored,
+            duration_ms: start.elapsed().as_millis() as u64,
+        })
+    }
+}
+
+/// The document `index add` would make of the file at `path`, holding the
+/// buffer's `content` instead of what is on disk.
+fn buffer_document(
+    path: &Path,
+    content: String,
+    tags: Vec<Tag>,
+    project_root: Option<PathBuf>,
+) -> Result<Document, String> {
+    // `index add` stores canonical paths; a new buffer has none yet
+    let path = path
+        .canonicalize()
+        .or_else(|_| std::path::absolute(path))
+        .map_err(|e| format!("invalid path {}: {}", path.display(), e))?;
+    let metadata = DocumentMetadata::for_file(&path, &content);
+    let checksum = calculate_checksum(&content);
+    let source = Source::local(path.to_string_lossy().to_string());
+    let mut document = Document::new(content, source, tags, checksum, metadata);
+    if let Some(relative) = project_root
+        .as_deref()
+        .and_then(|root| path.strip_prefix(root).ok())
+    {
+        document.attributes.insert(
+            RELATIVE_PATH_ATTRIBUTE.to_string(),
+            relative.to_string_lossy().into_owned(),
+        );
+    }
+    Ok(document)
+}
+
+/// Run the editor server on stdio until stdin is closed.
+pub async fn run_editor(config: Config) -> std::io::Result<()> {
+    EditorServer::new(config).run().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::stdio::{INVALID_PARAMS, METHOD_NOT_FOUND, handle_message};
+
+    fn server() -> EditorServer {
+        EditorServer::new(Config::default())
+    }
+
+    #[tokio::test]
+    async fn test_errors() {
+        let server = server();
+
+        let response = handle_message(&server, r#"{"jsonrpc":"2.0","id":1,"method":"hover"}"#)
+            .await
+            .unwrap();
+        assert_eq!(response.error.unwrap().code, METHOD_NOT_FOUND);
+
+        let response = handle_message(
+            &server,
+            r#"{"jsonrpc":"2.0","id":2,"method":"indexBuffer","params":{"path":"a.rs"}}"#,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.error.unwrap().code, INVALID_PARAMS);
+
+        let response = handle_message(
+            &server,
+            r#"{"jsonrpc":"2.0","id":3,"method":"search","params":{"query":" "}}"#,
+        )
+        .await
+        .unwrap();
+        let error = response.error.unwrap();
+        assert_eq!(error.code, REQUEST_FAILED);
+        assert_eq!(error.message, "query cannot be empty");
+
+        let response = handle_message(
+            &server,
+            r#"{"jsonrpc":"2.0","id":4,"method":"indexBuffer","params":{"path":"a.rs","content":"fn a() {}","tags":["bad"]}}"#,
+        )
+        .await
+        .unwrap();
+        assert!(response.error.unwrap().message.starts_with("invalid tags"));
+    }
+
+    #[test]
+    fn test_buffer_document() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        std::fs::create_dir(root.join("src")).unwrap();
+        std::fs::write(root.join("src/lib.rs"), "// on disk").unwrap();
+
+        let file = root.join("src/lib.rs");
+        let document = buffer_document(
+            &file,
+            "pub fn unsaved() {}".to_string(),
+            Vec::new(),
+            Some(root.clone()),
+        )
+        .unwrap();
+        // Same ID as `index add` gives the file, so indexing replaces it
+        let source = Source::local(file.to_string_lossy().to_string());
+        assert_eq!(document.id, Document::generate_id(&source));
+        assert_eq!(document.content, "pub fn unsaved() {}");
+        assert_eq!(document.metadata.language.as_deref(), Some("rust"));
+        assert_eq!(document.attributes[RELATIVE_PATH_ATTRIBUTE], "src/lib.rs");
+
+        // A buffer not yet written to disk
+        let new_file = root.join("src/new.rs");
+        let document = buffer_document(&new_file, "x".to_string(), Vec::new(), None).unwrap();
+        assert_eq!(document.source.location, new_file.to_string_lossy());
+    }
+}
@@ -1,21 +1,38 @@
-use std::path::Path;
-use std::sync::{Arc, Mutex};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, RwLock, RwLockReadGuard};
 
-use ort::session::{Session, builder::GraphOptimizationLevel};
+use ort::ep::{self, ExecutionProvider};
+use ort::session::Session;
+use ort::session::builder::{GraphOptimizationLevel, SessionBuilder};
 use ort::value::Tensor;
 use tokenizers::Tokenizer;
 use tokenizers::{PaddingParams, PaddingStrategy, TruncationParams, TruncationStrategy};
+use tracing::{info, warn};
 
 use crate::error::ModelError;
-use crate::models::EmbeddingConfig;
+use crate::models::{Device, EmbeddingConfig};
+use crate::server::model_dir_name;
+use crate::server::protocol::ModelSpec;
 
-const QUERY_INSTRUCTION: &str =
-    "Instruct: Given a search query, retrieve relevant passages\nQuery: ";
+/// Batch run through each session by [`EmbeddingModel::warm_up`].
+const WARM_UP_TEXTS: [&str; 2] = [
+    "warm up",
+    "Initialize the inference graph before the first request arrives.",
+];
 
 pub struct EmbeddingModel {
-    session: Mutex<Session>,
+    /// Independent sessions so concurrent requests don't serialize on one lock
+    sessions: Vec<Mutex<Session>>,
+    next_session: AtomicUsize,
     tokenizer: Tokenizer,
     dimension: usize,
+    /// Device actually in use after any fallback
+    device: Device,
+    /// Prefixes used when a request does not carry its own instruction
+    query_instruction: String,
+    document_instruction: String,
 }
 
 impl EmbeddingModel {
@@ -31,14 +48,24 @@ impl EmbeddingModel {
             )));
         }
 
-        let session = Session::builder()
-            .map_err(|e: ort::Error| ModelError::LoadError(e.to_string()))?
-            .with_optimization_level(GraphOptimizationLevel::Level3)
-            .map_err(|e: ort::Error| ModelError::LoadError(e.to_string()))?
-            .with_intra_threads(num_cpus())
-            .map_err(|e: ort::Error| ModelError::LoadError(e.to_string()))?
-            .commit_from_file(&model_path)
-            .map_err(|e: ort::Error| ModelError::LoadError(e.to_string()))?;
+        // Split the cores between sessions unless threads are set explicitly
+        let pool_size = config.sessions.max(1) as usize;
+        let intra_threads = config
+            .threads
+            .map_or(num_cpus() / pool_size, |n| n as usize)
+            .max(1);
+
+        let (sessions, device) =
+            match load_sessions(&model_path, pool_size, intra_threads, config.device) {
+                Ok(sessions) => (sessions, config.device),
+                Err(e) if config.device != Device::Cpu => {
+                    warn!(error = %e, device = %config.device, "falling back to CPU");
+                    let sessions =
+                        load_sessions(&model_path, pool_size, intra_threads, Device::Cpu)?;
+                    (sessions, Device::Cpu)
+                }
+                Err(e) => return Err(e),
+            };
 
         let mut tokenizer = Tokenizer::from_file(&tokenizer_path)
             .map_err(|e| ModelError::TokenizerError(e.to_string()))?;
@@ -59,24 +86,40 @@ impl EmbeddingModel {
         }));
 
         Ok(Self {
-            session: Mutex::new(session),
+            sessions,
+            next_session: AtomicUsize::new(0),
             tokenizer,
             dimension: config.dimension as usize,
+            device,
+            query_instruction: config.resolved_query_instruction().to_string(),
+            document_instruction: config.resolved_document_instruction().to_string(),
         })
     }
 
-    pub fn embed(&self, texts: &[String], is_query: bool) -> Result<Vec<Vec<f32>>, ModelError> {
+    /// Embed `texts`, prefixed with `instruction` or else the model's own
+    /// query or document instruction.
+    pub fn embed(
+        &self,
+        texts: &[String],
+        is_query: bool,
+        instruction: Option<&str>,
+    ) -> Result<Vec<Vec<f32>>, ModelError> {
         if texts.is_empty() {
             return Ok(Vec::new());
         }
 
-        let processed: Vec<String> = if is_query {
+        let instruction = instruction.unwrap_or(if is_query {
+            &self.query_instruction
+        } else {
+            &self.document_instruction
+        });
+        let processed: Vec<String> = if instruction.is_empty() {
+            texts.to_vec()
+        } else {
             texts
                 .iter()
-                .map(|t| format!("{}{}", QUERY_INSTRUCTION, t))
+                .map(|t| format!("{}{}", instruction, t))
                 .collect()
-        } else {
-            texts.to_vec()
         };
 
         let encodings = self
@@ -112,10 +155,7 @@ impl EmbeddingModel {
         let position_ids_tensor = Tensor::from_array(([batch_size, max_len], position_ids))
             .map_err(|e: ort::Error| ModelError::InferenceError(e.to_string()))?;
 
-        let mut session = self
-            .session
-            .lock()
-            .map_err(|_| ModelError::InferenceError("session lock poisoned".to_string()))?;
+        let mut session = self.acquire_session()?;
 
         let outputs = session
             .run(ort::inputs![
@@ -130,6 +170,13 @@ impl EmbeddingModel {
             .map_err(|e: ort::Error| ModelError::InferenceError(e.to_string()))?;
 
         let shape = output_array.shape();
+        let output_dim = shape.last().copied().unwrap_or(0);
+        if output_dim < self.dimension {
+            return Err(ModelError::InferenceError(format!(
+                "model outputs {} dimensions, fewer than the configured {}",
+                output_dim, self.dimension
+            )));
+        }
 
         let embeddings: Vec<Vec<f32>> = if shape.len() == 3 {
             (0..batch_size)
@@ -163,6 +210,97 @@ impl EmbeddingModel {
     pub fn dimension(&self) -> usize {
         self.dimension
     }
+
+    pub fn device(&self) -> Device {
+        self.device
+    }
+
+    /// Embed a small batch on every session, so the first real request
+    /// doesn't pay for the runtime's lazy graph initialization.
+    pub fn warm_up(&self) -> Result<(), ModelError> {
+        let texts: Vec<String> = WARM_UP_TEXTS.iter().map(|t| t.to_string()).collect();
+        // Sessions are taken round-robin, so each gets one pass while the daemon is idle
+        for _ in 0..self.sessions.len() {
+            self.embed(&texts, false, None)?;
+        }
+        Ok(())
+    }
+
+    /// Number of sessions in the pool.
+    pub fn pool_size(&self) -> usize {
+        self.sessions.len()
+    }
+
+    /// Take an idle session if there is one, otherwise wait for the next in turn.
+    fn acquire_session(&self) -> Result<MutexGuard<'_, Session>, ModelError> {
+        let len = self.sessions.len();
+        let start = self.next_session.fetch_add(1, Ordering::Relaxed) % len;
+
+        for offset in 0..len {
+            if let Ok(session) = self.sessions[(start + offset) % len].try_lock() {
+                return Ok(session);
+            }
+        }
+
+        self.sessions[start]
+            .lock()
+            .map_err(|_| ModelError::InferenceError("session lock poisoned".to_string()))
+    }
+}
+
+fn load_sessions(
+    model_path: &Path,
+    pool_size: usize,
+    intra_threads: usize,
+    device: Device,
+) -> Result<Vec<Mutex<Session>>, ModelError> {
+    (0..pool_size)
+        .map(|_| {
+            let builder = Session::builder()
+                .map_err(|e: ort::Error| ModelError::LoadError(e.to_string()))?
+                .with_optimization_level(GraphOptimizationLevel::Level3)
+                .map_err(|e: ort::Error| ModelError::LoadError(e.to_string()))?
+                .with_intra_threads(intra_threads)
+                .map_err(|e: ort::Error| ModelError::LoadError(e.to_string()))?;
+
+            with_device(builder, device)?
+                .commit_from_file(model_path)
+                .map(Mutex::new)
+                .map_err(|e: ort::Error| ModelError::LoadError(e.to_string()))
+        })
+        .collect()
+}
+
+/// Register the execution provider for `device`, failing instead of silently using CPU.
+fn with_device(builder: SessionBuilder, device: Device) -> Result<SessionBuilder, ModelError> {
+    let (available, provider) = match device {
+        Device::Cpu => return Ok(builder),
+        Device::Cuda => {
+            let ep = ep::CUDA::default();
+            (ep.is_available(), ep.build())
+        }
+        Device::CoreMl => {
+            let ep = ep::CoreML::default();
+            (ep.is_available(), ep.build())
+        }
+        Device::DirectMl => {
+            let ep = ep::DirectML::default();
+            (ep.is_available(), ep.build())
+        }
+    };
+
+    if !available.unwrap_or(false) {
+        return Err(ModelError::LoadError(format!(
+            "{} execution provider is not available in the loaded ONNX Runtime",
+            device
+        )));
+    }
+
+    builder
+        .with_execution_providers([provider.error_on_failure()])
+        .map_err(|e: ort::Error| {
+            ModelError::LoadError(format!("failed to register {} provider: {}", device, e))
+        })
 }
 
 fn normalize(v: &[f32]) -> Vec<f32> {
@@ -181,3 +319,120 @@ fn num_cpus() -> usize {
 }
 
 pub type SharedEmbeddingModel = Arc<EmbeddingModel>;
+
+/// Embedding models held by the daemon: the configured one, plus any other
+/// model requested by a collection, loaded on first use.
+pub struct ModelRegistry {
+    config: EmbeddingConfig,
+    models_dir: PathBuf,
+    /// Spec and model answering requests that name no model; swapped by `reload`
+    default: RwLock<(ModelSpec, SharedEmbeddingModel)>,
+    loaded: Mutex<HashMap<ModelSpec, SharedEmbeddingModel>>,
+}
+
+impl ModelRegistry {
+    pub fn load(config: &EmbeddingConfig, models_dir: PathBuf) -> Result<Self, ModelError> {
+        let model_dir = config
+            .model_path
+            .clone()
+            .unwrap_or_else(|| models_dir.join(model_dir_name(&config.model_id)));
+        let default = Arc::new(EmbeddingModel::load(config, &model_dir)?);
+
+        Ok(Self {
+            config: config.clone(),
+            models_dir,
+            default: RwLock::new((ModelSpec::from_config(config), default)),
+            loaded: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// The model loaded at startup from `[embedding]`, or the last one reloaded.
+    pub fn default_model(&self) -> SharedEmbeddingModel {
+        Arc::clone(&self.read_default().1)
+    }
+
+    pub fn default_spec(&self) -> ModelSpec {
+        self.read_default().0.clone()
+    }
+
+    /// Models loaded on demand besides the default, sorted by ID.
+    pub fn loaded_specs(&self) -> Vec<ModelSpec> {
+        let mut specs: Vec<ModelSpec> = self
+            .loaded
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .keys()
+            .cloned()
+            .collect();
+        specs.sort_by(|a, b| (&a.model_id, a.dimension).cmp(&(&b.model_id, b.dimension)));
+        specs
+    }
+
+    /// Resolve a model, loading it if needed. This may block for a while.
+    pub fn get(&self, spec: Option<&ModelSpec>) -> Result<SharedEmbeddingModel, ModelError> {
+        let spec = {
+            let default = self.read_default();
+            match spec.filter(|s| **s != default.0) {
+                Some(spec) => spec,
+                None => return Ok(Arc::clone(&default.1)),
+            }
+        };
+
+        let mut loaded = self.lock_loaded()?;
+        if let Some(model) = loaded.get(spec) {
+            return Ok(Arc::clone(model));
+        }
+
+        let model = self.load_spec(spec)?;
+        loaded.insert(spec.clone(), Arc::clone(&model));
+        Ok(model)
+    }
+
+    /// Load `spec`, check it can produce embeddings of its dimension and make
+    /// it the default model. The previous default is dropped once in-flight
+    /// requests release it. This may block for a while.
+    pub fn reload(&self, spec: ModelSpec) -> Result<SharedEmbeddingModel, ModelError> {
+        let cached = self.lock_loaded()?.remove(&spec);
+        let model = match cached {
+            Some(model) => model,
+            None => self.load_spec(&spec)?,
+        };
+        // Fails when the model's output is narrower than the requested dimension
+        model.embed(&["dimension probe".to_string()], false, None)?;
+
+        *self.default.write().unwrap_or_else(|e| e.into_inner()) = (spec, Arc::clone(&model));
+        Ok(model)
+    }
+
+    fn load_spec(&self, spec: &ModelSpec) -> Result<SharedEmbeddingModel, ModelError> {
+        info!(model = %spec.model_id, "loading embedding model");
+        let mut config = EmbeddingConfig {
+            model_id: spec.model_id.clone(),
+            model_path: spec.model_path.clone(),
+            dimension: spec.dimension,
+            ..self.config.clone()
+        };
+        // Explicit instructions belong to the configured model, not this one
+        if spec.model_id != self.config.model_id {
+            config.query_instruction = None;
+            config.document_instruction = None;
+        }
+        let model_dir = spec
+            .model_path
+            .clone()
+            .unwrap_or_else(|| self.models_dir.join(model_dir_name(&spec.model_id)));
+        Ok(Arc::new(EmbeddingModel::load(&config, &model_dir)?))
+    }
+
+    fn read_default(&self) -> RwLockReadGuard<'_, (ModelSpec, SharedEmbeddingModel)> {
+        self.default.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn lock_loaded(
+        &self,
+    ) -> Result<MutexGuard<'_, HashMap<ModelSpec, SharedEmbeddingModel>>, ModelError> {
+        self.loaded
+            .lock()
+            .map_err(|_| ModelError::LoadError("model registry lock poisoned".to_string()))
+    }
+}
@@ -0,0 +1,770 @@
+//! HTTP REST API for the daemon.
+//!
+//! Exposes the same operations as the Unix socket protocol (plus search and
+//! indexing) as JSON endpoints, sharing the daemon's loaded model and metrics.
+
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Instant;
+
+use async_trait::async_trait;
+use axum::extract::rejection::JsonRejection;
+use axum::extract::{ConnectInfo, State};
+use axum::http::{StatusCode, header};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response as HttpResponse};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::net::TcpListener;
+use tracing::{Instrument, debug, info_span, warn};
+
+use crate::error::{EmbeddingError, ModelError, SearchError};
+use crate::models::{
+    Document, DocumentMetadata, FilterExpr, SearchConfig, SearchFilter, SearchResults, Source,
+    SourceType, Tag, WORKSPACE_ATTRIBUTE, parse_tags,
+};
+use crate::server::DaemonServer;
+use crate::server::protocol::{
+    BusyResponse, EmbedRequest, ErrorCode, ErrorResponse, Priority, Request, Response,
+    constant_time_eq,
+};
+use crate::services::{
+    EmbeddingProvider, MAX_SEARCH_LIMIT, SearchOptions, SearchRecord, SearchService,
+};
+use crate::utils::file::detect_language;
+
+/// Search request body for `POST /search`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SearchRequest {
+    pub query: String,
+    #[serde(default)]
+    pub limit: Option<u32>,
+    /// Tag filters (format: key:value)
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub source_types: Vec<String>,
+    /// Filter expressions (format: key=value, path~glob, symbol~text, created_at>=date)
+    #[serde(default)]
+    pub filters: Vec<String>,
+    #[serde(default)]
+    pub min_score: Option<f32>,
+    /// Fuse keyword relevance with semantic similarity
+    #[serde(default)]
+    pub hybrid: bool,
+    /// Re-score candidates with the cross-encoder (defaults to config `search.rerank`)
+    #[serde(default)]
+    pub rerank: Option<bool>,
+    /// Recency boost weight (defaults to config `search.recency_weight`)
+    #[serde(default)]
+    pub recency: Option<f32>,
+    /// Drop results this similar to a higher-ranked one (defaults to config `search.dedup_threshold`)
+    #[serde(default)]
+    pub dedup_threshold: Option<f32>,
+    /// Only search chunks indexed in this workspace
+    #[serde(default)]
+    pub workspace: Option<String>,
+    /// Search every workspace, ignoring `workspace` and the configured default
+    #[serde(default)]
+    pub all_workspaces: bool,
+}
+
+/// Index request body for `POST /index`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct IndexRequest {
+    pub documents: Vec<IndexDocument>,
+    /// Tags applied to every document (format: key:value)
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Workspace stamped on every document's chunks
+    #[serde(default)]
+    pub workspace: Option<String>,
+}
+
+/// A document to index, in the same shape accepted by `ssearch import`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct IndexDocument {
+    pub content: String,
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub path: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub source_type: Option<String>,
+}
+
+impl SearchRequest {
+    /// A request for `query` with every other field at its default.
+    pub fn for_query(query: impl Into<String>) -> Self {
+        Self {
+            query: query.into(),
+            limit: None,
+            tags: Vec::new(),
+            source_types: Vec::new(),
+            filters: Vec::new(),
+            min_score: None,
+            hybrid: false,
+            rerank: None,
+            recency: None,
+            dedup_threshold: None,
+            workspace: None,
+            all_workspaces: false,
+        }
+    }
+
+    /// Parse the tag, source type, and `--filter` style expressions of the request.
+    pub fn filter(&self) -> Result<SearchFilter, SearchError> {
+        let tags = if self.tags.is_empty() {
+            Vec::new()
+        } else {
+            parse_tags(&self.tags.join(","))
+                .map_err(|e| SearchError::InvalidQuery(format!("invalid tags: {e}")))?
+        };
+        let source_types: Vec<SourceType> = self
+            .source_types
+            .iter()
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse().unwrap())
+            .collect();
+        let expressions: Vec<FilterExpr> = self
+            .filters
+            .iter()
+            .map(|f| f.parse())
+            .collect::<Result<_, _>>()?;
+
+        Ok(SearchFilter::new()
+            .with_tags(tags)
+            .with_source_types(source_types)
+            .with_expressions(expressions))
+    }
+
+    /// Options for this request, falling back to the `[search]` defaults
+    /// and searching `default_workspace` unless the request names one.
+    pub fn options(
+        &self,
+        config: &SearchConfig,
+        default_workspace: Option<&str>,
+    ) -> Result<SearchOptions, SearchError> {
+        let options = SearchOptions::from_config(config)
+            .with_limit(self.limit.unwrap_or(config.default_limit))
+            .with_min_score(self.min_score.or(config.default_min_score))
+            .with_hybrid(self.hybrid)
+            .with_rerank(self.rerank.unwrap_or(config.rerank))
+            .with_recency(self.recency.unwrap_or(config.recency_weight))
+            .with_dedup_threshold(self.dedup_threshold.or(config.dedup_threshold))
+            .with_filter(
+                self.filter()?
+                    .with_workspace(self.workspace(default_workspace)),
+            );
+        if options.limit > MAX_SEARCH_LIMIT {
+            return Err(SearchError::InvalidQuery(format!(
+                "limit must be between 1 and {MAX_SEARCH_LIMIT}"
+            )));
+        }
+        options.validate()?;
+        Ok(options)
+    }
+
+    /// Workspace to search: the request's, else `default`, unless `all_workspaces` is set.
+    pub fn workspace<'a>(&'a self, default: Option<&'a str>) -> Option<&'a str> {
+        if self.all_workspaces {
+            None
+        } else {
+            self.workspace.as_deref().or(default)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexResponse {
+    pub documents_indexed: u64,
+    pub documents_skipped: u64,
+    pub chunks_created: u64,
+    pub duration_ms: u64,
+}
+
+/// JSON error body, identical to the socket protocol's error response.
+struct ApiError {
+    status: StatusCode,
+    code: ErrorCode,
+    message: String,
+    /// Sent as `Retry-After` (whole seconds) with 429 responses
+    retry_after_secs: Option<u64>,
+}
+
+impl ApiError {
+    fn bad_request(message: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::BAD_REQUEST,
+            code: ErrorCode::InvalidRequest,
+            message: message.into(),
+            retry_after_secs: None,
+        }
+    }
+
+    fn internal(message: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+            code: ErrorCode::Internal,
+            message: message.into(),
+            retry_after_secs: None,
+        }
+    }
+}
+
+impl From<BusyResponse> for ApiError {
+    fn from(busy: BusyResponse) -> Self {
+        Self {
+            status: StatusCode::TOO_MANY_REQUESTS,
+            code: ErrorCode::Overloaded,
+            message: format!("rate limited; retry after {}ms", busy.retry_after_ms),
+            retry_after_secs: Some(busy.retry_after_ms.div_ceil(1000)),
+        }
+    }
+}
+
+impl From<ErrorResponse> for ApiError {
+    fn from(err: ErrorResponse) -> Self {
+        let status = match err.code {
+            ErrorCode::InvalidRequest
+            | ErrorCode::DimensionMismatch
+            | ErrorCode::VersionMismatch => StatusCode::BAD_REQUEST,
+            ErrorCode::Unauthorized => StatusCode::UNAUTHORIZED,
+            ErrorCode::MessageTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+            ErrorCode::ModelNotLoaded | ErrorCode::Overloaded => StatusCode::SERVICE_UNAVAILABLE,
+            ErrorCode::InferenceFailed | ErrorCode::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        Self {
+            status,
+            code: err.code,
+            message: err.message,
+            retry_after_secs: None,
+        }
+    }
+}
+
+impl From<ModelError> for ApiError {
+    fn from(err: ModelError) -> Self {
+        Self::from(ErrorResponse {
+            code: ErrorCode::from(&err),
+            message: err.to_string(),
+        })
+    }
+}
+
+impl From<SearchError> for ApiError {
+    fn from(err: SearchError) -> Self {
+        match err {
+            SearchError::EmbeddingError(EmbeddingError::ModelError(e)) => Self::from(e),
+            SearchError::InvalidQuery(message) => Self::bad_request(message),
+            err => Self::internal(err.to_string()),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> HttpResponse {
+        let body = ErrorResponse {
+            code: self.code,
+            message: self.message,
+        };
+        let mut response = (self.status, Json(body)).into_response();
+        if let Some(secs) = self.retry_after_secs {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, secs.into());
+        }
+        response
+    }
+}
+
+impl From<JsonRejection> for ApiError {
+    fn from(rejection: JsonRejection) -> Self {
+        Self {
+            status: rejection.status(),
+            code: ErrorCode::InvalidRequest,
+            message: format!("invalid request: {}", rejection.body_text()),
+            retry_after_secs: None,
+        }
+    }
+}
+
+type ApiResult<T> = Result<Json<T>, ApiError>;
+
+/// Build the router for the HTTP API.
+pub fn router(server: Arc<DaemonServer>) -> Router {
+    Router::new()
+        .route("/status", get(status))
+        .route("/embed", post(embed))
+        .route("/search", post(search))
+        .route("/index", post(index))
+        .layer(middleware::from_fn_with_state(
+            server.config.daemon.auth_token.as_deref().map(Arc::from),
+            require_token,
+        ))
+        .layer(middleware::from_fn_with_state(
+            Arc::clone(&server),
+            trace_request,
+        ))
+        .with_state(server)
+}
+
+/// Reject requests without `Authorization: Bearer <token>` when
+/// `[daemon] auth_token` is set, as the socket protocol does.
+async fn require_token(
+    State(expected): State<Option<Arc<str>>>,
+    request: axum::extract::Request,
+    next: Next,
+) -> HttpResponse {
+    if let Some(expected) = expected {
+        let token = request
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+        if !token.is_some_and(|token| constant_time_eq(token.as_bytes(), expected.as_bytes())) {
+            warn!("rejected unauthenticated request");
+            let mut response = ApiError {
+                status: StatusCode::UNAUTHORIZED,
+                code: ErrorCode::Unauthorized,
+                message: "unauthorized: missing or invalid auth token".to_string(),
+                retry_after_secs: None,
+            }
+            .into_response();
+            response.headers_mut().insert(
+                header::WWW_AUTHENTICATE,
+                header::HeaderValue::from_static("Bearer"),
+            );
+            return response;
+        }
+    }
+    next.run(request).await
+}
+
+/// Run each HTTP request in a span so its duration and status are logged,
+/// and count it by endpoint and outcome.
+async fn trace_request(
+    State(server): State<Arc<DaemonServer>>,
+    request: axum::extract::Request,
+    next: Next,
+) -> HttpResponse {
+    let kind = match request.uri().path() {
+        "/status" => "status",
+        "/embed" => "embed",
+        "/search" => "search",
+        "/index" => "index",
+        _ => "other",
+    };
+    let span = info_span!(
+        "request",
+        transport = "http",
+        method = %request.method(),
+        path = request.uri().path(),
+    );
+    async move {
+        let response = next.run(request).await;
+        let status = response.status();
+        if status.is_server_error() {
+            warn!(status = status.as_u16(), "request failed");
+        } else {
+            debug!(status = status.as_u16(), "request completed");
+        }
+        server.count_request("http", kind, status.is_success());
+        response
+    }
+    .instrument(span)
+    .await
+}
+
+/// Serve the HTTP API on an already-bound listener until the process exits.
+pub async fn serve(server: Arc<DaemonServer>, listener: TcpListener) -> std::io::Result<()> {
+    axum::serve(
+        listener,
+        router(server).into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+}
+
+/// Rate-limit key for an HTTP client.
+fn client_key(addr: SocketAddr) -> String {
+    format!("http:{}", addr.ip())
+}
+
+/// Charge `cost` texts to the client, failing with 429 when over its limit.
+fn admit(server: &DaemonServer, addr: SocketAddr, cost: usize) -> Result<(), ApiError> {
+    match server.admit(&client_key(addr), cost) {
+        Some(Response::Busy(busy)) => Err(busy.into()),
+        _ => Ok(()),
+    }
+}
+
+async fn status(
+    State(server): State<Arc<DaemonServer>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+) -> HttpResponse {
+    server.touch().await;
+    let response = server
+        .handle_request(&client_key(addr), Request::Status)
+        .await;
+    into_http(response)
+}
+
+async fn embed(
+    State(server): State<Arc<DaemonServer>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    payload: Result<Json<EmbedRequest>, JsonRejection>,
+) -> HttpResponse {
+    server.touch().await;
+    let Json(req) = match payload {
+        Ok(req) => req,
+        Err(rejection) => return ApiError::from(rejection).into_response(),
+    };
+    let response = server
+        .handle_request(&client_key(addr), Request::Embed(req))
+        .await;
+    into_http(response)
+}
+
+async fn search(
+    State(server): State<Arc<DaemonServer>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    payload: Result<Json<SearchRequest>, JsonRejection>,
+) -> ApiResult<SearchResults> {
+    server.touch().await;
+    let Json(req) = payload?;
+    let start = Instant::now();
+
+    let query = req.query.trim();
+    if query.is_empty() {
+        return Err(ApiError::bad_request("query cannot be empty"));
+    }
+
+    // The daemon is shared across projects, so only filter on an explicit workspace
+    let options = req
+        .options(&server.config.search, None)
+        .map_err(|e| ApiError::bad_request(e.to_string()))?;
+    let candidates = options.candidates(server.config.search.rerank_top_k);
+
+    // The query, plus each candidate the cross-encoder scores
+    let reranked = if options.rerank {
+        candidates as usize
+    } else {
+        0
+    };
+    admit(&server, addr, 1 + reranked)?;
+
+    let vector_store = server
+        .vector_store()
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?;
+    let service = SearchService::new(
+        Arc::new(ServerModels(Arc::clone(&server))),
+        Arc::clone(vector_store),
+        server.config.search.clone(),
+    );
+    let mut results = service.search(query, &options).await?;
+
+    results.duration_ms = start.elapsed().as_millis() as u64;
+    server.record_search(&SearchRecord::new(
+        query,
+        &options.filter,
+        &results.results,
+        results.duration_ms,
+    ));
+    Ok(Json(results))
+}
+
+/// The daemon's own models, embedding and reranking in-process for [`SearchService`].
+struct ServerModels(Arc<DaemonServer>);
+
+#[async_trait]
+impl EmbeddingProvider for ServerModels {
+    async fn embed_batch(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+        Ok(self
+            .0
+            .embed(texts, false, None, None, Priority::Low)
+            .await?)
+    }
+
+    async fn embed_queries(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+        Ok(self
+            .0
+            .embed(texts, true, None, None, Priority::High)
+            .await?)
+    }
+
+    async fn rerank(
+        &self,
+        query: &str,
+        documents: Vec<String>,
+    ) -> Result<Vec<f32>, EmbeddingError> {
+        Ok(self.0.rerank(query, &documents).await?)
+    }
+}
+
+async fn index(
+    State(server): State<Arc<DaemonServer>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    payload: Result<Json<IndexRequest>, JsonRejection>,
+) -> ApiResult<IndexResponse> {
+    server.touch().await;
+    let Json(req) = payload?;
+    let start = Instant::now();
+
+    let tags = parse_tag_list(&req.tags)?;
+
+    let mut stats = IndexResponse {
+        documents_indexed: 0,
+        documents_skipped: 0,
+        chunks_created: 0,
+        duration_ms: 0,
+    };
+
+    let mut chunks = Vec::new();
+    for doc in req.documents {
+        if doc.content.is_empty() {
+            stats.documents_skipped += 1;
+            continue;
+        }
+        let document = into_document(doc, &tags, req.workspace.as_deref());
+        let doc_chunks = server.chunker.chunk(&document);
+        stats.chunks_created += doc_chunks.len() as u64;
+        stats.documents_indexed += 1;
+        chunks.extend(doc_chunks);
+    }
+
+    if !chunks.is_empty() {
+        admit(&server, addr, chunks.len())?;
+        let vector_store = server
+            .vector_store()
+            .await
+            .map_err(|e| ApiError::internal(e.to_string()))?;
+        vector_store
+            .create_collection()
+            .await
+            .map_err(|e| ApiError::internal(e.to_string()))?;
+
+        let batch_size = (server.config.embedding.batch_size as usize).max(1);
+        while !chunks.is_empty() {
+            let mut batch: Vec<_> = chunks.drain(..batch_size.min(chunks.len())).collect();
+            let texts: Vec<String> = batch.iter().map(|c| c.content.clone()).collect();
+            let embeddings = server
+                .embed(texts, false, None, None, Priority::Low)
+                .await
+                .map_err(ApiError::from)?;
+            for (chunk, embedding) in batch.iter_mut().zip(embeddings) {
+                chunk.dense_vector = embedding;
+            }
+            vector_store
+                .upsert_points(batch)
+                .await
+                .map_err(|e| ApiError::internal(e.to_string()))?;
+        }
+    }
+
+    stats.duration_ms = start.elapsed().as_millis() as u64;
+    Ok(Json(stats))
+}
+
+fn into_http(response: Response) -> HttpResponse {
+    match response {
+        Response::Error(err) => ApiError::from(err).into_response(),
+        Response::Busy(busy) => ApiError::from(busy).into_response(),
+        other => Json(other).into_response(),
+    }
+}
+
+fn parse_tag_list(tags: &[String]) -> Result<Vec<Tag>, ApiError> {
+    if tags.is_empty() {
+        return Ok(Vec::new());
+    }
+    parse_tags(&tags.join(",")).map_err(|e| ApiError::bad_request(format!("invalid tags: {e}")))
+}
+
+/// Convert an API document into a [`Document`], merging in `base_tags` and
+/// stamping `workspace`.
+pub(crate) fn into_document(
+    doc: IndexDocument,
+    base_tags: &[Tag],
+    workspace: Option<&str>,
+) -> Document {
+    let checksum = hex::encode(Sha256::digest(doc.content.as_bytes()));
+
+    let source_type: SourceType = doc
+        .source_type
+        .as_deref()
+        .map(|s| s.parse().unwrap())
+        .unwrap_or_else(|| SourceType::Other("custom".to_string()));
+
+    // Location: url > path > checksum
+    let location = doc
+        .url
+        .clone()
+        .or_else(|| doc.path.clone())
+        .unwrap_or_else(|| checksum.clone());
+
+    let source = Source::new(source_type, location, doc.url.clone());
+
+    let metadata = DocumentMetadata {
+        filename: None,
+        extension: None,
+        language: doc
+            .path
+            .as_deref()
+            .and_then(|path| detect_language(Path::new(path))),
+        title: doc.title,
+        path: doc.path,
+        size_bytes: doc.content.len() as u64,
+    };
+
+    let mut tags = base_tags.to_vec();
+    for tag in doc.tags.iter().filter_map(|t| t.parse::<Tag>().ok()) {
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+
+    let mut document = Document::new(doc.content, source, tags, checksum, metadata);
+    if let Some(workspace) = workspace {
+        document
+            .attributes
+            .insert(WORKSPACE_ATTRIBUTE.to_string(), workspace.to_string());
+    }
+    document
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::protocol::RequestEnvelope;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Status code of a `GET /status` sent over TCP with an optional bearer token.
+    async fn status_code(addr: SocketAddr, token: Option<&str>) -> u16 {
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let authorization = token
+            .map(|token| format!("Authorization: Bearer {}\r\n", token))
+            .unwrap_or_default();
+        let request = format!(
+            "GET /status HTTP/1.1\r\nHost: localhost\r\n{}Connection: close\r\n\r\n",
+            authorization
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+            .split_whitespace()
+            .nth(1)
+            .and_then(|code| code.parse().ok())
+            .unwrap()
+    }
+
+    async fn serve_with_token(token: Option<&str>) -> SocketAddr {
+        let app = Router::new()
+            .route("/status", get(|| async { "ok" }))
+            .layer(middleware::from_fn_with_state(
+                token.map(Arc::from),
+                require_token,
+            ));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        addr
+    }
+
+    #[tokio::test]
+    async fn test_auth_token() {
+        // TCP: the bearer token is required once one is configured
+        let addr = serve_with_token(Some("secret")).await;
+        assert_eq!(status_code(addr, None).await, 401);
+        assert_eq!(status_code(addr, Some("secre")).await, 401);
+        assert_eq!(status_code(addr, Some("secret")).await, 200);
+        let open = serve_with_token(None).await;
+        assert_eq!(status_code(open, None).await, 200);
+
+        // Socket: the same token in the request envelope
+        let envelope = RequestEnvelope::new(Request::Ping, Some("secret".to_string()));
+        assert!(envelope.is_authorized(Some("secret")));
+        assert!(!RequestEnvelope::new(Request::Ping, None).is_authorized(Some("secret")));
+    }
+
+    #[test]
+    fn test_into_document_merges_tags() {
+        let doc = IndexDocument {
+            content: "hello world".to_string(),
+            url: None,
+            title: Some("Greeting".to_string()),
+            path: Some("notes/hello.md".to_string()),
+            tags: vec!["team:core".to_string(), "lang:en".to_string()],
+            source_type: None,
+        };
+        let base = vec!["team:core".parse::<Tag>().unwrap()];
+
+        let document = into_document(doc, &base, Some("/work/api"));
+
+        assert_eq!(document.source.location, "notes/hello.md");
+        assert_eq!(document.tags.len(), 2);
+        assert_eq!(document.attributes[WORKSPACE_ATTRIBUTE], "/work/api");
+    }
+
+    #[test]
+    fn test_search_request_defaults() {
+        let req: SearchRequest = serde_json::from_str(r#"{"query": "auth"}"#).unwrap();
+        assert_eq!(req.query, "auth");
+        assert!(req.limit.is_none());
+        assert!(req.tags.is_empty());
+    }
+
+    #[test]
+    fn test_search_request_options() {
+        let config = SearchConfig::default();
+        let mut req = SearchRequest::for_query("auth");
+        let options = req.options(&config, None).unwrap();
+        assert_eq!(options.limit, config.default_limit);
+
+        req.limit = Some(MAX_SEARCH_LIMIT);
+        assert!(req.options(&config, None).is_ok());
+        req.limit = Some(MAX_SEARCH_LIMIT + 1);
+        assert!(req.options(&config, None).is_err());
+    }
+
+    #[test]
+    fn test_search_request_filter() {
+        let req: SearchRequest = serde_json::from_str(
+            r#"{"query": "auth", "tags": ["team:core"], "source_types": ["jira"], "filters": ["branch:main"]}"#,
+        )
+        .unwrap();
+        let filter = req.filter().unwrap();
+        assert_eq!(filter.tags.len(), 1);
+        assert_eq!(filter.source_types, vec![SourceType::Jira]);
+        assert_eq!(filter.attributes[0].value, "main");
+
+        let bad: SearchRequest =
+            serde_json::from_str(r#"{"query": "auth", "filters": ["nocolon"]}"#).unwrap();
+        assert!(bad.filter().is_err());
+    }
+
+    #[test]
+    fn test_search_request_workspace() {
+        let req: SearchRequest = serde_json::from_str(r#"{"query": "auth"}"#).unwrap();
+        assert_eq!(req.workspace(Some("/work/api")), Some("/work/api"));
+
+        let req: SearchRequest =
+            serde_json::from_str(r#"{"query": "auth", "workspace": "/work/web"}"#).unwrap();
+        assert_eq!(req.workspace(Some("/work/api")), Some("/work/web"));
+
+        let req: SearchRequest = serde_json::from_str(
+            r#"{"query": "auth", "workspace": "/work/web", "all_workspaces": true}"#,
+        )
+        .unwrap();
+        assert_eq!(req.workspace(Some("/work/api")), None);
+    }
+}
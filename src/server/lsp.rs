@@ -0,0 +1,310 @@
+//! Experimental Language Server Protocol mode (`ssearch serve --lsp`).
+//!
+//! Any LSP-capable editor can start it as a language server and surface
+//! semantic matches through its usual UI:
+//!
+//! - `workspace/symbol`: the query is searched semantically; each match is
+//!   a symbol named after its enclosing code symbol (or file), located at
+//!   the matched lines
+//! - `ssearch/semanticSearch`: the `POST /search` body; returns
+//!   `{"results": [...]}`, the same symbols with their score and a preview
+//!
+//! Messages use the LSP `Content-Length` framing. Failures are errors with
+//! code [`REQUEST_FAILED`].
+
+use std::path::Path;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use tracing::{Instrument, info_span, warn};
+use url::Url;
+
+use crate::models::{
+    Config, RELATIVE_PATH_ATTRIBUTE, SYMBOL_ATTRIBUTE, SYMBOL_KIND_ATTRIBUTE, SearchResult,
+    SourceType,
+};
+use crate::server::http::SearchRequest;
+use crate::server::stdio::{RpcError, RpcHandler, StdioContext, failed, params, serve_framed};
+use crate::services::snippet::Snippet;
+
+/// LSP `RequestFailed`: the request was understood but failed
+pub const REQUEST_FAILED: i32 = -32803;
+
+/// Characters of matched content in a `ssearch/semanticSearch` preview
+const PREVIEW_CHARS: usize = 200;
+
+/// LSP `SymbolKind` of a file, used for matches outside any code symbol
+const FILE_KIND: u32 = 1;
+
+/// Params of `workspace/symbol`.
+#[derive(Debug, Deserialize)]
+struct WorkspaceSymbolParams {
+    query: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Position {
+    line: u32,
+    character: u32,
+}
+
+#[derive(Debug, Serialize)]
+struct Range {
+    start: Position,
+    end: Position,
+}
+
+#[derive(Debug, Serialize)]
+struct Location {
+    uri: String,
+    range: Range,
+}
+
+/// LSP `SymbolInformation`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SymbolInformation {
+    name: String,
+    kind: u32,
+    location: Location,
+    /// Project-relative path (or location) of the match, with its score
+    container_name: String,
+}
+
+/// One result of `ssearch/semanticSearch`.
+#[derive(Debug, Serialize)]
+struct SemanticMatch {
+    #[serde(flatten)]
+    symbol: SymbolInformation,
+    score: f32,
+    preview: String,
+}
+
+pub struct LspServer {
+    context: StdioContext,
+}
+
+#[async_trait]
+impl RpcHandler for LspServer {
+    async fn call(&self, method: &str, params: Value) -> Result<Value, RpcError> {
+        let span = info_span!("request", transport = "lsp", method = %method);
+        let result = async {
+            match method {
+                "initialize" => Ok(json!({
+                    "capabilities": {"workspaceSymbolProvider": true},
+                    "serverInfo": {
+                        "name": "ssearch",
+                        "version": env!("CARGO_PKG_VERSION"),
+                    },
+                })),
+                "shutdown" => Ok(Value::Null),
+                "workspace/symbol" => {
+                    let req: WorkspaceSymbolParams = self::params(params)?;
+                    // Editors ask with an empty query as the picker opens
+                    if req.query.trim().is_empty() {
+                        return Ok(json!([]));
+                    }
+                    let request = SearchRequest::for_query(req.query);
+                    let results = self
+                        .context
+                        .search(request)
+                        .await
+                        .map_err(failed(REQUEST_FAILED))?;
+                    let symbols: Vec<SymbolInformation> = results
+                        .results
+                        .iter()
+                        .filter_map(symbol_information)
+                        .collect();
+                    serde_json::to_value(symbols).map_err(failed(REQUEST_FAILED))
+                }
+                "ssearch/semanticSearch" => {
+                    let results = self.context.search(self::params(params)?).await;
+                    let results = results.map_err(failed(REQUEST_FAILED))?;
+                    let matches: Vec<SemanticMatch> = results
+                        .results
+                        .iter()
+                        .filter_map(|result| {
+                            Some(SemanticMatch {
+                                symbol: symbol_information(result)?,
+                                score: result.score,
+                                preview: Snippet::extract(
+                                    &result.content,
+                                    &results.query,
+                                    PREVIEW_CHARS,
+                                )
+                                .render(str::to_string),
+                            })
+                        })
+                        .collect();
+                    Ok(json!({ "results": matches }))
+                }
+                other => Err(RpcError::method_not_found(other)),
+            }
+        }
+        .instrument(span.clone())
+        .await;
+        if let Err(ref error) = result {
+            span.in_scope(|| warn!(error = %error.message, "request failed"));
+        }
+        result
+    }
+}
+
+impl LspServer {
+    pub fn new(config: Config) -> Self {
+        Self {
+            context: StdioContext::new(config),
+        }
+    }
+
+    /// Serve requests from stdin until it is closed or the client exits.
+    pub async fn run(&self) -> std::io::Result<()> {
+        serve_framed(self, "LSP").await
+    }
+}
+
+/// LSP `SymbolKind` of a chunker symbol kind (`fn`, `class`, ...).
+fn symbol_kind(kind: &str) -> u32 {
+    match kind {
+        "mod" => 2,
+        "class" | "impl" => 5,
+        "method" => 6,
+        "constructor" => 9,
+        "enum" => 10,
+        "trait" | "interface" => 11,
+        "fn" | "function" | "def" => 12,
+        "struct" | "record" => 23,
+        "type" => 26,
+        _ => FILE_KIND,
+    }
+}
+
+/// `result` as a symbol at its lines; `None` when it has no local path or
+/// URL an editor could open.
+fn symbol_information(result: &SearchResult) -> Option<SymbolInformation> {
+    let uri = match result.source.source_type {
+        SourceType::Local => Url::from_file_path(&result.source.location)
+            .ok()
+            .map(String::from),
+        _ => None,
+    }
+    .or_else(|| result.source.url.clone())?;
+
+    let path = result
+        .attributes
+        .get(RELATIVE_PATH_ATTRIBUTE)
+        .unwrap_or(&result.location);
+    let (name, kind) = match result.attributes.get(SYMBOL_ATTRIBUTE) {
+        Some(name) => {
+            let kind = result.attributes.get(SYMBOL_KIND_ATTRIBUTE);
+            (name.clone(), kind.map_or(FILE_KIND, |k| symbol_kind(k)))
+        }
+        None => {
+            let file = Path::new(path).file_name().map(|f| f.to_string_lossy());
+            (
+                file.map_or_else(|| path.clone(), |f| f.into_owned()),
+                FILE_KIND,
+            )
+        }
+    };
+
+    // LSP lines are 0-based; the range ends at the start of the line after the match
+    let start = result.line_start.unwrap_or(1).saturating_sub(1);
+    let end = result.line_end.map_or(start, |end| end.max(start + 1));
+    Some(SymbolInformation {
+        name,
+        kind,
+        location: Location {
+            uri,
+            range: Range {
+                start: Position {
+                    line: start,
+                    character: 0,
+                },
+                end: Position {
+                    line: end,
+                    character: 0,
+                },
+            },
+        },
+        container_name: format!("{} ({:.2})", path, result.score),
+    })
+}
+
+/// Run the language server on stdio until the client exits.
+pub async fn run_lsp(config: Config) -> std::io::Result<()> {
+    LspServer::new(config).run().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::stdio::handle_message;
+    use crate::testing::search_result;
+
+    #[tokio::test]
+    async fn test_initialize() {
+        let server = LspServer::new(Config::default());
+        let response = handle_message(
+            &server,
+            r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"capabilities":{}}}"#,
+        )
+        .await
+        .unwrap();
+        let result = response.result.unwrap();
+        assert_eq!(result["capabilities"]["workspaceSymbolProvider"], true);
+
+        let response = handle_message(
+            &server,
+            r#"{"jsonrpc":"2.0","id":2,"method":"workspace/symbol","params":{"query":""}}"#,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.result.unwrap(), json!([]));
+    }
+
+    #[test]
+    fn test_symbol_information() {
+        let mut result = search_result("/repo/src/auth.rs", "fn refresh() {}\n// ...", 0.8123);
+        result.line_start = Some(10);
+        result.line_end = Some(12);
+        result
+            .attributes
+            .insert(SYMBOL_ATTRIBUTE.to_string(), "refresh".to_string());
+        result
+            .attributes
+            .insert(SYMBOL_KIND_ATTRIBUTE.to_string(), "fn".to_string());
+        result.attributes.insert(
+            RELATIVE_PATH_ATTRIBUTE.to_string(),
+            "src/auth.rs".to_string(),
+        );
+
+        let symbol = serde_json::to_value(symbol_information(&result).unwrap()).unwrap();
+        assert_eq!(
+            symbol,
+            json!({
+                "name": "refresh",
+                "kind": 12,
+                "location": {
+                    "uri": "file:///repo/src/auth.rs",
+                    "range": {
+                        "start": {"line": 9, "character": 0},
+                        "end": {"line": 12, "character": 0},
+                    },
+                },
+                "containerName": "src/auth.rs (0.81)",
+            })
+        );
+
+        // Outside any symbol, the file names the match
+        let result = search_result("/repo/docs/deploy.md", "Roll out", 0.5);
+        let symbol = symbol_information(&result).unwrap();
+        assert_eq!(symbol.name, "deploy.md");
+        assert_eq!(symbol.kind, FILE_KIND);
+
+        // Relative locations can't be opened
+        let result = search_result("docs/deploy.md", "Roll out", 0.5);
+        assert!(symbol_information(&result).is_none());
+    }
+}
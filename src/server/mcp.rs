@@ -0,0 +1,290 @@
+//! Model Context Protocol (MCP) server over stdio.
+//!
+//! Exposes search, indexing, and status as MCP tools so coding agents can
+//! query the index directly. Messages are newline-delimited JSON-RPC 2.0 on
+//! stdin/stdout; diagnostics go to stderr. Embeddings come from the daemon,
+//! which is started on demand like for the CLI.
+
+use std::time::Instant;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tracing::{Instrument, info_span, warn};
+
+use crate::models::{Config, Document, parse_tags};
+use crate::server::http::{IndexRequest, IndexResponse, SearchRequest, into_document};
+use crate::server::stdio::{RpcError, RpcHandler, StdioContext, params, serve_stdio};
+
+/// Protocol revision implemented by this server
+pub const MCP_PROTOCOL_VERSION: &str = "2024-11-05";
+
+#[derive(Debug, Deserialize)]
+struct ToolCall {
+    name: String,
+    #[serde(default)]
+    arguments: Value,
+}
+
+pub struct McpServer {
+    context: StdioContext,
+}
+
+#[async_trait]
+impl RpcHandler for McpServer {
+    async fn call(&self, method: &str, params: Value) -> Result<Value, RpcError> {
+        match method {
+            "initialize" => Ok(initialize_result(&params)),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+            "tools/call" => Ok(self.call_tool(self::params(params)?).await),
+            other => Err(RpcError::method_not_found(other)),
+        }
+    }
+}
+
+impl McpServer {
+    pub fn new(config: Config) -> Self {
+        Self {
+            context: StdioContext::new(config),
+        }
+    }
+
+    /// Serve requests from stdin until it is closed.
+    pub async fn run(&self) -> std::io::Result<()> {
+        serve_stdio(self, "MCP").await
+    }
+
+    /// Run a tool; failures are reported in the result with `isError`.
+    async fn call_tool(&self, call: ToolCall) -> Value {
+        let span = info_span!("request", transport = "mcp", tool = %call.name);
+        let result = async {
+            match call.name.as_str() {
+                "search" => match serde_json::from_value(call.arguments) {
+                    Ok(req) => self.search(req).await,
+                    Err(e) => Err(format!("invalid arguments: {}", e)),
+                },
+                "index" => match serde_json::from_value(call.arguments) {
+                    Ok(req) => self.index(req).await,
+                    Err(e) => Err(format!("invalid arguments: {}", e)),
+                },
+                "status" => Ok(self.context.status().await),
+                other => Err(format!("unknown tool: {}", other)),
+            }
+        }
+        .instrument(span.clone())
+        .await;
+        if let Err(ref message) = result {
+            span.in_scope(|| warn!(error = %message, "tool call failed"));
+        }
+
+        match result {
+            Ok(value) => json!({
+                "content": [{
+                    "type": "text",
+                    "text": serde_json::to_string_pretty(&value).unwrap_or_default(),
+                }],
+                "isError": false,
+            }),
+            Err(message) => json!({
+                "content": [{ "type": "text", "text": message }],
+                "isError": true,
+            }),
+        }
+    }
+
+    async fn search(&self, req: SearchRequest) -> Result<Value, String> {
+        let results = self.context.search(req).await?;
+        serde_json::to_value(results).map_err(|e| e.to_string())
+    }
+
+    async fn index(&self, req: IndexRequest) -> Result<Value, String> {
+        let start = Instant::now();
+        let config = &self.context.config;
+
+        let tags = if req.tags.is_empty() {
+            Vec::new()
+        } else {
+            parse_tags(&req.tags.join(",")).map_err(|e| format!("invalid tags: {}", e))?
+        };
+        let workspace = req.workspace.as_deref().or(config.workspace());
+        let documents: Vec<Document> = req
+            .documents
+            .into_iter()
+            .map(|doc| into_document(doc, &tags, workspace))
+            .collect();
+
+        let service = self
+            .context
+            .index_service()
+            .await?
+            .with_workspace(workspace.map(String::from));
+        let summary = service
+            .index_documents(documents)
+            .await
+            .map_err(|e| format!("{:#}", e))?;
+
+        serde_json::to_value(IndexResponse {
+            documents_indexed: summary.documents_indexed,
+            documents_skipped: summary.documents_skipped,
+            chunks_created: summary.chunks_created,
+            duration_ms: start.elapsed().as_millis() as u64,
+        })
+        .map_err(|e| e.to_string())
+    }
+}
+
+/// Run the MCP server on stdio until stdin is closed.
+pub async fn run_mcp(config: Config) -> std::io::Result<()> {
+    McpServer::new(config).run().await
+}
+
+fn initialize_result(params: &Value) -> Value {
+    // Echo the client's protocol version when given; we only use stable basics
+    let version = params
+        .get("protocolVersion")
+        .and_then(Value::as_str)
+        .unwrap_or(MCP_PROTOCOL_VERSION);
+
+    json!({
+        "protocolVersion": version,
+        "capabilities": { "tools": {} },
+        "serverInfo": {
+            "name": "ssearch",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "instructions": "Semantic search over the local index of code, documents, and synced sources (Jira, Confluence, Figma, GitHub, Slack, Google Drive, email, web pages). Use `search` with natural-language queries.",
+    })
+}
+
+fn tool_definitions() -> Value {
+    let string_list = json!({ "type": "array", "items": { "type": "string" } });
+
+    json!([
+        {
+            "name": "search",
+            "description": "Semantic search over indexed content. Returns matching chunks with location, score, and tags.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string", "description": "Natural-language search query" },
+                    "limit": { "type": "integer", "minimum": 1, "maximum": 100, "description": "Maximum results" },
+                    "tags": { "type": "array", "items": { "type": "string" }, "description": "Tag filters (key:value)" },
+                    "source_types": { "type": "array", "items": { "type": "string" }, "description": "Source filters (local, jira, confluence, figma, github, slack)" },
+                    "filters": { "type": "array", "items": { "type": "string" }, "description": "Filter expressions (language=rust, path~src/**/*.rs, symbol~handle_, created_at>=2024-01-01)" },
+                    "min_score": { "type": "number", "minimum": 0.0, "maximum": 1.0 },
+                    "hybrid": { "type": "boolean", "description": "Fuse keyword relevance with semantic similarity" },
+                    "rerank": { "type": "boolean", "description": "Re-score candidates with the cross-encoder" },
+                    "recency": { "type": "number", "description": "Boost recently updated documents (0.0-1.0)" },
+                    "dedup_threshold": { "type": "number", "minimum": 0.0, "maximum": 1.0, "description": "Drop results this similar to a higher-ranked result" },
+                    "workspace": { "type": "string", "description": "Workspace to search (defaults to the current project)" },
+                    "all_workspaces": { "type": "boolean", "description": "Search every workspace instead of the current project" }
+                },
+                "required": ["query"]
+            }
+        },
+        {
+            "name": "index",
+            "description": "Add documents to the search index.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "documents": {
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "content": { "type": "string" },
+                                "url": { "type": "string" },
+                                "title": { "type": "string" },
+                                "path": { "type": "string" },
+                                "tags": string_list,
+                                "source_type": { "type": "string" }
+                            },
+                            "required": ["content"]
+                        }
+                    },
+                    "tags": { "type": "array", "items": { "type": "string" }, "description": "Tags applied to every document (key:value)" },
+                    "workspace": { "type": "string", "description": "Workspace stamped on the documents (defaults to the current project)" }
+                },
+                "required": ["documents"]
+            }
+        },
+        {
+            "name": "status",
+            "description": "Report daemon and vector store status, including the number of indexed chunks.",
+            "inputSchema": { "type": "object", "properties": {} }
+        }
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::stdio::{METHOD_NOT_FOUND, PARSE_ERROR, handle_message};
+
+    fn server() -> McpServer {
+        McpServer::new(Config::default())
+    }
+
+    #[tokio::test]
+    async fn test_initialize_and_list_tools() {
+        let server = server();
+
+        let response = handle_message(&server, r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2025-03-26"}}"#)
+            .await
+            .unwrap();
+        let result = response.result.unwrap();
+        assert_eq!(result["protocolVersion"], "2025-03-26");
+        assert_eq!(result["serverInfo"]["name"], "ssearch");
+
+        let response = handle_message(&server, r#"{"jsonrpc":"2.0","id":2,"method":"tools/list"}"#)
+            .await
+            .unwrap();
+        let tools = response.result.unwrap()["tools"].clone();
+        let names: Vec<&str> = tools
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, vec!["search", "index", "status"]);
+    }
+
+    #[tokio::test]
+    async fn test_notifications_and_errors() {
+        let server = server();
+
+        assert!(
+            handle_message(
+                &server,
+                r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#
+            )
+            .await
+            .is_none()
+        );
+
+        let response = handle_message(
+            &server,
+            r#"{"jsonrpc":"2.0","id":3,"method":"resources/list"}"#,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.error.unwrap().code, METHOD_NOT_FOUND);
+
+        let response = handle_message(&server, "not json").await.unwrap();
+        assert_eq!(response.error.unwrap().code, PARSE_ERROR);
+    }
+
+    #[tokio::test]
+    async fn test_tool_errors_are_results() {
+        let server = server();
+
+        let response = handle_message(&server, r#"{"jsonrpc":"2.0","id":4,"method":"tools/call","params":{"name":"search","arguments":{"query":"  "}}}"#)
+            .await
+            .unwrap();
+        let result = response.result.unwrap();
+        assert_eq!(result["isError"], true);
+        assert_eq!(result["content"][0]["text"], "query cannot be empty");
+    }
+}
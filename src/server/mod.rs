@@ -1,33 +1,98 @@
+pub mod activation;
+pub mod editor;
 pub mod embedding;
+pub mod http;
+pub mod lsp;
+pub mod mcp;
+pub mod prometheus;
 pub mod protocol;
+pub mod rate_limit;
+pub mod reranker;
+pub mod scheduler;
+mod stdio;
 
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
 use std::path::PathBuf;
-use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
-use tokio::net::UnixListener;
-use tokio::sync::RwLock;
+use tokio::net::{TcpListener, UnixListener, UnixStream};
+use tokio::signal::unix::{SignalKind, signal};
+use tokio::sync::{Notify, OnceCell, RwLock, mpsc};
+use tracing::{Instrument, debug, error, info, info_span, warn};
 
-use crate::error::ModelError;
+use crate::error::{ModelError, VectorStoreError};
 use crate::models::Config;
-use crate::server::embedding::{EmbeddingModel, SharedEmbeddingModel};
+use crate::server::activation::activated_listener;
+use crate::server::embedding::ModelRegistry;
 use crate::server::protocol::{
-    EmbedResponse, Request, Response, StatusResponse, decode_length, encode_message,
+    BatchAckResponse, EmbedRequest, EmbedResponse, ErrorCode, MAX_MESSAGE_BYTES, MetricsResponse,
+    ModelInfo, ModelReloadedResponse, ModelSpec, PROTOCOL_VERSION, Priority, ProgressResponse,
+    ReloadModelRequest, Request, RequestCount, RequestEnvelope, RerankResponse, Response,
+    StatusResponse, decode_length, encode_message,
+};
+use crate::server::rate_limit::RateLimiter;
+use crate::server::reranker::{RERANK_MAX_TOKENS, RerankerModel};
+use crate::server::scheduler::{EmbedScheduler, LOW_PRIORITY_SLICE};
+use crate::services::{
+    MetricsStore, SearchRecord, TextChunker, VectorStore, create_backend_with_embedding_config,
 };
-use crate::services::MetricsStore;
 
+pub use editor::run_editor;
 pub use embedding::EmbeddingModel as OnnxEmbeddingModel;
+pub use lsp::run_lsp;
+pub use mcp::run_mcp;
+
+/// How long a stopping daemon waits for in-flight socket requests.
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
 
 pub struct DaemonServer {
     config: Config,
     socket_path: PathBuf,
-    embedding_model: SharedEmbeddingModel,
+    models: Arc<ModelRegistry>,
+    /// Cross-encoder, loaded on the first rerank request
+    reranker: OnceCell<RerankerModel>,
     metrics: Option<MetricsStore>,
+    vector_store: OnceCell<Arc<dyn VectorStore>>,
+    /// Chunker for `POST /index`, with the tokenizer loaded once
+    chunker: TextChunker,
     last_request: Arc<RwLock<Instant>>,
     requests_served: Arc<AtomicU64>,
+    /// Requests handled per (transport, kind, success)
+    request_counts: Mutex<BTreeMap<(&'static str, &'static str, bool), u64>>,
+    started_at: Instant,
     shutdown: Arc<AtomicBool>,
+    /// Wakes the accept loop when a `Shutdown` request arrives
+    shutdown_signal: Notify,
+    /// Socket requests being handled, drained before the daemon exits
+    in_flight: AtomicUsize,
+    /// Listening on a socket handed over by systemd or launchd
+    socket_activated: AtomicBool,
+    /// `[daemon] rate_limit` and `client_rate_limit`
+    rate_limiter: RateLimiter,
+    /// Orders embed requests by priority for the model's sessions
+    scheduler: EmbedScheduler,
+    /// Set once the startup warm-up has finished
+    ready: AtomicBool,
+}
+
+/// Counts one request as in flight until dropped.
+struct InFlight<'a>(&'a AtomicUsize);
+
+impl<'a> InFlight<'a> {
+    fn start(counter: &'a AtomicUsize) -> Self {
+        counter.fetch_add(1, Ordering::SeqCst);
+        Self(counter)
+    }
+}
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
 }
 
 impl DaemonServer {
@@ -37,16 +102,14 @@ impl DaemonServer {
             ModelError::NotFound("could not determine models directory".to_string())
         })?;
 
-        eprintln!("Loading embedding model: {}", config.embedding.model_id);
-        let embedding_dir = config
-            .embedding
-            .model_path
-            .clone()
-            .unwrap_or_else(|| models_dir.join(model_dir_name(&config.embedding.model_id)));
-        let embedding_model = Arc::new(EmbeddingModel::load(&config.embedding, &embedding_dir)?);
-        eprintln!(
-            "Embedding model loaded (dim={})",
-            embedding_model.dimension()
+        info!(model = %config.embedding.model_id, "loading embedding model");
+        let models = Arc::new(ModelRegistry::load(&config.embedding, models_dir)?);
+        let embedding_model = models.default_model();
+        info!(
+            dim = embedding_model.dimension(),
+            sessions = embedding_model.pool_size(),
+            device = %embedding_model.device(),
+            "embedding model loaded"
         );
 
         let metrics = if config.metrics.enabled {
@@ -54,14 +117,14 @@ impl DaemonServer {
                 match MetricsStore::open(&path) {
                     Ok(store) => {
                         store.cleanup(config.metrics.retention_days);
-                        eprintln!(
-                            "Metrics enabled (retention: {} days)",
-                            config.metrics.retention_days
+                        info!(
+                            retention_days = config.metrics.retention_days,
+                            "metrics enabled"
                         );
                         Some(store)
                     }
                     Err(e) => {
-                        eprintln!("Warning: Failed to open metrics database: {}", e);
+                        warn!(error = %e, "failed to open metrics database");
                         None
                     }
                 }
@@ -73,40 +136,108 @@ impl DaemonServer {
         };
 
         Ok(Self {
+            chunker: TextChunker::from_config(&config),
+            rate_limiter: RateLimiter::from_config(&config.daemon),
+            scheduler: EmbedScheduler::new(embedding_model.pool_size()),
             config,
             socket_path,
-            embedding_model,
+            models,
+            reranker: OnceCell::new(),
             metrics,
+            vector_store: OnceCell::new(),
             last_request: Arc::new(RwLock::new(Instant::now())),
             requests_served: Arc::new(AtomicU64::new(0)),
+            request_counts: Mutex::new(BTreeMap::new()),
+            started_at: Instant::now(),
             shutdown: Arc::new(AtomicBool::new(false)),
+            shutdown_signal: Notify::new(),
+            in_flight: AtomicUsize::new(0),
+            socket_activated: AtomicBool::new(false),
+            ready: AtomicBool::new(false),
         })
     }
 
-    pub async fn run(&self) -> Result<(), std::io::Error> {
-        if self.socket_path.exists() {
-            std::fs::remove_file(&self.socket_path)?;
+    pub async fn run(self: Arc<Self>) -> Result<(), std::io::Error> {
+        let listener = match activated_listener()? {
+            Some(listener) => {
+                info!("using socket from the service manager");
+                self.socket_activated.store(true, Ordering::Relaxed);
+                listener
+            }
+            None => {
+                if self.socket_path.exists() {
+                    std::fs::remove_file(&self.socket_path)?;
+                }
+                UnixListener::bind(&self.socket_path)?
+            }
+        };
+        let socket_activated = self.socket_activated.load(Ordering::Relaxed);
+
+        if let Some(ref addr) = self.config.daemon.http_addr {
+            let addr: SocketAddr = addr.parse().map_err(|e| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!("invalid HTTP address '{}': {}", addr, e),
+                )
+            })?;
+            let http_listener = TcpListener::bind(addr).await?;
+            info!(addr = %addr, "HTTP API listening");
+
+            let server = Arc::clone(&self);
+            tokio::spawn(async move {
+                if let Err(e) = http::serve(server, http_listener).await {
+                    error!(error = %e, "HTTP server failed");
+                }
+            });
+        }
+
+        if let Some(ref addr) = self.config.metrics.http_addr {
+            let addr: SocketAddr = addr.parse().map_err(|e| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!("invalid metrics address '{}': {}", addr, e),
+                )
+            })?;
+            let metrics_listener = TcpListener::bind(addr).await?;
+            info!(addr = %addr, "metrics listening");
+
+            let server = Arc::clone(&self);
+            tokio::spawn(async move {
+                if let Err(e) = prometheus::serve(server, metrics_listener).await {
+                    error!(error = %e, "metrics server failed");
+                }
+            });
         }
 
-        let listener = UnixListener::bind(&self.socket_path)?;
         self.write_pid_file()?;
 
-        eprintln!("Daemon listening on: {}", self.socket_path.display());
-        eprintln!("Idle timeout: {}s", self.config.daemon.idle_timeout_secs);
+        // Requests are served meanwhile; Status reports ready once it is done
+        let server = Arc::clone(&self);
+        tokio::spawn(async move { server.warm_up().await });
+
+        info!(
+            socket = %self.socket_path.display(),
+            idle_timeout_secs = self.config.daemon.idle_timeout_secs,
+            "daemon listening"
+        );
 
         let idle_timeout = Duration::from_secs(self.config.daemon.idle_timeout_secs);
         let check_interval = Duration::from_secs(10);
+        let mut terminate = signal(SignalKind::terminate())?;
 
         loop {
             tokio::select! {
                 result = listener.accept() => {
                     match result {
                         Ok((stream, _)) => {
-                            *self.last_request.write().await = Instant::now();
-                            self.handle_connection(stream).await;
+                            self.touch().await;
+                            let server = Arc::clone(&self);
+                            tokio::spawn(async move {
+                                server.handle_connection(stream).await;
+                            });
                         }
                         Err(e) => {
-                            eprintln!("Accept error: {}", e);
+                            warn!(error = %e, "failed to accept connection");
                         }
                     }
                 }
@@ -116,27 +247,117 @@ impl DaemonServer {
                     }
                     let last = *self.last_request.read().await;
                     if last.elapsed() > idle_timeout {
-                        eprintln!("Idle timeout reached, shutting down");
+                        info!("idle timeout reached, shutting down");
                         break;
                     }
                 }
+                _ = self.shutdown_signal.notified() => {
+                    info!("shutdown requested");
+                    break;
+                }
+                _ = terminate.recv() => {
+                    info!("received SIGTERM, shutting down");
+                    break;
+                }
                 _ = tokio::signal::ctrl_c() => {
-                    eprintln!("Received SIGINT, shutting down");
+                    info!("received SIGINT, shutting down");
                     break;
                 }
             }
         }
 
+        // Release the socket first so a replacement daemon can bind it; an
+        // activated socket stays with the service manager for the next start
+        self.shutdown.store(true, Ordering::Relaxed);
+        drop(listener);
+        if !socket_activated {
+            let _ = std::fs::remove_file(&self.socket_path);
+        }
+        self.drain().await;
+
         self.cleanup();
         Ok(())
     }
 
-    async fn handle_connection(&self, mut stream: tokio::net::UnixStream) {
+    /// Warm up the embedding model and, with `[daemon] preload`, load the
+    /// reranker and connect to the vector store. Failures are logged and left
+    /// to surface on the first request that needs them.
+    async fn warm_up(&self) {
+        let start = Instant::now();
+        let model = self.models.default_model();
+        let warmed = tokio::task::spawn_blocking(move || model.warm_up())
+            .await
+            .unwrap_or_else(|e| {
+                Err(ModelError::InferenceError(format!(
+                    "warm-up task failed: {e}"
+                )))
+            });
+        if let Err(e) = warmed {
+            warn!(error = %e, "embedding warm-up failed");
+        }
+
+        if self.config.daemon.preload {
+            match self.reranker().await {
+                Ok(reranker) => {
+                    if let Err(e) = reranker.score("warm up", &["warm up".to_string()]) {
+                        warn!(error = %e, "reranker warm-up failed");
+                    }
+                }
+                Err(e) => warn!(error = %e, "failed to preload reranker"),
+            }
+            if let Err(e) = self.vector_store().await {
+                warn!(error = %e, "failed to connect to the vector store");
+            }
+        }
+
+        self.ready.store(true, Ordering::Relaxed);
+        info!(
+            elapsed_ms = start.elapsed().as_millis() as u64,
+            preload = self.config.daemon.preload,
+            "daemon ready"
+        );
+    }
+
+    /// Wait for in-flight socket requests to finish, up to [`DRAIN_TIMEOUT`].
+    async fn drain(&self) {
+        let start = Instant::now();
+        loop {
+            let pending = self.in_flight.load(Ordering::SeqCst);
+            if pending == 0 {
+                return;
+            }
+            if start.elapsed() > DRAIN_TIMEOUT {
+                warn!(pending, "in-flight requests did not finish before shutdown");
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    }
+
+    async fn handle_connection(&self, mut stream: UnixStream) {
+        // Rate limits apply per client process, which may open many connections
+        let client = match stream.peer_cred().ok().and_then(|cred| cred.pid()) {
+            Some(pid) => format!("pid:{}", pid),
+            None => "socket".to_string(),
+        };
         let mut len_buf = [0u8; 4];
+        // Embed batch being streamed over this connection
+        let mut batch: Option<EmbedRequest> = None;
 
         while stream.read_exact(&mut len_buf).await.is_ok() {
             let len = decode_length(&len_buf);
-            if len > 10 * 1024 * 1024 {
+            if len > MAX_MESSAGE_BYTES {
+                warn!(bytes = len, "rejected oversized message");
+                let response = Response::error(
+                    ErrorCode::MessageTooLarge,
+                    format!(
+                        "message of {} bytes exceeds the {} byte limit; stream large batches with begin_batch",
+                        len, MAX_MESSAGE_BYTES
+                    ),
+                );
+                if let Ok(encoded) = encode_message(&response) {
+                    let _ = stream.write_all(&encoded).await;
+                }
                 break;
             }
 
@@ -144,11 +365,16 @@ impl DaemonServer {
             if stream.read_exact(&mut msg_buf).await.is_err() {
                 break;
             }
+            let _in_flight = InFlight::start(&self.in_flight);
 
-            let request: Request = match serde_json::from_slice(&msg_buf) {
+            let envelope: RequestEnvelope = match serde_json::from_slice(&msg_buf) {
                 Ok(r) => r,
                 Err(e) => {
-                    let response = Response::error(format!("invalid request: {}", e));
+                    warn!(error = %e, "invalid request");
+                    let response = Response::error(
+                        ErrorCode::InvalidRequest,
+                        format!("invalid request: {}", e),
+                    );
                     if let Ok(encoded) = encode_message(&response) {
                         let _ = stream.write_all(&encoded).await;
                     }
@@ -156,8 +382,82 @@ impl DaemonServer {
                 }
             };
 
-            let response = self.handle_request(request).await;
-            self.requests_served.fetch_add(1, Ordering::Relaxed);
+            let kind = envelope.request.kind();
+            if !envelope.is_authorized(self.config.daemon.auth_token.as_deref()) {
+                warn!(kind, "rejected unauthenticated request");
+                self.count_request("socket", kind, false);
+                let response = Response::error(
+                    ErrorCode::Unauthorized,
+                    "unauthorized: missing or invalid auth token",
+                );
+                if let Ok(encoded) = encode_message(&response)
+                    && stream.write_all(&encoded).await.is_err()
+                {
+                    break;
+                }
+                continue;
+            }
+            let request = envelope.request;
+
+            let span = info_span!("request", transport = "socket", kind);
+            let response = async {
+                let response = match request {
+                    Request::BeginBatch(req) => {
+                        batch = Some(EmbedRequest {
+                            texts: Vec::new(),
+                            is_query: req.is_query,
+                            model: req.model,
+                            instruction: req.instruction,
+                            priority: req.priority,
+                            progress: req.progress,
+                        });
+                        Response::BatchAck(BatchAckResponse { buffered: 0 })
+                    }
+                    Request::AppendTexts(req) => match batch.as_mut() {
+                        Some(pending) => {
+                            pending.texts.extend(req.texts);
+                            Response::BatchAck(BatchAckResponse {
+                                buffered: pending.texts.len(),
+                            })
+                        }
+                        None => Response::error(
+                            ErrorCode::InvalidRequest,
+                            "append_texts without begin_batch",
+                        ),
+                    },
+                    Request::FinishBatch => match batch.take() {
+                        // Keep the batch buffered so a rate-limited client can finish it later
+                        Some(pending) => match self.admit(&client, pending.texts.len().max(1)) {
+                            Some(busy) => {
+                                batch = Some(pending);
+                                busy
+                            }
+                            None if pending.progress => {
+                                self.embed_with_progress(&mut stream, pending).await
+                            }
+                            None => self.dispatch(Request::Embed(pending)).await,
+                        },
+                        None => Response::error(
+                            ErrorCode::InvalidRequest,
+                            "finish_batch without begin_batch",
+                        ),
+                    },
+                    Request::Embed(req) if req.progress => {
+                        match self.admit(&client, req.texts.len()) {
+                            Some(busy) => busy,
+                            None => self.embed_with_progress(&mut stream, req).await,
+                        }
+                    }
+                    request => self.handle_request(&client, request).await,
+                };
+                if let Response::Error(ref e) = response {
+                    warn!(error = %e.message, "request failed");
+                }
+                response
+            }
+            .instrument(span)
+            .await;
+            self.count_request("socket", kind, !response.is_failure());
 
             if let Ok(encoded) = encode_message(&response)
                 && stream.write_all(&encoded).await.is_err()
@@ -165,22 +465,89 @@ impl DaemonServer {
                 break;
             }
 
-            if matches!(response, Response::ShutdownAck) {
-                self.shutdown.store(true, Ordering::Relaxed);
+            // Finish the current request, then let a stopping daemon exit
+            if self.shutdown.load(Ordering::Relaxed) {
                 break;
             }
         }
     }
 
-    async fn handle_request(&self, request: Request) -> Response {
+    /// Embed `req`, writing a `Progress` frame to `stream` after each slice
+    /// so the client can show how far a long batch has got.
+    async fn embed_with_progress(&self, stream: &mut UnixStream, req: EmbedRequest) -> Response {
+        let (progress_tx, mut progress_rx) = mpsc::unbounded_channel();
+        let priority = req.priority();
+        let embedding = self.embed_reporting(
+            req.texts,
+            req.is_query,
+            req.model,
+            req.instruction,
+            priority,
+            Some(progress_tx),
+        );
+        tokio::pin!(embedding);
+        let result = loop {
+            tokio::select! {
+                result = &mut embedding => break result,
+                Some(progress) = progress_rx.recv() => {
+                    // A client that went away fails the final write instead
+                    if let Ok(encoded) = encode_message(&Response::Progress(progress)) {
+                        let _ = stream.write_all(&encoded).await;
+                    }
+                }
+            }
+        };
+        match result {
+            Ok(embeddings) => Response::Embed(EmbedResponse { embeddings }),
+            Err(e) => Response::model_error(&e),
+        }
+    }
+
+    /// Serve a request from `client` unless it is over its rate limit.
+    async fn handle_request(&self, client: &str, request: Request) -> Response {
+        if let Some(busy) = self.admit(client, request.cost()) {
+            return busy;
+        }
+        self.dispatch(request).await
+    }
+
+    /// `Response::Busy` when `client` may not put `cost` more texts through a model yet.
+    fn admit(&self, client: &str, cost: usize) -> Option<Response> {
+        match self.rate_limiter.check(client, cost) {
+            Ok(()) => None,
+            Err(retry_after) => {
+                debug!(
+                    client,
+                    cost,
+                    retry_after_ms = retry_after.as_millis() as u64,
+                    "rate limited"
+                );
+                Some(Response::busy(retry_after))
+            }
+        }
+    }
+
+    async fn dispatch(&self, request: Request) -> Response {
         match request {
             Request::Ping => Response::Pong,
 
             Request::Shutdown => {
                 self.shutdown.store(true, Ordering::Relaxed);
+                self.shutdown_signal.notify_one();
                 Response::ShutdownAck
             }
 
+            Request::Hello(req) => {
+                if req.protocol_version != PROTOCOL_VERSION {
+                    warn!(
+                        client_version = %req.client_version,
+                        client_protocol = req.protocol_version,
+                        "client speaks another protocol version"
+                    );
+                }
+                Response::hello(&req, self.socket_activated.load(Ordering::Relaxed))
+            }
+
             Request::Status => {
                 let last = *self.last_request.read().await;
                 let metrics_summary = self
@@ -189,26 +556,286 @@ impl DaemonServer {
                     .map(|m| m.get_summary(self.config.metrics.retention_days));
                 Response::Status(StatusResponse {
                     running: true,
-                    embedding_model: self.config.embedding.model_id.clone(),
+                    embedding_model: self.models.default_spec().model_id,
+                    loaded_models: self
+                        .models
+                        .loaded_specs()
+                        .iter()
+                        .map(|spec| spec.to_string())
+                        .collect(),
                     idle_secs: last.elapsed().as_secs(),
                     requests_served: self.requests_served.load(Ordering::Relaxed),
                     metrics: metrics_summary,
+                    ready: self.ready.load(Ordering::Relaxed),
                 })
             }
 
             Request::Embed(req) => {
-                let start = Instant::now();
-                let result = self.embedding_model.embed(&req.texts, req.is_query);
-                let latency_ms = start.elapsed().as_millis() as u64;
-                let success = result.is_ok();
-                if let Some(ref metrics) = self.metrics {
-                    metrics.record(latency_ms, success);
-                }
-                match result {
+                let priority = req.priority();
+                match self
+                    .embed(
+                        req.texts,
+                        req.is_query,
+                        req.model,
+                        req.instruction,
+                        priority,
+                    )
+                    .await
+                {
                     Ok(embeddings) => Response::Embed(EmbedResponse { embeddings }),
-                    Err(e) => Response::error(e.to_string()),
+                    Err(e) => Response::model_error(&e),
                 }
             }
+
+            Request::BeginBatch(_) | Request::AppendTexts(_) | Request::FinishBatch => {
+                Response::error(
+                    ErrorCode::InvalidRequest,
+                    "batch requests are only supported on the daemon socket",
+                )
+            }
+
+            Request::Rerank(req) => match self.rerank(&req.query, &req.documents).await {
+                Ok(scores) => Response::Rerank(RerankResponse { scores }),
+                Err(e) => Response::model_error(&e),
+            },
+
+            Request::Metrics => Response::Metrics(self.metrics_snapshot()),
+
+            Request::ReloadModel(req) => match self.reload_model(req).await {
+                Ok(reloaded) => Response::ModelReloaded(reloaded),
+                Err(e) => Response::model_error(&e),
+            },
+        }
+    }
+
+    /// Make another model the default, refusing one whose dimension doesn't
+    /// match the collection this daemon serves.
+    async fn reload_model(
+        &self,
+        req: ReloadModelRequest,
+    ) -> Result<ModelReloadedResponse, ModelError> {
+        let previous = self.models.default_spec();
+        let spec = ModelSpec {
+            model_id: req.model_id,
+            dimension: req.dimension.unwrap_or(previous.dimension),
+            model_path: req.model_path,
+        };
+
+        // A missing collection will be created with the configured dimension
+        let check_failed =
+            |e: VectorStoreError| ModelError::LoadError(format!("could not check collection: {e}"));
+        let info = self
+            .vector_store()
+            .await
+            .map_err(check_failed)?
+            .get_collection_info()
+            .await
+            .map_err(check_failed)?;
+        let expected = info
+            .and_then(|info| info.dimension)
+            .unwrap_or(self.config.embedding.dimension as u64);
+        if spec.dimension as u64 != expected {
+            return Err(ModelError::DimensionMismatch {
+                model_id: spec.model_id,
+                dimension: spec.dimension as u64,
+                collection: self.config.vector_store.scoped_collection(),
+                expected,
+            });
+        }
+
+        info!(from = %previous.model_id, to = %spec.model_id, "reloading embedding model");
+        let models = Arc::clone(&self.models);
+        let reloaded = spec.clone();
+        tokio::task::spawn_blocking(move || models.reload(reloaded))
+            .await
+            .unwrap_or_else(|e| {
+                Err(ModelError::LoadError(format!(
+                    "model reload task failed: {e}"
+                )))
+            })?;
+        info!(model = %spec.model_id, dim = spec.dimension, "embedding model reloaded");
+
+        Ok(ModelReloadedResponse {
+            previous_model_id: previous.model_id,
+            model_id: spec.model_id,
+            dimension: spec.dimension,
+        })
+    }
+
+    /// Request counts, model info and recent model latency.
+    fn metrics_snapshot(&self) -> MetricsResponse {
+        let spec = self.models.default_spec();
+        let model = self.models.default_model();
+        let requests = self
+            .request_counts
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(|(&(transport, kind, success), &count)| RequestCount {
+                transport: transport.to_string(),
+                kind: kind.to_string(),
+                success,
+                count,
+            })
+            .collect();
+        MetricsResponse {
+            uptime_secs: self.started_at.elapsed().as_secs(),
+            model: ModelInfo {
+                model_id: spec.model_id,
+                dimension: model.dimension(),
+                device: model.device().to_string(),
+                sessions: model.pool_size(),
+            },
+            requests,
+            embed_latency: self.metrics.as_ref().map(|m| m.latency("embed")),
+            rerank_latency: self.metrics.as_ref().map(|m| m.latency("rerank")),
+        }
+    }
+
+    /// Score documents against a query with the cross-encoder, loading it on first use.
+    async fn rerank(&self, query: &str, documents: &[String]) -> Result<Vec<f32>, ModelError> {
+        let reranker = self.reranker().await?;
+
+        let start = Instant::now();
+        let result = reranker.score(query, documents);
+        let latency_ms = start.elapsed().as_millis() as u64;
+        if let Some(ref metrics) = self.metrics {
+            metrics.record("rerank", documents.len(), latency_ms, result.is_ok());
+        }
+        result
+    }
+
+    /// Cross-encoder, loaded on first use.
+    async fn reranker(&self) -> Result<&RerankerModel, ModelError> {
+        self.reranker
+            .get_or_try_init(|| async {
+                let model_dir = match self.config.search.rerank_model_path.clone() {
+                    Some(path) => path,
+                    None => Config::models_dir()
+                        .ok_or_else(|| {
+                            ModelError::NotFound("could not determine models directory".to_string())
+                        })?
+                        .join(model_dir_name(&self.config.search.rerank_model_id)),
+                };
+                info!(model = %self.config.search.rerank_model_id, "loading reranker model");
+                RerankerModel::load(&model_dir, RERANK_MAX_TOKENS)
+            })
+            .await
+    }
+
+    /// Embed texts on the blocking pool, recording latency in the metrics store.
+    ///
+    /// Low-priority texts are embedded [`LOW_PRIORITY_SLICE`] at a time, each
+    /// slice queueing behind any waiting high-priority request.
+    async fn embed(
+        &self,
+        texts: Vec<String>,
+        is_query: bool,
+        model: Option<ModelSpec>,
+        instruction: Option<String>,
+        priority: Priority,
+    ) -> Result<Vec<Vec<f32>>, ModelError> {
+        self.embed_reporting(texts, is_query, model, instruction, priority, None)
+            .await
+    }
+
+    /// [`Self::embed`], sending the texts embedded so far to `progress` after
+    /// every slice but the last. Requests that report progress are sliced
+    /// whatever their priority.
+    async fn embed_reporting(
+        &self,
+        texts: Vec<String>,
+        is_query: bool,
+        model: Option<ModelSpec>,
+        instruction: Option<String>,
+        priority: Priority,
+        progress: Option<mpsc::UnboundedSender<ProgressResponse>>,
+    ) -> Result<Vec<Vec<f32>>, ModelError> {
+        let start = Instant::now();
+        let batch_size = texts.len();
+        let slice = match priority {
+            Priority::High if progress.is_none() => batch_size.max(1),
+            _ => LOW_PRIORITY_SLICE,
+        };
+
+        let mut embeddings = Vec::with_capacity(batch_size);
+        let mut pending = texts;
+        let result = loop {
+            let rest = pending.split_off(slice.min(pending.len()));
+            let part = std::mem::replace(&mut pending, rest);
+            let permit = self.scheduler.acquire(priority).await;
+            let models = Arc::clone(&self.models);
+            let model = model.clone();
+            let instruction = instruction.clone();
+            let embedded = tokio::task::spawn_blocking(move || {
+                // Held until the session is free, even if the request is dropped
+                let _permit = permit;
+                models
+                    .get(model.as_ref())?
+                    .embed(&part, is_query, instruction.as_deref())
+            })
+            .await
+            .unwrap_or_else(|e| {
+                Err(ModelError::InferenceError(format!(
+                    "embedding task failed: {e}"
+                )))
+            });
+            match embedded {
+                Ok(part) => embeddings.extend(part),
+                Err(e) => break Err(e),
+            }
+            if pending.is_empty() {
+                break Ok(embeddings);
+            }
+            if let Some(ref progress) = progress {
+                let _ = progress.send(ProgressResponse {
+                    done: embeddings.len(),
+                    total: batch_size,
+                });
+            }
+        };
+        let latency_ms = start.elapsed().as_millis() as u64;
+        if let Some(ref metrics) = self.metrics {
+            metrics.record("embed", batch_size, latency_ms, result.is_ok());
+        }
+        result
+    }
+
+    /// Vector store backend, connected on first use.
+    async fn vector_store(&self) -> Result<&Arc<dyn VectorStore>, VectorStoreError> {
+        self.vector_store
+            .get_or_try_init(|| async {
+                create_backend_with_embedding_config(
+                    &self.config.vector_store,
+                    &self.config.embedding,
+                )
+                .await
+                .map(Arc::from)
+            })
+            .await
+    }
+
+    /// Mark activity so the idle timeout is reset.
+    async fn touch(&self) {
+        *self.last_request.write().await = Instant::now();
+    }
+
+    fn count_request(&self, transport: &'static str, kind: &'static str, success: bool) {
+        self.requests_served.fetch_add(1, Ordering::Relaxed);
+        *self
+            .request_counts
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry((transport, kind, success))
+            .or_default() += 1;
+    }
+
+    /// Add a search to the history, if `[metrics] history` is on.
+    fn record_search(&self, search: &SearchRecord) {
+        if let Some(ref metrics) = self.metrics
+            && self.config.metrics.history
+        {
+            metrics.record_search(search);
         }
     }
 
@@ -218,18 +845,22 @@ impl DaemonServer {
     }
 
     fn cleanup(&self) {
-        let _ = std::fs::remove_file(&self.socket_path);
-        let _ = std::fs::remove_file(self.config.pid_path());
-        eprintln!("Daemon stopped");
+        // A replacement daemon may already own the socket and pid file
+        if std::fs::read_to_string(self.config.pid_path())
+            .is_ok_and(|pid| pid.trim() == std::process::id().to_string())
+        {
+            let _ = std::fs::remove_file(self.config.pid_path());
+        }
+        info!("daemon stopped");
     }
 }
 
-fn model_dir_name(model_id: &str) -> String {
+pub(crate) fn model_dir_name(model_id: &str) -> String {
     model_id.replace('/', "--")
 }
 
 pub async fn run_daemon(config: Config) -> Result<(), Box<dyn std::error::Error>> {
-    let server = DaemonServer::new(config).await?;
+    let server = Arc::new(DaemonServer::new(config).await?);
     server.run().await?;
     Ok(())
 }
@@ -0,0 +1,179 @@
+//! Prometheus text exposition of daemon metrics.
+//!
+//! Served on `GET /metrics` of the `[metrics] http_addr` listener. The same
+//! data is available over the socket with `Request::Metrics`.
+
+use std::fmt::Write;
+use std::sync::Arc;
+
+use axum::Router;
+use axum::extract::State;
+use axum::http::header;
+use axum::response::IntoResponse;
+use axum::routing::get;
+use tokio::net::TcpListener;
+
+use crate::server::DaemonServer;
+use crate::server::protocol::MetricsResponse;
+use crate::services::LatencySummary;
+
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Serve `GET /metrics` on an already-bound listener until the process exits.
+pub async fn serve(server: Arc<DaemonServer>, listener: TcpListener) -> std::io::Result<()> {
+    let app = Router::new()
+        .route("/metrics", get(metrics))
+        .with_state(server);
+    axum::serve(listener, app).await
+}
+
+/// Scrapes deliberately don't reset the idle timeout.
+async fn metrics(State(server): State<Arc<DaemonServer>>) -> impl IntoResponse {
+    let snapshot = server.metrics_snapshot();
+    ([(header::CONTENT_TYPE, CONTENT_TYPE)], render(&snapshot))
+}
+
+/// Render a metrics snapshot in the Prometheus text format.
+pub fn render(metrics: &MetricsResponse) -> String {
+    let mut out = String::new();
+
+    header(
+        &mut out,
+        "ssearch_uptime_seconds",
+        "gauge",
+        "Seconds since the daemon started",
+    );
+    let _ = writeln!(out, "ssearch_uptime_seconds {}", metrics.uptime_secs);
+
+    header(
+        &mut out,
+        "ssearch_model_info",
+        "gauge",
+        "Embedding model served by the daemon",
+    );
+    let _ = writeln!(
+        out,
+        "ssearch_model_info{{model=\"{}\",device=\"{}\",dimension=\"{}\",sessions=\"{}\"}} 1",
+        escape(&metrics.model.model_id),
+        escape(&metrics.model.device),
+        metrics.model.dimension,
+        metrics.model.sessions
+    );
+
+    header(
+        &mut out,
+        "ssearch_requests_total",
+        "counter",
+        "Requests handled since startup",
+    );
+    for request in &metrics.requests {
+        let outcome = if request.success { "success" } else { "error" };
+        let _ = writeln!(
+            out,
+            "ssearch_requests_total{{transport=\"{}\",kind=\"{}\",outcome=\"{}\"}} {}",
+            escape(&request.transport),
+            escape(&request.kind),
+            outcome,
+            request.count
+        );
+    }
+
+    if let Some(ref latency) = metrics.embed_latency {
+        summary(
+            &mut out,
+            "ssearch_embed_latency_seconds",
+            "Embedding latency over the last 10 minutes",
+            latency,
+        );
+    }
+    if let Some(ref latency) = metrics.rerank_latency {
+        summary(
+            &mut out,
+            "ssearch_rerank_latency_seconds",
+            "Rerank latency over the last 10 minutes",
+            latency,
+        );
+    }
+
+    out
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+fn summary(out: &mut String, name: &str, help: &str, latency: &LatencySummary) {
+    header(out, name, "summary", help);
+    let seconds = |ms: u64| ms as f64 / 1000.0;
+    let _ = writeln!(
+        out,
+        "{name}{{quantile=\"0.5\"}} {}",
+        seconds(latency.p50_ms)
+    );
+    let _ = writeln!(
+        out,
+        "{name}{{quantile=\"0.95\"}} {}",
+        seconds(latency.p95_ms)
+    );
+    let _ = writeln!(out, "{name}_sum {}", seconds(latency.sum_ms));
+    let _ = writeln!(out, "{name}_count {}", latency.count);
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::protocol::{ModelInfo, RequestCount};
+
+    #[test]
+    fn test_render_prometheus_text() {
+        let metrics = MetricsResponse {
+            uptime_secs: 42,
+            model: ModelInfo {
+                model_id: "org/model".to_string(),
+                dimension: 384,
+                device: "cpu".to_string(),
+                sessions: 2,
+            },
+            requests: vec![RequestCount {
+                transport: "http".to_string(),
+                kind: "search".to_string(),
+                success: false,
+                count: 3,
+            }],
+            embed_latency: Some(LatencySummary {
+                count: 4,
+                sum_ms: 100,
+                p50_ms: 20,
+                p95_ms: 45,
+            }),
+            rerank_latency: None,
+        };
+
+        let text = render(&metrics);
+        assert!(text.contains("ssearch_uptime_seconds 42\n"));
+        assert!(text.contains(
+            "ssearch_model_info{model=\"org/model\",device=\"cpu\",dimension=\"384\",sessions=\"2\"} 1\n"
+        ));
+        assert!(text.contains(
+            "ssearch_requests_total{transport=\"http\",kind=\"search\",outcome=\"error\"} 3\n"
+        ));
+        assert!(text.contains("# TYPE ssearch_embed_latency_seconds summary\n"));
+        assert!(text.contains("ssearch_embed_latency_seconds{quantile=\"0.95\"} 0.045\n"));
+        assert!(text.contains("ssearch_embed_latency_seconds_sum 0.1\n"));
+        assert!(text.contains("ssearch_embed_latency_seconds_count 4\n"));
+        assert!(!text.contains("ssearch_rerank_latency_seconds"));
+    }
+
+    #[test]
+    fn test_escape_label_value() {
+        assert_eq!(escape("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
+    }
+}
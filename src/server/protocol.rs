@@ -1,6 +1,21 @@
+use std::fmt;
+use std::path::PathBuf;
+use std::time::Duration;
+
 use serde::{Deserialize, Serialize};
 
-use crate::services::MetricsSummary;
+use crate::error::{DaemonError, ModelError};
+use crate::models::EmbeddingConfig;
+use crate::services::{LatencySummary, MetricsSummary};
+
+/// Version of the socket protocol, bumped on incompatible message changes.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Largest frame the daemon accepts; bigger embed batches are streamed.
+pub const MAX_MESSAGE_BYTES: usize = 10 * 1024 * 1024;
+
+/// Payload budget for one `AppendTexts` frame, leaving room for JSON overhead.
+pub const BATCH_FRAME_BYTES: usize = MAX_MESSAGE_BYTES / 2;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -9,12 +24,208 @@ pub enum Request {
     Shutdown,
     Status,
     Embed(EmbedRequest),
+    /// Start a streamed embed batch on this connection.
+    BeginBatch(BeginBatchRequest),
+    /// Add texts to the batch opened by `BeginBatch`.
+    AppendTexts(AppendTextsRequest),
+    /// Embed everything appended since `BeginBatch`.
+    FinishBatch,
+    Rerank(RerankRequest),
+    /// Counters and latency quantiles for monitoring.
+    Metrics,
+    /// Swap the default embedding model without restarting the daemon.
+    ReloadModel(ReloadModelRequest),
+    /// Exchange protocol versions before other requests.
+    Hello(HelloRequest),
+}
+
+impl Request {
+    /// Short name used in logs and request spans.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Request::Ping => "ping",
+            Request::Shutdown => "shutdown",
+            Request::Status => "status",
+            Request::Embed(_) => "embed",
+            Request::BeginBatch(_) => "begin_batch",
+            Request::AppendTexts(_) => "append_texts",
+            Request::FinishBatch => "finish_batch",
+            Request::Rerank(_) => "rerank",
+            Request::Metrics => "metrics",
+            Request::ReloadModel(_) => "reload_model",
+            Request::Hello(_) => "hello",
+        }
+    }
+
+    /// Texts the request puts through a model, counted against `[daemon] rate_limit`.
+    ///
+    /// Streamed batches are charged when `FinishBatch` embeds them.
+    pub fn cost(&self) -> usize {
+        match self {
+            Request::Embed(req) => req.texts.len().max(1),
+            Request::Rerank(req) => req.documents.len().max(1),
+            _ => 0,
+        }
+    }
+}
+
+/// A request together with the credentials it was sent with.
+///
+/// The token sits next to the request's own fields, so plain requests from
+/// older clients still decode (without a token).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequestEnvelope {
+    /// `[daemon] auth_token`; required when the daemon has one configured
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    #[serde(flatten)]
+    pub request: Request,
+}
+
+impl RequestEnvelope {
+    pub fn new(request: Request, token: Option<String>) -> Self {
+        Self { token, request }
+    }
+
+    /// Whether a daemon expecting `expected` may serve this request.
+    pub fn is_authorized(&self, expected: Option<&str>) -> bool {
+        match expected {
+            None => true,
+            Some(expected) => self
+                .token
+                .as_deref()
+                .is_some_and(|token| constant_time_eq(token.as_bytes(), expected.as_bytes())),
+        }
+    }
+}
+
+/// Compare two secrets without stopping at the first differing byte.
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Scheduling lane of an embed request in the daemon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    /// Interactive searches, served before any waiting low-priority batch
+    High,
+    /// Bulk indexing, embedded in slices between high-priority requests
+    Low,
+}
+
+impl Priority {
+    /// High for search queries, low for documents being indexed.
+    pub fn for_query(is_query: bool) -> Self {
+        if is_query {
+            Priority::High
+        } else {
+            Priority::Low
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EmbedRequest {
     pub texts: Vec<String>,
     pub is_query: bool,
+    /// Model to embed with; the daemon's configured model when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<ModelSpec>,
+    /// Prefix for every text; the model's query or document instruction when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instruction: Option<String>,
+    /// Scheduling lane; follows `is_query` when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<Priority>,
+    /// Send `Progress` frames ahead of the response as slices are embedded
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub progress: bool,
+}
+
+impl EmbedRequest {
+    pub fn priority(&self) -> Priority {
+        self.priority
+            .unwrap_or_else(|| Priority::for_query(self.is_query))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BeginBatchRequest {
+    pub is_query: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<ModelSpec>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instruction: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<Priority>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub progress: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppendTextsRequest {
+    pub texts: Vec<String>,
+}
+
+/// Identifies an embedding model and the dimension it is truncated to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ModelSpec {
+    pub model_id: String,
+    pub dimension: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_path: Option<PathBuf>,
+}
+
+impl ModelSpec {
+    pub fn from_config(config: &EmbeddingConfig) -> Self {
+        Self {
+            model_id: config.model_id.clone(),
+            dimension: config.dimension,
+            model_path: config.model_path.clone(),
+        }
+    }
+}
+
+impl fmt::Display for ModelSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (dim {})", self.model_id, self.dimension)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReloadModelRequest {
+    pub model_id: String,
+    /// Embedding dimension; the current default model's when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dimension: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_path: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HelloRequest {
+    pub protocol_version: u32,
+    /// ssearch version of the client, for the daemon's logs
+    pub client_version: String,
+}
+
+impl HelloRequest {
+    pub fn current() -> Self {
+        Self {
+            protocol_version: PROTOCOL_VERSION,
+            client_version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RerankRequest {
+    pub query: String,
+    pub documents: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,6 +235,17 @@ pub enum Response {
     ShutdownAck,
     Status(StatusResponse),
     Embed(EmbedResponse),
+    /// Acknowledges `BeginBatch`/`AppendTexts` with the number of texts buffered so far.
+    BatchAck(BatchAckResponse),
+    Rerank(RerankResponse),
+    Metrics(MetricsResponse),
+    ModelReloaded(ModelReloadedResponse),
+    Hello(HelloResponse),
+    /// Over the daemon's rate limit; send the request again after `retry_after_ms`.
+    Busy(BusyResponse),
+    /// Texts embedded so far by a request that asked for progress; the
+    /// request's own response follows the last one.
+    Progress(ProgressResponse),
     Error(ErrorResponse),
 }
 
@@ -31,9 +253,64 @@ pub enum Response {
 pub struct StatusResponse {
     pub running: bool,
     pub embedding_model: String,
+    /// Other models loaded for collections that bind their own, as `model_id (dim N)`
+    #[serde(default)]
+    pub loaded_models: Vec<String>,
     pub idle_secs: u64,
     pub requests_served: u64,
     pub metrics: Option<MetricsSummary>,
+    /// Models warmed up (and preloaded with `serve --preload`); daemons without warm-up are always ready
+    #[serde(default = "default_ready")]
+    pub ready: bool,
+}
+
+fn default_ready() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricsResponse {
+    pub uptime_secs: u64,
+    pub model: ModelInfo,
+    /// Requests handled since startup, per transport, kind and outcome
+    pub requests: Vec<RequestCount>,
+    /// Embedding latency over the recent window; absent when metrics are disabled
+    pub embed_latency: Option<LatencySummary>,
+    pub rerank_latency: Option<LatencySummary>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelInfo {
+    pub model_id: String,
+    pub dimension: usize,
+    pub device: String,
+    pub sessions: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelReloadedResponse {
+    pub previous_model_id: String,
+    pub model_id: String,
+    pub dimension: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HelloResponse {
+    pub protocol_version: u32,
+    /// ssearch version of the running daemon
+    pub daemon_version: String,
+    pub pid: u32,
+    /// Started by systemd or launchd, which keep the socket open across restarts
+    #[serde(default)]
+    pub socket_activated: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RequestCount {
+    pub transport: String,
+    pub kind: String,
+    pub success: bool,
+    pub count: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -41,17 +318,158 @@ pub struct EmbedResponse {
     pub embeddings: Vec<Vec<f32>>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchAckResponse {
+    pub buffered: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProgressResponse {
+    pub done: usize,
+    pub total: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RerankResponse {
+    pub scores: Vec<f32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BusyResponse {
+    pub retry_after_ms: u64,
+}
+
+impl BusyResponse {
+    pub fn new(retry_after: Duration) -> Self {
+        Self {
+            retry_after_ms: retry_after.as_millis().max(1) as u64,
+        }
+    }
+
+    pub fn retry_after(&self) -> Duration {
+        Duration::from_millis(self.retry_after_ms)
+    }
+}
+
+impl From<BusyResponse> for DaemonError {
+    fn from(busy: BusyResponse) -> Self {
+        DaemonError::Remote {
+            code: ErrorCode::Overloaded,
+            message: format!(
+                "daemon is busy (rate limited); retry after {}ms",
+                busy.retry_after_ms
+            ),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ErrorResponse {
+    /// Kind of failure; `internal` from daemons that predate error codes
+    #[serde(default)]
+    pub code: ErrorCode,
     pub message: String,
 }
 
+/// Machine-readable kind of an `ErrorResponse`, so clients needn't match on messages.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// Malformed request, or one that isn't valid in the connection's state
+    InvalidRequest,
+    /// Missing or wrong `[daemon] auth_token`
+    Unauthorized,
+    /// Frame larger than `MAX_MESSAGE_BYTES`
+    MessageTooLarge,
+    /// The requested embedding or reranker model is missing or failed to load
+    ModelNotLoaded,
+    /// The model's dimension doesn't match the collection's
+    DimensionMismatch,
+    /// Tokenization or inference failed
+    InferenceFailed,
+    /// The daemon is too busy to serve the request right now
+    Overloaded,
+    /// Client and daemon speak different protocol versions
+    VersionMismatch,
+    /// Anything else, including codes this client doesn't know
+    #[default]
+    #[serde(other)]
+    Internal,
+}
+
+impl ErrorCode {
+    /// Whether the same request may succeed if sent again later.
+    pub fn is_retryable(self) -> bool {
+        matches!(self, ErrorCode::Overloaded)
+    }
+}
+
+impl From<&ModelError> for ErrorCode {
+    fn from(error: &ModelError) -> Self {
+        match error {
+            ModelError::NotFound(_) | ModelError::LoadError(_) | ModelError::DownloadError(_) => {
+                ErrorCode::ModelNotLoaded
+            }
+            ModelError::TokenizerError(_) | ModelError::InferenceError(_) => {
+                ErrorCode::InferenceFailed
+            }
+            ModelError::DimensionMismatch { .. } => ErrorCode::DimensionMismatch,
+        }
+    }
+}
+
+impl From<ErrorResponse> for DaemonError {
+    fn from(error: ErrorResponse) -> Self {
+        DaemonError::Remote {
+            code: error.code,
+            message: error.message,
+        }
+    }
+}
+
 impl Response {
-    pub fn error(message: impl Into<String>) -> Self {
+    /// Answer to a `Hello`, refusing clients of another protocol version.
+    pub fn hello(request: &HelloRequest, socket_activated: bool) -> Self {
+        if request.protocol_version != PROTOCOL_VERSION {
+            return Self::error(
+                ErrorCode::VersionMismatch,
+                format!(
+                    "daemon speaks protocol v{} (ssearch {}) but the client speaks v{} (ssearch {}); restart it with `ssearch serve restart`",
+                    PROTOCOL_VERSION,
+                    env!("CARGO_PKG_VERSION"),
+                    request.protocol_version,
+                    request.client_version
+                ),
+            );
+        }
+        Response::Hello(HelloResponse {
+            protocol_version: PROTOCOL_VERSION,
+            daemon_version: env!("CARGO_PKG_VERSION").to_string(),
+            pid: std::process::id(),
+            socket_activated,
+        })
+    }
+
+    pub fn busy(retry_after: Duration) -> Self {
+        Response::Busy(BusyResponse::new(retry_after))
+    }
+
+    /// Whether the request was not served.
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Response::Error(_) | Response::Busy(_))
+    }
+
+    pub fn error(code: ErrorCode, message: impl Into<String>) -> Self {
         Response::Error(ErrorResponse {
+            code,
             message: message.into(),
         })
     }
+
+    /// Error response for a failed model operation.
+    pub fn model_error(error: &ModelError) -> Self {
+        Self::error(error.into(), error.to_string())
+    }
 }
 
 pub fn encode_message(msg: &impl Serialize) -> Result<Vec<u8>, serde_json::Error> {
@@ -66,3 +484,257 @@ pub fn encode_message(msg: &impl Serialize) -> Result<Vec<u8>, serde_json::Error
 pub fn decode_length(buf: &[u8; 4]) -> usize {
     u32::from_be_bytes(*buf) as usize
 }
+
+/// Split texts into `AppendTexts` frames of at most `max_bytes` of text each.
+///
+/// A text larger than `max_bytes` gets a frame of its own.
+pub fn split_frames(texts: Vec<String>, max_bytes: usize) -> Vec<Vec<String>> {
+    let mut frames = Vec::new();
+    let mut current = Vec::new();
+    let mut current_bytes = 0;
+
+    for text in texts {
+        if !current.is_empty() && current_bytes + text.len() > max_bytes {
+            frames.push(std::mem::take(&mut current));
+            current_bytes = 0;
+        }
+        current_bytes += text.len();
+        current.push(text);
+    }
+    if !current.is_empty() {
+        frames.push(current);
+    }
+    frames
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::Retryable;
+
+    #[test]
+    fn test_split_frames() {
+        let texts: Vec<String> = ["aaaa", "bbbb", "cc", "dddddddddd", "e"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let frames = split_frames(texts, 8);
+        assert_eq!(
+            frames,
+            vec![
+                vec!["aaaa".to_string(), "bbbb".to_string()],
+                vec!["cc".to_string()],
+                vec!["dddddddddd".to_string()],
+                vec!["e".to_string()],
+            ]
+        );
+        assert!(split_frames(Vec::new(), 8).is_empty());
+    }
+
+    #[test]
+    fn test_batch_request_roundtrip() {
+        let encoded = serde_json::to_string(&Request::AppendTexts(AppendTextsRequest {
+            texts: vec!["hello".to_string()],
+        }))
+        .unwrap();
+        assert_eq!(encoded, r#"{"type":"append_texts","texts":["hello"]}"#);
+
+        let decoded: Request = serde_json::from_str(r#"{"type":"finish_batch"}"#).unwrap();
+        assert!(matches!(decoded, Request::FinishBatch));
+    }
+
+    #[test]
+    fn test_reload_model_request() {
+        let decoded: Request =
+            serde_json::from_str(r#"{"type":"reload_model","model_id":"org/model"}"#).unwrap();
+        assert_eq!(decoded.kind(), "reload_model");
+        let Request::ReloadModel(req) = decoded else {
+            panic!("expected reload_model");
+        };
+        assert_eq!(req.model_id, "org/model");
+        assert_eq!(req.dimension, None);
+        assert_eq!(req.model_path, None);
+    }
+
+    #[test]
+    fn test_embed_request_instruction() {
+        let decoded: Request =
+            serde_json::from_str(r#"{"type":"embed","texts":["hi"],"is_query":true}"#).unwrap();
+        let Request::Embed(req) = decoded else {
+            panic!("expected embed");
+        };
+        assert_eq!(req.instruction, None);
+        assert_eq!(req.priority(), Priority::High);
+        let bulk = EmbedRequest {
+            is_query: false,
+            ..req.clone()
+        };
+        assert_eq!(bulk.priority(), Priority::Low);
+        let urgent = EmbedRequest {
+            priority: Some(Priority::High),
+            ..bulk
+        };
+        assert_eq!(urgent.priority(), Priority::High);
+
+        // An empty instruction disables the model's default and must survive encoding
+        let encoded = serde_json::to_string(&Request::Embed(EmbedRequest {
+            instruction: Some(String::new()),
+            ..req
+        }))
+        .unwrap();
+        assert!(encoded.contains(r#""instruction":"""#));
+    }
+
+    #[test]
+    fn test_error_codes() {
+        let encoded = serde_json::to_string(&Response::model_error(&ModelError::NotFound(
+            "org/model".to_string(),
+        )))
+        .unwrap();
+        assert_eq!(
+            encoded,
+            r#"{"type":"error","code":"model_not_loaded","message":"model not found: org/model"}"#
+        );
+
+        // Older daemons send no code; newer ones may send codes we don't know
+        let decoded: Response =
+            serde_json::from_str(r#"{"type":"error","message":"boom"}"#).unwrap();
+        let Response::Error(err) = decoded else {
+            panic!("expected error");
+        };
+        assert_eq!(err.code, ErrorCode::Internal);
+        let decoded: Response =
+            serde_json::from_str(r#"{"type":"error","code":"not_yet_invented","message":"x"}"#)
+                .unwrap();
+        assert!(matches!(
+            decoded,
+            Response::Error(ErrorResponse {
+                code: ErrorCode::Internal,
+                ..
+            })
+        ));
+
+        assert!(ErrorCode::Overloaded.is_retryable());
+        assert!(!ErrorCode::InvalidRequest.is_retryable());
+    }
+
+    #[test]
+    fn test_status_loaded_models() {
+        // Daemons from before collection-bound models don't send the list
+        let status: StatusResponse = serde_json::from_str(
+            r#"{"running":true,"embedding_model":"org/base","idle_secs":3,"requests_served":7,"metrics":null}"#,
+        )
+        .unwrap();
+        assert!(status.loaded_models.is_empty());
+        assert!(status.ready);
+
+        let spec = ModelSpec {
+            model_id: "org/code".to_string(),
+            dimension: 768,
+            model_path: None,
+        };
+        assert_eq!(spec.to_string(), "org/code (dim 768)");
+        let Request::Embed(req) = serde_json::from_str(
+            r#"{"type":"embed","texts":["fn main"],"is_query":false,"model":{"model_id":"org/code","dimension":768}}"#,
+        )
+        .unwrap()
+        else {
+            panic!("expected embed");
+        };
+        assert_eq!(req.model, Some(spec));
+    }
+
+    #[test]
+    fn test_busy_response() {
+        let encoded = serde_json::to_string(&Response::busy(Duration::from_millis(250))).unwrap();
+        assert_eq!(encoded, r#"{"type":"busy","retry_after_ms":250}"#);
+        assert!(Response::busy(Duration::ZERO).is_failure());
+
+        let embed = Request::Embed(EmbedRequest {
+            texts: vec!["a".to_string(), "b".to_string()],
+            is_query: false,
+            model: None,
+            instruction: None,
+            priority: None,
+            progress: false,
+        });
+        assert_eq!(embed.cost(), 2);
+        assert_eq!(Request::Status.cost(), 0);
+
+        let error = DaemonError::from(BusyResponse::new(Duration::from_millis(5)));
+        assert!(error.is_retryable());
+    }
+
+    #[test]
+    fn test_progress_frames() {
+        // Older clients never ask for progress, so they only ever see the response
+        let Request::Embed(req) =
+            serde_json::from_str(r#"{"type":"embed","texts":["hi"],"is_query":false}"#).unwrap()
+        else {
+            panic!("expected embed");
+        };
+        assert!(!req.progress);
+        let encoded = serde_json::to_string(&Request::Embed(req.clone())).unwrap();
+        assert!(!encoded.contains("progress"));
+        let encoded = serde_json::to_string(&Request::Embed(EmbedRequest {
+            progress: true,
+            ..req
+        }))
+        .unwrap();
+        assert!(encoded.ends_with(r#""progress":true}"#));
+
+        let encoded = serde_json::to_string(&Response::Progress(ProgressResponse {
+            done: 16,
+            total: 40,
+        }))
+        .unwrap();
+        assert_eq!(encoded, r#"{"type":"progress","done":16,"total":40}"#);
+        assert!(!Response::Progress(ProgressResponse { done: 0, total: 1 }).is_failure());
+    }
+
+    #[test]
+    fn test_hello_handshake() {
+        let Response::Hello(hello) = Response::hello(&HelloRequest::current(), false) else {
+            panic!("expected hello");
+        };
+        assert_eq!(hello.protocol_version, PROTOCOL_VERSION);
+        assert_eq!(hello.pid, std::process::id());
+
+        let stale = HelloRequest {
+            protocol_version: PROTOCOL_VERSION + 1,
+            client_version: "9.9.9".to_string(),
+        };
+        let Response::Error(err) = Response::hello(&stale, false) else {
+            panic!("expected error");
+        };
+        assert_eq!(err.code, ErrorCode::VersionMismatch);
+        assert!(err.message.contains("ssearch serve restart"));
+
+        let encoded = serde_json::to_string(&Request::Hello(HelloRequest::current())).unwrap();
+        assert!(encoded.starts_with(r#"{"type":"hello","protocol_version":1"#));
+    }
+
+    #[test]
+    fn test_request_envelope() {
+        let encoded = serde_json::to_string(&RequestEnvelope::new(
+            Request::Ping,
+            Some("secret".to_string()),
+        ))
+        .unwrap();
+        assert_eq!(encoded, r#"{"token":"secret","type":"ping"}"#);
+
+        let decoded: RequestEnvelope = serde_json::from_str(&encoded).unwrap();
+        assert!(matches!(decoded.request, Request::Ping));
+        assert!(decoded.is_authorized(Some("secret")));
+        assert!(!decoded.is_authorized(Some("secre")));
+        assert!(!decoded.is_authorized(Some("Secret")));
+
+        // Requests without a token only pass when the daemon has none configured
+        let plain: RequestEnvelope =
+            serde_json::from_str(r#"{"type":"embed","texts":["hi"],"is_query":false}"#).unwrap();
+        assert_eq!(plain.token, None);
+        assert_eq!(plain.request.kind(), "embed");
+        assert!(plain.is_authorized(None));
+        assert!(!plain.is_authorized(Some("secret")));
+    }
+}
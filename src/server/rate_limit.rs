@@ -0,0 +1,190 @@
+//! Token-bucket limits on the texts the daemon embeds and reranks.
+//!
+//! Each client (a socket peer process or an HTTP address) has its own bucket,
+//! so a bulk indexing job runs out of budget before interactive searches from
+//! other processes do; a global bucket caps the daemon's total load. Requests
+//! over budget get `Response::Busy` and are retried by the client.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::models::DaemonConfig;
+
+/// Client buckets kept before idle ones are dropped.
+const MAX_CLIENTS: usize = 1024;
+
+/// Shortest delay suggested to a rejected client.
+const MIN_RETRY_AFTER: Duration = Duration::from_millis(10);
+
+/// Refills at `rate` tokens per second up to `capacity`.
+#[derive(Debug, Clone)]
+struct TokenBucket {
+    rate: f64,
+    capacity: f64,
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: f64, capacity: f64, now: Instant) -> Self {
+        Self {
+            rate,
+            capacity,
+            tokens: capacity,
+            updated: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        self.updated = now;
+    }
+
+    /// Take `cost` tokens, or return how long until they are available.
+    ///
+    /// A request larger than the bucket passes once it is full and leaves it
+    /// in debt, so oversized batches are slowed down rather than refused forever.
+    fn try_take(&mut self, cost: f64, now: Instant) -> Result<(), Duration> {
+        self.refill(now);
+        let needed = cost.min(self.capacity);
+        if self.tokens >= needed {
+            self.tokens -= cost;
+            return Ok(());
+        }
+        let wait = Duration::from_secs_f64((needed - self.tokens) / self.rate);
+        Err(wait.max(MIN_RETRY_AFTER))
+    }
+
+    fn give_back(&mut self, cost: f64) {
+        self.tokens = (self.tokens + cost).min(self.capacity);
+    }
+
+    fn is_full(&mut self, now: Instant) -> bool {
+        self.refill(now);
+        self.tokens >= self.capacity
+    }
+}
+
+/// `[daemon] rate_limit` and `client_rate_limit`.
+pub struct RateLimiter {
+    global: Option<Mutex<TokenBucket>>,
+    /// Rate and capacity of each client's bucket
+    per_client: Option<(f64, f64)>,
+    clients: Mutex<HashMap<String, TokenBucket>>,
+}
+
+impl RateLimiter {
+    pub fn from_config(config: &DaemonConfig) -> Self {
+        let now = Instant::now();
+        let capacity = |rate: f64| config.rate_limit_burst.unwrap_or(rate).max(1.0);
+        Self {
+            global: config
+                .rate_limit
+                .map(|rate| Mutex::new(TokenBucket::new(rate, capacity(rate), now))),
+            per_client: config.client_rate_limit.map(|rate| (rate, capacity(rate))),
+            clients: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.global.is_some() || self.per_client.is_some()
+    }
+
+    /// Admit a request costing `cost` texts from `client`, or return how long
+    /// it should wait before trying again.
+    pub fn check(&self, client: &str, cost: usize) -> Result<(), Duration> {
+        if cost == 0 || !self.is_enabled() {
+            return Ok(());
+        }
+        let now = Instant::now();
+        let cost = cost as f64;
+
+        let mut clients = self.clients.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((rate, capacity)) = self.per_client {
+            if clients.len() >= MAX_CLIENTS && !clients.contains_key(client) {
+                clients.retain(|_, bucket| !bucket.is_full(now));
+            }
+            clients
+                .entry(client.to_string())
+                .or_insert_with(|| TokenBucket::new(rate, capacity, now))
+                .try_take(cost, now)?;
+        }
+        if let Some(ref global) = self.global {
+            let taken = global
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .try_take(cost, now);
+            if let Err(wait) = taken {
+                // Not served, so it doesn't count against the client
+                if let Some(bucket) = clients.get_mut(client) {
+                    bucket.give_back(cost);
+                }
+                return Err(wait);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(rate_limit: Option<f64>, client_rate_limit: Option<f64>) -> RateLimiter {
+        RateLimiter::from_config(&DaemonConfig {
+            rate_limit,
+            client_rate_limit,
+            rate_limit_burst: Some(10.0),
+            ..DaemonConfig::default()
+        })
+    }
+
+    #[test]
+    fn test_token_bucket() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(10.0, 10.0, start);
+        assert!(bucket.try_take(6.0, start).is_ok());
+        let wait = bucket.try_take(6.0, start).unwrap_err();
+        assert_eq!(wait, Duration::from_millis(200));
+        assert!(bucket.try_take(6.0, start + wait).is_ok());
+
+        // Larger than the bucket: waits for a full bucket, then goes into debt
+        let later = start + Duration::from_secs(5);
+        assert!(bucket.try_take(25.0, later).is_ok());
+        assert_eq!(
+            bucket.try_take(1.0, later).unwrap_err(),
+            Duration::from_millis(1600)
+        );
+    }
+
+    #[test]
+    fn test_disabled_limiter() {
+        let limiter = limiter(None, None);
+        assert!(!limiter.is_enabled());
+        for _ in 0..100 {
+            assert!(limiter.check("pid:1", 1000).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_client_limit_spares_other_clients() {
+        let limiter = limiter(None, Some(1.0));
+        assert!(limiter.check("pid:1", 10).is_ok());
+        assert!(limiter.check("pid:1", 1).is_err());
+        assert!(limiter.check("pid:2", 1).is_ok());
+        // Control requests cost nothing
+        assert!(limiter.check("pid:1", 0).is_ok());
+    }
+
+    #[test]
+    fn test_global_limit_refunds_client() {
+        let limiter = limiter(Some(1.0), Some(1.0));
+        assert!(limiter.check("pid:1", 8).is_ok());
+        assert!(limiter.check("pid:2", 5).is_err());
+        // pid:2 was refused by the global bucket, so its own budget is intact
+        let clients = limiter.clients.lock().unwrap();
+        assert_eq!(clients["pid:2"].tokens, 10.0);
+    }
+}
@@ -0,0 +1,158 @@
+use std::path::Path;
+use std::sync::Mutex;
+
+use ort::session::{Session, builder::GraphOptimizationLevel};
+use ort::value::Tensor;
+use tokenizers::{
+    EncodeInput, PaddingParams, PaddingStrategy, Tokenizer, TruncationParams, TruncationStrategy,
+};
+
+use crate::error::ModelError;
+
+/// Maximum tokens per (query, passage) pair; cross-encoders are typically trained on 512
+pub const RERANK_MAX_TOKENS: usize = 512;
+
+/// Cross-encoder model that scores (query, passage) pairs jointly.
+pub struct RerankerModel {
+    session: Mutex<Session>,
+    tokenizer: Tokenizer,
+    uses_token_type_ids: bool,
+}
+
+impl RerankerModel {
+    pub fn load(model_dir: &Path, max_tokens: usize) -> Result<Self, ModelError> {
+        let model_path = model_dir.join("model.onnx");
+        let tokenizer_path = model_dir.join("tokenizer.json");
+
+        if !model_path.exists() {
+            return Err(ModelError::NotFound(format!(
+                "reranker model not found: {}",
+                model_path.display()
+            )));
+        }
+
+        let session = Session::builder()
+            .map_err(|e: ort::Error| ModelError::LoadError(e.to_string()))?
+            .with_optimization_level(GraphOptimizationLevel::Level3)
+            .map_err(|e: ort::Error| ModelError::LoadError(e.to_string()))?
+            .commit_from_file(&model_path)
+            .map_err(|e: ort::Error| ModelError::LoadError(e.to_string()))?;
+
+        // BERT-style cross-encoders take segment ids; XLM-R based ones do not
+        let uses_token_type_ids = session
+            .inputs()
+            .iter()
+            .any(|input| input.name() == "token_type_ids");
+
+        let mut tokenizer = Tokenizer::from_file(&tokenizer_path)
+            .map_err(|e| ModelError::TokenizerError(e.to_string()))?;
+
+        tokenizer
+            .with_truncation(Some(TruncationParams {
+                max_length: max_tokens,
+                strategy: TruncationStrategy::OnlySecond,
+                ..Default::default()
+            }))
+            .map_err(|e| ModelError::TokenizerError(e.to_string()))?;
+
+        tokenizer.with_padding(Some(PaddingParams {
+            strategy: PaddingStrategy::BatchLongest,
+            ..Default::default()
+        }));
+
+        Ok(Self {
+            session: Mutex::new(session),
+            tokenizer,
+            uses_token_type_ids,
+        })
+    }
+
+    /// Score each document against the query. Scores are in 0.0-1.0 (sigmoid of logits).
+    pub fn score(&self, query: &str, documents: &[String]) -> Result<Vec<f32>, ModelError> {
+        if documents.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let pairs: Vec<EncodeInput> = documents
+            .iter()
+            .map(|doc| (query.to_string(), doc.clone()).into())
+            .collect();
+
+        let encodings = self
+            .tokenizer
+            .encode_batch(pairs, true)
+            .map_err(|e| ModelError::TokenizerError(e.to_string()))?;
+
+        let max_len = encodings
+            .iter()
+            .map(|e| e.get_ids().len())
+            .max()
+            .unwrap_or(0);
+        let batch_size = encodings.len();
+
+        let mut input_ids = vec![0i64; batch_size * max_len];
+        let mut attention_mask = vec![0i64; batch_size * max_len];
+        let mut token_type_ids = vec![0i64; batch_size * max_len];
+
+        for (i, encoding) in encodings.iter().enumerate() {
+            let ids = encoding.get_ids();
+            let mask = encoding.get_attention_mask();
+            let type_ids = encoding.get_type_ids();
+            for j in 0..ids.len() {
+                input_ids[i * max_len + j] = ids[j] as i64;
+                attention_mask[i * max_len + j] = mask[j] as i64;
+                token_type_ids[i * max_len + j] = type_ids[j] as i64;
+            }
+        }
+
+        let input_ids_tensor = Tensor::from_array(([batch_size, max_len], input_ids))
+            .map_err(|e: ort::Error| ModelError::InferenceError(e.to_string()))?;
+        let attention_mask_tensor = Tensor::from_array(([batch_size, max_len], attention_mask))
+            .map_err(|e: ort::Error| ModelError::InferenceError(e.to_string()))?;
+
+        let mut session = self
+            .session
+            .lock()
+            .map_err(|_| ModelError::InferenceError("session lock poisoned".to_string()))?;
+
+        let outputs = if self.uses_token_type_ids {
+            let token_type_ids_tensor = Tensor::from_array(([batch_size, max_len], token_type_ids))
+                .map_err(|e: ort::Error| ModelError::InferenceError(e.to_string()))?;
+            session.run(ort::inputs![
+                "input_ids" => input_ids_tensor,
+                "attention_mask" => attention_mask_tensor,
+                "token_type_ids" => token_type_ids_tensor
+            ])
+        } else {
+            session.run(ort::inputs![
+                "input_ids" => input_ids_tensor,
+                "attention_mask" => attention_mask_tensor
+            ])
+        }
+        .map_err(|e: ort::Error| ModelError::InferenceError(e.to_string()))?;
+
+        let logits = outputs[0]
+            .try_extract_array::<f32>()
+            .map_err(|e: ort::Error| ModelError::InferenceError(e.to_string()))?;
+
+        // Logits are [batch, 1] (or [batch]); take the first value per row
+        let per_row = logits.len() / batch_size.max(1);
+        if per_row == 0 {
+            return Err(ModelError::InferenceError(format!(
+                "unexpected output shape: {:?}",
+                logits.shape()
+            )));
+        }
+
+        Ok(logits
+            .iter()
+            .step_by(per_row)
+            .take(batch_size)
+            .map(|&logit| sigmoid(logit))
+            .collect())
+    }
+}
+
+fn sigmoid(x: f32) -> f32 {
+    1.0 / (1.0 + (-x).exp())
+}
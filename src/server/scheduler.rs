@@ -0,0 +1,148 @@
+//! Two-lane queue in front of the embedding sessions.
+//!
+//! Search queries (high priority) are handed a free session before any
+//! waiting indexing batch (low priority), and low-priority batches are
+//! embedded in slices so a query never waits for a whole batch to finish.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::oneshot;
+
+use crate::server::protocol::Priority;
+
+/// Texts a low-priority request embeds per session turn.
+pub const LOW_PRIORITY_SLICE: usize = 16;
+
+#[derive(Default)]
+struct Lanes {
+    /// Sessions no request holds or has been handed
+    idle: usize,
+    high: VecDeque<oneshot::Sender<SessionPermit>>,
+    low: VecDeque<oneshot::Sender<SessionPermit>>,
+}
+
+/// Hands out up to `sessions` turns at the model, high-priority waiters first.
+pub struct EmbedScheduler {
+    lanes: Arc<Mutex<Lanes>>,
+}
+
+/// A turn at the model; passed to the next waiter when dropped.
+pub struct SessionPermit {
+    lanes: Arc<Mutex<Lanes>>,
+}
+
+impl EmbedScheduler {
+    pub fn new(sessions: usize) -> Self {
+        Self {
+            lanes: Arc::new(Mutex::new(Lanes {
+                idle: sessions.max(1),
+                ..Lanes::default()
+            })),
+        }
+    }
+
+    /// Wait for a session turn in the lane for `priority`.
+    pub async fn acquire(&self, priority: Priority) -> SessionPermit {
+        let rx = {
+            let mut lanes = self.lanes.lock().unwrap_or_else(|e| e.into_inner());
+            if lanes.idle > 0 {
+                lanes.idle -= 1;
+                return SessionPermit {
+                    lanes: Arc::clone(&self.lanes),
+                };
+            }
+            let (tx, rx) = oneshot::channel();
+            match priority {
+                Priority::High => lanes.high.push_back(tx),
+                Priority::Low => lanes.low.push_back(tx),
+            }
+            rx
+        };
+        // The sender is only dropped after handing over a permit or with the scheduler
+        match rx.await {
+            Ok(permit) => permit,
+            Err(_) => SessionPermit {
+                lanes: Arc::clone(&self.lanes),
+            },
+        }
+    }
+
+    /// Requests waiting for a session, as (high, low).
+    pub fn queued(&self) -> (usize, usize) {
+        let lanes = self.lanes.lock().unwrap_or_else(|e| e.into_inner());
+        (lanes.high.len(), lanes.low.len())
+    }
+}
+
+impl Drop for SessionPermit {
+    fn drop(&mut self) {
+        let mut lanes = self.lanes.lock().unwrap_or_else(|e| e.into_inner());
+        while let Some(waiter) = lanes.high.pop_front().or_else(|| lanes.low.pop_front()) {
+            // A waiter that gave up returns the permit; try the next one.
+            // Its lanes are ours, so forgetting it keeps this drop from recursing.
+            match waiter.send(SessionPermit {
+                lanes: Arc::clone(&self.lanes),
+            }) {
+                Ok(()) => return,
+                Err(permit) => std::mem::forget(permit),
+            }
+        }
+        lanes.idle += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_high_priority_jumps_queue() {
+        let scheduler = Arc::new(EmbedScheduler::new(1));
+        let held = scheduler.acquire(Priority::Low).await;
+
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let waiter = |priority: Priority, name: &'static str| {
+            let scheduler = Arc::clone(&scheduler);
+            let order = Arc::clone(&order);
+            tokio::spawn(async move {
+                let _permit = scheduler.acquire(priority).await;
+                order.lock().unwrap().push(name);
+            })
+        };
+        let low = waiter(Priority::Low, "low");
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        let high = waiter(Priority::High, "high");
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert_eq!(scheduler.queued(), (1, 1));
+
+        drop(held);
+        high.await.unwrap();
+        low.await.unwrap();
+        assert_eq!(*order.lock().unwrap(), vec!["high", "low"]);
+        assert_eq!(scheduler.queued(), (0, 0));
+    }
+
+    #[tokio::test]
+    async fn test_abandoned_waiter_releases_turn() {
+        let scheduler = Arc::new(EmbedScheduler::new(1));
+        let held = scheduler.acquire(Priority::High).await;
+
+        let abandoned = {
+            let scheduler = Arc::clone(&scheduler);
+            tokio::spawn(async move {
+                let _permit = scheduler.acquire(Priority::High).await;
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        abandoned.abort();
+        let _ = abandoned.await;
+
+        drop(held);
+        let acquired =
+            tokio::time::timeout(Duration::from_secs(1), scheduler.acquire(Priority::Low)).await;
+        assert!(acquired.is_ok());
+    }
+}
@@ -0,0 +1,395 @@
+//! Plumbing shared by the stdio servers (`serve --mcp`, `serve --stdio`,
+//! `serve --lsp`): JSON-RPC 2.0 on stdin/stdout, newline-delimited or with
+//! LSP `Content-Length` framing, and the search, index and status
+//! operations they expose. Diagnostics go to stderr.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use tokio::io::{
+    AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader,
+};
+use tokio::sync::OnceCell;
+use tracing::info;
+
+use crate::client::DaemonClient;
+use crate::models::{Config, SearchResults};
+use crate::server::http::SearchRequest;
+use crate::services::{
+    EmbeddingClient, IndexService, MetricsStore, SearchRecord, SearchService, TextChunker,
+    VectorStore, create_backend_with_embedding_config,
+};
+
+pub(crate) const PARSE_ERROR: i32 = -32700;
+pub(crate) const INVALID_REQUEST: i32 = -32600;
+pub(crate) const METHOD_NOT_FOUND: i32 = -32601;
+pub(crate) const INVALID_PARAMS: i32 = -32602;
+
+#[derive(Debug, Deserialize)]
+struct RpcRequest {
+    #[serde(default)]
+    jsonrpc: Option<String>,
+    /// Absent for notifications
+    #[serde(default)]
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct RpcResponse {
+    jsonrpc: &'static str,
+    id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) error: Option<RpcError>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct RpcError {
+    pub(crate) code: i32,
+    pub(crate) message: String,
+}
+
+impl RpcError {
+    pub(crate) fn new(code: i32, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    pub(crate) fn method_not_found(method: &str) -> Self {
+        Self::new(METHOD_NOT_FOUND, format!("method not found: {}", method))
+    }
+}
+
+impl RpcResponse {
+    fn result(id: Value, result: Value) -> Self {
+        Self {
+            jsonrpc: "2.0",
+            id,
+            result: Some(result),
+            error: None,
+        }
+    }
+
+    fn error(id: Value, error: RpcError) -> Self {
+        Self {
+            jsonrpc: "2.0",
+            id,
+            result: None,
+            error: Some(error),
+        }
+    }
+}
+
+/// Answers the requests of one stdio protocol.
+#[async_trait]
+pub(crate) trait RpcHandler: Sync {
+    /// Answer `method`; unknown methods are [`RpcError::method_not_found`].
+    async fn call(&self, method: &str, params: Value) -> Result<Value, RpcError>;
+}
+
+/// Report an error of a request that was understood but failed, with the
+/// protocol's `code` for such failures.
+pub(super) fn failed<E: ToString>(code: i32) -> impl FnOnce(E) -> RpcError {
+    move |error| RpcError::new(code, error.to_string())
+}
+
+/// Parse params, reporting a mismatch as invalid params.
+pub(crate) fn params<T: serde::de::DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    serde_json::from_value(params)
+        .map_err(|e| RpcError::new(INVALID_PARAMS, format!("invalid params: {}", e)))
+}
+
+/// Answer one line of input; notifications (no `id`) get no response.
+pub(crate) async fn handle_message<H: RpcHandler + ?Sized>(
+    handler: &H,
+    line: &str,
+) -> Option<RpcResponse> {
+    let request: RpcRequest = match serde_json::from_str(line) {
+        Ok(r) => r,
+        Err(e) => {
+            return Some(RpcResponse::error(
+                Value::Null,
+                RpcError::new(PARSE_ERROR, format!("parse error: {}", e)),
+            ));
+        }
+    };
+
+    // Notifications (no id) never get a response
+    let id = request.id?;
+
+    if request.jsonrpc.as_deref() != Some("2.0") {
+        return Some(RpcResponse::error(
+            id,
+            RpcError::new(INVALID_REQUEST, "jsonrpc must be \"2.0\""),
+        ));
+    }
+
+    Some(match handler.call(&request.method, request.params).await {
+        Ok(result) => RpcResponse::result(id, result),
+        Err(error) => RpcResponse::error(id, error),
+    })
+}
+
+/// Serve requests from stdin until it is closed.
+pub(crate) async fn serve_stdio<H: RpcHandler + ?Sized>(
+    handler: &H,
+    name: &str,
+) -> std::io::Result<()> {
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    let mut stdout = tokio::io::stdout();
+
+    info!("{} server ready (stdio)", name);
+
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        if let Some(response) = handle_message(handler, &line).await {
+            let mut encoded = serde_json::to_vec(&response).map_err(std::io::Error::other)?;
+            encoded.push(b'\n');
+            stdout.write_all(&encoded).await?;
+            stdout.flush().await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Read one `Content-Length` framed message (the LSP base protocol);
+/// `None` once the input is closed between messages.
+pub(crate) async fn read_frame<R: AsyncBufRead + Unpin>(
+    reader: &mut R,
+) -> std::io::Result<Option<String>> {
+    let invalid = |message: String| std::io::Error::new(std::io::ErrorKind::InvalidData, message);
+    let mut length = None;
+    let mut started = false;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).await? == 0 {
+            if started {
+                return Err(std::io::ErrorKind::UnexpectedEof.into());
+            }
+            return Ok(None);
+        }
+        started = true;
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        // Other headers (Content-Type) only ever name UTF-8 JSON-RPC
+        if let Some((name, value)) = header.split_once(':')
+            && name.trim().eq_ignore_ascii_case("content-length")
+        {
+            let value = value.trim();
+            length = Some(
+                value
+                    .parse::<usize>()
+                    .map_err(|_| invalid(format!("invalid Content-Length: {}", value)))?,
+            );
+        }
+    }
+
+    let length = length.ok_or_else(|| invalid("missing Content-Length header".to_string()))?;
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).await?;
+    String::from_utf8(body)
+        .map(Some)
+        .map_err(|_| invalid("message is not UTF-8".to_string()))
+}
+
+/// Write one `Content-Length` framed message.
+pub(crate) async fn write_frame<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    message: &impl Serialize,
+) -> std::io::Result<()> {
+    let body = serde_json::to_vec(message).map_err(std::io::Error::other)?;
+    writer
+        .write_all(format!("Content-Length: {}\r\n\r\n", body.len()).as_bytes())
+        .await?;
+    writer.write_all(&body).await?;
+    writer.flush().await
+}
+
+/// Serve `Content-Length` framed requests from stdin until it is closed or
+/// an `exit` notification arrives.
+pub(crate) async fn serve_framed<H: RpcHandler + ?Sized>(
+    handler: &H,
+    name: &str,
+) -> std::io::Result<()> {
+    let mut stdin = BufReader::new(tokio::io::stdin());
+    let mut stdout = tokio::io::stdout();
+
+    info!("{} server ready (stdio)", name);
+
+    while let Some(message) = read_frame(&mut stdin).await? {
+        // `exit` is a notification, so it never reaches the handler
+        let method = serde_json::from_str::<Value>(&message)
+            .ok()
+            .and_then(|v| v.get("method").and_then(Value::as_str).map(String::from));
+        if method.as_deref() == Some("exit") {
+            break;
+        }
+
+        if let Some(response) = handle_message(handler, &message).await {
+            write_frame(&mut stdout, &response).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Configuration and lazily connected services behind a stdio server.
+/// The vector store is connected on first use, so `status` works without it.
+pub(crate) struct StdioContext {
+    pub(crate) config: Config,
+    embedding_client: Arc<EmbeddingClient>,
+    chunker: TextChunker,
+    vector_store: OnceCell<Arc<dyn VectorStore>>,
+}
+
+impl StdioContext {
+    pub(crate) fn new(config: Config) -> Self {
+        Self {
+            embedding_client: Arc::new(EmbeddingClient::new(&config)),
+            chunker: TextChunker::from_config(&config),
+            config,
+            vector_store: OnceCell::new(),
+        }
+    }
+
+    async fn vector_store(&self) -> Result<Arc<dyn VectorStore>, String> {
+        self.vector_store
+            .get_or_try_init(|| async {
+                create_backend_with_embedding_config(
+                    &self.config.vector_store,
+                    &self.config.embedding,
+                )
+                .await
+                .map(Arc::from)
+            })
+            .await
+            .cloned()
+            .map_err(|e| e.to_string())
+    }
+
+    /// An index service with the `[indexing]` settings and workspace.
+    pub(crate) async fn index_service(&self) -> Result<IndexService, String> {
+        Ok(IndexService::new(
+            self.embedding_client.clone(),
+            self.vector_store().await?,
+            self.chunker.clone(),
+        )
+        .with_config(&self.config))
+    }
+
+    /// Run a search request against the current project's workspace and
+    /// record it in the search history.
+    pub(crate) async fn search(&self, req: SearchRequest) -> Result<SearchResults, String> {
+        let query = req.query.trim();
+        if query.is_empty() {
+            return Err("query cannot be empty".to_string());
+        }
+        let options = req
+            .options(&self.config.search, self.config.workspace())
+            .map_err(|e| e.to_string())?;
+
+        let service = SearchService::new(
+            self.embedding_client.clone(),
+            self.vector_store().await?,
+            self.config.search.clone(),
+        );
+        let results = service
+            .search(query, &options)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if let Some(history) = MetricsStore::open_history(&self.config) {
+            history.record_search(&SearchRecord::new(
+                query,
+                &options.filter,
+                &results.results,
+                results.duration_ms,
+            ));
+        }
+        Ok(results)
+    }
+
+    /// Daemon and vector store status, including the number of indexed chunks.
+    pub(crate) async fn status(&self) -> Value {
+        let client = DaemonClient::new(&self.config);
+        let daemon = if client.is_running() {
+            client.status().await.ok()
+        } else {
+            None
+        };
+
+        let (connected, points) = match self.vector_store().await {
+            Ok(store) => {
+                let connected = store.health_check().await.unwrap_or(false);
+                let points = if connected {
+                    store
+                        .get_collection_info()
+                        .await
+                        .ok()
+                        .flatten()
+                        .map_or(0, |info| info.points_count)
+                } else {
+                    0
+                };
+                (connected, points)
+            }
+            Err(_) => (false, 0),
+        };
+
+        json!({
+            "daemon_running": daemon.is_some(),
+            "embedding_model": daemon.as_ref().map(|d| d.embedding_model.clone()),
+            "vector_store_driver": self.config.vector_store.driver.to_string(),
+            "vector_store_connected": connected,
+            "collection": self.config.vector_store.collection,
+            "points": points,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_frames() {
+        let mut encoded = Vec::new();
+        write_frame(
+            &mut encoded,
+            &json!({"jsonrpc": "2.0", "id": 1, "result": "é"}),
+        )
+        .await
+        .unwrap();
+        let input = [
+            encoded.as_slice(),
+            b"content-length: 2\r\nContent-Type: application/vscode-jsonrpc; charset=utf-8\r\n\r\n{}",
+        ]
+        .concat();
+
+        let mut reader = BufReader::new(input.as_slice());
+        let first = read_frame(&mut reader).await.unwrap().unwrap();
+        assert_eq!(first, r#"{"jsonrpc":"2.0","id":1,"result":"é"}"#);
+        assert_eq!(read_frame(&mut reader).await.unwrap().unwrap(), "{}");
+        assert!(read_frame(&mut reader).await.unwrap().is_none());
+
+        let mut truncated = BufReader::new(&b"Content-Length: 10\r\n\r\n{}"[..]);
+        assert!(read_frame(&mut truncated).await.is_err());
+        let mut missing = BufReader::new(&b"Content-Type: json\r\n\r\n{}"[..]);
+        assert!(read_frame(&mut missing).await.is_err());
+    }
+}
@@ -1,35 +1,722 @@
-use anyhow::{Context, Result};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
 
-use crate::models::DocumentChunk;
-use crate::services::{EmbeddingClient, VectorStore};
+use anyhow::{Context, Result, anyhow};
+use tokio::sync::mpsc;
+use tracing::warn;
+
+use crate::models::{DocumentChunk, IndexingConfig, SearchFilter, WORKSPACE_ATTRIBUTE};
+use crate::services::{ChunkMatcher, EmbeddingProvider, VectorStore, redact_secrets};
+use crate::utils::{RetryConfig, Retryable, with_retry_notify};
+
+/// Embedding batches' worth of chunks buffered ahead of the embedder
+const CHUNK_BUFFER_BATCHES: usize = 4;
+
+/// Embedded batches waiting for the upserter
+const UPSERT_BUFFER: usize = 2;
+
+/// Stored chunks read per page by [`resend_stored_chunks`]
+const STORED_PAGE_SIZE: u32 = 256;
+
+/// Delay before the first retry of a failed embedding or vector store call
+const RETRY_INITIAL_DELAY: Duration = Duration::from_secs(1);
+
+/// Longest delay between retries
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// Receives a message each time a batch operation is retried, e.g. to print above a progress bar.
+pub type RetryNotice<'a> = &'a (dyn Fn(String) + Send + Sync);
+
+/// Receives the IDs of documents whose chunks have all been stored.
+pub type StoredNotice<'a> = &'a (dyn Fn(&[String]) + Send + Sync);
+
+/// Receives the number of chunks just embedded, as the daemon reports them.
+pub type EmbedNotice<'a> = &'a (dyn Fn(u64) + Send + Sync);
+
+/// Backoff for `max_retries` retries after the first attempt.
+fn retry_config(max_retries: u32) -> RetryConfig {
+    // RetryConfig counts the first attempt
+    RetryConfig::new(max_retries.saturating_add(1))
+        .with_initial_delay(RETRY_INITIAL_DELAY)
+        .with_max_delay(RETRY_MAX_DELAY)
+}
+
+/// Run `operation`, retrying transient failures and reporting each retry.
+async fn retrying<T, E, F, Fut>(
+    retry: &RetryConfig,
+    what: &str,
+    notice: Option<RetryNotice<'_>>,
+    operation: F,
+) -> Result<T, E>
+where
+    E: Retryable + Display + std::fmt::Debug,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    with_retry_notify(retry, operation, |error, attempt, delay| {
+        let message = format!(
+            "{} failed (attempt {}/{}), retrying in {:.1}s: {}",
+            what,
+            attempt,
+            retry.max_retries,
+            delay.as_secs_f64(),
+            error
+        );
+        match notice {
+            Some(notice) => notice(message),
+            None => warn!("{}", message),
+        }
+    })
+    .await
+    .into_result()
+}
 
 /// Process a batch of document chunks: generate embeddings and store in vector store.
 ///
 /// This function accepts any backend that implements the VectorStore trait,
-/// enabling backend-agnostic batch processing.
+/// enabling backend-agnostic batch processing. Transient failures are retried
+/// up to `max_retries` times.
 pub async fn process_batch<V: VectorStore + ?Sized>(
-    embedding_client: &EmbeddingClient,
+    embedding_client: &dyn EmbeddingProvider,
     vector_store: &V,
     chunks: &mut Vec<DocumentChunk>,
     texts: &mut Vec<String>,
+    max_retries: u32,
 ) -> Result<()> {
     if texts.is_empty() {
         return Ok(());
     }
 
-    let embeddings = embedding_client
-        .embed_batch(std::mem::take(texts))
-        .await
-        .context("failed to generate embeddings")?;
+    let retry = retry_config(max_retries);
+    let texts = std::mem::take(texts);
+    let embeddings = retrying(&retry, "embedding", None, || {
+        embedding_client.embed_batch(texts.clone())
+    })
+    .await
+    .context("failed to generate embeddings")?;
 
-    for (chunk, embedding) in chunks.iter_mut().zip(embeddings.into_iter()) {
+    for (chunk, embedding) in chunks.iter_mut().zip(embeddings) {
         chunk.dense_vector = embedding;
     }
 
-    vector_store
-        .upsert_points(std::mem::take(chunks))
-        .await
-        .context("failed to store chunks")?;
+    let chunks = std::mem::take(chunks);
+    retrying(&retry, "upsert", None, || {
+        vector_store.upsert_points(chunks.clone())
+    })
+    .await
+    .context("failed to store chunks")?;
 
     Ok(())
 }
+
+/// Each chunk's title, as an index into the batch's distinct titles.
+fn distinct_titles(chunks: &[DocumentChunk]) -> (Vec<String>, Vec<usize>) {
+    let mut titles: Vec<String> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    let indexes = chunks
+        .iter()
+        .map(|chunk| {
+            let title = chunk.title_text();
+            *positions.entry(title.clone()).or_insert_with(|| {
+                titles.push(title);
+                titles.len() - 1
+            })
+        })
+        .collect();
+    (titles, indexes)
+}
+
+enum PipelineItem {
+    /// Drop a document's stored chunks before its new chunks are written
+    Replace(String),
+    Chunk(Box<DocumentChunk>),
+    /// All of a document's chunks have been sent
+    Complete(String),
+}
+
+/// Totals reported by [`IndexPipeline::run`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PipelineStats {
+    /// Chunks embedded and written to the vector store
+    pub stored: u64,
+    /// Chunks skipped because another document already holds the same content
+    pub deduplicated: u64,
+    /// Secrets replaced with placeholders before embedding
+    pub redacted: u64,
+}
+
+/// Tracks which document owns each chunk content hash.
+#[derive(Default)]
+struct DedupIndex {
+    /// First document seen with each hash during this run
+    owners: HashMap<String, String>,
+    /// Documents rewritten during this run; their previously stored chunks don't count
+    replaced: HashSet<String>,
+}
+
+impl DedupIndex {
+    /// Drop chunks whose content is stored or queued for a different document.
+    async fn filter<V: VectorStore + ?Sized>(
+        &mut self,
+        vector_store: &V,
+        chunks: Vec<DocumentChunk>,
+    ) -> Result<(Vec<DocumentChunk>, u64)> {
+        let hashes: Vec<String> = chunks.iter().map(DocumentChunk::content_hash).collect();
+        let unseen: Vec<String> = hashes
+            .iter()
+            .filter(|hash| !self.owners.contains_key(*hash))
+            .cloned()
+            .collect();
+        for (hash, document_id) in vector_store
+            .find_content_hashes(&unseen)
+            .await
+            .context("failed to look up duplicate chunks")?
+        {
+            if !self.replaced.contains(&document_id) {
+                self.owners.entry(hash).or_insert(document_id);
+            }
+        }
+
+        let mut kept = Vec::with_capacity(chunks.len());
+        let mut skipped = 0;
+        for (chunk, hash) in chunks.into_iter().zip(hashes) {
+            let owner = self
+                .owners
+                .entry(hash)
+                .or_insert_with(|| chunk.document_id.clone());
+            if *owner == chunk.document_id {
+                kept.push(chunk);
+            } else {
+                skipped += 1;
+            }
+        }
+        Ok((kept, skipped))
+    }
+}
+
+struct EmbeddedBatch {
+    stale_ids: Vec<String>,
+    chunks: Vec<DocumentChunk>,
+    /// Documents fully stored once this batch is
+    completed: Vec<String>,
+}
+
+/// Producer side of an [`IndexPipeline`].
+pub struct ChunkSender {
+    tx: mpsc::Sender<PipelineItem>,
+}
+
+impl ChunkSender {
+    /// Queue a chunk for embedding, waiting while the pipeline is full.
+    pub async fn send(&self, chunk: DocumentChunk) -> Result<()> {
+        self.tx
+            .send(PipelineItem::Chunk(Box::new(chunk)))
+            .await
+            .map_err(|_| anyhow!("indexing pipeline stopped"))
+    }
+
+    /// Delete a document's stored chunks before any chunks sent after this call are upserted.
+    pub async fn replace(&self, document_id: String) -> Result<()> {
+        self.tx
+            .send(PipelineItem::Replace(document_id))
+            .await
+            .map_err(|_| anyhow!("indexing pipeline stopped"))
+    }
+
+    /// Mark a document's chunks as all sent; it is reported through
+    /// [`IndexPipeline::with_stored_notice`] once they are stored.
+    pub async fn complete(&self, document_id: String) -> Result<()> {
+        self.tx
+            .send(PipelineItem::Complete(document_id))
+            .await
+            .map_err(|_| anyhow!("indexing pipeline stopped"))
+    }
+}
+
+/// Send the chunks stored in `store` that match `filter` through an
+/// [`IndexPipeline`] again, without their vectors, so they are re-embedded
+/// from their stored content.
+///
+/// Returns the number of chunks sent.
+pub async fn resend_stored_chunks<V: VectorStore + ?Sized>(
+    store: &V,
+    filter: &SearchFilter,
+    sender: ChunkSender,
+) -> Result<u64> {
+    let matcher = ChunkMatcher::new(filter);
+    let mut sent = 0;
+    let mut offset = None;
+    loop {
+        let page = store
+            .scroll_chunks(offset, STORED_PAGE_SIZE)
+            .await
+            .context("failed to read stored chunks")?;
+        for mut chunk in page.chunks {
+            if !matcher.matches(&chunk) {
+                continue;
+            }
+            chunk.dense_vector.clear();
+            chunk.title_vector.clear();
+            sender.send(chunk).await?;
+            sent += 1;
+        }
+        offset = page.next_offset;
+        if offset.is_none() {
+            return Ok(sent);
+        }
+    }
+}
+
+/// Embeds and upserts chunks while the caller is still reading and chunking.
+///
+/// Run [`IndexPipeline::run`] alongside the producer (e.g. with `tokio::join!`)
+/// and drop the [`ChunkSender`] when done; the pipeline then flushes the last
+/// batch and returns. Embedding of one batch overlaps with the upsert of the
+/// previous one.
+pub struct IndexPipeline<'a, V: VectorStore + ?Sized> {
+    embedding_client: &'a dyn EmbeddingProvider,
+    vector_store: &'a V,
+    batch_size: usize,
+    dedup: bool,
+    workspace: Option<String>,
+    title_vectors: bool,
+    redact: bool,
+    retry: RetryConfig,
+    retry_notice: Option<RetryNotice<'a>>,
+    stored_notice: Option<StoredNotice<'a>>,
+    embed_notice: Option<EmbedNotice<'a>>,
+    rx: mpsc::Receiver<PipelineItem>,
+}
+
+impl<'a, V: VectorStore + ?Sized> IndexPipeline<'a, V> {
+    pub fn new(
+        embedding_client: &'a dyn EmbeddingProvider,
+        vector_store: &'a V,
+        batch_size: usize,
+    ) -> (ChunkSender, Self) {
+        let batch_size = batch_size.max(1);
+        let (tx, rx) = mpsc::channel(batch_size * CHUNK_BUFFER_BATCHES);
+        let pipeline = Self {
+            embedding_client,
+            vector_store,
+            batch_size,
+            dedup: false,
+            workspace: None,
+            title_vectors: false,
+            redact: false,
+            retry: retry_config(IndexingConfig::default().max_retries),
+            retry_notice: None,
+            stored_notice: None,
+            embed_notice: None,
+            rx,
+        };
+        (ChunkSender { tx }, pipeline)
+    }
+
+    /// Skip chunks whose exact content is already stored for another document.
+    #[must_use]
+    pub fn with_dedup(mut self, dedup: bool) -> Self {
+        self.dedup = dedup;
+        self
+    }
+
+    /// Stamp every chunk with the `workspace` attribute before it is stored.
+    #[must_use]
+    pub fn with_workspace(mut self, workspace: Option<String>) -> Self {
+        self.workspace = workspace;
+        self
+    }
+
+    /// Also embed each chunk's title (`[vector_store] title_vectors`).
+    #[must_use]
+    pub fn with_title_vectors(mut self, title_vectors: bool) -> Self {
+        self.title_vectors = title_vectors;
+        self
+    }
+
+    /// Replace secrets in chunk content with placeholders (`[indexing] redact_secrets`).
+    #[must_use]
+    pub fn with_redaction(mut self, redact: bool) -> Self {
+        self.redact = redact;
+        self
+    }
+
+    /// Retry a failed embedding or vector store call up to `max_retries` times
+    /// with exponential backoff before the run fails.
+    #[must_use]
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.retry = retry_config(max_retries);
+        self
+    }
+
+    /// Report retries through `notice` instead of the log.
+    #[must_use]
+    pub fn with_retry_notice(mut self, notice: RetryNotice<'a>) -> Self {
+        self.retry_notice = Some(notice);
+        self
+    }
+
+    /// Report documents marked with [`ChunkSender::complete`] once all their chunks are stored.
+    #[must_use]
+    pub fn with_stored_notice(mut self, notice: StoredNotice<'a>) -> Self {
+        self.stored_notice = Some(notice);
+        self
+    }
+
+    /// Report chunks as the daemon embeds them, rather than once per batch,
+    /// so a progress bar keeps moving through large batches.
+    #[must_use]
+    pub fn with_embed_notice(mut self, notice: EmbedNotice<'a>) -> Self {
+        self.embed_notice = Some(notice);
+        self
+    }
+
+    /// Process chunks until the sender is dropped.
+    pub async fn run(self) -> Result<PipelineStats> {
+        let Self {
+            embedding_client,
+            vector_store,
+            batch_size,
+            dedup,
+            workspace,
+            title_vectors,
+            redact,
+            retry,
+            retry_notice,
+            stored_notice,
+            embed_notice,
+            mut rx,
+        } = self;
+        let retry = &retry;
+        let (batch_tx, mut batch_rx) = mpsc::channel::<EmbeddedBatch>(UPSERT_BUFFER);
+
+        let embed = async move {
+            let mut dedup_index = dedup.then(DedupIndex::default);
+            let mut deduplicated = 0u64;
+            let mut redacted = 0u64;
+            let mut stale_ids = Vec::new();
+            let mut completed = Vec::new();
+            let mut chunks: Vec<DocumentChunk> = Vec::with_capacity(batch_size);
+            loop {
+                let item = rx.recv().await;
+                let finished = item.is_none();
+                match item {
+                    Some(PipelineItem::Replace(id)) => {
+                        if let Some(index) = dedup_index.as_mut() {
+                            index.replaced.insert(id.clone());
+                        }
+                        stale_ids.push(id);
+                    }
+                    Some(PipelineItem::Chunk(mut chunk)) => {
+                        if let Some(ref workspace) = workspace {
+                            chunk
+                                .attributes
+                                .insert(WORKSPACE_ATTRIBUTE.to_string(), workspace.clone());
+                        }
+                        // Before dedup, so content hashes and sparse vectors match the stored text
+                        if redact {
+                            let (content, count) = redact_secrets(&chunk.content);
+                            if count > 0 {
+                                chunk.content = content.into_owned();
+                                redacted += count as u64;
+                            }
+                        }
+                        chunks.push(*chunk);
+                    }
+                    Some(PipelineItem::Complete(id)) => completed.push(id),
+                    None if chunks.is_empty() && stale_ids.is_empty() && completed.is_empty() => {
+                        break;
+                    }
+                    None => {}
+                }
+                if chunks.len() < batch_size && !finished {
+                    continue;
+                }
+
+                if let Some(index) = dedup_index.as_mut() {
+                    let (kept, skipped) = index
+                        .filter(vector_store, std::mem::take(&mut chunks))
+                        .await?;
+                    chunks = kept;
+                    deduplicated += skipped;
+                }
+
+                let mut texts: Vec<String> = chunks.iter().map(|c| c.content.clone()).collect();
+                // Titles repeat across a document's chunks, so each is embedded once
+                let (titles, title_indexes) = if title_vectors {
+                    distinct_titles(&chunks)
+                } else {
+                    Default::default()
+                };
+                texts.extend(titles);
+                if !texts.is_empty() {
+                    // Titles follow the chunks, so progress past the chunks is not reported
+                    let chunk_count = chunks.len() as u64;
+                    let reported = AtomicU64::new(0);
+                    let progress = |done: usize, _total: usize| {
+                        if let Some(notice) = embed_notice {
+                            let done = (done as u64).min(chunk_count);
+                            let before = reported.fetch_max(done, Ordering::Relaxed);
+                            if done > before {
+                                notice(done - before);
+                            }
+                        }
+                    };
+                    let mut embeddings = retrying(retry, "embedding", retry_notice, || {
+                        let texts = texts.clone();
+                        async {
+                            match embed_notice {
+                                Some(_) => {
+                                    embedding_client
+                                        .embed_batch_with_progress(texts, &progress)
+                                        .await
+                                }
+                                None => embedding_client.embed_batch(texts).await,
+                            }
+                        }
+                    })
+                    .await
+                    .context("failed to generate embeddings")?;
+                    progress(chunks.len(), texts.len());
+                    let title_embeddings = embeddings.split_off(chunks.len().min(embeddings.len()));
+                    for (chunk, embedding) in chunks.iter_mut().zip(embeddings) {
+                        chunk.dense_vector = embedding;
+                    }
+                    for (chunk, index) in chunks.iter_mut().zip(title_indexes) {
+                        if let Some(embedding) = title_embeddings.get(index) {
+                            chunk.title_vector = embedding.clone();
+                        }
+                    }
+                }
+                let batch = EmbeddedBatch {
+                    stale_ids: std::mem::take(&mut stale_ids),
+                    chunks: std::mem::replace(&mut chunks, Vec::with_capacity(batch_size)),
+                    completed: std::mem::take(&mut completed),
+                };
+                batch_tx
+                    .send(batch)
+                    .await
+                    .map_err(|_| anyhow!("upsert stage stopped"))?;
+                if finished {
+                    break;
+                }
+            }
+            Ok::<(u64, u64), anyhow::Error>((deduplicated, redacted))
+        };
+
+        let upsert = async {
+            let mut stored = 0u64;
+            while let Some(batch) = batch_rx.recv().await {
+                if !batch.stale_ids.is_empty() {
+                    retrying(retry, "delete", retry_notice, || {
+                        vector_store.delete_by_document_ids(&batch.stale_ids)
+                    })
+                    .await
+                    .context("failed to remove outdated chunks")?;
+                }
+                stored += batch.chunks.len() as u64;
+                if !batch.chunks.is_empty() {
+                    retrying(retry, "upsert", retry_notice, || {
+                        vector_store.upsert_points(batch.chunks.clone())
+                    })
+                    .await
+                    .context("failed to store chunks")?;
+                }
+                if let Some(notice) = stored_notice
+                    && !batch.completed.is_empty()
+                {
+                    notice(&batch.completed);
+                }
+            }
+            Ok::<u64, anyhow::Error>(stored)
+        };
+
+        // A failed upsert closes its channel, so report it ahead of the embedder's send error
+        let (embedded, stored) = tokio::join!(embed, upsert);
+        let stored = stored?;
+        let (deduplicated, redacted) = embedded?;
+        Ok(PipelineStats {
+            stored,
+            deduplicated,
+            redacted,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    use crate::models::{Deleted, PathPattern};
+    use crate::services::MemoryBackend;
+    use crate::testing::{
+        DocumentBuilder, MockEmbeddingProvider, MockVectorStore, chunks, document,
+    };
+
+    #[tokio::test]
+    async fn test_pipeline_overlaps_producer() {
+        let embedder = MockEmbeddingProvider::default();
+        let store = MockVectorStore::default();
+        let stored_ids = std::sync::Mutex::new(Vec::new());
+        let record = |ids: &[String]| stored_ids.lock().unwrap().extend_from_slice(ids);
+        let (sender, pipeline) = IndexPipeline::new(&embedder, &store, 2);
+        let pipeline = pipeline.with_stored_notice(&record);
+
+        let documents: Vec<_> = (0..5)
+            .map(|i| document(&format!("docs/{i}.md"), &format!("Runbook step {i}.")))
+            .collect();
+        let produce = async {
+            let sender = sender;
+            for (i, document) in documents.iter().enumerate() {
+                sender.replace(document.id.clone()).await?;
+                for chunk in chunks(document) {
+                    sender.send(chunk).await?;
+                }
+                sender.complete(document.id.clone()).await?;
+                // The first batch is stored while the rest are still being produced
+                if i == 1 {
+                    tokio::time::timeout(Duration::from_secs(5), async {
+                        while store.upsert_calls() == 0 {
+                            tokio::task::yield_now().await;
+                        }
+                    })
+                    .await?;
+                }
+            }
+            Ok::<(), anyhow::Error>(())
+        };
+        let (produced, stats) = tokio::join!(produce, pipeline.run());
+        produced.unwrap();
+        assert_eq!(stats.unwrap().stored, 5);
+
+        // Batches of two, with the remainder flushed when the sender is dropped
+        assert_eq!(embedder.calls(), 3);
+        assert_eq!(store.upsert_calls(), 3);
+        let expected: Vec<String> = documents.iter().map(|d| d.id.clone()).collect();
+        assert_eq!(*stored_ids.lock().unwrap(), expected);
+    }
+
+    #[tokio::test]
+    async fn test_resend_stored_chunks() {
+        let embedder = MockEmbeddingProvider::default();
+        let source = MockVectorStore::default();
+        crate::testing::index(
+            &embedder,
+            &source,
+            &[
+                document("docs/a.md", "Rotate the signing keys every quarter."),
+                document("docs/b.md", "Page the on-call engineer for outages."),
+            ],
+        )
+        .await
+        .unwrap();
+        let gone = SearchFilter::new().with_paths(vec![PathPattern::new("docs/b.md")]);
+        source
+            .soft_delete_by_filter(&gone, chrono::Utc::now())
+            .await
+            .unwrap();
+
+        // Live chunks only
+        let target = MockVectorStore::new("target");
+        let (sender, pipeline) = IndexPipeline::new(&embedder, &target, 16);
+        let live = SearchFilter::new();
+        let produce = resend_stored_chunks(&source, &live, sender);
+        let (sent, stats) = tokio::join!(produce, pipeline.run());
+        assert_eq!(sent.unwrap(), 1);
+        assert_eq!(stats.unwrap().stored, 1);
+
+        // Soft-deleted chunks come along and stay soft-deleted
+        let target = MockVectorStore::new("target");
+        let (sender, pipeline) = IndexPipeline::new(&embedder, &target, 16);
+        let all = SearchFilter::new().with_deleted(Deleted::Any);
+        let produce = resend_stored_chunks(&source, &all, sender);
+        let (sent, stats) = tokio::join!(produce, pipeline.run());
+        assert_eq!(sent.unwrap(), 2);
+        assert_eq!(stats.unwrap().stored, 2);
+        let deleted = SearchFilter::new().with_deleted(Deleted::Only);
+        assert_eq!(target.count_by_filter(&deleted).await.unwrap(), 1);
+        assert_eq!(target.count_by_filter(&gone).await.unwrap(), 0);
+    }
+
+    /// Every chunk of `store` by ID, as (payload without vectors, dense vector).
+    async fn stored(store: &MemoryBackend) -> BTreeMap<String, (serde_json::Value, Vec<f32>)> {
+        let mut chunks = BTreeMap::new();
+        let mut offset = None;
+        loop {
+            let page = store.scroll_chunks(offset, 100).await.unwrap();
+            for mut chunk in page.chunks {
+                let vector = std::mem::take(&mut chunk.dense_vector);
+                chunk.title_vector.clear();
+                chunks.insert(
+                    chunk.id.clone(),
+                    (serde_json::to_value(&chunk).unwrap(), vector),
+                );
+            }
+            offset = page.next_offset;
+            if offset.is_none() {
+                return chunks;
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reembed_in_place_and_into() {
+        let old_model = MockEmbeddingProvider::new(8);
+        let new_model = MockEmbeddingProvider::new(16);
+        let source = MemoryBackend::new("docs");
+        crate::testing::index(
+            &old_model,
+            &source,
+            &[
+                DocumentBuilder::new("docs/a.md", "Rotate the signing keys every quarter.")
+                    .tag("team:security")
+                    .attribute("branch", "main")
+                    .build(),
+                document("docs/b.md", "Page the on-call engineer for outages."),
+            ],
+        )
+        .await
+        .unwrap();
+        let gone = SearchFilter::new().with_paths(vec![PathPattern::new("docs/b.md")]);
+        source
+            .soft_delete_by_filter(&gone, chrono::Utc::now())
+            .await
+            .unwrap();
+        let before = stored(&source).await;
+        let all = SearchFilter::new().with_deleted(Deleted::Any);
+
+        // Into a second collection, leaving the source untouched
+        let target = MemoryBackend::new("docs-v2");
+        let (sender, pipeline) = IndexPipeline::new(&new_model, &target, 1);
+        let produce = resend_stored_chunks(&source, &all, sender);
+        let (sent, stats) = tokio::join!(produce, pipeline.run());
+        assert_eq!(sent.unwrap(), before.len() as u64);
+        assert_eq!(stats.unwrap().stored, before.len() as u64);
+        assert_eq!(stored(&source).await, before);
+        let copied = stored(&target).await;
+        assert_eq!(copied.len(), before.len());
+        for (id, (payload, vector)) in &copied {
+            assert_eq!(payload, &before[id].0);
+            assert_eq!(vector.len(), 16);
+        }
+
+        // In place, replacing each chunk's vector under the same ID
+        let (sender, pipeline) = IndexPipeline::new(&new_model, &source, 1);
+        let produce = resend_stored_chunks(&source, &all, sender);
+        let (sent, stats) = tokio::join!(produce, pipeline.run());
+        assert_eq!(sent.unwrap(), before.len() as u64);
+        stats.unwrap();
+        let after = stored(&source).await;
+        assert_eq!(after.len(), before.len());
+        for (id, (payload, vector)) in &after {
+            assert_eq!(payload, &before[id].0);
+            assert_eq!(vector, &copied[id].1);
+        }
+        let deleted = SearchFilter::new().with_deleted(Deleted::Only);
+        assert_eq!(source.count_by_filter(&deleted).await.unwrap(), 1);
+    }
+}
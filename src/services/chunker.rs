@@ -1,7 +1,28 @@
 //! Text chunking with overlap for optimal embedding.
+//!
+//! Markdown, source code and prose can be split at headings, top-level
+//! definitions and sentences instead of fixed windows; units larger than a chunk fall back
+//! to overlapping character windows. With a tokenizer, chunks that still
+//! exceed the token limit are split again at token boundaries.
 
-use crate::models::{Document, DocumentChunk, IndexingConfig};
-use crate::utils::has_meaningful_content;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use tokenizers::Tokenizer;
+use tracing::warn;
+use tree_sitter::{Language, Node, Parser};
+
+use crate::models::{
+    BOILERPLATE_FILLER, ChunkOverrides, ChunkStrategy, ChunkTokenizer, Config, Document,
+    DocumentChunk, DocumentMetadata, FillerPolicy, IndexingConfig, SHORT_FILLER, SYMBOL_ATTRIBUTE,
+    SYMBOL_KIND_ATTRIBUTE, filler_tag,
+};
+use crate::server::model_dir_name;
+use crate::utils::meaningful_length;
+
+/// A chunk's content with its character offsets and line range.
+type Span = (String, u64, u64, u32, u32);
 
 /// Text chunker that splits documents into overlapping chunks.
 #[derive(Debug, Clone)]
@@ -10,20 +31,106 @@ pub struct TextChunker {
     chunk_size: usize,
     /// Overlap size in characters
     overlap: usize,
+    strategy: ChunkStrategy,
+    /// Counts real tokens when set; chunks over `max_tokens` are split again
+    tokenizer: Option<Arc<Tokenizer>>,
+    max_tokens: usize,
+    overlap_tokens: usize,
+    /// Most tokens the embedding model reads per chunk, with a tokenizer
+    token_limit: usize,
+    /// `[indexing.overrides]` and the `(chunk_size, chunk_overlap)` tokens they fall back to
+    overrides: ChunkOverrides,
+    default_sizes: (u32, u32),
+    /// Non-whitespace characters below which a chunk is filler
+    min_chunk_length: usize,
+    filler: FillerPolicy,
 }
 
 impl TextChunker {
     /// Create a new text chunker with the given configuration.
     pub fn new(config: &IndexingConfig) -> Self {
+        let mut chunker = Self {
+            chunk_size: 0,
+            overlap: 0,
+            strategy: config.strategy,
+            tokenizer: None,
+            max_tokens: 0,
+            overlap_tokens: 0,
+            token_limit: usize::MAX,
+            overrides: config.overrides.clone(),
+            default_sizes: (config.chunk_size, config.chunk_overlap),
+            min_chunk_length: config.min_chunk_length as usize,
+            filler: config.filler,
+        };
+        chunker.resize(config.chunk_size, config.chunk_overlap);
+        chunker
+    }
+
+    /// Target `chunk_tokens` per chunk, `overlap_tokens` shared between neighbours.
+    fn resize(&mut self, chunk_tokens: u32, overlap_tokens: u32) {
         // Convert tokens to approximate characters (1 token ≈ 4 characters)
-        let chunk_size = (config.chunk_size as usize) * 4;
-        let overlap = (config.chunk_overlap as usize) * 4;
-        Self {
-            chunk_size,
-            overlap,
+        self.chunk_size = (chunk_tokens as usize) * 4;
+        self.overlap = (overlap_tokens as usize) * 4;
+        self.max_tokens = (chunk_tokens as usize).min(self.token_limit).max(1);
+        self.overlap_tokens = (overlap_tokens as usize).min(self.max_tokens / 2);
+    }
+
+    /// A copy sized by the `[indexing.overrides]` entry for the document's
+    /// extension or language, if it has one.
+    fn sized_for(&self, metadata: &DocumentMetadata) -> Option<Self> {
+        let extension = metadata.extension.as_deref();
+        let language = metadata.language.as_deref();
+        self.overrides.get(extension, language)?;
+        let (chunk_tokens, overlap_tokens) =
+            self.overrides
+                .sizes(self.default_sizes, extension, language);
+        let mut chunker = self.clone();
+        chunker.resize(chunk_tokens, overlap_tokens);
+        Some(chunker)
+    }
+
+    /// Create a chunker for `config`, counting with the embedding model's
+    /// tokenizer when `[indexing] tokenizer = "model"`.
+    ///
+    /// Falls back to estimates if the tokenizer can't be loaded, e.g. before
+    /// the daemon has downloaded the model.
+    pub fn from_config(config: &Config) -> Self {
+        let chunker = Self::new(&config.indexing);
+        if config.indexing.tokenizer != ChunkTokenizer::Model {
+            return chunker;
+        }
+        let Some(path) = tokenizer_path(config) else {
+            return chunker;
+        };
+
+        match Tokenizer::from_file(&path) {
+            Ok(mut tokenizer) => {
+                // Count every token; the embedder truncates on its own
+                tokenizer.with_padding(None);
+                if tokenizer.with_truncation(None).is_err() {
+                    return chunker;
+                }
+                chunker.with_tokenizer(Arc::new(tokenizer), config.embedding.max_tokens as usize)
+            }
+            Err(e) => {
+                warn!(path = %path.display(), error = %e, "failed to load tokenizer, estimating chunk sizes");
+                chunker
+            }
         }
     }
 
+    /// Split chunks at token boundaries so that none exceeds the configured
+    /// chunk size or `max_tokens` (including the tokenizer's special tokens).
+    #[must_use]
+    pub fn with_tokenizer(mut self, tokenizer: Arc<Tokenizer>, max_tokens: usize) -> Self {
+        let special_tokens = tokenizer.encode("", true).map_or(0, |e| e.len());
+        self.token_limit = max_tokens.saturating_sub(special_tokens);
+        let (chunk_tokens, overlap_tokens) = self.default_sizes;
+        self.resize(chunk_tokens, overlap_tokens);
+        self.tokenizer = Some(tokenizer);
+        self
+    }
+
     /// Create a chunker with default settings.
     pub fn with_defaults() -> Self {
         Self::new(&IndexingConfig::default())
@@ -31,40 +138,73 @@ impl TextChunker {
 
     /// Chunk a document into overlapping segments.
     pub fn chunk(&self, document: &Document) -> Vec<DocumentChunk> {
+        self.chunk_screened(document).0
+    }
+
+    /// Chunk a document, also returning how many chunks shorter than
+    /// `[indexing] min_chunk_length` were skipped or tagged `filler:short`.
+    /// A document's only chunk is kept however short it is.
+    pub fn chunk_screened(&self, document: &Document) -> (Vec<DocumentChunk>, u64) {
+        match self.sized_for(&document.metadata) {
+            Some(chunker) => chunker.chunk_sized(document),
+            None => self.chunk_sized(document),
+        }
+    }
+
+    fn chunk_sized(&self, document: &Document) -> (Vec<DocumentChunk>, u64) {
         let content = &document.content;
 
         if content.is_empty() {
-            return Vec::new();
+            return (Vec::new(), 0);
         }
 
-        // If content is smaller than chunk size, return as single chunk
-        if content.len() <= self.chunk_size {
-            return vec![DocumentChunk::from_document(
-                document,
+        // If content is smaller than chunk size, keep it as a single chunk
+        let total_chars = content.chars().count();
+        let spans = if total_chars <= self.chunk_size {
+            self.split_tokens((
                 content.clone(),
                 0,
+                total_chars as u64,
                 1,
-                0,
-                content.len() as u64,
-                Some(1),
-                Some(content.lines().count() as u32),
-            )];
-        }
+                content.lines().count() as u32,
+            ))
+        } else {
+            self.split(document)
+                .into_iter()
+                .flat_map(|span| self.split_tokens(span))
+                .collect()
+        };
 
-        let chunks: Vec<_> = self
-            .split_with_overlap(content)
-            .into_iter()
-            .filter(|(chunk_content, _, _, _, _)| has_meaningful_content(chunk_content))
-            .collect();
+        let mut short = 0;
+        let chunks: Vec<(Span, bool)> = if spans.len() == 1 {
+            spans.into_iter().map(|span| (span, false)).collect()
+        } else {
+            spans
+                .into_iter()
+                .filter_map(|span| {
+                    if meaningful_length(&span.0) >= self.min_chunk_length {
+                        return Some((span, false));
+                    }
+                    short += 1;
+                    (self.filler == FillerPolicy::Flag).then_some((span, true))
+                })
+                .collect()
+        };
 
         let total_chunks = chunks.len() as u32;
+        let symbols = code_language(&document.metadata)
+            .and_then(|language| code_symbols(content, &language))
+            .unwrap_or_default();
 
-        chunks
+        let chunks = chunks
             .into_iter()
             .enumerate()
             .map(
-                |(idx, (chunk_content, start_offset, end_offset, line_start, line_end))| {
-                    DocumentChunk::from_document(
+                |(
+                    idx,
+                    ((chunk_content, start_offset, end_offset, line_start, line_end), filler),
+                )| {
+                    let mut chunk = DocumentChunk::from_document(
                         document,
                         chunk_content,
                         idx as u32,
@@ -73,14 +213,126 @@ impl TextChunker {
                         end_offset,
                         Some(line_start),
                         Some(line_end),
-                    )
+                    );
+                    if let Some(symbol) =
+                        enclosing_symbol(&symbols, start_offset as usize, end_offset as usize)
+                    {
+                        chunk
+                            .attributes
+                            .insert(SYMBOL_ATTRIBUTE.to_string(), symbol.name.clone());
+                        chunk
+                            .attributes
+                            .insert(SYMBOL_KIND_ATTRIBUTE.to_string(), symbol.kind.to_string());
+                    }
+                    if filler {
+                        chunk.tags.push(filler_tag(SHORT_FILLER));
+                    }
+                    chunk
                 },
             )
-            .collect()
+            .collect();
+        (chunks, short)
+    }
+
+    /// Split a document using the configured strategy.
+    fn split(&self, document: &Document) -> Vec<Span> {
+        let content = &document.content;
+        let boundaries = match self.resolve_strategy(&document.metadata) {
+            ChunkStrategy::Markdown => Some(markdown_boundaries(content)),
+            ChunkStrategy::Sentence => Some(sentence_boundaries(content)),
+            ChunkStrategy::Code => code_language(&document.metadata)
+                .and_then(|language| code_boundaries(content, &language, self.chunk_size)),
+            ChunkStrategy::Auto | ChunkStrategy::Chars => None,
+        };
+
+        match boundaries {
+            Some(boundaries) if !boundaries.is_empty() => self.pack(content, &boundaries),
+            _ => self.split_with_overlap(content),
+        }
+    }
+
+    /// Resolve `auto` from the document's language; code without a grammar uses windows.
+    fn resolve_strategy(&self, metadata: &DocumentMetadata) -> ChunkStrategy {
+        match self.strategy {
+            ChunkStrategy::Auto if is_markdown(metadata) => ChunkStrategy::Markdown,
+            ChunkStrategy::Auto if code_language(metadata).is_some() => ChunkStrategy::Code,
+            ChunkStrategy::Auto => ChunkStrategy::Chars,
+            strategy => strategy,
+        }
+    }
+
+    /// Greedily pack the units between boundaries (byte offsets) into chunks
+    /// of at most `chunk_size` characters, without overlap.
+    fn pack(&self, content: &str, boundaries: &[usize]) -> Vec<Span> {
+        let mut edges = Vec::with_capacity(boundaries.len() + 2);
+        edges.push(0);
+        edges.extend(
+            boundaries
+                .iter()
+                .copied()
+                .filter(|&b| b > 0 && b < content.len()),
+        );
+        edges.push(content.len());
+        edges.dedup();
+
+        // Character offset of each edge, so sizes match `chunk_size` for non-ASCII text
+        let mut char_edges = Vec::with_capacity(edges.len());
+        let mut chars = 0;
+        for (i, &edge) in edges.iter().enumerate() {
+            if i > 0 {
+                chars += content[edges[i - 1]..edge].chars().count();
+            }
+            char_edges.push(chars);
+        }
+        let length = |from: usize, to: usize| char_edges[to] - char_edges[from];
+
+        let mut cursor = Cursor::new(content);
+        let mut chunks = Vec::new();
+        // Indices into `edges`
+        let mut start = 0;
+        let mut end = 0;
+
+        for unit in 1..edges.len() {
+            if length(start, unit) <= self.chunk_size {
+                end = unit;
+                continue;
+            }
+
+            if end > start {
+                chunks.push(cursor.span(edges[start], edges[end]));
+                start = end;
+            }
+
+            if length(unit - 1, unit) > self.chunk_size {
+                let (unit_start, unit_end) = (edges[unit - 1], edges[unit]);
+                let (char_base, line_base) = cursor.advance(unit_start);
+                chunks.extend(
+                    self.split_with_overlap(&content[unit_start..unit_end])
+                        .into_iter()
+                        .map(|(text, from, to, line_start, line_end)| {
+                            (
+                                text,
+                                char_base as u64 + from,
+                                char_base as u64 + to,
+                                line_base + line_start - 1,
+                                line_base + line_end - 1,
+                            )
+                        }),
+                );
+                start = unit;
+            }
+            end = unit;
+        }
+
+        if end > start {
+            chunks.push(cursor.span(edges[start], edges[end]));
+        }
+
+        chunks
     }
 
     /// Split content into overlapping chunks with position information.
-    fn split_with_overlap(&self, content: &str) -> Vec<(String, u64, u64, u32, u32)> {
+    fn split_with_overlap(&self, content: &str) -> Vec<Span> {
         let mut chunks = Vec::new();
         let chars: Vec<char> = content.chars().collect();
         let total_chars = chars.len();
@@ -141,6 +393,54 @@ impl TextChunker {
         chunks
     }
 
+    /// Split a span into overlapping windows of at most `max_tokens` tokens.
+    ///
+    /// Spans are returned unchanged without a tokenizer or when they fit.
+    fn split_tokens(&self, span: Span) -> Vec<Span> {
+        let Some(ref tokenizer) = self.tokenizer else {
+            return vec![span];
+        };
+        let Ok(encoding) = tokenizer.encode_char_offsets(span.0.as_str(), false) else {
+            return vec![span];
+        };
+        let offsets = encoding.get_offsets();
+        if offsets.len() <= self.max_tokens {
+            return vec![span];
+        }
+
+        let (text, char_start, _, line_start, _) = span;
+        let chars: Vec<char> = text.chars().collect();
+        let step = self.max_tokens - self.overlap_tokens;
+        let mut spans = Vec::new();
+        let mut first = 0;
+        loop {
+            let last = (first + self.max_tokens).min(offsets.len());
+            let start = if first == 0 { 0 } else { offsets[first].0 };
+            let end = if last == offsets.len() {
+                chars.len()
+            } else {
+                offsets[last].0
+            };
+
+            let content: String = chars[start..end].iter().collect();
+            let line = line_start + chars[..start].iter().filter(|&&c| c == '\n').count() as u32;
+            let line_end = line + content.trim_end_matches('\n').matches('\n').count() as u32;
+            spans.push((
+                content,
+                char_start + start as u64,
+                char_start + end as u64,
+                line,
+                line_end,
+            ));
+
+            if last == offsets.len() {
+                break;
+            }
+            first += step;
+        }
+        spans
+    }
+
     /// Find a natural break point near the target end position.
     fn find_break_point(
         &self,
@@ -173,11 +473,9 @@ impl TextChunker {
                     }
                     last_newline = Some(pos + 1);
                 }
-                '.' | '!' | '?' => {
-                    // Sentence end followed by space or newline
-                    if search_range.get(i + 1).is_some_and(|c| c.is_whitespace()) {
-                        last_sentence = Some(pos + 1);
-                    }
+                // Sentence end followed by space or newline
+                '.' | '!' | '?' if search_range.get(i + 1).is_some_and(|c| c.is_whitespace()) => {
+                    last_sentence = Some(pos + 1);
                 }
                 ' ' | '\t' => {
                     last_space = Some(pos + 1);
@@ -194,6 +492,324 @@ impl TextChunker {
     }
 }
 
+/// Forward-only translation of byte offsets into character offsets and line numbers.
+struct Cursor<'a> {
+    text: &'a str,
+    byte: usize,
+    chars: usize,
+    line: u32,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            text,
+            byte: 0,
+            chars: 0,
+            line: 1,
+        }
+    }
+
+    /// Move to `byte`, returning the character offset and line there.
+    fn advance(&mut self, byte: usize) -> (usize, u32) {
+        let skipped = &self.text[self.byte..byte];
+        self.chars += skipped.chars().count();
+        self.line += skipped.bytes().filter(|&b| b == b'\n').count() as u32;
+        self.byte = byte;
+        (self.chars, self.line)
+    }
+
+    fn span(&mut self, start: usize, end: usize) -> Span {
+        let (char_start, line_start) = self.advance(start);
+        let (char_end, line) = self.advance(end);
+        let line_end = if self.text[..end].ends_with('\n') {
+            line - 1
+        } else {
+            line
+        };
+        (
+            self.text[start..end].to_string(),
+            char_start as u64,
+            char_end as u64,
+            line_start,
+            line_end.max(line_start),
+        )
+    }
+}
+
+fn is_markdown(metadata: &DocumentMetadata) -> bool {
+    metadata.language.as_deref() == Some("markdown")
+        || matches!(
+            metadata.extension.as_deref(),
+            Some("md" | "markdown" | "mdx")
+        )
+}
+
+/// Byte offsets of ATX headings, ignoring fenced code blocks.
+fn markdown_boundaries(content: &str) -> Vec<usize> {
+    let mut boundaries = Vec::new();
+    let mut fence: Option<&str> = None;
+    let mut offset = 0;
+
+    for line in content.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        let indent = line.len() - trimmed.len();
+
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+        } else if trimmed.starts_with("```") {
+            fence = Some("```");
+        } else if trimmed.starts_with("~~~") {
+            fence = Some("~~~");
+        } else if indent < 4 && is_heading(trimmed) {
+            boundaries.push(offset);
+        }
+
+        offset += line.len();
+    }
+
+    boundaries
+}
+
+/// Byte offsets where sentences start: after `.`, `!` or `?` followed by
+/// whitespace, and after blank lines.
+fn sentence_boundaries(content: &str) -> Vec<usize> {
+    let mut boundaries = Vec::new();
+    let mut chars = content.char_indices().peekable();
+
+    while let Some((_, c)) = chars.next() {
+        let Some(&(_, next)) = chars.peek() else {
+            break;
+        };
+        let ends_sentence = match c {
+            '.' | '!' | '?' => next.is_whitespace(),
+            '\n' => next == '\n',
+            _ => false,
+        };
+        if !ends_sentence {
+            continue;
+        }
+        while chars.next_if(|&(_, c)| c.is_whitespace()).is_some() {}
+        if let Some(&(offset, _)) = chars.peek() {
+            boundaries.push(offset);
+        }
+    }
+
+    boundaries
+}
+
+fn is_heading(line: &str) -> bool {
+    let level = line.bytes().take_while(|&b| b == b'#').count();
+    (1..=6).contains(&level)
+        && line[level..]
+            .chars()
+            .next()
+            .is_none_or(|c| c.is_whitespace())
+}
+
+/// Tree-sitter grammar for the document's language, if one is bundled.
+fn code_language(metadata: &DocumentMetadata) -> Option<Language> {
+    let language = match metadata.language.as_deref()? {
+        "rust" => tree_sitter_rust::LANGUAGE,
+        "python" => tree_sitter_python::LANGUAGE,
+        "javascript" => tree_sitter_javascript::LANGUAGE,
+        "typescript" if metadata.extension.as_deref() == Some("tsx") => {
+            tree_sitter_typescript::LANGUAGE_TSX
+        }
+        "typescript" => tree_sitter_typescript::LANGUAGE_TYPESCRIPT,
+        "go" => tree_sitter_go::LANGUAGE,
+        "java" => tree_sitter_java::LANGUAGE,
+        _ => return None,
+    };
+    Some(language.into())
+}
+
+/// Byte offsets where top-level definitions start, descending into any
+/// definition larger than `max_len` (e.g. a big impl block or class).
+fn code_boundaries(content: &str, language: &Language, max_len: usize) -> Option<Vec<usize>> {
+    let mut parser = Parser::new();
+    parser.set_language(language).ok()?;
+    let tree = parser.parse(content, None)?;
+
+    let mut boundaries = Vec::new();
+    collect_boundaries(tree.root_node(), max_len, &mut boundaries);
+    boundaries.sort_unstable();
+    boundaries.dedup();
+    Some(boundaries)
+}
+
+fn collect_boundaries(node: Node<'_>, max_len: usize, boundaries: &mut Vec<usize>) {
+    let mut cursor = node.walk();
+    let mut after_comment = false;
+
+    for child in node.named_children(&mut cursor) {
+        // Keep leading doc comments together with the item they describe
+        if !after_comment {
+            boundaries.push(child.start_byte());
+        }
+        after_comment = child.kind().contains("comment");
+
+        if child.byte_range().len() > max_len {
+            collect_boundaries(child, max_len, boundaries);
+        }
+    }
+}
+
+/// A named definition found in source code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CodeSymbol {
+    start: usize,
+    end: usize,
+    kind: &'static str,
+    name: String,
+}
+
+/// Every named definition in `content`, in source order.
+fn code_symbols(content: &str, language: &Language) -> Option<Vec<CodeSymbol>> {
+    let mut parser = Parser::new();
+    parser.set_language(language).ok()?;
+    let tree = parser.parse(content, None)?;
+
+    let mut symbols = Vec::new();
+    collect_symbols(tree.root_node(), content, &mut symbols);
+    Some(symbols)
+}
+
+fn collect_symbols(node: Node<'_>, content: &str, symbols: &mut Vec<CodeSymbol>) {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        if let Some((kind, name_field)) = symbol_kind(child.kind()) {
+            let name = child
+                .child_by_field_name(name_field)
+                .and_then(|name| name.utf8_text(content.as_bytes()).ok());
+            if let Some(name) = name {
+                symbols.push(CodeSymbol {
+                    start: child.start_byte(),
+                    end: child.end_byte(),
+                    kind,
+                    name: name.to_string(),
+                });
+            }
+        }
+        collect_symbols(child, content, symbols);
+    }
+}
+
+/// Display kind and name field for definition nodes across the bundled grammars.
+fn symbol_kind(node_kind: &str) -> Option<(&'static str, &'static str)> {
+    let kind = match node_kind {
+        "function_item" | "function_signature_item" => "fn",
+        "struct_item" => "struct",
+        "enum_item" | "enum_declaration" => "enum",
+        "trait_item" => "trait",
+        "mod_item" => "mod",
+        "impl_item" => return Some(("impl", "type")),
+        "function_definition" => "def",
+        "class_definition" | "class_declaration" => "class",
+        "function_declaration" => "function",
+        "method_definition" | "method_declaration" => "method",
+        "constructor_declaration" => "constructor",
+        "interface_declaration" => "interface",
+        "record_declaration" => "record",
+        "type_alias_declaration" | "type_spec" => "type",
+        _ => return None,
+    };
+    Some((kind, "name"))
+}
+
+/// The symbol a chunk belongs to: the first definition starting inside it,
+/// else the innermost definition it starts in.
+fn enclosing_symbol(symbols: &[CodeSymbol], start: usize, end: usize) -> Option<&CodeSymbol> {
+    symbols
+        .iter()
+        .find(|symbol| symbol.start >= start && symbol.start < end)
+        .or_else(|| {
+            symbols
+                .iter()
+                .filter(|symbol| symbol.start <= start && start < symbol.end)
+                .min_by_key(|symbol| symbol.end - symbol.start)
+        })
+}
+
+/// tokenizer.json for `[indexing] tokenizer = "model"`: the configured path,
+/// else the one next to the embedding model.
+fn tokenizer_path(config: &Config) -> Option<PathBuf> {
+    if let Some(ref path) = config.indexing.tokenizer_path {
+        return Some(path.clone());
+    }
+    config
+        .embedding
+        .model_path
+        .clone()
+        .or_else(|| {
+            Config::models_dir().map(|d| d.join(model_dir_name(&config.embedding.model_id)))
+        })
+        .map(|dir| dir.join("tokenizer.json"))
+}
+
+/// Counts the files each chunk content appears in, to find boilerplate such
+/// as license headers and generated banners repeated across a tree.
+#[derive(Debug, Default)]
+pub struct BoilerplateCounter {
+    files: HashMap<String, u32>,
+}
+
+impl BoilerplateCounter {
+    /// Count one file's chunks by content hash; repeats within the file count once.
+    pub fn add_file(&mut self, chunks: &[DocumentChunk]) {
+        let hashes: HashSet<String> = chunks.iter().map(DocumentChunk::content_hash).collect();
+        for hash in hashes {
+            *self.files.entry(hash).or_default() += 1;
+        }
+    }
+
+    /// Add the counts of another counter, e.g. one per worker thread.
+    #[must_use]
+    pub fn merge(mut self, other: Self) -> Self {
+        for (hash, files) in other.files {
+            *self.files.entry(hash).or_default() += files;
+        }
+        self
+    }
+
+    /// Content hashes found in at least `min_files` files.
+    pub fn boilerplate(self, min_files: u32) -> HashSet<String> {
+        self.files
+            .into_iter()
+            .filter(|(_, files)| *files >= min_files)
+            .map(|(hash, _)| hash)
+            .collect()
+    }
+}
+
+/// Skip chunks whose content hash is in `boilerplate`, or tag them
+/// `filler:boilerplate`, returning how many there were.
+pub fn screen_boilerplate(
+    chunks: &mut Vec<DocumentChunk>,
+    boilerplate: &HashSet<String>,
+    policy: FillerPolicy,
+) -> u64 {
+    if boilerplate.is_empty() {
+        return 0;
+    }
+    let before = chunks.len();
+    let mut flagged = 0;
+    chunks.retain_mut(|chunk| {
+        if !boilerplate.contains(&chunk.content_hash()) {
+            return true;
+        }
+        if policy == FillerPolicy::Skip {
+            return false;
+        }
+        chunk.tags.push(filler_tag(BOILERPLATE_FILLER));
+        flagged += 1;
+        true
+    });
+    (before - chunks.len()) as u64 + flagged
+}
+
 /// Estimate the number of tokens in a text.
 /// Uses a simple heuristic: ~4 characters per token on average.
 pub fn estimate_tokens(text: &str) -> usize {
@@ -203,7 +819,7 @@ pub fn estimate_tokens(text: &str) -> usize {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::{DocumentMetadata, Source};
+    use crate::models::{ChunkOverride, DocumentMetadata, Source};
 
     fn create_test_document(content: &str) -> Document {
         Document::new(
@@ -269,6 +885,280 @@ mod tests {
         assert_eq!(chunks[0].line_end, Some(3));
     }
 
+    fn create_typed_document(content: &str, language: &str, extension: &str) -> Document {
+        Document::new(
+            content.to_string(),
+            Source::local(format!("/test.{extension}")),
+            vec![],
+            "test_checksum".to_string(),
+            DocumentMetadata {
+                language: Some(language.to_string()),
+                extension: Some(extension.to_string()),
+                ..Default::default()
+            },
+        )
+    }
+
+    fn small_chunker(strategy: ChunkStrategy) -> TextChunker {
+        TextChunker::new(&IndexingConfig {
+            chunk_size: 40,   // 160 chars
+            chunk_overlap: 5, // 20 chars
+            strategy,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_overrides_resize_per_type() {
+        let mut config = IndexingConfig {
+            chunk_size: 40,   // 160 chars
+            chunk_overlap: 5, // 20 chars
+            strategy: ChunkStrategy::Chars,
+            ..Default::default()
+        };
+        config.overrides.insert(
+            "txt",
+            ChunkOverride {
+                chunk_size: Some(400),
+                chunk_overlap: None,
+            },
+        );
+        let chunker = TextChunker::new(&config);
+
+        let content = "word ".repeat(200);
+        let text = create_typed_document(&content, "text", "txt");
+        let other = create_typed_document(&content, "text", "log");
+        assert_eq!(chunker.chunk(&text).len(), 1);
+        assert!(chunker.chunk(&other).len() > 1);
+    }
+
+    #[test]
+    fn test_short_chunks_skipped_or_flagged() {
+        let section = |title: &str| format!("## {title}\n\n{}\n", "Body text. ".repeat(8));
+        let content = format!("{}{}", section("Install"), section("Usage"));
+        let doc = create_typed_document(&content, "markdown", "md");
+        let chunker = |filler: FillerPolicy| {
+            TextChunker::new(&IndexingConfig {
+                chunk_size: 40,
+                chunk_overlap: 5,
+                min_chunk_length: 1000,
+                filler,
+                ..Default::default()
+            })
+        };
+
+        let (chunks, short) = chunker(FillerPolicy::Skip).chunk_screened(&doc);
+        assert!(chunks.is_empty());
+        assert_eq!(short, 2);
+
+        let (chunks, short) = chunker(FillerPolicy::Flag).chunk_screened(&doc);
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(short, 2);
+        assert!(chunks[0].tags.contains(&filler_tag(SHORT_FILLER)));
+
+        // A document's only chunk is kept
+        let tiny = create_typed_document("tiny", "markdown", "md");
+        assert_eq!(chunker(FillerPolicy::Skip).chunk_screened(&tiny).1, 0);
+    }
+
+    #[test]
+    fn test_boilerplate_repeated_across_files() {
+        let license =
+            "## License\n\nCopyright Example Corp. Licensed under the Apache License 2.0.\n";
+        let file = |body: &str| {
+            let content = format!("{license}\n## Notes\n\n{}\n", body.repeat(16));
+            let doc = create_typed_document(&content, "markdown", "md");
+            small_chunker(ChunkStrategy::Markdown).chunk(&doc)
+        };
+        let files = [file("alpha beta "), file("gamma delta "), file("epsilon ")];
+
+        let mut counter = BoilerplateCounter::default();
+        for chunks in &files {
+            counter.add_file(chunks);
+        }
+        let boilerplate = counter.boilerplate(3);
+        assert_eq!(boilerplate.len(), 1);
+
+        let mut chunks = files[0].clone();
+        let total = chunks.len();
+        assert_eq!(
+            screen_boilerplate(&mut chunks, &boilerplate, FillerPolicy::Skip),
+            1
+        );
+        assert_eq!(chunks.len(), total - 1);
+
+        let mut chunks = files[1].clone();
+        assert_eq!(
+            screen_boilerplate(&mut chunks, &boilerplate, FillerPolicy::Flag),
+            1
+        );
+        assert!(chunks[0].tags.contains(&filler_tag(BOILERPLATE_FILLER)));
+    }
+
+    #[test]
+    fn test_markdown_chunks_start_at_headings() {
+        let section = |title: &str| {
+            format!(
+                "## {title}\n\n{}\n\n```sh\n# not a heading\n```\n",
+                "Body text for the section. ".repeat(3)
+            )
+        };
+        let content = format!("# Guide\n\n{}{}", section("Install"), section("Usage"));
+        let doc = create_typed_document(&content, "markdown", "md");
+
+        let chunks = small_chunker(ChunkStrategy::Auto).chunk(&doc);
+
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks[0].content.starts_with("# Guide"));
+        assert!(chunks[1].content.starts_with("## Usage"));
+        assert_eq!(chunks[1].line_start, Some(10));
+        assert_eq!(chunks[0].end_offset, chunks[1].start_offset);
+    }
+
+    #[test]
+    fn test_sentence_chunks_end_at_sentences() {
+        // 10 characters but 26 bytes per sentence
+        let content = "한국어 문장입니다. ".repeat(30);
+        let doc = create_typed_document(&content, "text", "txt");
+
+        let chunks = small_chunker(ChunkStrategy::Sentence).chunk(&doc);
+
+        // Packed by characters, not bytes: 16 sentences fit in 160 characters
+        assert_eq!(chunks.len(), 2);
+        for chunk in &chunks {
+            let length = chunk.content.chars().count();
+            assert!(length <= 160);
+            assert_eq!(chunk.end_offset - chunk.start_offset, length as u64);
+            assert!(chunk.content.trim_end().ends_with('.'));
+        }
+        assert_eq!(chunks[0].end_offset, chunks[1].start_offset);
+        assert_eq!(
+            sentence_boundaries("One. Two!\n\nThree? 3.5 four"),
+            vec![5, 11, 18]
+        );
+    }
+
+    #[test]
+    fn test_code_chunks_keep_functions_whole() {
+        let function = |name: &str| {
+            format!(
+                "/// Adds one to {name}.\nfn {name}(x: u32) -> u32 {{\n    let y = x + 1;\n    y\n}}\n\n"
+            )
+        };
+        let content: String = ["alpha", "beta", "gamma", "delta"]
+            .iter()
+            .map(|name| function(name))
+            .collect();
+        let doc = create_typed_document(&content, "rust", "rs");
+
+        let chunks = small_chunker(ChunkStrategy::Code).chunk(&doc);
+
+        assert!(chunks.len() > 1);
+        for chunk in &chunks {
+            assert!(chunk.content.starts_with("/// Adds one"));
+            assert_eq!(
+                chunk.content.matches('{').count(),
+                chunk.content.matches('}').count()
+            );
+        }
+        assert_eq!(
+            chunks
+                .iter()
+                .map(|c| c.content.as_str())
+                .collect::<String>(),
+            content
+        );
+    }
+
+    #[test]
+    fn test_code_chunks_record_enclosing_symbol() {
+        let body = "    let total = items.len();\n".repeat(12);
+        let content = format!(
+            "struct Server;\n\nimpl Server {{\n    fn handle_search(&self) {{\n{body}    }}\n}}\n"
+        );
+        let doc = create_typed_document(&content, "rust", "rs");
+
+        let chunks = small_chunker(ChunkStrategy::Code).chunk(&doc);
+
+        assert!(chunks.len() > 2);
+        assert_eq!(chunks[0].attributes[SYMBOL_ATTRIBUTE], "Server");
+        assert_eq!(chunks[0].attributes[SYMBOL_KIND_ATTRIBUTE], "struct");
+        // Windows inside the oversized method still name it
+        let last = chunks.last().unwrap();
+        assert_eq!(last.attributes[SYMBOL_ATTRIBUTE], "handle_search");
+        assert_eq!(last.attributes[SYMBOL_KIND_ATTRIBUTE], "fn");
+
+        let prose = create_typed_document(&content, "markdown", "md");
+        assert!(
+            !TextChunker::with_defaults().chunk(&prose)[0]
+                .attributes
+                .contains_key(SYMBOL_ATTRIBUTE)
+        );
+    }
+
+    #[test]
+    fn test_auto_without_grammar_uses_windows() {
+        let content = "word ".repeat(100);
+        let doc = create_typed_document(&content, "kotlin", "kt");
+
+        let auto = small_chunker(ChunkStrategy::Auto).chunk(&doc);
+        let chars = small_chunker(ChunkStrategy::Chars).chunk(&doc);
+
+        assert_eq!(auto.len(), chars.len());
+        assert!(auto.len() > 1);
+    }
+
+    fn word_tokenizer() -> Arc<Tokenizer> {
+        use tokenizers::models::wordlevel::WordLevel;
+        use tokenizers::pre_tokenizers::whitespace::Whitespace;
+
+        let vocab = [("[UNK]".to_string(), 0)].into_iter().collect();
+        let model = WordLevel::builder()
+            .vocab(vocab)
+            .unk_token("[UNK]".to_string())
+            .build()
+            .unwrap();
+        let mut tokenizer = Tokenizer::new(model);
+        tokenizer.with_pre_tokenizer(Some(Whitespace {}));
+        Arc::new(tokenizer)
+    }
+
+    #[test]
+    fn test_tokenizer_splits_at_token_limit() {
+        // 300 words fit in one estimated chunk but exceed 100 real tokens
+        let content = (0..300)
+            .map(|i| format!("w{i}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let doc = create_test_document(&content);
+        let chunker = TextChunker::new(&IndexingConfig {
+            chunk_overlap: 10,
+            ..Default::default()
+        })
+        .with_tokenizer(word_tokenizer(), 100);
+
+        let chunks = chunker.chunk(&doc);
+
+        assert_eq!(chunks.len(), 4);
+        for chunk in &chunks {
+            assert!(chunk.content.split_whitespace().count() <= 100);
+        }
+        assert!(chunks[0].content.starts_with("w0\n"));
+        assert!(chunks[1].content.starts_with("w90\n"));
+        assert_eq!(chunks[1].line_start, Some(91));
+        assert!(chunks[3].content.ends_with("w299"));
+        assert_eq!(chunks[3].line_end, Some(300));
+    }
+
+    #[test]
+    fn test_tokenizer_keeps_short_content() {
+        let doc = create_test_document("just a few words");
+        let chunker = TextChunker::with_defaults().with_tokenizer(word_tokenizer(), 100);
+        let chunks = chunker.chunk(&doc);
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].content, "just a few words");
+    }
+
     #[test]
     fn test_estimate_tokens() {
         assert_eq!(estimate_tokens("1234"), 1);
@@ -1,17 +1,65 @@
-use crate::client::DaemonClient;
+use async_trait::async_trait;
+
+use crate::client::{DaemonClient, EmbedProgress};
 use crate::error::EmbeddingError;
 use crate::models::Config;
+use crate::server::protocol::ModelSpec;
+
+/// Turns chunk text into vectors for the index pipeline, and queries into
+/// vectors and reranker scores for search.
+///
+/// [`EmbeddingClient`] asks the daemon; with the `test-util` feature,
+/// `testing::MockEmbeddingProvider` embeds deterministically in process.
+#[async_trait]
+pub trait EmbeddingProvider: Send + Sync {
+    /// Embed document texts, one vector per text.
+    async fn embed_batch(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>, EmbeddingError>;
+
+    /// [`Self::embed_batch`], reporting `(done, total)` texts through `progress`.
+    async fn embed_batch_with_progress(
+        &self,
+        texts: Vec<String>,
+        progress: EmbedProgress<'_>,
+    ) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+        let total = texts.len();
+        let embeddings = self.embed_batch(texts).await?;
+        progress(total, total);
+        Ok(embeddings)
+    }
+
+    /// Embed search queries, one vector per query.
+    async fn embed_queries(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>, EmbeddingError>;
+
+    /// Embed one search query.
+    async fn embed_query(&self, text: &str) -> Result<Vec<f32>, EmbeddingError> {
+        self.embed_queries(vec![text.to_string()])
+            .await?
+            .pop()
+            .ok_or_else(|| EmbeddingError::InvalidResponse("empty response".to_string()))
+    }
+
+    /// Score each document's relevance to `query`, one score per document.
+    async fn rerank(&self, query: &str, documents: Vec<String>)
+    -> Result<Vec<f32>, EmbeddingError>;
+}
 
 /// Client for generating embeddings via the daemon service.
 /// Batch management is handled by callers (source.rs, index.rs).
 pub struct EmbeddingClient {
     client: DaemonClient,
+    /// Model of the active collection, which may differ from the daemon's default
+    model: ModelSpec,
+    query_instruction: String,
+    document_instruction: String,
 }
 
 impl EmbeddingClient {
     pub fn new(config: &Config) -> Self {
         Self {
             client: DaemonClient::new(config),
+            model: ModelSpec::from_config(&config.embedding),
+            query_instruction: config.embedding.resolved_query_instruction().to_string(),
+            document_instruction: config.embedding.resolved_document_instruction().to_string(),
         }
     }
 
@@ -22,7 +70,35 @@ impl EmbeddingClient {
         }
 
         self.client
-            .embed(texts, false)
+            .embed(
+                texts,
+                false,
+                Some(self.model.clone()),
+                Some(self.document_instruction.clone()),
+            )
+            .await
+            .map_err(EmbeddingError::DaemonError)
+    }
+
+    /// [`Self::embed_batch`], with the daemon reporting `(done, total)` texts
+    /// through `progress` while it works.
+    pub async fn embed_batch_with_progress(
+        &self,
+        texts: Vec<String>,
+        progress: EmbedProgress<'_>,
+    ) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        self.client
+            .embed_with_progress(
+                texts,
+                false,
+                Some(self.model.clone()),
+                Some(self.document_instruction.clone()),
+                progress,
+            )
             .await
             .map_err(EmbeddingError::DaemonError)
     }
@@ -30,7 +106,12 @@ impl EmbeddingClient {
     pub async fn embed_query(&self, text: &str) -> Result<Vec<f32>, EmbeddingError> {
         let embeddings = self
             .client
-            .embed(vec![text.to_string()], true)
+            .embed(
+                vec![text.to_string()],
+                true,
+                Some(self.model.clone()),
+                Some(self.query_instruction.clone()),
+            )
             .await
             .map_err(EmbeddingError::DaemonError)?;
 
@@ -40,7 +121,93 @@ impl EmbeddingClient {
             .ok_or_else(|| EmbeddingError::InvalidResponse("empty response".to_string()))
     }
 
+    /// Embed several queries in one daemon call (query expansion).
+    pub async fn embed_queries(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let expected = texts.len();
+        let embeddings = self
+            .client
+            .embed(
+                texts,
+                true,
+                Some(self.model.clone()),
+                Some(self.query_instruction.clone()),
+            )
+            .await
+            .map_err(EmbeddingError::DaemonError)?;
+
+        if embeddings.len() != expected {
+            return Err(EmbeddingError::InvalidResponse(format!(
+                "expected {} embeddings, got {}",
+                expected,
+                embeddings.len()
+            )));
+        }
+        Ok(embeddings)
+    }
+
+    /// Re-score documents against a query with the daemon's cross-encoder.
+    pub async fn rerank(
+        &self,
+        query: &str,
+        documents: Vec<String>,
+    ) -> Result<Vec<f32>, EmbeddingError> {
+        if documents.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let expected = documents.len();
+        let scores = self
+            .client
+            .rerank(query.to_string(), documents)
+            .await
+            .map_err(EmbeddingError::DaemonError)?;
+
+        if scores.len() != expected {
+            return Err(EmbeddingError::InvalidResponse(format!(
+                "expected {} rerank scores, got {}",
+                expected,
+                scores.len()
+            )));
+        }
+        Ok(scores)
+    }
+
     pub fn is_daemon_running(&self) -> bool {
         self.client.is_running()
     }
 }
+
+#[async_trait]
+impl EmbeddingProvider for EmbeddingClient {
+    async fn embed_batch(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+        EmbeddingClient::embed_batch(self, texts).await
+    }
+
+    async fn embed_batch_with_progress(
+        &self,
+        texts: Vec<String>,
+        progress: EmbedProgress<'_>,
+    ) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+        EmbeddingClient::embed_batch_with_progress(self, texts, progress).await
+    }
+
+    async fn embed_queries(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+        EmbeddingClient::embed_queries(self, texts).await
+    }
+
+    async fn embed_query(&self, text: &str) -> Result<Vec<f32>, EmbeddingError> {
+        EmbeddingClient::embed_query(self, text).await
+    }
+
+    async fn rerank(
+        &self,
+        query: &str,
+        documents: Vec<String>,
+    ) -> Result<Vec<f32>, EmbeddingError> {
+        EmbeddingClient::rerank(self, query, documents).await
+    }
+}
@@ -0,0 +1,371 @@
+//! Relevance evaluation behind `ssearch eval`.
+//!
+//! A judgments file lists queries with the documents that should answer them,
+//! optionally graded. Each query's ranking is scored with recall@k, reciprocal
+//! rank and nDCG@k over distinct documents, and a run saved with `--save` can
+//! be compared query by query with a later one.
+
+use std::collections::{BTreeMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::EvalError;
+use crate::models::{RELATIVE_PATH_ATTRIBUTE, SearchResult};
+
+/// Documents scored per query when `-k` isn't given.
+pub const DEFAULT_EVAL_K: usize = 10;
+
+/// Chunks fetched per scored document, so repeated chunks of one document
+/// still leave k distinct documents.
+pub const EVAL_CANDIDATE_FACTOR: usize = 3;
+
+/// A labeled query: the documents that should answer it, with their grade.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Judgment {
+    pub query: String,
+    /// Document location, project-relative path or document ID → grade (≥ 1)
+    pub relevant: BTreeMap<String, u32>,
+}
+
+/// A judgments line: `relevant` lists documents, or maps them to grades.
+#[derive(Deserialize)]
+struct JudgmentLine {
+    query: String,
+    relevant: Relevant,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Relevant {
+    Listed(Vec<String>),
+    Graded(BTreeMap<String, u32>),
+}
+
+/// Parse a JSONL judgments file, one
+/// `{"query": "...", "relevant": ["src/auth.rs"]}` (or `{"src/auth.rs": 2}`)
+/// per line. Blank lines and lines starting with `#` are skipped.
+pub fn parse_judgments(text: &str) -> Result<Vec<Judgment>, EvalError> {
+    let mut judgments = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let invalid = |message: String| EvalError::InvalidJudgment {
+            line: i + 1,
+            message,
+        };
+        let parsed: JudgmentLine =
+            serde_json::from_str(line).map_err(|e| invalid(e.to_string()))?;
+        let relevant: BTreeMap<String, u32> = match parsed.relevant {
+            Relevant::Listed(documents) => documents.into_iter().map(|d| (d, 1)).collect(),
+            Relevant::Graded(grades) => grades.into_iter().filter(|(_, g)| *g > 0).collect(),
+        };
+        if parsed.query.trim().is_empty() {
+            return Err(invalid("query is empty".to_string()));
+        }
+        if relevant.is_empty() {
+            return Err(invalid("no relevant documents".to_string()));
+        }
+        judgments.push(Judgment {
+            query: parsed.query.trim().to_string(),
+            relevant,
+        });
+    }
+    if judgments.is_empty() {
+        return Err(EvalError::NoJudgments);
+    }
+    Ok(judgments)
+}
+
+/// Scores of one query's ranking.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueryEval {
+    pub query: String,
+    pub recall: f64,
+    pub reciprocal_rank: f64,
+    pub ndcg: f64,
+    /// Relevant documents ranked in the top k, in rank order
+    pub found: Vec<String>,
+    /// Relevant documents missing from the top k
+    pub missed: Vec<String>,
+}
+
+impl QueryEval {
+    /// Score `ranking` (best first) against `judgment`, counting each
+    /// document once, at the rank of its first chunk.
+    pub fn score(judgment: &Judgment, ranking: &[SearchResult], k: usize) -> Self {
+        let mut seen = HashSet::new();
+        let documents: Vec<&SearchResult> = ranking
+            .iter()
+            .filter(|result| seen.insert(result.document_id.as_str()))
+            .take(k)
+            .collect();
+
+        let mut found = Vec::new();
+        let mut dcg = 0.0;
+        let mut reciprocal_rank = 0.0;
+        for (rank, result) in documents.iter().enumerate() {
+            let Some(label) = judgment
+                .relevant
+                .keys()
+                .find(|label| matches_label(result, label) && !found.contains(*label))
+            else {
+                continue;
+            };
+            if found.is_empty() {
+                reciprocal_rank = 1.0 / (rank + 1) as f64;
+            }
+            dcg += gain(judgment.relevant[label], rank);
+            found.push(label.clone());
+        }
+
+        let mut grades: Vec<u32> = judgment.relevant.values().copied().collect();
+        grades.sort_unstable_by(|a, b| b.cmp(a));
+        let ideal: f64 = grades
+            .iter()
+            .take(k)
+            .enumerate()
+            .map(|(rank, &grade)| gain(grade, rank))
+            .sum();
+
+        let missed = judgment
+            .relevant
+            .keys()
+            .filter(|label| !found.contains(label))
+            .cloned()
+            .collect();
+        Self {
+            query: judgment.query.clone(),
+            recall: found.len() as f64 / judgment.relevant.len() as f64,
+            reciprocal_rank,
+            ndcg: if ideal > 0.0 { dcg / ideal } else { 0.0 },
+            found,
+            missed,
+        }
+    }
+}
+
+/// Discounted gain of a document graded `grade` at 0-based `rank`.
+fn gain(grade: u32, rank: usize) -> f64 {
+    (2f64.powi(grade as i32) - 1.0) / (rank as f64 + 2.0).log2()
+}
+
+/// A label names a result by its location (or a path suffix of it), its
+/// project-relative path or its document ID.
+fn matches_label(result: &SearchResult, label: &str) -> bool {
+    let location = result.source.location.as_str();
+    location == label
+        || location
+            .strip_suffix(label)
+            .is_some_and(|prefix| prefix.ends_with('/'))
+        || result
+            .attributes
+            .get(RELATIVE_PATH_ATTRIBUTE)
+            .is_some_and(|path| path == label)
+        || result.document_id == label
+}
+
+/// Scores of every judged query, averaged, with what produced them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvalRun {
+    pub k: usize,
+    pub collection: String,
+    pub model_id: String,
+    /// Search options that shaped the rankings (e.g., "hybrid", "rerank")
+    #[serde(default)]
+    pub modes: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub recall: f64,
+    pub mrr: f64,
+    pub ndcg: f64,
+    pub queries: Vec<QueryEval>,
+}
+
+impl EvalRun {
+    pub fn new(
+        k: usize,
+        collection: String,
+        model_id: String,
+        modes: Vec<String>,
+        queries: Vec<QueryEval>,
+    ) -> Self {
+        let mean = |metric: fn(&QueryEval) -> f64| {
+            if queries.is_empty() {
+                0.0
+            } else {
+                queries.iter().map(metric).sum::<f64>() / queries.len() as f64
+            }
+        };
+        Self {
+            k,
+            collection,
+            model_id,
+            modes,
+            created_at: Utc::now(),
+            recall: mean(|q| q.recall),
+            mrr: mean(|q| q.reciprocal_rank),
+            ndcg: mean(|q| q.ndcg),
+            queries,
+        }
+    }
+
+    /// Per-query nDCG changes against `baseline`, largest first; queries
+    /// only one of the runs judged are left out.
+    pub fn compare(&self, baseline: &EvalRun) -> Vec<QueryDelta> {
+        let before: BTreeMap<&str, &QueryEval> = baseline
+            .queries
+            .iter()
+            .map(|q| (q.query.as_str(), q))
+            .collect();
+        let mut deltas: Vec<QueryDelta> = self
+            .queries
+            .iter()
+            .filter_map(|after| {
+                let before = before.get(after.query.as_str())?;
+                Some(QueryDelta {
+                    query: after.query.clone(),
+                    before: before.ndcg,
+                    after: after.ndcg,
+                })
+            })
+            .collect();
+        deltas.sort_by(|a, b| b.change().abs().total_cmp(&a.change().abs()));
+        deltas
+    }
+}
+
+/// One query's nDCG in a baseline run and in the current one.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct QueryDelta {
+    pub query: String,
+    pub before: f64,
+    pub after: f64,
+}
+
+impl QueryDelta {
+    pub fn change(&self) -> f64 {
+        self.after - self.before
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Source;
+
+    fn result(location: &str, chunk: u32) -> SearchResult {
+        SearchResult {
+            chunk_id: format!("{location}#{chunk}"),
+            document_id: location.to_string(),
+            chunk_index: chunk,
+            score: 0.5,
+            content: String::new(),
+            location: location.to_string(),
+            source: Source::local(location),
+            tags: Vec::new(),
+            line_start: None,
+            line_end: None,
+            attributes: Default::default(),
+            updated_at: None,
+            context_before: Vec::new(),
+            context_after: Vec::new(),
+            explanation: None,
+            keyword_match: false,
+        }
+    }
+
+    #[test]
+    fn test_parse_judgments() {
+        let text = r#"
+# auth
+{"query": "token refresh", "relevant": ["src/auth.rs", "docs/auth.md"]}
+{"query": "retry policy", "relevant": {"src/retry.rs": 2, "src/old.rs": 0}}
+"#;
+        let judgments = parse_judgments(text).unwrap();
+        assert_eq!(judgments.len(), 2);
+        assert_eq!(judgments[0].relevant["src/auth.rs"], 1);
+        assert_eq!(judgments[1].relevant.len(), 1);
+        assert_eq!(judgments[1].relevant["src/retry.rs"], 2);
+
+        let error = parse_judgments("\n{\"query\": \"x\", \"relevant\": []}").unwrap_err();
+        assert!(error.to_string().starts_with("line 2:"));
+        assert!(matches!(
+            parse_judgments("# none"),
+            Err(EvalError::NoJudgments)
+        ));
+    }
+
+    #[test]
+    fn test_query_scores() {
+        let judgment = Judgment {
+            query: "token refresh".to_string(),
+            relevant: BTreeMap::from([
+                ("src/auth.rs".to_string(), 2),
+                ("docs/auth.md".to_string(), 1),
+            ]),
+        };
+        let ranking = vec![
+            result("/repo/src/main.rs", 0),
+            result("/repo/src/auth.rs", 0),
+            result("/repo/src/auth.rs", 1),
+            result("/repo/README.md", 0),
+        ];
+
+        let eval = QueryEval::score(&judgment, &ranking, 10);
+        assert_eq!(eval.found, vec!["src/auth.rs"]);
+        assert_eq!(eval.missed, vec!["docs/auth.md"]);
+        assert_eq!(eval.recall, 0.5);
+        assert_eq!(eval.reciprocal_rank, 0.5);
+        // Gain 3 at rank 2 against an ideal of 3 at rank 1 and 1 at rank 2
+        let ideal = 3.0 + 1.0 / 3f64.log2();
+        assert!((eval.ndcg - (3.0 / 3f64.log2()) / ideal).abs() < 1e-9);
+
+        // Only the first document counts at k = 1
+        let eval = QueryEval::score(&judgment, &ranking, 1);
+        assert_eq!(eval.recall, 0.0);
+        assert_eq!(eval.ndcg, 0.0);
+
+        let perfect = vec![
+            result("/repo/src/auth.rs", 0),
+            result("/repo/docs/auth.md", 0),
+        ];
+        let eval = QueryEval::score(&judgment, &perfect, 10);
+        assert_eq!((eval.recall, eval.reciprocal_rank), (1.0, 1.0));
+        assert!((eval.ndcg - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_run_comparison() {
+        let eval = |query: &str, ndcg: f64| QueryEval {
+            query: query.to_string(),
+            recall: ndcg,
+            reciprocal_rank: ndcg,
+            ndcg,
+            found: Vec::new(),
+            missed: Vec::new(),
+        };
+        let model = || "model".to_string();
+        let baseline = EvalRun::new(
+            10,
+            "docs".into(),
+            model(),
+            Vec::new(),
+            vec![eval("a", 0.5), eval("b", 0.9)],
+        );
+        let run = EvalRun::new(
+            10,
+            "docs".into(),
+            model(),
+            Vec::new(),
+            vec![eval("a", 0.6), eval("b", 0.4), eval("c", 1.0)],
+        );
+        assert!((run.ndcg - 2.0 / 3.0).abs() < 1e-9);
+
+        let deltas = run.compare(&baseline);
+        assert_eq!(deltas.len(), 2);
+        assert_eq!(deltas[0].query, "b");
+        assert!((deltas[0].change() + 0.5).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,157 @@
+//! Query expansion: reformulations of a terse query searched alongside it.
+
+use crate::error::LlmError;
+use crate::models::{LlmConfig, QueryExpansion};
+use crate::services::LlmClient;
+
+/// Reformulations searched besides the original query.
+pub const MAX_EXPANSIONS: usize = 3;
+
+/// Shorthand common in code and tickets, with the spelled-out term.
+const ABBREVIATIONS: &[(&str, &str)] = &[
+    ("api", "API endpoint"),
+    ("auth", "authentication"),
+    ("authn", "authentication"),
+    ("authz", "authorization"),
+    ("bug", "error"),
+    ("cfg", "configuration"),
+    ("config", "configuration"),
+    ("db", "database"),
+    ("deps", "dependencies"),
+    ("doc", "documentation"),
+    ("docs", "documentation"),
+    ("env", "environment"),
+    ("err", "error"),
+    ("impl", "implementation"),
+    ("k8s", "kubernetes"),
+    ("msg", "message"),
+    ("perf", "performance"),
+    ("repo", "repository"),
+    ("req", "request"),
+    ("resp", "response"),
+];
+
+const EXPANSION_PROMPT: &str = "You rewrite queries for a semantic search engine over code and \
+documentation. Reply with alternative phrasings of the user's query, one per line, with no \
+numbering or commentary. Spell out abbreviations and use the terms the documents would use.";
+
+/// The query followed by up to [`MAX_EXPANSIONS`] distinct reformulations.
+pub async fn expand_query(
+    query: &str,
+    mode: QueryExpansion,
+    llm: &LlmConfig,
+) -> Result<Vec<String>, LlmError> {
+    let expansions = match mode {
+        QueryExpansion::Off => Vec::new(),
+        QueryExpansion::Heuristic => heuristic_expansions(query),
+        QueryExpansion::Llm => {
+            let request = format!("Write {MAX_EXPANSIONS} phrasings of: {query}");
+            let reply = LlmClient::new(llm).chat(EXPANSION_PROMPT, &request).await?;
+            parse_expansions(&reply)
+        }
+    };
+
+    let mut queries = vec![query.to_string()];
+    for expansion in expansions {
+        if queries.len() > MAX_EXPANSIONS {
+            break;
+        }
+        if !queries.iter().any(|q| q.eq_ignore_ascii_case(&expansion)) {
+            queries.push(expansion);
+        }
+    }
+    Ok(queries)
+}
+
+/// Spell out known abbreviations, then phrase the query as a question
+/// about how it works and as a troubleshooting lookup.
+pub fn heuristic_expansions(query: &str) -> Vec<String> {
+    let spelled = query
+        .split_whitespace()
+        .map(|word| {
+            ABBREVIATIONS
+                .iter()
+                .find(|(short, _)| word.eq_ignore_ascii_case(short))
+                .map_or(word, |(_, long)| long)
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let mut expansions = Vec::new();
+    if spelled != query {
+        expansions.push(spelled.clone());
+    }
+    expansions.push(format!("how does {spelled} work"));
+    expansions.push(format!("{spelled} problem and fix"));
+    expansions
+}
+
+/// One reformulation per non-empty line, without list markers or quotes.
+fn parse_expansions(reply: &str) -> Vec<String> {
+    reply
+        .lines()
+        .map(|line| {
+            let line = line.trim();
+            let line = line.trim_start_matches(['-', '*', '•']);
+            let line = match line.split_once(['.', ')']) {
+                Some((n, rest)) if n.chars().all(|c| c.is_ascii_digit()) && !n.is_empty() => rest,
+                _ => line,
+            };
+            line.trim()
+                .trim_matches(['"', '\'', '`'])
+                .trim()
+                .to_string()
+        })
+        .filter(|line| !line.is_empty())
+        .take(MAX_EXPANSIONS)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heuristic_expansions() {
+        assert_eq!(
+            heuristic_expansions("auth bug"),
+            vec![
+                "authentication error",
+                "how does authentication error work",
+                "authentication error problem and fix",
+            ]
+        );
+        // Nothing to spell out: only the templates
+        assert_eq!(
+            heuristic_expansions("retry backoff"),
+            vec![
+                "how does retry backoff work",
+                "retry backoff problem and fix"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_expansions() {
+        let reply = "1. authentication failure\n2) \"login error\"\n\n- session token bug\n* extra";
+        assert_eq!(
+            parse_expansions(reply),
+            vec!["authentication failure", "login error", "session token bug"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_expand_query() {
+        let llm = LlmConfig::default();
+        let queries = expand_query("auth bug", QueryExpansion::Heuristic, &llm)
+            .await
+            .unwrap();
+        assert_eq!(queries.len(), 1 + MAX_EXPANSIONS);
+        assert_eq!(queries[0], "auth bug");
+
+        let queries = expand_query("auth bug", QueryExpansion::Off, &llm)
+            .await
+            .unwrap();
+        assert_eq!(queries, vec!["auth bug"]);
+    }
+}
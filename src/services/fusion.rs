@@ -0,0 +1,319 @@
+//! Rank fusion for combining multiple result lists.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+
+use crate::models::{RankedScore, ScoreExplanation, SearchResult, Tag};
+
+/// Reciprocal Rank Fusion smoothing constant.
+pub const RRF_K: f32 = 60.0;
+
+/// Candidate multiplier when scores are adjusted by recency, so fresher
+/// documents ranked just past the limit can move up.
+pub const RECENCY_CANDIDATE_FACTOR: u32 = 3;
+
+/// Candidate multiplier when near-duplicate results are suppressed, so the
+/// limit can still be filled after duplicates are dropped.
+pub const DEDUP_CANDIDATE_FACTOR: u32 = 3;
+
+/// Fuse ranked result lists with Reciprocal Rank Fusion.
+///
+/// Results are matched by `chunk_id`. Fused scores are normalized so that a
+/// result ranked first in every list scores 1.0.
+pub fn reciprocal_rank_fusion(lists: Vec<Vec<SearchResult>>, limit: usize) -> Vec<SearchResult> {
+    let list_count = lists.iter().filter(|l| !l.is_empty()).count();
+    if list_count == 0 {
+        return Vec::new();
+    }
+    let max_score = list_count as f32 / (RRF_K + 1.0);
+
+    let mut fused: HashMap<String, (f32, SearchResult)> = HashMap::new();
+    let mut order: Vec<String> = Vec::new();
+
+    for list in lists {
+        for (rank, result) in list.into_iter().enumerate() {
+            let contribution = 1.0 / (RRF_K + rank as f32 + 1.0);
+            match fused.get_mut(&result.chunk_id) {
+                Some((score, _)) => *score += contribution,
+                None => {
+                    order.push(result.chunk_id.clone());
+                    fused.insert(result.chunk_id.clone(), (contribution, result));
+                }
+            }
+        }
+    }
+
+    let mut results: Vec<SearchResult> = order
+        .into_iter()
+        .filter_map(|id| fused.remove(&id))
+        .map(|(score, mut result)| {
+            result.score = score / max_score;
+            result
+        })
+        .collect();
+
+    results.sort_by(|a, b| b.score.total_cmp(&a.score));
+    results.truncate(limit);
+    results
+}
+
+/// Replace result scores (e.g., with reranker scores) and re-sort.
+///
+/// `scores` must be aligned with `results`.
+pub fn rescore(mut results: Vec<SearchResult>, scores: &[f32], limit: usize) -> Vec<SearchResult> {
+    for (result, &score) in results.iter_mut().zip(scores) {
+        result.score = score;
+    }
+    results.sort_by(|a, b| b.score.total_cmp(&a.score));
+    results.truncate(limit);
+    results
+}
+
+/// Blend document freshness into scores and re-sort.
+///
+/// Each score keeps `1 - weight` of its value and gains `weight` times an
+/// exponential decay on the document's age that halves every
+/// `half_life_days`. Results without a timestamp are left unchanged.
+pub fn apply_recency(
+    mut results: Vec<SearchResult>,
+    weight: f32,
+    half_life_days: f32,
+    now: DateTime<Utc>,
+) -> Vec<SearchResult> {
+    if weight <= 0.0 || half_life_days <= 0.0 {
+        return results;
+    }
+    let weight = weight.min(1.0);
+
+    for result in &mut results {
+        if let Some(updated_at) = result.updated_at {
+            let age_days = (now - updated_at).num_seconds().max(0) as f32 / 86_400.0;
+            let decay = 0.5_f32.powf(age_days / half_life_days);
+            result.score *= 1.0 - weight + weight * decay;
+        }
+    }
+    results.sort_by(|a, b| b.score.total_cmp(&a.score));
+    results
+}
+
+/// Drop results whose vector is at least `threshold` cosine-similar to a
+/// higher-ranked result that was kept.
+///
+/// `vectors` maps chunk IDs to their dense vectors; results without one are kept.
+pub fn suppress_near_duplicates(
+    results: Vec<SearchResult>,
+    vectors: &HashMap<String, Vec<f32>>,
+    threshold: f32,
+) -> Vec<SearchResult> {
+    let mut kept_vectors: Vec<&[f32]> = Vec::new();
+    results
+        .into_iter()
+        .filter(|result| {
+            let Some(vector) = vectors.get(&result.chunk_id) else {
+                return true;
+            };
+            if kept_vectors
+                .iter()
+                .any(|kept| cosine_similarity(kept, vector) >= threshold)
+            {
+                return false;
+            }
+            kept_vectors.push(vector);
+            true
+        })
+        .collect()
+}
+
+/// Percentage of `history` scores at or below `score`, or `None` without history.
+pub fn score_percentile(score: f32, history: &[f32]) -> Option<f32> {
+    if history.is_empty() {
+        return None;
+    }
+    let below = history.iter().filter(|&&s| s <= score).count();
+    Some(below as f32 * 100.0 / history.len() as f32)
+}
+
+/// Attach a [`ScoreExplanation`] to each result.
+///
+/// `dense` is the original query's semantic ranking, which supplies the raw
+/// cosine score; `keyword` is the BM25 ranking of a hybrid search. Both are
+/// matched by `chunk_id`, and `history` holds recent searches' top scores.
+pub fn explain_scores(
+    results: &mut [SearchResult],
+    dense: &[SearchResult],
+    keyword: Option<&[SearchResult]>,
+    filter_tags: &[Tag],
+    history: &[f32],
+) {
+    let ranked = |list: &[SearchResult], chunk_id: &str| {
+        list.iter()
+            .position(|r| r.chunk_id == chunk_id)
+            .map(|i| RankedScore {
+                rank: i as u32 + 1,
+                score: list[i].score,
+            })
+    };
+
+    for result in results {
+        let dense_rank = ranked(dense, &result.chunk_id);
+        let raw_score = dense_rank.map(|r| r.score);
+        result.explanation = Some(ScoreExplanation {
+            raw_score,
+            percentile: raw_score.and_then(|s| score_percentile(s, history)),
+            matched_tags: filter_tags
+                .iter()
+                .filter(|t| result.tags.contains(t))
+                .cloned()
+                .collect(),
+            dense: dense_rank.filter(|_| keyword.is_some()),
+            keyword: keyword.and_then(|k| ranked(k, &result.chunk_id)),
+        });
+    }
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a * norm_b)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::models::Source;
+
+    fn result(id: &str) -> SearchResult {
+        SearchResult {
+            chunk_id: id.to_string(),
+            document_id: id.to_string(),
+            chunk_index: 0,
+            score: 0.0,
+            content: String::new(),
+            source: Source::local(id),
+            tags: Vec::new(),
+            location: id.to_string(),
+            line_start: None,
+            line_end: None,
+            attributes: BTreeMap::new(),
+            updated_at: None,
+            context_before: Vec::new(),
+            context_after: Vec::new(),
+            explanation: None,
+            keyword_match: false,
+        }
+    }
+
+    #[test]
+    fn test_rrf_prefers_results_in_both_lists() {
+        let dense = vec![result("a"), result("b"), result("c")];
+        let keyword = vec![result("c"), result("d")];
+
+        let fused = reciprocal_rank_fusion(vec![dense, keyword], 10);
+
+        assert_eq!(fused.len(), 4);
+        assert_eq!(fused[0].chunk_id, "c");
+        assert!(fused.iter().all(|r| r.score > 0.0 && r.score <= 1.0));
+    }
+
+    #[test]
+    fn test_rrf_top_in_all_lists_scores_one() {
+        let fused = reciprocal_rank_fusion(vec![vec![result("a")], vec![result("a")]], 10);
+        assert!((fused[0].score - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_rescore_reorders() {
+        let results = vec![result("a"), result("b"), result("c")];
+        let rescored = rescore(results, &[0.1, 0.9, 0.5], 2);
+        let ids: Vec<&str> = rescored.iter().map(|r| r.chunk_id.as_str()).collect();
+        assert_eq!(ids, vec!["b", "c"]);
+    }
+
+    #[test]
+    fn test_recency_prefers_fresh_documents() {
+        let now = Utc::now();
+        let mut old = result("old");
+        old.score = 0.8;
+        old.updated_at = Some(now - chrono::TimeDelta::days(90));
+        let mut fresh = result("fresh");
+        fresh.score = 0.7;
+        fresh.updated_at = Some(now - chrono::TimeDelta::days(1));
+        let mut undated = result("undated");
+        undated.score = 0.75;
+
+        let boosted = apply_recency(vec![old, fresh, undated], 0.5, 30.0, now);
+        let ids: Vec<&str> = boosted.iter().map(|r| r.chunk_id.as_str()).collect();
+        assert_eq!(ids, vec!["undated", "fresh", "old"]);
+        assert!((boosted[0].score - 0.75).abs() < 1e-6);
+
+        let unchanged = apply_recency(vec![result("a")], 0.0, 30.0, now);
+        assert_eq!(unchanged[0].score, 0.0);
+    }
+
+    #[test]
+    fn test_suppress_near_duplicates_keeps_higher_ranked() {
+        let vectors = HashMap::from([
+            ("a".to_string(), vec![1.0, 0.0]),
+            ("b".to_string(), vec![0.99, 0.05]),
+            ("c".to_string(), vec![0.0, 1.0]),
+        ]);
+        let results = vec![result("a"), result("b"), result("c"), result("d")];
+
+        let deduped = suppress_near_duplicates(results, &vectors, 0.95);
+        let ids: Vec<&str> = deduped.iter().map(|r| r.chunk_id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "c", "d"]);
+
+        let results = vec![result("a"), result("b")];
+        assert_eq!(suppress_near_duplicates(results, &vectors, 1.0).len(), 2);
+    }
+
+    #[test]
+    fn test_rrf_respects_limit() {
+        let fused = reciprocal_rank_fusion(vec![vec![result("a"), result("b")]], 1);
+        assert_eq!(fused.len(), 1);
+    }
+
+    #[test]
+    fn test_explain_scores() {
+        let tag: Tag = "source:jira".parse().unwrap();
+        let mut a = result("a");
+        a.tags.push(tag.clone());
+        let mut results = vec![a, result("b")];
+        let mut dense = vec![result("b"), result("a")];
+        dense[0].score = 0.9;
+        dense[1].score = 0.6;
+        let keyword = vec![result("a")];
+
+        explain_scores(
+            &mut results,
+            &dense,
+            Some(&keyword),
+            std::slice::from_ref(&tag),
+            &[0.5, 0.7, 0.8, 0.95],
+        );
+
+        let a = results[0].explanation.as_ref().unwrap();
+        assert_eq!(a.raw_score, Some(0.6));
+        assert_eq!(a.percentile, Some(25.0));
+        assert_eq!(a.matched_tags, vec![tag]);
+        assert_eq!(a.dense.unwrap().rank, 2);
+        assert_eq!(a.keyword.unwrap().rank, 1);
+
+        let b = results[1].explanation.as_ref().unwrap();
+        assert_eq!(b.percentile, Some(75.0));
+        assert!(b.matched_tags.is_empty());
+        assert!(b.keyword.is_none());
+
+        // Without a keyword ranking there is no hybrid breakdown
+        explain_scores(&mut results, &dense, None, &[], &[]);
+        let a = results[0].explanation.as_ref().unwrap();
+        assert!(a.dense.is_none() && a.percentile.is_none());
+    }
+}
@@ -0,0 +1,232 @@
+//! Checks behind `ssearch health`.
+//!
+//! Each check passes, warns (degraded but usable, e.g. the daemon will be
+//! started on demand) or fails (searches or indexing won't work).
+
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+use serde::Serialize;
+
+use super::vector_store::CollectionInfo;
+
+/// Free space under which the cache directory check warns.
+pub const DISK_WARN_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Free space under which the cache directory check fails; a model download no longer fits.
+pub const DISK_FAIL_BYTES: u64 = 200 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl fmt::Display for CheckStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CheckStatus::Pass => write!(f, "pass"),
+            CheckStatus::Warn => write!(f, "warn"),
+            CheckStatus::Fail => write!(f, "fail"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthCheck {
+    pub name: String,
+    pub status: CheckStatus,
+    pub message: String,
+}
+
+impl HealthCheck {
+    pub fn new(name: impl Into<String>, status: CheckStatus, message: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status,
+            message: message.into(),
+        }
+    }
+
+    pub fn pass(name: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::new(name, CheckStatus::Pass, message)
+    }
+
+    pub fn warn(name: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::new(name, CheckStatus::Warn, message)
+    }
+
+    pub fn fail(name: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::new(name, CheckStatus::Fail, message)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct HealthReport {
+    pub checks: Vec<HealthCheck>,
+}
+
+impl HealthReport {
+    pub fn push(&mut self, check: HealthCheck) {
+        self.checks.push(check);
+    }
+
+    /// The worst status of any check.
+    pub fn status(&self) -> CheckStatus {
+        self.checks
+            .iter()
+            .map(|check| check.status)
+            .max()
+            .unwrap_or(CheckStatus::Pass)
+    }
+
+    pub fn failed(&self) -> usize {
+        self.checks
+            .iter()
+            .filter(|check| check.status == CheckStatus::Fail)
+            .count()
+    }
+}
+
+/// Compare the dimension the daemon's model produced with `[embedding] dimension`.
+pub fn embedding_check(model_id: &str, produced: usize, configured: u32) -> HealthCheck {
+    if produced == configured as usize {
+        HealthCheck::pass(
+            "embedding",
+            format!("{model_id} produces {produced}-dimensional embeddings"),
+        )
+    } else {
+        HealthCheck::fail(
+            "embedding",
+            format!(
+                "{model_id} produces {produced}-dimensional embeddings but [embedding] dimension is {configured}"
+            ),
+        )
+    }
+}
+
+/// Compare the collection's vectors with the dimension searches will embed at.
+pub fn collection_check(
+    collection: &str,
+    info: Option<&CollectionInfo>,
+    expected: u64,
+) -> HealthCheck {
+    let Some(info) = info else {
+        return HealthCheck::warn(
+            "collection",
+            format!("'{collection}' doesn't exist yet; the first index creates it"),
+        );
+    };
+    match info.dimension {
+        Some(dimension) if dimension != expected => HealthCheck::fail(
+            "collection",
+            format!(
+                "'{collection}' stores {dimension}-dimensional vectors but the model produces {expected}; run `ssearch collection migrate --re-embed`"
+            ),
+        ),
+        _ => HealthCheck::pass(
+            "collection",
+            format!("'{collection}' holds {} points", info.points_count),
+        ),
+    }
+}
+
+/// Rate the free space left for models, metrics and snapshots under `dir`.
+pub fn disk_space_check(dir: &Path, available: u64) -> HealthCheck {
+    let status = if available < DISK_FAIL_BYTES {
+        CheckStatus::Fail
+    } else if available < DISK_WARN_BYTES {
+        CheckStatus::Warn
+    } else {
+        CheckStatus::Pass
+    };
+    HealthCheck::new(
+        "disk",
+        status,
+        format!(
+            "{} MB free for {}",
+            available / (1024 * 1024),
+            dir.display()
+        ),
+    )
+}
+
+/// Bytes available to unprivileged users on the file system holding `path`,
+/// or the nearest ancestor that exists.
+pub fn available_space(path: &Path) -> io::Result<u64> {
+    let existing = path
+        .ancestors()
+        .find(|dir| dir.exists())
+        .unwrap_or(Path::new("/"));
+    let stats = nix::sys::statvfs::statvfs(existing).map_err(io::Error::from)?;
+    Ok(stats.blocks_available() as u64 * stats.fragment_size() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_status() {
+        let mut report = HealthReport::default();
+        assert_eq!(report.status(), CheckStatus::Pass);
+
+        report.push(HealthCheck::pass("daemon", "ok"));
+        report.push(HealthCheck::warn("cli:gh", "not installed"));
+        assert_eq!(report.status(), CheckStatus::Warn);
+        assert_eq!(report.failed(), 0);
+
+        report.push(HealthCheck::fail("vector_store", "refused"));
+        assert_eq!(report.status(), CheckStatus::Fail);
+        assert_eq!(report.failed(), 1);
+    }
+
+    #[test]
+    fn test_dimension_checks() {
+        assert_eq!(embedding_check("bge", 1024, 1024).status, CheckStatus::Pass);
+        assert_eq!(embedding_check("bge", 384, 1024).status, CheckStatus::Fail);
+
+        assert_eq!(
+            collection_check("docs", None, 1024).status,
+            CheckStatus::Warn
+        );
+        let info = CollectionInfo {
+            points_count: 10,
+            dimension: Some(384),
+        };
+        assert_eq!(
+            collection_check("docs", Some(&info), 1024).status,
+            CheckStatus::Fail
+        );
+        assert_eq!(
+            collection_check("docs", Some(&info), 384).status,
+            CheckStatus::Pass
+        );
+        let unknown = CollectionInfo {
+            points_count: 10,
+            dimension: None,
+        };
+        assert_eq!(
+            collection_check("docs", Some(&unknown), 1024).status,
+            CheckStatus::Pass
+        );
+    }
+
+    #[test]
+    fn test_disk_space_check() {
+        let dir = Path::new("/tmp");
+        assert_eq!(disk_space_check(dir, 0).status, CheckStatus::Fail);
+        assert_eq!(
+            disk_space_check(dir, DISK_FAIL_BYTES).status,
+            CheckStatus::Warn
+        );
+        assert_eq!(
+            disk_space_check(dir, DISK_WARN_BYTES).status,
+            CheckStatus::Pass
+        );
+        assert!(available_space(&std::env::temp_dir().join("missing/child")).is_ok());
+    }
+}
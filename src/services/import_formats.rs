@@ -0,0 +1,558 @@
+//! Import adapters for CSV exports, Markdown notes and Obsidian vaults.
+//! Tabular CSV/Parquet rows are handled by `services::tabular`.
+//!
+//! Each adapter turns its input into plain [`Document`]s: CSV rows through a
+//! column mapping, Markdown files with their YAML frontmatter lifted into the
+//! title, tags and attributes, and Obsidian notes with `[[wiki links]]`
+//! resolved to the vault paths they point at.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde_yaml::Value;
+
+use crate::error::ImportError;
+use crate::models::{Document, DocumentMetadata, Source, SourceType, Tag};
+use crate::utils::file::{calculate_checksum, detect_language, walk_files};
+use crate::utils::text::slug;
+
+/// Attribute key holding the vault paths an Obsidian note links to, comma-separated.
+pub const LINKS_ATTRIBUTE: &str = "links";
+
+/// `[[target]]`, `[[target#heading]]`, `[[target|alias]]` and `![[embeds]]`.
+static WIKI_LINK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"!?\[\[([^\[\]|#^]+)(?:[#^][^\[\]|]*)?(?:\|[^\[\]]*)?\]\]").expect("valid regex")
+});
+
+/// Obsidian inline tags: `#tag` or `#nested/tag` after whitespace or at line start.
+static INLINE_TAG: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)(?:^|[\s(])#([\p{L}\p{N}_][\p{L}\p{N}_/-]*)").expect("valid regex")
+});
+
+/// Layout of an import file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ImportFormat {
+    /// JSON array or JSON Lines of `{content, title, path, url, tags}`
+    #[default]
+    Json,
+    /// One document per row, mapped with [`CsvColumns`]
+    Csv,
+    /// Markdown files with optional YAML frontmatter
+    Markdown,
+    /// An Obsidian vault directory
+    Obsidian,
+    /// CSV or Parquet rows rendered as text, several rows per document
+    Table,
+}
+
+impl fmt::Display for ImportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportFormat::Json => write!(f, "json"),
+            ImportFormat::Csv => write!(f, "csv"),
+            ImportFormat::Markdown => write!(f, "markdown"),
+            ImportFormat::Obsidian => write!(f, "obsidian"),
+            ImportFormat::Table => write!(f, "table"),
+        }
+    }
+}
+
+impl FromStr for ImportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" | "jsonl" => Ok(ImportFormat::Json),
+            "csv" => Ok(ImportFormat::Csv),
+            "markdown" | "md" => Ok(ImportFormat::Markdown),
+            "obsidian" => Ok(ImportFormat::Obsidian),
+            "table" | "tabular" | "parquet" => Ok(ImportFormat::Table),
+            _ => Err(format!("unknown import format: {}", s)),
+        }
+    }
+}
+
+/// Which CSV columns hold each document field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvColumns {
+    pub content: String,
+    pub title: Option<String>,
+    pub path: Option<String>,
+    pub url: Option<String>,
+    /// Columns whose values become `<column>:<value>` tags
+    pub tags: Vec<String>,
+}
+
+impl Default for CsvColumns {
+    fn default() -> Self {
+        Self {
+            content: "content".to_string(),
+            title: None,
+            path: None,
+            url: None,
+            tags: Vec::new(),
+        }
+    }
+}
+
+/// Build a document per CSV row. Rows with empty content are left out.
+///
+/// Rows are located by their url or path column, else by `location#row<n>`.
+pub fn csv_documents(
+    input: &str,
+    location: &str,
+    columns: &CsvColumns,
+    source_type: &SourceType,
+    tags: &[Tag],
+) -> Result<Vec<Document>, ImportError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(input.as_bytes());
+    let headers = reader.headers()?.clone();
+    let index = |name: &str| {
+        headers.iter().position(|h| h == name).ok_or_else(|| {
+            ImportError::ValidationError(format!(
+                "CSV has no '{}' column (columns: {})",
+                name,
+                headers.iter().collect::<Vec<_>>().join(", ")
+            ))
+        })
+    };
+    let optional = |name: &Option<String>| name.as_deref().map(index).transpose();
+
+    let content_col = index(&columns.content)?;
+    let title_col = optional(&columns.title)?;
+    let path_col = optional(&columns.path)?;
+    let url_col = optional(&columns.url)?;
+    let tag_cols = columns
+        .tags
+        .iter()
+        .map(|name| Ok((slug(name), index(name)?)))
+        .collect::<Result<Vec<_>, ImportError>>()?;
+
+    let mut documents = Vec::new();
+    for (row, record) in reader.records().enumerate() {
+        let record = record?;
+        let field = |col: Option<usize>| {
+            col.and_then(|i| record.get(i))
+                .filter(|v| !v.is_empty())
+                .map(String::from)
+        };
+
+        let Some(content) = field(Some(content_col)) else {
+            continue;
+        };
+        let url = field(url_col);
+        let path = field(path_col);
+        let location = url
+            .clone()
+            .or_else(|| path.clone())
+            .unwrap_or_else(|| format!("{}#row{}", location, row + 1));
+
+        let mut doc_tags = tags.to_vec();
+        for (key, col) in &tag_cols {
+            if let Some(value) = field(Some(*col)) {
+                push_tag(&mut doc_tags, &format!("{}:{}", key, slug(&value)));
+            }
+        }
+
+        let metadata = DocumentMetadata {
+            filename: None,
+            extension: None,
+            language: path.as_deref().and_then(|p| detect_language(Path::new(p))),
+            title: field(title_col),
+            path,
+            size_bytes: content.len() as u64,
+        };
+        let checksum = calculate_checksum(&content);
+        let source = Source::new(source_type.clone(), location, url);
+        documents.push(Document::new(content, source, doc_tags, checksum, metadata));
+    }
+
+    Ok(documents)
+}
+
+/// Title, tags and scalar attributes read from a note's YAML frontmatter.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Frontmatter {
+    pub title: Option<String>,
+    pub tags: Vec<String>,
+    pub aliases: Vec<String>,
+    pub attributes: BTreeMap<String, String>,
+}
+
+/// Split a note into its frontmatter and body.
+///
+/// Notes without a leading `---` block, or whose block isn't a YAML mapping,
+/// are returned whole as the body.
+pub fn parse_frontmatter(input: &str) -> (Frontmatter, &str) {
+    let Some(rest) = input
+        .strip_prefix("---\n")
+        .or_else(|| input.strip_prefix("---\r\n"))
+    else {
+        return (Frontmatter::default(), input);
+    };
+    let Some((yaml, body)) = ["\n---\n", "\n---\r\n", "\n...\n"]
+        .iter()
+        .filter_map(|fence| {
+            rest.find(fence)
+                .map(|i| (&rest[..i], &rest[i + fence.len()..]))
+        })
+        .min_by_key(|(yaml, _)| yaml.len())
+        .or_else(|| rest.strip_suffix("\n---").map(|yaml| (yaml, "")))
+    else {
+        return (Frontmatter::default(), input);
+    };
+    let Ok(Value::Mapping(mapping)) = serde_yaml::from_str::<Value>(yaml) else {
+        return (Frontmatter::default(), input);
+    };
+
+    let mut frontmatter = Frontmatter::default();
+    for (key, value) in mapping {
+        let Some(key) = key.as_str() else {
+            continue;
+        };
+        match key.to_lowercase().as_str() {
+            "title" => frontmatter.title = scalar(&value),
+            "tags" | "tag" => frontmatter.tags.extend(list(&value)),
+            "aliases" | "alias" => frontmatter.aliases.extend(list(&value)),
+            other => {
+                let key = slug(other);
+                if let Some(value) = scalar(&value)
+                    && !key.is_empty()
+                {
+                    frontmatter.attributes.insert(key, value);
+                }
+            }
+        }
+    }
+    (frontmatter, body)
+}
+
+/// Build a document from a Markdown note, frontmatter lifted into metadata.
+///
+/// The title falls back to the first `# heading`, then the file stem.
+/// Frontmatter tags without a key become `tag:<name>`.
+pub fn markdown_document(
+    input: &str,
+    location: &str,
+    source_type: &SourceType,
+    tags: &[Tag],
+) -> Document {
+    let (frontmatter, body) = parse_frontmatter(input);
+    let path = Path::new(location);
+
+    let mut doc_tags = tags.to_vec();
+    for tag in &frontmatter.tags {
+        push_tag(&mut doc_tags, &note_tag(tag));
+    }
+
+    let title = frontmatter
+        .title
+        .clone()
+        .or_else(|| first_heading(body))
+        .or_else(|| path.file_stem().map(|s| s.to_string_lossy().into_owned()));
+    let metadata = DocumentMetadata {
+        filename: path.file_name().map(|s| s.to_string_lossy().into_owned()),
+        extension: path.extension().map(|s| s.to_string_lossy().into_owned()),
+        language: Some("markdown".to_string()),
+        title,
+        path: Some(location.to_string()),
+        size_bytes: body.len() as u64,
+    };
+    let content = body.trim_start().to_string();
+    let checksum = calculate_checksum(&content);
+    let source = Source::new(source_type.clone(), location.to_string(), None);
+    Document::new(content, source, doc_tags, checksum, metadata)
+        .with_attributes(frontmatter.attributes)
+}
+
+/// Markdown files under `path` (or `path` itself), skipping hidden directories
+/// such as `.obsidian` and `.trash`.
+pub fn markdown_files(path: &Path) -> Result<Vec<PathBuf>, ImportError> {
+    let files = walk_files(path, true).map_err(std::io::Error::other)?;
+    let mut files: Vec<PathBuf> = files
+        .into_iter()
+        .filter(|file| {
+            file.extension().is_some_and(|ext| {
+                ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("markdown")
+            })
+        })
+        .filter(|file| {
+            file.strip_prefix(path)
+                .unwrap_or(file)
+                .components()
+                .all(|c| !c.as_os_str().to_string_lossy().starts_with('.'))
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Read an Obsidian vault and build a document per note.
+pub fn obsidian_documents(
+    vault: &Path,
+    source_type: &SourceType,
+    tags: &[Tag],
+) -> Result<Vec<Document>, ImportError> {
+    if !vault.is_dir() {
+        return Err(ImportError::ValidationError(format!(
+            "{} is not an Obsidian vault directory",
+            vault.display()
+        )));
+    }
+    let mut notes = Vec::new();
+    for file in markdown_files(vault)? {
+        let relative = file
+            .strip_prefix(vault)
+            .unwrap_or(&file)
+            .to_string_lossy()
+            .replace('\\', "/");
+        notes.push((relative, std::fs::read_to_string(&file)?));
+    }
+    Ok(vault_documents(vault, &notes, source_type, tags))
+}
+
+/// Build documents from vault notes given as `(vault-relative path, content)`.
+///
+/// Inline `#tags` join the frontmatter tags, and wiki links that resolve to a
+/// note in the vault are stored in the [`LINKS_ATTRIBUTE`] attribute.
+pub fn vault_documents(
+    vault: &Path,
+    notes: &[(String, String)],
+    source_type: &SourceType,
+    tags: &[Tag],
+) -> Vec<Document> {
+    let resolver = LinkResolver::new(notes);
+    notes
+        .iter()
+        .map(|(relative, content)| {
+            let location = vault.join(relative).display().to_string();
+            let mut document = markdown_document(content, &location, source_type, tags);
+
+            for capture in INLINE_TAG.captures_iter(&document.content) {
+                let name = &capture[1];
+                // Obsidian ignores all-numeric tags such as issue numbers
+                if !name.chars().all(|c| c.is_ascii_digit()) {
+                    push_tag(&mut document.tags, &note_tag(name));
+                }
+            }
+
+            let links: BTreeSet<&str> = WIKI_LINK
+                .captures_iter(&document.content)
+                .filter_map(|capture| resolver.resolve(&capture[1]))
+                .filter(|target| target != relative)
+                .collect();
+            if !links.is_empty() {
+                document.attributes.insert(
+                    LINKS_ATTRIBUTE.to_string(),
+                    links.into_iter().collect::<Vec<_>>().join(","),
+                );
+            }
+            document
+        })
+        .collect()
+}
+
+/// Resolves wiki link targets the way Obsidian does: by vault path, note
+/// name or alias, case-insensitively. Ambiguous names go to the shortest path.
+struct LinkResolver<'a> {
+    targets: HashMap<String, &'a str>,
+}
+
+impl<'a> LinkResolver<'a> {
+    fn new(notes: &'a [(String, String)]) -> Self {
+        let mut by_length: Vec<&(String, String)> = notes.iter().collect();
+        by_length.sort_by(|a, b| a.0.len().cmp(&b.0.len()).then_with(|| a.0.cmp(&b.0)));
+
+        let mut targets: HashMap<String, &'a str> = HashMap::new();
+        for (relative, content) in by_length {
+            let without_ext = strip_markdown_ext(relative);
+            let name = without_ext.rsplit('/').next().unwrap_or(without_ext);
+            let aliases = parse_frontmatter(content).0.aliases;
+            for key in [without_ext, name]
+                .into_iter()
+                .map(str::to_string)
+                .chain(aliases)
+            {
+                targets.entry(key.to_lowercase()).or_insert(relative);
+            }
+        }
+        Self { targets }
+    }
+
+    fn resolve(&self, target: &str) -> Option<&'a str> {
+        let target = strip_markdown_ext(target.trim().trim_start_matches('/'));
+        self.targets.get(&target.to_lowercase()).copied()
+    }
+}
+
+fn strip_markdown_ext(path: &str) -> &str {
+    path.strip_suffix(".md")
+        .or_else(|| path.strip_suffix(".markdown"))
+        .unwrap_or(path)
+}
+
+/// A frontmatter or inline tag as a [`Tag`] string: `key:value` is kept,
+/// anything else becomes `tag:<name>` with `/` nesting flattened to `-`.
+fn note_tag(tag: &str) -> String {
+    let tag = tag.trim().trim_start_matches('#');
+    if tag.parse::<Tag>().is_ok() {
+        tag.to_string()
+    } else {
+        format!("tag:{}", slug(tag))
+    }
+}
+
+fn push_tag(tags: &mut Vec<Tag>, tag_str: &str) {
+    if let Ok(tag) = tag_str.parse::<Tag>()
+        && !tags.contains(&tag)
+    {
+        tags.push(tag);
+    }
+}
+
+fn first_heading(body: &str) -> Option<String> {
+    body.lines()
+        .find_map(|line| line.strip_prefix("# "))
+        .map(|heading| heading.trim().to_string())
+        .filter(|heading| !heading.is_empty())
+}
+
+fn scalar(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// A YAML list, or a string of comma- or space-separated items.
+fn list(value: &Value) -> Vec<String> {
+    match value {
+        Value::Sequence(items) => items.iter().filter_map(scalar).collect(),
+        Value::String(s) => s
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|item| !item.is_empty())
+            .map(String::from)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tag_strings(document: &Document) -> Vec<String> {
+        document.tags.iter().map(|t| t.to_string()).collect()
+    }
+
+    #[test]
+    fn test_csv_documents() {
+        let input = "id,body,Team,file\n1,First row,Core Platform,src/lib.rs\n2,,core,\n3,\"Third, quoted\",web,\n";
+        let columns = CsvColumns {
+            content: "body".to_string(),
+            title: Some("id".to_string()),
+            path: Some("file".to_string()),
+            tags: vec!["Team".to_string()],
+            ..Default::default()
+        };
+        let documents = csv_documents(
+            input,
+            "faq.csv",
+            &columns,
+            &SourceType::Other("faq".into()),
+            &[],
+        )
+        .unwrap();
+
+        assert_eq!(documents.len(), 2);
+        assert_eq!(documents[0].source.location, "src/lib.rs");
+        assert_eq!(documents[0].metadata.language.as_deref(), Some("rust"));
+        assert_eq!(documents[0].metadata.title.as_deref(), Some("1"));
+        assert_eq!(tag_strings(&documents[0]), vec!["team:core-platform"]);
+        assert_eq!(documents[1].content, "Third, quoted");
+        assert_eq!(documents[1].source.location, "faq.csv#row3");
+
+        let missing = CsvColumns::default();
+        let err = csv_documents(input, "faq.csv", &missing, &SourceType::Local, &[]).unwrap_err();
+        assert!(err.to_string().contains("no 'content' column"));
+    }
+
+    #[test]
+    fn test_markdown_frontmatter() {
+        let input = "---\ntitle: Deploy guide\ntags: [ops, \"team:infra\", area/k8s]\nstatus: draft\nreviewers:\n  - kim\n---\n\n# Heading\nBody text\n";
+        let document = markdown_document(input, "/notes/deploy.md", &SourceType::Local, &[]);
+
+        assert_eq!(document.metadata.title.as_deref(), Some("Deploy guide"));
+        assert_eq!(document.content, "# Heading\nBody text\n");
+        assert_eq!(
+            tag_strings(&document),
+            vec!["tag:ops", "team:infra", "tag:area-k8s"]
+        );
+        assert_eq!(
+            document.attributes.get("status").map(String::as_str),
+            Some("draft")
+        );
+        assert!(!document.attributes.contains_key("reviewers"));
+
+        let plain = markdown_document("# Only a heading\n", "/notes/x.md", &SourceType::Local, &[]);
+        assert_eq!(plain.metadata.title.as_deref(), Some("Only a heading"));
+
+        let (frontmatter, body) = parse_frontmatter("---\nnot yaml: [\n---\ntext");
+        assert_eq!(frontmatter, Frontmatter::default());
+        assert!(body.starts_with("---"));
+    }
+
+    #[test]
+    fn test_vault_wiki_links() {
+        let notes = vec![
+            (
+                "Projects/Alpha.md".to_string(),
+                "---\naliases: [A]\n---\nSee [[Beta|the beta]] and ![[diagram]] #status/active #123".to_string(),
+            ),
+            ("Beta.md".to_string(), "Back to [[a]] and [[Projects/Alpha#Goals]], [[Missing]]".to_string()),
+            ("Archive/Beta.md".to_string(), "Old beta".to_string()),
+        ];
+        let documents = vault_documents(Path::new("/vault"), &notes, &SourceType::Local, &[]);
+
+        assert_eq!(documents[0].source.location, "/vault/Projects/Alpha.md");
+        assert_eq!(
+            documents[0]
+                .attributes
+                .get(LINKS_ATTRIBUTE)
+                .map(String::as_str),
+            Some("Beta.md")
+        );
+        assert_eq!(tag_strings(&documents[0]), vec!["tag:status-active"]);
+        assert_eq!(
+            documents[1]
+                .attributes
+                .get(LINKS_ATTRIBUTE)
+                .map(String::as_str),
+            Some("Projects/Alpha.md")
+        );
+        assert!(!documents[2].attributes.contains_key(LINKS_ATTRIBUTE));
+    }
+
+    #[test]
+    fn test_import_format_parse() {
+        assert_eq!("CSV".parse::<ImportFormat>().unwrap(), ImportFormat::Csv);
+        assert_eq!(
+            "md".parse::<ImportFormat>().unwrap(),
+            ImportFormat::Markdown
+        );
+        assert_eq!(ImportFormat::Obsidian.to_string(), "obsidian");
+        assert_eq!(
+            "parquet".parse::<ImportFormat>().unwrap(),
+            ImportFormat::Table
+        );
+        assert!("xml".parse::<ImportFormat>().is_err());
+    }
+}
@@ -0,0 +1,336 @@
+//! Index-side library API: chunk documents or files, embed and store them
+//! through the [`IndexPipeline`], without a CLI around it.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use crate::error::IndexError;
+use crate::models::{Config, Document, DocumentMetadata, Source, Tag};
+use crate::services::{
+    ChunkSender, EmbeddingClient, EmbeddingProvider, IndexPipeline, TextChunker, VectorStore,
+    create_backend_with_embedding_config,
+};
+use crate::utils::file::{calculate_checksum, is_text_file, read_file_content, walk_files};
+
+/// What one [`IndexService`] call indexed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct IndexSummary {
+    pub documents_indexed: u64,
+    /// Empty documents, and files that aren't readable text
+    pub documents_skipped: u64,
+    pub chunks_created: u64,
+    /// Chunks embedded and written to the store (fewer than created with dedup)
+    pub chunks_stored: u64,
+}
+
+/// Indexes documents into one collection with one embedding model.
+///
+/// Each document replaces whatever was stored under its ID, so indexing the
+/// same location again updates it instead of leaving stale chunks behind.
+///
+/// ```ignore
+/// let service = IndexService::from_config(&config).await?;
+/// let summary = service.index_path(Path::new("docs"), &parse_tags("team:infra")?).await?;
+/// ```
+pub struct IndexService {
+    embedder: Arc<dyn EmbeddingProvider>,
+    store: Arc<dyn VectorStore>,
+    chunker: TextChunker,
+    batch_size: usize,
+    max_retries: u32,
+    max_file_size: u64,
+    dedup: bool,
+    redact: bool,
+    title_vectors: bool,
+    workspace: Option<String>,
+}
+
+impl IndexService {
+    /// A service with the default `[embedding]` batch size and `[indexing]` settings.
+    pub fn new(
+        embedder: Arc<dyn EmbeddingProvider>,
+        store: Arc<dyn VectorStore>,
+        chunker: TextChunker,
+    ) -> Self {
+        let config = Config::default();
+        Self {
+            embedder,
+            store,
+            chunker,
+            batch_size: config.embedding.batch_size as usize,
+            max_retries: config.indexing.max_retries,
+            max_file_size: config.indexing.max_file_size,
+            dedup: config.indexing.dedup,
+            redact: config.indexing.redact_secrets,
+            title_vectors: config.vector_store.title_vectors,
+            workspace: None,
+        }
+    }
+
+    /// Embed through the daemon into the configured collection, chunking,
+    /// batching, deduplicating and redacting as `index add` would.
+    pub async fn from_config(config: &Config) -> Result<Self, IndexError> {
+        let store =
+            create_backend_with_embedding_config(&config.vector_store, &config.embedding).await?;
+        Ok(Self::new(
+            Arc::new(EmbeddingClient::new(config)),
+            Arc::from(store),
+            TextChunker::from_config(config),
+        )
+        .with_config(config))
+    }
+
+    /// Take the batch size, `[indexing]` settings, title vectors and
+    /// workspace from `config`.
+    #[must_use]
+    pub fn with_config(self, config: &Config) -> Self {
+        self.with_batch_size(config.embedding.batch_size as usize)
+            .with_max_retries(config.indexing.max_retries)
+            .with_max_file_size(config.indexing.max_file_size)
+            .with_dedup(config.indexing.dedup)
+            .with_redaction(config.indexing.redact_secrets)
+            .with_title_vectors(config.vector_store.title_vectors)
+            .with_workspace(config.workspace().map(String::from))
+    }
+
+    /// Chunks per embedding call.
+    #[must_use]
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Retries of a failed embedding or store call.
+    #[must_use]
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Largest file [`Self::index_path`] reads, in bytes.
+    #[must_use]
+    pub fn with_max_file_size(mut self, max_file_size: u64) -> Self {
+        self.max_file_size = max_file_size;
+        self
+    }
+
+    /// Skip chunks whose exact content is already stored for another document.
+    #[must_use]
+    pub fn with_dedup(mut self, dedup: bool) -> Self {
+        self.dedup = dedup;
+        self
+    }
+
+    /// Replace secrets with placeholders before embedding.
+    #[must_use]
+    pub fn with_redaction(mut self, redact: bool) -> Self {
+        self.redact = redact;
+        self
+    }
+
+    /// Also embed each chunk's document title (`[vector_store] title_vectors`).
+    #[must_use]
+    pub fn with_title_vectors(mut self, title_vectors: bool) -> Self {
+        self.title_vectors = title_vectors;
+        self
+    }
+
+    /// Stamp every chunk with the `workspace` attribute.
+    #[must_use]
+    pub fn with_workspace(mut self, workspace: Option<String>) -> Self {
+        self.workspace = workspace;
+        self
+    }
+
+    pub fn store(&self) -> &dyn VectorStore {
+        self.store.as_ref()
+    }
+
+    /// The store, for callers that outlive a borrow of the service.
+    pub fn shared_store(&self) -> Arc<dyn VectorStore> {
+        Arc::clone(&self.store)
+    }
+
+    pub fn chunker(&self) -> &TextChunker {
+        &self.chunker
+    }
+
+    /// An [`IndexPipeline`] with this service's settings, for callers that
+    /// chunk documents themselves; add notices before running it.
+    pub fn pipeline(&self) -> (ChunkSender, IndexPipeline<'_, dyn VectorStore>) {
+        let (sender, pipeline) =
+            IndexPipeline::new(self.embedder.as_ref(), self.store.as_ref(), self.batch_size);
+        let pipeline = pipeline
+            .with_max_retries(self.max_retries)
+            .with_dedup(self.dedup)
+            .with_redaction(self.redact)
+            .with_title_vectors(self.title_vectors)
+            .with_workspace(self.workspace.clone());
+        (sender, pipeline)
+    }
+
+    /// Chunk, embed and store `documents`, creating the collection if needed.
+    pub async fn index_documents(&self, documents: Vec<Document>) -> Result<IndexSummary> {
+        let mut summary = IndexSummary::default();
+        let documents: Vec<Document> = documents
+            .into_iter()
+            .filter(|document| {
+                let empty = document.content.trim().is_empty();
+                summary.documents_skipped += u64::from(empty);
+                !empty
+            })
+            .collect();
+        if documents.is_empty() {
+            return Ok(summary);
+        }
+
+        self.store
+            .create_collection()
+            .await
+            .context("failed to create collection")?;
+        let (sender, pipeline) = self.pipeline();
+
+        let produce = async {
+            let sender = sender;
+            let mut chunks_created = 0;
+            for document in &documents {
+                let chunks = self.chunker.chunk(document);
+                chunks_created += chunks.len() as u64;
+                sender.replace(document.id.clone()).await?;
+                for chunk in chunks {
+                    sender.send(chunk).await?;
+                }
+            }
+            Ok::<u64, anyhow::Error>(chunks_created)
+        };
+        let (produced, stats) = tokio::join!(produce, pipeline.run());
+        let stats = stats?;
+        summary.chunks_created = produced?;
+        summary.chunks_stored = stats.stored;
+        summary.documents_indexed = documents.len() as u64;
+        Ok(summary)
+    }
+
+    /// Index the text files under `path` (or the file itself) as local
+    /// documents tagged with `tags`, honoring `.gitignore`.
+    pub async fn index_path(&self, path: &Path, tags: &[Tag]) -> Result<IndexSummary> {
+        let path = path
+            .canonicalize()
+            .with_context(|| format!("failed to resolve {}", path.display()))?;
+        let files = if path.is_dir() {
+            walk_files(&path, true).map_err(|e| IndexError::WalkError(e.to_string()))?
+        } else {
+            vec![path]
+        };
+        if files.is_empty() {
+            return Err(IndexError::NoFilesFound.into());
+        }
+
+        let mut unreadable = 0;
+        let mut documents = Vec::with_capacity(files.len());
+        for file in files {
+            let content = if is_text_file(&file) {
+                read_file_content(&file, self.max_file_size).ok()
+            } else {
+                None
+            };
+            let Some(content) = content else {
+                unreadable += 1;
+                continue;
+            };
+            let metadata = DocumentMetadata::for_file(&file, &content);
+            let checksum = calculate_checksum(&content);
+            let source = Source::local(file.to_string_lossy().to_string());
+            documents.push(Document::new(
+                content,
+                source,
+                tags.to_vec(),
+                checksum,
+                metadata,
+            ));
+        }
+
+        let mut summary = self.index_documents(documents).await?;
+        summary.documents_skipped += unreadable;
+        Ok(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{SearchFilter, parse_tags};
+    use crate::testing::{MockEmbeddingProvider, MockVectorStore, document};
+
+    fn service(store: &Arc<MockVectorStore>) -> IndexService {
+        IndexService::new(
+            Arc::new(MockEmbeddingProvider::default()),
+            store.clone(),
+            TextChunker::with_defaults(),
+        )
+        .with_max_retries(0)
+    }
+
+    #[tokio::test]
+    async fn test_index_documents() {
+        let store = Arc::new(MockVectorStore::default());
+        let service = service(&store).with_workspace(Some("api".to_string()));
+        let summary = service
+            .index_documents(vec![
+                document("docs/a.md", "Rotate the signing keys every quarter."),
+                document("docs/empty.md", "  "),
+            ])
+            .await
+            .unwrap();
+        assert_eq!(summary.documents_indexed, 1);
+        assert_eq!(summary.documents_skipped, 1);
+        assert_eq!(summary.chunks_stored, summary.chunks_created);
+
+        // Indexing the same location again replaces it
+        service
+            .index_documents(vec![document("docs/a.md", "Rotate keys monthly.")])
+            .await
+            .unwrap();
+        let filter = SearchFilter::new().with_workspace(Some("api"));
+        assert_eq!(store.count_by_filter(&filter).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_index_title_vectors() {
+        let store = Arc::new(MockVectorStore::default());
+        let mut config = Config::default();
+        config.vector_store.title_vectors = true;
+        service(&store)
+            .with_config(&config)
+            .index_documents(vec![document("docs/a.md", "Rotate the signing keys.")])
+            .await
+            .unwrap();
+
+        let page = store.scroll_chunks(None, 10).await.unwrap();
+        assert_eq!(page.chunks.len(), 1);
+        assert!(!page.chunks[0].title_vector.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_index_path() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("notes.md"), "# Notes\n\nOn-call rotation.").unwrap();
+        std::fs::write(dir.path().join("image.png"), [0x89, 0x50, 0x4e, 0x47, 0]).unwrap();
+
+        let store = Arc::new(MockVectorStore::default());
+        let tags = parse_tags("team:infra").unwrap();
+        let summary = service(&store).index_path(dir.path(), &tags).await.unwrap();
+        assert_eq!(summary.documents_indexed, 1);
+        assert_eq!(summary.documents_skipped, 1);
+        let filter = SearchFilter::new().with_tags(tags);
+        assert_eq!(store.count_by_filter(&filter).await.unwrap(), 1);
+
+        let missing = service(&store)
+            .index_path(&dir.path().join("missing"), &[])
+            .await;
+        assert!(missing.is_err());
+    }
+}
@@ -0,0 +1,230 @@
+//! Checkpoint journal for resumable `index add` runs.
+//!
+//! Each run appends JSON lines: documents are recorded as pending when their
+//! chunks are queued and as stored once every chunk is written. A run that
+//! succeeds removes its journal; one that fails leaves it for `--resume`.
+
+use std::collections::{HashMap, HashSet};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::error::IndexError;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum JournalEntry {
+    Start {
+        roots: Vec<PathBuf>,
+        collection: String,
+    },
+    Pending {
+        document_id: String,
+        checksum: String,
+    },
+    Stored {
+        document_id: String,
+    },
+}
+
+/// What an interrupted run finished, rebuilt from its journal.
+#[derive(Debug, Default)]
+pub struct JournalState {
+    /// Documents whose chunks were all stored, with the checksum they were indexed at
+    pub completed: HashMap<String, String>,
+    /// Documents queued but not confirmed stored; their chunks may be partial
+    pub pending: HashSet<String>,
+}
+
+impl JournalState {
+    /// Whether the document was fully stored at this checksum.
+    pub fn is_completed(&self, document_id: &str, checksum: &str) -> bool {
+        self.completed.get(document_id).map(String::as_str) == Some(checksum)
+    }
+}
+
+/// Append-only record of an `index add` run.
+pub struct IndexJournal {
+    path: PathBuf,
+    file: File,
+}
+
+impl IndexJournal {
+    /// Journal location for indexing `roots` into `collection`.
+    pub fn path_for(state_dir: &Path, roots: &[PathBuf], collection: &str) -> PathBuf {
+        let mut hasher = Sha256::new();
+        for root in roots {
+            hasher.update(root.to_string_lossy().as_bytes());
+            hasher.update([0]);
+        }
+        hasher.update(collection.as_bytes());
+        let digest = hex::encode(hasher.finalize());
+        state_dir.join(format!("index-{}.jsonl", &digest[..16]))
+    }
+
+    /// Rebuild the state of a previous run, if it left a journal.
+    ///
+    /// Lines that don't parse, such as one cut off by a crash, are skipped.
+    pub fn replay(path: &Path) -> Result<Option<JournalState>, IndexError> {
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(journal_error(path, e)),
+        };
+
+        let mut state = JournalState::default();
+        let mut checksums: HashMap<String, String> = HashMap::new();
+        for line in BufReader::new(file).lines() {
+            let line = line.map_err(|e| journal_error(path, e))?;
+            let Ok(entry) = serde_json::from_str::<JournalEntry>(&line) else {
+                continue;
+            };
+            match entry {
+                JournalEntry::Start { .. } => {}
+                JournalEntry::Pending {
+                    document_id,
+                    checksum,
+                } => {
+                    state.completed.remove(&document_id);
+                    state.pending.insert(document_id.clone());
+                    checksums.insert(document_id, checksum);
+                }
+                JournalEntry::Stored { document_id } => {
+                    state.pending.remove(&document_id);
+                    if let Some(checksum) = checksums.remove(&document_id) {
+                        state.completed.insert(document_id, checksum);
+                    }
+                }
+            }
+        }
+        Ok(Some(state))
+    }
+
+    /// Start a journal, appending to the existing one when resuming.
+    pub fn open(
+        path: &Path,
+        roots: &[PathBuf],
+        collection: &str,
+        resume: bool,
+    ) -> Result<Self, IndexError> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| journal_error(dir, e))?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(resume)
+            .truncate(!resume)
+            .open(path)
+            .map_err(|e| journal_error(path, e))?;
+
+        let mut journal = Self {
+            path: path.to_path_buf(),
+            file,
+        };
+        if resume {
+            // Terminate a line the interrupted run may have left unfinished
+            journal.write("\n")?;
+        }
+        journal.append(&JournalEntry::Start {
+            roots: roots.to_vec(),
+            collection: collection.to_string(),
+        })?;
+        Ok(journal)
+    }
+
+    /// Record that a document's chunks are about to be queued.
+    pub fn pending(&mut self, document_id: &str, checksum: &str) -> Result<(), IndexError> {
+        self.append(&JournalEntry::Pending {
+            document_id: document_id.to_string(),
+            checksum: checksum.to_string(),
+        })
+    }
+
+    /// Record that every chunk of these documents is stored.
+    pub fn stored(&mut self, document_ids: &[String]) -> Result<(), IndexError> {
+        let mut lines = String::new();
+        for document_id in document_ids {
+            let entry = JournalEntry::Stored {
+                document_id: document_id.clone(),
+            };
+            lines.push_str(&encode(&entry)?);
+        }
+        self.write(&lines)
+    }
+
+    /// Remove the journal after a successful run.
+    pub fn finish(self) -> Result<(), IndexError> {
+        std::fs::remove_file(&self.path).map_err(|e| journal_error(&self.path, e))
+    }
+
+    fn append(&mut self, entry: &JournalEntry) -> Result<(), IndexError> {
+        let line = encode(entry)?;
+        self.write(&line)
+    }
+
+    fn write(&mut self, lines: &str) -> Result<(), IndexError> {
+        self.file
+            .write_all(lines.as_bytes())
+            .map_err(|e| journal_error(&self.path, e))
+    }
+}
+
+fn encode(entry: &JournalEntry) -> Result<String, IndexError> {
+    let mut line =
+        serde_json::to_string(entry).map_err(|e| IndexError::JournalError(e.to_string()))?;
+    line.push('\n');
+    Ok(line)
+}
+
+fn journal_error(path: &Path, error: std::io::Error) -> IndexError {
+    IndexError::JournalError(format!("{}: {}", path.display(), error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_journal_replay_and_resume() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = &[PathBuf::from("/work/repo")];
+        let path = IndexJournal::path_for(dir.path(), root, "code");
+        assert_ne!(path, IndexJournal::path_for(dir.path(), root, "notes"));
+        let both = [PathBuf::from("/work/repo"), PathBuf::from("/work/docs")];
+        assert_ne!(path, IndexJournal::path_for(dir.path(), &both, "code"));
+        assert!(IndexJournal::replay(&path).unwrap().is_none());
+
+        let mut journal = IndexJournal::open(&path, root, "code", false).unwrap();
+        journal.pending("a", "sum-a").unwrap();
+        journal.pending("b", "sum-b").unwrap();
+        journal.stored(&["a".to_string()]).unwrap();
+        drop(journal);
+
+        let state = IndexJournal::replay(&path).unwrap().unwrap();
+        assert!(state.is_completed("a", "sum-a"));
+        assert!(!state.is_completed("a", "other"));
+        assert!(state.pending.contains("b"));
+
+        // A crash can leave a torn last line; resuming appends after it
+        let mut journal = IndexJournal::open(&path, root, "code", true).unwrap();
+        journal.write("{\"event\":\"pend").unwrap();
+        drop(journal);
+        let mut journal = IndexJournal::open(&path, root, "code", true).unwrap();
+        journal.pending("b", "sum-b").unwrap();
+        journal.stored(&["b".to_string()]).unwrap();
+        drop(journal);
+
+        let state = IndexJournal::replay(&path).unwrap().unwrap();
+        assert!(state.is_completed("a", "sum-a"));
+        assert!(state.is_completed("b", "sum-b"));
+        assert!(state.pending.is_empty());
+
+        let journal = IndexJournal::open(&path, root, "code", true).unwrap();
+        journal.finish().unwrap();
+        assert!(!path.exists());
+    }
+}
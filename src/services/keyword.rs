@@ -0,0 +1,94 @@
+//! Keyword tokenization and sparse term vectors for hybrid search.
+
+use std::collections::BTreeMap;
+
+/// BM25 term-frequency saturation parameter.
+const BM25_K1: f32 = 1.2;
+
+/// Split text into lowercase keyword terms.
+///
+/// Identifiers such as `handle_request` or `E0308` are kept whole; snake_case
+/// identifiers additionally contribute their individual parts.
+pub fn tokenize(text: &str) -> Vec<String> {
+    let mut terms = Vec::new();
+
+    for word in text.split(|c: char| !(c.is_alphanumeric() || c == '_')) {
+        let word = word.trim_matches('_');
+        if word.chars().count() < 2 {
+            continue;
+        }
+
+        let lower = word.to_lowercase();
+        if lower.contains('_') {
+            terms.extend(
+                lower
+                    .split('_')
+                    .filter(|part| part.chars().count() >= 2)
+                    .map(str::to_string),
+            );
+        }
+        terms.push(lower);
+    }
+
+    terms
+}
+
+/// Build a sparse term vector for the given text.
+///
+/// Terms are hashed into `u32` indices and weighted by saturated term
+/// frequency; inverse document frequency is applied by the vector store.
+/// Returns `(indices, values)` with unique, ascending indices.
+pub fn sparse_vector(text: &str) -> (Vec<u32>, Vec<f32>) {
+    let mut counts: BTreeMap<u32, f32> = BTreeMap::new();
+    for term in tokenize(text) {
+        *counts.entry(term_index(&term)).or_insert(0.0) += 1.0;
+    }
+
+    counts
+        .into_iter()
+        .map(|(index, tf)| (index, tf * (BM25_K1 + 1.0) / (tf + BM25_K1)))
+        .unzip()
+}
+
+/// Stable 32-bit FNV-1a hash of a term.
+fn term_index(term: &str) -> u32 {
+    let mut hash: u32 = 0x811c_9dc5;
+    for byte in term.bytes() {
+        hash ^= u32::from(byte);
+        hash = hash.wrapping_mul(0x0100_0193);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize_keeps_identifiers() {
+        let terms = tokenize("Error E0308 in handle_request()");
+        assert!(terms.contains(&"e0308".to_string()));
+        assert!(terms.contains(&"handle_request".to_string()));
+        assert!(terms.contains(&"handle".to_string()));
+        assert!(terms.contains(&"request".to_string()));
+        assert!(tokenize("a _ b").is_empty());
+    }
+
+    #[test]
+    fn test_sparse_vector_unique_sorted() {
+        let (indices, values) = sparse_vector("retry retry retry backoff");
+        assert_eq!(indices.len(), 2);
+        assert_eq!(values.len(), 2);
+        assert!(indices.windows(2).all(|w| w[0] < w[1]));
+
+        let retry = values[indices
+            .iter()
+            .position(|&i| i == term_index("retry"))
+            .unwrap()];
+        let backoff = values[indices
+            .iter()
+            .position(|&i| i == term_index("backoff"))
+            .unwrap()];
+        assert!(retry > backoff);
+    }
+}
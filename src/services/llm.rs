@@ -0,0 +1,231 @@
+//! Chat completion client for `ssearch ask`.
+//!
+//! Speaks the OpenAI-compatible and Ollama chat APIs through `curl`, like the
+//! source integrations, so no HTTP client is linked in.
+
+use std::fmt::Write as FmtWrite;
+use std::io::Write;
+use std::process::Stdio;
+
+use serde_json::{Value, json};
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use crate::error::LlmError;
+use crate::models::{LlmConfig, LlmProvider, SearchResult};
+
+/// Instructions sent as the system message.
+pub const SYSTEM_PROMPT: &str = "You answer questions using only the numbered sources you are given. \
+Cite every claim with the number of its source in square brackets, e.g. [1] or [2][3]. \
+If the sources do not contain the answer, say that you don't know.";
+
+/// Client for the endpoint configured in `[llm]`.
+pub struct LlmClient {
+    provider: LlmProvider,
+    base_url: String,
+    model: String,
+    api_key: Option<String>,
+    timeout_secs: u64,
+}
+
+impl LlmClient {
+    pub fn new(config: &LlmConfig) -> Self {
+        Self {
+            provider: config.provider,
+            base_url: config.base_url().to_string(),
+            model: config.model.clone(),
+            api_key: config.api_key.clone(),
+            timeout_secs: config.timeout_secs,
+        }
+    }
+
+    /// Use another model than `[llm] model`.
+    #[must_use]
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = model.into();
+        self
+    }
+
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// Send a system and a user message and return the reply.
+    pub async fn chat(&self, system: &str, user: &str) -> Result<String, LlmError> {
+        let url = match self.provider {
+            LlmProvider::Ollama => format!("{}/api/chat", self.base_url),
+            LlmProvider::OpenAi => format!("{}/chat/completions", self.base_url),
+        };
+        let body = request_body(self.provider, &self.model, system, user);
+
+        // The body can be large, so it goes through a file; stdin carries the headers
+        let mut body_file =
+            tempfile::NamedTempFile::new().map_err(|e| LlmError::RequestFailed(e.to_string()))?;
+        body_file
+            .write_all(body.to_string().as_bytes())
+            .map_err(|e| LlmError::RequestFailed(e.to_string()))?;
+
+        let mut child = Command::new("curl")
+            .args(["-sS", "--fail-with-body", "-X", "POST", "-H", "@-"])
+            .arg("--max-time")
+            .arg(self.timeout_secs.to_string())
+            .arg("--data-binary")
+            .arg(format!("@{}", body_file.path().display()))
+            .arg(&url)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| LlmError::RequestFailed(format!("failed to run curl: {e}")))?;
+
+        // Pass the key on stdin so it does not appear in the process list
+        let mut headers = String::from("Content-Type: application/json\n");
+        if let Some(ref key) = self.api_key {
+            writeln!(headers, "Authorization: Bearer {key}").unwrap();
+        }
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(headers.as_bytes())
+                .await
+                .map_err(|e| LlmError::RequestFailed(e.to_string()))?;
+        }
+        let output = child
+            .wait_with_output()
+            .await
+            .map_err(|e| LlmError::RequestFailed(e.to_string()))?;
+
+        if !output.status.success() {
+            let detail = error_message(&output.stdout)
+                .unwrap_or_else(|| String::from_utf8_lossy(&output.stderr).trim().to_string());
+            return Err(LlmError::RequestFailed(format!("{url}: {detail}")));
+        }
+
+        parse_reply(self.provider, &output.stdout)
+    }
+}
+
+fn request_body(provider: LlmProvider, model: &str, system: &str, user: &str) -> Value {
+    let messages = json!([
+        {"role": "system", "content": system},
+        {"role": "user", "content": user},
+    ]);
+    match provider {
+        LlmProvider::Ollama => json!({"model": model, "messages": messages, "stream": false}),
+        LlmProvider::OpenAi => json!({"model": model, "messages": messages}),
+    }
+}
+
+/// Extract the assistant message from a chat response.
+fn parse_reply(provider: LlmProvider, body: &[u8]) -> Result<String, LlmError> {
+    let value: Value =
+        serde_json::from_slice(body).map_err(|e| LlmError::InvalidResponse(e.to_string()))?;
+    let content = match provider {
+        LlmProvider::Ollama => value.pointer("/message/content"),
+        LlmProvider::OpenAi => value.pointer("/choices/0/message/content"),
+    };
+    content
+        .and_then(Value::as_str)
+        .map(|s| s.trim().to_string())
+        .ok_or_else(|| LlmError::InvalidResponse("no message content in response".to_string()))
+}
+
+/// Error text from an OpenAI (`{"error": {"message"}}`) or Ollama (`{"error"}`) body.
+fn error_message(body: &[u8]) -> Option<String> {
+    let value: Value = serde_json::from_slice(body).ok()?;
+    let error = value.get("error")?;
+    error
+        .get("message")
+        .unwrap_or(error)
+        .as_str()
+        .map(String::from)
+}
+
+/// User message with each result as a numbered source, followed by the question.
+pub fn build_prompt(question: &str, sources: &[SearchResult]) -> String {
+    let mut prompt = String::from("Sources:\n\n");
+    for (i, source) in sources.iter().enumerate() {
+        writeln!(prompt, "[{}] {}", i + 1, source.citation()).unwrap();
+        writeln!(prompt, "{}\n", source.content.trim()).unwrap();
+    }
+    write!(prompt, "Question: {}", question).unwrap();
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Source;
+
+    fn result(location: &str, lines: Option<(u32, u32)>, content: &str) -> SearchResult {
+        SearchResult {
+            chunk_id: "c".to_string(),
+            document_id: "d".to_string(),
+            chunk_index: 0,
+            score: 0.9,
+            content: content.to_string(),
+            source: Source::local(location.to_string()),
+            tags: Vec::new(),
+            location: location.to_string(),
+            line_start: lines.map(|l| l.0),
+            line_end: lines.map(|l| l.1),
+            attributes: Default::default(),
+            updated_at: None,
+            context_before: Vec::new(),
+            context_after: Vec::new(),
+            explanation: None,
+            keyword_match: false,
+        }
+    }
+
+    #[test]
+    fn test_build_prompt() {
+        let sources = vec![
+            result("src/retry.rs", Some((10, 42)), "fn with_retry() {}\n"),
+            result("docs/retry.md", None, "Retries back off exponentially."),
+        ];
+        let prompt = build_prompt("How do retries work?", &sources);
+        assert_eq!(
+            prompt,
+            "Sources:\n\n\
+             [1] src/retry.rs:10-42\nfn with_retry() {}\n\n\
+             [2] docs/retry.md\nRetries back off exponentially.\n\n\
+             Question: How do retries work?"
+        );
+    }
+
+    #[test]
+    fn test_parse_reply() {
+        let openai =
+            br#"{"choices":[{"message":{"role":"assistant","content":" It backs off [1]. "}}]}"#;
+        assert_eq!(
+            parse_reply(LlmProvider::OpenAi, openai).unwrap(),
+            "It backs off [1]."
+        );
+
+        let ollama = br#"{"model":"llama3.1","message":{"role":"assistant","content":"See [2]."},"done":true}"#;
+        assert_eq!(
+            parse_reply(LlmProvider::Ollama, ollama).unwrap(),
+            "See [2]."
+        );
+        assert!(parse_reply(LlmProvider::Ollama, openai).is_err());
+
+        assert_eq!(
+            error_message(br#"{"error":{"message":"invalid api key","type":"auth"}}"#).as_deref(),
+            Some("invalid api key")
+        );
+        assert_eq!(
+            error_message(br#"{"error":"model 'x' not found"}"#).as_deref(),
+            Some("model 'x' not found")
+        );
+    }
+
+    #[test]
+    fn test_request_body() {
+        let body = request_body(LlmProvider::Ollama, "llama3.1", "sys", "user");
+        assert_eq!(body["stream"], false);
+        assert_eq!(body["messages"][1]["content"], "user");
+        let body = request_body(LlmProvider::OpenAi, "gpt-4o-mini", "sys", "user");
+        assert!(body.get("stream").is_none());
+        assert_eq!(body["model"], "gpt-4o-mini");
+    }
+}
@@ -1,7 +1,12 @@
+use std::collections::BTreeMap;
 use std::path::Path;
+use std::sync::Mutex;
 
 use rusqlite::{Connection, params};
 use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::models::{Config, SearchFilter, SearchResult};
 
 const SCHEMA: &str = r#"
 CREATE TABLE IF NOT EXISTS request_log (
@@ -12,10 +17,31 @@ CREATE TABLE IF NOT EXISTS request_log (
 );
 
 CREATE INDEX IF NOT EXISTS idx_request_log_timestamp ON request_log(timestamp);
+
+CREATE TABLE IF NOT EXISTS search_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    timestamp TEXT NOT NULL,
+    query TEXT NOT NULL,
+    filters TEXT,
+    result_count INTEGER NOT NULL,
+    top_score REAL,
+    duration_ms INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_search_log_timestamp ON search_log(timestamp);
 "#;
 
+/// Window over which latency quantiles are computed, in seconds.
+pub const LATENCY_WINDOW_SECS: u64 = 600;
+
+/// Top score below which a search counts as poor in `ssearch history stats`.
+pub const DEFAULT_POOR_SCORE: f32 = 0.5;
+
+/// Queries listed per section of [`SearchStats`].
+const STATS_QUERY_LIMIT: u32 = 10;
+
 pub struct MetricsStore {
-    conn: Connection,
+    conn: Mutex<Connection>,
 }
 
 impl MetricsStore {
@@ -24,17 +50,121 @@ impl MetricsStore {
         conn.pragma_update(None, "journal_mode", "WAL")?;
         conn.pragma_update(None, "auto_vacuum", "INCREMENTAL")?;
         conn.execute_batch(SCHEMA)?;
-        Ok(Self { conn })
+        Self::migrate_kind(&conn)?;
+        Self::migrate_batch_size(&conn)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Open the metrics database for recording searches, unless
+    /// `[metrics] enabled` or `history` is off.
+    pub fn open_history(config: &Config) -> Option<Self> {
+        if !config.metrics.enabled || !config.metrics.history {
+            return None;
+        }
+        let path = Config::metrics_db_path()?;
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        Self::open(&path)
+            .inspect_err(|e| debug!(error = %e, "failed to open metrics database"))
+            .ok()
+    }
+
+    /// Add the `kind` column to logs written before requests were distinguished.
+    fn migrate_kind(conn: &Connection) -> Result<(), rusqlite::Error> {
+        let has_kind: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('request_log') WHERE name = 'kind'",
+            [],
+            |row| row.get(0),
+        )?;
+        if !has_kind {
+            // Only embeddings were recorded before rerank existed
+            conn.execute_batch(
+                "ALTER TABLE request_log ADD COLUMN kind TEXT NOT NULL DEFAULT 'embed'",
+            )?;
+        }
+        conn.execute_batch(
+            "CREATE INDEX IF NOT EXISTS idx_request_log_kind ON request_log(kind, timestamp)",
+        )
     }
 
-    pub fn record(&self, latency_ms: u64, success: bool) {
-        let _ = self.conn.execute(
-            "INSERT INTO request_log (timestamp, latency_ms, success)
-             VALUES (datetime('now'), ?1, ?2)",
-            params![latency_ms as i64, success as i32],
+    /// Add the `batch_size` column to logs written before it was recorded.
+    fn migrate_batch_size(conn: &Connection) -> Result<(), rusqlite::Error> {
+        let has_batch_size: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('request_log') WHERE name = 'batch_size'",
+            [],
+            |row| row.get(0),
+        )?;
+        if !has_batch_size {
+            conn.execute_batch("ALTER TABLE request_log ADD COLUMN batch_size INTEGER")?;
+        }
+        Ok(())
+    }
+
+    /// Record one model call (`kind` is e.g. "embed" or "rerank") over
+    /// `batch_size` texts.
+    pub fn record(&self, kind: &str, batch_size: usize, latency_ms: u64, success: bool) {
+        let conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        let _ = conn.execute(
+            "INSERT INTO request_log (timestamp, latency_ms, success, kind, batch_size)
+             VALUES (datetime('now'), ?1, ?2, ?3, ?4)",
+            params![latency_ms as i64, success as i32, kind, batch_size as i64],
         );
     }
 
+    /// Model calls of the last `days`, oldest first.
+    pub fn requests(&self, days: u32) -> Result<Vec<RequestRecord>, rusqlite::Error> {
+        let conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        let mut stmt = conn.prepare(
+            "SELECT timestamp, kind, batch_size, latency_ms, success
+             FROM request_log
+             WHERE timestamp >= datetime('now', ?1)
+             ORDER BY timestamp, id",
+        )?;
+        stmt.query_map(params![format!("-{} days", days)], |row| {
+            Ok(RequestRecord {
+                timestamp: row.get(0)?,
+                kind: row.get(1)?,
+                batch_size: row.get::<_, Option<i64>>(2)?.map(|n| n.max(0) as u64),
+                latency_ms: row.get::<_, i64>(3)?.max(0) as u64,
+                success: row.get::<_, i64>(4)? != 0,
+            })
+        })?
+        .collect()
+    }
+
+    /// Daemon request counts, success rate, batch size and latency per
+    /// `period` bucket and kind over the last `days`.
+    pub fn daemon_stats(
+        &self,
+        days: u32,
+        period: StatsPeriod,
+    ) -> Result<DaemonStats, rusqlite::Error> {
+        let requests = self.requests(days)?;
+        Ok(DaemonStats::from_requests(days, period, &requests))
+    }
+
+    /// Latency quantiles of one kind of call over the last [`LATENCY_WINDOW_SECS`].
+    pub fn latency(&self, kind: &str) -> LatencySummary {
+        let conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        let latencies: Vec<u64> = conn
+            .prepare(
+                "SELECT latency_ms FROM request_log
+                 WHERE kind = ?1 AND timestamp >= datetime('now', ?2)
+                 ORDER BY latency_ms",
+            )
+            .and_then(|mut stmt| {
+                let window = format!("-{} seconds", LATENCY_WINDOW_SECS);
+                stmt.query_map(params![kind, window], |row| row.get::<_, i64>(0))?
+                    .map(|latency| latency.map(|ms| ms.max(0) as u64))
+                    .collect()
+            })
+            .unwrap_or_default();
+        LatencySummary::from_sorted(&latencies)
+    }
+
     pub fn get_summary(&self, retention_days: u32) -> MetricsSummary {
         let query = format!(
             r#"
@@ -48,23 +178,333 @@ impl MetricsStore {
             retention_days
         );
 
-        self.conn
-            .query_row(&query, [], |row| {
-                Ok(MetricsSummary {
-                    total_requests: row.get::<_, i64>(0)? as u64,
-                    avg_latency_ms: row.get::<_, f64>(1)? as u64,
-                    error_rate: row.get::<_, f64>(2)? as f32,
-                })
+        let conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        conn.query_row(&query, [], |row| {
+            Ok(MetricsSummary {
+                total_requests: row.get::<_, i64>(0)? as u64,
+                avg_latency_ms: row.get::<_, f64>(1)? as u64,
+                error_rate: row.get::<_, f64>(2)? as f32,
             })
-            .unwrap_or_default()
+        })
+        .unwrap_or_default()
     }
 
     pub fn cleanup(&self, retention_days: u32) {
-        let query = format!(
-            "DELETE FROM request_log WHERE timestamp < datetime('now', '-{} days')",
-            retention_days
+        let conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        for table in ["request_log", "search_log"] {
+            let query = format!(
+                "DELETE FROM {} WHERE timestamp < datetime('now', '-{} days')",
+                table, retention_days
+            );
+            let _ = conn.execute(&query, []);
+        }
+    }
+
+    pub fn record_search(&self, search: &SearchRecord) {
+        let conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        let _ = conn.execute(
+            "INSERT INTO search_log (timestamp, query, filters, result_count, top_score, duration_ms)
+             VALUES (datetime('now'), ?1, ?2, ?3, ?4, ?5)",
+            params![
+                search.query,
+                search.filters,
+                search.result_count as i64,
+                search.top_score,
+                search.duration_ms as i64
+            ],
         );
-        let _ = self.conn.execute(&query, []);
+    }
+
+    /// Most recent searches first; only those without results if `zero_results`.
+    pub fn recent_searches(
+        &self,
+        limit: u32,
+        zero_results: bool,
+    ) -> Result<Vec<SearchHistoryEntry>, rusqlite::Error> {
+        let conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        let mut stmt = conn.prepare(
+            "SELECT timestamp, query, filters, result_count, top_score, duration_ms
+             FROM search_log
+             WHERE ?1 = 0 OR result_count = 0
+             ORDER BY id DESC
+             LIMIT ?2",
+        )?;
+        stmt.query_map(params![zero_results, limit], |row| {
+            Ok(SearchHistoryEntry {
+                timestamp: row.get(0)?,
+                search: SearchRecord {
+                    query: row.get(1)?,
+                    filters: row.get(2)?,
+                    result_count: row.get::<_, i64>(3)? as u64,
+                    top_score: row.get(4)?,
+                    duration_ms: row.get::<_, i64>(5)? as u64,
+                },
+            })
+        })?
+        .collect()
+    }
+
+    /// Aggregate searches of the last `days`; searches whose top score is
+    /// below `poor_score` count as poor.
+    pub fn search_stats(&self, days: u32, poor_score: f32) -> Result<SearchStats, rusqlite::Error> {
+        let window = format!("-{} days", days);
+        let conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+
+        let mut stats = conn.query_row(
+            "SELECT
+                COUNT(*),
+                COALESCE(SUM(CASE WHEN result_count = 0 THEN 1 ELSE 0 END), 0),
+                COALESCE(SUM(CASE WHEN result_count > 0 AND top_score < ?2 THEN 1 ELSE 0 END), 0),
+                COALESCE(AVG(duration_ms), 0)
+             FROM search_log
+             WHERE timestamp >= datetime('now', ?1)",
+            params![window, poor_score],
+            |row| {
+                Ok(SearchStats {
+                    days,
+                    poor_score,
+                    total_searches: row.get::<_, i64>(0)? as u64,
+                    zero_result_searches: row.get::<_, i64>(1)? as u64,
+                    poor_result_searches: row.get::<_, i64>(2)? as u64,
+                    avg_duration_ms: row.get::<_, f64>(3)? as u64,
+                    ..Default::default()
+                })
+            },
+        )?;
+
+        let grouped = |condition: &str| -> Result<Vec<QueryStats>, rusqlite::Error> {
+            let sql = format!(
+                "SELECT query, COUNT(*), AVG(top_score), MAX(timestamp)
+                 FROM search_log
+                 WHERE timestamp >= datetime('now', ?1) AND {condition}
+                 GROUP BY query
+                 ORDER BY COUNT(*) DESC, MAX(timestamp) DESC
+                 LIMIT ?2"
+            );
+            let mut stmt = conn.prepare(&sql)?;
+            stmt.query_map(params![window, STATS_QUERY_LIMIT], |row| {
+                Ok(QueryStats {
+                    query: row.get(0)?,
+                    count: row.get::<_, i64>(1)? as u64,
+                    avg_top_score: row.get::<_, Option<f64>>(2)?.map(|s| s as f32),
+                    last_searched: row.get(3)?,
+                })
+            })?
+            .collect()
+        };
+        stats.top_queries = grouped("1")?;
+        stats.zero_result_queries = grouped("result_count = 0")?;
+        stats.poor_queries = grouped(&format!("result_count > 0 AND top_score < {}", poor_score))?;
+
+        Ok(stats)
+    }
+
+    /// Delete all recorded searches, returning how many were removed.
+    pub fn clear_searches(&self) -> Result<usize, rusqlite::Error> {
+        let conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        conn.execute("DELETE FROM search_log", [])
+    }
+}
+
+/// One executed search, as recorded in the history.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchRecord {
+    pub query: String,
+    /// Filters in `ssearch search` syntax; `None` when unfiltered
+    pub filters: Option<String>,
+    pub result_count: u64,
+    pub top_score: Option<f32>,
+    pub duration_ms: u64,
+}
+
+impl SearchRecord {
+    pub fn new(
+        query: &str,
+        filter: &SearchFilter,
+        results: &[SearchResult],
+        duration_ms: u64,
+    ) -> Self {
+        Self {
+            query: query.to_string(),
+            filters: (!filter.is_empty()).then(|| filter.to_string()),
+            result_count: results.len() as u64,
+            top_score: results.iter().map(|r| r.score).reduce(f32::max),
+            duration_ms,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchHistoryEntry {
+    /// UTC time the search ran (`YYYY-MM-DD HH:MM:SS`)
+    pub timestamp: String,
+    #[serde(flatten)]
+    pub search: SearchRecord,
+}
+
+/// Aggregated search history for `ssearch history stats`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SearchStats {
+    pub days: u32,
+    pub poor_score: f32,
+    pub total_searches: u64,
+    pub zero_result_searches: u64,
+    pub poor_result_searches: u64,
+    pub avg_duration_ms: u64,
+    /// Most frequent queries
+    pub top_queries: Vec<QueryStats>,
+    /// Queries that returned nothing, most frequent first
+    pub zero_result_queries: Vec<QueryStats>,
+    /// Queries whose best result scored below `poor_score`
+    pub poor_queries: Vec<QueryStats>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryStats {
+    pub query: String,
+    pub count: u64,
+    pub avg_top_score: Option<f32>,
+    pub last_searched: String,
+}
+
+/// One recorded daemon model call.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RequestRecord {
+    /// UTC time of the call (`YYYY-MM-DD HH:MM:SS`)
+    pub timestamp: String,
+    pub kind: String,
+    /// Texts in the call; `None` for calls logged before batch sizes were kept
+    pub batch_size: Option<u64>,
+    pub latency_ms: u64,
+    pub success: bool,
+}
+
+impl RequestRecord {
+    pub const CSV_HEADER: &str = "timestamp,kind,batch_size,latency_ms,success";
+
+    pub fn to_csv(&self) -> String {
+        format!(
+            "{},{},{},{},{}",
+            self.timestamp,
+            self.kind,
+            self.batch_size.map(|n| n.to_string()).unwrap_or_default(),
+            self.latency_ms,
+            self.success
+        )
+    }
+}
+
+/// Bucket size for [`DaemonStats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatsPeriod {
+    #[default]
+    Daily,
+    Weekly,
+}
+
+impl StatsPeriod {
+    /// Bucket label for a `YYYY-MM-DD HH:MM:SS` timestamp: the day, or the
+    /// Monday starting its ISO week.
+    fn bucket(self, timestamp: &str) -> String {
+        let day = timestamp.get(..10).unwrap_or(timestamp);
+        match self {
+            StatsPeriod::Daily => day.to_string(),
+            StatsPeriod::Weekly => chrono::NaiveDate::parse_from_str(day, "%Y-%m-%d")
+                .map(|date| {
+                    let monday = date.week(chrono::Weekday::Mon).first_day();
+                    monday.format("%Y-%m-%d").to_string()
+                })
+                .unwrap_or_else(|_| day.to_string()),
+        }
+    }
+}
+
+/// Daemon performance history for `ssearch serve stats`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DaemonStats {
+    pub days: u32,
+    pub period: StatsPeriod,
+    /// One row per period and kind, oldest period first
+    pub rows: Vec<PeriodStats>,
+}
+
+/// Aggregated calls of one kind within one period.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PeriodStats {
+    /// First day of the period (`YYYY-MM-DD`)
+    pub period: String,
+    pub kind: String,
+    pub requests: u64,
+    /// Percentage of calls that succeeded
+    pub success_rate: f64,
+    pub avg_batch_size: Option<f64>,
+    pub p50_ms: u64,
+    pub p95_ms: u64,
+    pub p99_ms: u64,
+}
+
+impl DaemonStats {
+    fn from_requests(days: u32, period: StatsPeriod, requests: &[RequestRecord]) -> Self {
+        let mut groups: BTreeMap<(String, String), Vec<&RequestRecord>> = BTreeMap::new();
+        for request in requests {
+            groups
+                .entry((period.bucket(&request.timestamp), request.kind.clone()))
+                .or_default()
+                .push(request);
+        }
+
+        let rows = groups
+            .into_iter()
+            .map(|((bucket, kind), calls)| {
+                let mut latencies: Vec<u64> = calls.iter().map(|c| c.latency_ms).collect();
+                latencies.sort_unstable();
+                let successes = calls.iter().filter(|c| c.success).count();
+                let batches: Vec<u64> = calls.iter().filter_map(|c| c.batch_size).collect();
+                PeriodStats {
+                    period: bucket,
+                    kind,
+                    requests: calls.len() as u64,
+                    success_rate: successes as f64 * 100.0 / calls.len() as f64,
+                    avg_batch_size: (!batches.is_empty())
+                        .then(|| batches.iter().sum::<u64>() as f64 / batches.len() as f64),
+                    p50_ms: nearest_rank(&latencies, 0.5),
+                    p95_ms: nearest_rank(&latencies, 0.95),
+                    p99_ms: nearest_rank(&latencies, 0.99),
+                }
+            })
+            .collect();
+
+        Self { days, period, rows }
+    }
+}
+
+/// Nearest-rank quantile of sorted values.
+fn nearest_rank(sorted: &[u64], q: f64) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (q * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Latency distribution of recent calls, in milliseconds.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LatencySummary {
+    pub count: u64,
+    pub sum_ms: u64,
+    pub p50_ms: u64,
+    pub p95_ms: u64,
+}
+
+impl LatencySummary {
+    fn from_sorted(latencies: &[u64]) -> Self {
+        Self {
+            count: latencies.len() as u64,
+            sum_ms: latencies.iter().sum(),
+            p50_ms: nearest_rank(latencies, 0.5),
+            p95_ms: nearest_rank(latencies, 0.95),
+        }
     }
 }
 
@@ -74,3 +514,113 @@ pub struct MetricsSummary {
     pub avg_latency_ms: u64,
     pub error_rate: f32,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latency_quantiles() {
+        let latencies: Vec<u64> = (1..=100).collect();
+        let summary = LatencySummary::from_sorted(&latencies);
+        assert_eq!(summary.count, 100);
+        assert_eq!(summary.sum_ms, 5050);
+        assert_eq!(summary.p50_ms, 50);
+        assert_eq!(summary.p95_ms, 95);
+
+        assert_eq!(LatencySummary::from_sorted(&[]), LatencySummary::default());
+        assert_eq!(LatencySummary::from_sorted(&[7]).p95_ms, 7);
+    }
+
+    #[test]
+    fn test_latency_by_kind() {
+        let store = MetricsStore::open(Path::new(":memory:")).unwrap();
+        store.record("embed", 4, 10, true);
+        store.record("embed", 8, 30, true);
+        store.record("rerank", 20, 500, false);
+
+        let embed = store.latency("embed");
+        assert_eq!(embed.count, 2);
+        assert_eq!(embed.p95_ms, 30);
+        assert_eq!(store.latency("rerank").count, 1);
+        assert_eq!(store.get_summary(30).total_requests, 3);
+    }
+
+    #[test]
+    fn test_daemon_stats() {
+        let call = |timestamp: &str, kind: &str, latency_ms: u64, success: bool| RequestRecord {
+            timestamp: timestamp.to_string(),
+            kind: kind.to_string(),
+            batch_size: (kind == "embed").then_some(16),
+            latency_ms,
+            success,
+        };
+        let requests = [
+            call("2024-05-06 09:00:00", "embed", 10, true),
+            call("2024-05-06 10:00:00", "embed", 30, false),
+            call("2024-05-07 09:00:00", "embed", 20, true),
+            call("2024-05-07 09:30:00", "rerank", 200, true),
+        ];
+
+        let daily = DaemonStats::from_requests(7, StatsPeriod::Daily, &requests);
+        assert_eq!(daily.rows.len(), 3);
+        assert_eq!(daily.rows[0].period, "2024-05-06");
+        assert_eq!(daily.rows[0].requests, 2);
+        assert_eq!(daily.rows[0].success_rate, 50.0);
+        assert_eq!(daily.rows[0].avg_batch_size, Some(16.0));
+        assert_eq!(daily.rows[0].p99_ms, 30);
+        assert_eq!(daily.rows[2].kind, "rerank");
+        assert_eq!(daily.rows[2].avg_batch_size, None);
+
+        // 2024-05-06 is a Monday, so both days share a week
+        let weekly = DaemonStats::from_requests(7, StatsPeriod::Weekly, &requests);
+        assert_eq!(weekly.rows.len(), 2);
+        assert_eq!(weekly.rows[0].period, "2024-05-06");
+        assert_eq!(weekly.rows[0].requests, 3);
+        assert_eq!(weekly.rows[0].p50_ms, 20);
+
+        let store = MetricsStore::open(Path::new(":memory:")).unwrap();
+        store.record("embed", 4, 10, true);
+        let recorded = store.requests(1).unwrap();
+        assert_eq!(recorded[0].batch_size, Some(4));
+        assert!(recorded[0].to_csv().ends_with(",embed,4,10,true"));
+    }
+
+    fn search(query: &str, result_count: u64, top_score: Option<f32>) -> SearchRecord {
+        SearchRecord {
+            query: query.to_string(),
+            filters: None,
+            result_count,
+            top_score,
+            duration_ms: 20,
+        }
+    }
+
+    #[test]
+    fn test_search_history() {
+        let store = MetricsStore::open(Path::new(":memory:")).unwrap();
+        store.record_search(&search("retry policy", 5, Some(0.82)));
+        store.record_search(&search("grpc timeout", 0, None));
+        store.record_search(&search("retry policy", 3, Some(0.4)));
+        store.record_search(&search("grpc timeout", 0, None));
+
+        let recent = store.recent_searches(10, false).unwrap();
+        assert_eq!(recent.len(), 4);
+        assert_eq!(recent[0].search, search("grpc timeout", 0, None));
+        assert_eq!(store.recent_searches(1, true).unwrap().len(), 1);
+
+        let stats = store.search_stats(7, 0.5).unwrap();
+        assert_eq!(stats.total_searches, 4);
+        assert_eq!(stats.zero_result_searches, 2);
+        assert_eq!(stats.poor_result_searches, 1);
+        assert_eq!(stats.avg_duration_ms, 20);
+        assert_eq!(stats.top_queries.len(), 2);
+        assert_eq!(stats.zero_result_queries[0].query, "grpc timeout");
+        assert_eq!(stats.zero_result_queries[0].count, 2);
+        assert_eq!(stats.poor_queries.len(), 1);
+        assert_eq!(stats.poor_queries[0].query, "retry policy");
+
+        assert_eq!(store.clear_searches().unwrap(), 4);
+        assert_eq!(store.search_stats(7, 0.5).unwrap().total_searches, 0);
+    }
+}
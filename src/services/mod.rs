@@ -1,14 +1,64 @@
 mod batch;
 mod chunker;
 mod embedding;
+mod eval;
+mod expansion;
+pub mod fusion;
+mod health;
+mod import_formats;
+mod indexing;
+mod journal;
+pub mod keyword;
+mod llm;
 mod metrics;
+mod openapi;
+mod pii;
+mod redact;
+mod retention;
+mod search;
+pub mod snippet;
+mod summary;
+mod tabular;
 pub mod vector_store;
 
-pub use batch::process_batch;
-pub use chunker::{TextChunker, estimate_tokens};
-pub use embedding::EmbeddingClient;
-pub use metrics::{MetricsStore, MetricsSummary};
+pub use batch::{ChunkSender, IndexPipeline, PipelineStats, process_batch, resend_stored_chunks};
+pub use chunker::{BoilerplateCounter, TextChunker, estimate_tokens, screen_boilerplate};
+pub use embedding::{EmbeddingClient, EmbeddingProvider};
+pub use eval::{
+    DEFAULT_EVAL_K, EVAL_CANDIDATE_FACTOR, EvalRun, Judgment, QueryDelta, QueryEval,
+    parse_judgments,
+};
+pub use expansion::{MAX_EXPANSIONS, expand_query, heuristic_expansions};
+pub use health::{
+    CheckStatus, DISK_FAIL_BYTES, DISK_WARN_BYTES, HealthCheck, HealthReport, available_space,
+    collection_check, disk_space_check, embedding_check,
+};
+pub use import_formats::{
+    CsvColumns, Frontmatter, ImportFormat, LINKS_ATTRIBUTE, csv_documents, markdown_document,
+    markdown_files, obsidian_documents, parse_frontmatter, vault_documents,
+};
+pub use indexing::{IndexService, IndexSummary};
+pub use journal::{IndexJournal, JournalState};
+pub use llm::{LlmClient, SYSTEM_PROMPT, build_prompt};
+pub use metrics::{
+    DEFAULT_POOR_SCORE, DaemonStats, LATENCY_WINDOW_SECS, LatencySummary, MetricsStore,
+    MetricsSummary, PeriodStats, QueryStats, RequestRecord, SearchHistoryEntry, SearchRecord,
+    SearchStats, StatsPeriod,
+};
+pub use openapi::openapi_documents;
+pub use pii::{PiiOutcome, pii_kinds, screen_pii};
+pub use redact::{redact_secrets, redaction_placeholder};
+pub use retention::{ExpiredSource, PruneReport, prune};
+pub use search::{MAX_SEARCH_LIMIT, SearchOptions, SearchRun, SearchService};
+pub use summary::{
+    SUMMARY_INPUT_CHARS, SUMMARY_MAX_CHARS, Summarizer, extractive_summary, summary_chunk,
+    truncate_chars,
+};
 
+pub use tabular::{DEFAULT_ROWS_PER_CHUNK, RowTemplate, Table, table_documents};
 pub use vector_store::{
-    CollectionInfo, EMBEDDING_DIM, PgVectorBackend, QdrantBackend, VectorStore, create_backend,
+    ChunkMatcher, ChunkPage, CollectionInfo, EMBEDDING_DIM, IndexInventory, IndexedDocument,
+    MemoryBackend, PgVectorBackend, QdrantBackend, SourceInventory, VectorStore, check_dimension,
+    create_backend, create_backend_with_dimension, create_backend_with_embedding_config,
+    expand_context, keyword_fallback,
 };
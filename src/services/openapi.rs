@@ -0,0 +1,573 @@
+//! OpenAPI / Swagger specs split into one document per endpoint.
+//!
+//! Parameters, request bodies, and responses are flattened to indented
+//! property lists so an endpoint can be found by what it does or what it
+//! takes ("cancel order", "refund reason"), not just by its path.
+
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use serde_json::{Map, Value};
+
+use crate::error::ImportError;
+use crate::models::{Document, DocumentMetadata, Source, SourceType, Tag};
+use crate::utils::file::calculate_checksum;
+use crate::utils::text::slug;
+
+const METHODS: [&str; 8] = [
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// Nested schemas below this depth are summarized by type only.
+const MAX_SCHEMA_DEPTH: usize = 6;
+
+/// Parse a JSON or YAML spec and build a document per operation.
+///
+/// `location` (the spec's path) prefixes each document's location, followed
+/// by a JSON pointer to the operation, e.g. `api.yaml#/paths/~1orders/post`.
+pub fn openapi_documents(
+    input: &str,
+    location: &str,
+    tags: &[Tag],
+) -> Result<Vec<Document>, ImportError> {
+    let spec = parse_spec(input)?;
+    if spec.get("openapi").is_none() && spec.get("swagger").is_none() {
+        return Err(ImportError::ValidationError(
+            "not an OpenAPI spec: missing 'openapi' or 'swagger' version".to_string(),
+        ));
+    }
+    let paths = spec
+        .get("paths")
+        .and_then(Value::as_object)
+        .ok_or_else(|| ImportError::ValidationError("spec has no paths".to_string()))?;
+
+    let api_title = spec
+        .pointer("/info/title")
+        .and_then(Value::as_str)
+        .unwrap_or("API")
+        .to_string();
+    let resolver = Resolver { spec: &spec };
+
+    let mut documents = Vec::new();
+    for (path, item) in paths {
+        let item = resolver.resolve(item);
+        let shared_params = item
+            .get("parameters")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+
+        for method in METHODS {
+            let Some(operation) = item.get(method) else {
+                continue;
+            };
+            let endpoint = Endpoint {
+                api_title: &api_title,
+                path,
+                method,
+                operation,
+                shared_params: &shared_params,
+            };
+            documents.push(endpoint.to_document(&resolver, location, tags));
+        }
+    }
+    Ok(documents)
+}
+
+fn parse_spec(input: &str) -> Result<Value, ImportError> {
+    if input.trim_start().starts_with('{') {
+        return Ok(serde_json::from_str(input)?);
+    }
+    let yaml: serde_yaml::Value = serde_yaml::from_str(input)?;
+    Ok(yaml_to_json(yaml))
+}
+
+/// YAML to JSON, stringifying non-string keys such as `200:` response codes.
+fn yaml_to_json(value: serde_yaml::Value) -> Value {
+    match value {
+        serde_yaml::Value::Null => Value::Null,
+        serde_yaml::Value::Bool(b) => Value::Bool(b),
+        serde_yaml::Value::Number(n) => serde_json::to_value(n).unwrap_or(Value::Null),
+        serde_yaml::Value::String(s) => Value::String(s),
+        serde_yaml::Value::Sequence(items) => {
+            Value::Array(items.into_iter().map(yaml_to_json).collect())
+        }
+        serde_yaml::Value::Mapping(mapping) => {
+            let mut map = Map::new();
+            for (key, value) in mapping {
+                let key = match yaml_to_json(key) {
+                    Value::String(s) => s,
+                    other => other.to_string(),
+                };
+                map.insert(key, yaml_to_json(value));
+            }
+            Value::Object(map)
+        }
+        serde_yaml::Value::Tagged(tagged) => yaml_to_json(tagged.value),
+    }
+}
+
+/// Follows local `$ref`s (`#/components/...`, `#/definitions/...`).
+struct Resolver<'a> {
+    spec: &'a Value,
+}
+
+impl<'a> Resolver<'a> {
+    fn resolve<'v>(&'v self, value: &'v Value) -> &'v Value
+    where
+        'a: 'v,
+    {
+        let mut current = value;
+        // Bounded so a ref cycle can't loop forever
+        for _ in 0..MAX_SCHEMA_DEPTH {
+            match ref_target(current).and_then(|pointer| self.spec.pointer(pointer)) {
+                Some(target) => current = target,
+                None => break,
+            }
+        }
+        current
+    }
+}
+
+fn ref_target(value: &Value) -> Option<&str> {
+    value.get("$ref")?.as_str()?.strip_prefix('#')
+}
+
+/// Name of the schema a `$ref` points to, e.g. `Order` for `#/components/schemas/Order`.
+fn ref_name(value: &Value) -> Option<&str> {
+    ref_target(value)?.rsplit('/').next()
+}
+
+struct Endpoint<'a> {
+    api_title: &'a str,
+    path: &'a str,
+    method: &'a str,
+    operation: &'a Value,
+    shared_params: &'a [Value],
+}
+
+impl Endpoint<'_> {
+    fn to_document(&self, resolver: &Resolver, location: &str, tags: &[Tag]) -> Document {
+        let op = self.operation;
+        let heading = format!("{} {}", self.method.to_uppercase(), self.path);
+        let text = |key: &str| op.get(key).and_then(Value::as_str).map(str::trim);
+        let op_tags: Vec<&str> = op
+            .get("tags")
+            .and_then(Value::as_array)
+            .map(|t| t.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+
+        let mut content = format!("# {}\n\n", heading);
+        writeln!(content, "API: {}", self.api_title).unwrap();
+        if let Some(id) = text("operationId") {
+            writeln!(content, "Operation: {}", id).unwrap();
+        }
+        if let Some(summary) = text("summary") {
+            writeln!(content, "Summary: {}", summary).unwrap();
+        }
+        if !op_tags.is_empty() {
+            writeln!(content, "Tags: {}", op_tags.join(", ")).unwrap();
+        }
+        if op.get("deprecated").and_then(Value::as_bool) == Some(true) {
+            writeln!(content, "Deprecated").unwrap();
+        }
+        if let Some(description) = text("description").filter(|d| !d.is_empty()) {
+            write!(content, "\n{}\n", description).unwrap();
+        }
+
+        // Operation parameters override path-level ones with the same name and location
+        let mut params: Vec<&Value> = Vec::new();
+        let op_params = op.get("parameters").and_then(Value::as_array);
+        for param in op_params
+            .into_iter()
+            .flatten()
+            .chain(self.shared_params.iter())
+        {
+            let param = resolver.resolve(param);
+            let key = (param.get("name"), param.get("in"));
+            if !params.iter().any(|p| (p.get("name"), p.get("in")) == key) {
+                params.push(param);
+            }
+        }
+
+        let (body_params, params): (Vec<&Value>, Vec<&Value>) = params
+            .into_iter()
+            .partition(|p| p.get("in").and_then(Value::as_str) == Some("body"));
+        if !params.is_empty() {
+            content.push_str("\n## Parameters\n");
+            for param in params {
+                let name = param.get("name").and_then(Value::as_str).unwrap_or("?");
+                let place = param.get("in").and_then(Value::as_str).unwrap_or("query");
+                // OpenAPI 3 nests the type under `schema`; Swagger 2 puts it on the parameter
+                let schema = param.get("schema").map_or(param, |s| resolver.resolve(s));
+                let mut attrs = vec![place.to_string(), type_label(schema, resolver)];
+                if param.get("required").and_then(Value::as_bool) == Some(true) {
+                    attrs.push("required".to_string());
+                }
+                write!(content, "- {} ({})", name, attrs.join(", ")).unwrap();
+                if let Some(description) = param.get("description").and_then(Value::as_str) {
+                    write!(content, ": {}", one_line(description)).unwrap();
+                }
+                content.push('\n');
+            }
+        }
+
+        // Swagger 2 body parameter, or OpenAPI 3 requestBody
+        if let Some(body) = body_params.first() {
+            content.push_str("\n## Request body\n");
+            if let Some(schema) = body.get("schema") {
+                flatten_schema(schema, resolver, "", 0, &mut HashSet::new(), &mut content);
+            }
+        } else if let Some(body) = op.get("requestBody") {
+            let body = resolver.resolve(body);
+            if let Some((media, schema)) = media_schema(body) {
+                writeln!(content, "\n## Request body ({})", media).unwrap();
+                if let Some(description) = body.get("description").and_then(Value::as_str) {
+                    writeln!(content, "{}", one_line(description)).unwrap();
+                }
+                flatten_schema(schema, resolver, "", 0, &mut HashSet::new(), &mut content);
+            }
+        }
+
+        if let Some(responses) = op.get("responses").and_then(Value::as_object) {
+            content.push_str("\n## Responses\n");
+            for (status, response) in responses {
+                let response = resolver.resolve(response);
+                write!(content, "- {}", status).unwrap();
+                if let Some(description) = response.get("description").and_then(Value::as_str) {
+                    write!(content, ": {}", one_line(description)).unwrap();
+                }
+                content.push('\n');
+                let schema = response
+                    .get("schema")
+                    .or_else(|| media_schema(response).map(|(_, schema)| schema));
+                if let Some(schema) = schema {
+                    flatten_schema(schema, resolver, "  ", 0, &mut HashSet::new(), &mut content);
+                }
+            }
+        }
+
+        let pointer_path = self.path.replace('~', "~0").replace('/', "~1");
+        let doc_location = format!("{}#/paths/{}/{}", location, pointer_path, self.method);
+        let source = Source::new(SourceType::Other("openapi".to_string()), doc_location, None);
+        let checksum = calculate_checksum(&content);
+
+        let mut path_parts = vec![self.api_title.to_string()];
+        if let Some(tag) = op_tags.first() {
+            path_parts.push(tag.to_string());
+        }
+        path_parts.push(heading.clone());
+        let metadata = DocumentMetadata {
+            filename: None,
+            extension: None,
+            language: Some("markdown".to_string()),
+            title: Some(match text("summary") {
+                Some(summary) => format!("{} - {}", heading, summary),
+                None => heading,
+            }),
+            path: Some(path_parts.join(" > ")),
+            size_bytes: content.len() as u64,
+        };
+
+        let mut tag_strs = vec![
+            "source:openapi".to_string(),
+            format!("api-path:{}", path_tag_value(self.path)),
+            format!("api-method:{}", self.method),
+        ];
+        for tag in &op_tags {
+            tag_strs.push(format!("api-tag:{}", slug(tag)));
+        }
+        let mut all_tags = tags.to_vec();
+        for tag_str in tag_strs {
+            if let Ok(tag) = tag_str.parse::<Tag>()
+                && !all_tags.contains(&tag)
+            {
+                all_tags.push(tag);
+            }
+        }
+
+        Document::new(content, source, all_tags, checksum, metadata)
+    }
+}
+
+/// The JSON media type's schema if there is one, else the first listed.
+fn media_schema(value: &Value) -> Option<(&str, &Value)> {
+    let content = value.get("content")?.as_object()?;
+    let (media, entry) = content
+        .iter()
+        .find(|(media, _)| media.contains("json"))
+        .or_else(|| content.iter().next())?;
+    Some((media.as_str(), entry.get("schema")?))
+}
+
+/// Short type description, e.g. `string (uuid)`, `array of Order`, `enum: a, b`.
+fn type_label(schema: &Value, resolver: &Resolver) -> String {
+    if let Some(name) = ref_name(schema) {
+        return name.to_string();
+    }
+    let schema = resolver.resolve(schema);
+    let mut label = match schema.get("type").and_then(Value::as_str) {
+        Some("array") => match schema.get("items") {
+            Some(items) => format!("array of {}", type_label(items, resolver)),
+            None => "array".to_string(),
+        },
+        Some(kind) => kind.to_string(),
+        None if schema.get("properties").is_some() => "object".to_string(),
+        None => ["oneOf", "anyOf"]
+            .iter()
+            .find_map(|key| schema.get(*key).and_then(Value::as_array))
+            .map(|variants| {
+                variants
+                    .iter()
+                    .map(|v| type_label(v, resolver))
+                    .collect::<Vec<_>>()
+                    .join(" | ")
+            })
+            .unwrap_or_else(|| "any".to_string()),
+    };
+    if let Some(format) = schema.get("format").and_then(Value::as_str) {
+        write!(label, " ({})", format).unwrap();
+    }
+    if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+        let values: Vec<String> = values
+            .iter()
+            .map(|v| v.as_str().map_or_else(|| v.to_string(), String::from))
+            .collect();
+        write!(label, ", enum: {}", values.join(", ")).unwrap();
+    }
+    label
+}
+
+/// Append the properties of an object schema as an indented list.
+fn flatten_schema(
+    schema: &Value,
+    resolver: &Resolver,
+    indent: &str,
+    depth: usize,
+    visiting: &mut HashSet<String>,
+    out: &mut String,
+) {
+    if depth >= MAX_SCHEMA_DEPTH {
+        return;
+    }
+    // Recursive schemas (a Category with child Categories) are listed once
+    let ref_key = ref_target(schema).map(String::from);
+    if let Some(ref key) = ref_key
+        && !visiting.insert(key.clone())
+    {
+        return;
+    }
+
+    let resolved = resolver.resolve(schema);
+    if resolved.get("type").and_then(Value::as_str) == Some("array") {
+        if let Some(items) = resolved.get("items") {
+            writeln!(out, "{}- items: {}", indent, type_label(items, resolver)).unwrap();
+            flatten_schema(
+                items,
+                resolver,
+                &format!("{}  ", indent),
+                depth + 1,
+                visiting,
+                out,
+            );
+        }
+    } else {
+        // allOf parts contribute their properties to the same object
+        let mut parts = vec![resolved];
+        if let Some(all_of) = resolved.get("allOf").and_then(Value::as_array) {
+            parts.extend(all_of.iter().map(|part| resolver.resolve(part)));
+        }
+        for part in parts {
+            let required: Vec<&str> = part
+                .get("required")
+                .and_then(Value::as_array)
+                .map(|r| r.iter().filter_map(Value::as_str).collect())
+                .unwrap_or_default();
+            let Some(properties) = part.get("properties").and_then(Value::as_object) else {
+                continue;
+            };
+            for (name, property) in properties {
+                write!(
+                    out,
+                    "{}- {} ({}",
+                    indent,
+                    name,
+                    type_label(property, resolver)
+                )
+                .unwrap();
+                if required.contains(&name.as_str()) {
+                    out.push_str(", required");
+                }
+                out.push(')');
+                let described = resolver.resolve(property);
+                if let Some(description) = described.get("description").and_then(Value::as_str) {
+                    write!(out, ": {}", one_line(description)).unwrap();
+                }
+                out.push('\n');
+                flatten_schema(
+                    property,
+                    resolver,
+                    &format!("{}  ", indent),
+                    depth + 1,
+                    visiting,
+                    out,
+                );
+            }
+        }
+    }
+
+    if let Some(key) = ref_key {
+        visiting.remove(&key);
+    }
+}
+
+fn one_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Tag value for a path: segments joined with `.`, parameter braces dropped
+/// (`/orders/{orderId}/cancel` becomes `orders.orderId.cancel`).
+fn path_tag_value(path: &str) -> String {
+    let value = path
+        .split('/')
+        .map(|segment| {
+            segment
+                .chars()
+                .filter(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'))
+                .collect::<String>()
+        })
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>()
+        .join(".");
+    if value.is_empty() {
+        "root".to_string()
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC: &str = r##"
+openapi: 3.0.3
+info:
+  title: Shop API
+paths:
+  /orders/{orderId}/cancel:
+    parameters:
+      - $ref: "#/components/parameters/OrderId"
+    post:
+      operationId: cancelOrder
+      summary: Cancel an order
+      tags: [Order Management]
+      requestBody:
+        content:
+          application/json:
+            schema:
+              type: object
+              required: [reason]
+              properties:
+                reason:
+                  type: string
+                  enum: [customer_request, fraud]
+                  description: Why the order is cancelled
+      responses:
+        200:
+          description: The cancelled order
+          content:
+            application/json:
+              schema:
+                $ref: "#/components/schemas/Order"
+        "404":
+          description: Order not found
+  /orders:
+    get:
+      summary: List orders
+      responses:
+        "200":
+          description: Orders
+components:
+  parameters:
+    OrderId:
+      name: orderId
+      in: path
+      required: true
+      schema:
+        type: string
+        format: uuid
+  schemas:
+    Order:
+      type: object
+      properties:
+        id:
+          type: string
+        parent:
+          $ref: "#/components/schemas/Order"
+        lines:
+          type: array
+          items:
+            type: object
+            properties:
+              sku:
+                type: string
+"##;
+
+    #[test]
+    fn test_openapi_documents() {
+        let docs = openapi_documents(SPEC, "shop.yaml", &[]).unwrap();
+        assert_eq!(docs.len(), 2);
+
+        let cancel = &docs[0];
+        assert_eq!(
+            cancel.source.location,
+            "shop.yaml#/paths/~1orders~1{orderId}~1cancel/post"
+        );
+        assert_eq!(
+            cancel.content,
+            "# POST /orders/{orderId}/cancel\n\n\
+             API: Shop API\n\
+             Operation: cancelOrder\n\
+             Summary: Cancel an order\n\
+             Tags: Order Management\n\
+             \n## Parameters\n\
+             - orderId (path, string (uuid), required)\n\
+             \n## Request body (application/json)\n\
+             - reason (string, enum: customer_request, fraud, required): Why the order is cancelled\n\
+             \n## Responses\n\
+             - 200: The cancelled order\n  \
+             - id (string)\n  \
+             - parent (Order)\n  \
+             - lines (array of object)\n    \
+             - items: object\n      \
+             - sku (string)\n\
+             - 404: Order not found\n"
+        );
+
+        let tags: Vec<String> = cancel.tags.iter().map(ToString::to_string).collect();
+        assert!(tags.contains(&"api-path:orders.orderId.cancel".to_string()));
+        assert!(tags.contains(&"api-method:post".to_string()));
+        assert!(tags.contains(&"api-tag:order-management".to_string()));
+    }
+
+    #[test]
+    fn test_swagger_body_parameter() {
+        let spec = r##"{
+            "swagger": "2.0",
+            "info": {"title": "Legacy"},
+            "paths": {"/refunds": {"post": {
+                "parameters": [{"in": "body", "name": "body", "schema": {"$ref": "#/definitions/Refund"}}],
+                "responses": {"201": {"description": "Created"}}
+            }}},
+            "definitions": {"Refund": {"properties": {"amount": {"type": "integer", "format": "int64"}}}}
+        }"##;
+        let docs = openapi_documents(spec, "legacy.json", &[]).unwrap();
+        assert!(
+            docs[0]
+                .content
+                .contains("## Request body\n- amount (integer (int64))\n")
+        );
+        assert!(openapi_documents("{\"paths\": {}}", "x.json", &[]).is_err());
+    }
+}
@@ -0,0 +1,133 @@
+//! Personal data detection for `[indexing] pii`.
+//!
+//! Documents containing email addresses, phone numbers or national ID numbers
+//! are tagged `pii:true` or left out of the index, depending on the policy.
+
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::models::{Document, PiiPolicy, pii_tag};
+
+/// Kinds of personal data, checked in order.
+static PII_PATTERNS: LazyLock<Vec<(&'static str, Regex)>> = LazyLock::new(|| {
+    [
+        (
+            "email",
+            r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}\b",
+        ),
+        // +1 415 555 0100, (415) 555-0100, 010-1234-5678
+        (
+            "phone",
+            r"(?:\+\d{1,3}[ .-]?)?(?:\(\d{2,4}\)|\b\d{2,4})[ .-]\d{3,4}[ .-]\d{4}\b",
+        ),
+        // US social security number
+        (
+            "ssn",
+            r"\b(?:00[1-9]|0[1-9]\d|[1-578]\d\d|6[0-57-9]\d|66[0-57-9])-\d{2}-\d{4}\b",
+        ),
+        // Korean resident registration number
+        (
+            "rrn",
+            r"\b\d{2}(?:0[1-9]|1[0-2])(?:0[1-9]|[12]\d|3[01])-[1-8]\d{6}\b",
+        ),
+    ]
+    .into_iter()
+    .map(|(kind, pattern)| (kind, Regex::new(pattern).expect("valid regex")))
+    .collect()
+});
+
+/// The kinds of personal data found in `text`, e.g. `["email", "phone"]`.
+pub fn pii_kinds(text: &str) -> Vec<&'static str> {
+    PII_PATTERNS
+        .iter()
+        .filter(|(_, regex)| regex.is_match(text))
+        .map(|(kind, _)| *kind)
+        .collect()
+}
+
+/// What [`screen_pii`] did with a document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PiiOutcome {
+    /// No personal data found, or the policy ignores it
+    Clean,
+    /// Tagged `pii:true`; holds the kinds found
+    Flagged(Vec<&'static str>),
+    /// Must not be indexed; holds the kinds found
+    Skipped(Vec<&'static str>),
+}
+
+/// Apply `policy` to a document before it is chunked.
+pub fn screen_pii(policy: PiiPolicy, document: &mut Document) -> PiiOutcome {
+    if policy == PiiPolicy::Ignore {
+        return PiiOutcome::Clean;
+    }
+    let kinds = pii_kinds(&document.content);
+    if kinds.is_empty() {
+        return PiiOutcome::Clean;
+    }
+    match policy {
+        PiiPolicy::Skip => PiiOutcome::Skipped(kinds),
+        _ => {
+            let tag = pii_tag();
+            if !document.tags.contains(&tag) {
+                document.tags.push(tag);
+            }
+            PiiOutcome::Flagged(kinds)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{DocumentMetadata, Source};
+
+    fn document(content: &str) -> Document {
+        Document::new(
+            content.to_string(),
+            Source::local("notes.md"),
+            Vec::new(),
+            String::new(),
+            DocumentMetadata::default(),
+        )
+    }
+
+    #[test]
+    fn test_pii_kinds() {
+        assert_eq!(pii_kinds("Contact jane.doe@example.co.uk"), vec!["email"]);
+        assert_eq!(pii_kinds("Call (415) 555-0100 today"), vec!["phone"]);
+        assert_eq!(pii_kinds("휴대폰 010-1234-5678"), vec!["phone"]);
+        assert_eq!(pii_kinds("SSN 123-45-6789"), vec!["ssn"]);
+        assert_eq!(pii_kinds("주민번호 900101-1234567"), vec!["rrn"]);
+        assert!(pii_kinds("Released 2024-10-16, version 1.2.3, port 8080").is_empty());
+        assert!(pii_kinds("use crate::models::Tag; x@y").is_empty());
+    }
+
+    #[test]
+    fn test_screen_pii() {
+        let mut doc = document("Owner: jane@example.com");
+        assert_eq!(screen_pii(PiiPolicy::Ignore, &mut doc), PiiOutcome::Clean);
+        assert!(doc.tags.is_empty());
+
+        assert_eq!(
+            screen_pii(PiiPolicy::Flag, &mut doc),
+            PiiOutcome::Flagged(vec!["email"])
+        );
+        assert_eq!(
+            screen_pii(PiiPolicy::Flag, &mut doc),
+            PiiOutcome::Flagged(vec!["email"])
+        );
+        assert_eq!(doc.tags, vec![pii_tag()]);
+
+        let mut doc = document("Owner: jane@example.com");
+        assert_eq!(
+            screen_pii(PiiPolicy::Skip, &mut doc),
+            PiiOutcome::Skipped(vec!["email"])
+        );
+        assert!(doc.tags.is_empty());
+
+        let mut doc = document("No personal data here");
+        assert_eq!(screen_pii(PiiPolicy::Skip, &mut doc), PiiOutcome::Clean);
+    }
+}
@@ -0,0 +1,234 @@
+//! `index prune`: drop chunks that outlived `[indexing.retention]`.
+//!
+//! Chunks are selected by their document's `updated_at`, so a document is
+//! removed whole; soft-deleted chunks count and expire like live ones. Counts
+//! come from the store before anything is deleted, which keeps a dry run's
+//! report identical to a real one.
+
+use chrono::{DateTime, TimeDelta, TimeZone, Utc};
+use serde::Serialize;
+
+use crate::error::VectorStoreError;
+use crate::models::{Deleted, RangeOp, SearchFilter, SourceType, TimeBound, TimeField};
+use crate::services::VectorStore;
+
+/// Chunks of one source type past its `max_age`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExpiredSource {
+    pub source_type: SourceType,
+    /// Chunks last updated before this are expired
+    pub cutoff: DateTime<Utc>,
+    pub chunks: u64,
+}
+
+/// What [`prune`] removed, or would remove on a dry run.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct PruneReport {
+    /// Chunks in the collection before pruning
+    pub chunks_before: u64,
+    pub expired: Vec<ExpiredSource>,
+    /// Chunks not yet expired that were removed to get under `max_points`
+    pub over_budget: u64,
+    /// Chunks last updated before this were removed for `max_points`
+    pub budget_cutoff: Option<DateTime<Utc>>,
+}
+
+impl PruneReport {
+    pub fn removed(&self) -> u64 {
+        self.expired.iter().map(|e| e.chunks).sum::<u64>() + self.over_budget
+    }
+
+    pub fn chunks_after(&self) -> u64 {
+        self.chunks_before.saturating_sub(self.removed())
+    }
+}
+
+fn updated_between(
+    source_type: Option<&SourceType>,
+    from: Option<DateTime<Utc>>,
+    before: DateTime<Utc>,
+) -> SearchFilter {
+    let mut bounds = vec![TimeBound::new(TimeField::UpdatedAt, RangeOp::Lt, before)];
+    if let Some(from) = from {
+        bounds.push(TimeBound::new(TimeField::UpdatedAt, RangeOp::Gte, from));
+    }
+    SearchFilter::new()
+        .with_source_types(source_type.into_iter().cloned().collect())
+        .with_time_bounds(bounds)
+        .with_deleted(Deleted::Any)
+}
+
+/// Chunks left after removing the expired ones and everything updated
+/// before `cutoff`.
+async fn remaining_after(
+    store: &dyn VectorStore,
+    unexpired: u64,
+    expired: &[ExpiredSource],
+    cutoff: DateTime<Utc>,
+) -> Result<u64, VectorStoreError> {
+    let mut removed = store
+        .count_by_filter(&updated_between(None, None, cutoff))
+        .await?;
+    // Expired chunks are already gone, whether or not they predate the cutoff
+    for source in expired {
+        if source.cutoff > cutoff {
+            let filter = updated_between(Some(&source.source_type), Some(cutoff), source.cutoff);
+            removed += store.count_by_filter(&filter).await?;
+        }
+    }
+    let expired_total: u64 = expired.iter().map(|e| e.chunks).sum();
+    Ok((unexpired + expired_total).saturating_sub(removed))
+}
+
+/// Remove chunks older than their source type's `max_age`, then the least
+/// recently updated ones until at most `max_points` remain.
+///
+/// Chunks without an `updated_at` are never pruned, so a collection of them
+/// may stay over budget.
+pub async fn prune(
+    store: &dyn VectorStore,
+    max_ages: &[(SourceType, TimeDelta)],
+    max_points: Option<u64>,
+    now: DateTime<Utc>,
+    dry_run: bool,
+) -> Result<PruneReport, VectorStoreError> {
+    let mut report = PruneReport {
+        chunks_before: store
+            .count_by_filter(&SearchFilter::new().with_deleted(Deleted::Any))
+            .await?,
+        ..Default::default()
+    };
+
+    for (source_type, max_age) in max_ages {
+        let cutoff = now - *max_age;
+        let chunks = store
+            .count_by_filter(&updated_between(Some(source_type), None, cutoff))
+            .await?;
+        report.expired.push(ExpiredSource {
+            source_type: source_type.clone(),
+            cutoff,
+            chunks,
+        });
+    }
+
+    let unexpired = report.chunks_after();
+    if let Some(max_points) = max_points
+        && unexpired > max_points
+    {
+        // Earliest cutoff (to the second) that gets under budget
+        let (mut low, mut high) = (0, now.timestamp());
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let cutoff = Utc.timestamp_opt(mid, 0).single().unwrap_or(now);
+            if remaining_after(store, unexpired, &report.expired, cutoff).await? <= max_points {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+        let cutoff = Utc.timestamp_opt(high, 0).single().unwrap_or(now);
+        let remaining = remaining_after(store, unexpired, &report.expired, cutoff).await?;
+        report.over_budget = unexpired - remaining;
+        report.budget_cutoff = Some(cutoff);
+    }
+
+    if !dry_run {
+        for source in &report.expired {
+            let filter = updated_between(Some(&source.source_type), None, source.cutoff);
+            store.delete_by_filter(&filter).await?;
+        }
+        if let Some(cutoff) = report.budget_cutoff {
+            store
+                .delete_by_filter(&updated_between(None, None, cutoff))
+                .await?;
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{DocumentBuilder, MockEmbeddingProvider, MockVectorStore};
+
+    #[tokio::test]
+    async fn test_prune() {
+        let now = Utc::now();
+        let days_ago = |days: i64| Some(now - TimeDelta::days(days));
+        let documents = [
+            DocumentBuilder::new("PROJ-1", "Login fails after the token refresh.")
+                .source_type(SourceType::Jira)
+                .build()
+                .with_timestamps(None, days_ago(200)),
+            DocumentBuilder::new("PROJ-2", "Dashboard loads slowly on Mondays.")
+                .source_type(SourceType::Jira)
+                .build()
+                .with_timestamps(None, days_ago(10)),
+            DocumentBuilder::new("docs/old.md", "Deploy with the legacy scripts.")
+                .build()
+                .with_timestamps(None, days_ago(400)),
+            DocumentBuilder::new("docs/new.md", "Deploy with the pipeline.")
+                .build()
+                .with_timestamps(None, days_ago(1)),
+        ];
+        let store = MockVectorStore::default();
+        crate::testing::index(&MockEmbeddingProvider::default(), &store, &documents)
+            .await
+            .unwrap();
+
+        let max_ages = [(SourceType::Jira, TimeDelta::days(90))];
+        let dry_run = prune(&store, &max_ages, Some(2), now, true).await.unwrap();
+        assert_eq!(dry_run.chunks_before, 4);
+        assert_eq!(dry_run.expired[0].chunks, 1);
+        // docs/old.md is the oldest left
+        assert_eq!(dry_run.over_budget, 1);
+        assert_eq!(dry_run.chunks_after(), 2);
+        assert_eq!(
+            store.count_by_filter(&SearchFilter::new()).await.unwrap(),
+            4
+        );
+
+        let report = prune(&store, &max_ages, Some(2), now, false).await.unwrap();
+        assert_eq!(report, dry_run);
+        let filter = SearchFilter::new().with_source_types(vec![SourceType::Local]);
+        assert_eq!(store.count_by_filter(&filter).await.unwrap(), 1);
+        assert_eq!(
+            store.count_by_filter(&SearchFilter::new()).await.unwrap(),
+            2
+        );
+
+        // Already within every limit
+        let report = prune(&store, &max_ages, Some(2), now, false).await.unwrap();
+        assert_eq!(report.removed(), 0);
+        assert!(report.budget_cutoff.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_prune_soft_deleted() {
+        let now = Utc::now();
+        let documents = [
+            DocumentBuilder::new("PROJ-1", "Login fails after the token refresh.")
+                .source_type(SourceType::Jira)
+                .build()
+                .with_timestamps(None, Some(now - TimeDelta::days(200))),
+            DocumentBuilder::new("PROJ-2", "Dashboard loads slowly on Mondays.")
+                .source_type(SourceType::Jira)
+                .build()
+                .with_timestamps(None, Some(now - TimeDelta::days(10))),
+        ];
+        let store = MockVectorStore::default();
+        crate::testing::index(&MockEmbeddingProvider::default(), &store, &documents)
+            .await
+            .unwrap();
+        let any = SearchFilter::new().with_deleted(Deleted::Any);
+        store.soft_delete_by_filter(&any, now).await.unwrap();
+
+        // Soft-deleted chunks expire like live ones
+        let max_ages = [(SourceType::Jira, TimeDelta::days(90))];
+        let report = prune(&store, &max_ages, None, now, false).await.unwrap();
+        assert_eq!(report.chunks_before, 2);
+        assert_eq!(report.removed(), 1);
+        assert_eq!(store.count_by_filter(&any).await.unwrap(), 1);
+    }
+}
@@ -0,0 +1,540 @@
+//! Query-side library API: embed a query, search the store and re-rank,
+//! the way `ssearch search`, the MCP server and the HTTP API do.
+
+use std::iter;
+use std::sync::Arc;
+use std::time::Instant;
+
+use chrono::Utc;
+
+use crate::error::{SearchError, VectorStoreError};
+use crate::models::{Config, SearchConfig, SearchFilter, SearchResult, SearchResults, summary_tag};
+use crate::services::fusion::{
+    DEDUP_CANDIDATE_FACTOR, RECENCY_CANDIDATE_FACTOR, apply_recency, reciprocal_rank_fusion,
+    rescore, suppress_near_duplicates,
+};
+use crate::services::{
+    EmbeddingClient, EmbeddingProvider, VectorStore, create_backend_with_embedding_config,
+    keyword_fallback,
+};
+
+/// Most results one search request to the daemon or MCP server may return.
+pub const MAX_SEARCH_LIMIT: u32 = 100;
+
+/// How one [`SearchService::search`] call ranks and filters.
+#[derive(Debug, Clone)]
+pub struct SearchOptions {
+    pub limit: u32,
+    /// Results to skip, to page through large result sets
+    pub offset: u32,
+    pub min_score: Option<f32>,
+    /// Fuse keyword (BM25) relevance with semantic similarity
+    pub hybrid: bool,
+    /// Re-score candidates with the cross-encoder
+    pub rerank: bool,
+    /// Weight of recency in the final score (0.0 = off)
+    pub recency: f32,
+    /// Drop results this similar (cosine) to a higher-ranked one
+    pub dedup_threshold: Option<f32>,
+    /// Retry an empty semantic search as a keyword search
+    pub keyword_fallback: bool,
+    /// Reformulations searched alongside the query, fused by rank
+    pub expansions: Vec<String>,
+    /// Match document summaries first, then search only those documents' chunks
+    pub summaries_only: bool,
+    /// Keep the query's own rankings in [`SearchRun::explained`]
+    pub explain: bool,
+    pub filter: SearchFilter,
+}
+
+impl SearchOptions {
+    /// The `[search]` defaults, with no filter.
+    pub fn from_config(config: &SearchConfig) -> Self {
+        Self {
+            limit: config.default_limit,
+            offset: 0,
+            min_score: config.default_min_score,
+            hybrid: false,
+            rerank: config.rerank,
+            recency: config.recency_weight,
+            dedup_threshold: config.dedup_threshold,
+            keyword_fallback: config.keyword_fallback,
+            expansions: Vec::new(),
+            summaries_only: false,
+            explain: false,
+            filter: SearchFilter::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_limit(mut self, limit: u32) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    #[must_use]
+    pub fn with_offset(mut self, offset: u32) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    #[must_use]
+    pub fn with_min_score(mut self, min_score: Option<f32>) -> Self {
+        self.min_score = min_score;
+        self
+    }
+
+    #[must_use]
+    pub fn with_hybrid(mut self, hybrid: bool) -> Self {
+        self.hybrid = hybrid;
+        self
+    }
+
+    #[must_use]
+    pub fn with_rerank(mut self, rerank: bool) -> Self {
+        self.rerank = rerank;
+        self
+    }
+
+    #[must_use]
+    pub fn with_recency(mut self, recency: f32) -> Self {
+        self.recency = recency;
+        self
+    }
+
+    #[must_use]
+    pub fn with_dedup_threshold(mut self, threshold: Option<f32>) -> Self {
+        self.dedup_threshold = threshold;
+        self
+    }
+
+    #[must_use]
+    pub fn with_keyword_fallback(mut self, keyword_fallback: bool) -> Self {
+        self.keyword_fallback = keyword_fallback;
+        self
+    }
+
+    #[must_use]
+    pub fn with_expansions(mut self, expansions: Vec<String>) -> Self {
+        self.expansions = expansions;
+        self
+    }
+
+    #[must_use]
+    pub fn with_summaries_only(mut self, summaries_only: bool) -> Self {
+        self.summaries_only = summaries_only;
+        self
+    }
+
+    #[must_use]
+    pub fn with_explain(mut self, explain: bool) -> Self {
+        self.explain = explain;
+        self
+    }
+
+    #[must_use]
+    pub fn with_filter(mut self, filter: SearchFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Check that the limit and every weight or threshold are in range.
+    pub fn validate(&self) -> Result<(), SearchError> {
+        let invalid = |message: &str| Err(SearchError::InvalidQuery(message.to_string()));
+        if self.limit == 0 {
+            return invalid("limit must be at least 1");
+        }
+        if self.min_score.is_some_and(|s| !(0.0..=1.0).contains(&s)) {
+            return invalid("min_score must be between 0.0 and 1.0");
+        }
+        if !(0.0..=1.0).contains(&self.recency) {
+            return invalid("recency must be between 0.0 and 1.0");
+        }
+        if self
+            .dedup_threshold
+            .is_some_and(|t| !(0.0..=1.0).contains(&t))
+        {
+            return invalid("dedup_threshold must be between 0.0 and 1.0");
+        }
+        Ok(())
+    }
+
+    /// Whether results are reordered after retrieval, so pages are cut from
+    /// the reordered candidates rather than by the vector store.
+    fn reorders(&self) -> bool {
+        self.rerank
+            || !self.expansions.is_empty()
+            || self.recency > 0.0
+            || self.dedup_threshold.is_some()
+            || self.summaries_only
+    }
+
+    /// Results to rank before the page is cut.
+    fn wanted(&self) -> u32 {
+        if self.reorders() {
+            self.limit.saturating_add(self.offset)
+        } else {
+            self.limit
+        }
+    }
+
+    /// Results to fetch from the store so reranking, recency and dedup
+    /// still leave `limit` good ones.
+    pub fn candidates(&self, rerank_top_k: u32) -> u32 {
+        let wanted = self.wanted();
+        if self.rerank {
+            wanted.max(rerank_top_k)
+        } else if self.recency > 0.0 {
+            wanted.saturating_mul(RECENCY_CANDIDATE_FACTOR)
+        } else if self.dedup_threshold.is_some() {
+            wanted.saturating_mul(DEDUP_CANDIDATE_FACTOR)
+        } else {
+            wanted
+        }
+    }
+}
+
+/// One [`SearchService::run`]: the page of results and how it was found.
+#[derive(Debug, Default)]
+pub struct SearchRun {
+    pub results: Vec<SearchResult>,
+    /// Filter searched, narrowed to the matched documents with `summaries_only`
+    pub filter: SearchFilter,
+    /// The query's own dense and (with `hybrid`) keyword rankings before
+    /// fusion or reranking, with `explain`
+    pub explained: Option<(Vec<SearchResult>, Option<Vec<SearchResult>>)>,
+    /// Documents whose summaries matched, with `summaries_only`
+    pub summary_documents: usize,
+    /// Matches of the keyword fallback, when the semantic search found nothing
+    pub keyword_fallback: Option<Result<usize, VectorStoreError>>,
+    /// Results dropped as near-duplicates
+    pub suppressed: usize,
+    pub embed_ms: u64,
+    pub search_ms: u64,
+    pub rerank_ms: u64,
+}
+
+/// Searches one collection with one embedding model.
+///
+/// ```ignore
+/// let service = SearchService::from_config(&Config::load()?.config).await?;
+/// let options = service.options().with_limit(5).with_hybrid(true);
+/// let results = service.search("token refresh", &options).await?;
+/// ```
+pub struct SearchService {
+    embedder: Arc<dyn EmbeddingProvider>,
+    store: Arc<dyn VectorStore>,
+    config: SearchConfig,
+}
+
+impl SearchService {
+    pub fn new(
+        embedder: Arc<dyn EmbeddingProvider>,
+        store: Arc<dyn VectorStore>,
+        config: SearchConfig,
+    ) -> Self {
+        Self {
+            embedder,
+            store,
+            config,
+        }
+    }
+
+    /// Embed through the daemon and search the configured collection.
+    pub async fn from_config(config: &Config) -> Result<Self, SearchError> {
+        let store =
+            create_backend_with_embedding_config(&config.vector_store, &config.embedding).await?;
+        Ok(Self::new(
+            Arc::new(EmbeddingClient::new(config)),
+            Arc::from(store),
+            config.search.clone(),
+        ))
+    }
+
+    /// Options starting from this service's `[search]` defaults.
+    pub fn options(&self) -> SearchOptions {
+        SearchOptions::from_config(&self.config)
+    }
+
+    pub fn store(&self) -> &dyn VectorStore {
+        self.store.as_ref()
+    }
+
+    /// Run `query`, best results first.
+    pub async fn search(
+        &self,
+        query: &str,
+        options: &SearchOptions,
+    ) -> Result<SearchResults, SearchError> {
+        let start = Instant::now();
+        let run = self.run(query, options).await?;
+        let total = run.results.len() as u64;
+        Ok(SearchResults::new(
+            query.trim().to_string(),
+            run.results,
+            total,
+            start.elapsed().as_millis() as u64,
+        )
+        .with_offset(u64::from(options.offset)))
+    }
+
+    /// [`Self::search`], returning the rankings and timings behind the results.
+    pub async fn run(
+        &self,
+        query: &str,
+        options: &SearchOptions,
+    ) -> Result<SearchRun, SearchError> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Err(SearchError::InvalidQuery(
+                "query cannot be empty".to_string(),
+            ));
+        }
+        options.validate()?;
+
+        let reorders = options.reorders();
+        let wanted = options.wanted();
+        let store_offset = if reorders { 0 } else { options.offset };
+        let mut candidates = options.candidates(self.config.rerank_top_k);
+        let min_score = options.min_score;
+        let mut filter = options.filter.clone();
+        let store = self.store.as_ref();
+        let mut run = SearchRun::default();
+
+        let queries: Vec<String> = iter::once(query.to_string())
+            .chain(options.expansions.iter().cloned())
+            .collect();
+        let embed_start = Instant::now();
+        let embeddings = self.embedder.embed_queries(queries.clone()).await?;
+        run.embed_ms = embed_start.elapsed().as_millis() as u64;
+        let Some(query_embedding) = embeddings.first().cloned() else {
+            return Ok(run);
+        };
+
+        let search_start = Instant::now();
+        if options.summaries_only {
+            let document_ids =
+                summary_documents(store, query_embedding.clone(), wanted, &filter).await?;
+            run.summary_documents = document_ids.len();
+            // Room for the summary chunks dropped from the results below
+            candidates = candidates.saturating_add(document_ids.len() as u32);
+            filter = filter.with_document_ids(document_ids);
+        }
+        let mut rankings = Vec::with_capacity(queries.len());
+        for (text, embedding) in queries.iter().zip(embeddings) {
+            let ranking = if options.hybrid {
+                store
+                    .hybrid_search(
+                        embedding,
+                        text,
+                        u64::from(candidates),
+                        u64::from(store_offset),
+                        &filter,
+                        min_score,
+                    )
+                    .await?
+            } else {
+                store
+                    .search(
+                        embedding,
+                        u64::from(candidates),
+                        u64::from(store_offset),
+                        &filter,
+                        min_score,
+                    )
+                    .await?
+            };
+            rankings.push(ranking);
+        }
+        if options.explain {
+            run.explained = Some(if options.hybrid {
+                let pool = u64::from(candidates.saturating_add(store_offset));
+                let dense = store
+                    .search(query_embedding.clone(), pool, 0, &filter, min_score)
+                    .await?;
+                let keyword = store.keyword_search(query, pool, &filter).await?;
+                (dense, Some(keyword))
+            } else {
+                (rankings[0].clone(), None)
+            });
+        }
+        // Each reformulation votes through its ranking, as with hybrid search
+        let mut results = if rankings.len() > 1 {
+            reciprocal_rank_fusion(rankings, candidates as usize)
+        } else {
+            rankings.pop().unwrap_or_default()
+        };
+
+        // Hybrid search already weighs keyword matches in. An empty page past
+        // the last vector hit is the end of the results, not a failed search
+        if results.is_empty()
+            && !options.hybrid
+            && options.keyword_fallback
+            && (store_offset == 0
+                || store
+                    .search(query_embedding, 1, 0, &filter, min_score)
+                    .await?
+                    .is_empty())
+        {
+            let pool = candidates.saturating_add(store_offset);
+            match keyword_fallback(store, query, u64::from(pool), &filter).await {
+                Ok(mut matches) => {
+                    run.keyword_fallback = Some(Ok(matches.len()));
+                    // Keyword search has no offset, so pages the store would have cut are cut here
+                    if !reorders {
+                        matches.drain(..matches.len().min(store_offset as usize));
+                    }
+                    results = matches;
+                }
+                // Collections without a keyword index just stay empty
+                Err(e) => run.keyword_fallback = Some(Err(e)),
+            }
+        }
+        if options.summaries_only {
+            results.retain(|r| !r.is_summary());
+        }
+        run.search_ms = search_start.elapsed().as_millis() as u64;
+
+        if let Some(threshold) = options.dedup_threshold
+            && results.len() > 1
+        {
+            let chunk_ids: Vec<String> = results.iter().map(|r| r.chunk_id.clone()).collect();
+            let vectors = store.get_vectors(&chunk_ids).await?;
+            let before = results.len();
+            results = suppress_near_duplicates(results, &vectors, threshold);
+            run.suppressed = before - results.len();
+        }
+
+        let rerank_start = Instant::now();
+        if options.rerank && !results.is_empty() {
+            let documents = results.iter().map(|r| r.content.clone()).collect();
+            let scores = self.embedder.rerank(query, documents).await?;
+            results = rescore(results, &scores, wanted as usize);
+        }
+        run.rerank_ms = rerank_start.elapsed().as_millis() as u64;
+
+        results = apply_recency(
+            results,
+            options.recency,
+            self.config.recency_half_life_days,
+            Utc::now(),
+        );
+        if reorders {
+            results.drain(..results.len().min(options.offset as usize));
+        }
+        results.truncate(options.limit as usize);
+
+        run.results = results;
+        run.filter = filter;
+        Ok(run)
+    }
+}
+
+/// IDs of the documents whose summary chunks best match the query, best first.
+async fn summary_documents(
+    store: &dyn VectorStore,
+    embedding: Vec<f32>,
+    limit: u32,
+    filter: &SearchFilter,
+) -> Result<Vec<String>, VectorStoreError> {
+    let mut filter = filter.clone();
+    filter.tags.push(summary_tag());
+    let summaries = store
+        .search(embedding, u64::from(limit), 0, &filter, None)
+        .await?;
+    Ok(summaries.into_iter().map(|r| r.document_id).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::parse_tags;
+    use crate::testing::{DocumentBuilder, MockEmbeddingProvider, MockVectorStore, document};
+
+    #[tokio::test]
+    async fn test_search_service() {
+        let embedder = Arc::new(MockEmbeddingProvider::default());
+        let store = Arc::new(MockVectorStore::default());
+        crate::testing::index(
+            embedder.as_ref(),
+            store.as_ref(),
+            &[
+                DocumentBuilder::new("src/auth.rs", "Refresh the access token before it expires.")
+                    .tag("team:identity")
+                    .build(),
+                document("docs/deploy.md", "Roll out the kubernetes deployment."),
+            ],
+        )
+        .await
+        .unwrap();
+
+        let service = SearchService::new(embedder, store, SearchConfig::default());
+        let options = service.options().with_limit(1).with_rerank(true);
+        let results = service.search(" access token ", &options).await.unwrap();
+        assert_eq!(results.query, "access token");
+        assert_eq!(results.results.len(), 1);
+        assert_eq!(results.results[0].source.location, "src/auth.rs");
+
+        let filter = SearchFilter::new().with_tags(parse_tags("team:identity").unwrap());
+        let options = service.options().with_filter(filter);
+        let results = service.search("kubernetes", &options).await.unwrap();
+        assert!(
+            results
+                .results
+                .iter()
+                .all(|r| r.source.location == "src/auth.rs")
+        );
+
+        assert!(matches!(
+            service.search("  ", &service.options()).await,
+            Err(SearchError::InvalidQuery(_))
+        ));
+        let options = service.options().with_limit(0);
+        assert!(service.search("token", &options).await.is_err());
+
+        // A page past the first skips its results, with or without reordering
+        for rerank in [false, true] {
+            let options = service.options().with_limit(1).with_rerank(rerank);
+            let first = service.search("access token", &options).await.unwrap();
+            let options = options.with_offset(1);
+            let second = service.search("access token", &options).await.unwrap();
+            assert_eq!(second.offset, 1);
+            assert_eq!(second.results.len(), 1);
+            assert_ne!(second.results[0].chunk_id, first.results[0].chunk_id);
+        }
+
+        // A page past the last vector hit is empty rather than keyword matches
+        let options = service.options().with_limit(1).with_keyword_fallback(true);
+        let run = service
+            .run("access token", &options.clone().with_offset(5))
+            .await
+            .unwrap();
+        assert!(run.results.is_empty());
+        assert!(run.keyword_fallback.is_none());
+        // Without any vector hit, every page falls back
+        let options = options.with_min_score(Some(1.0));
+        let mut pages = Vec::new();
+        for offset in [0, 1] {
+            let run = service
+                .run("kubernetes token", &options.clone().with_offset(offset))
+                .await
+                .unwrap();
+            assert!(matches!(run.keyword_fallback, Some(Ok(_))));
+            assert_eq!(run.results.len(), 1);
+            assert!(run.results[0].keyword_match);
+            pages.push(run.results[0].chunk_id.clone());
+        }
+        assert_ne!(pages[0], pages[1]);
+
+        let options = service
+            .options()
+            .with_expansions(vec!["kubernetes rollout".to_string()])
+            .with_explain(true);
+        let run = service.run("access token", &options).await.unwrap();
+        assert_eq!(run.results.len(), 2);
+        assert!(
+            run.explained
+                .is_some_and(|(dense, keyword)| !dense.is_empty() && keyword.is_none())
+        );
+    }
+}
@@ -0,0 +1,223 @@
+//! Query-aware snippets of search result content.
+
+use std::collections::HashSet;
+use std::ops::Range;
+
+use crate::services::keyword::tokenize;
+
+/// Context kept before the first match of a snippet, in characters
+/// (at most a quarter of the snippet).
+const LEAD_CHARS: usize = 40;
+
+/// Shortest query term that also matches longer words it prefixes
+/// (e.g. `index` matches `indexing`).
+const MIN_PREFIX_CHARS: usize = 3;
+
+/// A window of chunk content with the query terms it contains.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snippet {
+    text: String,
+    /// Byte ranges of matched words in `text`
+    highlights: Vec<Range<usize>>,
+    leading: bool,
+    trailing: bool,
+}
+
+impl Snippet {
+    /// Pick the `max_chars` window of `content` that covers the most query terms.
+    ///
+    /// Falls back to the start of the content when no term matches.
+    pub fn extract(content: &str, query: &str, max_chars: usize) -> Self {
+        let terms: Vec<String> = {
+            let mut seen = HashSet::new();
+            tokenize(query)
+                .into_iter()
+                .filter(|t| seen.insert(t.clone()))
+                .collect()
+        };
+        let chars: Vec<char> = content.chars().collect();
+        let matches = find_matches(&chars, &terms);
+
+        let (start, end) = if chars.len() <= max_chars {
+            (0, chars.len())
+        } else if let Some(anchor) = best_anchor(&matches, max_chars) {
+            window_around(&chars, anchor, max_chars)
+        } else {
+            (0, max_chars)
+        };
+
+        let mut text = String::new();
+        let mut offsets = Vec::with_capacity(end - start + 1);
+        for &c in &chars[start..end] {
+            offsets.push(text.len());
+            text.push(c);
+        }
+        offsets.push(text.len());
+
+        let highlights = matches
+            .iter()
+            .filter(|m| m.range.start >= start && m.range.end <= end)
+            .map(|m| offsets[m.range.start - start]..offsets[m.range.end - start])
+            .collect();
+
+        Self {
+            text,
+            highlights,
+            leading: start > 0,
+            trailing: end < chars.len(),
+        }
+    }
+
+    /// Render the snippet, wrapping each matched word with `highlight` and
+    /// marking cut-off ends with `...`.
+    pub fn render(&self, highlight: impl Fn(&str) -> String) -> String {
+        let mut out = String::with_capacity(self.text.len() + 6);
+        if self.leading {
+            out.push_str("...");
+        }
+        let mut pos = 0;
+        for range in &self.highlights {
+            out.push_str(&self.text[pos..range.start]);
+            out.push_str(&highlight(&self.text[range.clone()]));
+            pos = range.end;
+        }
+        out.push_str(&self.text[pos..]);
+        if self.trailing {
+            out.push_str("...");
+        }
+        out
+    }
+}
+
+struct TermMatch {
+    /// Character range of the matched word
+    range: Range<usize>,
+    term: usize,
+}
+
+/// Words of `chars` that match a query term, in order.
+fn find_matches(chars: &[char], terms: &[String]) -> Vec<TermMatch> {
+    let mut matches = Vec::new();
+    if terms.is_empty() {
+        return matches;
+    }
+
+    let is_word = |c: &char| c.is_alphanumeric() || *c == '_';
+    let mut i = 0;
+    while i < chars.len() {
+        if !is_word(&chars[i]) {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < chars.len() && is_word(&chars[i]) {
+            i += 1;
+        }
+        let word: String = chars[start..i].iter().collect::<String>().to_lowercase();
+        if let Some(term) = terms.iter().position(|t| word_matches(&word, t)) {
+            matches.push(TermMatch {
+                range: start..i,
+                term,
+            });
+        }
+    }
+    matches
+}
+
+fn word_matches(word: &str, term: &str) -> bool {
+    let prefix =
+        |w: &str| w == term || (term.chars().count() >= MIN_PREFIX_CHARS && w.starts_with(term));
+    prefix(word.trim_matches('_')) || word.split('_').any(prefix)
+}
+
+/// Index of the match whose window covers the most distinct terms, then the most matches.
+fn best_anchor(matches: &[TermMatch], max_chars: usize) -> Option<usize> {
+    let mut best: Option<(usize, (usize, usize))> = None;
+    for (i, anchor) in matches.iter().enumerate() {
+        let limit = anchor.range.start + max_chars;
+        let covered: Vec<&TermMatch> = matches[i..]
+            .iter()
+            .take_while(|m| m.range.end <= limit)
+            .collect();
+        let distinct: HashSet<usize> = covered.iter().map(|m| m.term).collect();
+        let score = (distinct.len(), covered.len());
+        if best.is_none_or(|(_, b)| score > b) {
+            best = Some((i, score));
+        }
+    }
+    best.map(|(i, _)| matches[i].range.start)
+}
+
+/// Window of `max_chars` starting a little before `anchor`, cut at whitespace where possible.
+fn window_around(chars: &[char], anchor: usize, max_chars: usize) -> (usize, usize) {
+    let mut start = anchor.saturating_sub(LEAD_CHARS.min(max_chars / 4));
+    if start > 0
+        && let Some(space) = chars[start..anchor].iter().position(|c| c.is_whitespace())
+    {
+        start += space + 1;
+    }
+    let start = start.min(chars.len().saturating_sub(max_chars));
+
+    let mut end = (start + max_chars).min(chars.len());
+    if end < chars.len()
+        && let Some(space) = chars[anchor..end].iter().rposition(|c| c.is_whitespace())
+    {
+        end = anchor + space;
+    }
+    (start, end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bracket(s: &str) -> String {
+        format!("[{}]", s)
+    }
+
+    #[test]
+    fn test_snippet_short_content() {
+        let snippet = Snippet::extract("Configure the Qdrant URL", "qdrant url", 200);
+        assert_eq!(snippet.render(bracket), "Configure the [Qdrant] [URL]");
+    }
+
+    #[test]
+    fn test_snippet_picks_matching_window() {
+        let filler = "lorem ipsum dolor sit amet ".repeat(10);
+        let content = format!("{filler}the retry backoff doubles each attempt. {filler}");
+        let snippet = Snippet::extract(&content, "retry backoff", 80);
+        let rendered = snippet.render(bracket);
+
+        assert!(rendered.starts_with("..."));
+        assert!(rendered.ends_with("..."));
+        assert!(rendered.contains("the [retry] [backoff] doubles"));
+        assert!(rendered.chars().count() <= 80 + 6);
+    }
+
+    #[test]
+    fn test_snippet_prefers_more_distinct_terms() {
+        let content = format!(
+            "{}token refresh {}token refresh expiry{}",
+            "a ".repeat(50),
+            "b ".repeat(50),
+            " c".repeat(50)
+        );
+        let rendered = Snippet::extract(&content, "token expiry", 40).render(bracket);
+        assert!(rendered.contains("[expiry]"), "{rendered}");
+    }
+
+    #[test]
+    fn test_snippet_without_matches_starts_at_beginning() {
+        let content = "x".repeat(300);
+        let rendered = Snippet::extract(&content, "missing", 200).render(bracket);
+        assert_eq!(rendered, format!("{}...", "x".repeat(200)));
+    }
+
+    #[test]
+    fn test_word_matches() {
+        assert!(word_matches("indexing", "index"));
+        assert!(word_matches("max_tokens", "tokens"));
+        assert!(!word_matches("domain", "in"));
+        assert!(word_matches("in", "in"));
+    }
+}
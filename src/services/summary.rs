@@ -0,0 +1,166 @@
+//! Per-document summaries stored as `chunk_type:summary` chunks.
+//!
+//! `search --summaries-only` matches these first and then searches the chunks
+//! of the matching documents only.
+
+use crate::error::LlmError;
+use crate::models::{DocumentChunk, LlmConfig, SummaryMode, summary_tag};
+use crate::services::LlmClient;
+
+/// Longest extractive summary, in characters.
+pub const SUMMARY_MAX_CHARS: usize = 600;
+
+/// Leading characters of a document given to the summarizer.
+pub const SUMMARY_INPUT_CHARS: usize = 12_000;
+
+const SUMMARY_PROMPT: &str = "You summarize documents for a semantic search index. Reply with \
+a summary of at most three sentences that names the document's subject, purpose and key terms. \
+Reply with the summary only.";
+
+/// Writes document summaries in the configured mode.
+pub struct Summarizer {
+    mode: SummaryMode,
+    llm: LlmClient,
+}
+
+impl Summarizer {
+    pub fn new(mode: SummaryMode, llm: &LlmConfig) -> Self {
+        Self {
+            mode,
+            llm: LlmClient::new(llm),
+        }
+    }
+
+    pub fn mode(&self) -> SummaryMode {
+        self.mode
+    }
+
+    /// Summarize the leading text of a document; empty when summaries are off.
+    pub async fn summarize(&self, text: &str) -> Result<String, LlmError> {
+        match self.mode {
+            SummaryMode::Off => Ok(String::new()),
+            SummaryMode::Extractive => Ok(extractive_summary(text)),
+            SummaryMode::Llm => self.llm.chat(SUMMARY_PROMPT, text).await,
+        }
+    }
+}
+
+/// The first sentence of each paragraph, up to [`SUMMARY_MAX_CHARS`].
+///
+/// Markdown heading markers are dropped and fenced code blocks skipped.
+pub fn extractive_summary(text: &str) -> String {
+    let mut summary = String::new();
+    let mut in_fence = false;
+    for paragraph in text.split("\n\n") {
+        let mut lines = Vec::new();
+        for line in paragraph.lines().map(str::trim) {
+            if line.starts_with("```") || line.starts_with("~~~") {
+                in_fence = !in_fence;
+                continue;
+            }
+            if !in_fence && !line.is_empty() {
+                lines.push(line.trim_start_matches('#').trim());
+            }
+        }
+        let paragraph = lines.join(" ");
+        let sentence = first_sentence(&paragraph);
+        if sentence.is_empty() {
+            continue;
+        }
+        if !summary.is_empty() {
+            summary.push(' ');
+        }
+        summary.push_str(sentence);
+        if summary.chars().count() >= SUMMARY_MAX_CHARS {
+            break;
+        }
+    }
+    truncate_chars(&summary, SUMMARY_MAX_CHARS).to_string()
+}
+
+/// Text up to and including the first `.`, `!` or `?` followed by whitespace.
+fn first_sentence(paragraph: &str) -> &str {
+    let bytes = paragraph.as_bytes();
+    for (i, c) in paragraph.char_indices() {
+        if matches!(c, '.' | '!' | '?') && bytes.get(i + 1).is_none_or(u8::is_ascii_whitespace) {
+            return &paragraph[..=i];
+        }
+    }
+    paragraph
+}
+
+/// At most `max` characters of `text`.
+pub fn truncate_chars(text: &str, max: usize) -> &str {
+    match text.char_indices().nth(max) {
+        Some((end, _)) => &text[..end],
+        None => text,
+    }
+}
+
+/// The summary chunk of the document `chunks` were split from, placed after them.
+///
+/// Source, tags and attributes are taken from the document's first chunk.
+pub fn summary_chunk(chunks: &[DocumentChunk], summary: String) -> Option<DocumentChunk> {
+    let first = chunks.first()?;
+    let chunk_index = chunks.len() as u32;
+    let mut chunk = first.clone();
+    chunk.id = DocumentChunk::generate_id(&first.document_id, chunk_index);
+    chunk.content = summary;
+    chunk.chunk_index = chunk_index;
+    chunk.total_chunks = chunk_index;
+    chunk.start_offset = 0;
+    chunk.end_offset = 0;
+    chunk.line_start = None;
+    chunk.line_end = None;
+    chunk.dense_vector = Vec::new();
+    chunk.tags.push(summary_tag());
+    Some(chunk)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extractive_summary() {
+        let text = "# Retry policy\n\n\
+                    Failed requests are retried with backoff. The delay doubles each time.\n\n\
+                    ```rust\nfn retry() {}\n```\n\n\
+                    Version 1.2 added jitter!\nIt avoids bursts.";
+        assert_eq!(
+            extractive_summary(text),
+            "Retry policy Failed requests are retried with backoff. Version 1.2 added jitter!"
+        );
+
+        let long = "word ".repeat(500);
+        assert_eq!(extractive_summary(&long).chars().count(), SUMMARY_MAX_CHARS);
+        assert_eq!(extractive_summary(""), "");
+    }
+
+    #[test]
+    fn test_summary_chunk() {
+        use crate::models::{Document, DocumentMetadata, Source};
+
+        let document = Document::new(
+            "body".to_string(),
+            Source::local("/notes.md".to_string()),
+            Vec::new(),
+            "sum".to_string(),
+            DocumentMetadata::default(),
+        );
+        let chunks: Vec<DocumentChunk> = (0..2)
+            .map(|i| {
+                DocumentChunk::from_document(&document, "body".into(), i, 2, 0, 4, Some(1), Some(1))
+            })
+            .collect();
+
+        let summary = summary_chunk(&chunks, "About notes.".to_string()).unwrap();
+        assert!(summary.is_summary());
+        assert!(!chunks[0].is_summary());
+        assert_eq!(summary.chunk_index, 2);
+        assert_eq!(summary.document_id, document.id);
+        assert_eq!(summary.id, DocumentChunk::generate_id(&document.id, 2));
+        assert_eq!(summary.line_start, None);
+        assert!(summary_chunk(&[], String::new()).is_none());
+    }
+}
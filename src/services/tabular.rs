@@ -0,0 +1,308 @@
+//! Tabular ingestion for CSV and Parquet files.
+//!
+//! Each row is rendered as one line of text, either through a `{column}`
+//! template or as `column: value` pairs, and consecutive rows are grouped into
+//! windows of `rows_per_chunk`, stored as one document each. Searches then
+//! surface the rows of a data dictionary or exported report that match.
+
+use std::fs::File;
+use std::path::Path;
+
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::record::Field;
+
+use crate::error::ImportError;
+use crate::models::{Document, DocumentMetadata, Source, SourceType, Tag};
+use crate::utils::file::calculate_checksum;
+
+/// Rows grouped into one document when `--rows-per-chunk` isn't given.
+pub const DEFAULT_ROWS_PER_CHUNK: usize = 20;
+
+/// First bytes of every Parquet file.
+const PARQUET_MAGIC: &[u8] = b"PAR1";
+
+/// Column names and string cells of a CSV or Parquet file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Table {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// Read a CSV file with a header row.
+    pub fn from_csv(input: &str) -> Result<Self, ImportError> {
+        let mut reader = csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .flexible(true)
+            .from_reader(input.as_bytes());
+        let columns = reader.headers()?.iter().map(String::from).collect();
+        let rows = reader
+            .records()
+            .map(|record| Ok(record?.iter().map(String::from).collect()))
+            .collect::<Result<_, ImportError>>()?;
+        Ok(Self { columns, rows })
+    }
+
+    /// Read every row group of a Parquet file; nested values are rendered
+    /// as JSON-like text.
+    pub fn from_parquet(path: &Path) -> Result<Self, ImportError> {
+        let reader = SerializedFileReader::new(File::open(path)?).map_err(parquet_error)?;
+        let columns = reader
+            .metadata()
+            .file_metadata()
+            .schema_descr()
+            .root_schema()
+            .get_fields()
+            .iter()
+            .map(|field| field.name().to_string())
+            .collect();
+        let mut rows = Vec::new();
+        for row in reader.get_row_iter(None).map_err(parquet_error)? {
+            let row = row.map_err(parquet_error)?;
+            rows.push(row.get_column_iter().map(|(_, f)| cell(f)).collect());
+        }
+        Ok(Self { columns, rows })
+    }
+
+    /// Read `path` as Parquet when it starts with the Parquet magic bytes, else as CSV.
+    pub fn read(path: &Path) -> Result<Self, ImportError> {
+        let bytes = std::fs::read(path)?;
+        if bytes.starts_with(PARQUET_MAGIC) {
+            return Self::from_parquet(path);
+        }
+        Self::from_csv(&String::from_utf8_lossy(&bytes))
+    }
+}
+
+fn parquet_error(error: parquet::errors::ParquetError) -> ImportError {
+    ImportError::ValidationError(format!("invalid Parquet file: {}", error))
+}
+
+/// A cell as text; strings without the quotes `Field`'s `Display` adds.
+fn cell(field: &Field) -> String {
+    match field {
+        Field::Null => String::new(),
+        Field::Str(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// How a row becomes a line of text.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum RowTemplate {
+    /// `column: value` for each non-empty cell, separated by `; `
+    #[default]
+    Pairs,
+    /// Text with `{column}` placeholders, e.g. `{name} ({type}): {description}`
+    Columns(String),
+}
+
+impl RowTemplate {
+    /// Parse `template`, checking that its placeholders name columns of `table`.
+    pub fn parse(template: Option<&str>, table: &Table) -> Result<Self, ImportError> {
+        let Some(template) = template else {
+            return Ok(Self::Pairs);
+        };
+        for name in placeholders(template) {
+            if !table.columns.iter().any(|c| c == name) {
+                return Err(ImportError::ValidationError(format!(
+                    "row template names unknown column '{}' (columns: {})",
+                    name,
+                    table.columns.join(", ")
+                )));
+            }
+        }
+        Ok(Self::Columns(template.to_string()))
+    }
+
+    /// Render one row; missing cells are empty.
+    pub fn render(&self, columns: &[String], row: &[String]) -> String {
+        let value = |i: usize| row.get(i).map(String::as_str).unwrap_or_default();
+        match self {
+            Self::Pairs => columns
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| !value(*i).is_empty())
+                .map(|(i, column)| format!("{}: {}", column, value(i)))
+                .collect::<Vec<_>>()
+                .join("; "),
+            Self::Columns(template) => {
+                let mut line = template.clone();
+                for (i, column) in columns.iter().enumerate() {
+                    line = line.replace(&format!("{{{}}}", column), value(i));
+                }
+                line
+            }
+        }
+    }
+}
+
+fn placeholders(template: &str) -> impl Iterator<Item = &str> {
+    template
+        .split('{')
+        .skip(1)
+        .filter_map(|part| part.split_once('}').map(|(name, _)| name))
+}
+
+/// Build a document per window of `rows_per_chunk` rows, located at
+/// `location#rows<first>-<last>` (1-based). Blank rows are left out.
+pub fn table_documents(
+    table: &Table,
+    location: &str,
+    template: &RowTemplate,
+    rows_per_chunk: usize,
+    source_type: &SourceType,
+    tags: &[Tag],
+) -> Vec<Document> {
+    let name = Path::new(location).file_name().map_or_else(
+        || location.to_string(),
+        |n| n.to_string_lossy().into_owned(),
+    );
+    let extension = Path::new(location)
+        .extension()
+        .map(|e| e.to_string_lossy().into_owned());
+
+    let rows: Vec<(usize, String)> = table
+        .rows
+        .iter()
+        .enumerate()
+        .map(|(i, row)| (i + 1, template.render(&table.columns, row)))
+        .filter(|(_, line)| !line.trim().is_empty())
+        .collect();
+
+    rows.chunks(rows_per_chunk.max(1))
+        .map(|window| {
+            let first = window[0].0;
+            let last = window[window.len() - 1].0;
+            let content = window
+                .iter()
+                .map(|(_, line)| line.as_str())
+                .collect::<Vec<_>>()
+                .join("\n");
+            let metadata = DocumentMetadata {
+                filename: Some(name.clone()),
+                extension: extension.clone(),
+                language: None,
+                title: Some(format!("{} rows {}-{}", name, first, last)),
+                path: Some(location.to_string()),
+                size_bytes: content.len() as u64,
+            };
+            let checksum = calculate_checksum(&content);
+            let source = Source::new(
+                source_type.clone(),
+                format!("{}#rows{}-{}", location, first, last),
+                None,
+            );
+            Document::new(content, source, tags.to_vec(), checksum, metadata)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use parquet::data_type::{ByteArray, ByteArrayType, Int64Type};
+    use parquet::file::properties::WriterProperties;
+    use parquet::file::writer::SerializedFileWriter;
+    use parquet::schema::parser::parse_message_type;
+
+    use super::*;
+
+    const DICTIONARY: &str = "\
+column,type,description
+user_id,bigint,Primary key of the user
+email,text,
+created_at,timestamp,When the account was created
+";
+
+    #[test]
+    fn test_csv_rows() {
+        let table = Table::from_csv(DICTIONARY).unwrap();
+        assert_eq!(table.columns, vec!["column", "type", "description"]);
+        assert_eq!(table.rows.len(), 3);
+
+        let pairs = RowTemplate::Pairs;
+        assert_eq!(
+            pairs.render(&table.columns, &table.rows[1]),
+            "column: email; type: text"
+        );
+        let template =
+            RowTemplate::parse(Some("{column} ({type}): {description}"), &table).unwrap();
+        assert_eq!(
+            template.render(&table.columns, &table.rows[0]),
+            "user_id (bigint): Primary key of the user"
+        );
+        assert!(RowTemplate::parse(Some("{name}"), &table).is_err());
+    }
+
+    #[test]
+    fn test_row_windows() {
+        let table = Table::from_csv(DICTIONARY).unwrap();
+        let source_type = SourceType::Other("custom".to_string());
+        let docs = table_documents(
+            &table,
+            "schema.csv",
+            &RowTemplate::Pairs,
+            2,
+            &source_type,
+            &[],
+        );
+        assert_eq!(docs.len(), 2);
+        assert_eq!(docs[0].source.location, "schema.csv#rows1-2");
+        assert_eq!(docs[0].content.lines().count(), 2);
+        assert_eq!(docs[1].source.location, "schema.csv#rows3-3");
+        assert_eq!(
+            docs[1].metadata.title.as_deref(),
+            Some("schema.csv rows 3-3")
+        );
+        assert_ne!(docs[0].id, docs[1].id);
+    }
+
+    #[test]
+    fn test_parquet_rows() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("report.parquet");
+        let schema = Arc::new(
+            parse_message_type(
+                "message report { REQUIRED BYTE_ARRAY region (UTF8); OPTIONAL INT64 revenue; }",
+            )
+            .unwrap(),
+        );
+        let mut writer = SerializedFileWriter::new(
+            File::create(&path).unwrap(),
+            schema,
+            Arc::new(WriterProperties::builder().build()),
+        )
+        .unwrap();
+        let mut group = writer.next_row_group().unwrap();
+        let mut column = group.next_column().unwrap().unwrap();
+        column
+            .typed::<ByteArrayType>()
+            .write_batch(
+                &[ByteArray::from("emea"), ByteArray::from("apac")],
+                None,
+                None,
+            )
+            .unwrap();
+        column.close().unwrap();
+        let mut column = group.next_column().unwrap().unwrap();
+        column
+            .typed::<Int64Type>()
+            .write_batch(&[1200], Some(&[1, 0]), None)
+            .unwrap();
+        column.close().unwrap();
+        group.close().unwrap();
+        writer.close().unwrap();
+
+        let table = Table::read(&path).unwrap();
+        assert_eq!(table.columns, vec!["region", "revenue"]);
+        assert_eq!(
+            table.rows,
+            vec![
+                vec!["emea".to_string(), "1200".to_string()],
+                vec!["apac".to_string(), String::new()],
+            ]
+        );
+    }
+}
@@ -0,0 +1,231 @@
+//! Client-side encryption of chunk content (`[vector_store] encryption_key`).
+//!
+//! Content is sealed with AES-256-GCM before it reaches the vector store, bound
+//! to its chunk ID so a ciphertext can't be moved to another point. Content
+//! hashes and document checksums are keyed with HMAC-SHA256, so stored hashes
+//! can't confirm guessed content. Vectors, tags and other payload fields stay in plaintext for
+//! filtering.
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use ring::aead::{AES_256_GCM, Aad, LessSafeKey, NONCE_LEN, Nonce, UnboundKey};
+use ring::hmac;
+use ring::rand::{SecureRandom, SystemRandom};
+use std::process::Command;
+
+use crate::error::VectorStoreError;
+use crate::models::VectorStoreConfig;
+
+/// Context the content hash key is derived under, so it differs from the data key.
+const HASH_KEY_CONTEXT: &[u8] = b"ssearch content hash v1";
+
+/// Marks stored content as sealed, with the format version.
+const SEALED_PREFIX: &str = "enc:v1:";
+
+/// Marks a keyed document checksum, so stored chunks written back (e.g. by
+/// `index reembed`) aren't keyed twice.
+const KEYED_CHECKSUM_PREFIX: &str = "hmac:";
+
+/// Whether stored content was sealed by a [`ContentCipher`].
+pub fn is_sealed(content: &str) -> bool {
+    content.starts_with(SEALED_PREFIX)
+}
+
+/// Seals and opens chunk content with one AES-256-GCM key.
+pub struct ContentCipher {
+    key: LessSafeKey,
+    /// Keys content hashes, derived from the data key
+    hash_key: hmac::Key,
+    rng: SystemRandom,
+}
+
+impl ContentCipher {
+    /// Cipher for a 32-byte key.
+    pub fn new(key: &[u8]) -> Result<Self, VectorStoreError> {
+        let data_key = UnboundKey::new(&AES_256_GCM, key).map_err(|_| {
+            VectorStoreError::EncryptionError(format!("key must be 32 bytes, got {}", key.len()))
+        })?;
+        let derived = hmac::sign(&hmac::Key::new(hmac::HMAC_SHA256, key), HASH_KEY_CONTEXT);
+        Ok(Self {
+            key: LessSafeKey::new(data_key),
+            hash_key: hmac::Key::new(hmac::HMAC_SHA256, derived.as_ref()),
+            rng: SystemRandom::new(),
+        })
+    }
+
+    /// Cipher for the configured key, or `None` when encryption is off.
+    ///
+    /// `encryption_key` takes precedence over `encryption_key_command`, whose
+    /// trimmed stdout is the base64 key.
+    pub fn from_config(config: &VectorStoreConfig) -> Result<Option<Self>, VectorStoreError> {
+        let encoded = match (&config.encryption_key, &config.encryption_key_command) {
+            (Some(key), _) => key.trim().to_string(),
+            (None, Some(command)) => key_from_command(command)?,
+            (None, None) => return Ok(None),
+        };
+        let key = STANDARD.decode(&encoded).map_err(|e| {
+            VectorStoreError::EncryptionError(format!("encryption key is not base64: {}", e))
+        })?;
+        Self::new(&key).map(Some)
+    }
+
+    /// Encrypt `plaintext` for the chunk `chunk_id`.
+    pub fn seal(&self, chunk_id: &str, plaintext: &str) -> Result<String, VectorStoreError> {
+        let mut nonce = [0u8; NONCE_LEN];
+        self.rng
+            .fill(&mut nonce)
+            .map_err(|_| VectorStoreError::EncryptionError("no randomness available".into()))?;
+        let mut sealed = plaintext.as_bytes().to_vec();
+        self.key
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(chunk_id.as_bytes()),
+                &mut sealed,
+            )
+            .map_err(|_| VectorStoreError::EncryptionError("failed to encrypt content".into()))?;
+
+        let mut bytes = nonce.to_vec();
+        bytes.extend(sealed);
+        Ok(format!("{}{}", SEALED_PREFIX, STANDARD.encode(bytes)))
+    }
+
+    /// Keyed form of a chunk's [`content_hash`](crate::models::DocumentChunk::content_hash),
+    /// stored in its place so duplicates are still found by equality.
+    pub fn keyed_hash(&self, content_hash: &str) -> String {
+        hex::encode(hmac::sign(&self.hash_key, content_hash.as_bytes()))
+    }
+
+    /// Keyed form of a document checksum, stored in its place so changed
+    /// documents are still found by comparing checksums.
+    pub fn keyed_checksum(&self, checksum: &str) -> String {
+        if checksum.starts_with(KEYED_CHECKSUM_PREFIX) {
+            return checksum.to_string();
+        }
+        format!("{}{}", KEYED_CHECKSUM_PREFIX, self.keyed_hash(checksum))
+    }
+
+    /// Decrypt content stored for `chunk_id`; content stored before
+    /// encryption was turned on is returned unchanged.
+    pub fn open(&self, chunk_id: &str, stored: String) -> Result<String, VectorStoreError> {
+        let Some(encoded) = stored.strip_prefix(SEALED_PREFIX) else {
+            return Ok(stored);
+        };
+        let failed = || {
+            VectorStoreError::EncryptionError(format!(
+                "failed to decrypt chunk {} (wrong encryption key?)",
+                chunk_id
+            ))
+        };
+        let mut bytes = STANDARD.decode(encoded).map_err(|_| failed())?;
+        if bytes.len() < NONCE_LEN {
+            return Err(failed());
+        }
+        let mut sealed = bytes.split_off(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(&bytes).map_err(|_| failed())?;
+        let plaintext = self
+            .key
+            .open_in_place(nonce, Aad::from(chunk_id.as_bytes()), &mut sealed)
+            .map_err(|_| failed())?;
+        String::from_utf8(plaintext.to_vec()).map_err(|_| failed())
+    }
+}
+
+/// Open content read without a cipher, which only works if it isn't sealed.
+pub fn open_content(
+    cipher: Option<&ContentCipher>,
+    chunk_id: &str,
+    stored: String,
+) -> Result<String, VectorStoreError> {
+    match cipher {
+        Some(cipher) => cipher.open(chunk_id, stored),
+        None if is_sealed(&stored) => Err(VectorStoreError::EncryptionError(
+            "content is encrypted; set [vector_store] encryption_key or encryption_key_command"
+                .to_string(),
+        )),
+        None => Ok(stored),
+    }
+}
+
+fn key_from_command(command: &str) -> Result<String, VectorStoreError> {
+    let output = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .output()
+        .map_err(|e| VectorStoreError::EncryptionError(format!("{}: {}", command, e)))?;
+    if !output.status.success() {
+        return Err(VectorStoreError::EncryptionError(format!(
+            "{} exited with {}: {}",
+            command,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seal_and_open() {
+        let cipher = ContentCipher::new(&[7u8; 32]).unwrap();
+        let sealed = cipher.seal("chunk-1", "salary review notes").unwrap();
+        assert!(is_sealed(&sealed));
+        assert!(!sealed.contains("salary"));
+        assert_ne!(
+            sealed,
+            cipher.seal("chunk-1", "salary review notes").unwrap()
+        );
+        assert_eq!(
+            cipher.open("chunk-1", sealed.clone()).unwrap(),
+            "salary review notes"
+        );
+
+        // Bound to the chunk ID and the key
+        assert!(cipher.open("chunk-2", sealed.clone()).is_err());
+        let other = ContentCipher::new(&[8u8; 32]).unwrap();
+        assert!(other.open("chunk-1", sealed.clone()).is_err());
+        assert!(open_content(None, "chunk-1", sealed).is_err());
+
+        // Content stored before encryption was enabled passes through
+        assert_eq!(cipher.open("chunk-1", "plain".into()).unwrap(), "plain");
+        assert!(ContentCipher::new(&[0u8; 16]).is_err());
+    }
+
+    #[test]
+    fn test_keyed_hash() {
+        let cipher = ContentCipher::new(&[7u8; 32]).unwrap();
+        let hash = cipher.keyed_hash("abc123");
+        assert_eq!(hash, cipher.keyed_hash("abc123"));
+        assert_ne!(hash, cipher.keyed_hash("abc124"));
+        assert_ne!(hash, "abc123");
+        assert_eq!(hash.len(), 64);
+        // Only the key holder can compute it
+        let other = ContentCipher::new(&[8u8; 32]).unwrap();
+        assert_ne!(hash, other.keyed_hash("abc123"));
+
+        // Checksums are keyed once, even when written back
+        let checksum = cipher.keyed_checksum("abc123");
+        assert!(checksum.ends_with(&hash));
+        assert_eq!(cipher.keyed_checksum(&checksum), checksum);
+    }
+
+    #[test]
+    fn test_cipher_from_config() {
+        let mut config = VectorStoreConfig::default();
+        assert!(ContentCipher::from_config(&config).unwrap().is_none());
+
+        let key = STANDARD.encode([1u8; 32]);
+        config.encryption_key_command = Some(format!("echo {}", key));
+        let from_command = ContentCipher::from_config(&config).unwrap().unwrap();
+        config.encryption_key_command = None;
+        config.encryption_key = Some(key);
+        let from_key = ContentCipher::from_config(&config).unwrap().unwrap();
+        let sealed = from_command.seal("id", "text").unwrap();
+        assert_eq!(from_key.open("id", sealed).unwrap(), "text");
+
+        config.encryption_key = Some("not base64!".to_string());
+        assert!(ContentCipher::from_config(&config).is_err());
+    }
+}
@@ -0,0 +1,979 @@
+//! In-memory vector store backend for one-shot searches.
+//!
+//! Nothing is persisted: the store lives as long as the process that created it.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use regex::Regex;
+
+use super::{
+    ChunkPage, CollectionInfo, IndexInventory, IndexedDocument, InventoryBuilder, VectorStore,
+    add_tenant_tags, cosine_similarity, title_weighted_score,
+};
+use crate::error::VectorStoreError;
+use crate::models::{
+    Deleted, DocumentChunk, LANGUAGE_ATTRIBUTE, RELATIVE_PATH_ATTRIBUTE, RangeOp, SearchFilter,
+    SearchResult, SourceType, Tag, TimeField, WORKSPACE_ATTRIBUTE, attribute_payload,
+    parse_timestamp,
+};
+use crate::services::keyword::sparse_vector;
+
+/// A chunk with what the persistent backends keep alongside it.
+struct StoredChunk {
+    chunk: DocumentChunk,
+    content_hash: String,
+    /// Hashed keyword terms and their saturated frequencies
+    terms: HashMap<u32, f32>,
+    indexed_at: DateTime<Utc>,
+}
+
+/// Vector store holding every chunk in process memory, with brute-force search.
+pub struct MemoryBackend {
+    collection: String,
+    /// Keyed by chunk ID, so scrolling is in a stable order; shared with tenant views
+    chunks: Arc<RwLock<BTreeMap<String, StoredChunk>>>,
+    /// Share of the title similarity in scores; 0 ignores title vectors
+    title_weight: f32,
+    /// Tags added to every stored chunk; only chunks carrying all of them are seen
+    tenant_tags: Vec<Tag>,
+}
+
+impl MemoryBackend {
+    pub fn new(collection: impl Into<String>) -> Self {
+        Self {
+            collection: collection.into(),
+            chunks: Arc::new(RwLock::new(BTreeMap::new())),
+            title_weight: 0.0,
+            tenant_tags: Vec::new(),
+        }
+    }
+
+    /// The same chunks as seen by the tenant with `tenant_tags`, like a
+    /// second backend on a shared collection with `[vector_store] tenant_tags`.
+    pub fn tenant(&self, tenant_tags: Vec<Tag>) -> Self {
+        Self {
+            collection: self.collection.clone(),
+            chunks: self.chunks.clone(),
+            title_weight: self.title_weight,
+            tenant_tags,
+        }
+    }
+
+    /// Whether the chunk belongs to this backend's tenant.
+    fn owns(&self, chunk: &DocumentChunk) -> bool {
+        self.tenant_tags.iter().all(|tag| chunk.tags.contains(tag))
+    }
+
+    /// [`Self::owns`], for chunks that aren't soft-deleted.
+    fn owns_live(&self, chunk: &DocumentChunk) -> bool {
+        self.owns(chunk) && chunk.deleted_at.is_none()
+    }
+
+    /// Combine body and title similarity for chunks that have a title vector.
+    #[must_use]
+    pub fn with_title_weight(mut self, weight: f32) -> Self {
+        self.title_weight = weight;
+        self
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, BTreeMap<String, StoredChunk>> {
+        self.chunks.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, BTreeMap<String, StoredChunk>> {
+        self.chunks.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Compiled form of a [`SearchFilter`], checked against each chunk.
+pub struct ChunkMatcher<'a> {
+    filter: &'a SearchFilter,
+    paths: Vec<Regex>,
+    relative_paths: Vec<Regex>,
+}
+
+impl<'a> ChunkMatcher<'a> {
+    pub fn new(filter: &'a SearchFilter) -> Self {
+        let paths = filter
+            .paths
+            .iter()
+            .filter_map(|p| Regex::new(&p.to_regex()).ok())
+            .collect();
+        let relative_paths = filter
+            .relative_paths
+            .iter()
+            .filter_map(|p| Regex::new(&p.to_relative_regex()).ok())
+            .collect();
+        Self {
+            filter,
+            paths,
+            relative_paths,
+        }
+    }
+
+    pub fn matches(&self, chunk: &DocumentChunk) -> bool {
+        let filter = self.filter;
+        filter.deleted.matches(chunk.deleted_at.is_some())
+            && filter.tags.iter().all(|tag| chunk.tags.contains(tag))
+            && (filter.source_types.is_empty()
+                || filter.source_types.contains(&chunk.source.source_type))
+            && filter
+                .attributes
+                .iter()
+                .all(|a| chunk.attributes.get(&a.key) == Some(&a.value))
+            && (filter.languages.is_empty()
+                || chunk
+                    .attributes
+                    .get(LANGUAGE_ATTRIBUTE)
+                    .is_some_and(|language| filter.languages.contains(language)))
+            && self
+                .paths
+                .iter()
+                .all(|p| p.is_match(&chunk.source.location))
+            && self.relative_paths.iter().all(|p| {
+                chunk
+                    .attributes
+                    .get(RELATIVE_PATH_ATTRIBUTE)
+                    .is_some_and(|path| {
+                        p.is_match(&attribute_payload(RELATIVE_PATH_ATTRIBUTE, path))
+                    })
+            })
+            && filter.matches_symbol(&chunk.attributes)
+            && filter
+                .document_ids
+                .as_ref()
+                .is_none_or(|ids| ids.contains(&chunk.document_id))
+            && filter.time_bounds.iter().all(|bound| {
+                let stored = match bound.field {
+                    TimeField::CreatedAt => &chunk.created_at,
+                    TimeField::UpdatedAt => &chunk.updated_at,
+                };
+                parse_timestamp(stored).is_some_and(|time| match bound.op {
+                    RangeOp::Gt => time > bound.value,
+                    RangeOp::Gte => time >= bound.value,
+                    RangeOp::Lt => time < bound.value,
+                    RangeOp::Lte => time <= bound.value,
+                })
+            })
+    }
+}
+
+fn chunk_to_result(chunk: &DocumentChunk, score: f32) -> SearchResult {
+    let location = if let Some(url) = &chunk.source.url {
+        url.clone()
+    } else if let (Some(start), Some(end)) = (chunk.line_start, chunk.line_end) {
+        format!("{}:{}-{}", chunk.source.location, start, end)
+    } else {
+        chunk.source.location.clone()
+    };
+
+    SearchResult {
+        chunk_id: chunk.id.clone(),
+        document_id: chunk.document_id.clone(),
+        chunk_index: chunk.chunk_index,
+        score,
+        content: chunk.content.clone(),
+        source: chunk.source.clone(),
+        tags: chunk.tags.clone(),
+        location,
+        line_start: chunk.line_start,
+        line_end: chunk.line_end,
+        attributes: chunk.attributes.clone(),
+        updated_at: parse_timestamp(&chunk.updated_at),
+        context_before: Vec::new(),
+        context_after: Vec::new(),
+        explanation: None,
+        keyword_match: false,
+    }
+}
+
+/// Sort by descending score, then keep the requested page.
+fn page(mut scored: Vec<SearchResult>, limit: u64, offset: u64) -> Vec<SearchResult> {
+    scored.sort_by(|a, b| b.score.total_cmp(&a.score));
+    scored
+        .into_iter()
+        .skip(offset as usize)
+        .take(limit as usize)
+        .collect()
+}
+
+#[async_trait]
+impl VectorStore for MemoryBackend {
+    async fn health_check(&self) -> Result<bool, VectorStoreError> {
+        Ok(true)
+    }
+
+    async fn get_collection_info(&self) -> Result<Option<CollectionInfo>, VectorStoreError> {
+        let chunks = self.read();
+        let owned = chunks.values().filter(|stored| self.owns(&stored.chunk));
+        Ok(Some(CollectionInfo {
+            dimension: owned
+                .clone()
+                .next()
+                .map(|stored| stored.chunk.dense_vector.len() as u64),
+            points_count: owned.count() as u64,
+        }))
+    }
+
+    async fn create_collection(&self) -> Result<(), VectorStoreError> {
+        Ok(())
+    }
+
+    async fn upsert_points(&self, chunks: Vec<DocumentChunk>) -> Result<(), VectorStoreError> {
+        let indexed_at = Utc::now();
+        let mut stored = self.write();
+        for mut chunk in chunks {
+            add_tenant_tags(&mut chunk.tags, &self.tenant_tags);
+            let (indices, values) = sparse_vector(&chunk.content);
+            stored.insert(
+                chunk.id.clone(),
+                StoredChunk {
+                    content_hash: chunk.content_hash(),
+                    terms: indices.into_iter().zip(values).collect(),
+                    indexed_at,
+                    chunk,
+                },
+            );
+        }
+        Ok(())
+    }
+
+    async fn search(
+        &self,
+        query_vector: Vec<f32>,
+        limit: u64,
+        offset: u64,
+        filter: &SearchFilter,
+        min_score: Option<f32>,
+    ) -> Result<Vec<SearchResult>, VectorStoreError> {
+        let matcher = ChunkMatcher::new(filter);
+        let scored = self
+            .read()
+            .values()
+            .filter(|stored| self.owns(&stored.chunk) && matcher.matches(&stored.chunk))
+            .map(|stored| {
+                let chunk = &stored.chunk;
+                let body = cosine_similarity(&query_vector, &chunk.dense_vector);
+                let title = (self.title_weight > 0.0 && !chunk.title_vector.is_empty())
+                    .then(|| cosine_similarity(&query_vector, &chunk.title_vector));
+                (stored, title_weighted_score(body, title, self.title_weight))
+            })
+            .filter(|(_, score)| min_score.is_none_or(|min| *score >= min))
+            .map(|(stored, score)| chunk_to_result(&stored.chunk, score))
+            .collect();
+        Ok(page(scored, limit, offset))
+    }
+
+    async fn keyword_search(
+        &self,
+        query_text: &str,
+        limit: u64,
+        filter: &SearchFilter,
+    ) -> Result<Vec<SearchResult>, VectorStoreError> {
+        let (query_terms, _) = sparse_vector(query_text);
+        if query_terms.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let matcher = ChunkMatcher::new(filter);
+        let chunks = self.read();
+        let candidates: Vec<&StoredChunk> = chunks
+            .values()
+            .filter(|stored| self.owns(&stored.chunk) && matcher.matches(&stored.chunk))
+            .collect();
+
+        // BM25 inverse document frequency over the filtered chunks, as Qdrant's IDF modifier
+        let total = candidates.len() as f32;
+        let idf: HashMap<u32, f32> = query_terms
+            .iter()
+            .map(|term| {
+                let df = candidates
+                    .iter()
+                    .filter(|stored| stored.terms.contains_key(term))
+                    .count() as f32;
+                (*term, ((total - df + 0.5) / (df + 0.5) + 1.0).ln())
+            })
+            .collect();
+
+        let scored = candidates
+            .into_iter()
+            .filter_map(|stored| {
+                let score: f32 = query_terms
+                    .iter()
+                    .filter_map(|term| stored.terms.get(term).map(|tf| tf * idf[term]))
+                    .sum();
+                (score > 0.0).then(|| chunk_to_result(&stored.chunk, score))
+            })
+            .collect();
+        Ok(page(scored, limit, 0))
+    }
+
+    async fn delete_by_tags(&self, tags: &[Tag]) -> Result<(), VectorStoreError> {
+        if tags.is_empty() {
+            return Ok(());
+        }
+        self.write().retain(|_, stored| {
+            !(self.owns(&stored.chunk) && tags.iter().all(|tag| stored.chunk.tags.contains(tag)))
+        });
+        Ok(())
+    }
+
+    async fn delete_by_document_ids(
+        &self,
+        document_ids: &[String],
+    ) -> Result<(), VectorStoreError> {
+        let ids: HashSet<&String> = document_ids.iter().collect();
+        self.write().retain(|_, stored| {
+            !(self.owns(&stored.chunk) && ids.contains(&stored.chunk.document_id))
+        });
+        Ok(())
+    }
+
+    async fn clear_collection(&self) -> Result<(), VectorStoreError> {
+        // Other tenants' chunks stay; only this tenant's are removed
+        self.write().retain(|_, stored| !self.owns(&stored.chunk));
+        Ok(())
+    }
+
+    async fn delete_collection(&self) -> Result<(), VectorStoreError> {
+        self.clear_collection().await
+    }
+
+    async fn delete_by_source_type(&self, source_type: SourceType) -> Result<(), VectorStoreError> {
+        self.write().retain(|_, stored| {
+            !(self.owns(&stored.chunk) && stored.chunk.source.source_type == source_type)
+        });
+        Ok(())
+    }
+
+    async fn count_by_filter(&self, filter: &SearchFilter) -> Result<u64, VectorStoreError> {
+        let matcher = ChunkMatcher::new(filter);
+        Ok(self
+            .read()
+            .values()
+            .filter(|stored| self.owns(&stored.chunk) && matcher.matches(&stored.chunk))
+            .count() as u64)
+    }
+
+    async fn delete_by_filter(&self, filter: &SearchFilter) -> Result<u64, VectorStoreError> {
+        let matcher = ChunkMatcher::new(filter);
+        let mut chunks = self.write();
+        let before = chunks.len();
+        chunks.retain(|_, stored| !(self.owns(&stored.chunk) && matcher.matches(&stored.chunk)));
+        Ok((before - chunks.len()) as u64)
+    }
+
+    async fn soft_delete_by_filter(
+        &self,
+        filter: &SearchFilter,
+        deleted_at: DateTime<Utc>,
+    ) -> Result<u64, VectorStoreError> {
+        let filter = filter.clone().with_deleted(Deleted::Live);
+        let matcher = ChunkMatcher::new(&filter);
+        let mut deleted = 0;
+        for stored in self.write().values_mut() {
+            if self.owns(&stored.chunk) && matcher.matches(&stored.chunk) {
+                stored.chunk.deleted_at = Some(deleted_at.to_rfc3339());
+                deleted += 1;
+            }
+        }
+        Ok(deleted)
+    }
+
+    async fn restore_by_filter(&self, filter: &SearchFilter) -> Result<u64, VectorStoreError> {
+        let filter = filter.clone().with_deleted(Deleted::Only);
+        let matcher = ChunkMatcher::new(&filter);
+        let mut restored = 0;
+        for stored in self.write().values_mut() {
+            if self.owns(&stored.chunk) && matcher.matches(&stored.chunk) {
+                stored.chunk.deleted_at = None;
+                restored += 1;
+            }
+        }
+        Ok(restored)
+    }
+
+    async fn list_all_tags(&self) -> Result<Vec<(String, u64)>, VectorStoreError> {
+        let mut tag_counts: HashMap<String, u64> = HashMap::new();
+        for stored in self.read().values().filter(|s| self.owns_live(&s.chunk)) {
+            for tag in &stored.chunk.tags {
+                *tag_counts.entry(tag.to_payload_string()).or_insert(0) += 1;
+            }
+        }
+        let mut tags: Vec<(String, u64)> = tag_counts.into_iter().collect();
+        tags.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        Ok(tags)
+    }
+
+    async fn list_documents(
+        &self,
+        source_type: &SourceType,
+    ) -> Result<Vec<IndexedDocument>, VectorStoreError> {
+        let mut documents: BTreeMap<String, IndexedDocument> = BTreeMap::new();
+        for stored in self.read().values() {
+            let chunk = &stored.chunk;
+            if chunk.source.source_type != *source_type || !self.owns(chunk) {
+                continue;
+            }
+            documents
+                .entry(chunk.document_id.clone())
+                .or_insert_with(|| IndexedDocument {
+                    document_id: chunk.document_id.clone(),
+                    location: chunk.source.location.clone(),
+                    checksum: chunk.checksum.clone(),
+                    chunk_count: 0,
+                    workspace: chunk.attributes.get(WORKSPACE_ATTRIBUTE).cloned(),
+                })
+                .chunk_count += 1;
+        }
+        Ok(documents.into_values().collect())
+    }
+
+    async fn find_content_hashes(
+        &self,
+        hashes: &[String],
+    ) -> Result<Vec<(String, String)>, VectorStoreError> {
+        let wanted: HashSet<&String> = hashes.iter().collect();
+        Ok(self
+            .read()
+            .values()
+            .filter(|stored| self.owns(&stored.chunk) && wanted.contains(&stored.content_hash))
+            .map(|stored| {
+                (
+                    stored.content_hash.clone(),
+                    stored.chunk.document_id.clone(),
+                )
+            })
+            .collect())
+    }
+
+    async fn get_vectors(
+        &self,
+        chunk_ids: &[String],
+    ) -> Result<HashMap<String, Vec<f32>>, VectorStoreError> {
+        let chunks = self.read();
+        Ok(chunk_ids
+            .iter()
+            .filter_map(|id| {
+                chunks
+                    .get(id)
+                    .filter(|stored| self.owns(&stored.chunk))
+                    .map(|stored| (id.clone(), stored.chunk.dense_vector.clone()))
+            })
+            .collect())
+    }
+
+    async fn get_adjacent_chunks(
+        &self,
+        document_id: &str,
+        chunk_index: u32,
+        radius: u32,
+    ) -> Result<Vec<DocumentChunk>, VectorStoreError> {
+        let range = chunk_index.saturating_sub(radius)..=chunk_index.saturating_add(radius);
+        let mut neighbors: Vec<DocumentChunk> = self
+            .read()
+            .values()
+            .map(|stored| &stored.chunk)
+            .filter(|chunk| {
+                chunk.document_id == document_id
+                    && self.owns(chunk)
+                    && chunk.chunk_index != chunk_index
+                    && range.contains(&chunk.chunk_index)
+            })
+            .map(|chunk| DocumentChunk {
+                dense_vector: Vec::new(),
+                title_vector: Vec::new(),
+                ..chunk.clone()
+            })
+            .collect();
+        neighbors.sort_by_key(|chunk| chunk.chunk_index);
+        Ok(neighbors)
+    }
+
+    async fn scroll_by_document(
+        &self,
+        document_id: &str,
+    ) -> Result<Vec<DocumentChunk>, VectorStoreError> {
+        let mut chunks: Vec<DocumentChunk> = self
+            .read()
+            .values()
+            .map(|stored| &stored.chunk)
+            .filter(|chunk| chunk.document_id == document_id && self.owns(chunk))
+            .map(|chunk| DocumentChunk {
+                dense_vector: Vec::new(),
+                title_vector: Vec::new(),
+                ..chunk.clone()
+            })
+            .collect();
+        chunks.sort_by_key(|chunk| chunk.chunk_index);
+        Ok(chunks)
+    }
+
+    async fn inventory(&self) -> Result<IndexInventory, VectorStoreError> {
+        let mut builder = InventoryBuilder::default();
+        let mut storage_bytes = 0u64;
+        for stored in self.read().values().filter(|s| self.owns_live(&s.chunk)) {
+            let chunk = &stored.chunk;
+            let tags: Vec<String> = chunk.tags.iter().map(Tag::to_payload_string).collect();
+            builder.add_chunk(
+                &chunk.source.source_type.to_string(),
+                &chunk.document_id,
+                &tags,
+                Some(stored.indexed_at),
+            );
+            storage_bytes += (chunk.content.len()
+                + (chunk.dense_vector.len() + chunk.title_vector.len())
+                    * std::mem::size_of::<f32>()) as u64;
+        }
+        Ok(builder.build(storage_bytes))
+    }
+
+    async fn scroll_chunks(
+        &self,
+        offset: Option<String>,
+        limit: u32,
+    ) -> Result<ChunkPage, VectorStoreError> {
+        let chunks = self.read();
+        let start = match offset {
+            Some(ref id) => std::ops::Bound::Included(id.clone()),
+            None => std::ops::Bound::Unbounded,
+        };
+        let mut range = chunks
+            .range((start, std::ops::Bound::Unbounded))
+            .filter(|(_, stored)| self.owns(&stored.chunk));
+        let page: Vec<DocumentChunk> = range
+            .by_ref()
+            .take(limit as usize)
+            .map(|(_, stored)| stored.chunk.clone())
+            .collect();
+        Ok(ChunkPage {
+            chunks: page,
+            next_offset: range.next().map(|(id, _)| id.clone()),
+        })
+    }
+
+    fn collection(&self) -> &str {
+        &self.collection
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AttributeFilter, PathPattern, Source};
+    use crate::services::vector_store::check_dimension;
+
+    fn chunk(
+        id: &str,
+        document_id: &str,
+        index: u32,
+        content: &str,
+        vector: Vec<f32>,
+    ) -> DocumentChunk {
+        DocumentChunk {
+            id: id.to_string(),
+            document_id: document_id.to_string(),
+            content: content.to_string(),
+            chunk_index: index,
+            total_chunks: 3,
+            start_offset: 0,
+            end_offset: content.len() as u64,
+            line_start: None,
+            line_end: None,
+            dense_vector: vector,
+            title_vector: Vec::new(),
+            source: Source::local(format!("/repo/{document_id}.md")),
+            tags: vec![Tag::new("project", "demo").unwrap()],
+            attributes: BTreeMap::from([("language".to_string(), "markdown".to_string())]),
+            checksum: "checksum".to_string(),
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            updated_at: "2024-01-01T00:00:00Z".to_string(),
+            deleted_at: None,
+        }
+    }
+
+    async fn store() -> MemoryBackend {
+        let store = MemoryBackend::new("scratch");
+        store
+            .upsert_points(vec![
+                chunk(
+                    "a0",
+                    "a",
+                    0,
+                    "retry with exponential backoff",
+                    vec![1.0, 0.0],
+                ),
+                chunk("a1", "a", 1, "backoff doubles each attempt", vec![0.8, 0.6]),
+                chunk("b0", "b", 0, "configure the database url", vec![0.0, 1.0]),
+            ])
+            .await
+            .unwrap();
+        store
+    }
+
+    #[tokio::test]
+    async fn test_memory_search_ranks_by_similarity() {
+        let store = store().await;
+        let filter = SearchFilter::new();
+
+        let results = store
+            .search(vec![1.0, 0.0], 2, 0, &filter, None)
+            .await
+            .unwrap();
+        let ids: Vec<&str> = results.iter().map(|r| r.chunk_id.as_str()).collect();
+        assert_eq!(ids, ["a0", "a1"]);
+
+        let next = store
+            .search(vec![1.0, 0.0], 2, 2, &filter, None)
+            .await
+            .unwrap();
+        assert_eq!(next.len(), 1);
+        assert_eq!(next[0].chunk_id, "b0");
+
+        let strict = store
+            .search(vec![1.0, 0.0], 10, 0, &filter, Some(0.9))
+            .await
+            .unwrap();
+        assert_eq!(strict.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_check_dimension() {
+        assert!(
+            check_dimension(&MemoryBackend::new("empty"), 1024)
+                .await
+                .is_ok()
+        );
+
+        let store = store().await;
+        assert!(check_dimension(&store, 2).await.is_ok());
+        assert!(matches!(
+            check_dimension(&store, 1024).await,
+            Err(VectorStoreError::DimensionMismatch {
+                stored: 2,
+                expected: 1024,
+                ..
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_memory_filters_and_keywords() {
+        let store = store().await;
+
+        let filter =
+            SearchFilter::new().with_attributes(vec![AttributeFilter::new("language", "rust")]);
+        assert!(
+            store
+                .search(vec![1.0, 0.0], 10, 0, &filter, None)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        let filter =
+            SearchFilter::new().with_languages(vec!["rust".to_string(), "markdown".to_string()]);
+        assert_eq!(
+            store
+                .search(vec![1.0, 0.0], 10, 0, &filter, None)
+                .await
+                .unwrap()
+                .len(),
+            3
+        );
+
+        let results = store
+            .keyword_search("database", 10, &SearchFilter::new())
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].chunk_id, "b0");
+
+        let neighbors = store.get_adjacent_chunks("a", 0, 1).await.unwrap();
+        assert_eq!(neighbors.len(), 1);
+        assert_eq!(neighbors[0].id, "a1");
+
+        let chunks = store.scroll_by_document("a").await.unwrap();
+        let ids: Vec<&str> = chunks.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, ["a0", "a1"]);
+        assert!(chunks[0].dense_vector.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_memory_delete_by_filter() {
+        let store = store().await;
+        let filter = SearchFilter::new()
+            .with_tags(vec![Tag::new("project", "demo").unwrap()])
+            .with_source_types(vec![SourceType::Local])
+            .with_expressions(vec!["path~/repo/a.md".parse().unwrap()]);
+
+        assert_eq!(store.count_by_filter(&filter).await.unwrap(), 2);
+        assert_eq!(store.delete_by_filter(&filter).await.unwrap(), 2);
+        assert_eq!(store.count_by_filter(&filter).await.unwrap(), 0);
+        assert_eq!(
+            store.count_by_filter(&SearchFilter::new()).await.unwrap(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_memory_tenants() {
+        let store = MemoryBackend::new("shared");
+        let alpha = store.tenant(vec![Tag::new("tenant", "alpha").unwrap()]);
+        let beta = store.tenant(vec![Tag::new("tenant", "beta").unwrap()]);
+        alpha
+            .upsert_points(vec![chunk("a0", "a", 0, "alpha notes", vec![1.0, 0.0])])
+            .await
+            .unwrap();
+        beta.upsert_points(vec![
+            chunk("b0", "b", 0, "beta notes", vec![1.0, 0.0]),
+            chunk("b1", "b", 1, "more beta notes", vec![0.0, 1.0]),
+        ])
+        .await
+        .unwrap();
+
+        let results = alpha
+            .search(vec![1.0, 0.0], 10, 0, &SearchFilter::new(), None)
+            .await
+            .unwrap();
+        let ids: Vec<&str> = results.iter().map(|r| r.chunk_id.as_str()).collect();
+        assert_eq!(ids, ["a0"]);
+        let info = beta.get_collection_info().await.unwrap().unwrap();
+        assert_eq!(info.points_count, 2);
+
+        // Deleting the collection as one tenant leaves the other's chunks
+        alpha.delete_collection().await.unwrap();
+        let info = alpha.get_collection_info().await.unwrap().unwrap();
+        assert_eq!(info.points_count, 0);
+        assert_eq!(beta.count_by_filter(&SearchFilter::new()).await.unwrap(), 2);
+        assert_eq!(
+            store.count_by_filter(&SearchFilter::new()).await.unwrap(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn test_memory_soft_delete_and_restore() {
+        let store = store().await;
+        let document_a = SearchFilter::new().with_document_ids(vec!["a".to_string()]);
+
+        assert_eq!(
+            store
+                .soft_delete_by_filter(&document_a, Utc::now())
+                .await
+                .unwrap(),
+            2
+        );
+        // Soft-deleted chunks are hidden from searches and counts
+        let results = store
+            .search(vec![1.0, 0.0], 10, 0, &SearchFilter::new(), None)
+            .await
+            .unwrap();
+        let ids: Vec<&str> = results.iter().map(|r| r.chunk_id.as_str()).collect();
+        assert_eq!(ids, ["b0"]);
+        assert_eq!(store.count_by_filter(&document_a).await.unwrap(), 0);
+        let deleted = SearchFilter::new().with_deleted(Deleted::Only);
+        assert_eq!(store.count_by_filter(&deleted).await.unwrap(), 2);
+        // Already soft-deleted
+        assert_eq!(
+            store
+                .soft_delete_by_filter(&document_a, Utc::now())
+                .await
+                .unwrap(),
+            0
+        );
+
+        assert_eq!(store.restore_by_filter(&document_a).await.unwrap(), 2);
+        assert_eq!(store.count_by_filter(&deleted).await.unwrap(), 0);
+        assert_eq!(
+            store.count_by_filter(&SearchFilter::new()).await.unwrap(),
+            3
+        );
+    }
+
+    #[tokio::test]
+    async fn test_memory_list_documents() {
+        let store = store().await;
+        let mut stamped = chunk("c0", "c", 0, "stamped", vec![1.0, 0.0]);
+        stamped
+            .attributes
+            .insert(WORKSPACE_ATTRIBUTE.to_string(), "/work/api".to_string());
+        store.upsert_points(vec![stamped]).await.unwrap();
+
+        let documents = store.list_documents(&SourceType::Local).await.unwrap();
+        let summary: Vec<(&str, u64, Option<&str>)> = documents
+            .iter()
+            .map(|d| {
+                (
+                    d.document_id.as_str(),
+                    d.chunk_count,
+                    d.workspace.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [("a", 2, None), ("b", 1, None), ("c", 1, Some("/work/api"))]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_memory_deleted_state() {
+        let store = store().await;
+        let document_a = SearchFilter::new().with_document_ids(vec!["a".to_string()]);
+        store
+            .soft_delete_by_filter(&document_a, Utc::now())
+            .await
+            .unwrap();
+
+        // Listings only count live chunks
+        let inventory = store.inventory().await.unwrap();
+        assert_eq!(inventory.total_chunks(), 1);
+        assert_eq!(inventory.tags, [("project:demo".to_string(), 1)]);
+        assert_eq!(
+            store.list_all_tags().await.unwrap(),
+            [("project:demo".to_string(), 1)]
+        );
+
+        let any = SearchFilter::new().with_deleted(Deleted::Any);
+        assert_eq!(store.count_by_filter(&any).await.unwrap(), 3);
+        // A hard delete of every state removes soft-deleted chunks too
+        let any_a = document_a.clone().with_deleted(Deleted::Any);
+        assert_eq!(store.delete_by_filter(&any_a).await.unwrap(), 2);
+        assert_eq!(store.count_by_filter(&any).await.unwrap(), 1);
+        assert_eq!(store.restore_by_filter(&document_a).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_memory_relative_path_filter() {
+        let store = MemoryBackend::new("scratch");
+        let mut nested = chunk("c0", "c", 0, "service client", vec![1.0, 0.0]);
+        nested.attributes.insert(
+            RELATIVE_PATH_ATTRIBUTE.to_string(),
+            "src/services/client.rs".to_string(),
+        );
+        let mut top = chunk("d0", "d", 0, "entry point", vec![1.0, 0.0]);
+        top.attributes.insert(
+            RELATIVE_PATH_ATTRIBUTE.to_string(),
+            "src/main.rs".to_string(),
+        );
+        store.upsert_points(vec![nested, top]).await.unwrap();
+
+        let filter =
+            SearchFilter::new().with_relative_paths(vec![PathPattern::new("./src/services/**")]);
+        let results = store
+            .search(vec![1.0, 0.0], 10, 0, &filter, None)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].chunk_id, "c0");
+    }
+
+    #[tokio::test]
+    async fn test_memory_keyword_fallback() {
+        use crate::services::keyword_fallback;
+
+        let store = store().await;
+        let filter = SearchFilter::new();
+        // The query points away from every chunk, but one contains its text
+        let semantic = store
+            .search(vec![-1.0, 0.0], 10, 0, &filter, Some(0.5))
+            .await
+            .unwrap();
+        assert!(semantic.is_empty());
+
+        let matches = keyword_fallback(&store, "database url", 10, &filter)
+            .await
+            .unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].chunk_id, "b0");
+        assert!(matches[0].keyword_match);
+    }
+
+    #[tokio::test]
+    async fn test_memory_from_search_results() {
+        let store = store().await;
+        let previous = store
+            .search(vec![1.0, 0.0], 10, 0, &SearchFilter::new(), None)
+            .await
+            .unwrap();
+
+        let refined = MemoryBackend::new("refine");
+        let chunks = previous
+            .iter()
+            .map(|r| {
+                let vector = if r.chunk_id == "b0" {
+                    vec![0.0, 1.0]
+                } else {
+                    vec![1.0, 0.0]
+                };
+                DocumentChunk::from_search_result(r.clone(), vector)
+            })
+            .collect();
+        refined.upsert_points(chunks).await.unwrap();
+
+        let results = refined
+            .search(vec![0.0, 1.0], 10, 0, &SearchFilter::new(), None)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].chunk_id, "b0");
+        assert_eq!(results[0].location, previous[2].location);
+        assert_eq!(results[0].updated_at, previous[2].updated_at);
+
+        let filter = SearchFilter::new().with_expressions(vec!["path~/repo/a.md".parse().unwrap()]);
+        let results = refined
+            .search(vec![0.0, 1.0], 10, 0, &filter, None)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_memory_title_vectors() {
+        let store = MemoryBackend::new("scratch").with_title_weight(0.5);
+        let mut titled = chunk("a0", "a", 0, "retry loop", vec![0.6, 0.8]);
+        titled.title_vector = vec![1.0, 0.0];
+        let untitled = chunk("b0", "b", 0, "backoff", vec![0.7, 0.7]);
+        store.upsert_points(vec![titled, untitled]).await.unwrap();
+
+        // a0's title lifts it from 0.6 to 0.5 * 0.6 + 0.5 * 1.0; b0 keeps its body score
+        let results = store
+            .search(vec![1.0, 0.0], 10, 0, &SearchFilter::new(), None)
+            .await
+            .unwrap();
+        assert_eq!(results[0].chunk_id, "a0");
+        assert!((results[0].score - 0.8).abs() < 1e-6);
+        assert!((results[1].score - std::f32::consts::FRAC_1_SQRT_2).abs() < 1e-6);
+    }
+
+    #[tokio::test]
+    async fn test_memory_document_filter() {
+        let store = MemoryBackend::new("scratch");
+        store
+            .upsert_points(vec![
+                chunk("a0", "a", 0, "alpha", vec![1.0, 0.0]),
+                chunk("b0", "b", 0, "beta", vec![1.0, 0.0]),
+            ])
+            .await
+            .unwrap();
+
+        let filter = SearchFilter::new().with_document_ids(vec!["b".to_string()]);
+        let results = store
+            .search(vec![1.0, 0.0], 10, 0, &filter, None)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].document_id, "b");
+
+        // No matching documents means no results, not an unfiltered search
+        let filter = SearchFilter::new().with_document_ids(Vec::new());
+        let results = store
+            .search(vec![1.0, 0.0], 10, 0, &filter, None)
+            .await
+            .unwrap();
+        assert!(results.is_empty());
+    }
+}
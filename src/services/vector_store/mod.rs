@@ -1,20 +1,30 @@
 //! Vector store abstraction layer.
 //!
 //! This module provides a trait-based abstraction over different vector store backends
-//! (Qdrant, PostgreSQL/pgvector) allowing seamless switching based on configuration.
+//! (Qdrant, PostgreSQL/pgvector) allowing seamless switching based on configuration,
+//! plus a transient in-memory backend for one-shot searches.
 
+mod cipher;
+mod memory;
 mod pgvector;
 mod qdrant;
 
+pub use memory::{ChunkMatcher, MemoryBackend};
 pub use pgvector::PgVectorBackend;
 pub use qdrant::QdrantBackend;
 
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 
 use crate::error::VectorStoreError;
 use crate::models::{
-    DocumentChunk, EmbeddingConfig, SearchResult, SourceType, Tag, VectorDriver, VectorStoreConfig,
+    DocumentChunk, EmbeddingConfig, SearchFilter, SearchResult, SourceType, Tag, VectorDriver,
+    VectorStoreConfig,
 };
+use crate::services::fusion::reciprocal_rank_fusion;
 
 /// Default embedding dimension (Qwen3-Embedding-0.6B produces 1024-dimensional vectors)
 /// This is used when no embedding config is provided
@@ -23,10 +33,157 @@ pub const DEFAULT_EMBEDDING_DIM: u64 = 1024;
 /// Embedding dimension - alias for backward compatibility
 pub const EMBEDDING_DIM: u64 = DEFAULT_EMBEDDING_DIM;
 
+/// Name of the title vector stored alongside the body embedding.
+pub const TITLE_VECTOR_NAME: &str = "title";
+
+/// Combined score of a chunk's body and title similarity to the query.
+///
+/// Chunks stored without a title vector keep their body score, so collections
+/// indexed partly without titles still rank consistently.
+pub fn title_weighted_score(body: f32, title: Option<f32>, weight: f32) -> f32 {
+    match title {
+        Some(title) => (1.0 - weight) * body + weight * title,
+        None => body,
+    }
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+/// Summary of an indexed document, aggregated from its chunks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexedDocument {
+    pub document_id: String,
+    pub location: String,
+    pub checksum: String,
+    pub chunk_count: u64,
+    /// Workspace its chunks were stamped with, if any
+    pub workspace: Option<String>,
+}
+
+/// Indexed documents and chunks of one source type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceInventory {
+    pub source_type: String,
+    pub documents: u64,
+    pub chunks: u64,
+    /// Most recent time a chunk of this source was written
+    pub last_indexed: Option<DateTime<Utc>>,
+}
+
+/// What a collection holds, aggregated from stored chunks.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IndexInventory {
+    /// Per source type, sorted by name
+    pub sources: Vec<SourceInventory>,
+    /// Tags with the number of documents carrying them, most common first
+    pub tags: Vec<(String, u64)>,
+    /// Approximate storage used by the collection
+    pub storage_bytes: u64,
+}
+
+impl IndexInventory {
+    pub fn total_documents(&self) -> u64 {
+        self.sources.iter().map(|s| s.documents).sum()
+    }
+
+    pub fn total_chunks(&self) -> u64 {
+        self.sources.iter().map(|s| s.chunks).sum()
+    }
+
+    pub fn last_indexed(&self) -> Option<DateTime<Utc>> {
+        self.sources.iter().filter_map(|s| s.last_indexed).max()
+    }
+}
+
+#[derive(Default)]
+struct SourceTotals {
+    documents: HashSet<String>,
+    chunks: u64,
+    last_indexed: Option<DateTime<Utc>>,
+}
+
+/// Accumulates an [`IndexInventory`] for backends that aggregate client-side.
+#[derive(Default)]
+struct InventoryBuilder {
+    sources: HashMap<String, SourceTotals>,
+    tags: HashMap<String, HashSet<String>>,
+}
+
+impl InventoryBuilder {
+    fn add_chunk(
+        &mut self,
+        source_type: &str,
+        document_id: &str,
+        tags: &[String],
+        indexed_at: Option<DateTime<Utc>>,
+    ) {
+        let totals = self.sources.entry(source_type.to_string()).or_default();
+        totals.documents.insert(document_id.to_string());
+        totals.chunks += 1;
+        totals.last_indexed = totals.last_indexed.max(indexed_at);
+
+        for tag in tags {
+            self.tags
+                .entry(tag.clone())
+                .or_default()
+                .insert(document_id.to_string());
+        }
+    }
+
+    fn build(self, storage_bytes: u64) -> IndexInventory {
+        let mut sources: Vec<SourceInventory> = self
+            .sources
+            .into_iter()
+            .map(|(source_type, totals)| SourceInventory {
+                source_type,
+                documents: totals.documents.len() as u64,
+                chunks: totals.chunks,
+                last_indexed: totals.last_indexed,
+            })
+            .collect();
+        sources.sort_by(|a, b| a.source_type.cmp(&b.source_type));
+
+        let mut tags: Vec<(String, u64)> = self
+            .tags
+            .into_iter()
+            .map(|(tag, documents)| (tag, documents.len() as u64))
+            .collect();
+        tags.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        IndexInventory {
+            sources,
+            tags,
+            storage_bytes,
+        }
+    }
+}
+
+/// A page of stored chunks, including their embeddings.
+#[derive(Debug, Clone, Default)]
+pub struct ChunkPage {
+    pub chunks: Vec<DocumentChunk>,
+    /// Offset to pass to the next call; `None` when all chunks have been read
+    pub next_offset: Option<String>,
+}
+
+/// Candidates fetched per ranking list for each requested hybrid result
+const HYBRID_CANDIDATE_FACTOR: u64 = 4;
+
 /// Collection/table information
 #[derive(Debug, Clone)]
 pub struct CollectionInfo {
     pub points_count: u64,
+    /// Embedding dimension the collection was created with, when the backend reports it
+    pub dimension: Option<u64>,
 }
 
 /// Abstract trait for vector store operations.
@@ -48,16 +205,48 @@ pub trait VectorStore: Send + Sync {
     /// Insert or update document chunks with their embeddings.
     async fn upsert_points(&self, chunks: Vec<DocumentChunk>) -> Result<(), VectorStoreError>;
 
-    /// Search for similar vectors with optional filtering.
+    /// Search for similar vectors with optional filtering, skipping the
+    /// `offset` best matches.
     async fn search(
         &self,
         query_vector: Vec<f32>,
         limit: u64,
-        tags: &[Tag],
-        source_types: &[SourceType],
+        offset: u64,
+        filter: &SearchFilter,
         min_score: Option<f32>,
     ) -> Result<Vec<SearchResult>, VectorStoreError>;
 
+    /// Search by keyword relevance (BM25 / full-text rank) with optional filtering.
+    async fn keyword_search(
+        &self,
+        query_text: &str,
+        limit: u64,
+        filter: &SearchFilter,
+    ) -> Result<Vec<SearchResult>, VectorStoreError>;
+
+    /// Search combining dense similarity and keyword relevance.
+    ///
+    /// Both rankings are fused with Reciprocal Rank Fusion. `min_score` applies
+    /// to the dense candidates only. The fused ranking is paged with `offset`.
+    async fn hybrid_search(
+        &self,
+        query_vector: Vec<f32>,
+        query_text: &str,
+        limit: u64,
+        offset: u64,
+        filter: &SearchFilter,
+        min_score: Option<f32>,
+    ) -> Result<Vec<SearchResult>, VectorStoreError> {
+        let wanted = limit.saturating_add(offset);
+        let candidates = wanted.saturating_mul(HYBRID_CANDIDATE_FACTOR);
+        let dense = self
+            .search(query_vector, candidates, 0, filter, min_score)
+            .await?;
+        let keyword = self.keyword_search(query_text, candidates, filter).await?;
+        let fused = reciprocal_rank_fusion(vec![dense, keyword], wanted as usize);
+        Ok(fused.into_iter().skip(offset as usize).collect())
+    }
+
     /// Delete points by matching tags.
     async fn delete_by_tags(&self, tags: &[Tag]) -> Result<(), VectorStoreError>;
 
@@ -68,16 +257,228 @@ pub trait VectorStore: Send + Sync {
     /// Clear all points from the collection.
     async fn clear_collection(&self) -> Result<(), VectorStoreError>;
 
+    /// Drop the collection/table entirely. Does nothing if it doesn't exist.
+    ///
+    /// With tenant tags only the tenant's points are removed, as by
+    /// [`Self::clear_collection`], and the shared collection stays.
+    async fn delete_collection(&self) -> Result<(), VectorStoreError>;
+
+    /// Write a backup of the collection to `path` in the backend's native
+    /// format: a Qdrant snapshot, or a binary `COPY` of the Postgres rows.
+    async fn create_snapshot(&self, _path: &Path) -> Result<(), VectorStoreError> {
+        Err(VectorStoreError::SnapshotError(
+            "not supported by this backend".to_string(),
+        ))
+    }
+
+    /// Replace the collection's contents with a backup from [`Self::create_snapshot`].
+    async fn restore_snapshot(&self, _path: &Path) -> Result<(), VectorStoreError> {
+        Err(VectorStoreError::SnapshotError(
+            "not supported by this backend".to_string(),
+        ))
+    }
+
+    /// File extension of [`Self::create_snapshot`] backups.
+    fn snapshot_extension(&self) -> &'static str {
+        "snapshot"
+    }
+
+    /// Rebuild the approximate nearest-neighbour index with the configured
+    /// tuning, e.g. after bulk loads. Returns `false` when the backend keeps
+    /// its index up to date by itself and there is nothing to rebuild.
+    async fn rebuild_index(&self) -> Result<bool, VectorStoreError> {
+        Ok(false)
+    }
+
     /// Delete points by source type.
     async fn delete_by_source_type(&self, source_type: SourceType) -> Result<(), VectorStoreError>;
 
+    /// Count points matching a filter, e.g. to preview [`Self::delete_by_filter`].
+    async fn count_by_filter(&self, filter: &SearchFilter) -> Result<u64, VectorStoreError>;
+
+    /// Delete points matching a filter, returning how many were deleted.
+    async fn delete_by_filter(&self, filter: &SearchFilter) -> Result<u64, VectorStoreError>;
+
+    /// Mark the live points matching a filter as deleted at `deleted_at`,
+    /// hiding them from searches, and return how many were marked.
+    async fn soft_delete_by_filter(
+        &self,
+        filter: &SearchFilter,
+        deleted_at: DateTime<Utc>,
+    ) -> Result<u64, VectorStoreError>;
+
+    /// Bring back the soft-deleted points matching a filter, returning how
+    /// many were restored.
+    async fn restore_by_filter(&self, filter: &SearchFilter) -> Result<u64, VectorStoreError>;
+
     /// List all unique tags with their counts.
     async fn list_all_tags(&self) -> Result<Vec<(String, u64)>, VectorStoreError>;
 
+    /// List indexed documents of a source type with their stored checksums.
+    async fn list_documents(
+        &self,
+        source_type: &SourceType,
+    ) -> Result<Vec<IndexedDocument>, VectorStoreError>;
+
+    /// Form a document checksum is stored in, for comparing a document's
+    /// current checksum with [`IndexedDocument::checksum`].
+    fn stored_checksum(&self, checksum: &str) -> String {
+        checksum.to_string()
+    }
+
+    /// Find stored chunks by content hash, as `(content_hash, document_id)` pairs.
+    ///
+    /// Chunks written before content hashes were stored are never matched.
+    async fn find_content_hashes(
+        &self,
+        hashes: &[String],
+    ) -> Result<Vec<(String, String)>, VectorStoreError>;
+
+    /// Fetch the dense vectors of chunks by ID. Unknown IDs are left out.
+    async fn get_vectors(
+        &self,
+        chunk_ids: &[String],
+    ) -> Result<HashMap<String, Vec<f32>>, VectorStoreError>;
+
+    /// Fetch the chunks of `document_id` within `radius` positions of
+    /// `chunk_index`, ordered by position and excluding the chunk itself.
+    /// Embeddings are not returned.
+    async fn get_adjacent_chunks(
+        &self,
+        document_id: &str,
+        chunk_index: u32,
+        radius: u32,
+    ) -> Result<Vec<DocumentChunk>, VectorStoreError>;
+
+    /// Fetch every chunk of `document_id`, ordered by position.
+    /// Embeddings are not returned.
+    async fn scroll_by_document(
+        &self,
+        document_id: &str,
+    ) -> Result<Vec<DocumentChunk>, VectorStoreError>;
+
+    /// Aggregate per-source and per-tag counts, storage size and index times.
+    async fn inventory(&self) -> Result<IndexInventory, VectorStoreError>;
+
+    /// Read stored chunks with their embeddings, one page at a time.
+    ///
+    /// Pass `None` to start from the beginning, then the returned `next_offset`.
+    /// Offset counters not kept by the store (`total_chunks`, `start_offset`,
+    /// `end_offset`) are returned as zero.
+    async fn scroll_chunks(
+        &self,
+        offset: Option<String>,
+        limit: u32,
+    ) -> Result<ChunkPage, VectorStoreError>;
+
     /// Get the collection/table name.
     fn collection(&self) -> &str;
 }
 
+/// Fill `context_before` and `context_after` of each result with up to
+/// `radius` neighboring chunks of the same document.
+pub async fn expand_context<V: VectorStore + ?Sized>(
+    store: &V,
+    results: &mut [SearchResult],
+    radius: u32,
+) -> Result<(), VectorStoreError> {
+    if radius == 0 {
+        return Ok(());
+    }
+    for result in results.iter_mut() {
+        if result.document_id.is_empty() {
+            continue;
+        }
+        let neighbors = store
+            .get_adjacent_chunks(&result.document_id, result.chunk_index, radius)
+            .await?;
+        let (before, after): (Vec<_>, Vec<_>) = neighbors
+            .into_iter()
+            .filter(|chunk| !chunk.is_summary())
+            .partition(|chunk| chunk.chunk_index < result.chunk_index);
+        result.context_before = before.into_iter().map(|c| c.content).collect();
+        result.context_after = after.into_iter().map(|c| c.content).collect();
+    }
+    Ok(())
+}
+
+/// Keyword matches for a query whose semantic search found nothing above the
+/// minimum score, marked as [`SearchResult::keyword_match`].
+///
+/// Exact strings such as identifiers and error codes often embed far from any
+/// chunk, yet match its text verbatim.
+pub async fn keyword_fallback<V: VectorStore + ?Sized>(
+    store: &V,
+    query_text: &str,
+    limit: u64,
+    filter: &SearchFilter,
+) -> Result<Vec<SearchResult>, VectorStoreError> {
+    let mut matches = store.keyword_search(query_text, limit, filter).await?;
+    for result in &mut matches {
+        result.keyword_match = true;
+    }
+    Ok(matches)
+}
+
+/// Fail with [`VectorStoreError::DimensionMismatch`] when the collection
+/// exists with vectors of another dimension than the model produces.
+///
+/// Searching or upserting across dimensions fails deep inside the backend
+/// with an unhelpful error, so commands check up front.
+pub async fn check_dimension<V: VectorStore + ?Sized>(
+    store: &V,
+    expected: u64,
+) -> Result<(), VectorStoreError> {
+    match store.get_collection_info().await? {
+        Some(CollectionInfo {
+            dimension: Some(stored),
+            ..
+        }) if stored != expected => Err(VectorStoreError::DimensionMismatch {
+            collection: store.collection().to_string(),
+            stored,
+            expected,
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Validate the tenant settings and parse the tags every chunk must carry.
+///
+/// The prefix ends up in Qdrant collection and PostgreSQL table names, so it
+/// is limited to ASCII letters, digits and underscores.
+fn tenant_tags(config: &VectorStoreConfig) -> Result<Vec<Tag>, VectorStoreError> {
+    if let Some(ref prefix) = config.tenant_prefix
+        && !prefix
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_')
+    {
+        return Err(VectorStoreError::InvalidTenant(format!(
+            "tenant_prefix '{}' may only contain ASCII letters, digits and underscores",
+            prefix
+        )));
+    }
+
+    let mut tags: Vec<Tag> = Vec::new();
+    for raw in &config.tenant_tags {
+        let tag: Tag = raw
+            .parse()
+            .map_err(|e| VectorStoreError::InvalidTenant(format!("tenant tag '{}': {}", raw, e)))?;
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    Ok(tags)
+}
+
+/// Add the tenant tags a chunk doesn't carry yet.
+fn add_tenant_tags(tags: &mut Vec<Tag>, tenant_tags: &[Tag]) {
+    for tag in tenant_tags {
+        if !tags.contains(tag) {
+            tags.push(tag.clone());
+        }
+    }
+}
+
 /// Create a vector store backend based on configuration.
 ///
 /// This is the main factory function that returns the appropriate backend
@@ -130,4 +531,59 @@ mod tests {
     fn test_embedding_dim() {
         assert_eq!(EMBEDDING_DIM, 1024);
     }
+
+    #[test]
+    fn test_tenant_tags() {
+        let mut config = VectorStoreConfig {
+            tenant_prefix: Some("team_a".to_string()),
+            tenant_tags: vec!["team:a".to_string(), "team:a".to_string()],
+            ..Default::default()
+        };
+        let tags = tenant_tags(&config).unwrap();
+        assert_eq!(tags, vec![Tag::new("team", "a").unwrap()]);
+
+        let mut chunk_tags = vec![Tag::new("project", "x").unwrap()];
+        add_tenant_tags(&mut chunk_tags, &tags);
+        add_tenant_tags(&mut chunk_tags, &tags);
+        assert_eq!(chunk_tags.len(), 2);
+
+        config.tenant_tags = vec!["team".to_string()];
+        assert!(matches!(
+            tenant_tags(&config),
+            Err(VectorStoreError::InvalidTenant(_))
+        ));
+
+        config.tenant_tags.clear();
+        config.tenant_prefix = Some("team-a".to_string());
+        assert!(matches!(
+            tenant_tags(&config),
+            Err(VectorStoreError::InvalidTenant(_))
+        ));
+    }
+
+    #[test]
+    fn test_inventory_builder() {
+        let older = "2024-01-01T00:00:00Z".parse::<DateTime<Utc>>().ok();
+        let newer = "2024-06-01T00:00:00Z".parse::<DateTime<Utc>>().ok();
+        let project = vec!["project:main".to_string()];
+
+        let mut builder = InventoryBuilder::default();
+        builder.add_chunk("local", "a", &project, older);
+        builder.add_chunk("local", "a", &project, newer);
+        builder.add_chunk("local", "b", &[], None);
+        builder.add_chunk("jira", "c", &project, older);
+        let inventory = builder.build(42);
+
+        assert_eq!(inventory.sources.len(), 2);
+        assert_eq!(inventory.sources[0].source_type, "jira");
+        let local = &inventory.sources[1];
+        assert_eq!((local.documents, local.chunks), (2, 3));
+        assert_eq!(local.last_indexed, newer);
+
+        assert_eq!(inventory.tags, vec![("project:main".to_string(), 2)]);
+        assert_eq!(inventory.total_documents(), 3);
+        assert_eq!(inventory.total_chunks(), 4);
+        assert_eq!(inventory.last_indexed(), newer);
+        assert_eq!(inventory.storage_bytes, 42);
+    }
 }
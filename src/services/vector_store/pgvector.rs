@@ -1,18 +1,116 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use pgvector::Vector;
-use sqlx::Row;
-use sqlx::postgres::{PgPool, PgPoolOptions, PgRow};
+use sqlx::postgres::{PgArguments, PgPool, PgPoolOptions, PgRow};
+use sqlx::query::Query;
+use sqlx::{Postgres, Row, Transaction};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
 use std::time::Duration;
-
-use super::{CollectionInfo, DEFAULT_EMBEDDING_DIM, VectorStore};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::OnceCell;
+use tokio_stream::StreamExt;
+
+use super::{
+    ChunkPage, CollectionInfo, DEFAULT_EMBEDDING_DIM, IndexInventory, IndexedDocument,
+    SourceInventory, VectorStore, add_tenant_tags, tenant_tags,
+};
 use crate::error::VectorStoreError;
-use crate::models::{DocumentChunk, SearchResult, Source, SourceType, Tag, VectorStoreConfig};
+use crate::models::{
+    AnnIndex, Deleted, DocumentChunk, Quantization, SYMBOL_ATTRIBUTE, SearchFilter, SearchResult,
+    Source, SourceType, Tag, VectorStoreConfig, WORKSPACE_ATTRIBUTE, attribute_payload,
+    parse_attribute_payload, parse_timestamp,
+};
+use crate::services::keyword::tokenize;
+
+/// Columns written by upserts, in bind order.
+const UPSERT_COLUMNS: &str = "id, document_id, chunk_index, content, embedding, source_type, \
+     source_location, source_url, tags, checksum, created_at, line_start, line_end, \
+     attributes, updated_at, content_hash, title_embedding, deleted_at";
+
+/// Columns carried by snapshots; `content_tsv` is generated, so it's rebuilt on restore.
+const SNAPSHOT_COLUMNS: &str = "id, document_id, chunk_index, content, embedding, source_type, \
+     source_location, source_url, tags, checksum, content_hash, created_at, updated_at, \
+     indexed_at, line_start, line_end, attributes, title_embedding, deleted_at";
+
+const UPSERT_CONFLICT: &str = r#"ON CONFLICT (id) DO UPDATE SET
+                document_id = EXCLUDED.document_id,
+                chunk_index = EXCLUDED.chunk_index,
+                content = EXCLUDED.content,
+                embedding = EXCLUDED.embedding,
+                source_type = EXCLUDED.source_type,
+                source_location = EXCLUDED.source_location,
+                source_url = EXCLUDED.source_url,
+                tags = EXCLUDED.tags,
+                checksum = EXCLUDED.checksum,
+                created_at = EXCLUDED.created_at,
+                line_start = EXCLUDED.line_start,
+                line_end = EXCLUDED.line_end,
+                attributes = EXCLUDED.attributes,
+                updated_at = EXCLUDED.updated_at,
+                content_hash = EXCLUDED.content_hash,
+                title_embedding = EXCLUDED.title_embedding,
+                deleted_at = EXCLUDED.deleted_at,
+                indexed_at = now()"#;
+
+/// One chunk as stored in the table.
+struct UpsertRow {
+    id: uuid::Uuid,
+    document_id: String,
+    chunk_index: i32,
+    content: String,
+    embedding: Vector,
+    source_type: String,
+    source_location: String,
+    source_url: Option<String>,
+    tags: Vec<String>,
+    checksum: String,
+    created_at: DateTime<Utc>,
+    line_start: Option<i32>,
+    line_end: Option<i32>,
+    attributes: Vec<String>,
+    updated_at: DateTime<Utc>,
+    content_hash: String,
+    title_embedding: Option<Vector>,
+    deleted_at: Option<DateTime<Utc>>,
+}
+
+fn has_duplicate_ids(rows: &[UpsertRow]) -> bool {
+    let mut seen = HashSet::with_capacity(rows.len());
+    !rows.iter().all(|row| seen.insert(row.id))
+}
+
+/// Split `rows` into statements of at most `batch_size` rows, each paired with
+/// whether it can be written as one multi-row insert.
+fn upsert_batches(
+    rows: &[UpsertRow],
+    batch_size: usize,
+) -> impl Iterator<Item = (&[UpsertRow], bool)> {
+    rows.chunks(batch_size).map(|batch| {
+        // ON CONFLICT can't touch a row twice in one statement
+        let bulk = batch.len() > 1 && !has_duplicate_ids(batch);
+        (batch, bulk)
+    })
+}
 
 pub struct PgVectorBackend {
     pool: PgPool,
     table_name: String,
     collection: String,
     embedding_dim: u64,
+    quantization: Quantization,
+    index_type: AnnIndex,
+    /// `WITH (...)` storage parameters of the embedding index
+    index_parameters: Vec<String>,
+    /// `SET LOCAL` statements run before each vector search
+    search_settings: Vec<String>,
+    upsert_batch_size: usize,
+    /// Index the title column for title-weighted search
+    title_vectors: bool,
+    /// Share of the title similarity in combined scores; 0 searches the body only
+    title_weight: f32,
+    tenant_tags: Vec<Tag>,
+    columns_migrated: OnceCell<()>,
 }
 
 impl PgVectorBackend {
@@ -20,6 +118,13 @@ impl PgVectorBackend {
         config: &VectorStoreConfig,
         embedding_dim: u64,
     ) -> Result<Self, VectorStoreError> {
+        if config.encrypts() {
+            // content_tsv is generated from the stored content, so it can't be sealed
+            return Err(VectorStoreError::EncryptionError(
+                "content encryption is only supported by the qdrant driver".to_string(),
+            ));
+        }
+        let tenant_tags = tenant_tags(config)?;
         let pool = PgPoolOptions::new()
             .max_connections(config.pool_max)
             .acquire_timeout(Duration::from_secs(config.pool_acquire_timeout.into()))
@@ -30,8 +135,21 @@ impl PgVectorBackend {
         let backend = Self {
             pool,
             table_name: config.qualified_table_name(),
-            collection: config.collection.clone(),
+            collection: config.scoped_collection(),
             embedding_dim,
+            quantization: config.quantization,
+            index_type: config.index_type,
+            index_parameters: Self::index_parameters(config),
+            search_settings: Self::search_settings(config),
+            upsert_batch_size: config.upsert_batch_size.max(1) as usize,
+            title_vectors: config.title_vectors,
+            title_weight: if config.title_vectors {
+                config.title_weight
+            } else {
+                0.0
+            },
+            tenant_tags,
+            columns_migrated: OnceCell::new(),
         };
 
         backend.check_pgvector_extension().await?;
@@ -47,6 +165,86 @@ impl PgVectorBackend {
         Self::new(config, DEFAULT_EMBEDDING_DIM).await
     }
 
+    fn index_parameters(config: &VectorStoreConfig) -> Vec<String> {
+        let parameters = match config.index_type {
+            AnnIndex::Hnsw => vec![
+                ("m", config.hnsw_m),
+                ("ef_construction", config.hnsw_ef_construction),
+            ],
+            AnnIndex::Ivfflat => vec![("lists", config.ivfflat_lists)],
+        };
+        parameters
+            .into_iter()
+            .filter_map(|(name, value)| value.map(|v| format!("{} = {}", name, v)))
+            .collect()
+    }
+
+    fn search_settings(config: &VectorStoreConfig) -> Vec<String> {
+        let setting = match config.index_type {
+            AnnIndex::Hnsw => config.ef_search.map(|v| ("hnsw.ef_search", v)),
+            AnnIndex::Ivfflat => config.ivfflat_probes.map(|v| ("ivfflat.probes", v)),
+        };
+        setting
+            .map(|(name, value)| format!("SET LOCAL {} = {}", name, value))
+            .into_iter()
+            .collect()
+    }
+
+    /// `CREATE INDEX` statement for a vector column with the configured
+    /// index type, parameters and quantization.
+    fn vector_index_sql(&self, column: &str) -> String {
+        // Quantized indexes hold halfvec/bit copies; search rescores candidates with the full vectors
+        let expression = match self.quantization {
+            Quantization::None => format!("{column} vector_cosine_ops"),
+            Quantization::Int8 => format!(
+                "({column}::halfvec({})) halfvec_cosine_ops",
+                self.embedding_dim
+            ),
+            Quantization::Binary => format!(
+                "(binary_quantize({column})::bit({})) bit_hamming_ops",
+                self.embedding_dim
+            ),
+        };
+        let with = if self.index_parameters.is_empty() {
+            String::new()
+        } else {
+            format!(" WITH ({})", self.index_parameters.join(", "))
+        };
+        format!(
+            "CREATE INDEX IF NOT EXISTS {}_{}_idx ON {} USING {} ({}){}",
+            self.collection, column, self.table_name, self.index_type, expression, with
+        )
+    }
+
+    /// Distance from `$1` that the index on `column` orders by.
+    fn approximate_distance(&self, column: &str, dim: usize) -> String {
+        match self.quantization {
+            Quantization::None => format!("{column} <=> $1"),
+            Quantization::Int8 => format!("{column}::halfvec({dim}) <=> $1::halfvec({dim})"),
+            Quantization::Binary => {
+                format!("binary_quantize({column})::bit({dim}) <~> binary_quantize($1)::bit({dim})")
+            }
+        }
+    }
+
+    /// Run a vector search, applying the per-query index settings in a
+    /// transaction so they don't leak to other users of the connection.
+    async fn fetch_search(
+        &self,
+        query: Query<'_, Postgres, PgArguments>,
+    ) -> Result<Vec<PgRow>, sqlx::Error> {
+        if self.search_settings.is_empty() {
+            return query.fetch_all(&self.pool).await;
+        }
+        let mut tx = self.pool.begin().await?;
+        for setting in &self.search_settings {
+            sqlx::query(setting).execute(&mut *tx).await?;
+        }
+        let rows = query.fetch_all(&mut *tx).await?;
+        tx.commit().await?;
+        Ok(rows)
+    }
+
     async fn check_pgvector_extension(&self) -> Result<(), VectorStoreError> {
         let result: Option<(String,)> =
             sqlx::query_as("SELECT extname FROM pg_extension WHERE extname = 'vector'")
@@ -72,6 +270,682 @@ impl PgVectorBackend {
         Ok(())
     }
 
+    /// Add columns and indexes introduced after the initial table layout:
+    /// the full-text column used by keyword search, document attributes, title
+    /// embeddings, and timestamp columns (`created_at` was originally stored as text).
+    ///
+    /// Existing tables are migrated in place; runs once per backend instance.
+    async fn ensure_columns(&self) -> Result<(), VectorStoreError> {
+        self.columns_migrated
+            .get_or_try_init(|| async {
+                let mut statements = vec![
+                    format!(
+                        "ALTER TABLE {} ADD COLUMN IF NOT EXISTS attributes TEXT[] NOT NULL DEFAULT '{{}}'",
+                        self.table_name
+                    ),
+                    format!(
+                        "CREATE INDEX IF NOT EXISTS {}_attributes_idx ON {} USING GIN(attributes)",
+                        self.collection, self.table_name
+                    ),
+                    format!(
+                        "ALTER TABLE {} ADD COLUMN IF NOT EXISTS content_tsv tsvector \
+                         GENERATED ALWAYS AS (to_tsvector('simple', content)) STORED",
+                        self.table_name
+                    ),
+                    format!(
+                        "CREATE INDEX IF NOT EXISTS {}_content_tsv_idx ON {} USING GIN(content_tsv)",
+                        self.collection, self.table_name
+                    ),
+                    format!(
+                        "ALTER TABLE {} ADD COLUMN IF NOT EXISTS content_hash TEXT",
+                        self.table_name
+                    ),
+                    format!(
+                        "CREATE INDEX IF NOT EXISTS {}_content_hash_idx ON {} (content_hash)",
+                        self.collection, self.table_name
+                    ),
+                    format!(
+                        "ALTER TABLE {} ADD COLUMN IF NOT EXISTS title_embedding vector({})",
+                        self.table_name, self.embedding_dim
+                    ),
+                    format!(
+                        "ALTER TABLE {} ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ",
+                        self.table_name
+                    ),
+                ];
+                if self.title_vectors {
+                    statements.push(self.vector_index_sql("title_embedding"));
+                }
+
+                for sql in &statements {
+                    sqlx::query(sql)
+                        .execute(&self.pool)
+                        .await
+                        .map_err(|e| VectorStoreError::CollectionError(e.to_string()))?;
+                }
+
+                self.migrate_timestamps().await
+            })
+            .await
+            .map(|_| ())
+    }
+
+    async fn migrate_timestamps(&self) -> Result<(), VectorStoreError> {
+        let columns: Vec<(String, String)> = sqlx::query_as(
+            "SELECT attname::text, format_type(atttypid, atttypmod) FROM pg_attribute \
+             WHERE attrelid = to_regclass($1) AND attname IN ('created_at', 'updated_at', 'indexed_at') \
+             AND NOT attisdropped",
+        )
+        .bind(&self.table_name)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| VectorStoreError::CollectionError(e.to_string()))?;
+        let column_type = |name: &str| {
+            columns
+                .iter()
+                .find(|(column, _)| column == name)
+                .map(|(_, ty)| ty.as_str())
+        };
+
+        let mut statements = Vec::new();
+        if column_type("created_at") == Some("text") {
+            statements.push(format!(
+                "ALTER TABLE {} ALTER COLUMN created_at TYPE TIMESTAMPTZ USING created_at::timestamptz",
+                self.table_name
+            ));
+        }
+        if column_type("updated_at").is_none() {
+            statements.extend([
+                format!(
+                    "ALTER TABLE {} ADD COLUMN updated_at TIMESTAMPTZ",
+                    self.table_name
+                ),
+                format!("UPDATE {} SET updated_at = created_at", self.table_name),
+                format!(
+                    "ALTER TABLE {} ALTER COLUMN updated_at SET NOT NULL",
+                    self.table_name
+                ),
+            ]);
+        }
+        if column_type("indexed_at").is_none() {
+            // Existing rows have no record of when they were written; updated_at is the best guess
+            statements.extend([
+                format!(
+                    "ALTER TABLE {} ADD COLUMN indexed_at TIMESTAMPTZ",
+                    self.table_name
+                ),
+                format!("UPDATE {} SET indexed_at = updated_at", self.table_name),
+                format!(
+                    "ALTER TABLE {} ALTER COLUMN indexed_at SET DEFAULT now(), ALTER COLUMN indexed_at SET NOT NULL",
+                    self.table_name
+                ),
+            ]);
+        }
+        statements.push(format!(
+            "CREATE INDEX IF NOT EXISTS {}_updated_at_idx ON {} (updated_at)",
+            self.collection, self.table_name
+        ));
+
+        for sql in &statements {
+            sqlx::query(sql)
+                .execute(&self.pool)
+                .await
+                .map_err(|e| VectorStoreError::CollectionError(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    /// Nearest neighbours by the quantized index, rescored with full-precision vectors.
+    async fn search_quantized(
+        &self,
+        query_vector: Vec<f32>,
+        limit: u64,
+        offset: u64,
+        filter: &SearchFilter,
+        min_score: Option<f32>,
+    ) -> Result<Vec<SearchResult>, VectorStoreError> {
+        // Cast to the query's dimension so the collection's index expression matches
+        let approximate_distance = self.approximate_distance("embedding", query_vector.len());
+        let embedding = Vector::from(query_vector);
+
+        let mut param_index = 2;
+        let mut where_parts = Self::filter_conditions(filter, &mut param_index);
+        where_parts.extend(self.tenant_condition());
+        let where_clause = if where_parts.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", where_parts.join(" AND "))
+        };
+        let score_clause = min_score
+            .map(|score| format!("WHERE (1 - (embedding <=> $1)) >= {}", score))
+            .unwrap_or_default();
+
+        let query = format!(
+            r#"
+            WITH candidates AS (
+                SELECT id, document_id, chunk_index, content, source_type, source_location,
+                       source_url, tags, attributes, line_start, line_end, updated_at, embedding
+                FROM {}
+                {}
+                ORDER BY {}
+                LIMIT {}
+            )
+            SELECT
+                id::text as chunk_id,
+                document_id,
+                chunk_index,
+                1 - (embedding <=> $1) as score,
+                content,
+                source_type,
+                source_location,
+                source_url,
+                tags,
+                attributes,
+                line_start,
+                line_end,
+                updated_at
+            FROM candidates
+            {}
+            ORDER BY embedding <=> $1
+            LIMIT {} OFFSET {}
+            "#,
+            self.table_name,
+            where_clause,
+            approximate_distance,
+            limit
+                .saturating_add(offset)
+                .saturating_mul(self.quantization.oversampling()),
+            score_clause,
+            limit,
+            offset
+        );
+
+        let query_builder = Self::bind_filter(sqlx::query(&query).bind(&embedding), filter);
+
+        let rows = self
+            .fetch_search(query_builder)
+            .await
+            .map_err(|e| VectorStoreError::SearchError(e.to_string()))?;
+
+        Ok(rows.into_iter().map(Self::row_to_result).collect())
+    }
+
+    /// Nearest neighbours by body or title embedding, ranked by combined score.
+    ///
+    /// Chunks without a title embedding keep their body score.
+    async fn search_title_weighted(
+        &self,
+        query_vector: Vec<f32>,
+        limit: u64,
+        offset: u64,
+        filter: &SearchFilter,
+        min_score: Option<f32>,
+    ) -> Result<Vec<SearchResult>, VectorStoreError> {
+        let dim = query_vector.len();
+        let embedding = Vector::from(query_vector);
+
+        let mut param_index = 2;
+        let mut where_parts = Self::filter_conditions(filter, &mut param_index);
+        where_parts.extend(self.tenant_condition());
+        let where_clause = if where_parts.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", where_parts.join(" AND "))
+        };
+        where_parts.push("title_embedding IS NOT NULL".to_string());
+        let title_where_clause = format!("WHERE {}", where_parts.join(" AND "));
+        let score_clause = min_score
+            .map(|score| format!("WHERE score >= {}", score))
+            .unwrap_or_default();
+        let candidates = limit
+            .saturating_add(offset)
+            .saturating_mul(self.quantization.oversampling());
+
+        let query = format!(
+            r#"
+            WITH body AS (
+                SELECT id FROM {table} {where_clause} ORDER BY {body_distance} LIMIT {candidates}
+            ),
+            title AS (
+                SELECT id FROM {table} {title_where_clause} ORDER BY {title_distance} LIMIT {candidates}
+            ),
+            scored AS (
+                SELECT
+                    id::text as chunk_id,
+                    document_id,
+                    chunk_index,
+                    (1 - {weight}) * (1 - (embedding <=> $1))
+                        + {weight} * COALESCE(1 - (title_embedding <=> $1), 1 - (embedding <=> $1))
+                        as score,
+                    content,
+                    source_type,
+                    source_location,
+                    source_url,
+                    tags,
+                    attributes,
+                    line_start,
+                    line_end,
+                    updated_at
+                FROM {table}
+                WHERE id IN (SELECT id FROM body UNION SELECT id FROM title)
+            )
+            SELECT * FROM scored
+            {score_clause}
+            ORDER BY score DESC
+            LIMIT {limit} OFFSET {offset}
+            "#,
+            table = self.table_name,
+            body_distance = self.approximate_distance("embedding", dim),
+            title_distance = self.approximate_distance("title_embedding", dim),
+            weight = self.title_weight,
+        );
+
+        let query_builder = Self::bind_filter(sqlx::query(&query).bind(&embedding), filter);
+
+        let rows = self
+            .fetch_search(query_builder)
+            .await
+            .map_err(|e| VectorStoreError::SearchError(e.to_string()))?;
+
+        Ok(rows.into_iter().map(Self::row_to_result).collect())
+    }
+
+    /// Convert a chunk to column values, adding tenant tags.
+    fn upsert_row(&self, mut chunk: DocumentChunk) -> Result<UpsertRow, VectorStoreError> {
+        add_tenant_tags(&mut chunk.tags, &self.tenant_tags);
+        let id = uuid::Uuid::parse_str(&chunk.id)
+            .map_err(|e| VectorStoreError::UpsertError(format!("Invalid UUID: {}", e)))?;
+        let created_at = parse_timestamp(&chunk.created_at).unwrap_or_else(Utc::now);
+
+        Ok(UpsertRow {
+            id,
+            content_hash: chunk.content_hash(),
+            tags: chunk.tags.iter().map(|t| t.to_payload_string()).collect(),
+            attributes: chunk
+                .attributes
+                .iter()
+                .map(|(k, v)| attribute_payload(k, v))
+                .collect(),
+            created_at,
+            updated_at: parse_timestamp(&chunk.updated_at).unwrap_or(created_at),
+            source_type: chunk.source.source_type.to_string(),
+            chunk_index: chunk.chunk_index as i32,
+            line_start: chunk.line_start.map(|v| v as i32),
+            line_end: chunk.line_end.map(|v| v as i32),
+            embedding: Vector::from(chunk.dense_vector),
+            title_embedding: Some(chunk.title_vector)
+                .filter(|v| !v.is_empty())
+                .map(Vector::from),
+            deleted_at: chunk.deleted_at.as_deref().and_then(parse_timestamp),
+            document_id: chunk.document_id,
+            content: chunk.content,
+            source_location: chunk.source.location,
+            source_url: chunk.source.url,
+            checksum: chunk.checksum,
+        })
+    }
+
+    /// Insert rows one statement at a time.
+    async fn insert_rows(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        rows: &[UpsertRow],
+    ) -> Result<(), sqlx::Error> {
+        let query = format!(
+            r#"
+            INSERT INTO {} ({})
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17,
+                    $18)
+            {}
+            "#,
+            self.table_name, UPSERT_COLUMNS, UPSERT_CONFLICT
+        );
+
+        for row in rows {
+            sqlx::query(&query)
+                .bind(row.id)
+                .bind(&row.document_id)
+                .bind(row.chunk_index)
+                .bind(&row.content)
+                .bind(&row.embedding)
+                .bind(&row.source_type)
+                .bind(&row.source_location)
+                .bind(&row.source_url)
+                .bind(&row.tags)
+                .bind(&row.checksum)
+                .bind(row.created_at)
+                .bind(row.line_start)
+                .bind(row.line_end)
+                .bind(&row.attributes)
+                .bind(row.updated_at)
+                .bind(&row.content_hash)
+                .bind(&row.title_embedding)
+                .bind(row.deleted_at)
+                .execute(&mut **tx)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Insert rows in one statement by unnesting a bound array per column.
+    ///
+    /// Postgres has no ragged 2-D arrays, so each row's tags and attributes
+    /// travel as a JSON array and are expanded back to `TEXT[]`.
+    async fn insert_batch(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        rows: &[UpsertRow],
+    ) -> Result<(), sqlx::Error> {
+        let query = format!(
+            r#"
+            INSERT INTO {} ({})
+            SELECT u.id, u.document_id, u.chunk_index, u.content, u.embedding, u.source_type,
+                   u.source_location, u.source_url,
+                   ARRAY(SELECT jsonb_array_elements_text(u.tags)),
+                   u.checksum, u.created_at, u.line_start, u.line_end,
+                   ARRAY(SELECT jsonb_array_elements_text(u.attributes)),
+                   u.updated_at, u.content_hash, u.title_embedding, u.deleted_at
+            FROM UNNEST(
+                $1::uuid[], $2::text[], $3::int4[], $4::text[], $5::vector[], $6::text[],
+                $7::text[], $8::text[], $9::text[]::jsonb[], $10::text[], $11::timestamptz[],
+                $12::int4[], $13::int4[], $14::text[]::jsonb[], $15::timestamptz[], $16::text[],
+                $17::vector[], $18::timestamptz[]
+            ) AS u({})
+            {}
+            "#,
+            self.table_name, UPSERT_COLUMNS, UPSERT_COLUMNS, UPSERT_CONFLICT
+        );
+
+        fn column<'a, T>(rows: &'a [UpsertRow], f: impl Fn(&'a UpsertRow) -> T) -> Vec<T> {
+            rows.iter().map(f).collect()
+        }
+        let json = |values: &[String]| serde_json::to_string(values).unwrap_or_default();
+
+        sqlx::query(&query)
+            .bind(column(rows, |r| r.id))
+            .bind(column(rows, |r| r.document_id.as_str()))
+            .bind(column(rows, |r| r.chunk_index))
+            .bind(column(rows, |r| r.content.as_str()))
+            .bind(column(rows, |r| r.embedding.clone()))
+            .bind(column(rows, |r| r.source_type.as_str()))
+            .bind(column(rows, |r| r.source_location.as_str()))
+            .bind(column(rows, |r| r.source_url.as_deref()))
+            .bind(column(rows, |r| json(&r.tags)))
+            .bind(column(rows, |r| r.checksum.as_str()))
+            .bind(column(rows, |r| r.created_at))
+            .bind(column(rows, |r| r.line_start))
+            .bind(column(rows, |r| r.line_end))
+            .bind(column(rows, |r| json(&r.attributes)))
+            .bind(column(rows, |r| r.updated_at))
+            .bind(column(rows, |r| r.content_hash.as_str()))
+            .bind(column(rows, |r| r.title_embedding.clone()))
+            .bind(column(rows, |r| r.deleted_at))
+            .execute(&mut **tx)
+            .await?;
+        Ok(())
+    }
+
+    /// Condition restricting rows to those carrying every tenant tag.
+    ///
+    /// Tags are validated to alphanumerics, `_`, `-` and `.`, so they are
+    /// inlined rather than bound to keep placeholder numbering unchanged.
+    fn tenant_condition(&self) -> Option<String> {
+        if self.tenant_tags.is_empty() {
+            return None;
+        }
+        let literals: Vec<String> = self
+            .tenant_tags
+            .iter()
+            .map(|tag| format!("'{}'", tag.to_payload_string().replace('\'', "''")))
+            .collect();
+        Some(format!("tags @> ARRAY[{}]::text[]", literals.join(", ")))
+    }
+
+    /// `" AND <tenant condition>"`, or empty without tenant tags.
+    fn and_tenant(&self) -> String {
+        self.tenant_condition()
+            .map(|c| format!(" AND {}", c))
+            .unwrap_or_default()
+    }
+
+    /// `"WHERE <tenant condition>"`, or empty without tenant tags.
+    fn where_tenant(&self) -> String {
+        self.tenant_condition()
+            .map(|c| format!("WHERE {}", c))
+            .unwrap_or_default()
+    }
+
+    /// `"WHERE deleted_at IS NULL"`, plus the tenant condition.
+    fn where_live(&self) -> String {
+        format!("WHERE deleted_at IS NULL{}", self.and_tenant())
+    }
+
+    /// Build WHERE conditions for a search filter, numbering placeholders
+    /// from `param_index`. Bind values with [`Self::bind_filter`].
+    fn filter_conditions(filter: &SearchFilter, param_index: &mut usize) -> Vec<String> {
+        let mut where_parts = Vec::new();
+        match filter.deleted {
+            Deleted::Live => where_parts.push("deleted_at IS NULL".to_string()),
+            Deleted::Only => where_parts.push("deleted_at IS NOT NULL".to_string()),
+            Deleted::Any => {}
+        }
+
+        for _ in &filter.tags {
+            where_parts.push(format!("${} = ANY(tags)", param_index));
+            *param_index += 1;
+        }
+
+        for _ in &filter.attributes {
+            where_parts.push(format!("${} = ANY(attributes)", param_index));
+            *param_index += 1;
+        }
+
+        if !filter.languages.is_empty() {
+            where_parts.push(format!("attributes && ${}::text[]", param_index));
+            *param_index += 1;
+        }
+
+        if !filter.source_types.is_empty() {
+            let placeholders: Vec<String> = filter
+                .source_types
+                .iter()
+                .map(|_| {
+                    let p = format!("${}", param_index);
+                    *param_index += 1;
+                    p
+                })
+                .collect();
+            where_parts.push(format!("source_type IN ({})", placeholders.join(", ")));
+        }
+
+        for _ in &filter.paths {
+            where_parts.push(format!("source_location ~ ${}", param_index));
+            *param_index += 1;
+        }
+
+        for _ in &filter.relative_paths {
+            where_parts.push(format!(
+                "EXISTS (SELECT 1 FROM unnest(attributes) AS a WHERE a ~ ${})",
+                param_index
+            ));
+            *param_index += 1;
+        }
+
+        for _ in &filter.symbols {
+            where_parts.push(format!(
+                "EXISTS (SELECT 1 FROM unnest(attributes) AS a WHERE a ~ ${})",
+                param_index
+            ));
+            *param_index += 1;
+        }
+
+        for bound in &filter.time_bounds {
+            where_parts.push(format!(
+                "{} {} ${}",
+                bound.field.as_str(),
+                bound.op.as_str(),
+                param_index
+            ));
+            *param_index += 1;
+        }
+
+        if filter.document_ids.is_some() {
+            where_parts.push(format!("document_id = ANY(${})", param_index));
+            *param_index += 1;
+        }
+
+        where_parts
+    }
+
+    /// `WHERE` clause for a filter and the tenant condition, with
+    /// placeholders from `$1`.
+    fn filter_where(&self, filter: &SearchFilter) -> String {
+        let mut conditions = Self::filter_conditions(filter, &mut 1);
+        conditions.extend(self.tenant_condition());
+        if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        }
+    }
+
+    /// Bind filter values in the order used by [`Self::filter_conditions`].
+    fn bind_filter<'q>(
+        mut query: Query<'q, Postgres, PgArguments>,
+        filter: &SearchFilter,
+    ) -> Query<'q, Postgres, PgArguments> {
+        for tag in &filter.tags {
+            query = query.bind(tag.to_payload_string());
+        }
+        for attribute in &filter.attributes {
+            query = query.bind(attribute.to_payload_string());
+        }
+        if !filter.languages.is_empty() {
+            query = query.bind(filter.language_payloads());
+        }
+        for source_type in &filter.source_types {
+            query = query.bind(source_type.to_string());
+        }
+        for pattern in &filter.paths {
+            query = query.bind(pattern.to_regex());
+        }
+        for pattern in &filter.relative_paths {
+            query = query.bind(pattern.to_relative_regex());
+        }
+        for symbol in &filter.symbols {
+            query = query.bind(format!("^{}=.*{}", SYMBOL_ATTRIBUTE, regex::escape(symbol)));
+        }
+        for bound in &filter.time_bounds {
+            query = query.bind(bound.value);
+        }
+        if let Some(ref document_ids) = filter.document_ids {
+            query = query.bind(document_ids.clone());
+        }
+        query
+    }
+
+    /// Convert a row of stored chunk columns; `embedding` and `title_embedding`
+    /// may be left out of the query.
+    fn row_to_chunk(row: PgRow) -> DocumentChunk {
+        let embedding: Option<Vector> = row.try_get("embedding").ok();
+        let title_embedding: Option<Vector> = row.try_get("title_embedding").ok().flatten();
+        let source_type: String = row.get("source_type");
+        let chunk_index: i32 = row.get("chunk_index");
+        let line_start: Option<i32> = row.get("line_start");
+        let line_end: Option<i32> = row.get("line_end");
+        let tags: Vec<String> = row.get("tags");
+        let attributes: Vec<String> = row.get("attributes");
+
+        DocumentChunk {
+            id: row.get("chunk_id"),
+            document_id: row.get("document_id"),
+            content: row.get("content"),
+            chunk_index: chunk_index as u32,
+            total_chunks: 0,
+            start_offset: 0,
+            end_offset: 0,
+            line_start: line_start.map(|v| v as u32),
+            line_end: line_end.map(|v| v as u32),
+            dense_vector: embedding.map(|e| e.to_vec()).unwrap_or_default(),
+            title_vector: title_embedding.map(|e| e.to_vec()).unwrap_or_default(),
+            source: Source {
+                source_type: source_type.parse().unwrap_or(SourceType::Local),
+                location: row.get("source_location"),
+                url: row.get("source_url"),
+            },
+            tags: tags.iter().filter_map(|s| s.parse().ok()).collect(),
+            attributes: attributes
+                .iter()
+                .filter_map(|s| parse_attribute_payload(s))
+                .collect(),
+            checksum: row.get("checksum"),
+            created_at: row.get::<DateTime<Utc>, _>("created_at").to_rfc3339(),
+            updated_at: row.get::<DateTime<Utc>, _>("updated_at").to_rfc3339(),
+            deleted_at: row
+                .try_get::<Option<DateTime<Utc>>, _>("deleted_at")
+                .ok()
+                .flatten()
+                .map(|t| t.to_rfc3339()),
+        }
+    }
+
+    fn row_to_result(row: PgRow) -> SearchResult {
+        let chunk_id: String = row.get("chunk_id");
+        let document_id: String = row.get("document_id");
+        let chunk_index: i32 = row.get("chunk_index");
+        let score: f64 = row.get("score");
+        let content: String = row.get("content");
+        let source_type_str: String = row.get("source_type");
+        let source_location: String = row.get("source_location");
+        let source_url: Option<String> = row.get("source_url");
+        let tag_strings: Vec<String> = row.get("tags");
+        let line_start: Option<i32> = row.get("line_start");
+        let line_end: Option<i32> = row.get("line_end");
+        let attribute_strings: Vec<String> = row.get("attributes");
+        let updated_at: DateTime<Utc> = row.get("updated_at");
+
+        let source_type: SourceType = source_type_str.parse().unwrap_or(SourceType::Local);
+        let tags: Vec<Tag> = tag_strings
+            .into_iter()
+            .filter_map(|s| s.parse().ok())
+            .collect();
+        let line_start_u32 = line_start.map(|v| v as u32);
+        let line_end_u32 = line_end.map(|v| v as u32);
+
+        let location = Self::build_location(
+            &source_location,
+            source_url.as_deref(),
+            line_start_u32,
+            line_end_u32,
+        );
+
+        SearchResult {
+            chunk_id,
+            document_id,
+            chunk_index: chunk_index as u32,
+            score: score as f32,
+            content,
+            source: Source {
+                source_type,
+                location: source_location,
+                url: source_url,
+            },
+            tags,
+            location,
+            line_start: line_start_u32,
+            line_end: line_end_u32,
+            attributes: attribute_strings
+                .iter()
+                .filter_map(|s| parse_attribute_payload(s))
+                .collect(),
+            updated_at: Some(updated_at),
+            context_before: Vec::new(),
+            context_after: Vec::new(),
+            explanation: None,
+            keyword_match: false,
+        }
+    }
+
     fn build_location(
         source_location: &str,
         source_url: Option<&str>,
@@ -117,14 +991,26 @@ impl VectorStore for PgVectorBackend {
             .await
             .map_err(|e| VectorStoreError::PostgresError(e.to_string()))?;
 
+        // pgvector stores the declared dimension of vector(N) as the type modifier
+        let dimension: Option<(i32,)> = sqlx::query_as(
+            "SELECT atttypmod FROM pg_attribute WHERE attrelid = to_regclass($1) AND attname = 'embedding'",
+        )
+        .bind(&self.table_name)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| VectorStoreError::PostgresError(e.to_string()))?;
+
         Ok(Some(CollectionInfo {
             points_count: row.0 as u64,
+            dimension: dimension
+                .filter(|(modifier,)| *modifier > 0)
+                .map(|(modifier,)| modifier as u64),
         }))
     }
 
     async fn create_collection(&self) -> Result<(), VectorStoreError> {
         if self.get_collection_info().await?.is_some() {
-            return Ok(());
+            return self.ensure_columns().await;
         }
 
         let create_table = format!(
@@ -139,8 +1025,12 @@ impl VectorStore for PgVectorBackend {
                 source_location TEXT NOT NULL,
                 source_url TEXT,
                 tags TEXT[] NOT NULL DEFAULT '{{}}',
+                attributes TEXT[] NOT NULL DEFAULT '{{}}',
                 checksum TEXT NOT NULL,
-                created_at TEXT NOT NULL,
+                content_hash TEXT,
+                created_at TIMESTAMPTZ NOT NULL,
+                updated_at TIMESTAMPTZ NOT NULL,
+                indexed_at TIMESTAMPTZ NOT NULL DEFAULT now(),
                 line_start INTEGER,
                 line_end INTEGER
             )
@@ -154,10 +1044,7 @@ impl VectorStore for PgVectorBackend {
             .map_err(|e| VectorStoreError::CollectionError(e.to_string()))?;
 
         let indices = [
-            format!(
-                "CREATE INDEX IF NOT EXISTS {}_embedding_idx ON {} USING hnsw (embedding vector_cosine_ops)",
-                self.collection, self.table_name
-            ),
+            self.vector_index_sql("embedding"),
             format!(
                 "CREATE INDEX IF NOT EXISTS {}_tags_idx ON {} USING GIN(tags)",
                 self.collection, self.table_name
@@ -179,7 +1066,7 @@ impl VectorStore for PgVectorBackend {
                 .map_err(|e| VectorStoreError::CollectionError(e.to_string()))?;
         }
 
-        Ok(())
+        self.ensure_columns().await
     }
 
     async fn upsert_points(&self, chunks: Vec<DocumentChunk>) -> Result<(), VectorStoreError> {
@@ -187,27 +1074,12 @@ impl VectorStore for PgVectorBackend {
             return Ok(());
         }
 
-        let query = format!(
-            r#"
-            INSERT INTO {} (id, document_id, chunk_index, content, embedding, source_type,
-                          source_location, source_url, tags, checksum, created_at, line_start, line_end)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
-            ON CONFLICT (id) DO UPDATE SET
-                document_id = EXCLUDED.document_id,
-                chunk_index = EXCLUDED.chunk_index,
-                content = EXCLUDED.content,
-                embedding = EXCLUDED.embedding,
-                source_type = EXCLUDED.source_type,
-                source_location = EXCLUDED.source_location,
-                source_url = EXCLUDED.source_url,
-                tags = EXCLUDED.tags,
-                checksum = EXCLUDED.checksum,
-                created_at = EXCLUDED.created_at,
-                line_start = EXCLUDED.line_start,
-                line_end = EXCLUDED.line_end
-            "#,
-            self.table_name
-        );
+        self.ensure_columns().await?;
+
+        let rows = chunks
+            .into_iter()
+            .map(|chunk| self.upsert_row(chunk))
+            .collect::<Result<Vec<_>, _>>()?;
 
         let mut tx = self
             .pool
@@ -215,30 +1087,13 @@ impl VectorStore for PgVectorBackend {
             .await
             .map_err(|e| VectorStoreError::UpsertError(e.to_string()))?;
 
-        for chunk in chunks {
-            let id = uuid::Uuid::parse_str(&chunk.id)
-                .map_err(|e| VectorStoreError::UpsertError(format!("Invalid UUID: {}", e)))?;
-
-            let embedding = Vector::from(chunk.dense_vector);
-            let tags: Vec<String> = chunk.tags.iter().map(|t| t.to_payload_string()).collect();
-
-            sqlx::query(&query)
-                .bind(id)
-                .bind(&chunk.document_id)
-                .bind(chunk.chunk_index as i32)
-                .bind(&chunk.content)
-                .bind(&embedding)
-                .bind(chunk.source.source_type.to_string())
-                .bind(&chunk.source.location)
-                .bind(&chunk.source.url)
-                .bind(&tags)
-                .bind(&chunk.checksum)
-                .bind(&chunk.created_at)
-                .bind(chunk.line_start.map(|v| v as i32))
-                .bind(chunk.line_end.map(|v| v as i32))
-                .execute(&mut *tx)
-                .await
-                .map_err(|e| VectorStoreError::UpsertError(e.to_string()))?;
+        for (batch, bulk) in upsert_batches(&rows, self.upsert_batch_size) {
+            if bulk {
+                self.insert_batch(&mut tx, batch).await
+            } else {
+                self.insert_rows(&mut tx, batch).await
+            }
+            .map_err(|e| VectorStoreError::UpsertError(e.to_string()))?;
         }
 
         tx.commit()
@@ -252,32 +1107,30 @@ impl VectorStore for PgVectorBackend {
         &self,
         query_vector: Vec<f32>,
         limit: u64,
-        tags: &[Tag],
-        source_types: &[SourceType],
+        offset: u64,
+        filter: &SearchFilter,
         min_score: Option<f32>,
     ) -> Result<Vec<SearchResult>, VectorStoreError> {
-        let embedding = Vector::from(query_vector);
+        self.ensure_columns().await?;
 
-        let mut where_parts = Vec::new();
-        let mut param_index = 2;
-
-        for _ in tags {
-            where_parts.push(format!("${} = ANY(tags)", param_index));
-            param_index += 1;
+        if self.title_weight > 0.0 {
+            return self
+                .search_title_weighted(query_vector, limit, offset, filter, min_score)
+                .await;
         }
 
-        if !source_types.is_empty() {
-            let placeholders: Vec<String> = source_types
-                .iter()
-                .map(|_| {
-                    let p = format!("${}", param_index);
-                    param_index += 1;
-                    p
-                })
-                .collect();
-            where_parts.push(format!("source_type IN ({})", placeholders.join(", ")));
+        if self.quantization != Quantization::None {
+            return self
+                .search_quantized(query_vector, limit, offset, filter, min_score)
+                .await;
         }
 
+        let embedding = Vector::from(query_vector);
+
+        let mut param_index = 2;
+        let mut where_parts = Self::filter_conditions(filter, &mut param_index);
+        where_parts.extend(self.tenant_condition());
+
         if let Some(score) = min_score {
             where_parts.push(format!("(1 - (embedding <=> $1)) >= {}", score));
         }
@@ -292,83 +1145,95 @@ impl VectorStore for PgVectorBackend {
             r#"
             SELECT
                 id::text as chunk_id,
+                document_id,
+                chunk_index,
                 1 - (embedding <=> $1) as score,
                 content,
                 source_type,
                 source_location,
                 source_url,
                 tags,
+                attributes,
                 line_start,
-                line_end
+                line_end,
+                updated_at
             FROM {}
             {}
             ORDER BY embedding <=> $1
-            LIMIT {}
+            LIMIT {} OFFSET {}
             "#,
-            self.table_name, where_clause, limit
+            self.table_name, where_clause, limit, offset
         );
 
-        let mut query_builder = sqlx::query(&query).bind(&embedding);
+        let query_builder = Self::bind_filter(sqlx::query(&query).bind(&embedding), filter);
 
-        for tag in tags {
-            query_builder = query_builder.bind(tag.to_payload_string());
-        }
+        let rows = self
+            .fetch_search(query_builder)
+            .await
+            .map_err(|e| VectorStoreError::SearchError(e.to_string()))?;
+
+        let results = rows.into_iter().map(Self::row_to_result).collect();
+
+        Ok(results)
+    }
 
-        for source_type in source_types {
-            query_builder = query_builder.bind(source_type.to_string());
+    async fn keyword_search(
+        &self,
+        query_text: &str,
+        limit: u64,
+        filter: &SearchFilter,
+    ) -> Result<Vec<SearchResult>, VectorStoreError> {
+        let mut terms = tokenize(query_text);
+        terms.sort();
+        terms.dedup();
+        if terms.is_empty() {
+            return Ok(Vec::new());
         }
 
+        self.ensure_columns().await?;
+
+        // Match any term; ranking rewards documents matching more of them
+        let ts_query = terms.join(" | ");
+
+        let mut param_index = 2;
+        let mut where_parts = Self::filter_conditions(filter, &mut param_index);
+        where_parts.insert(0, "content_tsv @@ ts_query".to_string());
+        where_parts.extend(self.tenant_condition());
+
+        let query = format!(
+            r#"
+            SELECT
+                id::text as chunk_id,
+                document_id,
+                chunk_index,
+                ts_rank_cd(content_tsv, ts_query)::float8 as score,
+                content,
+                source_type,
+                source_location,
+                source_url,
+                tags,
+                attributes,
+                line_start,
+                line_end,
+                updated_at
+            FROM {}, to_tsquery('simple', $1) ts_query
+            WHERE {}
+            ORDER BY score DESC
+            LIMIT {}
+            "#,
+            self.table_name,
+            where_parts.join(" AND "),
+            limit
+        );
+
+        let query_builder = Self::bind_filter(sqlx::query(&query).bind(&ts_query), filter);
+
         let rows = query_builder
             .fetch_all(&self.pool)
             .await
             .map_err(|e| VectorStoreError::SearchError(e.to_string()))?;
 
-        let results = rows
-            .into_iter()
-            .map(|row: PgRow| {
-                let chunk_id: String = row.get("chunk_id");
-                let score: f64 = row.get("score");
-                let content: String = row.get("content");
-                let source_type_str: String = row.get("source_type");
-                let source_location: String = row.get("source_location");
-                let source_url: Option<String> = row.get("source_url");
-                let tag_strings: Vec<String> = row.get("tags");
-                let line_start: Option<i32> = row.get("line_start");
-                let line_end: Option<i32> = row.get("line_end");
-
-                let source_type: SourceType = source_type_str.parse().unwrap_or(SourceType::Local);
-                let tags: Vec<Tag> = tag_strings
-                    .into_iter()
-                    .filter_map(|s| s.parse().ok())
-                    .collect();
-                let line_start_u32 = line_start.map(|v| v as u32);
-                let line_end_u32 = line_end.map(|v| v as u32);
-
-                let location = Self::build_location(
-                    &source_location,
-                    source_url.as_deref(),
-                    line_start_u32,
-                    line_end_u32,
-                );
-
-                SearchResult {
-                    chunk_id,
-                    score: score as f32,
-                    content,
-                    source: Source {
-                        source_type,
-                        location: source_location,
-                        url: source_url,
-                    },
-                    tags,
-                    location,
-                    line_start: line_start_u32,
-                    line_end: line_end_u32,
-                }
-            })
-            .collect();
-
-        Ok(results)
+        Ok(rows.into_iter().map(Self::row_to_result).collect())
     }
 
     async fn delete_by_tags(&self, tags: &[Tag]) -> Result<(), VectorStoreError> {
@@ -376,11 +1241,12 @@ impl VectorStore for PgVectorBackend {
             return Ok(());
         }
 
-        let conditions: Vec<String> = tags
+        let mut conditions: Vec<String> = tags
             .iter()
             .enumerate()
             .map(|(i, _)| format!("${} = ANY(tags)", i + 1))
             .collect();
+        conditions.extend(self.tenant_condition());
 
         let query = format!(
             "DELETE FROM {} WHERE {}",
@@ -410,8 +1276,9 @@ impl VectorStore for PgVectorBackend {
         }
 
         let query = format!(
-            "DELETE FROM {} WHERE document_id = ANY($1)",
-            self.table_name
+            "DELETE FROM {} WHERE document_id = ANY($1){}",
+            self.table_name,
+            self.and_tenant()
         );
 
         sqlx::query(&query)
@@ -428,7 +1295,11 @@ impl VectorStore for PgVectorBackend {
             return Ok(());
         }
 
-        let query = format!("TRUNCATE TABLE {}", self.table_name);
+        // Other tenants' rows stay; only this tenant's are removed
+        let query = match self.tenant_condition() {
+            Some(condition) => format!("DELETE FROM {} WHERE {}", self.table_name, condition),
+            None => format!("TRUNCATE TABLE {}", self.table_name),
+        };
         sqlx::query(&query)
             .execute(&self.pool)
             .await
@@ -437,13 +1308,146 @@ impl VectorStore for PgVectorBackend {
         Ok(())
     }
 
+    async fn delete_collection(&self) -> Result<(), VectorStoreError> {
+        // Other tenants share the table, so only this tenant's rows go
+        if self.tenant_condition().is_some() {
+            return self.clear_collection().await;
+        }
+        let query = format!("DROP TABLE IF EXISTS {}", self.table_name);
+        sqlx::query(&query)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| VectorStoreError::DeleteError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn create_snapshot(&self, path: &Path) -> Result<(), VectorStoreError> {
+        self.ensure_columns().await?;
+
+        // Binary COPY with an explicit column list, so migrated tables whose
+        // columns were appended in a different order restore the same way
+        let statement = format!(
+            "COPY (SELECT {} FROM {} {}) TO STDOUT (FORMAT binary)",
+            SNAPSHOT_COLUMNS,
+            self.table_name,
+            self.where_tenant()
+        );
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| VectorStoreError::SnapshotError(e.to_string()))?;
+        let mut stream = conn
+            .copy_out_raw(&statement)
+            .await
+            .map_err(|e| VectorStoreError::SnapshotError(e.to_string()))?;
+        let mut file = tokio::fs::File::create(path)
+            .await
+            .map_err(|e| VectorStoreError::SnapshotError(e.to_string()))?;
+        while let Some(bytes) = stream.next().await {
+            let bytes = bytes.map_err(|e| VectorStoreError::SnapshotError(e.to_string()))?;
+            file.write_all(&bytes)
+                .await
+                .map_err(|e| VectorStoreError::SnapshotError(e.to_string()))?;
+        }
+        file.flush()
+            .await
+            .map_err(|e| VectorStoreError::SnapshotError(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn restore_snapshot(&self, path: &Path) -> Result<(), VectorStoreError> {
+        self.create_collection().await?;
+        let file = tokio::fs::File::open(path)
+            .await
+            .map_err(|e| VectorStoreError::SnapshotError(e.to_string()))?;
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| VectorStoreError::SnapshotError(e.to_string()))?;
+        sqlx::query(&format!(
+            "DELETE FROM {} {}",
+            self.table_name,
+            self.where_tenant()
+        ))
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| VectorStoreError::SnapshotError(e.to_string()))?;
+
+        let mut copy = tx
+            .copy_in_raw(&format!(
+                "COPY {} ({}) FROM STDIN (FORMAT binary)",
+                self.table_name, SNAPSHOT_COLUMNS
+            ))
+            .await
+            .map_err(|e| VectorStoreError::SnapshotError(e.to_string()))?;
+        copy.read_from(file)
+            .await
+            .map_err(|e| VectorStoreError::SnapshotError(e.to_string()))?;
+        copy.finish()
+            .await
+            .map_err(|e| VectorStoreError::SnapshotError(e.to_string()))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| VectorStoreError::SnapshotError(e.to_string()))?;
+        Ok(())
+    }
+
+    fn snapshot_extension(&self) -> &'static str {
+        "pgcopy"
+    }
+
+    async fn rebuild_index(&self) -> Result<bool, VectorStoreError> {
+        if self.get_collection_info().await?.is_none() {
+            return Err(VectorStoreError::CollectionError(format!(
+                "table {} does not exist",
+                self.table_name
+            )));
+        }
+
+        // The index lives in the table's schema, so qualify it the same way
+        let mut columns = vec!["embedding"];
+        if self.title_vectors {
+            columns.push("title_embedding");
+        }
+        let mut statements = Vec::new();
+        for column in columns {
+            statements.push(format!(
+                "DROP INDEX IF EXISTS {}_{}_idx",
+                self.table_name, column
+            ));
+            statements.push(self.vector_index_sql(column));
+        }
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| VectorStoreError::CollectionError(e.to_string()))?;
+        for statement in statements {
+            sqlx::query(&statement)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| VectorStoreError::CollectionError(e.to_string()))?;
+        }
+        tx.commit()
+            .await
+            .map_err(|e| VectorStoreError::CollectionError(e.to_string()))?;
+
+        Ok(true)
+    }
+
     async fn delete_by_source_type(&self, source_type: SourceType) -> Result<(), VectorStoreError> {
         let source_type_str = source_type.to_string();
         let source_tag = format!("source:{}", source_type_str);
 
         let query = format!(
-            "DELETE FROM {} WHERE source_type = $1 OR $2 = ANY(tags)",
-            self.table_name
+            "DELETE FROM {} WHERE (source_type = $1 OR $2 = ANY(tags)){}",
+            self.table_name,
+            self.and_tenant()
         );
 
         sqlx::query(&query)
@@ -456,15 +1460,94 @@ impl VectorStore for PgVectorBackend {
         Ok(())
     }
 
+    async fn count_by_filter(&self, filter: &SearchFilter) -> Result<u64, VectorStoreError> {
+        if self.get_collection_info().await?.is_none() {
+            return Ok(0);
+        }
+        self.ensure_columns().await?;
+        let where_clause = self.filter_where(filter);
+        let query = format!("SELECT COUNT(*) FROM {} {}", self.table_name, where_clause);
+        let count: i64 = Self::bind_filter(sqlx::query(&query), filter)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| VectorStoreError::SearchError(e.to_string()))?
+            .get(0);
+        Ok(count as u64)
+    }
+
+    async fn delete_by_filter(&self, filter: &SearchFilter) -> Result<u64, VectorStoreError> {
+        if self.get_collection_info().await?.is_none() {
+            return Ok(0);
+        }
+        self.ensure_columns().await?;
+        let where_clause = self.filter_where(filter);
+        let query = format!("DELETE FROM {} {}", self.table_name, where_clause);
+        let result = Self::bind_filter(sqlx::query(&query), filter)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| VectorStoreError::DeleteError(e.to_string()))?;
+        Ok(result.rows_affected())
+    }
+
+    async fn soft_delete_by_filter(
+        &self,
+        filter: &SearchFilter,
+        deleted_at: DateTime<Utc>,
+    ) -> Result<u64, VectorStoreError> {
+        if self.get_collection_info().await?.is_none() {
+            return Ok(0);
+        }
+        self.ensure_columns().await?;
+        let filter = filter.clone().with_deleted(Deleted::Live);
+        // The timestamp is bound after the filter's values
+        let mut param_index = 1;
+        let mut conditions = Self::filter_conditions(&filter, &mut param_index);
+        conditions.extend(self.tenant_condition());
+        let query = format!(
+            "UPDATE {} SET deleted_at = ${} WHERE {}",
+            self.table_name,
+            param_index,
+            conditions.join(" AND ")
+        );
+        let result = Self::bind_filter(sqlx::query(&query), &filter)
+            .bind(deleted_at)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| VectorStoreError::DeleteError(e.to_string()))?;
+        Ok(result.rows_affected())
+    }
+
+    async fn restore_by_filter(&self, filter: &SearchFilter) -> Result<u64, VectorStoreError> {
+        if self.get_collection_info().await?.is_none() {
+            return Ok(0);
+        }
+        self.ensure_columns().await?;
+        let filter = filter.clone().with_deleted(Deleted::Only);
+        let query = format!(
+            "UPDATE {} SET deleted_at = NULL {}",
+            self.table_name,
+            self.filter_where(&filter)
+        );
+        let result = Self::bind_filter(sqlx::query(&query), &filter)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| VectorStoreError::UpsertError(e.to_string()))?;
+        Ok(result.rows_affected())
+    }
+
     async fn list_all_tags(&self) -> Result<Vec<(String, u64)>, VectorStoreError> {
+        self.ensure_columns().await?;
+
         let query = format!(
             r#"
             SELECT tag, COUNT(*) as count
             FROM {}, unnest(tags) as tag
+            {}
             GROUP BY tag
             ORDER BY count DESC, tag ASC
             "#,
-            self.table_name
+            self.table_name,
+            self.where_live()
         );
 
         let rows = sqlx::query(&query)
@@ -484,7 +1567,389 @@ impl VectorStore for PgVectorBackend {
         Ok(tags)
     }
 
+    async fn list_documents(
+        &self,
+        source_type: &SourceType,
+    ) -> Result<Vec<IndexedDocument>, VectorStoreError> {
+        let query = format!(
+            r#"
+            SELECT document_id, MIN(source_location) as location, MIN(checksum) as checksum,
+                   COUNT(*) as chunk_count, MIN(workspace.attribute) as workspace
+            FROM {}
+            LEFT JOIN LATERAL (
+                SELECT attribute FROM unnest(attributes) as attribute
+                WHERE starts_with(attribute, $2)
+                LIMIT 1
+            ) workspace ON true
+            WHERE source_type = $1{}
+            GROUP BY document_id
+            ORDER BY location ASC
+            "#,
+            self.table_name,
+            self.and_tenant()
+        );
+
+        let rows = sqlx::query(&query)
+            .bind(source_type.to_string())
+            .bind(attribute_payload(WORKSPACE_ATTRIBUTE, ""))
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| VectorStoreError::SearchError(e.to_string()))?;
+
+        let documents = rows
+            .into_iter()
+            .map(|row: PgRow| {
+                let chunk_count: i64 = row.get("chunk_count");
+                IndexedDocument {
+                    document_id: row.get("document_id"),
+                    location: row.get("location"),
+                    checksum: row.get("checksum"),
+                    chunk_count: chunk_count as u64,
+                    workspace: row
+                        .get::<Option<String>, _>("workspace")
+                        .and_then(|a| parse_attribute_payload(&a))
+                        .map(|(_, value)| value),
+                }
+            })
+            .collect();
+
+        Ok(documents)
+    }
+
+    async fn find_content_hashes(
+        &self,
+        hashes: &[String],
+    ) -> Result<Vec<(String, String)>, VectorStoreError> {
+        if hashes.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        self.ensure_columns().await?;
+
+        let query = format!(
+            "SELECT content_hash, document_id FROM {} WHERE content_hash = ANY($1){}",
+            self.table_name,
+            self.and_tenant()
+        );
+
+        sqlx::query_as(&query)
+            .bind(hashes)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| VectorStoreError::SearchError(e.to_string()))
+    }
+
+    async fn get_vectors(
+        &self,
+        chunk_ids: &[String],
+    ) -> Result<HashMap<String, Vec<f32>>, VectorStoreError> {
+        let ids: Vec<uuid::Uuid> = chunk_ids
+            .iter()
+            .filter_map(|id| uuid::Uuid::parse_str(id).ok())
+            .collect();
+        if ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let query = format!(
+            "SELECT id::text as chunk_id, embedding FROM {} WHERE id = ANY($1)",
+            self.table_name
+        );
+
+        let rows = sqlx::query(&query)
+            .bind(&ids)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| VectorStoreError::SearchError(e.to_string()))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row: PgRow| {
+                let embedding: Vector = row.get("embedding");
+                (row.get("chunk_id"), embedding.to_vec())
+            })
+            .collect())
+    }
+
+    async fn inventory(&self) -> Result<IndexInventory, VectorStoreError> {
+        self.ensure_columns().await?;
+
+        let sources_query = format!(
+            r#"
+            SELECT source_type, COUNT(DISTINCT document_id) as documents,
+                   COUNT(*) as chunks, MAX(indexed_at) as last_indexed
+            FROM {}
+            {}
+            GROUP BY source_type
+            ORDER BY source_type ASC
+            "#,
+            self.table_name,
+            self.where_live()
+        );
+        let sources = sqlx::query(&sources_query)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| VectorStoreError::SearchError(e.to_string()))?
+            .into_iter()
+            .map(|row: PgRow| {
+                let documents: i64 = row.get("documents");
+                let chunks: i64 = row.get("chunks");
+                SourceInventory {
+                    source_type: row.get("source_type"),
+                    documents: documents as u64,
+                    chunks: chunks as u64,
+                    last_indexed: row.get("last_indexed"),
+                }
+            })
+            .collect();
+
+        let tags_query = format!(
+            r#"
+            SELECT tag, COUNT(DISTINCT document_id) as documents
+            FROM {}, unnest(tags) as tag
+            {}
+            GROUP BY tag
+            ORDER BY documents DESC, tag ASC
+            "#,
+            self.table_name,
+            self.where_live()
+        );
+        let tags = sqlx::query(&tags_query)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| VectorStoreError::SearchError(e.to_string()))?
+            .into_iter()
+            .map(|row: PgRow| {
+                let documents: i64 = row.get("documents");
+                (row.get("tag"), documents as u64)
+            })
+            .collect();
+
+        // Table, TOAST and index pages together, shared by every tenant of the table
+        let storage_bytes: i64 =
+            sqlx::query_scalar("SELECT COALESCE(pg_total_relation_size(to_regclass($1)), 0)")
+                .bind(&self.table_name)
+                .fetch_one(&self.pool)
+                .await
+                .map_err(|e| VectorStoreError::SearchError(e.to_string()))?;
+
+        Ok(IndexInventory {
+            sources,
+            tags,
+            storage_bytes: storage_bytes as u64,
+        })
+    }
+
+    async fn get_adjacent_chunks(
+        &self,
+        document_id: &str,
+        chunk_index: u32,
+        radius: u32,
+    ) -> Result<Vec<DocumentChunk>, VectorStoreError> {
+        self.ensure_columns().await?;
+
+        let query = format!(
+            r#"
+            SELECT id::text as chunk_id, document_id, chunk_index, content,
+                   source_type, source_location, source_url, tags, attributes, checksum,
+                   created_at, updated_at, line_start, line_end
+            FROM {}
+            WHERE document_id = $1 AND chunk_index BETWEEN $2 AND $3 AND chunk_index <> $4{}
+            ORDER BY chunk_index
+            "#,
+            self.table_name,
+            self.and_tenant()
+        );
+
+        let rows = sqlx::query(&query)
+            .bind(document_id)
+            .bind(chunk_index.saturating_sub(radius) as i32)
+            .bind(chunk_index.saturating_add(radius).min(i32::MAX as u32) as i32)
+            .bind(chunk_index as i32)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| VectorStoreError::SearchError(e.to_string()))?;
+
+        Ok(rows.into_iter().map(Self::row_to_chunk).collect())
+    }
+
+    async fn scroll_by_document(
+        &self,
+        document_id: &str,
+    ) -> Result<Vec<DocumentChunk>, VectorStoreError> {
+        self.ensure_columns().await?;
+
+        let query = format!(
+            r#"
+            SELECT id::text as chunk_id, document_id, chunk_index, content,
+                   source_type, source_location, source_url, tags, attributes, checksum,
+                   created_at, updated_at, line_start, line_end
+            FROM {}
+            WHERE document_id = $1{}
+            ORDER BY chunk_index
+            "#,
+            self.table_name,
+            self.and_tenant()
+        );
+
+        let rows = sqlx::query(&query)
+            .bind(document_id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| VectorStoreError::SearchError(e.to_string()))?;
+
+        Ok(rows.into_iter().map(Self::row_to_chunk).collect())
+    }
+
+    async fn scroll_chunks(
+        &self,
+        offset: Option<String>,
+        limit: u32,
+    ) -> Result<ChunkPage, VectorStoreError> {
+        self.ensure_columns().await?;
+
+        let after = offset
+            .map(|off| uuid::Uuid::parse_str(&off))
+            .transpose()
+            .map_err(|e| VectorStoreError::SearchError(format!("Invalid offset: {}", e)))?;
+
+        let query = format!(
+            r#"
+            SELECT id::text as chunk_id, document_id, chunk_index, content, embedding,
+                   source_type, source_location, source_url, tags, attributes, checksum,
+                   created_at, updated_at, line_start, line_end, deleted_at
+            FROM {}
+            WHERE ($1::uuid IS NULL OR id > $1){}
+            ORDER BY id
+            LIMIT {}
+            "#,
+            self.table_name,
+            self.and_tenant(),
+            limit
+        );
+
+        let rows = sqlx::query(&query)
+            .bind(after)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| VectorStoreError::SearchError(e.to_string()))?;
+
+        let chunks: Vec<DocumentChunk> = rows.into_iter().map(Self::row_to_chunk).collect();
+
+        let next_offset = if chunks.len() == limit as usize {
+            chunks.last().map(|c| c.id.clone())
+        } else {
+            None
+        };
+
+        Ok(ChunkPage {
+            chunks,
+            next_offset,
+        })
+    }
+
     fn collection(&self) -> &str {
         &self.collection
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(id: u128) -> UpsertRow {
+        UpsertRow {
+            id: uuid::Uuid::from_u128(id),
+            document_id: "doc".to_string(),
+            chunk_index: 0,
+            content: String::new(),
+            embedding: Vector::from(vec![0.0]),
+            source_type: "local".to_string(),
+            source_location: "docs/a.md".to_string(),
+            source_url: None,
+            tags: Vec::new(),
+            checksum: String::new(),
+            created_at: Utc::now(),
+            line_start: None,
+            line_end: None,
+            attributes: Vec::new(),
+            updated_at: Utc::now(),
+            content_hash: String::new(),
+            title_embedding: None,
+            deleted_at: None,
+        }
+    }
+
+    /// A backend that never connects, for building SQL.
+    fn backend(tenant_tags: Vec<Tag>) -> PgVectorBackend {
+        PgVectorBackend {
+            pool: PgPoolOptions::new()
+                .connect_lazy("postgres://localhost/test")
+                .unwrap(),
+            table_name: "chunks".to_string(),
+            collection: "chunks".to_string(),
+            embedding_dim: 4,
+            quantization: Quantization::None,
+            index_type: AnnIndex::Hnsw,
+            index_parameters: Vec::new(),
+            search_settings: Vec::new(),
+            upsert_batch_size: 64,
+            title_vectors: false,
+            title_weight: 0.0,
+            tenant_tags,
+            columns_migrated: OnceCell::new(),
+        }
+    }
+
+    fn rows(ids: &[u128]) -> Vec<UpsertRow> {
+        ids.iter().copied().map(row).collect()
+    }
+
+    /// Batch sizes and whether each is bulk-inserted.
+    fn split(ids: &[u128], batch_size: usize) -> Vec<(usize, bool)> {
+        let rows = rows(ids);
+        upsert_batches(&rows, batch_size)
+            .map(|(batch, bulk)| (batch.len(), bulk))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_filter_where() {
+        let any = SearchFilter::new().with_deleted(Deleted::Any);
+        assert_eq!(backend(Vec::new()).filter_where(&any), "");
+
+        let tenant = backend(vec![Tag::new("team", "search").unwrap()]);
+        assert!(tenant.filter_where(&any).starts_with("WHERE tags @> "));
+        assert!(
+            backend(Vec::new())
+                .filter_where(&SearchFilter::new())
+                .starts_with("WHERE ")
+        );
+    }
+
+    #[test]
+    fn test_has_duplicate_ids() {
+        assert!(!has_duplicate_ids(&[]));
+        assert!(!has_duplicate_ids(&rows(&[1])));
+        assert!(!has_duplicate_ids(&rows(&[1, 2, 3])));
+        assert!(has_duplicate_ids(&rows(&[1, 2, 1])));
+        assert!(has_duplicate_ids(&rows(&[4, 4])));
+    }
+
+    #[test]
+    fn test_upsert_batches_at_boundaries() {
+        assert!(split(&[], 4).is_empty());
+        assert_eq!(split(&[1, 2, 3, 4], 4), vec![(4, true)]);
+        assert_eq!(split(&[1, 2, 3, 4, 5], 4), vec![(4, true), (1, false)]);
+        assert_eq!(
+            split(&[1, 2, 3, 4, 5, 6, 7, 8], 4),
+            vec![(4, true), (4, true)]
+        );
+        assert_eq!(split(&[1, 2, 3], 1), vec![(1, false); 3]);
+
+        // Only a repeat within one statement forces row-by-row inserts
+        assert_eq!(split(&[1, 2, 3, 1], 4), vec![(4, false)]);
+        assert_eq!(split(&[1, 2, 3, 4, 1], 4), vec![(4, true), (1, false)]);
+        assert_eq!(split(&[1, 2, 3, 4, 4, 5], 4), vec![(4, true), (2, true)]);
+    }
+}
@@ -1,42 +1,114 @@
 //! Qdrant vector store backend implementation.
 
 use async_trait::async_trait;
-use qdrant_client::Qdrant;
+use chrono::{DateTime, Utc};
+use prost_types::Timestamp;
 use qdrant_client::qdrant::{
-    Condition, CreateCollectionBuilder, DeletePointsBuilder, Distance, Filter,
-    PayloadIncludeSelector, PointStruct, ScrollPointsBuilder, SearchPointsBuilder,
-    UpsertPointsBuilder, VectorParamsBuilder,
+    BinaryQuantizationBuilder, Condition, CountPointsBuilder, CreateCollectionBuilder,
+    CreateFieldIndexCollectionBuilder, DatetimeRange, DeletePayloadPointsBuilder,
+    DeletePointsBuilder, DeleteSnapshotRequestBuilder, Distance, FieldType, Filter,
+    GetPointsBuilder, Memory, Modifier, NamedVectors, PayloadIncludeSelector, PointId, PointStruct,
+    PointsOperationResponse, QuantizationSearchParamsBuilder, Query, QueryPointsBuilder, Range,
+    RetrievedPoint, ScalarQuantizationBuilder, ScoredPoint, ScrollPointsBuilder,
+    SearchParamsBuilder, SearchPointsBuilder, SetPayloadPointsBuilder, SnapshotDownloadBuilder,
+    SparseVectorParamsBuilder, SparseVectorsConfigBuilder, UpsertPointsBuilder, Vector,
+    VectorInput, VectorParamsBuilder, VectorsConfig, VectorsConfigBuilder, VectorsOutput,
+    VectorsSelector, points_selector::PointsSelectorOneOf, vectors_config,
+};
+use qdrant_client::{Qdrant, QdrantError};
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::Mutex;
+use tokio::io::AsyncWriteExt;
+use tokio::task::{JoinError, JoinSet};
+
+use super::cipher::{ContentCipher, open_content};
+use super::{
+    ChunkPage, CollectionInfo, DEFAULT_EMBEDDING_DIM, IndexInventory, IndexedDocument,
+    InventoryBuilder, TITLE_VECTOR_NAME, VectorStore, add_tenant_tags, cosine_similarity,
+    tenant_tags, title_weighted_score,
 };
-use std::collections::HashMap;
-
-use super::{CollectionInfo, DEFAULT_EMBEDDING_DIM, VectorStore};
 use crate::error::VectorStoreError;
-use crate::models::{DocumentChunk, SearchResult, Source, SourceType, Tag, VectorStoreConfig};
+use crate::models::{
+    Deleted, DocumentChunk, Quantization, RELATIVE_PATH_ATTRIBUTE, RangeOp, SearchFilter,
+    SearchResult, Source, SourceType, Tag, TimeField, VectorStoreConfig, WORKSPACE_ATTRIBUTE,
+    attribute_payload, parse_attribute_payload, parse_timestamp,
+};
+use crate::services::keyword::sparse_vector;
+
+/// Name of the sparse keyword vector stored alongside the dense embedding.
+const SPARSE_VECTOR_NAME: &str = "bm25";
+
+/// Page size multiplier when paths or symbols are filtered client-side.
+const PATH_FILTER_OVERFETCH: u64 = 5;
+
+/// Vectors a collection holds besides the default dense embedding.
+#[derive(Debug, Clone, Copy)]
+struct VectorLayout {
+    sparse: bool,
+    title: bool,
+}
 
 /// Qdrant vector store backend.
 pub struct QdrantBackend {
     client: Qdrant,
     collection: String,
     embedding_dim: u64,
+    quantization: Quantization,
+    upsert_batch_size: usize,
+    upsert_wait: bool,
+    upsert_parallelism: usize,
+    /// Create new collections with the named title vector
+    title_vectors: bool,
+    /// Share of the title similarity in combined scores; 0 searches the body only
+    title_weight: f32,
+    /// REST endpoint for snapshot transfers, which gRPC doesn't cover
+    rest_url: String,
+    api_key: Option<String>,
+    /// Tags every stored point carries and every read or delete is restricted to
+    tenant_tags: Vec<Tag>,
+    /// Cached vector layout of the collection
+    vector_layout: Mutex<Option<VectorLayout>>,
+    /// Encrypts the content payload when a key is configured
+    cipher: Option<ContentCipher>,
 }
 
 impl QdrantBackend {
     /// Create a new Qdrant backend from configuration with custom embedding dimension.
     pub fn new(config: &VectorStoreConfig, embedding_dim: u64) -> Result<Self, VectorStoreError> {
-        let mut builder = Qdrant::from_url(&config.url);
+        // The version check prints to stdout, which corrupts JSON and MCP output
+        let mut builder = Qdrant::from_url(&config.url).skip_compatibility_check();
 
         if let Some(ref api_key) = config.api_key {
             builder = builder.api_key(api_key.clone());
         }
 
+        let tenant_tags = tenant_tags(config)?;
         let client = builder
             .build()
             .map_err(|e| VectorStoreError::ConnectionError(e.to_string()))?;
 
         Ok(Self {
             client,
-            collection: config.collection.clone(),
+            collection: config.scoped_collection(),
             embedding_dim,
+            quantization: config.quantization,
+            upsert_batch_size: config.upsert_batch_size.max(1) as usize,
+            upsert_wait: config.upsert_wait,
+            upsert_parallelism: config.upsert_parallelism.max(1) as usize,
+            title_vectors: config.title_vectors,
+            title_weight: if config.title_vectors {
+                config.title_weight
+            } else {
+                0.0
+            },
+            rest_url: config.qdrant_rest_url(),
+            api_key: config.api_key.clone(),
+            tenant_tags,
+            vector_layout: Mutex::new(None),
+            cipher: ContentCipher::from_config(config)?,
         })
     }
 
@@ -45,27 +117,862 @@ impl QdrantBackend {
         Self::new(&VectorStoreConfig::default(), DEFAULT_EMBEDDING_DIM)
     }
 
-    fn build_search_filter(tags: &[Tag], source_types: &[SourceType]) -> Option<Filter> {
+    fn build_search_filter(filter: &SearchFilter) -> Filter {
         let mut must_conditions: Vec<Condition> = Vec::new();
+        let mut must_not_conditions: Vec<Condition> = Vec::new();
+
+        // Live chunks have no `deleted_at`
+        let live = Condition::is_empty("deleted_at");
+        match filter.deleted {
+            Deleted::Live => must_conditions.push(live),
+            Deleted::Only => must_not_conditions.push(live),
+            Deleted::Any => {}
+        }
 
-        for tag in tags {
+        for tag in &filter.tags {
             must_conditions.push(Condition::matches("tags", tag.to_payload_string()));
         }
 
-        if !source_types.is_empty() {
-            let source_conditions: Vec<Condition> = source_types
+        for attribute in &filter.attributes {
+            must_conditions.push(Condition::matches(
+                "attributes",
+                attribute.to_payload_string(),
+            ));
+        }
+
+        if !filter.languages.is_empty() {
+            must_conditions.push(Condition::matches("attributes", filter.language_payloads()));
+        }
+
+        if !filter.source_types.is_empty() {
+            let source_conditions: Vec<Condition> = filter
+                .source_types
                 .iter()
                 .map(|st| Condition::matches("source_type", st.to_string()))
                 .collect();
             must_conditions.push(Filter::should(source_conditions).into());
         }
 
-        if must_conditions.is_empty() {
-            None
+        for bound in &filter.time_bounds {
+            let timestamp = Some(Timestamp {
+                seconds: bound.value.timestamp(),
+                nanos: bound.value.timestamp_subsec_nanos() as i32,
+            });
+            let range = match bound.op {
+                RangeOp::Gt => DatetimeRange {
+                    gt: timestamp,
+                    ..Default::default()
+                },
+                RangeOp::Gte => DatetimeRange {
+                    gte: timestamp,
+                    ..Default::default()
+                },
+                RangeOp::Lt => DatetimeRange {
+                    lt: timestamp,
+                    ..Default::default()
+                },
+                RangeOp::Lte => DatetimeRange {
+                    lte: timestamp,
+                    ..Default::default()
+                },
+            };
+            must_conditions.push(Condition::datetime_range(bound.field.as_str(), range));
+        }
+
+        if let Some(ref document_ids) = filter.document_ids {
+            must_conditions.push(Condition::matches("document_id", document_ids.clone()));
+        }
+
+        Filter {
+            must: must_conditions,
+            must_not: must_not_conditions,
+            ..Default::default()
+        }
+    }
+
+    /// Restrict a filter to points carrying every tenant tag.
+    fn scoped(&self, filter: Option<Filter>) -> Option<Filter> {
+        if self.tenant_tags.is_empty() {
+            return filter;
+        }
+        let mut conditions: Vec<Condition> = self
+            .tenant_tags
+            .iter()
+            .map(|tag| Condition::matches("tags", tag.to_payload_string()))
+            .collect();
+        conditions.extend(filter.map(Condition::from));
+        Some(Filter::must(conditions))
+    }
+
+    /// [`Self::scoped`] for a filter that is always present.
+    fn scoped_all(&self, filter: Filter) -> Filter {
+        self.scoped(Some(filter)).unwrap_or_default()
+    }
+
+    /// This tenant's points that aren't soft-deleted.
+    fn live_points(&self) -> Filter {
+        self.scoped_all(Filter::must([Condition::is_empty("deleted_at")]))
+    }
+
+    /// Snapshots hold the whole collection, so a tenant can't take or restore one.
+    fn check_snapshot_tenancy(&self) -> Result<(), VectorStoreError> {
+        if self.tenant_tags.is_empty() {
+            Ok(())
+        } else {
+            Err(VectorStoreError::SnapshotError(
+                "Qdrant snapshots cover the whole collection and are unavailable with tenant_tags"
+                    .to_string(),
+            ))
+        }
+    }
+
+    /// The points matching a filter, as a selector for payload updates, and
+    /// how many there are.
+    async fn select_points(
+        &self,
+        filter: &SearchFilter,
+    ) -> Result<(PointsSelectorOneOf, u64), VectorStoreError> {
+        if Self::filters_client_side(filter) {
+            let ids = self.matching_point_ids(filter).await?;
+            let count = ids.len() as u64;
+            return Ok((ids.into(), count));
+        }
+        let count = self.count_by_filter(filter).await?;
+        let selector = self.scoped_all(Self::build_search_filter(filter)).into();
+        Ok((selector, count))
+    }
+
+    /// The `content_hash` payload of a chunk: keyed when content is encrypted.
+    fn stored_hash(&self, content_hash: String) -> String {
+        match self.cipher {
+            Some(ref cipher) => cipher.keyed_hash(&content_hash),
+            None => content_hash,
+        }
+    }
+
+    /// The point a chunk is stored as, with its payload and named vectors.
+    fn chunk_point(
+        &self,
+        mut chunk: DocumentChunk,
+        layout: VectorLayout,
+        indexed_at: &str,
+    ) -> Result<PointStruct, VectorStoreError> {
+        add_tenant_tags(&mut chunk.tags, &self.tenant_tags);
+        let content_hash = self.stored_hash(chunk.content_hash());
+        let mut payload: HashMap<String, qdrant_client::qdrant::Value> = HashMap::new();
+        payload.insert("content_hash".to_string(), content_hash.into());
+        payload.insert("document_id".to_string(), chunk.document_id.into());
+        payload.insert(
+            "chunk_index".to_string(),
+            i64::from(chunk.chunk_index).into(),
+        );
+        let content = match self.cipher {
+            Some(ref cipher) => cipher.seal(&chunk.id, &chunk.content)?,
+            None => chunk.content.clone(),
+        };
+        payload.insert("content".to_string(), content.into());
+        payload.insert(
+            "source_type".to_string(),
+            chunk.source.source_type.to_string().into(),
+        );
+        payload.insert("source_location".to_string(), chunk.source.location.into());
+        if let Some(url) = chunk.source.url {
+            payload.insert("source_url".to_string(), url.into());
+        }
+        payload.insert(
+            "checksum".to_string(),
+            self.stored_checksum(&chunk.checksum).into(),
+        );
+        let updated_at = if chunk.updated_at.is_empty() {
+            chunk.created_at.clone()
+        } else {
+            chunk.updated_at
+        };
+        payload.insert("created_at".to_string(), chunk.created_at.into());
+        payload.insert("updated_at".to_string(), updated_at.into());
+        payload.insert("indexed_at".to_string(), indexed_at.into());
+        if let Some(deleted_at) = chunk.deleted_at {
+            payload.insert("deleted_at".to_string(), deleted_at.into());
+        }
+
+        let tag_strings: Vec<qdrant_client::qdrant::Value> = chunk
+            .tags
+            .into_iter()
+            .map(|t| t.to_payload_string().into())
+            .collect();
+        payload.insert("tags".to_string(), tag_strings.into());
+
+        if !chunk.attributes.is_empty() {
+            let attribute_strings: Vec<qdrant_client::qdrant::Value> = chunk
+                .attributes
+                .iter()
+                .map(|(k, v)| attribute_payload(k, v).into())
+                .collect();
+            payload.insert("attributes".to_string(), attribute_strings.into());
+        }
+
+        if let Some(line_start) = chunk.line_start {
+            payload.insert("line_start".to_string(), i64::from(line_start).into());
+        }
+        if let Some(line_end) = chunk.line_end {
+            payload.insert("line_end".to_string(), i64::from(line_end).into());
+        }
+
+        let title_vector =
+            Some(chunk.title_vector).filter(|vector| layout.title && !vector.is_empty());
+        // Term hashes would reveal the vocabulary of encrypted content
+        let sparse = layout.sparse && self.cipher.is_none();
+        let point = if sparse || title_vector.is_some() {
+            let mut vectors = NamedVectors::default().add_vector("", chunk.dense_vector);
+            if sparse {
+                let (indices, values) = sparse_vector(&chunk.content);
+                vectors =
+                    vectors.add_vector(SPARSE_VECTOR_NAME, Vector::new_sparse(indices, values));
+            }
+            if let Some(title_vector) = title_vector {
+                vectors = vectors.add_vector(TITLE_VECTOR_NAME, title_vector);
+            }
+            PointStruct::new(chunk.id, vectors, payload)
+        } else {
+            PointStruct::new(chunk.id, chunk.dense_vector, payload)
+        };
+        Ok(point)
+    }
+
+    /// Surface the error of a finished upsert batch, if any.
+    fn finish_upsert(
+        result: Option<Result<Result<PointsOperationResponse, QdrantError>, JoinError>>,
+    ) -> Result<(), VectorStoreError> {
+        match result {
+            Some(Ok(Err(e))) => Err(VectorStoreError::UpsertError(e.to_string())),
+            Some(Err(e)) => Err(VectorStoreError::UpsertError(e.to_string())),
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether the filter has path globs or symbol substrings, which are
+    /// checked client-side.
+    fn filters_client_side(filter: &SearchFilter) -> bool {
+        !filter.paths.is_empty() || !filter.relative_paths.is_empty() || !filter.symbols.is_empty()
+    }
+
+    /// Page through candidates until `wanted` of them match the filter's
+    /// client-side checks or the store runs out.
+    ///
+    /// Qdrant has no pattern match on keyword payloads, so path globs and
+    /// symbol substrings are applied to over-fetched pages instead. `fetch`
+    /// returns the page at an offset and size, and whether more may follow.
+    async fn fill_client_side<F, Fut>(
+        filter: &SearchFilter,
+        wanted: u64,
+        mut fetch: F,
+    ) -> Result<Vec<SearchResult>, VectorStoreError>
+    where
+        F: FnMut(u64, u64) -> Fut,
+        Fut: Future<Output = Result<(Vec<SearchResult>, bool), VectorStoreError>>,
+    {
+        let matcher = ClientSideMatcher::new(filter);
+        let page_size = wanted.saturating_mul(PATH_FILTER_OVERFETCH).max(1);
+        let wanted = usize::try_from(wanted).unwrap_or(usize::MAX);
+        let mut seen = HashSet::new();
+        let mut matched = Vec::new();
+        let mut offset = 0;
+
+        while matched.len() < wanted {
+            let (page, more) = fetch(offset, page_size).await?;
+            matched.extend(page.into_iter().filter(|r| {
+                seen.insert(r.chunk_id.clone())
+                    && matcher.matches(&r.source.location, &r.attributes)
+            }));
+            if !more {
+                break;
+            }
+            offset = offset.saturating_add(page_size);
+        }
+
+        matched.truncate(wanted);
+        Ok(matched)
+    }
+
+    /// IDs of the points matching a filter whose paths or symbols are
+    /// checked client-side, found by scrolling the server-side matches.
+    async fn matching_point_ids(
+        &self,
+        filter: &SearchFilter,
+    ) -> Result<Vec<PointId>, VectorStoreError> {
+        let matcher = ClientSideMatcher::new(filter);
+        let server_filter = self.scoped_all(Self::build_search_filter(filter));
+        let mut ids = Vec::new();
+        let mut offset: Option<PointId> = None;
+
+        loop {
+            let mut scroll_builder = ScrollPointsBuilder::new(&self.collection)
+                .limit(100)
+                .with_payload(true)
+                .with_vectors(false)
+                .filter(server_filter.clone());
+            if let Some(off) = offset {
+                scroll_builder = scroll_builder.offset(off);
+            }
+
+            let response = self
+                .client
+                .scroll(scroll_builder)
+                .await
+                .map_err(|e| VectorStoreError::SearchError(e.to_string()))?;
+
+            for point in response.result {
+                let Some(id) = point.id.clone() else {
+                    continue;
+                };
+                let chunk = self.point_to_chunk(point)?;
+                if matcher.matches(&chunk.source.location, &chunk.attributes) {
+                    ids.push(id);
+                }
+            }
+
+            offset = response.next_page_offset;
+            if offset.is_none() {
+                break;
+            }
+        }
+        Ok(ids)
+    }
+
+    /// Check which named vectors the collection was created with.
+    ///
+    /// Collections created before hybrid search support only hold dense vectors,
+    /// and only collections created with `title_vectors` hold title vectors.
+    async fn vector_layout(&self) -> Result<VectorLayout, VectorStoreError> {
+        if let Some(layout) = *self.vector_layout.lock().unwrap_or_else(|e| e.into_inner()) {
+            return Ok(layout);
+        }
+
+        let params = match self.client.collection_info(&self.collection).await {
+            Ok(info) => info.result.and_then(|r| r.config).and_then(|c| c.params),
+            Err(e) => return Err(VectorStoreError::CollectionError(e.to_string())),
+        };
+        let layout = VectorLayout {
+            sparse: params
+                .as_ref()
+                .and_then(|p| p.sparse_vectors_config.as_ref())
+                .is_some_and(|s| s.map.contains_key(SPARSE_VECTOR_NAME)),
+            title: params
+                .as_ref()
+                .and_then(|p| p.vectors_config.as_ref())
+                .and_then(|v| v.config.as_ref())
+                .is_some_and(|c| match c {
+                    vectors_config::Config::ParamsMap(map) => {
+                        map.map.contains_key(TITLE_VECTOR_NAME)
+                    }
+                    vectors_config::Config::Params(_) => false,
+                }),
+        };
+
+        self.set_vector_layout(Some(layout));
+        Ok(layout)
+    }
+
+    async fn sparse_enabled(&self) -> Result<bool, VectorStoreError> {
+        Ok(self.vector_layout().await?.sparse)
+    }
+
+    fn set_vector_layout(&self, layout: Option<VectorLayout>) {
+        *self.vector_layout.lock().unwrap_or_else(|e| e.into_inner()) = layout;
+    }
+
+    /// Search the body and title vectors and rank their union by combined score.
+    ///
+    /// Both similarities are recomputed from the returned vectors, since each
+    /// search only scores the vector it ran against.
+    async fn title_weighted_search(
+        &self,
+        query_vector: Vec<f32>,
+        limit: u64,
+        offset: u64,
+        filter: &SearchFilter,
+        min_score: Option<f32>,
+    ) -> Result<Vec<SearchResult>, VectorStoreError> {
+        let wanted = limit.saturating_add(offset);
+        let mut results = if !Self::filters_client_side(filter) {
+            self.title_weighted_page(&query_vector, 0, wanted, filter, min_score)
+                .await?
+                .0
+        } else {
+            Self::fill_client_side(filter, wanted, |offset, limit| {
+                self.title_weighted_page(&query_vector, offset, limit, filter, min_score)
+            })
+            .await?
+        };
+        results.sort_by(|a, b| b.score.total_cmp(&a.score));
+        results.truncate(usize::try_from(wanted).unwrap_or(usize::MAX));
+        Ok(results.into_iter().skip(offset as usize).collect())
+    }
+
+    /// One page of both the body and title searches, scored by their combined
+    /// similarity, and whether either search may have more.
+    async fn title_weighted_page(
+        &self,
+        query_vector: &[f32],
+        offset: u64,
+        limit: u64,
+        filter: &SearchFilter,
+        min_score: Option<f32>,
+    ) -> Result<(Vec<SearchResult>, bool), VectorStoreError> {
+        let mut points: HashMap<String, ScoredPoint> = HashMap::new();
+        let mut more = false;
+        for name in ["", TITLE_VECTOR_NAME] {
+            let mut search_builder =
+                SearchPointsBuilder::new(&self.collection, query_vector.to_vec(), limit)
+                    .with_payload(true)
+                    .with_vectors(VectorsSelector::from(vec![
+                        String::new(),
+                        TITLE_VECTOR_NAME.to_string(),
+                    ]));
+            if !name.is_empty() {
+                search_builder = search_builder.vector_name(name);
+            }
+            if offset > 0 {
+                search_builder = search_builder.offset(offset);
+            }
+            search_builder =
+                search_builder.filter(self.scoped_all(Self::build_search_filter(filter)));
+            if self.quantization != Quantization::None {
+                search_builder = search_builder.params(
+                    SearchParamsBuilder::default().quantization(
+                        QuantizationSearchParamsBuilder::default()
+                            .rescore(true)
+                            .oversampling(self.quantization.oversampling() as f64),
+                    ),
+                );
+            }
+
+            let response = self
+                .client
+                .search_points(search_builder)
+                .await
+                .map_err(|e| VectorStoreError::SearchError(e.to_string()))?;
+            more |= response.result.len() as u64 >= limit;
+            for point in response.result {
+                let id = point.id.as_ref().map(point_id_string).unwrap_or_default();
+                points.entry(id).or_insert(point);
+            }
+        }
+
+        let mut results: Vec<SearchResult> = points
+            .into_values()
+            .map(|point| {
+                let vectors = point.vectors.as_ref();
+                let body = Self::named_vector(vectors, "")
+                    .map(|v| cosine_similarity(query_vector, &v))
+                    .unwrap_or(point.score);
+                let title = Self::named_vector(vectors, TITLE_VECTOR_NAME)
+                    .map(|v| cosine_similarity(query_vector, &v));
+                let mut result = self.point_to_result(point)?;
+                result.score = title_weighted_score(body, title, self.title_weight);
+                Ok(result)
+            })
+            .collect::<Result<_, VectorStoreError>>()?;
+        results.retain(|r| min_score.is_none_or(|min| r.score >= min));
+        Ok((results, more))
+    }
+
+    /// One page of dense search results, and whether more may follow.
+    async fn dense_page(
+        &self,
+        query_vector: &[f32],
+        offset: u64,
+        limit: u64,
+        filter: &SearchFilter,
+        min_score: Option<f32>,
+    ) -> Result<(Vec<SearchResult>, bool), VectorStoreError> {
+        let mut search_builder =
+            SearchPointsBuilder::new(&self.collection, query_vector.to_vec(), limit)
+                .with_payload(true);
+
+        if offset > 0 {
+            search_builder = search_builder.offset(offset);
+        }
+
+        search_builder = search_builder.filter(self.scoped_all(Self::build_search_filter(filter)));
+
+        if let Some(score) = min_score {
+            search_builder = search_builder.score_threshold(score);
+        }
+
+        if self.quantization != Quantization::None {
+            search_builder = search_builder.params(
+                SearchParamsBuilder::default().quantization(
+                    QuantizationSearchParamsBuilder::default()
+                        .rescore(true)
+                        .oversampling(self.quantization.oversampling() as f64),
+                ),
+            );
+        }
+
+        let results = self
+            .client
+            .search_points(search_builder)
+            .await
+            .map_err(|e| VectorStoreError::SearchError(e.to_string()))?;
+
+        let more = results.result.len() as u64 >= limit;
+        let search_results = results
+            .result
+            .into_iter()
+            .map(|point| self.point_to_result(point))
+            .collect::<Result<_, _>>()?;
+        Ok((search_results, more))
+    }
+
+    /// One page of sparse keyword search results, and whether more may follow.
+    async fn keyword_page(
+        &self,
+        indices: &[u32],
+        values: &[f32],
+        offset: u64,
+        limit: u64,
+        filter: &SearchFilter,
+    ) -> Result<(Vec<SearchResult>, bool), VectorStoreError> {
+        let mut query_builder = QueryPointsBuilder::new(&self.collection)
+            .query(Query::new_nearest(VectorInput::new_sparse(
+                indices.to_vec(),
+                values.to_vec(),
+            )))
+            .using(SPARSE_VECTOR_NAME)
+            .limit(limit)
+            .with_payload(true);
+
+        if offset > 0 {
+            query_builder = query_builder.offset(offset);
+        }
+
+        query_builder = query_builder.filter(self.scoped_all(Self::build_search_filter(filter)));
+
+        let response = self
+            .client
+            .query(query_builder)
+            .await
+            .map_err(|e| VectorStoreError::SearchError(e.to_string()))?;
+
+        let more = response.result.len() as u64 >= limit;
+        let results = response
+            .result
+            .into_iter()
+            .map(|point| self.point_to_result(point))
+            .collect::<Result<_, _>>()?;
+        Ok((results, more))
+    }
+
+    fn point_to_result(&self, point: ScoredPoint) -> Result<SearchResult, VectorStoreError> {
+        let chunk_id = point.id.as_ref().map(point_id_string).unwrap_or_default();
+        let payload = point.payload;
+
+        let content = payload
+            .get("content")
+            .and_then(|v| match &v.kind {
+                Some(qdrant_client::qdrant::value::Kind::StringValue(s)) => Some(s.as_str()),
+                _ => None,
+            })
+            .unwrap_or("")
+            .to_string();
+        let content = open_content(self.cipher.as_ref(), &chunk_id, content)?;
+
+        let source_type_str = payload
+            .get("source_type")
+            .and_then(|v| match &v.kind {
+                Some(qdrant_client::qdrant::value::Kind::StringValue(s)) => Some(s.as_str()),
+                _ => None,
+            })
+            .unwrap_or("local");
+        let source_type: SourceType = source_type_str.parse().unwrap_or(SourceType::Local);
+
+        let source_location = payload
+            .get("source_location")
+            .and_then(|v| match &v.kind {
+                Some(qdrant_client::qdrant::value::Kind::StringValue(s)) => Some(s.as_str()),
+                _ => None,
+            })
+            .unwrap_or("")
+            .to_string();
+
+        let source_url = payload.get("source_url").and_then(|v| match &v.kind {
+            Some(qdrant_client::qdrant::value::Kind::StringValue(s)) => Some(s.clone()),
+            _ => None,
+        });
+
+        let tags: Vec<Tag> = payload
+            .get("tags")
+            .and_then(|v| match &v.kind {
+                Some(qdrant_client::qdrant::value::Kind::ListValue(list)) => Some(
+                    list.values
+                        .iter()
+                        .filter_map(|v| match &v.kind {
+                            Some(qdrant_client::qdrant::value::Kind::StringValue(s)) => {
+                                s.parse().ok()
+                            }
+                            _ => None,
+                        })
+                        .collect(),
+                ),
+                _ => None,
+            })
+            .unwrap_or_default();
+
+        let attributes = payload
+            .get("attributes")
+            .and_then(|v| match &v.kind {
+                Some(qdrant_client::qdrant::value::Kind::ListValue(list)) => Some(
+                    list.values
+                        .iter()
+                        .filter_map(|v| match &v.kind {
+                            Some(qdrant_client::qdrant::value::Kind::StringValue(s)) => {
+                                parse_attribute_payload(s)
+                            }
+                            _ => None,
+                        })
+                        .collect(),
+                ),
+                _ => None,
+            })
+            .unwrap_or_default();
+
+        let line_start = payload.get("line_start").and_then(|v| match &v.kind {
+            Some(qdrant_client::qdrant::value::Kind::IntegerValue(n)) => Some(*n as u32),
+            _ => None,
+        });
+
+        let line_end = payload.get("line_end").and_then(|v| match &v.kind {
+            Some(qdrant_client::qdrant::value::Kind::IntegerValue(n)) => Some(*n as u32),
+            _ => None,
+        });
+
+        let document_id = payload
+            .get("document_id")
+            .and_then(|v| match &v.kind {
+                Some(qdrant_client::qdrant::value::Kind::StringValue(s)) => Some(s.clone()),
+                _ => None,
+            })
+            .unwrap_or_default();
+
+        let chunk_index = payload
+            .get("chunk_index")
+            .and_then(|v| match &v.kind {
+                Some(qdrant_client::qdrant::value::Kind::IntegerValue(n)) => Some(*n as u32),
+                _ => None,
+            })
+            .unwrap_or(0);
+
+        let location = if let Some(url) = &source_url {
+            url.clone()
+        } else if let (Some(start), Some(end)) = (line_start, line_end) {
+            format!("{}:{}-{}", source_location, start, end)
         } else {
-            Some(Filter::must(must_conditions))
+            source_location.clone()
+        };
+
+        let source = Source {
+            source_type,
+            location: source_location,
+            url: source_url,
+        };
+
+        let updated_at = ["updated_at", "created_at"].iter().find_map(|field| {
+            payload.get(*field).and_then(|v| match &v.kind {
+                Some(qdrant_client::qdrant::value::Kind::StringValue(s)) => parse_timestamp(s),
+                _ => None,
+            })
+        });
+
+        Ok(SearchResult {
+            chunk_id,
+            document_id,
+            chunk_index,
+            score: point.score,
+            content,
+            source,
+            tags,
+            location,
+            line_start,
+            line_end,
+            attributes,
+            updated_at,
+            context_before: Vec::new(),
+            context_after: Vec::new(),
+            explanation: None,
+            keyword_match: false,
+        })
+    }
+
+    fn named_vector(vectors: Option<&VectorsOutput>, name: &str) -> Option<Vec<f32>> {
+        vectors
+            .and_then(|v| v.get_vector_by_name(name))
+            .and_then(|v| match v {
+                qdrant_client::qdrant::vector_output::Vector::Dense(dense) => Some(dense.data),
+                _ => None,
+            })
+    }
+
+    fn dense_vector(point: &RetrievedPoint) -> Vec<f32> {
+        Self::named_vector(point.vectors.as_ref(), "").unwrap_or_default()
+    }
+
+    fn point_to_chunk(&self, point: RetrievedPoint) -> Result<DocumentChunk, VectorStoreError> {
+        let dense_vector = Self::dense_vector(&point);
+        let title_vector =
+            Self::named_vector(point.vectors.as_ref(), TITLE_VECTOR_NAME).unwrap_or_default();
+
+        let id = point.id.as_ref().map(point_id_string).unwrap_or_default();
+        let payload = point.payload;
+        let get_str = |key: &str| {
+            payload.get(key).and_then(|v| match &v.kind {
+                Some(qdrant_client::qdrant::value::Kind::StringValue(s)) => Some(s.clone()),
+                _ => None,
+            })
+        };
+        let get_int = |key: &str| {
+            payload.get(key).and_then(|v| match &v.kind {
+                Some(qdrant_client::qdrant::value::Kind::IntegerValue(n)) => Some(*n as u32),
+                _ => None,
+            })
+        };
+        let get_list = |key: &str| -> Vec<String> {
+            payload
+                .get(key)
+                .and_then(|v| match &v.kind {
+                    Some(qdrant_client::qdrant::value::Kind::ListValue(list)) => Some(
+                        list.values
+                            .iter()
+                            .filter_map(|v| match &v.kind {
+                                Some(qdrant_client::qdrant::value::Kind::StringValue(s)) => {
+                                    Some(s.clone())
+                                }
+                                _ => None,
+                            })
+                            .collect(),
+                    ),
+                    _ => None,
+                })
+                .unwrap_or_default()
+        };
+
+        let source_type: SourceType = get_str("source_type")
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(SourceType::Local);
+        let content = open_content(
+            self.cipher.as_ref(),
+            &id,
+            get_str("content").unwrap_or_default(),
+        )?;
+
+        Ok(DocumentChunk {
+            id,
+            document_id: get_str("document_id").unwrap_or_default(),
+            content,
+            chunk_index: get_int("chunk_index").unwrap_or(0),
+            total_chunks: 0,
+            start_offset: 0,
+            end_offset: 0,
+            line_start: get_int("line_start"),
+            line_end: get_int("line_end"),
+            dense_vector,
+            title_vector,
+            source: Source {
+                source_type,
+                location: get_str("source_location").unwrap_or_default(),
+                url: get_str("source_url"),
+            },
+            tags: get_list("tags")
+                .iter()
+                .filter_map(|s| s.parse().ok())
+                .collect(),
+            attributes: get_list("attributes")
+                .iter()
+                .filter_map(|s| parse_attribute_payload(s))
+                .collect(),
+            checksum: get_str("checksum").unwrap_or_default(),
+            created_at: get_str("created_at").unwrap_or_default(),
+            updated_at: get_str("updated_at").unwrap_or_default(),
+            deleted_at: get_str("deleted_at"),
+        })
+    }
+}
+
+/// The conditions of a [`SearchFilter`] that Qdrant cannot evaluate:
+/// path globs, project-relative path globs and symbol substrings.
+struct ClientSideMatcher<'a> {
+    filter: &'a SearchFilter,
+    paths: Vec<Regex>,
+    relative_paths: Vec<Regex>,
+}
+
+impl<'a> ClientSideMatcher<'a> {
+    fn new(filter: &'a SearchFilter) -> Self {
+        let compile = |regexes: Vec<String>| -> Vec<Regex> {
+            regexes.iter().filter_map(|r| Regex::new(r).ok()).collect()
+        };
+        Self {
+            filter,
+            paths: compile(filter.paths.iter().map(|p| p.to_regex()).collect()),
+            relative_paths: compile(
+                filter
+                    .relative_paths
+                    .iter()
+                    .map(|p| p.to_relative_regex())
+                    .collect(),
+            ),
         }
     }
+
+    fn matches(&self, location: &str, attributes: &BTreeMap<String, String>) -> bool {
+        let relative_path = attributes
+            .get(RELATIVE_PATH_ATTRIBUTE)
+            .map(|path| attribute_payload(RELATIVE_PATH_ATTRIBUTE, path));
+        self.paths.iter().all(|p| p.is_match(location))
+            && self.relative_paths.iter().all(|p| {
+                relative_path
+                    .as_deref()
+                    .is_some_and(|path| p.is_match(path))
+            })
+            && self.filter.matches_symbol(attributes)
+    }
+}
+
+fn point_id_string(id: &PointId) -> String {
+    match &id.point_id_options {
+        Some(qdrant_client::qdrant::point_id::PointIdOptions::Uuid(uuid)) => uuid.clone(),
+        Some(qdrant_client::qdrant::point_id::PointIdOptions::Num(num)) => num.to_string(),
+        None => String::new(),
+    }
+}
+
+/// Split `points` into batches of at most `batch_size`, returning the final
+/// batch (sent last, as the barrier) apart from the ones before it.
+fn upsert_batches<T>(points: Vec<T>, batch_size: usize) -> (Vec<Vec<T>>, Vec<T>) {
+    let mut batches = Vec::new();
+    let mut points = points.into_iter().peekable();
+    while points.peek().is_some() {
+        batches.push(points.by_ref().take(batch_size).collect::<Vec<_>>());
+    }
+    let last = batches.pop().unwrap_or_default();
+    (batches, last)
+}
+
+/// Size of the collection's unnamed dense vector.
+fn dense_dimension(info: &qdrant_client::qdrant::CollectionInfo) -> Option<u64> {
+    use qdrant_client::qdrant::vectors_config::Config as VectorsConfig;
+
+    let vectors = info
+        .config
+        .as_ref()?
+        .params
+        .as_ref()?
+        .vectors_config
+        .as_ref()?;
+    match vectors.config.as_ref()? {
+        VectorsConfig::Params(params) => Some(params.size),
+        VectorsConfig::ParamsMap(map) => map.map.get("").map(|params| params.size),
+    }
 }
 
 #[async_trait]
@@ -81,7 +988,11 @@ impl VectorStore for QdrantBackend {
     async fn get_collection_info(&self) -> Result<Option<CollectionInfo>, VectorStoreError> {
         match self.client.collection_info(&self.collection).await {
             Ok(info) => Ok(Some(CollectionInfo {
-                points_count: info.result.map_or(0, |r| r.points_count.unwrap_or(0)),
+                points_count: info
+                    .result
+                    .as_ref()
+                    .map_or(0, |r| r.points_count.unwrap_or(0)),
+                dimension: info.result.as_ref().and_then(dense_dimension),
             })),
             Err(e) => {
                 let msg = e.to_string();
@@ -99,15 +1010,78 @@ impl VectorStore for QdrantBackend {
             return Ok(());
         }
 
-        let create_collection = CreateCollectionBuilder::new(&self.collection).vectors_config(
-            VectorParamsBuilder::new(self.embedding_dim, Distance::Cosine),
+        let mut sparse_config = SparseVectorsConfigBuilder::default();
+        sparse_config.add_named_vector_params(
+            SPARSE_VECTOR_NAME,
+            SparseVectorParamsBuilder::default().modifier(Modifier::Idf as i32),
         );
 
+        let mut vector_params = VectorParamsBuilder::new(self.embedding_dim, Distance::Cosine);
+        let mut create_collection = CreateCollectionBuilder::new(&self.collection);
+        match self.quantization {
+            Quantization::None => {}
+            Quantization::Int8 => {
+                create_collection = create_collection.quantization_config(
+                    ScalarQuantizationBuilder::default()
+                        .quantile(0.99)
+                        .memory(Memory::Pinned),
+                );
+            }
+            Quantization::Binary => {
+                create_collection = create_collection.quantization_config(
+                    BinaryQuantizationBuilder::default().memory(Memory::Pinned),
+                );
+            }
+        }
+        // Quantized vectors serve the search from RAM; originals are only read to rescore
+        if self.quantization != Quantization::None {
+            vector_params = vector_params.on_disk(true);
+        }
+        // Titles are searched with the same model, so they share the body's parameters
+        let vectors_config: VectorsConfig = if self.title_vectors {
+            let mut named = VectorsConfigBuilder::default();
+            named
+                .add_vector_params(vector_params.clone())
+                .add_named_vector_params(TITLE_VECTOR_NAME, vector_params);
+            named.into()
+        } else {
+            vector_params.into()
+        };
+        let create_collection = create_collection
+            .vectors_config(vectors_config)
+            .sparse_vectors_config(sparse_config);
+
         self.client
             .create_collection(create_collection)
             .await
             .map_err(|e| VectorStoreError::CollectionError(e.to_string()))?;
 
+        // Index timestamps so date range and soft delete filters don't scan
+        // every payload, content hashes for duplicate lookups at upsert time,
+        // and chunk positions for context expansion
+        let field_indexes = [
+            (TimeField::CreatedAt.as_str(), FieldType::Datetime),
+            (TimeField::UpdatedAt.as_str(), FieldType::Datetime),
+            ("deleted_at", FieldType::Datetime),
+            ("content_hash", FieldType::Keyword),
+            ("document_id", FieldType::Keyword),
+            ("chunk_index", FieldType::Integer),
+        ];
+        for (field, field_type) in field_indexes {
+            self.client
+                .create_field_index(CreateFieldIndexCollectionBuilder::new(
+                    &self.collection,
+                    field,
+                    field_type,
+                ))
+                .await
+                .map_err(|e| VectorStoreError::CollectionError(e.to_string()))?;
+        }
+
+        self.set_vector_layout(Some(VectorLayout {
+            sparse: true,
+            title: self.title_vectors,
+        }));
         Ok(())
     }
 
@@ -116,49 +1090,33 @@ impl VectorStore for QdrantBackend {
             return Ok(());
         }
 
+        let layout = self.vector_layout().await?;
+        let indexed_at = chrono::Utc::now().to_rfc3339();
+
         let points: Vec<PointStruct> = chunks
             .into_iter()
-            .map(|chunk| {
-                let mut payload: HashMap<String, qdrant_client::qdrant::Value> = HashMap::new();
-                payload.insert("document_id".to_string(), chunk.document_id.into());
-                payload.insert(
-                    "chunk_index".to_string(),
-                    i64::from(chunk.chunk_index).into(),
-                );
-                payload.insert("content".to_string(), chunk.content.into());
-                payload.insert(
-                    "source_type".to_string(),
-                    chunk.source.source_type.to_string().into(),
-                );
-                payload.insert("source_location".to_string(), chunk.source.location.into());
-                if let Some(url) = chunk.source.url {
-                    payload.insert("source_url".to_string(), url.into());
-                }
-                payload.insert("checksum".to_string(), chunk.checksum.into());
-                payload.insert("created_at".to_string(), chunk.created_at.into());
-
-                let tag_strings: Vec<qdrant_client::qdrant::Value> = chunk
-                    .tags
-                    .into_iter()
-                    .map(|t| t.to_payload_string().into())
-                    .collect();
-                payload.insert("tags".to_string(), tag_strings.into());
-
-                if let Some(line_start) = chunk.line_start {
-                    payload.insert("line_start".to_string(), i64::from(line_start).into());
-                }
-                if let Some(line_end) = chunk.line_end {
-                    payload.insert("line_end".to_string(), i64::from(line_end).into());
-                }
+            .map(|chunk| self.chunk_point(chunk, layout, &indexed_at))
+            .collect::<Result<_, VectorStoreError>>()?;
 
-                PointStruct::new(chunk.id, chunk.dense_vector, payload)
-            })
-            .collect();
+        let (batches, last) = upsert_batches(points, self.upsert_batch_size);
 
-        let upsert = UpsertPointsBuilder::new(&self.collection, points);
+        let mut in_flight = JoinSet::new();
+        for batch in batches {
+            if in_flight.len() >= self.upsert_parallelism {
+                Self::finish_upsert(in_flight.join_next().await)?;
+            }
+            let client = self.client.clone();
+            let upsert = UpsertPointsBuilder::new(&self.collection, batch).wait(self.upsert_wait);
+            in_flight.spawn(async move { client.upsert_points(upsert).await });
+        }
+        while let Some(result) = in_flight.join_next().await {
+            Self::finish_upsert(Some(result))?;
+        }
 
+        // Updates are applied in order, so waiting on the final batch also
+        // waits for every acknowledged batch before it
         self.client
-            .upsert_points(upsert)
+            .upsert_points(UpsertPointsBuilder::new(&self.collection, last).wait(true))
             .await
             .map_err(|e| VectorStoreError::UpsertError(e.to_string()))?;
 
@@ -169,142 +1127,64 @@ impl VectorStore for QdrantBackend {
         &self,
         query_vector: Vec<f32>,
         limit: u64,
-        tags: &[Tag],
-        source_types: &[SourceType],
+        offset: u64,
+        filter: &SearchFilter,
         min_score: Option<f32>,
     ) -> Result<Vec<SearchResult>, VectorStoreError> {
-        let filter = Self::build_search_filter(tags, source_types);
+        if self.title_weight > 0.0 && self.vector_layout().await?.title {
+            return self
+                .title_weighted_search(query_vector, limit, offset, filter, min_score)
+                .await;
+        }
 
-        let mut search_builder =
-            SearchPointsBuilder::new(&self.collection, query_vector, limit).with_payload(true);
+        if !Self::filters_client_side(filter) {
+            return Ok(self
+                .dense_page(&query_vector, offset, limit, filter, min_score)
+                .await?
+                .0);
+        }
 
-        if let Some(f) = filter {
-            search_builder = search_builder.filter(f);
+        // Path and symbol filters are applied client-side, so their pages are cut after filtering
+        let wanted = limit.saturating_add(offset);
+        let results = Self::fill_client_side(filter, wanted, |offset, limit| {
+            self.dense_page(&query_vector, offset, limit, filter, min_score)
+        })
+        .await?;
+        Ok(results.into_iter().skip(offset as usize).collect())
+    }
+
+    async fn keyword_search(
+        &self,
+        query_text: &str,
+        limit: u64,
+        filter: &SearchFilter,
+    ) -> Result<Vec<SearchResult>, VectorStoreError> {
+        // Encrypted chunks carry no keyword index, so hybrid search is dense-only
+        if self.cipher.is_some() {
+            return Ok(Vec::new());
+        }
+        if !self.sparse_enabled().await? {
+            return Err(VectorStoreError::SearchError(format!(
+                "collection '{}' has no keyword index; run 'ssearch index clear' and re-index to enable hybrid search",
+                self.collection
+            )));
         }
 
-        if let Some(score) = min_score {
-            search_builder = search_builder.score_threshold(score);
+        let (indices, values) = sparse_vector(query_text);
+        if indices.is_empty() {
+            return Ok(Vec::new());
         }
 
-        let results = self
-            .client
-            .search_points(search_builder)
-            .await
-            .map_err(|e| VectorStoreError::SearchError(e.to_string()))?;
-
-        let search_results: Vec<SearchResult> = results
-            .result
-            .into_iter()
-            .map(|point| {
-                let payload = point.payload;
-
-                let content = payload
-                    .get("content")
-                    .and_then(|v| match &v.kind {
-                        Some(qdrant_client::qdrant::value::Kind::StringValue(s)) => {
-                            Some(s.as_str())
-                        }
-                        _ => None,
-                    })
-                    .unwrap_or("")
-                    .to_string();
-
-                let source_type_str = payload
-                    .get("source_type")
-                    .and_then(|v| match &v.kind {
-                        Some(qdrant_client::qdrant::value::Kind::StringValue(s)) => {
-                            Some(s.as_str())
-                        }
-                        _ => None,
-                    })
-                    .unwrap_or("local");
-                let source_type: SourceType = source_type_str.parse().unwrap_or(SourceType::Local);
-
-                let source_location = payload
-                    .get("source_location")
-                    .and_then(|v| match &v.kind {
-                        Some(qdrant_client::qdrant::value::Kind::StringValue(s)) => {
-                            Some(s.as_str())
-                        }
-                        _ => None,
-                    })
-                    .unwrap_or("")
-                    .to_string();
-
-                let source_url = payload.get("source_url").and_then(|v| match &v.kind {
-                    Some(qdrant_client::qdrant::value::Kind::StringValue(s)) => Some(s.clone()),
-                    _ => None,
-                });
-
-                let tags: Vec<Tag> = payload
-                    .get("tags")
-                    .and_then(|v| match &v.kind {
-                        Some(qdrant_client::qdrant::value::Kind::ListValue(list)) => Some(
-                            list.values
-                                .iter()
-                                .filter_map(|v| match &v.kind {
-                                    Some(qdrant_client::qdrant::value::Kind::StringValue(s)) => {
-                                        s.parse().ok()
-                                    }
-                                    _ => None,
-                                })
-                                .collect(),
-                        ),
-                        _ => None,
-                    })
-                    .unwrap_or_default();
-
-                let line_start = payload.get("line_start").and_then(|v| match &v.kind {
-                    Some(qdrant_client::qdrant::value::Kind::IntegerValue(n)) => Some(*n as u32),
-                    _ => None,
-                });
-
-                let line_end = payload.get("line_end").and_then(|v| match &v.kind {
-                    Some(qdrant_client::qdrant::value::Kind::IntegerValue(n)) => Some(*n as u32),
-                    _ => None,
-                });
-
-                let location = if let Some(url) = &source_url {
-                    url.clone()
-                } else if let (Some(start), Some(end)) = (line_start, line_end) {
-                    format!("{}:{}-{}", source_location, start, end)
-                } else {
-                    source_location.clone()
-                };
-
-                let source = Source {
-                    source_type,
-                    location: source_location,
-                    url: source_url,
-                };
-
-                let chunk_id = match &point.id {
-                    Some(id) => match &id.point_id_options {
-                        Some(qdrant_client::qdrant::point_id::PointIdOptions::Uuid(uuid)) => {
-                            uuid.clone()
-                        }
-                        Some(qdrant_client::qdrant::point_id::PointIdOptions::Num(num)) => {
-                            num.to_string()
-                        }
-                        None => String::new(),
-                    },
-                    None => String::new(),
-                };
-
-                SearchResult {
-                    chunk_id,
-                    score: point.score,
-                    content,
-                    source,
-                    tags,
-                    location,
-                    line_start,
-                    line_end,
-                }
-            })
-            .collect();
-
-        Ok(search_results)
+        if !Self::filters_client_side(filter) {
+            return Ok(self
+                .keyword_page(&indices, &values, 0, limit, filter)
+                .await?
+                .0);
+        }
+        Self::fill_client_side(filter, limit, |offset, limit| {
+            self.keyword_page(&indices, &values, offset, limit, filter)
+        })
+        .await
     }
 
     async fn delete_by_tags(&self, tags: &[Tag]) -> Result<(), VectorStoreError> {
@@ -318,7 +1198,7 @@ impl VectorStore for QdrantBackend {
             .collect();
 
         let filter = Filter::must(filter_conditions);
-        let delete = DeletePointsBuilder::new(&self.collection).points(filter);
+        let delete = DeletePointsBuilder::new(&self.collection).points(self.scoped_all(filter));
 
         self.client
             .delete_points(delete)
@@ -342,7 +1222,7 @@ impl VectorStore for QdrantBackend {
             .collect();
 
         let filter = Filter::should(conditions);
-        let delete = DeletePointsBuilder::new(&self.collection).points(filter);
+        let delete = DeletePointsBuilder::new(&self.collection).points(self.scoped_all(filter));
 
         self.client
             .delete_points(delete)
@@ -357,16 +1237,125 @@ impl VectorStore for QdrantBackend {
             return Ok(());
         }
 
+        // Other tenants' points stay; only this tenant's are removed
+        if let Some(filter) = self.scoped(None) {
+            return self
+                .client
+                .delete_points(DeletePointsBuilder::new(&self.collection).points(filter))
+                .await
+                .map(|_| ())
+                .map_err(|e| VectorStoreError::DeleteError(e.to_string()));
+        }
+
         self.client
             .delete_collection(&self.collection)
             .await
             .map_err(|e| VectorStoreError::DeleteError(e.to_string()))?;
 
+        self.set_vector_layout(None);
         self.create_collection().await?;
 
         Ok(())
     }
 
+    async fn delete_collection(&self) -> Result<(), VectorStoreError> {
+        if self.get_collection_info().await?.is_none() {
+            return Ok(());
+        }
+        // Other tenants share the collection, so only this tenant's points go
+        if !self.tenant_tags.is_empty() {
+            return self.clear_collection().await;
+        }
+
+        self.client
+            .delete_collection(&self.collection)
+            .await
+            .map_err(|e| VectorStoreError::DeleteError(e.to_string()))?;
+
+        self.set_vector_layout(None);
+
+        Ok(())
+    }
+
+    async fn create_snapshot(&self, path: &Path) -> Result<(), VectorStoreError> {
+        self.check_snapshot_tenancy()?;
+        let name = self
+            .client
+            .create_snapshot(self.collection.as_str())
+            .await
+            .map_err(|e| VectorStoreError::SnapshotError(e.to_string()))?
+            .snapshot_description
+            .map(|description| description.name)
+            .ok_or_else(|| {
+                VectorStoreError::SnapshotError("server returned no snapshot".to_string())
+            })?;
+
+        let download = SnapshotDownloadBuilder::new(path, &self.collection)
+            .snapshot_name(name.as_str())
+            .rest_api_uri(self.rest_url.as_str());
+        let downloaded = self.client.download_snapshot(download).await;
+
+        // The downloaded file is the backup; don't leave a copy on the server
+        let _ = self
+            .client
+            .delete_snapshot(DeleteSnapshotRequestBuilder::new(&self.collection, name))
+            .await;
+
+        downloaded.map_err(|e| {
+            VectorStoreError::SnapshotError(format!("download from {}: {}", self.rest_url, e))
+        })
+    }
+
+    async fn restore_snapshot(&self, path: &Path) -> Result<(), VectorStoreError> {
+        self.check_snapshot_tenancy()?;
+        let url = format!(
+            "{}/collections/{}/snapshots/upload?priority=snapshot&wait=true",
+            self.rest_url, self.collection
+        );
+
+        let mut child = tokio::process::Command::new("curl")
+            .args(["-sS", "--fail-with-body", "-X", "POST", "-H", "@-"])
+            .arg("-F")
+            .arg(format!("snapshot=@{}", path.display()))
+            .arg(&url)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| VectorStoreError::SnapshotError(format!("failed to run curl: {e}")))?;
+
+        // Pass the key on stdin so it does not appear in the process list
+        if let Some(mut stdin) = child.stdin.take() {
+            let headers = self
+                .api_key
+                .as_ref()
+                .map(|key| format!("api-key: {key}\n"))
+                .unwrap_or_default();
+            stdin
+                .write_all(headers.as_bytes())
+                .await
+                .map_err(|e| VectorStoreError::SnapshotError(e.to_string()))?;
+        }
+        let output = child
+            .wait_with_output()
+            .await
+            .map_err(|e| VectorStoreError::SnapshotError(e.to_string()))?;
+        if !output.status.success() {
+            let detail = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            let detail = if detail.is_empty() {
+                String::from_utf8_lossy(&output.stderr).trim().to_string()
+            } else {
+                detail
+            };
+            return Err(VectorStoreError::SnapshotError(format!("{url}: {detail}")));
+        }
+
+        // The restored collection may differ in whether it has the sparse or title vector
+        self.set_vector_layout(None);
+
+        Ok(())
+    }
+
     async fn delete_by_source_type(&self, source_type: SourceType) -> Result<(), VectorStoreError> {
         let source_type_str = source_type.to_string();
         let source_tag = format!("source:{}", source_type_str);
@@ -375,7 +1364,7 @@ impl VectorStore for QdrantBackend {
             Condition::matches("source_type", source_type_str),
             Condition::matches("tags", source_tag),
         ]);
-        let delete = DeletePointsBuilder::new(&self.collection).points(filter);
+        let delete = DeletePointsBuilder::new(&self.collection).points(self.scoped_all(filter));
 
         self.client
             .delete_points(delete)
@@ -385,6 +1374,101 @@ impl VectorStore for QdrantBackend {
         Ok(())
     }
 
+    async fn count_by_filter(&self, filter: &SearchFilter) -> Result<u64, VectorStoreError> {
+        if self.get_collection_info().await?.is_none() {
+            return Ok(0);
+        }
+        if Self::filters_client_side(filter) {
+            return Ok(self.matching_point_ids(filter).await?.len() as u64);
+        }
+
+        let count_builder = CountPointsBuilder::new(&self.collection)
+            .exact(true)
+            .filter(self.scoped_all(Self::build_search_filter(filter)));
+        let response = self
+            .client
+            .count(count_builder)
+            .await
+            .map_err(|e| VectorStoreError::SearchError(e.to_string()))?;
+        Ok(response.result.map_or(0, |r| r.count))
+    }
+
+    async fn delete_by_filter(&self, filter: &SearchFilter) -> Result<u64, VectorStoreError> {
+        if self.get_collection_info().await?.is_none() {
+            return Ok(0);
+        }
+        if Self::filters_client_side(filter) {
+            let ids = self.matching_point_ids(filter).await?;
+            for batch in ids.chunks(1000) {
+                self.client
+                    .delete_points(
+                        DeletePointsBuilder::new(&self.collection).points(batch.to_vec()),
+                    )
+                    .await
+                    .map_err(|e| VectorStoreError::DeleteError(e.to_string()))?;
+            }
+            return Ok(ids.len() as u64);
+        }
+
+        let count = self.count_by_filter(filter).await?;
+        let delete = DeletePointsBuilder::new(&self.collection)
+            .points(self.scoped_all(Self::build_search_filter(filter)));
+        self.client
+            .delete_points(delete)
+            .await
+            .map_err(|e| VectorStoreError::DeleteError(e.to_string()))?;
+        Ok(count)
+    }
+
+    async fn soft_delete_by_filter(
+        &self,
+        filter: &SearchFilter,
+        deleted_at: DateTime<Utc>,
+    ) -> Result<u64, VectorStoreError> {
+        if self.get_collection_info().await?.is_none() {
+            return Ok(0);
+        }
+        let (points, count) = self
+            .select_points(&filter.clone().with_deleted(Deleted::Live))
+            .await?;
+        if count == 0 {
+            return Ok(0);
+        }
+
+        let payload = HashMap::from([("deleted_at".to_string(), deleted_at.to_rfc3339().into())]);
+        self.client
+            .set_payload(
+                SetPayloadPointsBuilder::new(&self.collection, payload)
+                    .points_selector(points)
+                    .wait(true),
+            )
+            .await
+            .map_err(|e| VectorStoreError::DeleteError(e.to_string()))?;
+        Ok(count)
+    }
+
+    async fn restore_by_filter(&self, filter: &SearchFilter) -> Result<u64, VectorStoreError> {
+        if self.get_collection_info().await?.is_none() {
+            return Ok(0);
+        }
+        let (points, count) = self
+            .select_points(&filter.clone().with_deleted(Deleted::Only))
+            .await?;
+        if count == 0 {
+            return Ok(0);
+        }
+
+        self.client
+            .delete_payload(
+                DeletePayloadPointsBuilder::new(&self.collection, vec!["deleted_at".to_string()])
+                    .points_selector(points)
+                    .wait(true),
+            )
+            .await
+            .map_err(|e| VectorStoreError::UpsertError(e.to_string()))?;
+        Ok(count)
+    }
+
     async fn list_all_tags(&self) -> Result<Vec<(String, u64)>, VectorStoreError> {
         let mut tag_counts: HashMap<String, u64> = HashMap::new();
         let mut offset: Option<qdrant_client::qdrant::PointId> = None;
@@ -396,7 +1480,8 @@ impl VectorStore for QdrantBackend {
                 .with_payload(PayloadIncludeSelector {
                     fields: vec!["tags".to_string()],
                 })
-                .with_vectors(false);
+                .with_vectors(false)
+                .filter(self.live_points());
 
             if let Some(off) = offset {
                 scroll_builder = scroll_builder.offset(off);
@@ -440,7 +1525,515 @@ impl VectorStore for QdrantBackend {
         Ok(tags)
     }
 
+    async fn list_documents(
+        &self,
+        source_type: &SourceType,
+    ) -> Result<Vec<IndexedDocument>, VectorStoreError> {
+        let mut documents: HashMap<String, IndexedDocument> = HashMap::new();
+        let mut offset: Option<qdrant_client::qdrant::PointId> = None;
+        let batch_size = 100u32;
+        let filter = self.scoped_all(Filter::must([Condition::matches(
+            "source_type",
+            source_type.to_string(),
+        )]));
+
+        loop {
+            let mut scroll_builder = ScrollPointsBuilder::new(&self.collection)
+                .filter(filter.clone())
+                .limit(batch_size)
+                .with_payload(PayloadIncludeSelector {
+                    fields: vec![
+                        "document_id".to_string(),
+                        "source_location".to_string(),
+                        "checksum".to_string(),
+                        "attributes".to_string(),
+                    ],
+                })
+                .with_vectors(false);
+
+            if let Some(off) = offset {
+                scroll_builder = scroll_builder.offset(off);
+            }
+
+            let response = self
+                .client
+                .scroll(scroll_builder)
+                .await
+                .map_err(|e| VectorStoreError::SearchError(e.to_string()))?;
+
+            let points = response.result;
+            if points.is_empty() {
+                break;
+            }
+
+            for point in &points {
+                let get_str = |key: &str| {
+                    point.payload.get(key).and_then(|v| match &v.kind {
+                        Some(qdrant_client::qdrant::value::Kind::StringValue(s)) => Some(s.clone()),
+                        _ => None,
+                    })
+                };
+                let Some(document_id) = get_str("document_id") else {
+                    continue;
+                };
+                let workspace = || match point
+                    .payload
+                    .get("attributes")
+                    .and_then(|v| v.kind.as_ref())
+                {
+                    Some(qdrant_client::qdrant::value::Kind::ListValue(list)) => {
+                        list.values.iter().find_map(|v| match &v.kind {
+                            Some(qdrant_client::qdrant::value::Kind::StringValue(s)) => {
+                                parse_attribute_payload(s)
+                                    .filter(|(key, _)| key == WORKSPACE_ATTRIBUTE)
+                                    .map(|(_, value)| value)
+                            }
+                            _ => None,
+                        })
+                    }
+                    _ => None,
+                };
+                documents
+                    .entry(document_id.clone())
+                    .or_insert_with(|| IndexedDocument {
+                        document_id,
+                        location: get_str("source_location").unwrap_or_default(),
+                        checksum: get_str("checksum").unwrap_or_default(),
+                        chunk_count: 0,
+                        workspace: workspace(),
+                    })
+                    .chunk_count += 1;
+            }
+
+            offset = response.next_page_offset;
+            if offset.is_none() {
+                break;
+            }
+        }
+
+        let mut documents: Vec<IndexedDocument> = documents.into_values().collect();
+        documents.sort_by(|a, b| a.location.cmp(&b.location));
+        Ok(documents)
+    }
+
+    fn stored_checksum(&self, checksum: &str) -> String {
+        match self.cipher {
+            Some(ref cipher) => cipher.keyed_checksum(checksum),
+            None => checksum.to_string(),
+        }
+    }
+
+    async fn find_content_hashes(
+        &self,
+        hashes: &[String],
+    ) -> Result<Vec<(String, String)>, VectorStoreError> {
+        if hashes.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Stored (keyed) hash → the hash asked for
+        let wanted: HashMap<String, &String> = hashes
+            .iter()
+            .map(|hash| (self.stored_hash(hash.clone()), hash))
+            .collect();
+        let filter = self.scoped_all(Filter::must([Condition::matches(
+            "content_hash",
+            wanted.keys().cloned().collect::<Vec<_>>(),
+        )]));
+        let mut found = Vec::new();
+        let mut offset: Option<PointId> = None;
+
+        loop {
+            let mut scroll_builder = ScrollPointsBuilder::new(&self.collection)
+                .filter(filter.clone())
+                .limit(100)
+                .with_payload(PayloadIncludeSelector {
+                    fields: vec!["content_hash".to_string(), "document_id".to_string()],
+                })
+                .with_vectors(false);
+
+            if let Some(off) = offset {
+                scroll_builder = scroll_builder.offset(off);
+            }
+
+            let response = self
+                .client
+                .scroll(scroll_builder)
+                .await
+                .map_err(|e| VectorStoreError::SearchError(e.to_string()))?;
+
+            for point in &response.result {
+                let get_str = |key: &str| {
+                    point.payload.get(key).and_then(|v| match &v.kind {
+                        Some(qdrant_client::qdrant::value::Kind::StringValue(s)) => Some(s.clone()),
+                        _ => None,
+                    })
+                };
+                if let (Some(hash), Some(document_id)) =
+                    (get_str("content_hash"), get_str("document_id"))
+                    && let Some(hash) = wanted.get(&hash)
+                {
+                    found.push(((*hash).clone(), document_id));
+                }
+            }
+
+            offset = response.next_page_offset;
+            if offset.is_none() {
+                break;
+            }
+        }
+
+        Ok(found)
+    }
+
+    async fn get_vectors(
+        &self,
+        chunk_ids: &[String],
+    ) -> Result<HashMap<String, Vec<f32>>, VectorStoreError> {
+        if chunk_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let ids: Vec<PointId> = chunk_ids
+            .iter()
+            .map(|id| PointId::from(id.clone()))
+            .collect();
+        let response = self
+            .client
+            .get_points(
+                GetPointsBuilder::new(&self.collection, ids)
+                    .with_vectors(true)
+                    .with_payload(false),
+            )
+            .await
+            .map_err(|e| VectorStoreError::SearchError(e.to_string()))?;
+
+        Ok(response
+            .result
+            .iter()
+            .filter_map(|point| {
+                let id = point.id.as_ref().map(point_id_string)?;
+                Some((id, Self::dense_vector(point)))
+            })
+            .collect())
+    }
+
+    async fn inventory(&self) -> Result<IndexInventory, VectorStoreError> {
+        let mut builder = InventoryBuilder::default();
+        let mut content_bytes = 0u64;
+        let mut chunk_count = 0u64;
+        let mut offset: Option<PointId> = None;
+        let batch_size = 100u32;
+
+        loop {
+            let mut scroll_builder = ScrollPointsBuilder::new(&self.collection)
+                .limit(batch_size)
+                .with_payload(PayloadIncludeSelector {
+                    fields: vec![
+                        "document_id".to_string(),
+                        "source_type".to_string(),
+                        "tags".to_string(),
+                        "content".to_string(),
+                        "indexed_at".to_string(),
+                        "updated_at".to_string(),
+                    ],
+                })
+                .with_vectors(false)
+                .filter(self.live_points());
+
+            if let Some(off) = offset {
+                scroll_builder = scroll_builder.offset(off);
+            }
+
+            let response = self
+                .client
+                .scroll(scroll_builder)
+                .await
+                .map_err(|e| VectorStoreError::SearchError(e.to_string()))?;
+
+            let points = response.result;
+            if points.is_empty() {
+                break;
+            }
+
+            for point in &points {
+                let get_str = |key: &str| {
+                    point.payload.get(key).and_then(|v| match &v.kind {
+                        Some(qdrant_client::qdrant::value::Kind::StringValue(s)) => {
+                            Some(s.as_str())
+                        }
+                        _ => None,
+                    })
+                };
+                let tags: Vec<String> =
+                    match point.payload.get("tags").and_then(|v| v.kind.as_ref()) {
+                        Some(qdrant_client::qdrant::value::Kind::ListValue(list)) => list
+                            .values
+                            .iter()
+                            .filter_map(|v| match &v.kind {
+                                Some(qdrant_client::qdrant::value::Kind::StringValue(s)) => {
+                                    Some(s.clone())
+                                }
+                                _ => None,
+                            })
+                            .collect(),
+                        _ => Vec::new(),
+                    };
+                // Points written before indexed_at was stored fall back to updated_at
+                let indexed_at = get_str("indexed_at")
+                    .or_else(|| get_str("updated_at"))
+                    .and_then(parse_timestamp);
+
+                builder.add_chunk(
+                    get_str("source_type").unwrap_or_default(),
+                    get_str("document_id").unwrap_or_default(),
+                    &tags,
+                    indexed_at,
+                );
+                content_bytes += get_str("content").map_or(0, |c| c.len() as u64);
+                chunk_count += 1;
+            }
+
+            offset = response.next_page_offset;
+            if offset.is_none() {
+                break;
+            }
+        }
+
+        // Payload text plus the f32 dense vectors; index overhead is not counted
+        let vector_bytes = chunk_count * self.embedding_dim * 4;
+        Ok(builder.build(content_bytes + vector_bytes))
+    }
+
+    async fn get_adjacent_chunks(
+        &self,
+        document_id: &str,
+        chunk_index: u32,
+        radius: u32,
+    ) -> Result<Vec<DocumentChunk>, VectorStoreError> {
+        let lower = chunk_index.saturating_sub(radius);
+        let upper = chunk_index.saturating_add(radius);
+        let filter = Filter::must([
+            Condition::matches("document_id", document_id.to_string()),
+            Condition::range(
+                "chunk_index",
+                Range {
+                    gte: Some(f64::from(lower)),
+                    lte: Some(f64::from(upper)),
+                    ..Default::default()
+                },
+            ),
+        ]);
+
+        let response = self
+            .client
+            .scroll(
+                ScrollPointsBuilder::new(&self.collection)
+                    .filter(self.scoped_all(filter))
+                    .limit(upper - lower + 1)
+                    .with_payload(true)
+                    .with_vectors(false),
+            )
+            .await
+            .map_err(|e| VectorStoreError::SearchError(e.to_string()))?;
+
+        let mut chunks: Vec<DocumentChunk> = response
+            .result
+            .into_iter()
+            .map(|point| self.point_to_chunk(point))
+            .collect::<Result<Vec<_>, _>>()?;
+        chunks.retain(|chunk| chunk.chunk_index != chunk_index);
+        chunks.sort_by_key(|chunk| chunk.chunk_index);
+        Ok(chunks)
+    }
+
+    async fn scroll_by_document(
+        &self,
+        document_id: &str,
+    ) -> Result<Vec<DocumentChunk>, VectorStoreError> {
+        let filter = self.scoped_all(Filter::must([Condition::matches(
+            "document_id",
+            document_id.to_string(),
+        )]));
+        let mut chunks = Vec::new();
+        let mut offset: Option<PointId> = None;
+
+        loop {
+            let mut scroll_builder = ScrollPointsBuilder::new(&self.collection)
+                .filter(filter.clone())
+                .limit(100)
+                .with_payload(true)
+                .with_vectors(false);
+
+            if let Some(off) = offset {
+                scroll_builder = scroll_builder.offset(off);
+            }
+
+            let response = self
+                .client
+                .scroll(scroll_builder)
+                .await
+                .map_err(|e| VectorStoreError::SearchError(e.to_string()))?;
+
+            for point in response.result {
+                chunks.push(self.point_to_chunk(point)?);
+            }
+
+            offset = response.next_page_offset;
+            if offset.is_none() {
+                break;
+            }
+        }
+
+        chunks.sort_by_key(|chunk| chunk.chunk_index);
+        Ok(chunks)
+    }
+
+    async fn scroll_chunks(
+        &self,
+        offset: Option<String>,
+        limit: u32,
+    ) -> Result<ChunkPage, VectorStoreError> {
+        let mut scroll_builder = ScrollPointsBuilder::new(&self.collection)
+            .limit(limit)
+            .with_payload(true)
+            .with_vectors(true);
+        if let Some(filter) = self.scoped(None) {
+            scroll_builder = scroll_builder.filter(filter);
+        }
+
+        if let Some(off) = offset {
+            let id = match off.parse::<u64>() {
+                Ok(num) => PointId::from(num),
+                Err(_) => PointId::from(off),
+            };
+            scroll_builder = scroll_builder.offset(id);
+        }
+
+        let response = self
+            .client
+            .scroll(scroll_builder)
+            .await
+            .map_err(|e| VectorStoreError::SearchError(e.to_string()))?;
+
+        Ok(ChunkPage {
+            chunks: response
+                .result
+                .into_iter()
+                .map(|point| self.point_to_chunk(point))
+                .collect::<Result<_, _>>()?,
+            next_offset: response.next_page_offset.as_ref().map(point_id_string),
+        })
+    }
+
     fn collection(&self) -> &str {
         &self.collection
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::PathPattern;
+    use crate::testing::{chunks, document, search_result};
+    use base64::Engine;
+
+    #[test]
+    fn test_upsert_batches() {
+        let sizes = |count: u32, batch_size: usize| {
+            let (batches, last) = upsert_batches((0..count).collect(), batch_size);
+            let mut sizes: Vec<usize> = batches.iter().map(Vec::len).collect();
+            sizes.push(last.len());
+            sizes
+        };
+        assert_eq!(sizes(0, 4), vec![0]);
+        assert_eq!(sizes(3, 4), vec![3]);
+        assert_eq!(sizes(4, 4), vec![4]);
+        assert_eq!(sizes(5, 4), vec![4, 1]);
+        assert_eq!(sizes(8, 4), vec![4, 4]);
+        assert_eq!(sizes(9, 4), vec![4, 4, 1]);
+
+        // Points keep their order, with the final batch last
+        let (batches, last) = upsert_batches((0..5).collect(), 2);
+        assert_eq!(batches, vec![vec![0, 1], vec![2, 3]]);
+        assert_eq!(last, vec![4]);
+    }
+
+    #[test]
+    fn test_encrypted_point_keys_hashes() {
+        let stored_string = |point: &PointStruct, key: &str| match point
+            .payload
+            .get(key)
+            .and_then(|v| v.kind.as_ref())
+        {
+            Some(qdrant_client::qdrant::value::Kind::StringValue(s)) => s.clone(),
+            other => panic!("{key} is not a string: {other:?}"),
+        };
+        let layout = VectorLayout {
+            sparse: false,
+            title: false,
+        };
+        let chunk = chunks(&document("docs/salary.md", "salary review notes")).remove(0);
+        let (checksum, content_hash) = (chunk.checksum.clone(), chunk.content_hash());
+        assert_eq!(checksum.len(), 64);
+
+        let mut config = VectorStoreConfig::default();
+        let plain = QdrantBackend::new(&config, 4).unwrap();
+        let point = plain.chunk_point(chunk.clone(), layout, "now").unwrap();
+        assert_eq!(stored_string(&point, "checksum"), checksum);
+        assert_eq!(stored_string(&point, "content_hash"), content_hash);
+
+        config.encryption_key = Some(base64::engine::general_purpose::STANDARD.encode([7u8; 32]));
+        let sealed = QdrantBackend::new(&config, 4).unwrap();
+        let point = sealed.chunk_point(chunk, layout, "now").unwrap();
+        let stored_checksum = stored_string(&point, "checksum");
+        for value in point.payload.values() {
+            if let Some(qdrant_client::qdrant::value::Kind::StringValue(s)) = &value.kind {
+                assert_ne!(s, &checksum);
+                assert_ne!(s, &content_hash);
+            }
+        }
+        // Incremental indexing compares in the same keyed form
+        assert_eq!(sealed.stored_checksum(&checksum), stored_checksum);
+        assert_eq!(sealed.stored_checksum(&stored_checksum), stored_checksum);
+    }
+
+    #[tokio::test]
+    async fn test_fill_client_side_pages_past_overfetch() {
+        // Only every 20th candidate matches, far sparser than one over-fetched page
+        let candidates: Vec<SearchResult> = (0..100)
+            .map(|i| {
+                let dir = if i % 20 == 0 { "docs" } else { "src" };
+                search_result(&format!("{dir}/{i}.md"), "text", 1.0)
+            })
+            .collect();
+        let fetch = |offset: u64, limit: u64| {
+            let page: Vec<SearchResult> = candidates
+                .iter()
+                .skip(offset as usize)
+                .take(limit as usize)
+                .cloned()
+                .collect();
+            let more = offset + limit < candidates.len() as u64;
+            async move { Ok((page, more)) }
+        };
+        let filter = SearchFilter::new().with_paths(vec![PathPattern::new("docs/*")]);
+        let locations = |results: Vec<SearchResult>| -> Vec<String> {
+            results.into_iter().map(|r| r.location).collect()
+        };
+
+        let results = QdrantBackend::fill_client_side(&filter, 3, fetch)
+            .await
+            .unwrap();
+        assert_eq!(
+            locations(results),
+            ["docs/0.md", "docs/20.md", "docs/40.md"]
+        );
+
+        // Stops at the end of the store with whatever matched
+        let results = QdrantBackend::fill_client_side(&filter, 10, fetch)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 5);
+    }
+}
@@ -0,0 +1,223 @@
+//! Text extraction for downloaded attachments.
+//!
+//! PDFs go through `pdftotext` (poppler-utils). With the `ocr` feature,
+//! images and scanned PDFs without a text layer are run through `tesseract`.
+
+use std::io::ErrorKind;
+use std::path::Path;
+use std::process::Command;
+
+use crate::error::SourceError;
+use crate::utils::has_meaningful_content;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AttachmentKind {
+    Text,
+    Pdf,
+    Image,
+    Unsupported,
+}
+
+impl AttachmentKind {
+    /// Classify by media type, falling back to the file extension.
+    pub(crate) fn detect(media_type: Option<&str>, filename: &str) -> Self {
+        let media_type = media_type.unwrap_or_default().to_ascii_lowercase();
+        match media_type.as_str() {
+            "application/pdf" => return Self::Pdf,
+            "application/json" | "application/xml" => return Self::Text,
+            m if m.starts_with("image/") => return Self::Image,
+            m if m.starts_with("text/") => return Self::Text,
+            _ => {}
+        }
+
+        let extension = Path::new(filename)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_ascii_lowercase)
+            .unwrap_or_default();
+        match extension.as_str() {
+            "pdf" => Self::Pdf,
+            "png" | "jpg" | "jpeg" | "gif" | "bmp" | "tif" | "tiff" | "webp" => Self::Image,
+            "txt" | "md" | "csv" | "json" | "xml" | "yaml" | "yml" | "log" => Self::Text,
+            _ => Self::Unsupported,
+        }
+    }
+
+    /// Whether this build can extract text from the kind, so others are not downloaded.
+    pub(crate) fn is_extractable(self) -> bool {
+        match self {
+            Self::Text | Self::Pdf => true,
+            Self::Image => cfg!(feature = "ocr"),
+            Self::Unsupported => false,
+        }
+    }
+}
+
+/// Extract the text of an attachment file.
+///
+/// Returns `None` for kinds that cannot be read in this build (e.g. images
+/// without the `ocr` feature) or when nothing meaningful was extracted.
+pub(crate) fn extract_text(
+    path: &Path,
+    kind: AttachmentKind,
+) -> Result<Option<String>, SourceError> {
+    let text = match kind {
+        AttachmentKind::Text => {
+            let bytes =
+                std::fs::read(path).map_err(|e| SourceError::ExecutionError(e.to_string()))?;
+            Some(String::from_utf8_lossy(&bytes).into_owned())
+        }
+        AttachmentKind::Pdf => {
+            let text = run_tool(
+                Command::new("pdftotext").arg("-layout").arg(path).arg("-"),
+                "pdftotext",
+            )?;
+            #[cfg(feature = "ocr")]
+            let text = if has_meaningful_content(&text) {
+                text
+            } else {
+                ocr::pdf(path)?
+            };
+            Some(text)
+        }
+        #[cfg(feature = "ocr")]
+        AttachmentKind::Image => Some(ocr::image(path)?),
+        #[cfg(not(feature = "ocr"))]
+        AttachmentKind::Image => None,
+        AttachmentKind::Unsupported => None,
+    };
+
+    Ok(text
+        .map(|t| t.trim().to_string())
+        .filter(|t| has_meaningful_content(t)))
+}
+
+/// Run an extraction tool and return its stdout.
+fn run_tool(command: &mut Command, tool: &str) -> Result<String, SourceError> {
+    let output = command.output().map_err(|e| match e.kind() {
+        ErrorKind::NotFound => SourceError::CliNotFound(format!(
+            "{} not found. Install poppler-utils{}",
+            tool,
+            if cfg!(feature = "ocr") {
+                " and tesseract"
+            } else {
+                ""
+            }
+        )),
+        _ => SourceError::ExecutionError(e.to_string()),
+    })?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(SourceError::ExecutionError(format!(
+            "{} failed: {}",
+            tool,
+            stderr.trim()
+        )));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(feature = "ocr")]
+mod ocr {
+    use std::path::Path;
+    use std::process::Command;
+
+    use super::run_tool;
+    use crate::error::SourceError;
+
+    /// Resolution used when rasterizing PDF pages for OCR
+    const PDF_RENDER_DPI: &str = "300";
+
+    pub(super) fn image(path: &Path) -> Result<String, SourceError> {
+        run_tool(
+            Command::new("tesseract").arg(path).arg("stdout"),
+            "tesseract",
+        )
+    }
+
+    /// Rasterize every page and OCR them in order.
+    pub(super) fn pdf(path: &Path) -> Result<String, SourceError> {
+        let dir = tempfile::tempdir().map_err(|e| SourceError::ExecutionError(e.to_string()))?;
+        let prefix = dir.path().join("page");
+        run_tool(
+            Command::new("pdftoppm")
+                .args(["-r", PDF_RENDER_DPI, "-png"])
+                .arg(path)
+                .arg(&prefix),
+            "pdftoppm",
+        )?;
+
+        let mut pages: Vec<_> = std::fs::read_dir(dir.path())
+            .map_err(|e| SourceError::ExecutionError(e.to_string()))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .collect();
+        // pdftoppm zero-pads page numbers, so name order is page order
+        pages.sort();
+
+        let mut text = String::new();
+        for page in pages {
+            text.push_str(&image(&page)?);
+            text.push_str("\n\n");
+        }
+        Ok(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_kind() {
+        assert_eq!(
+            AttachmentKind::detect(Some("application/pdf"), "spec"),
+            AttachmentKind::Pdf
+        );
+        assert_eq!(
+            AttachmentKind::detect(Some("image/png"), "diagram"),
+            AttachmentKind::Image
+        );
+        assert_eq!(
+            AttachmentKind::detect(Some("text/plain"), "notes"),
+            AttachmentKind::Text
+        );
+        assert_eq!(
+            AttachmentKind::detect(None, "Scan.JPG"),
+            AttachmentKind::Image
+        );
+        assert_eq!(
+            AttachmentKind::detect(Some("application/octet-stream"), "spec.pdf"),
+            AttachmentKind::Pdf
+        );
+        assert_eq!(
+            AttachmentKind::detect(Some("application/zip"), "bundle.zip"),
+            AttachmentKind::Unsupported
+        );
+    }
+
+    #[test]
+    fn test_extract_text_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.txt");
+        std::fs::write(
+            &path,
+            "  Release checklist for the payment gateway migration in March.  \n",
+        )
+        .unwrap();
+        assert_eq!(
+            extract_text(&path, AttachmentKind::Text)
+                .unwrap()
+                .as_deref(),
+            Some("Release checklist for the payment gateway migration in March.")
+        );
+
+        std::fs::write(&path, "tiny").unwrap();
+        assert_eq!(extract_text(&path, AttachmentKind::Text).unwrap(), None);
+        assert_eq!(
+            extract_text(&path, AttachmentKind::Unsupported).unwrap(),
+            None
+        );
+    }
+}
@@ -1,17 +1,29 @@
 use std::collections::HashSet;
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use std::sync::LazyLock;
 
 use regex::Regex;
 use serde::Deserialize;
+use tracing::{debug, info, warn};
 
 use crate::error::SourceError;
-use crate::models::{Document, DocumentMetadata, Source, SourceType, Tag};
+use crate::models::{Document, DocumentMetadata, Source, SourceType, Tag, parse_timestamp};
 use crate::sources::SyncOptions;
-use crate::utils::file::{calculate_checksum, sanitize_filename};
+use crate::sources::attachment::{self, AttachmentKind};
+use crate::utils::file::{calculate_checksum, detect_language, sanitize_filename};
 use crate::utils::has_meaningful_content;
 
+/// Environment variable with the Atlassian account email, used to download attachments
+pub const ATLASSIAN_EMAIL_ENV: &str = "ATLASSIAN_EMAIL";
+
+/// Environment variable with the Atlassian API token, used to download attachments
+pub const ATLASSIAN_API_TOKEN_ENV: &str = "ATLASSIAN_API_TOKEN";
+
+const PAGE_EXPAND: &str = "body.storage,ancestors";
+const PAGE_EXPAND_WITH_ATTACHMENTS: &str = "body.storage,ancestors,children.attachment";
+
 #[derive(Debug, Deserialize)]
 struct ConfluencePage {
     id: String,
@@ -20,6 +32,84 @@ struct ConfluencePage {
     ancestors: Option<Vec<Ancestor>>,
     #[serde(rename = "_links")]
     links: Option<Links>,
+    version: Option<Version>,
+    history: Option<History>,
+    children: Option<Children>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Children {
+    attachment: Option<AttachmentList>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AttachmentList {
+    #[serde(default)]
+    results: Vec<ConfluenceAttachment>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ConfluenceAttachment {
+    id: String,
+    title: String,
+    extensions: Option<MediaInfo>,
+    metadata: Option<MediaInfo>,
+    #[serde(rename = "_links")]
+    links: Option<AttachmentLinks>,
+    version: Option<Version>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MediaInfo {
+    #[serde(rename = "mediaType")]
+    media_type: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AttachmentLinks {
+    download: Option<String>,
+    webui: Option<String>,
+}
+
+impl ConfluenceAttachment {
+    fn media_type(&self) -> Option<&str> {
+        [&self.extensions, &self.metadata]
+            .into_iter()
+            .find_map(|m| m.as_ref().and_then(|m| m.media_type.as_deref()))
+    }
+}
+
+/// The page an attachment document is linked back to.
+#[derive(Debug)]
+struct ParentPage {
+    id: String,
+    path: String,
+    base_url: String,
+}
+
+impl ParentPage {
+    fn of(page: &ConfluencePage) -> Self {
+        Self {
+            id: page.id.clone(),
+            path: build_page_path(page),
+            base_url: page
+                .links
+                .as_ref()
+                .and_then(|l| l.base.clone())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Version {
+    when: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct History {
+    #[serde(rename = "createdDate")]
+    created_date: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -43,7 +133,7 @@ struct Links {
     base: Option<String>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub struct ConfluenceSource;
 
 impl ConfluenceSource {
@@ -94,26 +184,39 @@ impl ConfluenceSource {
             ));
         }
 
+        let downloader = if options.attachments {
+            Some(AttachmentDownloader::from_env()?)
+        } else {
+            None
+        };
+        let downloader = downloader.as_ref();
+
         if let Some(ref space) = options.project {
             let cql = format!("space=\"{}\" AND type=page", space);
-            return self.fetch_pages_streaming(&cql, &options, on_document);
+            return self.fetch_pages_streaming(&cql, &options, downloader, on_document);
         }
 
         let query = options.query.as_deref().unwrap_or("type=page");
 
         if let Some(page_id) = extract_page_id(query) {
+            // `confluence get` cannot expand attachments, so search for the page instead
+            if downloader.is_some() {
+                let cql = format!("id={}", page_id);
+                return self.fetch_pages_streaming(&cql, &options, downloader, on_document);
+            }
             let doc = self.fetch_page(&page_id, &options.tags)?;
             on_document(doc)?;
             return Ok(1);
         }
 
-        self.fetch_pages_streaming(query, &options, on_document)
+        self.fetch_pages_streaming(query, &options, downloader, on_document)
     }
 
     fn fetch_pages_streaming<F>(
         &self,
         cql: &str,
         options: &SyncOptions,
+        downloader: Option<&AttachmentDownloader>,
         mut on_document: F,
     ) -> Result<u64, SourceError>
     where
@@ -122,7 +225,14 @@ impl ConfluenceSource {
         let excluded_ids = self.get_excluded_ids(&options.exclude_ancestors)?;
 
         if let Some(limit) = options.limit {
-            return self.fetch_pages_batch(cql, options, on_document, &excluded_ids, limit);
+            return self.fetch_pages_batch(
+                cql,
+                options,
+                downloader,
+                on_document,
+                &excluded_ids,
+                limit,
+            );
         }
 
         let args = [
@@ -132,12 +242,12 @@ impl ConfluenceSource {
             "--format",
             "markdown",
             "--expand",
-            "body.storage,ancestors",
+            expand(downloader),
             "--all",
             "--stream",
         ];
 
-        eprintln!("Running: atlassian-cli {}", args.join(" "));
+        info!(args = %args.join(" "), "running atlassian-cli");
 
         let mut child = Command::new("atlassian-cli")
             .args(args)
@@ -171,15 +281,15 @@ impl ConfluenceSource {
                 continue;
             }
 
-            match self.page_to_document(page, &options.tags) {
-                Ok(doc) => {
-                    on_document(doc)?;
-                    count += 1;
-                    if count.is_multiple_of(50) {
-                        eprintln!("  Processed {} pages...", count);
+            match self.emit_page(page, &options.tags, downloader, &mut on_document)? {
+                0 => skipped += 1,
+                emitted => {
+                    let before = count;
+                    count += emitted;
+                    if count / 50 > before / 50 {
+                        info!(count, "processed documents");
                     }
                 }
-                Err(_) => skipped += 1,
             }
         }
 
@@ -196,12 +306,12 @@ impl ConfluenceSource {
                 })
                 .unwrap_or_default();
             if !stderr.is_empty() {
-                eprintln!("Warning: {}", stderr.trim());
+                warn!(stderr = %stderr.trim(), "atlassian-cli reported errors");
             }
         }
 
         if skipped > 0 {
-            eprintln!("  Skipped {} pages (excluded or empty)", skipped);
+            info!(skipped, "skipped pages (excluded or empty)");
         }
 
         Ok(count)
@@ -211,6 +321,7 @@ impl ConfluenceSource {
         &self,
         cql: &str,
         options: &SyncOptions,
+        downloader: Option<&AttachmentDownloader>,
         mut on_document: F,
         excluded_ids: &HashSet<String>,
         limit: u32,
@@ -226,12 +337,12 @@ impl ConfluenceSource {
             "--format",
             "markdown",
             "--expand",
-            "body.storage,ancestors",
+            expand(downloader),
             "--limit",
             &limit_str,
         ];
 
-        eprintln!("Running: atlassian-cli {}", args.join(" "));
+        info!(args = %args.join(" "), "running atlassian-cli");
 
         let output = Command::new("atlassian-cli")
             .args(args)
@@ -263,17 +374,14 @@ impl ConfluenceSource {
                 continue;
             }
 
-            match self.page_to_document(page, &options.tags) {
-                Ok(doc) => {
-                    on_document(doc)?;
-                    count += 1;
-                }
-                Err(_) => skipped += 1,
+            match self.emit_page(page, &options.tags, downloader, &mut on_document)? {
+                0 => skipped += 1,
+                emitted => count += emitted,
             }
         }
 
         if skipped > 0 {
-            eprintln!("  Skipped {} pages (excluded or empty)", skipped);
+            info!(skipped, "skipped pages (excluded or empty)");
         }
 
         Ok(count)
@@ -341,12 +449,83 @@ impl ConfluenceSource {
         }
 
         if !excluded.is_empty() {
-            eprintln!("  Excluding {} pages (ancestor filter)", excluded.len());
+            info!(
+                excluded = excluded.len(),
+                "excluding pages (ancestor filter)"
+            );
         }
 
         Ok(excluded)
     }
 
+    /// Emit a page and, with a downloader, its attachments.
+    ///
+    /// Returns the number of documents emitted. Attachments are emitted even when
+    /// the page body itself is empty, since pages often only wrap an attached file.
+    fn emit_page<F>(
+        &self,
+        mut page: ConfluencePage,
+        tags: &[Tag],
+        downloader: Option<&AttachmentDownloader>,
+        on_document: &mut F,
+    ) -> Result<u64, SourceError>
+    where
+        F: FnMut(Document) -> Result<(), SourceError>,
+    {
+        let attachments = page
+            .children
+            .take()
+            .and_then(|c| c.attachment)
+            .map(|a| a.results)
+            .unwrap_or_default();
+        let parent = ParentPage::of(&page);
+
+        let mut emitted = 0;
+        if let Ok(doc) = self.page_to_document(page, tags) {
+            on_document(doc)?;
+            emitted += 1;
+        }
+
+        let Some(downloader) = downloader else {
+            return Ok(emitted);
+        };
+        for attachment in &attachments {
+            match self.fetch_attachment(attachment, &parent, tags, downloader) {
+                Ok(Some(doc)) => {
+                    on_document(doc)?;
+                    emitted += 1;
+                }
+                Ok(None) => {
+                    debug!(attachment = %attachment.title, "skipping attachment without extractable text");
+                }
+                Err(e) => {
+                    warn!(attachment = %attachment.title, page = %parent.id, error = %e, "failed to sync attachment");
+                }
+            }
+        }
+
+        Ok(emitted)
+    }
+
+    fn fetch_attachment(
+        &self,
+        attachment: &ConfluenceAttachment,
+        parent: &ParentPage,
+        tags: &[Tag],
+        downloader: &AttachmentDownloader,
+    ) -> Result<Option<Document>, SourceError> {
+        let kind = AttachmentKind::detect(attachment.media_type(), &attachment.title);
+        if !kind.is_extractable() {
+            return Ok(None);
+        }
+
+        let file = downloader.download(&parent.base_url, attachment)?;
+        let text = attachment::extract_text(&file, kind);
+        std::fs::remove_file(&file).ok();
+
+        Ok(text?.map(|text| attachment_to_document(attachment, text, parent, tags)))
+    }
+
     fn page_to_document(
         &self,
         page: ConfluencePage,
@@ -396,13 +575,21 @@ impl ConfluenceSource {
             all_tags.push(tag);
         }
 
-        Ok(Document::new(
-            full_content,
-            source,
-            all_tags,
-            checksum,
-            metadata,
-        ))
+        let created = page
+            .history
+            .as_ref()
+            .and_then(|h| h.created_date.as_deref())
+            .and_then(parse_timestamp);
+        let updated = page
+            .version
+            .as_ref()
+            .and_then(|v| v.when.as_deref())
+            .and_then(parse_timestamp);
+
+        Ok(
+            Document::new(full_content, source, all_tags, checksum, metadata)
+                .with_timestamps(created, updated),
+        )
     }
 }
 
@@ -412,6 +599,148 @@ impl Default for ConfluenceSource {
     }
 }
 
+fn expand(downloader: Option<&AttachmentDownloader>) -> &'static str {
+    if downloader.is_some() {
+        PAGE_EXPAND_WITH_ATTACHMENTS
+    } else {
+        PAGE_EXPAND
+    }
+}
+
+/// Downloads attachments with the Atlassian account from the environment.
+struct AttachmentDownloader {
+    user: String,
+    dir: tempfile::TempDir,
+}
+
+impl AttachmentDownloader {
+    fn from_env() -> Result<Self, SourceError> {
+        let (Ok(email), Ok(token)) = (
+            std::env::var(ATLASSIAN_EMAIL_ENV),
+            std::env::var(ATLASSIAN_API_TOKEN_ENV),
+        ) else {
+            return Err(SourceError::SyncError(format!(
+                "set {} and {} to download attachments",
+                ATLASSIAN_EMAIL_ENV, ATLASSIAN_API_TOKEN_ENV
+            )));
+        };
+        let dir = tempfile::Builder::new()
+            .prefix("ssearch-attachments-")
+            .tempdir()
+            .map_err(|e| SourceError::ExecutionError(e.to_string()))?;
+
+        Ok(Self {
+            user: format!("{}:{}", email, token),
+            dir,
+        })
+    }
+
+    fn download(
+        &self,
+        base_url: &str,
+        attachment: &ConfluenceAttachment,
+    ) -> Result<PathBuf, SourceError> {
+        let link = attachment
+            .links
+            .as_ref()
+            .and_then(|l| l.download.as_deref())
+            .ok_or_else(|| {
+                SourceError::ParseError(format!(
+                    "attachment {} has no download link",
+                    attachment.id
+                ))
+            })?;
+        let path = self.path_for(attachment);
+
+        let mut child = Command::new("curl")
+            .args(["-sSfL", "-K", "-", "-o"])
+            .arg(&path)
+            .arg(format!("{}{}", base_url, link))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| SourceError::ExecutionError(e.to_string()))?;
+        // Pass the credentials on stdin so they do not appear in the process list
+        if let Some(mut stdin) = child.stdin.take() {
+            writeln!(stdin, "user = \"{}\"", self.user)
+                .map_err(|e| SourceError::ExecutionError(e.to_string()))?;
+        }
+        let output = child
+            .wait_with_output()
+            .map_err(|e| SourceError::ExecutionError(e.to_string()))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(SourceError::ExecutionError(format!(
+                "attachment download failed: {}",
+                stderr.trim()
+            )));
+        }
+
+        Ok(path)
+    }
+
+    fn path_for(&self, attachment: &ConfluenceAttachment) -> PathBuf {
+        self.dir.path().join(format!(
+            "{}-{}",
+            sanitize_filename(&attachment.id),
+            sanitize_filename(&attachment.title)
+        ))
+    }
+}
+
+/// Build the document for an attachment's extracted text, tagged with its parent page.
+fn attachment_to_document(
+    attachment: &ConfluenceAttachment,
+    text: String,
+    parent: &ParentPage,
+    tags: &[Tag],
+) -> Document {
+    let full_content = format!("# {}\n\n{}", attachment.title, text);
+
+    let url = attachment
+        .links
+        .as_ref()
+        .and_then(|l| l.webui.as_deref().or(l.download.as_deref()))
+        .map_or_else(
+            || attachment.id.clone(),
+            |link| format!("{}{}", parent.base_url, link),
+        );
+    let source = Source::with_url(SourceType::Confluence, attachment.id.clone(), url);
+    let checksum = calculate_checksum(&full_content);
+
+    let metadata = DocumentMetadata {
+        filename: Some(sanitize_filename(&attachment.title)),
+        extension: Path::new(&attachment.title)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_ascii_lowercase),
+        language: detect_language(Path::new(&attachment.title)),
+        title: Some(attachment.title.clone()),
+        path: Some(format!("{} > {}", parent.path, attachment.title)),
+        size_bytes: full_content.len() as u64,
+    };
+
+    let mut all_tags = tags.to_vec();
+    for tag in [
+        "source:confluence".to_string(),
+        format!("parent-page:{}", parent.id),
+    ] {
+        if let Ok(tag) = tag.parse() {
+            all_tags.push(tag);
+        }
+    }
+
+    let updated = attachment
+        .version
+        .as_ref()
+        .and_then(|v| v.when.as_deref())
+        .and_then(parse_timestamp);
+
+    Document::new(full_content, source, all_tags, checksum, metadata).with_timestamps(None, updated)
+}
+
 fn extract_page_id(query: &str) -> Option<String> {
     let query = query.trim();
 
@@ -514,7 +843,52 @@ mod tests {
                 },
             ]),
             links: None,
+            version: None,
+            history: None,
+            children: None,
         };
         assert_eq!(build_page_path(&page), "Root > Parent > My Page");
     }
+
+    #[test]
+    fn test_attachment_document() {
+        let json = r#"{
+            "id": "777",
+            "title": "Onboarding Spec",
+            "_links": {"base": "https://example.atlassian.net/wiki", "webui": "/spaces/DEV/pages/777"},
+            "children": {"attachment": {"results": [{
+                "id": "att901",
+                "title": "scanned-spec.PDF",
+                "extensions": {"mediaType": "application/pdf", "fileSize": 48213},
+                "_links": {"download": "/download/attachments/777/scanned-spec.PDF?api=v2"},
+                "version": {"when": "2024-03-01T10:00:00.000Z"}
+            }]}}
+        }"#;
+        let mut page: ConfluencePage = serde_json::from_str(json).unwrap();
+        let attachments = page.children.take().unwrap().attachment.unwrap().results;
+        assert_eq!(attachments.len(), 1);
+        assert_eq!(attachments[0].media_type(), Some("application/pdf"));
+
+        let parent = ParentPage::of(&page);
+        let tags = vec![Tag::new("team", "docs").unwrap()];
+        let text = "Section 1. Accounts are provisioned by the identity team.".to_string();
+        let doc = attachment_to_document(&attachments[0], text, &parent, &tags);
+
+        assert_eq!(doc.source.location, "att901");
+        assert_eq!(
+            doc.source.url.as_deref(),
+            Some(
+                "https://example.atlassian.net/wiki/download/attachments/777/scanned-spec.PDF?api=v2"
+            )
+        );
+        assert!(doc.content.starts_with("# scanned-spec.PDF\n\nSection 1."));
+        assert_eq!(
+            doc.metadata.path.as_deref(),
+            Some("Onboarding Spec > scanned-spec.PDF")
+        );
+        assert_eq!(doc.metadata.extension.as_deref(), Some("pdf"));
+        assert!(doc.updated_at.starts_with("2024-03-01"));
+        let tags: Vec<String> = doc.tags.iter().map(ToString::to_string).collect();
+        assert_eq!(tags, ["team:docs", "source:confluence", "parent-page:777"]);
+    }
 }
@@ -1,8 +1,10 @@
 //! Figma data source via figma-cli integration.
 
+use std::collections::HashSet;
 use std::process::Command;
 
 use serde::Deserialize;
+use tracing::warn;
 
 use crate::error::SourceError;
 use crate::models::{Document, DocumentMetadata, Source, SourceType, Tag};
@@ -63,10 +65,96 @@ struct FigmaNode {
     children: Vec<FigmaNode>,
     #[serde(default)]
     characters: Option<String>,
+    /// Prototype flows starting on this page (CANVAS nodes only)
+    #[serde(default, rename = "flowStartingPoints")]
+    flow_starting_points: Vec<FlowStartingPoint>,
 }
 
-/// Figma data source implementation.
+#[derive(Debug, Deserialize)]
+struct FlowStartingPoint {
+    #[serde(rename = "nodeId")]
+    node_id: String,
+    name: String,
+}
+
+/// figma-cli comments output format (Figma REST `GET /files/:key/comments`)
+#[derive(Debug, Deserialize)]
+struct CommentsOutput {
+    comments: Vec<FigmaComment>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct FigmaComment {
+    id: String,
+    message: String,
+    /// Root comment of the thread; empty or absent for the root itself
+    #[serde(default)]
+    parent_id: Option<String>,
+    user: FigmaUser,
+    created_at: String,
+    #[serde(default)]
+    resolved_at: Option<String>,
+    #[serde(default)]
+    client_meta: Option<ClientMeta>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct FigmaUser {
+    handle: String,
+}
+
+/// Where a comment is pinned; `node_id` is absent for canvas-positioned comments.
+#[derive(Debug, Clone, Deserialize)]
+struct ClientMeta {
+    #[serde(default)]
+    node_id: Option<String>,
+}
+
+/// A root comment and its replies, oldest first.
 #[derive(Debug)]
+struct CommentThread {
+    node_id: Option<String>,
+    comments: Vec<FigmaComment>,
+}
+
+impl CommentThread {
+    fn root(&self) -> &FigmaComment {
+        &self.comments[0]
+    }
+
+    fn is_resolved(&self) -> bool {
+        self.root().resolved_at.is_some()
+    }
+}
+
+/// File-level context shared by every frame document of a sync.
+struct FileContext<'a> {
+    key: &'a str,
+    name: &'a str,
+    tags: &'a [Tag],
+    threads: &'a [CommentThread],
+}
+
+impl FileContext<'_> {
+    /// Comment threads pinned to `node` or anything inside it.
+    fn threads_in(&self, node: &FigmaNode) -> Vec<&CommentThread> {
+        let mut ids = HashSet::new();
+        collect_node_ids(node, &mut ids);
+        self.threads
+            .iter()
+            .filter(|t| t.node_id.as_ref().is_some_and(|id| ids.contains(id)))
+            .collect()
+    }
+}
+
+/// Page a frame was found on, with the prototype flows that start there.
+struct PageContext<'a> {
+    name: &'a str,
+    flows: &'a [FlowStartingPoint],
+}
+
+/// Figma data source implementation.
+#[derive(Debug, Clone, Copy)]
 pub struct FigmaSource;
 
 impl FigmaSource {
@@ -103,6 +191,23 @@ impl FigmaSource {
     /// Sync designs from Figma using figma-cli.
     /// Creates separate documents for each significant node (page/frame).
     pub fn sync(&self, options: SyncOptions) -> Result<Vec<Document>, SourceError> {
+        let mut documents = Vec::new();
+        self.sync_streaming(options, |doc| {
+            documents.push(doc);
+            Ok(())
+        })?;
+        Ok(documents)
+    }
+
+    /// Sync designs, handing each document to `on_document` as soon as its page is inspected.
+    pub fn sync_streaming<F>(
+        &self,
+        options: SyncOptions,
+        mut on_document: F,
+    ) -> Result<u64, SourceError>
+    where
+        F: FnMut(Document) -> Result<(), SourceError>,
+    {
         if !self.check_available()? {
             return Err(SourceError::CliNotFound(
                 "figma-cli not found. Install with: cargo install figma-cli".to_string(),
@@ -115,12 +220,17 @@ impl FigmaSource {
 
         // Check if URL has node-id → inspect that specific node
         if let Some(node_id) = extract_node_id(query) {
-            return self.sync_single_node(query, &node_id, &options.tags);
+            let documents = self.sync_single_node(query, &node_id, &options.tags)?;
+            let count = documents.len() as u64;
+            for doc in documents {
+                on_document(doc)?;
+            }
+            return Ok(count);
         }
 
         // Extract file structure to get pages
         let file_key = extract_file_key(query).unwrap_or_else(|| query.to_owned());
-        self.sync_all_pages(&file_key, &options.tags, options.limit)
+        self.sync_all_pages(&file_key, &options.tags, options.limit, on_document)
     }
 
     /// Sync a single node by its ID.
@@ -147,15 +257,17 @@ impl FigmaSource {
         let inspect: InspectOutput = serde_json::from_str(&stdout)
             .map_err(|e| SourceError::ParseError(format!("failed to parse inspect: {}", e)))?;
 
+        let threads = self.fetch_comments(&inspect.file.key);
+        let file = FileContext {
+            key: &inspect.file.key,
+            name: &inspect.file.name,
+            tags,
+            threads: &threads,
+        };
+
         let mut documents = Vec::new();
         for (id, wrapper) in &inspect.nodes {
-            if let Some(doc) = self.node_to_document(
-                &wrapper.document,
-                &inspect.file.key,
-                &inspect.file.name,
-                id,
-                tags,
-            ) {
+            if let Some(doc) = self.node_to_document(&wrapper.document, &file, id) {
                 documents.push(doc);
             }
         }
@@ -164,12 +276,16 @@ impl FigmaSource {
     }
 
     /// Sync all pages from a Figma file.
-    fn sync_all_pages(
+    fn sync_all_pages<F>(
         &self,
         file_key: &str,
         tags: &[Tag],
         limit: Option<u32>,
-    ) -> Result<Vec<Document>, SourceError> {
+        mut on_document: F,
+    ) -> Result<u64, SourceError>
+    where
+        F: FnMut(Document) -> Result<(), SourceError>,
+    {
         // Step 1: Extract to get page list
         let output = Command::new("figma-cli")
             .args(["extract", file_key, "--format", "json"])
@@ -208,7 +324,16 @@ impl FigmaSource {
             .collect();
 
         let page_limit = limit.unwrap_or(100) as usize;
-        let mut documents = Vec::new();
+        let mut count = 0u64;
+
+        let threads = self.fetch_comments(&file_key);
+        let file = FileContext {
+            key: &file_key,
+            name: &file_name,
+            tags,
+            threads: &threads,
+        };
+        let mut attached = HashSet::new();
 
         // Step 2: Inspect each page to get frames
         for page in pages.iter().take(page_limit) {
@@ -219,29 +344,43 @@ impl FigmaSource {
                 .map_err(|e| SourceError::ExecutionError(e.to_string()))?;
 
             if !inspect_output.status.success() {
-                eprintln!("Warning: failed to inspect page {}", page.name);
+                warn!(page = %page.name, "failed to inspect page");
                 continue;
             }
 
             let inspect_stdout = String::from_utf8_lossy(&inspect_output.stdout);
+            let mut documents = Vec::new();
             if let Ok(inspect) = serde_json::from_str::<InspectOutput>(&inspect_stdout) {
+                let page = PageContext {
+                    name: &page.name,
+                    flows: &[],
+                };
                 for (id, wrapper) in &inspect.nodes {
                     // Create documents for top-level frames in this page
                     self.collect_frame_documents(
                         &wrapper.document,
-                        &file_key,
-                        &file_name,
-                        &page.name,
+                        &file,
+                        &page,
                         id,
-                        tags,
                         &mut documents,
+                        &mut attached,
                     );
                 }
             }
+            for doc in documents {
+                on_document(doc)?;
+                count += 1;
+            }
+        }
+
+        // Threads on frames that weren't synced, or on the canvas, become their own documents
+        for thread in threads.iter().filter(|t| !attached.contains(&t.root().id)) {
+            on_document(self.thread_to_document(thread, &file))?;
+            count += 1;
         }
 
         // If no frames found, create at least a file-level document
-        if documents.is_empty() {
+        if count == 0 {
             let content = format!(
                 "# {}\n\n## Pages\n{}",
                 file_name,
@@ -269,40 +408,43 @@ impl FigmaSource {
                     all_tags.push(tag);
                 }
 
-                documents.push(Document::new(content, source, all_tags, checksum, metadata));
+                on_document(Document::new(content, source, all_tags, checksum, metadata))?;
+                count += 1;
             }
         }
 
-        Ok(documents)
+        Ok(count)
     }
 
     /// Recursively collect frame documents from node tree.
-    #[allow(clippy::too_many_arguments)]
+    ///
+    /// IDs of the comment threads attached to a document are added to `attached`.
     fn collect_frame_documents(
         &self,
         node: &FigmaNode,
-        file_key: &str,
-        file_name: &str,
-        page_name: &str,
+        file: &FileContext,
+        page: &PageContext,
         node_id: &str,
-        tags: &[Tag],
         documents: &mut Vec<Document>,
+        attached: &mut HashSet<String>,
     ) {
         // Create document for FRAME, COMPONENT, COMPONENT_SET at top level
         match node.node_type.as_str() {
             "FRAME" | "COMPONENT" | "COMPONENT_SET" | "INSTANCE" => {
-                if let Some(doc) =
-                    self.frame_to_document(node, file_key, file_name, page_name, node_id, tags)
-                {
+                if let Some(doc) = self.frame_to_document(node, file, page, node_id) {
                     documents.push(doc);
+                    attached.extend(file.threads_in(node).iter().map(|t| t.root().id.clone()));
                 }
             }
             "CANVAS" => {
-                // Page node - recurse into children
+                // Page node - recurse into children with the page's prototype flows
+                let page = PageContext {
+                    name: page.name,
+                    flows: &node.flow_starting_points,
+                };
                 for child in &node.children {
-                    let child_id = &child.id;
                     self.collect_frame_documents(
-                        child, file_key, file_name, page_name, child_id, tags, documents,
+                        child, file, &page, &child.id, documents, attached,
                     );
                 }
             }
@@ -314,18 +456,30 @@ impl FigmaSource {
     fn frame_to_document(
         &self,
         node: &FigmaNode,
-        file_key: &str,
-        file_name: &str,
-        page_name: &str,
+        file: &FileContext,
+        page: &PageContext,
         node_id: &str,
-        tags: &[Tag],
     ) -> Option<Document> {
+        let (file_key, file_name, page_name) = (file.key, file.name, page.name);
         let mut content_parts = Vec::new();
 
         // Title with context
         content_parts.push(format!("# {} / {} / {}\n", file_name, page_name, node.name));
         content_parts.push(format!("\nType: {}\n", node.node_type));
 
+        let flows: Vec<&str> = page
+            .flows
+            .iter()
+            .filter(|flow| flow.node_id == node.id)
+            .map(|flow| flow.name.as_str())
+            .collect();
+        if !flows.is_empty() {
+            content_parts.push("\n## Prototype flows\n".to_string());
+            for flow in &flows {
+                content_parts.push(format!("- {} (starts here)\n", flow));
+            }
+        }
+
         // Collect text content from tree
         let mut texts = Vec::new();
         Self::collect_texts(node, &mut texts);
@@ -349,6 +503,14 @@ impl FigmaSource {
             }
         }
 
+        let threads = file.threads_in(node);
+        if !threads.is_empty() {
+            content_parts.push("\n## Comments\n".to_string());
+            for thread in threads {
+                content_parts.push(format_thread(thread));
+            }
+        }
+
         let content = content_parts.join("");
         if content.len() < 50 {
             return None;
@@ -373,17 +535,99 @@ impl FigmaSource {
             size_bytes: content.len() as u64,
         };
 
-        let mut all_tags = tags.to_vec();
+        let mut all_tags = file.tags.to_vec();
         if let Ok(tag) = "source:figma".parse() {
             all_tags.push(tag);
         }
         if let Ok(tag) = format!("figma-type:{}", node.node_type.to_lowercase()).parse() {
             all_tags.push(tag);
         }
+        for flow in flows {
+            if let Ok(tag) = format!("figma-flow:{}", flow_tag_value(flow)).parse() {
+                all_tags.push(tag);
+            }
+        }
 
         Some(Document::new(content, source, all_tags, checksum, metadata))
     }
 
+    /// Fetch the file's comment threads; sync continues without them on failure.
+    fn fetch_comments(&self, file_key: &str) -> Vec<CommentThread> {
+        let output = match Command::new("figma-cli")
+            .args(["comments", file_key, "--format", "json"])
+            .output()
+        {
+            Ok(output) if output.status.success() => output,
+            Ok(output) => {
+                warn!(
+                    stderr = %String::from_utf8_lossy(&output.stderr).trim(),
+                    "failed to fetch comments"
+                );
+                return Vec::new();
+            }
+            Err(e) => {
+                warn!(error = %e, "failed to fetch comments");
+                return Vec::new();
+            }
+        };
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        match parse_comments(&stdout) {
+            Ok(comments) => group_threads(comments),
+            Err(e) => {
+                warn!(error = %e, "failed to parse comments");
+                Vec::new()
+            }
+        }
+    }
+
+    /// Convert a comment thread not attached to a synced frame to a Document.
+    fn thread_to_document(&self, thread: &CommentThread, file: &FileContext) -> Document {
+        let root = thread.root();
+        let content = format!(
+            "# {} / Comment by {}\n\n{}",
+            file.name,
+            root.user.handle,
+            format_thread(thread)
+        );
+
+        let url = match thread.node_id {
+            Some(ref node_id) => format!(
+                "https://www.figma.com/design/{}?node-id={}#{}",
+                file.key,
+                node_id.replace(':', "-"),
+                root.id
+            ),
+            None => format!("https://www.figma.com/design/{}#{}", file.key, root.id),
+        };
+        let source = Source::with_url(
+            SourceType::Figma,
+            format!("{}:comment:{}", file.key, root.id),
+            url,
+        );
+        let checksum = calculate_checksum(&content);
+
+        let summary: String = root.message.chars().take(60).collect();
+        let title = format!("Comment by {}: {}", root.user.handle, summary.trim());
+        let metadata = DocumentMetadata {
+            filename: Some(format!("comment-{}.md", sanitize_filename(&root.id))),
+            extension: Some("md".to_string()),
+            language: Some("markdown".to_string()),
+            title: Some(title),
+            path: Some(format!("{} > Comments", file.name)),
+            size_bytes: content.len() as u64,
+        };
+
+        let mut all_tags = file.tags.to_vec();
+        for tag in ["source:figma", "figma-type:comment"] {
+            if let Ok(tag) = tag.parse() {
+                all_tags.push(tag);
+            }
+        }
+
+        Document::new(content, source, all_tags, checksum, metadata)
+    }
+
     /// Recursively collect text content from nodes.
     fn collect_texts(node: &FigmaNode, texts: &mut Vec<String>) {
         if node.node_type == "TEXT" {
@@ -423,12 +667,14 @@ impl FigmaSource {
     fn node_to_document(
         &self,
         node: &FigmaNode,
-        file_key: &str,
-        file_name: &str,
+        file: &FileContext,
         node_id: &str,
-        tags: &[Tag],
     ) -> Option<Document> {
-        self.frame_to_document(node, file_key, file_name, "Selected", node_id, tags)
+        let page = PageContext {
+            name: "Selected",
+            flows: &[],
+        };
+        self.frame_to_document(node, file, &page, node_id)
     }
 }
 
@@ -583,6 +829,80 @@ fn is_meaningful_text(text: &str) -> bool {
     true
 }
 
+/// Flow name as a tag value: lowercase, runs of other characters as `-`.
+fn flow_tag_value(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+fn collect_node_ids(node: &FigmaNode, ids: &mut HashSet<String>) {
+    ids.insert(node.id.clone());
+    for child in &node.children {
+        collect_node_ids(child, ids);
+    }
+}
+
+/// Parse figma-cli comments output, skipping any log lines before the JSON.
+fn parse_comments(output: &str) -> Result<Vec<FigmaComment>, SourceError> {
+    let json_start = output
+        .find('{')
+        .ok_or_else(|| SourceError::ParseError("no JSON in comments output".to_string()))?;
+    let parsed: CommentsOutput = serde_json::from_str(&output[json_start..])
+        .map_err(|e| SourceError::ParseError(format!("failed to parse comments: {}", e)))?;
+    Ok(parsed.comments)
+}
+
+/// Group comments into threads by their root comment, ordered by creation time.
+fn group_threads(mut comments: Vec<FigmaComment>) -> Vec<CommentThread> {
+    // RFC 3339 timestamps in UTC sort chronologically as strings
+    comments.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+
+    let mut threads: Vec<CommentThread> = Vec::new();
+    for comment in comments {
+        match comment.parent_id.as_deref().filter(|id| !id.is_empty()) {
+            Some(parent_id) => {
+                if let Some(thread) = threads.iter_mut().find(|t| t.root().id == parent_id) {
+                    thread.comments.push(comment);
+                }
+            }
+            None => threads.push(CommentThread {
+                node_id: comment
+                    .client_meta
+                    .as_ref()
+                    .and_then(|meta| meta.node_id.clone()),
+                comments: vec![comment],
+            }),
+        }
+    }
+    threads
+}
+
+/// Render a thread as a markdown list: the root comment, then indented replies.
+fn format_thread(thread: &CommentThread) -> String {
+    let mut output = String::new();
+    for (i, comment) in thread.comments.iter().enumerate() {
+        let indent = if i == 0 { "" } else { "  " };
+        let date = comment.created_at.get(..10).unwrap_or(&comment.created_at);
+        let resolved = if i == 0 && thread.is_resolved() {
+            " [resolved]"
+        } else {
+            ""
+        };
+        output.push_str(&format!(
+            "{}- **{}** ({}){}: {}\n",
+            indent,
+            comment.user.handle,
+            date,
+            resolved,
+            comment.message.trim().replace('\n', " ")
+        ));
+    }
+    output
+}
+
 /// Extract node-id from Figma URL query parameter.
 fn extract_node_id(query: &str) -> Option<String> {
     if !query.contains("node-id=") {
@@ -654,8 +974,10 @@ mod tests {
                 name: "Hello World".to_string(),
                 children: vec![],
                 characters: Some("Hello World".to_string()),
+                flow_starting_points: vec![],
             }],
             characters: None,
+            flow_starting_points: vec![],
         };
 
         let mut texts = Vec::new();
@@ -663,4 +985,80 @@ mod tests {
         assert_eq!(texts.len(), 1);
         assert_eq!(texts[0], "Hello World");
     }
+
+    #[test]
+    fn test_comment_threads() {
+        let output = r#"fetching comments...
+{"comments": [
+  {"id": "2", "message": "Agreed, shipping v2", "parent_id": "1", "user": {"handle": "kim"},
+   "created_at": "2024-05-02T09:00:00Z"},
+  {"id": "1", "message": "Use the v2 checkout\nbutton here?", "parent_id": "", "user": {"handle": "lee"},
+   "created_at": "2024-05-01T09:00:00Z", "resolved_at": "2024-05-03T09:00:00Z",
+   "client_meta": {"node_id": "1:3", "node_offset": {"x": 1, "y": 2}}},
+  {"id": "3", "message": "Typo in title", "user": {"handle": "park"},
+   "created_at": "2024-05-04T09:00:00Z", "client_meta": {"x": 10, "y": 20}}
+]}"#;
+        let threads = group_threads(parse_comments(output).unwrap());
+        assert_eq!(threads.len(), 2);
+        assert_eq!(threads[0].node_id.as_deref(), Some("1:3"));
+        assert_eq!(threads[0].comments.len(), 2);
+        assert_eq!(threads[1].node_id, None);
+        assert_eq!(
+            format_thread(&threads[0]),
+            "- **lee** (2024-05-01) [resolved]: Use the v2 checkout button here?\n  \
+             - **kim** (2024-05-02): Agreed, shipping v2\n"
+        );
+
+        let frame = FigmaNode {
+            node_type: "FRAME".to_string(),
+            id: "1:2".to_string(),
+            name: "Checkout".to_string(),
+            children: vec![FigmaNode {
+                node_type: "TEXT".to_string(),
+                id: "1:3".to_string(),
+                name: "Pay now".to_string(),
+                children: vec![],
+                characters: Some("Pay now".to_string()),
+                flow_starting_points: vec![],
+            }],
+            characters: None,
+            flow_starting_points: vec![],
+        };
+        let file = FileContext {
+            key: "AbcXyz123DefGhi456",
+            name: "Shop",
+            tags: &[],
+            threads: &threads,
+        };
+        let flows = [FlowStartingPoint {
+            node_id: "1:2".to_string(),
+            name: "Purchase Flow".to_string(),
+        }];
+        let page = PageContext {
+            name: "Flows",
+            flows: &flows,
+        };
+        let doc = FigmaSource::new()
+            .frame_to_document(&frame, &file, &page, "1-2")
+            .unwrap();
+        assert!(
+            doc.content
+                .contains("## Prototype flows\n- Purchase Flow (starts here)")
+        );
+        assert!(doc.content.contains("## Comments\n- **lee**"));
+        assert!(!doc.content.contains("Typo in title"));
+        assert!(
+            doc.tags
+                .iter()
+                .any(|t| t.to_string() == "figma-flow:purchase-flow")
+        );
+
+        let doc = FigmaSource::new().thread_to_document(&threads[1], &file);
+        assert!(doc.content.starts_with("# Shop / Comment by park"));
+        assert!(
+            doc.tags
+                .iter()
+                .any(|t| t.to_string() == "figma-type:comment")
+        );
+    }
 }
@@ -0,0 +1,737 @@
+//! Google Docs and Sheets via the Drive API, using `curl`.
+
+use std::collections::VecDeque;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::LazyLock;
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use regex::Regex;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use tracing::{info, warn};
+
+use crate::error::SourceError;
+use crate::models::{Document, DocumentMetadata, Source, SourceType, Tag, parse_timestamp};
+use crate::sources::SyncOptions;
+use crate::utils::file::calculate_checksum;
+use crate::utils::has_meaningful_content;
+
+/// Environment variable holding an OAuth access token with Drive read access
+pub const GOOGLE_DRIVE_TOKEN_ENV: &str = "GOOGLE_DRIVE_TOKEN";
+
+/// Environment variable pointing to a service account key file
+pub const GOOGLE_CREDENTIALS_ENV: &str = "GOOGLE_APPLICATION_CREDENTIALS";
+
+const DRIVE_API_URL: &str = "https://www.googleapis.com/drive/v3";
+const DRIVE_SCOPE: &str = "https://www.googleapis.com/auth/drive.readonly";
+const API_PAGE_SIZE: &str = "100";
+const FILE_FIELDS: &str =
+    "id,name,mimeType,webViewLink,createdTime,modifiedTime,owners(displayName)";
+
+const FOLDER_MIME: &str = "application/vnd.google-apps.folder";
+const DOCUMENT_MIME: &str = "application/vnd.google-apps.document";
+const SPREADSHEET_MIME: &str = "application/vnd.google-apps.spreadsheet";
+
+static RE_FILE_URL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"docs\.google\.com/(?:document|spreadsheets)/d/([A-Za-z0-9_-]+)").unwrap()
+});
+static RE_FOLDER_URL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"drive\.google\.com/drive/(?:u/\d+/)?folders/([A-Za-z0-9_-]+)").unwrap()
+});
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DriveFile {
+    id: String,
+    name: String,
+    mime_type: String,
+    #[serde(default)]
+    web_view_link: Option<String>,
+    #[serde(default)]
+    created_time: Option<String>,
+    #[serde(default)]
+    modified_time: Option<String>,
+    #[serde(default)]
+    owners: Vec<DriveUser>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DriveUser {
+    display_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FileList {
+    #[serde(default)]
+    files: Vec<DriveFile>,
+    #[serde(default)]
+    next_page_token: Option<String>,
+}
+
+/// Service account key file (the fields used to mint a token).
+#[derive(Debug, Deserialize)]
+struct ServiceAccountKey {
+    client_email: String,
+    private_key: String,
+    token_uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+}
+
+/// Kind of Google file, deciding the export format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FileKind {
+    Doc,
+    Sheet,
+}
+
+impl FileKind {
+    fn from_mime(mime: &str) -> Option<Self> {
+        match mime {
+            DOCUMENT_MIME => Some(FileKind::Doc),
+            SPREADSHEET_MIME => Some(FileKind::Sheet),
+            _ => None,
+        }
+    }
+
+    /// Export MIME type; Sheets export their first sheet as CSV.
+    fn export_mime(self) -> &'static str {
+        match self {
+            FileKind::Doc => "text/plain",
+            FileKind::Sheet => "text/csv",
+        }
+    }
+
+    fn tag_value(self) -> &'static str {
+        match self {
+            FileKind::Doc => "doc",
+            FileKind::Sheet => "sheet",
+        }
+    }
+}
+
+/// What `--query` / `--project` select.
+#[derive(Debug, PartialEq, Eq)]
+enum Selection {
+    /// One file by ID or URL
+    File(String),
+    /// Every Doc and Sheet under these folders, recursively
+    Folders(Vec<String>),
+    /// A Drive search expression (e.g., `name contains 'spec'`)
+    Search(String),
+    /// Every Doc and Sheet the account can read
+    All,
+}
+
+impl Selection {
+    fn from_options(options: &SyncOptions) -> Self {
+        if let Some(ref project) = options.project {
+            let folders = project
+                .split(',')
+                .map(str::trim)
+                .filter(|f| !f.is_empty())
+                .map(|f| {
+                    RE_FOLDER_URL
+                        .captures(f)
+                        .map_or(f.to_string(), |caps| caps[1].to_string())
+                })
+                .collect();
+            return Selection::Folders(folders);
+        }
+        match options.query.as_deref().map(str::trim) {
+            None | Some("") => Selection::All,
+            Some(query) => {
+                if let Some(caps) = RE_FILE_URL.captures(query) {
+                    Selection::File(caps[1].to_string())
+                } else if let Some(caps) = RE_FOLDER_URL.captures(query) {
+                    Selection::Folders(vec![caps[1].to_string()])
+                } else if query
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+                {
+                    Selection::File(query.to_string())
+                } else {
+                    Selection::Search(query.to_string())
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct GoogleDriveSource;
+
+impl GoogleDriveSource {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn source_type(&self) -> SourceType {
+        SourceType::GoogleDrive
+    }
+
+    pub fn name(&self) -> &str {
+        "Google Drive"
+    }
+
+    pub fn check_available(&self) -> Result<bool, SourceError> {
+        Command::new("which")
+            .arg("curl")
+            .output()
+            .map(|o| o.status.success())
+            .map_err(|e| SourceError::ExecutionError(e.to_string()))
+    }
+
+    pub fn install_instructions(&self) -> &str {
+        "Install curl and set GOOGLE_DRIVE_TOKEN (OAuth access token), or \
+         GOOGLE_APPLICATION_CREDENTIALS to a service account key (requires openssl)"
+    }
+
+    /// Export Docs and Sheets as documents.
+    ///
+    /// `options.project` selects folders (comma-separated IDs or URLs, searched
+    /// recursively); otherwise `options.query` is a file ID/URL or a Drive
+    /// search expression. Every readable Doc and Sheet is synced when both are
+    /// unset. Documents are tagged with the name of the folder they are in.
+    pub fn sync(&self, options: SyncOptions) -> Result<Vec<Document>, SourceError> {
+        let mut documents = Vec::new();
+        self.sync_streaming(options, |doc| {
+            documents.push(doc);
+            Ok(())
+        })?;
+        Ok(documents)
+    }
+
+    /// Sync files, handing each document to `on_document` as soon as it is exported.
+    pub fn sync_streaming<F>(
+        &self,
+        options: SyncOptions,
+        mut on_document: F,
+    ) -> Result<u64, SourceError>
+    where
+        F: FnMut(Document) -> Result<(), SourceError>,
+    {
+        let api = DriveApi::from_env()?;
+        let limit = options.limit.map_or(usize::MAX, |l| l as usize);
+
+        // (file, folder path) pairs
+        let files: Vec<(DriveFile, Vec<String>)> = match Selection::from_options(&options) {
+            Selection::File(id) => vec![(api.file(&id)?, Vec::new())],
+            Selection::Folders(folders) => api.folder_files(&folders, limit)?,
+            Selection::Search(query) => api
+                .list(&format!("({}) and {}", query, exportable_filter()), limit)?
+                .into_iter()
+                .map(|file| (file, Vec::new()))
+                .collect(),
+            Selection::All => api
+                .list(&exportable_filter(), limit)?
+                .into_iter()
+                .map(|file| (file, Vec::new()))
+                .collect(),
+        };
+
+        let mut count = 0u64;
+        for (file, folders) in files.into_iter().take(limit) {
+            let Some(kind) = FileKind::from_mime(&file.mime_type) else {
+                warn!(file = %file.name, mime = %file.mime_type, "skipping unsupported file type");
+                continue;
+            };
+            let text = match api.export(&file.id, kind) {
+                Ok(text) => text,
+                Err(e) => {
+                    warn!(file = %file.name, error = %e, "failed to export file");
+                    continue;
+                }
+            };
+            if let Some(doc) = file_to_document(&file, kind, &text, &folders, &options.tags) {
+                on_document(doc)?;
+                count += 1;
+            }
+        }
+
+        Ok(count)
+    }
+}
+
+impl Default for GoogleDriveSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Drive search clause matching Docs and Sheets that aren't in the trash.
+fn exportable_filter() -> String {
+    format!(
+        "trashed = false and (mimeType = '{}' or mimeType = '{}')",
+        DOCUMENT_MIME, SPREADSHEET_MIME
+    )
+}
+
+/// Minimal Drive API client using `curl`.
+struct DriveApi {
+    token: String,
+}
+
+impl DriveApi {
+    /// Use `GOOGLE_DRIVE_TOKEN`, or mint a token from the service account key.
+    fn from_env() -> Result<Self, SourceError> {
+        if let Ok(token) = std::env::var(GOOGLE_DRIVE_TOKEN_ENV) {
+            return Ok(Self { token });
+        }
+        let key_path = std::env::var(GOOGLE_CREDENTIALS_ENV).map_err(|_| {
+            SourceError::SyncError(format!(
+                "set {} or {} to access Google Drive",
+                GOOGLE_DRIVE_TOKEN_ENV, GOOGLE_CREDENTIALS_ENV
+            ))
+        })?;
+        let token = service_account_token(Path::new(&key_path))?;
+        Ok(Self { token })
+    }
+
+    fn get(&self, url: &str, params: &[(&str, &str)]) -> Result<Vec<u8>, SourceError> {
+        let mut command = Command::new("curl");
+        command
+            .args(["-sS", "--fail-with-body", "-G", "-H", "@-"])
+            .arg(url);
+        for (key, value) in params {
+            command
+                .arg("--data-urlencode")
+                .arg(format!("{key}={value}"));
+        }
+
+        // Pass the token on stdin so it does not appear in the process list
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| SourceError::ExecutionError(e.to_string()))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            writeln!(stdin, "Authorization: Bearer {}", self.token)
+                .map_err(|e| SourceError::ExecutionError(e.to_string()))?;
+        }
+        let output = child
+            .wait_with_output()
+            .map_err(|e| SourceError::ExecutionError(e.to_string()))?;
+
+        if !output.status.success() {
+            return Err(SourceError::SyncError(format!(
+                "google drive request failed: {}",
+                api_error(&output.stdout).unwrap_or_else(|| String::from_utf8_lossy(
+                    &output.stderr
+                )
+                .trim()
+                .to_string())
+            )));
+        }
+        Ok(output.stdout)
+    }
+
+    fn get_json<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        params: &[(&str, &str)],
+    ) -> Result<T, SourceError> {
+        let body = self.get(url, params)?;
+        serde_json::from_slice(&body)
+            .map_err(|e| SourceError::ParseError(format!("google drive: {}", e)))
+    }
+
+    fn file(&self, id: &str) -> Result<DriveFile, SourceError> {
+        self.get_json(
+            &format!("{}/files/{}", DRIVE_API_URL, id),
+            &[("fields", FILE_FIELDS), ("supportsAllDrives", "true")],
+        )
+    }
+
+    /// Files matching a Drive search expression, up to `limit`.
+    fn list(&self, query: &str, limit: usize) -> Result<Vec<DriveFile>, SourceError> {
+        let fields = format!("nextPageToken,files({})", FILE_FIELDS);
+        let mut files = Vec::new();
+        let mut page_token = String::new();
+        loop {
+            let page: FileList = self.get_json(
+                &format!("{}/files", DRIVE_API_URL),
+                &[
+                    ("q", query),
+                    ("fields", &fields),
+                    ("pageSize", API_PAGE_SIZE),
+                    ("pageToken", &page_token),
+                    ("supportsAllDrives", "true"),
+                    ("includeItemsFromAllDrives", "true"),
+                ],
+            )?;
+            files.extend(page.files);
+            match page.next_page_token.filter(|t| !t.is_empty()) {
+                Some(next) if files.len() < limit => page_token = next,
+                _ => break,
+            }
+        }
+        Ok(files)
+    }
+
+    /// Docs and Sheets under the folders, with the folder names leading to each.
+    fn folder_files(
+        &self,
+        roots: &[String],
+        limit: usize,
+    ) -> Result<Vec<(DriveFile, Vec<String>)>, SourceError> {
+        let mut queue: VecDeque<(String, Vec<String>)> = VecDeque::new();
+        for root in roots {
+            let folder = self.file(root)?;
+            if folder.mime_type != FOLDER_MIME {
+                return Err(SourceError::SyncError(format!(
+                    "'{}' is not a Google Drive folder",
+                    folder.name
+                )));
+            }
+            queue.push_back((folder.id, vec![folder.name]));
+        }
+
+        let mut files = Vec::new();
+        while let Some((folder_id, path)) = queue.pop_front() {
+            info!(folder = %path.join(" / "), "listing folder");
+            let children = self.list(
+                &format!("'{}' in parents and trashed = false", folder_id),
+                usize::MAX,
+            )?;
+            for child in children {
+                if child.mime_type == FOLDER_MIME {
+                    let mut child_path = path.clone();
+                    child_path.push(child.name.clone());
+                    queue.push_back((child.id, child_path));
+                } else if FileKind::from_mime(&child.mime_type).is_some() {
+                    files.push((child, path.clone()));
+                    if files.len() >= limit {
+                        return Ok(files);
+                    }
+                }
+            }
+        }
+        Ok(files)
+    }
+
+    fn export(&self, id: &str, kind: FileKind) -> Result<String, SourceError> {
+        let body = self.get(
+            &format!("{}/files/{}/export", DRIVE_API_URL, id),
+            &[("mimeType", kind.export_mime())],
+        )?;
+        // Docs exports start with a byte order mark
+        Ok(String::from_utf8_lossy(&body)
+            .trim_start_matches('\u{feff}')
+            .to_string())
+    }
+}
+
+/// Error message from a Google API error body (`{"error": {"message"}}`).
+fn api_error(body: &[u8]) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_slice(body).ok()?;
+    value
+        .pointer("/error/message")
+        .or_else(|| value.get("error_description"))
+        .and_then(|v| v.as_str())
+        .map(String::from)
+}
+
+/// Exchange a signed JWT for an access token (service account flow).
+///
+/// The JWT is signed with `openssl`, so no crypto library is linked in.
+fn service_account_token(key_path: &Path) -> Result<String, SourceError> {
+    let content = std::fs::read_to_string(key_path)
+        .map_err(|e| SourceError::ExecutionError(format!("{}: {}", key_path.display(), e)))?;
+    let key: ServiceAccountKey = serde_json::from_str(&content)
+        .map_err(|e| SourceError::ParseError(format!("{}: {}", key_path.display(), e)))?;
+
+    let now = chrono::Utc::now().timestamp();
+    let unsigned = jwt_signing_input(&key, now);
+
+    // tempfile creates the file readable by the owner only
+    let mut key_file =
+        tempfile::NamedTempFile::new().map_err(|e| SourceError::ExecutionError(e.to_string()))?;
+    key_file
+        .write_all(key.private_key.as_bytes())
+        .map_err(|e| SourceError::ExecutionError(e.to_string()))?;
+
+    let mut child = Command::new("openssl")
+        .args(["dgst", "-sha256", "-sign"])
+        .arg(key_file.path())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| SourceError::ExecutionError(format!("failed to run openssl: {}", e)))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(unsigned.as_bytes())
+            .map_err(|e| SourceError::ExecutionError(e.to_string()))?;
+    }
+    let signed = child
+        .wait_with_output()
+        .map_err(|e| SourceError::ExecutionError(e.to_string()))?;
+    if !signed.status.success() {
+        return Err(SourceError::ExecutionError(format!(
+            "openssl signing failed: {}",
+            String::from_utf8_lossy(&signed.stderr).trim()
+        )));
+    }
+    let assertion = format!("{}.{}", unsigned, URL_SAFE_NO_PAD.encode(&signed.stdout));
+
+    // The assertion goes on stdin as the request body, keeping it out of the process list
+    let mut child = Command::new("curl")
+        .args([
+            "-sS",
+            "--fail-with-body",
+            "-X",
+            "POST",
+            "-H",
+            "Content-Type: application/x-www-form-urlencoded",
+            "--data-binary",
+            "@-",
+        ])
+        .arg(&key.token_uri)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| SourceError::ExecutionError(e.to_string()))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        write!(
+            stdin,
+            "grant_type=urn%3Aietf%3Aparams%3Aoauth%3Agrant-type%3Ajwt-bearer&assertion={}",
+            assertion
+        )
+        .map_err(|e| SourceError::ExecutionError(e.to_string()))?;
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|e| SourceError::ExecutionError(e.to_string()))?;
+    if !output.status.success() {
+        return Err(SourceError::SyncError(format!(
+            "service account token request failed: {}",
+            api_error(&output.stdout)
+                .unwrap_or_else(|| String::from_utf8_lossy(&output.stderr).trim().to_string())
+        )));
+    }
+
+    let response: TokenResponse = serde_json::from_slice(&output.stdout)
+        .map_err(|e| SourceError::ParseError(format!("token response: {}", e)))?;
+    Ok(response.access_token)
+}
+
+/// `header.claims` of the JWT, base64url encoded, valid for an hour from `now`.
+fn jwt_signing_input(key: &ServiceAccountKey, now: i64) -> String {
+    let header = serde_json::json!({"alg": "RS256", "typ": "JWT"});
+    let claims = serde_json::json!({
+        "iss": key.client_email,
+        "scope": DRIVE_SCOPE,
+        "aud": key.token_uri,
+        "iat": now,
+        "exp": now + 3600,
+    });
+    format!(
+        "{}.{}",
+        URL_SAFE_NO_PAD.encode(header.to_string()),
+        URL_SAFE_NO_PAD.encode(claims.to_string())
+    )
+}
+
+/// Folder name as a tag value: lowercase, runs of other characters as `-`.
+fn folder_tag_value(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+fn file_to_document(
+    file: &DriveFile,
+    kind: FileKind,
+    text: &str,
+    folders: &[String],
+    tags: &[Tag],
+) -> Option<Document> {
+    let mut content = format!("# {}\n\n", file.name);
+    if !folders.is_empty() {
+        content.push_str(&format!("Folder: {}\n\n", folders.join(" / ")));
+    }
+    content.push_str(text.trim());
+
+    if !has_meaningful_content(text) {
+        return None;
+    }
+
+    let url = file.web_view_link.clone().or_else(|| {
+        let kind = match kind {
+            FileKind::Doc => "document",
+            FileKind::Sheet => "spreadsheets",
+        };
+        Some(format!("https://docs.google.com/{}/d/{}", kind, file.id))
+    });
+    let source = Source::new(SourceType::GoogleDrive, file.id.clone(), url);
+    let checksum = calculate_checksum(&content);
+
+    let mut path = folders.to_vec();
+    path.push(file.name.clone());
+    let metadata = DocumentMetadata {
+        filename: None,
+        extension: None,
+        language: Some(match kind {
+            FileKind::Doc => "text".to_string(),
+            FileKind::Sheet => "csv".to_string(),
+        }),
+        title: Some(file.name.clone()),
+        path: Some(path.join(" > ")),
+        size_bytes: content.len() as u64,
+    };
+
+    let mut all_tags = tags.to_vec();
+    let mut tag_strs = vec![
+        "source:gdrive".to_string(),
+        format!("gdrive-type:{}", kind.tag_value()),
+    ];
+    if let Some(folder) = folders.last() {
+        tag_strs.push(format!("gdrive-folder:{}", folder_tag_value(folder)));
+    }
+    if let Some(owner) = file.owners.first() {
+        tag_strs.push(format!("author:{}", folder_tag_value(&owner.display_name)));
+    }
+    for tag_str in tag_strs {
+        if let Ok(tag) = tag_str.parse::<Tag>()
+            && !all_tags.contains(&tag)
+        {
+            all_tags.push(tag);
+        }
+    }
+
+    let created = file.created_time.as_deref().and_then(parse_timestamp);
+    let updated = file.modified_time.as_deref().and_then(parse_timestamp);
+    Some(
+        Document::new(content, source, all_tags, checksum, metadata)
+            .with_timestamps(created, updated),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(mime: &str) -> DriveFile {
+        DriveFile {
+            id: "1AbC-dEf_123".to_string(),
+            name: "Checkout Spec".to_string(),
+            mime_type: mime.to_string(),
+            web_view_link: None,
+            created_time: Some("2024-03-01T10:00:00.000Z".to_string()),
+            modified_time: Some("2024-04-02T12:30:00.000Z".to_string()),
+            owners: vec![DriveUser {
+                display_name: "Kim Minji".to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_gdrive_source_creation() {
+        let source = GoogleDriveSource::new();
+        assert_eq!(source.source_type(), SourceType::GoogleDrive);
+        assert_eq!(source.name(), "Google Drive");
+    }
+
+    #[test]
+    fn test_selection() {
+        let options = |query: Option<&str>, project: Option<&str>| SyncOptions {
+            query: query.map(String::from),
+            project: project.map(String::from),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            Selection::from_options(&options(None, None)),
+            Selection::All
+        );
+        assert_eq!(
+            Selection::from_options(&options(
+                Some("https://docs.google.com/document/d/1AbC-dEf_123/edit#heading=h.x"),
+                None
+            )),
+            Selection::File("1AbC-dEf_123".to_string())
+        );
+        assert_eq!(
+            Selection::from_options(&options(Some("1AbC-dEf_123"), None)),
+            Selection::File("1AbC-dEf_123".to_string())
+        );
+        assert_eq!(
+            Selection::from_options(&options(Some("name contains 'spec'"), None)),
+            Selection::Search("name contains 'spec'".to_string())
+        );
+        assert_eq!(
+            Selection::from_options(&options(
+                None,
+                Some("https://drive.google.com/drive/u/0/folders/0BxFolder, 0BxOther")
+            )),
+            Selection::Folders(vec!["0BxFolder".to_string(), "0BxOther".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_file_to_document() {
+        let folders = vec!["Product".to_string(), "Payments Team".to_string()];
+        let doc = file_to_document(
+            &file(DOCUMENT_MIME),
+            FileKind::Doc,
+            "\u{feff}Overview\n\nThe checkout flow charges the card once the order is confirmed.",
+            &folders,
+            &[],
+        )
+        .unwrap();
+
+        assert!(
+            doc.content
+                .starts_with("# Checkout Spec\n\nFolder: Product / Payments Team\n\n")
+        );
+        assert_eq!(
+            doc.source.url.as_deref(),
+            Some("https://docs.google.com/document/d/1AbC-dEf_123")
+        );
+        assert_eq!(
+            doc.metadata.path.as_deref(),
+            Some("Product > Payments Team > Checkout Spec")
+        );
+        let tags: Vec<String> = doc.tags.iter().map(ToString::to_string).collect();
+        assert!(tags.contains(&"gdrive-type:doc".to_string()));
+        assert!(tags.contains(&"gdrive-folder:payments-team".to_string()));
+        assert!(tags.contains(&"author:kim-minji".to_string()));
+        assert!(doc.updated_at.starts_with("2024-04-02T12:30:00"));
+
+        assert!(
+            file_to_document(&file(SPREADSHEET_MIME), FileKind::Sheet, " ", &[], &[]).is_none()
+        );
+    }
+
+    #[test]
+    fn test_jwt_signing_input() {
+        let key = ServiceAccountKey {
+            client_email: "indexer@project.iam.gserviceaccount.com".to_string(),
+            private_key: String::new(),
+            token_uri: "https://oauth2.googleapis.com/token".to_string(),
+        };
+        let input = jwt_signing_input(&key, 1_700_000_000);
+        let (header, claims) = input.split_once('.').unwrap();
+        let claims: serde_json::Value =
+            serde_json::from_slice(&URL_SAFE_NO_PAD.decode(claims).unwrap()).unwrap();
+        assert_eq!(
+            URL_SAFE_NO_PAD.decode(header).unwrap(),
+            br#"{"alg":"RS256","typ":"JWT"}"#
+        );
+        assert_eq!(claims["scope"], DRIVE_SCOPE);
+        assert_eq!(claims["exp"], 1_700_003_600);
+    }
+}
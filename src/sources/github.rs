@@ -0,0 +1,576 @@
+use std::process::Command;
+
+use serde::Deserialize;
+use tracing::{info, warn};
+
+use crate::error::SourceError;
+use crate::models::{Document, DocumentMetadata, Source, SourceType, Tag, parse_timestamp};
+use crate::sources::SyncOptions;
+use crate::utils::file::calculate_checksum;
+use crate::utils::has_meaningful_content;
+
+/// Item limit passed to `gh ... list` when syncing everything
+const ALL_ITEMS_LIMIT: u32 = 100_000;
+
+/// Discussions fetched per GraphQL page
+const DISCUSSION_PAGE_SIZE: u32 = 50;
+
+const ISSUE_FIELDS: &str = "number,title,body,state,url,author,labels,comments,createdAt,updatedAt";
+const PR_FIELDS: &str =
+    "number,title,body,state,url,author,labels,comments,reviews,createdAt,updatedAt";
+
+const DISCUSSIONS_QUERY: &str = r#"
+query($owner: String!, $name: String!, $first: Int!, $endCursor: String) {
+  repository(owner: $owner, name: $name) {
+    discussions(first: $first, after: $endCursor, orderBy: {field: UPDATED_AT, direction: DESC}) {
+      nodes {
+        number
+        title
+        body
+        url
+        closed
+        createdAt
+        updatedAt
+        author { login }
+        category { name }
+        comments(first: 50) { nodes { author { login } body } }
+      }
+      pageInfo { hasNextPage endCursor }
+    }
+  }
+}
+"#;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ItemKind {
+    Issue,
+    PullRequest,
+    Discussion,
+}
+
+impl ItemKind {
+    fn tag_value(self) -> &'static str {
+        match self {
+            ItemKind::Issue => "issue",
+            ItemKind::PullRequest => "pr",
+            ItemKind::Discussion => "discussion",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            ItemKind::Issue => "Issues",
+            ItemKind::PullRequest => "Pull Requests",
+            ItemKind::Discussion => "Discussions",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Author {
+    login: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Label {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Comment {
+    author: Option<Author>,
+    #[serde(default)]
+    body: String,
+}
+
+/// Issue or pull request as returned by `gh issue list` / `gh pr list`.
+#[derive(Debug, Deserialize)]
+struct GitHubItem {
+    number: u64,
+    title: String,
+    #[serde(default)]
+    body: Option<String>,
+    state: String,
+    url: String,
+    author: Option<Author>,
+    #[serde(default)]
+    labels: Vec<Label>,
+    #[serde(default)]
+    comments: Vec<Comment>,
+    #[serde(default)]
+    reviews: Vec<Comment>,
+    #[serde(default, rename = "createdAt")]
+    created_at: Option<String>,
+    #[serde(default, rename = "updatedAt")]
+    updated_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DiscussionsResponse {
+    data: DiscussionsData,
+}
+
+#[derive(Debug, Deserialize)]
+struct DiscussionsData {
+    repository: Option<DiscussionsRepository>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DiscussionsRepository {
+    discussions: DiscussionConnection,
+}
+
+#[derive(Debug, Deserialize)]
+struct DiscussionConnection {
+    nodes: Vec<Discussion>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Discussion {
+    number: u64,
+    title: String,
+    #[serde(default)]
+    body: Option<String>,
+    url: String,
+    closed: bool,
+    #[serde(default, rename = "createdAt")]
+    created_at: Option<String>,
+    #[serde(default, rename = "updatedAt")]
+    updated_at: Option<String>,
+    author: Option<Author>,
+    category: Option<Label>,
+    comments: Option<CommentConnection>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CommentConnection {
+    nodes: Vec<Comment>,
+}
+
+impl From<Discussion> for GitHubItem {
+    fn from(discussion: Discussion) -> Self {
+        Self {
+            number: discussion.number,
+            title: discussion.title,
+            body: discussion.body,
+            state: if discussion.closed { "closed" } else { "open" }.to_string(),
+            url: discussion.url,
+            author: discussion.author,
+            labels: discussion.category.into_iter().collect(),
+            comments: discussion.comments.map(|c| c.nodes).unwrap_or_default(),
+            reviews: Vec::new(),
+            created_at: discussion.created_at,
+            updated_at: discussion.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct GitHubSource;
+
+impl GitHubSource {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn source_type(&self) -> SourceType {
+        SourceType::GitHub
+    }
+
+    pub fn name(&self) -> &str {
+        "GitHub"
+    }
+
+    pub fn check_available(&self) -> Result<bool, SourceError> {
+        Command::new("which")
+            .arg("gh")
+            .output()
+            .map(|o| o.status.success())
+            .map_err(|e| SourceError::ExecutionError(e.to_string()))
+    }
+
+    pub fn install_instructions(&self) -> &str {
+        "Install GitHub CLI (https://cli.github.com) and run: gh auth login"
+    }
+
+    /// Sync issues, pull requests, and discussions of a repository.
+    ///
+    /// `options.project` selects the repository (`owner/repo`, defaults to the
+    /// current directory's repository). `options.query` is passed to GitHub
+    /// search for issues and pull requests; discussions are skipped when set.
+    pub fn sync(&self, options: SyncOptions) -> Result<Vec<Document>, SourceError> {
+        let mut documents = Vec::new();
+        self.sync_streaming(options, |doc| {
+            documents.push(doc);
+            Ok(())
+        })?;
+        Ok(documents)
+    }
+
+    /// Sync items, handing each document to `on_document` as soon as it is built.
+    pub fn sync_streaming<F>(
+        &self,
+        options: SyncOptions,
+        mut on_document: F,
+    ) -> Result<u64, SourceError>
+    where
+        F: FnMut(Document) -> Result<(), SourceError>,
+    {
+        if !self.check_available()? {
+            return Err(SourceError::CliNotFound(
+                "gh not found. Install from: https://cli.github.com".to_string(),
+            ));
+        }
+
+        let repo = match options.project {
+            Some(ref repo) => repo.clone(),
+            None => current_repo()?,
+        };
+        let (owner, name) = split_repo(&repo)?;
+
+        let mut count = 0u64;
+        let mut skipped = 0u64;
+
+        for kind in [ItemKind::Issue, ItemKind::PullRequest] {
+            for item in self.list_items(kind, &repo, &options)? {
+                match item_to_document(item, kind, &repo, &options.tags) {
+                    Ok(doc) => {
+                        on_document(doc)?;
+                        count += 1;
+                    }
+                    Err(_) => skipped += 1,
+                }
+            }
+        }
+
+        if options.query.is_some() {
+            info!("skipping discussions (--query applies to issues and pull requests)");
+        } else {
+            match self.list_discussions(owner, name, options.limit) {
+                Ok(discussions) => {
+                    for item in discussions {
+                        match item_to_document(item, ItemKind::Discussion, &repo, &options.tags) {
+                            Ok(doc) => {
+                                on_document(doc)?;
+                                count += 1;
+                            }
+                            Err(_) => skipped += 1,
+                        }
+                    }
+                }
+                Err(e) => warn!(error = %e, "failed to fetch discussions"),
+            }
+        }
+
+        if skipped > 0 {
+            info!(skipped, "skipped items (empty content)");
+        }
+
+        Ok(count)
+    }
+
+    fn list_items(
+        &self,
+        kind: ItemKind,
+        repo: &str,
+        options: &SyncOptions,
+    ) -> Result<Vec<GitHubItem>, SourceError> {
+        let (command, fields) = match kind {
+            ItemKind::PullRequest => ("pr", PR_FIELDS),
+            _ => ("issue", ISSUE_FIELDS),
+        };
+        let limit = options.limit.unwrap_or(ALL_ITEMS_LIMIT).to_string();
+
+        let mut args = vec![
+            command, "list", "--repo", repo, "--state", "all", "--limit", &limit, "--json", fields,
+        ];
+        if let Some(ref query) = options.query {
+            args.extend(["--search", query.as_str()]);
+        }
+
+        info!(args = %args.join(" "), "running gh");
+
+        let output = Command::new("gh")
+            .args(&args)
+            .output()
+            .map_err(|e| SourceError::ExecutionError(e.to_string()))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(SourceError::ExecutionError(format!(
+                "gh {} list failed: {}",
+                command,
+                stderr.trim()
+            )));
+        }
+
+        serde_json::from_slice(&output.stdout).map_err(|e| {
+            SourceError::ParseError(format!("failed to parse {} list: {}", command, e))
+        })
+    }
+
+    fn list_discussions(
+        &self,
+        owner: &str,
+        name: &str,
+        limit: Option<u32>,
+    ) -> Result<Vec<GitHubItem>, SourceError> {
+        let first = limit.map_or(DISCUSSION_PAGE_SIZE, |l| l.min(100));
+        let query_arg = format!("query={}", DISCUSSIONS_QUERY);
+        let owner_arg = format!("owner={}", owner);
+        let name_arg = format!("name={}", name);
+        let first_arg = format!("first={}", first);
+
+        let mut args = vec![
+            "api", "graphql", "-f", &query_arg, "-f", &owner_arg, "-f", &name_arg, "-F", &first_arg,
+        ];
+        if limit.is_none() {
+            args.push("--paginate");
+        }
+
+        info!(repo = %format!("{owner}/{name}"), "running gh api graphql for discussions");
+
+        let output = Command::new("gh")
+            .args(&args)
+            .output()
+            .map_err(|e| SourceError::ExecutionError(e.to_string()))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(SourceError::ExecutionError(stderr.trim().to_string()));
+        }
+
+        let mut items = parse_discussions(&output.stdout)?;
+        if let Some(limit) = limit {
+            items.truncate(limit as usize);
+        }
+        Ok(items)
+    }
+}
+
+impl Default for GitHubSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn current_repo() -> Result<String, SourceError> {
+    let output = Command::new("gh")
+        .args([
+            "repo",
+            "view",
+            "--json",
+            "nameWithOwner",
+            "--jq",
+            ".nameWithOwner",
+        ])
+        .output()
+        .map_err(|e| SourceError::ExecutionError(e.to_string()))?;
+
+    if !output.status.success() {
+        return Err(SourceError::SyncError(
+            "no repository given; use --project owner/repo".to_string(),
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn split_repo(repo: &str) -> Result<(&str, &str), SourceError> {
+    repo.split_once('/')
+        .filter(|(owner, name)| !owner.is_empty() && !name.is_empty() && !name.contains('/'))
+        .ok_or_else(|| {
+            SourceError::SyncError(format!(
+                "invalid repository '{}', expected owner/repo",
+                repo
+            ))
+        })
+}
+
+/// Parse GraphQL discussion pages (`--paginate` prints one JSON object per page).
+fn parse_discussions(output: &[u8]) -> Result<Vec<GitHubItem>, SourceError> {
+    let mut items = Vec::new();
+
+    for page in serde_json::Deserializer::from_slice(output).into_iter::<DiscussionsResponse>() {
+        let page = page
+            .map_err(|e| SourceError::ParseError(format!("failed to parse discussions: {}", e)))?;
+        if let Some(repository) = page.data.repository {
+            items.extend(
+                repository
+                    .discussions
+                    .nodes
+                    .into_iter()
+                    .map(GitHubItem::from),
+            );
+        }
+    }
+
+    Ok(items)
+}
+
+fn item_to_document(
+    item: GitHubItem,
+    kind: ItemKind,
+    repo: &str,
+    tags: &[Tag],
+) -> Result<Document, SourceError> {
+    let body = item.body.as_deref().unwrap_or("").trim();
+    let author = item.author.as_ref().map_or("ghost", |a| a.login.as_str());
+    let state = item.state.to_lowercase();
+
+    let mut content = format!(
+        "# {}\n\n{} #{} in {} ({}) by @{}",
+        item.title,
+        kind.label().trim_end_matches('s'),
+        item.number,
+        repo,
+        state,
+        author
+    );
+    if !body.is_empty() {
+        content.push_str("\n\n");
+        content.push_str(body);
+    }
+    append_comments(&mut content, "Reviews", &item.reviews);
+    append_comments(&mut content, "Comments", &item.comments);
+
+    if !has_meaningful_content(&content) {
+        return Err(SourceError::ParseError(format!(
+            "{}#{} has no meaningful content",
+            repo, item.number
+        )));
+    }
+
+    let location = format!("{}#{}", repo, item.number);
+    let source = Source::with_url(SourceType::GitHub, location, item.url);
+    let checksum = calculate_checksum(&content);
+
+    let metadata = DocumentMetadata {
+        filename: Some(format!("{}.md", item.number)),
+        extension: Some("md".to_string()),
+        language: Some("markdown".to_string()),
+        title: Some(item.title.clone()),
+        path: Some(format!("{} > {} > {}", repo, kind.label(), item.title)),
+        size_bytes: content.len() as u64,
+    };
+
+    let (owner, name) = split_repo(repo)?;
+    let mut all_tags = tags.to_vec();
+    let generated = [
+        "source:github".to_string(),
+        format!("gh-owner:{}", owner.to_lowercase()),
+        format!("gh-repo:{}", name.to_lowercase()),
+        format!("gh-type:{}", kind.tag_value()),
+        format!("gh-state:{}", state),
+    ];
+    let labels = item
+        .labels
+        .iter()
+        .map(|l| format!("gh-label:{}", l.name.to_lowercase().replace(' ', "-")));
+    for tag_str in generated.into_iter().chain(labels) {
+        if let Ok(tag) = tag_str.parse::<Tag>()
+            && !all_tags.contains(&tag)
+        {
+            all_tags.push(tag);
+        }
+    }
+
+    let created = item.created_at.as_deref().and_then(parse_timestamp);
+    let updated = item.updated_at.as_deref().and_then(parse_timestamp);
+    Ok(Document::new(content, source, all_tags, checksum, metadata)
+        .with_timestamps(created, updated))
+}
+
+fn append_comments(content: &mut String, heading: &str, comments: &[Comment]) {
+    let comments: Vec<&Comment> = comments
+        .iter()
+        .filter(|c| !c.body.trim().is_empty())
+        .collect();
+    if comments.is_empty() {
+        return;
+    }
+
+    content.push_str(&format!("\n\n## {}\n", heading));
+    for comment in comments {
+        let author = comment
+            .author
+            .as_ref()
+            .map_or("ghost", |a| a.login.as_str());
+        content.push_str(&format!("\n**@{}**: {}\n", author, comment.body.trim()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_github_source_creation() {
+        let source = GitHubSource::new();
+        assert_eq!(source.source_type(), SourceType::GitHub);
+        assert_eq!(source.name(), "GitHub");
+    }
+
+    #[test]
+    fn test_split_repo() {
+        assert_eq!(split_repo("rust-lang/rust").unwrap(), ("rust-lang", "rust"));
+        assert!(split_repo("rust").is_err());
+        assert!(split_repo("a/b/c").is_err());
+        assert!(split_repo("/rust").is_err());
+    }
+
+    #[test]
+    fn test_item_to_document() {
+        let json = r#"{
+            "number": 42,
+            "title": "Crash on empty config",
+            "body": "Running with an empty config file panics.",
+            "state": "MERGED",
+            "url": "https://github.com/acme/widgets/pull/42",
+            "author": {"login": "alice"},
+            "labels": [{"name": "Bug Fix"}],
+            "comments": [{"author": {"login": "bob"}, "body": "Thanks!"}],
+            "reviews": [{"author": {"login": "carol"}, "body": "", "state": "APPROVED"}]
+        }"#;
+        let item: GitHubItem = serde_json::from_str(json).unwrap();
+
+        let doc = item_to_document(item, ItemKind::PullRequest, "acme/widgets", &[]).unwrap();
+
+        assert_eq!(doc.source.location, "acme/widgets#42");
+        assert_eq!(
+            doc.source.url.as_deref(),
+            Some("https://github.com/acme/widgets/pull/42")
+        );
+        assert!(doc.content.contains("**@bob**: Thanks!"));
+        assert!(!doc.content.contains("## Reviews"));
+
+        let tags: Vec<String> = doc.tags.iter().map(ToString::to_string).collect();
+        for expected in [
+            "source:github",
+            "gh-repo:widgets",
+            "gh-type:pr",
+            "gh-state:merged",
+            "gh-label:bug-fix",
+        ] {
+            assert!(tags.contains(&expected.to_string()), "missing {expected}");
+        }
+    }
+
+    #[test]
+    fn test_parse_discussion_pages() {
+        let page = |n: u64| {
+            format!(
+                r#"{{"data":{{"repository":{{"discussions":{{"nodes":[{{"number":{n},"title":"Q","body":"How?","url":"u","closed":{closed},"author":null,"category":{{"name":"Q&A"}},"comments":{{"nodes":[]}}}}],"pageInfo":{{"hasNextPage":false,"endCursor":null}}}}}}}}}}"#,
+                closed = n.is_multiple_of(2)
+            )
+        };
+        let output = format!("{}{}", page(1), page(2));
+
+        let items = parse_discussions(output.as_bytes()).unwrap();
+
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].state, "open");
+        assert_eq!(items[1].state, "closed");
+        assert_eq!(items[0].labels[0].name, "Q&A");
+    }
+}
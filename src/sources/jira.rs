@@ -2,9 +2,10 @@ use std::io::{BufRead, BufReader};
 use std::process::{Command, Stdio};
 
 use serde::Deserialize;
+use tracing::{info, warn};
 
 use crate::error::SourceError;
-use crate::models::{Document, DocumentMetadata, Source, SourceType, Tag};
+use crate::models::{Document, DocumentMetadata, Source, SourceType, Tag, parse_timestamp};
 use crate::sources::SyncOptions;
 use crate::utils::file::calculate_checksum;
 use crate::utils::has_meaningful_content;
@@ -23,6 +24,8 @@ struct JiraFields {
     status: Option<Status>,
     project: Option<Project>,
     parent: Option<Parent>,
+    created: Option<String>,
+    updated: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -52,7 +55,7 @@ struct ParentFields {
     summary: Option<String>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub struct JiraSource;
 
 impl JiraSource {
@@ -136,7 +139,7 @@ impl JiraSource {
             "jira", "search", jql, "--format", "markdown", "--all", "--stream",
         ];
 
-        eprintln!("Running: atlassian-cli {}", args.join(" "));
+        info!(args = %args.join(" "), "running atlassian-cli");
 
         let mut child = Command::new("atlassian-cli")
             .args(args)
@@ -170,7 +173,7 @@ impl JiraSource {
                     on_document(doc)?;
                     count += 1;
                     if count.is_multiple_of(50) {
-                        eprintln!("  Processed {} issues...", count);
+                        info!(count, "processed issues");
                     }
                 }
                 Err(_) => skipped += 1,
@@ -190,12 +193,12 @@ impl JiraSource {
                 })
                 .unwrap_or_default();
             if !stderr.is_empty() {
-                eprintln!("Warning: {}", stderr.trim());
+                warn!(stderr = %stderr.trim(), "atlassian-cli reported errors");
             }
         }
 
         if skipped > 0 {
-            eprintln!("  Skipped {} issues (empty content)", skipped);
+            info!(skipped, "skipped issues (empty content)");
         }
 
         Ok(count)
@@ -216,7 +219,7 @@ impl JiraSource {
             "jira", "search", jql, "--format", "markdown", "--limit", &limit_str,
         ];
 
-        eprintln!("Running: atlassian-cli {}", args.join(" "));
+        info!(args = %args.join(" "), "running atlassian-cli");
 
         let output = Command::new("atlassian-cli")
             .args(args)
@@ -253,7 +256,7 @@ impl JiraSource {
         }
 
         if skipped > 0 {
-            eprintln!("  Skipped {} issues (empty content)", skipped);
+            info!(skipped, "skipped issues (empty content)");
         }
 
         Ok(count)
@@ -336,7 +339,10 @@ impl JiraSource {
             all_tags.push(tag);
         }
 
-        Ok(Document::new(content, source, all_tags, checksum, metadata))
+        let created = issue.fields.created.as_deref().and_then(parse_timestamp);
+        let updated = issue.fields.updated.as_deref().and_then(parse_timestamp);
+        Ok(Document::new(content, source, all_tags, checksum, metadata)
+            .with_timestamps(created, updated))
     }
 }
 
@@ -451,6 +457,8 @@ mod tests {
                         summary: Some("Parent Epic".to_string()),
                     }),
                 }),
+                created: None,
+                updated: None,
             },
         };
 
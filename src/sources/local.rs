@@ -1,11 +1,12 @@
 //! Local file system data source.
 
 use std::path::{Path, PathBuf};
-use walkdir::WalkDir;
 
 use crate::error::SourceError;
 use crate::models::{Document, DocumentMetadata, Source, SourceType, Tag};
-use crate::utils::file::{calculate_checksum, is_text_file, read_file_content};
+use crate::utils::file::{
+    calculate_checksum, detect_language, is_text_file, read_file_content, walk_files,
+};
 
 /// Local file system data source.
 #[derive(Debug)]
@@ -18,6 +19,9 @@ pub struct LocalSource {
 
     /// Maximum file size
     max_file_size: u64,
+
+    /// Skip files excluded by .gitignore, .ignore and .ssearchignore
+    respect_ignore: bool,
 }
 
 impl LocalSource {
@@ -27,26 +31,27 @@ impl LocalSource {
             root,
             exclude_patterns,
             max_file_size,
+            respect_ignore: true,
         }
     }
 
+    /// Set whether ignore files are honored (on by default).
+    #[must_use]
+    pub fn with_ignore_files(mut self, respect_ignore: bool) -> Self {
+        self.respect_ignore = respect_ignore;
+        self
+    }
+
     /// Collect all indexable files from the source.
     pub fn collect_files(&self) -> Result<Vec<PathBuf>, SourceError> {
-        let mut files = Vec::new();
-
         if self.root.is_file() {
-            files.push(self.root.clone());
-            return Ok(files);
+            return Ok(vec![self.root.clone()]);
         }
 
-        for entry in WalkDir::new(&self.root).follow_links(false) {
-            let entry = entry.map_err(|e| SourceError::SyncError(e.to_string()))?;
-            let path = entry.path();
-
-            if !path.is_file() {
-                continue;
-            }
-
+        let mut files = Vec::new();
+        let walked = walk_files(&self.root, self.respect_ignore)
+            .map_err(|e| SourceError::SyncError(e.to_string()))?;
+        for path in walked {
             // Check exclusions
             let path_str = path.to_string_lossy();
             let mut excluded = false;
@@ -61,8 +66,8 @@ impl LocalSource {
                 }
             }
 
-            if !excluded && is_text_file(path) {
-                files.push(path.to_path_buf());
+            if !excluded && is_text_file(&path) {
+                files.push(path);
             }
         }
 
@@ -94,54 +99,3 @@ impl LocalSource {
         SourceType::Local
     }
 }
-
-/// Detect programming language from file extension.
-fn detect_language(path: &Path) -> Option<String> {
-    path.extension().and_then(|ext| {
-        let ext = ext.to_string_lossy().to_lowercase();
-        match ext.as_str() {
-            "rs" => Some("rust"),
-            "py" => Some("python"),
-            "js" | "jsx" => Some("javascript"),
-            "ts" | "tsx" => Some("typescript"),
-            "go" => Some("go"),
-            "java" => Some("java"),
-            "kt" | "kts" => Some("kotlin"),
-            "c" | "h" => Some("c"),
-            "cpp" | "hpp" | "cc" | "cxx" => Some("cpp"),
-            "rb" => Some("ruby"),
-            "php" => Some("php"),
-            "swift" => Some("swift"),
-            "scala" => Some("scala"),
-            "sh" | "bash" => Some("shell"),
-            "sql" => Some("sql"),
-            "html" | "htm" => Some("html"),
-            "css" | "scss" | "sass" => Some("css"),
-            "json" => Some("json"),
-            "yaml" | "yml" => Some("yaml"),
-            "toml" => Some("toml"),
-            "xml" => Some("xml"),
-            "md" | "markdown" => Some("markdown"),
-            _ => None,
-        }
-        .map(String::from)
-    })
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_detect_language() {
-        assert_eq!(
-            detect_language(Path::new("test.rs")),
-            Some("rust".to_string())
-        );
-        assert_eq!(
-            detect_language(Path::new("test.py")),
-            Some("python".to_string())
-        );
-        assert_eq!(detect_language(Path::new("test.unknown")), None);
-    }
-}
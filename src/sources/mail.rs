@@ -0,0 +1,872 @@
+//! Mailing list archives from mbox files or an IMAP folder (via `curl`).
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::LazyLock;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use tracing::{info, warn};
+
+use crate::error::SourceError;
+use crate::models::{Document, DocumentMetadata, Source, SourceType, Tag};
+use crate::sources::SyncOptions;
+use crate::utils::file::calculate_checksum;
+use crate::utils::has_meaningful_content;
+
+/// Environment variable with the IMAP user name (if not in the URL)
+pub const MAIL_USERNAME_ENV: &str = "MAIL_USERNAME";
+
+/// Environment variable with the IMAP password
+pub const MAIL_PASSWORD_ENV: &str = "MAIL_PASSWORD";
+
+/// Nested multipart bodies deeper than this are ignored.
+const MAX_MIME_DEPTH: usize = 8;
+
+static RE_ENCODED_WORD: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"=\?([^?]+)\?([BbQq])\?([^?]*)\?=").unwrap());
+static RE_MESSAGE_ID: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<([^<>\s]+)>").unwrap());
+static RE_HTML_TAG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?s)<[^>]*>").unwrap());
+static RE_ATTRIBUTION: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^On .+ wrote:$").unwrap());
+
+/// One parsed email.
+#[derive(Debug, Clone)]
+struct MailMessage {
+    message_id: String,
+    in_reply_to: Option<String>,
+    references: Vec<String>,
+    subject: String,
+    from_name: String,
+    from_address: String,
+    date: Option<DateTime<Utc>>,
+    list_id: Option<String>,
+    body: String,
+}
+
+/// Messages of one thread, oldest first.
+#[derive(Debug)]
+struct MailThread {
+    root_id: String,
+    messages: Vec<MailMessage>,
+}
+
+impl MailThread {
+    fn latest(&self) -> Option<DateTime<Utc>> {
+        self.messages.iter().filter_map(|m| m.date).max()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct MailSource;
+
+impl MailSource {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn source_type(&self) -> SourceType {
+        SourceType::Mail
+    }
+
+    pub fn name(&self) -> &str {
+        "Email"
+    }
+
+    pub fn check_available(&self) -> Result<bool, SourceError> {
+        Command::new("which")
+            .arg("curl")
+            .output()
+            .map(|o| o.status.success())
+            .map_err(|e| SourceError::ExecutionError(e.to_string()))
+    }
+
+    pub fn install_instructions(&self) -> &str {
+        "Pass an mbox file or directory with --query, or an IMAP folder URL \
+         (imaps://host/INBOX) with MAIL_USERNAME and MAIL_PASSWORD set (requires curl)"
+    }
+
+    /// Sync email threads as documents.
+    ///
+    /// `options.query` is an mbox file, a directory of mbox files, or an
+    /// `imap://` / `imaps://` folder URL. Messages are grouped into threads by
+    /// `References` and `In-Reply-To`. `options.limit` caps threads (most
+    /// recently active first).
+    pub fn sync(&self, options: SyncOptions) -> Result<Vec<Document>, SourceError> {
+        let mut documents = Vec::new();
+        self.sync_streaming(options, |doc| {
+            documents.push(doc);
+            Ok(())
+        })?;
+        Ok(documents)
+    }
+
+    /// Sync threads, handing each document to `on_document` as soon as it is built.
+    pub fn sync_streaming<F>(
+        &self,
+        options: SyncOptions,
+        mut on_document: F,
+    ) -> Result<u64, SourceError>
+    where
+        F: FnMut(Document) -> Result<(), SourceError>,
+    {
+        let query = options.query.as_deref().map(str::trim).ok_or_else(|| {
+            SourceError::SyncError(
+                "pass an mbox path or an IMAP folder URL with --query".to_string(),
+            )
+        })?;
+
+        let raw_messages = if query.starts_with("imap://") || query.starts_with("imaps://") {
+            fetch_imap(query)?
+        } else {
+            read_mbox_path(Path::new(query))?
+        };
+
+        let messages: Vec<MailMessage> =
+            raw_messages.iter().map(|raw| parse_message(raw)).collect();
+        let mut threads = group_threads(messages);
+        threads.sort_by_key(|thread| std::cmp::Reverse(thread.latest()));
+        if let Some(limit) = options.limit {
+            threads.truncate(limit as usize);
+        }
+        info!(
+            messages = raw_messages.len(),
+            threads = threads.len(),
+            "grouped email threads"
+        );
+
+        let mut count = 0u64;
+        let mut skipped = 0u64;
+        for thread in &threads {
+            match thread_to_document(thread, &options.tags) {
+                Some(doc) => {
+                    on_document(doc)?;
+                    count += 1;
+                }
+                None => skipped += 1,
+            }
+        }
+
+        if skipped > 0 {
+            info!(skipped, "skipped threads (empty content)");
+        }
+
+        Ok(count)
+    }
+}
+
+impl Default for MailSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Raw messages from an mbox file, or every file in a directory.
+fn read_mbox_path(path: &Path) -> Result<Vec<String>, SourceError> {
+    let files = if path.is_dir() {
+        let mut files: Vec<_> = std::fs::read_dir(path)
+            .map_err(|e| SourceError::ExecutionError(format!("{}: {}", path.display(), e)))?
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|p| p.is_file())
+            .collect();
+        files.sort();
+        files
+    } else if path.is_file() {
+        vec![path.to_path_buf()]
+    } else {
+        return Err(SourceError::SyncError(format!(
+            "mbox file or directory not found: {}",
+            path.display()
+        )));
+    };
+
+    let mut messages = Vec::new();
+    for file in files {
+        info!(file = %file.display(), "reading mbox");
+        let bytes = std::fs::read(&file)
+            .map_err(|e| SourceError::ExecutionError(format!("{}: {}", file.display(), e)))?;
+        messages.extend(split_mbox(&String::from_utf8_lossy(&bytes)));
+    }
+    Ok(messages)
+}
+
+/// Split an mbox into messages at `From ` separator lines, undoing `>From ` quoting.
+fn split_mbox(content: &str) -> Vec<String> {
+    let mut messages = Vec::new();
+    let mut current: Option<String> = None;
+    let mut previous_blank = true;
+
+    for line in content.lines() {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        if previous_blank && line.starts_with("From ") {
+            if let Some(message) = current.take() {
+                messages.push(message);
+            }
+            current = Some(String::new());
+        } else if let Some(ref mut message) = current {
+            let unquoted = match line.strip_prefix('>') {
+                Some(rest) if rest.trim_start_matches('>').starts_with("From ") => rest,
+                _ => line,
+            };
+            message.push_str(unquoted);
+            message.push('\n');
+        }
+        previous_blank = line.is_empty();
+    }
+    messages.extend(current);
+    messages
+}
+
+/// Fetch every message in an IMAP folder with `curl`.
+///
+/// Credentials go to curl as a config file on stdin so they stay out of the
+/// process list.
+fn fetch_imap(url: &str) -> Result<Vec<String>, SourceError> {
+    let url = url.trim_end_matches('/');
+    let credentials = match (
+        std::env::var(MAIL_USERNAME_ENV),
+        std::env::var(MAIL_PASSWORD_ENV),
+    ) {
+        (Ok(user), Ok(password)) => Some(format!("{}:{}", user, password)),
+        (Err(_), Ok(password)) => Some(format!(":{}", password)),
+        _ => None,
+    };
+
+    let search = imap_request(url, Some("UID SEARCH ALL"), credentials.as_deref())?;
+    let uids = parse_search_response(&search);
+    info!(url, messages = uids.len(), "fetching IMAP folder");
+
+    let mut messages = Vec::with_capacity(uids.len());
+    for uid in uids {
+        match imap_request(
+            &format!("{}/;UID={}", url, uid),
+            None,
+            credentials.as_deref(),
+        ) {
+            Ok(message) => messages.push(message),
+            Err(e) => warn!(uid, error = %e, "failed to fetch message"),
+        }
+    }
+    Ok(messages)
+}
+
+fn imap_request(
+    url: &str,
+    command: Option<&str>,
+    credentials: Option<&str>,
+) -> Result<String, SourceError> {
+    let mut cmd = Command::new("curl");
+    cmd.args(["-sS", "-K", "-"]);
+    if let Some(command) = command {
+        cmd.args(["-X", command]);
+    }
+    let mut child = cmd
+        .arg(url)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| SourceError::ExecutionError(format!("failed to run curl: {}", e)))?;
+    if let Some(mut stdin) = child.stdin.take()
+        && let Some(credentials) = credentials
+    {
+        let escaped = credentials.replace('\\', "\\\\").replace('"', "\\\"");
+        writeln!(stdin, "user = \"{}\"", escaped)
+            .map_err(|e| SourceError::ExecutionError(e.to_string()))?;
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|e| SourceError::ExecutionError(e.to_string()))?;
+
+    if !output.status.success() {
+        return Err(SourceError::SyncError(format!(
+            "IMAP request failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// UIDs from a `* SEARCH 1 2 3` response.
+fn parse_search_response(response: &str) -> Vec<u32> {
+    response
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("* SEARCH"))
+        .flat_map(|rest| rest.split_whitespace().filter_map(|uid| uid.parse().ok()))
+        .collect()
+}
+
+/// Header fields (lowercase names, folded lines joined) and the raw body.
+fn split_headers(raw: &str) -> (HashMap<String, String>, &str) {
+    let (head, body) = raw
+        .split_once("\r\n\r\n")
+        .or_else(|| raw.split_once("\n\n"))
+        .unwrap_or((raw, ""));
+
+    let mut headers: HashMap<String, String> = HashMap::new();
+    let mut last: Option<String> = None;
+    for line in head.lines() {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        if line.starts_with([' ', '\t']) {
+            if let Some(value) = last.as_ref().and_then(|name| headers.get_mut(name)) {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+        } else if let Some((name, value)) = line.split_once(':') {
+            let name = name.trim().to_lowercase();
+            // Keep the first occurrence, which is the one the sender wrote
+            headers
+                .entry(name.clone())
+                .or_insert_with(|| value.trim().to_string());
+            last = Some(name);
+        }
+    }
+    (headers, body)
+}
+
+fn parse_message(raw: &str) -> MailMessage {
+    let (headers, body) = split_headers(raw);
+    let header = |name: &str| headers.get(name).map(String::as_str).unwrap_or("");
+
+    let message_ids = |value: &str| -> Vec<String> {
+        RE_MESSAGE_ID
+            .captures_iter(value)
+            .map(|caps| caps[1].to_string())
+            .collect()
+    };
+    let message_id = message_ids(header("message-id"))
+        .into_iter()
+        .next()
+        .unwrap_or_else(|| format!("{}@ssearch.local", &calculate_checksum(raw)[..16]));
+    let (from_name, from_address) = parse_address(&decode_header(header("from")));
+    let list_id = message_ids(header("list-id")).into_iter().next();
+
+    MailMessage {
+        message_id,
+        in_reply_to: message_ids(header("in-reply-to")).into_iter().next(),
+        references: message_ids(header("references")),
+        subject: decode_header(header("subject")),
+        from_name,
+        from_address,
+        date: parse_date(header("date")),
+        list_id,
+        body: clean_body(&extract_text(&headers, body, 0)),
+    }
+}
+
+/// RFC 2822 date, ignoring trailing comments like `(UTC)`.
+fn parse_date(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.split(" (").next().unwrap_or(value).trim();
+    DateTime::parse_from_rfc2822(value)
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+/// Display name and address from `"Name" <addr>` or a bare address.
+fn parse_address(value: &str) -> (String, String) {
+    match (value.rfind('<'), value.rfind('>')) {
+        (Some(start), Some(end)) if start < end => {
+            let address = value[start + 1..end].trim().to_lowercase();
+            let name = value[..start].trim().trim_matches('"').trim().to_string();
+            let name = if name.is_empty() {
+                address.clone()
+            } else {
+                name
+            };
+            (name, address)
+        }
+        _ => {
+            let address = value.trim().to_lowercase();
+            (address.clone(), address)
+        }
+    }
+}
+
+/// Decode RFC 2047 encoded words (`=?UTF-8?B?...?=`); other charsets are read as UTF-8.
+fn decode_header(value: &str) -> String {
+    // Whitespace between adjacent encoded words is not part of the text
+    let joined = value.replace("?= =?", "?==?");
+    RE_ENCODED_WORD
+        .replace_all(&joined, |caps: &regex::Captures| {
+            let bytes = match &caps[2] {
+                "B" | "b" => STANDARD.decode(&caps[3]).unwrap_or_default(),
+                _ => decode_quoted_printable(&caps[3].replace('_', " ")),
+            };
+            String::from_utf8_lossy(&bytes).into_owned()
+        })
+        .into_owned()
+}
+
+fn decode_quoted_printable(text: &str) -> Vec<u8> {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'=' {
+            // Soft line break
+            if bytes[i + 1..].starts_with(b"\r\n") {
+                i += 3;
+                continue;
+            }
+            if bytes[i + 1..].starts_with(b"\n") {
+                i += 2;
+                continue;
+            }
+            if let Some(hex) = bytes.get(i + 1..i + 3)
+                && let Ok(byte) = u8::from_str_radix(&String::from_utf8_lossy(hex), 16)
+            {
+                out.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    out
+}
+
+/// Parameter of a header value, e.g. `boundary` in `multipart/mixed; boundary="x"`.
+fn header_param(value: &str, param: &str) -> Option<String> {
+    value.split(';').skip(1).find_map(|part| {
+        let (name, value) = part.split_once('=')?;
+        name.trim()
+            .eq_ignore_ascii_case(param)
+            .then(|| value.trim().trim_matches('"').to_string())
+    })
+}
+
+/// Readable text of a MIME entity: the first `text/plain` part, else HTML
+/// with the tags stripped.
+fn extract_text(headers: &HashMap<String, String>, body: &str, depth: usize) -> String {
+    let content_type = headers
+        .get("content-type")
+        .map(String::as_str)
+        .unwrap_or("text/plain");
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_lowercase();
+
+    if mime.starts_with("multipart/") {
+        let Some(boundary) = header_param(content_type, "boundary") else {
+            return String::new();
+        };
+        if depth >= MAX_MIME_DEPTH {
+            return String::new();
+        }
+        let delimiter = format!("--{}", boundary);
+        let parts: Vec<&str> = body
+            .split(delimiter.as_str())
+            .skip(1)
+            .take_while(|part| !part.starts_with("--"))
+            .collect();
+
+        let mut html = None;
+        for part in parts {
+            let part = part.trim_start_matches(['\r', '\n']);
+            let (part_headers, part_body) = split_headers(part);
+            let is_attachment = part_headers
+                .get("content-disposition")
+                .is_some_and(|d| d.to_lowercase().starts_with("attachment"));
+            if is_attachment {
+                continue;
+            }
+            let part_type = part_headers
+                .get("content-type")
+                .map(|t| t.to_lowercase())
+                .unwrap_or_else(|| "text/plain".to_string());
+            let text = extract_text(&part_headers, part_body, depth + 1);
+            if text.trim().is_empty() {
+                continue;
+            }
+            if part_type.starts_with("text/html") {
+                html.get_or_insert(text);
+            } else {
+                return text;
+            }
+        }
+        return html.unwrap_or_default();
+    }
+
+    if !mime.starts_with("text/") {
+        return String::new();
+    }
+
+    let encoding = headers
+        .get("content-transfer-encoding")
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default();
+    let decoded = match encoding.as_str() {
+        "base64" => {
+            let compact: String = body.chars().filter(|c| !c.is_whitespace()).collect();
+            STANDARD.decode(compact).unwrap_or_default()
+        }
+        "quoted-printable" => decode_quoted_printable(body),
+        _ => body.as_bytes().to_vec(),
+    };
+    let text = String::from_utf8_lossy(&decoded).into_owned();
+
+    if mime == "text/html" {
+        RE_HTML_TAG
+            .replace_all(&text, " ")
+            .replace("&nbsp;", " ")
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&amp;", "&")
+    } else {
+        text
+    }
+}
+
+/// Drop quoted replies, their attribution line, and the signature.
+fn clean_body(body: &str) -> String {
+    let mut lines: Vec<&str> = Vec::new();
+    for line in body.lines() {
+        let line = line.trim_end();
+        if line == "--" || line == "-- " {
+            break;
+        }
+        if line.starts_with('>') {
+            continue;
+        }
+        lines.push(line);
+    }
+    // The attribution line directly precedes the quote it introduced
+    while lines
+        .last()
+        .is_some_and(|l| l.is_empty() || RE_ATTRIBUTION.is_match(l.trim()))
+    {
+        lines.pop();
+    }
+    lines.retain(|l| !RE_ATTRIBUTION.is_match(l.trim()));
+
+    let mut cleaned = String::new();
+    let mut blank = false;
+    for line in lines {
+        if line.trim().is_empty() {
+            blank = true;
+            continue;
+        }
+        if blank && !cleaned.is_empty() {
+            cleaned.push('\n');
+        }
+        blank = false;
+        cleaned.push_str(line);
+        cleaned.push('\n');
+    }
+    cleaned.trim().to_string()
+}
+
+/// Group messages into threads keyed by the root message ID, oldest first.
+fn group_threads(messages: Vec<MailMessage>) -> Vec<MailThread> {
+    let mut parents: HashMap<String, Option<String>> = HashMap::new();
+    for message in &messages {
+        let parent = message
+            .in_reply_to
+            .clone()
+            .or_else(|| message.references.last().cloned());
+        parents.insert(message.message_id.clone(), parent);
+    }
+
+    // Follow parents to the oldest known ancestor; the first reference names
+    // the root when it isn't in the archive
+    let root_of = |message: &MailMessage| -> String {
+        let mut current = message.message_id.clone();
+        for _ in 0..parents.len() {
+            match parents.get(&current) {
+                Some(Some(parent)) if parents.contains_key(parent) => current = parent.clone(),
+                Some(Some(parent)) => {
+                    return message.references.first().unwrap_or(parent).clone();
+                }
+                _ => break,
+            }
+        }
+        current
+    };
+
+    let mut order: Vec<String> = Vec::new();
+    let mut threads: HashMap<String, Vec<MailMessage>> = HashMap::new();
+    let mut seen = std::collections::HashSet::new();
+    for message in messages {
+        if !seen.insert(message.message_id.clone()) {
+            continue;
+        }
+        let root = root_of(&message);
+        if !threads.contains_key(&root) {
+            order.push(root.clone());
+        }
+        threads.entry(root).or_default().push(message);
+    }
+
+    order
+        .into_iter()
+        .map(|root_id| {
+            let mut messages = threads.remove(&root_id).unwrap_or_default();
+            messages.sort_by_key(|m| m.date);
+            MailThread { root_id, messages }
+        })
+        .collect()
+}
+
+/// `Re:`/`Fwd:` prefixes and list tags like `[dev]` removed from a subject.
+fn base_subject(subject: &str) -> String {
+    let mut subject = subject.trim();
+    loop {
+        let lower = subject.to_lowercase();
+        if let Some(prefix) = ["re:", "fwd:", "fw:", "aw:"]
+            .iter()
+            .find(|p| lower.starts_with(*p))
+        {
+            subject = subject[prefix.len()..].trim_start();
+        } else if subject.starts_with('[')
+            && let Some(end) = subject.find(']')
+        {
+            subject = subject[end + 1..].trim_start();
+        } else {
+            break;
+        }
+    }
+    subject.to_string()
+}
+
+/// `mid:` URL (RFC 2392) for a message ID.
+fn message_url(message_id: &str) -> String {
+    let mut url = String::from("mid:");
+    for c in message_id.chars() {
+        match c {
+            '%' | '/' | '?' | '#' | '<' | '>' | '"' | ' ' => {
+                let mut buf = [0u8; 4];
+                for byte in c.encode_utf8(&mut buf).bytes() {
+                    url.push_str(&format!("%{:02X}", byte));
+                }
+            }
+            _ => url.push(c),
+        }
+    }
+    url
+}
+
+/// Tag value for an address: `@` becomes `-at-`, other invalid characters `-`.
+fn address_tag_value(address: &str) -> String {
+    address
+        .replace('@', "-at-")
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '_' || c == '-' || c == '.' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+fn thread_to_document(thread: &MailThread, tags: &[Tag]) -> Option<Document> {
+    let first = thread.messages.first()?;
+    let subject = base_subject(&first.subject);
+    let title = if subject.is_empty() {
+        "(no subject)".to_string()
+    } else {
+        subject
+    };
+
+    let mut senders: Vec<&MailMessage> = Vec::new();
+    let mut body = String::new();
+    for message in &thread.messages {
+        let date = message
+            .date
+            .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_default();
+        body.push_str(&format!(
+            "## {} ({})\n\n{}\n\n",
+            message.from_name, date, message.body
+        ));
+        if !senders
+            .iter()
+            .any(|s| s.from_address == message.from_address)
+        {
+            senders.push(message);
+        }
+    }
+
+    let participants: Vec<&str> = senders.iter().map(|m| m.from_name.as_str()).collect();
+    let content = format!(
+        "# {}\n\nParticipants: {} | Messages: {}\n\n{}",
+        title,
+        participants.join(", "),
+        thread.messages.len(),
+        body.trim_end()
+    );
+    if !thread
+        .messages
+        .iter()
+        .any(|m| has_meaningful_content(&m.body))
+    {
+        return None;
+    }
+
+    let source = Source::new(
+        SourceType::Mail,
+        thread.root_id.clone(),
+        Some(message_url(&thread.root_id)),
+    );
+    let checksum = calculate_checksum(&content);
+    let list_id = thread.messages.iter().find_map(|m| m.list_id.clone());
+
+    let metadata = DocumentMetadata {
+        filename: None,
+        extension: None,
+        language: Some("markdown".to_string()),
+        title: Some(title.clone()),
+        path: Some(match list_id {
+            Some(ref list) => format!("{} > {}", list, title),
+            None => title.clone(),
+        }),
+        size_bytes: content.len() as u64,
+    };
+
+    let mut tag_strs = vec!["source:mail".to_string()];
+    for sender in &senders {
+        tag_strs.push(format!(
+            "mail-from:{}",
+            address_tag_value(&sender.from_address)
+        ));
+    }
+    if let Some(date) = first.date {
+        tag_strs.push(format!("mail-date:{}", date.format("%Y-%m")));
+    }
+    if let Some(ref list) = list_id {
+        tag_strs.push(format!("mail-list:{}", address_tag_value(list)));
+    }
+    let mut all_tags = tags.to_vec();
+    for tag_str in tag_strs {
+        if let Ok(tag) = tag_str.parse::<Tag>()
+            && !all_tags.contains(&tag)
+        {
+            all_tags.push(tag);
+        }
+    }
+
+    let created = thread.messages.iter().filter_map(|m| m.date).min();
+    Some(
+        Document::new(content, source, all_tags, checksum, metadata)
+            .with_timestamps(created, thread.latest()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MBOX: &str = "From alice@example.com Mon Mar  4 10:00:00 2024\n\
+Message-ID: <root@example.com>\n\
+From: \"Alice Kim\" <Alice@Example.com>\n\
+Subject: [dev] Retry policy for\n the payment webhook\n\
+Date: Mon, 4 Mar 2024 10:00:00 +0900\n\
+List-Id: Dev list <dev.lists.example.com>\n\
+\n\
+Should we retry webhook deliveries with exponential backoff?\n\
+>From the logs, most failures are transient timeouts.\n\
+\n\
+From bob@example.com Mon Mar  4 11:00:00 2024\n\
+Message-ID: <reply@example.com>\n\
+In-Reply-To: <root@example.com>\n\
+References: <root@example.com>\n\
+From: Bob <bob@example.com>\n\
+Subject: Re: [dev] Retry policy for the payment webhook\n\
+Date: Mon, 4 Mar 2024 11:00:00 +0900 (KST)\n\
+Content-Type: multipart/alternative; boundary=\"b1\"\n\
+\n\
+--b1\n\
+Content-Type: text/plain; charset=utf-8\n\
+Content-Transfer-Encoding: quoted-printable\n\
+\n\
+Yes, with a cap of five attempts and jitter =\n\
+between them.\n\
+\n\
+On Mon, Mar 4, 2024 at 10:00 AM Alice Kim wrote:\n\
+> Should we retry webhook deliveries?\n\
+\n\
+-- \n\
+Bob\n\
+--b1\n\
+Content-Type: text/html\n\
+\n\
+<p>Yes</p>\n\
+--b1--\n\
+\n\
+From carol@example.com Tue Mar  5 09:00:00 2024\n\
+Message-ID: <other@example.com>\n\
+From: =?UTF-8?B?7LqQ66Gk?= <carol@example.com>\n\
+Subject: Deploy freeze next week\n\
+Date: Tue, 5 Mar 2024 09:00:00 +0900\n\
+\n\
+No deploys between Monday and Wednesday during the migration.\n";
+
+    #[test]
+    fn test_mail_source_creation() {
+        let source = MailSource::new();
+        assert_eq!(source.source_type(), SourceType::Mail);
+        assert_eq!(source.name(), "Email");
+    }
+
+    #[test]
+    fn test_parse_messages() {
+        let raw = split_mbox(MBOX);
+        assert_eq!(raw.len(), 3);
+        assert!(raw[0].contains("\nFrom the logs"));
+
+        let root = parse_message(&raw[0]);
+        assert_eq!(root.subject, "[dev] Retry policy for the payment webhook");
+        assert_eq!(root.from_address, "alice@example.com");
+        assert_eq!(root.list_id.as_deref(), Some("dev.lists.example.com"));
+
+        let reply = parse_message(&raw[1]);
+        assert_eq!(reply.in_reply_to.as_deref(), Some("root@example.com"));
+        assert_eq!(
+            reply.body,
+            "Yes, with a cap of five attempts and jitter between them."
+        );
+        assert!(reply.date.is_some());
+
+        let other = parse_message(&raw[2]);
+        assert_eq!(other.from_name, "캐롤");
+    }
+
+    #[test]
+    fn test_thread_to_document() {
+        let messages = split_mbox(MBOX).iter().map(|m| parse_message(m)).collect();
+        let threads = group_threads(messages);
+        assert_eq!(threads.len(), 2);
+        assert_eq!(threads[0].root_id, "root@example.com");
+        assert_eq!(threads[0].messages.len(), 2);
+
+        let doc = thread_to_document(&threads[0], &[]).unwrap();
+        assert!(
+            doc.content.starts_with(
+                "# Retry policy for the payment webhook\n\nParticipants: Alice Kim, Bob"
+            )
+        );
+        assert_eq!(doc.source.url.as_deref(), Some("mid:root@example.com"));
+        let tags: Vec<String> = doc.tags.iter().map(ToString::to_string).collect();
+        assert!(tags.contains(&"mail-from:alice-at-example.com".to_string()));
+        assert!(tags.contains(&"mail-date:2024-03".to_string()));
+        assert!(tags.contains(&"mail-list:dev.lists.example.com".to_string()));
+    }
+
+    #[test]
+    fn test_parse_search_response() {
+        assert_eq!(
+            parse_search_response("* SEARCH 3 7 12\r\nA001 OK done\r\n"),
+            vec![3, 7, 12]
+        );
+        assert!(parse_search_response("* SEARCH\r\n").is_empty());
+    }
+}
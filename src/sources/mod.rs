@@ -1,16 +1,37 @@
+mod attachment;
 mod confluence;
 mod figma;
+mod gdrive;
+mod github;
 mod jira;
 mod local;
+mod mail;
+mod slack;
+mod web;
 
 pub use confluence::ConfluenceSource;
 pub use figma::FigmaSource;
+pub use gdrive::GoogleDriveSource;
+pub use github::GitHubSource;
 pub use jira::JiraSource;
 pub use local::LocalSource;
+pub use mail::MailSource;
+pub use slack::SlackSource;
+pub use web::{DEFAULT_MAX_PAGES, WebSource};
+
+use std::pin::Pin;
+
+use async_trait::async_trait;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::{Stream, StreamExt};
 
 use crate::error::SourceError;
 use crate::models::{Document, SourceType, Tag};
 
+/// Documents fetched but not yet consumed before a source blocks
+const STREAM_BUFFER: usize = 64;
+
 #[derive(Debug, Clone, Default)]
 pub struct SyncOptions {
     pub query: Option<String>,
@@ -18,16 +39,71 @@ pub struct SyncOptions {
     pub tags: Vec<Tag>,
     pub limit: Option<u32>,
     pub exclude_ancestors: Vec<String>,
+    /// Also sync page attachments as separate documents (Confluence only)
+    pub attachments: bool,
+    /// Link levels to follow from the start pages (Web only)
+    pub depth: u32,
 }
 
+/// Documents in the order a source produces them. An `Err` item ends the sync.
+pub type DocumentStream = Pin<Box<dyn Stream<Item = Result<Document, SourceError>> + Send>>;
+
+#[async_trait]
 pub trait DataSource: Send + Sync {
     fn source_type(&self) -> SourceType;
     fn name(&self) -> &str;
-    fn check_available(&self) -> Result<bool, SourceError>;
+    async fn check_available(&self) -> Result<bool, SourceError>;
     fn install_instructions(&self) -> &str;
-    fn sync(&self, options: SyncOptions) -> Result<Vec<Document>, SourceError>;
+
+    /// Stream documents as they are fetched, so indexing can start before the sync ends.
+    ///
+    /// Dropping the stream stops the source at its next document.
+    fn sync_stream(&self, options: SyncOptions) -> DocumentStream;
+
+    /// Fetch every document before returning.
+    async fn sync(&self, options: SyncOptions) -> Result<Vec<Document>, SourceError> {
+        let mut stream = self.sync_stream(options);
+        let mut documents = Vec::new();
+        while let Some(document) = stream.next().await {
+            documents.push(document?);
+        }
+        Ok(documents)
+    }
+}
+
+/// Run a CLI-backed call on the blocking pool.
+async fn run_blocking<T, F>(f: F) -> Result<T, SourceError>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, SourceError> + Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .unwrap_or_else(|e| Err(SourceError::ExecutionError(e.to_string())))
+}
+
+/// Drive a blocking `sync_streaming` on the blocking pool, forwarding its
+/// documents through a bounded channel.
+fn spawn_stream<F>(sync: F) -> DocumentStream
+where
+    F: FnOnce(&mut dyn FnMut(Document) -> Result<(), SourceError>) -> Result<u64, SourceError>
+        + Send
+        + 'static,
+{
+    let (tx, rx) = mpsc::channel(STREAM_BUFFER);
+    tokio::task::spawn_blocking(move || {
+        let mut emit = |document| {
+            tx.blocking_send(Ok(document))
+                .map_err(|_| SourceError::SyncError("sync cancelled".to_string()))
+        };
+        if let Err(e) = sync(&mut emit) {
+            let _ = tx.blocking_send(Err(e));
+        }
+    });
+    Box::pin(ReceiverStream::new(rx))
 }
 
+#[async_trait]
 impl DataSource for JiraSource {
     fn source_type(&self) -> SourceType {
         JiraSource::source_type(self)
@@ -37,12 +113,14 @@ impl DataSource for JiraSource {
         JiraSource::name(self)
     }
 
-    fn check_available(&self) -> Result<bool, SourceError> {
-        JiraSource::check_available(self)
+    async fn check_available(&self) -> Result<bool, SourceError> {
+        let source = *self;
+        run_blocking(move || source.check_available()).await
     }
 
-    fn sync(&self, options: SyncOptions) -> Result<Vec<Document>, SourceError> {
-        JiraSource::sync(self, options)
+    fn sync_stream(&self, options: SyncOptions) -> DocumentStream {
+        let source = *self;
+        spawn_stream(move |emit| source.sync_streaming(options, emit))
     }
 
     fn install_instructions(&self) -> &str {
@@ -50,6 +128,7 @@ impl DataSource for JiraSource {
     }
 }
 
+#[async_trait]
 impl DataSource for ConfluenceSource {
     fn source_type(&self) -> SourceType {
         ConfluenceSource::source_type(self)
@@ -59,12 +138,14 @@ impl DataSource for ConfluenceSource {
         ConfluenceSource::name(self)
     }
 
-    fn check_available(&self) -> Result<bool, SourceError> {
-        ConfluenceSource::check_available(self)
+    async fn check_available(&self) -> Result<bool, SourceError> {
+        let source = *self;
+        run_blocking(move || source.check_available()).await
     }
 
-    fn sync(&self, options: SyncOptions) -> Result<Vec<Document>, SourceError> {
-        ConfluenceSource::sync(self, options)
+    fn sync_stream(&self, options: SyncOptions) -> DocumentStream {
+        let source = *self;
+        spawn_stream(move |emit| source.sync_streaming(options, emit))
     }
 
     fn install_instructions(&self) -> &str {
@@ -72,6 +153,7 @@ impl DataSource for ConfluenceSource {
     }
 }
 
+#[async_trait]
 impl DataSource for FigmaSource {
     fn source_type(&self) -> SourceType {
         FigmaSource::source_type(self)
@@ -81,12 +163,14 @@ impl DataSource for FigmaSource {
         FigmaSource::name(self)
     }
 
-    fn check_available(&self) -> Result<bool, SourceError> {
-        FigmaSource::check_available(self)
+    async fn check_available(&self) -> Result<bool, SourceError> {
+        let source = *self;
+        run_blocking(move || source.check_available()).await
     }
 
-    fn sync(&self, options: SyncOptions) -> Result<Vec<Document>, SourceError> {
-        FigmaSource::sync(self, options)
+    fn sync_stream(&self, options: SyncOptions) -> DocumentStream {
+        let source = *self;
+        spawn_stream(move |emit| source.sync_streaming(options, emit))
     }
 
     fn install_instructions(&self) -> &str {
@@ -94,11 +178,174 @@ impl DataSource for FigmaSource {
     }
 }
 
+#[async_trait]
+impl DataSource for GitHubSource {
+    fn source_type(&self) -> SourceType {
+        GitHubSource::source_type(self)
+    }
+
+    fn name(&self) -> &str {
+        GitHubSource::name(self)
+    }
+
+    async fn check_available(&self) -> Result<bool, SourceError> {
+        let source = *self;
+        run_blocking(move || source.check_available()).await
+    }
+
+    fn sync_stream(&self, options: SyncOptions) -> DocumentStream {
+        let source = *self;
+        spawn_stream(move |emit| source.sync_streaming(options, emit))
+    }
+
+    fn install_instructions(&self) -> &str {
+        GitHubSource::install_instructions(self)
+    }
+}
+
+#[async_trait]
+impl DataSource for SlackSource {
+    fn source_type(&self) -> SourceType {
+        SlackSource::source_type(self)
+    }
+
+    fn name(&self) -> &str {
+        SlackSource::name(self)
+    }
+
+    async fn check_available(&self) -> Result<bool, SourceError> {
+        let source = *self;
+        run_blocking(move || source.check_available()).await
+    }
+
+    fn sync_stream(&self, options: SyncOptions) -> DocumentStream {
+        let source = *self;
+        spawn_stream(move |emit| source.sync_streaming(options, emit))
+    }
+
+    fn install_instructions(&self) -> &str {
+        SlackSource::install_instructions(self)
+    }
+}
+
+#[async_trait]
+impl DataSource for GoogleDriveSource {
+    fn source_type(&self) -> SourceType {
+        GoogleDriveSource::source_type(self)
+    }
+
+    fn name(&self) -> &str {
+        GoogleDriveSource::name(self)
+    }
+
+    async fn check_available(&self) -> Result<bool, SourceError> {
+        let source = *self;
+        run_blocking(move || source.check_available()).await
+    }
+
+    fn sync_stream(&self, options: SyncOptions) -> DocumentStream {
+        let source = *self;
+        spawn_stream(move |emit| source.sync_streaming(options, emit))
+    }
+
+    fn install_instructions(&self) -> &str {
+        GoogleDriveSource::install_instructions(self)
+    }
+}
+
+#[async_trait]
+impl DataSource for MailSource {
+    fn source_type(&self) -> SourceType {
+        MailSource::source_type(self)
+    }
+
+    fn name(&self) -> &str {
+        MailSource::name(self)
+    }
+
+    async fn check_available(&self) -> Result<bool, SourceError> {
+        let source = *self;
+        run_blocking(move || source.check_available()).await
+    }
+
+    fn sync_stream(&self, options: SyncOptions) -> DocumentStream {
+        let source = *self;
+        spawn_stream(move |emit| source.sync_streaming(options, emit))
+    }
+
+    fn install_instructions(&self) -> &str {
+        MailSource::install_instructions(self)
+    }
+}
+
+#[async_trait]
+impl DataSource for WebSource {
+    fn source_type(&self) -> SourceType {
+        WebSource::source_type(self)
+    }
+
+    fn name(&self) -> &str {
+        WebSource::name(self)
+    }
+
+    async fn check_available(&self) -> Result<bool, SourceError> {
+        let source = *self;
+        run_blocking(move || source.check_available()).await
+    }
+
+    fn sync_stream(&self, options: SyncOptions) -> DocumentStream {
+        let source = *self;
+        spawn_stream(move |emit| source.sync_streaming(options, emit))
+    }
+
+    fn install_instructions(&self) -> &str {
+        WebSource::install_instructions(self)
+    }
+}
+
 pub fn get_data_source(source_type: SourceType) -> Option<Box<dyn DataSource>> {
     match source_type {
         SourceType::Jira => Some(Box::new(JiraSource::new())),
         SourceType::Confluence => Some(Box::new(ConfluenceSource::new())),
         SourceType::Figma => Some(Box::new(FigmaSource::new())),
+        SourceType::GitHub => Some(Box::new(GitHubSource::new())),
+        SourceType::Slack => Some(Box::new(SlackSource::new())),
+        SourceType::GoogleDrive => Some(Box::new(GoogleDriveSource::new())),
+        SourceType::Mail => Some(Box::new(MailSource::new())),
+        SourceType::Web => Some(Box::new(WebSource::new())),
         SourceType::Local | SourceType::Other(_) => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{DocumentMetadata, Source};
+
+    fn document(content: &str) -> Document {
+        Document::new(
+            content.to_string(),
+            Source::new(SourceType::Jira, content.to_string(), None),
+            Vec::new(),
+            content.to_string(),
+            DocumentMetadata::default(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_spawn_stream_forwards_documents_then_error() {
+        let mut stream = spawn_stream(|emit| {
+            emit(document("first"))?;
+            emit(document("second"))?;
+            Err(SourceError::SyncError("boom".to_string()))
+        });
+
+        assert_eq!(stream.next().await.unwrap().unwrap().content, "first");
+        assert_eq!(stream.next().await.unwrap().unwrap().content, "second");
+        assert!(matches!(
+            stream.next().await,
+            Some(Err(SourceError::SyncError(_)))
+        ));
+        assert!(stream.next().await.is_none());
+    }
+}
@@ -0,0 +1,882 @@
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use regex::Regex;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use tracing::{info, warn};
+
+use crate::error::SourceError;
+use crate::models::{Document, DocumentMetadata, Source, SourceType, Tag};
+use crate::sources::SyncOptions;
+use crate::utils::file::calculate_checksum;
+use crate::utils::has_meaningful_content;
+
+/// Environment variable holding the Slack API token (bot or user token)
+pub const SLACK_TOKEN_ENV: &str = "SLACK_TOKEN";
+
+/// Environment variable with the workspace URL, used for permalinks in export mode
+pub const SLACK_WORKSPACE_URL_ENV: &str = "SLACK_WORKSPACE_URL";
+
+const SLACK_API_URL: &str = "https://slack.com/api";
+const API_PAGE_SIZE: &str = "200";
+const RATE_LIMIT_RETRIES: u32 = 3;
+const RATE_LIMIT_BACKOFF: Duration = Duration::from_secs(20);
+
+#[derive(Debug, Clone, Deserialize)]
+struct SlackMessage {
+    ts: String,
+    #[serde(default)]
+    thread_ts: Option<String>,
+    #[serde(default)]
+    user: Option<String>,
+    #[serde(default)]
+    username: Option<String>,
+    #[serde(default)]
+    user_profile: Option<UserProfile>,
+    #[serde(default)]
+    text: String,
+    #[serde(default)]
+    subtype: Option<String>,
+    #[serde(default)]
+    reply_count: Option<u32>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct UserProfile {
+    #[serde(default)]
+    display_name: Option<String>,
+    #[serde(default)]
+    real_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SlackUser {
+    id: String,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    real_name: Option<String>,
+    #[serde(default)]
+    profile: Option<UserProfile>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct SlackChannel {
+    id: String,
+    name: String,
+    #[serde(default)]
+    is_member: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ResponseMetadata {
+    #[serde(default)]
+    next_cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessagesPage {
+    #[serde(default)]
+    messages: Vec<SlackMessage>,
+    #[serde(default)]
+    response_metadata: Option<ResponseMetadata>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UsersPage {
+    #[serde(default)]
+    members: Vec<SlackUser>,
+    #[serde(default)]
+    response_metadata: Option<ResponseMetadata>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChannelsPage {
+    #[serde(default)]
+    channels: Vec<SlackChannel>,
+    #[serde(default)]
+    response_metadata: Option<ResponseMetadata>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AuthTest {
+    url: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConversationKind {
+    /// A message and its thread replies
+    Thread,
+    /// Standalone (unthreaded) messages of one day
+    Day,
+}
+
+/// A group of messages indexed as one document.
+#[derive(Debug)]
+struct Conversation {
+    kind: ConversationKind,
+    /// Thread timestamp or `YYYY-MM-DD`
+    key: String,
+    messages: Vec<SlackMessage>,
+}
+
+/// A channel with its messages, loaded from an export or the API.
+struct ChannelData {
+    channel: SlackChannel,
+    messages: Vec<SlackMessage>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SlackSource;
+
+impl SlackSource {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn source_type(&self) -> SourceType {
+        SourceType::Slack
+    }
+
+    pub fn name(&self) -> &str {
+        "Slack"
+    }
+
+    pub fn check_available(&self) -> Result<bool, SourceError> {
+        Command::new("which")
+            .arg("curl")
+            .output()
+            .map(|o| o.status.success())
+            .map_err(|e| SourceError::ExecutionError(e.to_string()))
+    }
+
+    pub fn install_instructions(&self) -> &str {
+        "Install curl and set SLACK_TOKEN, or pass an unzipped Slack export with --query <dir>"
+    }
+
+    /// Sync channel history as thread and daily-log documents.
+    ///
+    /// `options.query` may point to an unzipped Slack export directory;
+    /// otherwise the Web API is used with `SLACK_TOKEN`. `options.project`
+    /// selects channels (comma-separated names or IDs, default: all).
+    /// `options.limit` caps documents per channel (most recent first).
+    pub fn sync(&self, options: SyncOptions) -> Result<Vec<Document>, SourceError> {
+        let mut documents = Vec::new();
+        self.sync_streaming(options, |doc| {
+            documents.push(doc);
+            Ok(())
+        })?;
+        Ok(documents)
+    }
+
+    /// Sync conversations, handing each document to `on_document` as soon as it is built.
+    pub fn sync_streaming<F>(
+        &self,
+        options: SyncOptions,
+        mut on_document: F,
+    ) -> Result<u64, SourceError>
+    where
+        F: FnMut(Document) -> Result<(), SourceError>,
+    {
+        let channel_filter: Vec<String> = options
+            .project
+            .as_deref()
+            .map(|p| {
+                p.split(',')
+                    .map(|c| c.trim().trim_start_matches('#').to_string())
+                    .filter(|c| !c.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let (channels, users, workspace_url) = match options.query.as_deref() {
+            Some(dir) if Path::new(dir).is_dir() => {
+                info!(dir, "reading Slack export");
+                let workspace_url = std::env::var(SLACK_WORKSPACE_URL_ENV).ok();
+                let (channels, users) = load_export(Path::new(dir), &channel_filter)?;
+                (channels, users, workspace_url)
+            }
+            Some(other) => {
+                return Err(SourceError::SyncError(format!(
+                    "Slack export directory not found: {}",
+                    other
+                )));
+            }
+            None => {
+                let token = std::env::var(SLACK_TOKEN_ENV).map_err(|_| {
+                    SourceError::SyncError(format!(
+                        "set {} or pass a Slack export directory with --query",
+                        SLACK_TOKEN_ENV
+                    ))
+                })?;
+                let api = SlackApi { token };
+                let workspace_url = api.call::<AuthTest>("auth.test", &[])?.url;
+                let users = api.users()?;
+                let channels = api.channels(&channel_filter, options.limit)?;
+                (channels, users, Some(workspace_url))
+            }
+        };
+
+        let mut count = 0u64;
+        let mut skipped = 0u64;
+
+        for data in channels {
+            let mut conversations = group_conversations(data.messages);
+            if let Some(limit) = options.limit {
+                conversations.truncate(limit as usize);
+            }
+
+            info!(
+                channel = %data.channel.name,
+                conversations = conversations.len(),
+                "grouped channel history"
+            );
+
+            for conversation in conversations {
+                match conversation_to_document(
+                    &conversation,
+                    &data.channel,
+                    &users,
+                    workspace_url.as_deref(),
+                    &options.tags,
+                ) {
+                    Ok(doc) => {
+                        on_document(doc)?;
+                        count += 1;
+                    }
+                    Err(_) => skipped += 1,
+                }
+            }
+        }
+
+        if skipped > 0 {
+            info!(skipped, "skipped conversations (empty content)");
+        }
+
+        Ok(count)
+    }
+}
+
+impl Default for SlackSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Load channels and users from an unzipped Slack export.
+///
+/// Layout: `channels.json`, `users.json`, and `<channel>/<YYYY-MM-DD>.json`.
+fn load_export(
+    dir: &Path,
+    channel_filter: &[String],
+) -> Result<(Vec<ChannelData>, HashMap<String, String>), SourceError> {
+    let channels: Vec<SlackChannel> = read_json(&dir.join("channels.json"))?;
+    let users: Vec<SlackUser> = if dir.join("users.json").exists() {
+        read_json(&dir.join("users.json"))?
+    } else {
+        Vec::new()
+    };
+
+    let mut result = Vec::new();
+    for channel in channels {
+        if !matches_channel(&channel, channel_filter) {
+            continue;
+        }
+
+        let channel_dir = dir.join(&channel.name);
+        let Ok(entries) = std::fs::read_dir(&channel_dir) else {
+            continue;
+        };
+
+        let mut files: Vec<_> = entries
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        files.sort();
+
+        let mut messages = Vec::new();
+        for file in files {
+            messages.extend(read_json::<Vec<SlackMessage>>(&file)?);
+        }
+
+        result.push(ChannelData { channel, messages });
+    }
+
+    Ok((result, user_names(users)))
+}
+
+fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T, SourceError> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| SourceError::ExecutionError(format!("{}: {}", path.display(), e)))?;
+    serde_json::from_str(&content)
+        .map_err(|e| SourceError::ParseError(format!("{}: {}", path.display(), e)))
+}
+
+fn matches_channel(channel: &SlackChannel, filter: &[String]) -> bool {
+    filter.is_empty()
+        || filter
+            .iter()
+            .any(|f| f == &channel.id || f.eq_ignore_ascii_case(&channel.name))
+}
+
+fn user_names(users: Vec<SlackUser>) -> HashMap<String, String> {
+    users
+        .into_iter()
+        .filter_map(|user| {
+            let name = user
+                .profile
+                .as_ref()
+                .and_then(profile_name)
+                .or(user.real_name)
+                .or(user.name)?;
+            Some((user.id, name))
+        })
+        .collect()
+}
+
+fn profile_name(profile: &UserProfile) -> Option<String> {
+    profile
+        .display_name
+        .clone()
+        .filter(|n| !n.is_empty())
+        .or_else(|| profile.real_name.clone().filter(|n| !n.is_empty()))
+}
+
+/// Minimal Slack Web API client using `curl`.
+struct SlackApi {
+    token: String,
+}
+
+impl SlackApi {
+    fn call<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: &[(&str, &str)],
+    ) -> Result<T, SourceError> {
+        let mut attempt = 0;
+        loop {
+            let mut command = Command::new("curl");
+            command
+                .args(["-sS", "-G", "-H", "@-"])
+                .arg(format!("{}/{}", SLACK_API_URL, method));
+            for (key, value) in params {
+                command
+                    .arg("--data-urlencode")
+                    .arg(format!("{key}={value}"));
+            }
+
+            // Pass the token on stdin so it does not appear in the process list
+            let mut child = command
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .spawn()
+                .map_err(|e| SourceError::ExecutionError(e.to_string()))?;
+            if let Some(mut stdin) = child.stdin.take() {
+                writeln!(stdin, "Authorization: Bearer {}", self.token)
+                    .map_err(|e| SourceError::ExecutionError(e.to_string()))?;
+            }
+            let output = child
+                .wait_with_output()
+                .map_err(|e| SourceError::ExecutionError(e.to_string()))?;
+
+            if !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                return Err(SourceError::ExecutionError(format!(
+                    "slack {} failed: {}",
+                    method,
+                    stderr.trim()
+                )));
+            }
+
+            let value: serde_json::Value = serde_json::from_slice(&output.stdout)
+                .map_err(|e| SourceError::ParseError(format!("slack {}: {}", method, e)))?;
+
+            if value["ok"].as_bool() == Some(true) {
+                return serde_json::from_value(value)
+                    .map_err(|e| SourceError::ParseError(format!("slack {}: {}", method, e)));
+            }
+
+            let error = value["error"].as_str().unwrap_or("unknown error");
+            if error == "ratelimited" && attempt < RATE_LIMIT_RETRIES {
+                attempt += 1;
+                warn!(attempt, "rate limited by Slack, retrying");
+                std::thread::sleep(RATE_LIMIT_BACKOFF);
+                continue;
+            }
+            return Err(SourceError::SyncError(format!(
+                "slack {} failed: {}",
+                method, error
+            )));
+        }
+    }
+
+    fn users(&self) -> Result<HashMap<String, String>, SourceError> {
+        let mut users = Vec::new();
+        let mut cursor = String::new();
+        loop {
+            let page: UsersPage = self.call(
+                "users.list",
+                &[("limit", API_PAGE_SIZE), ("cursor", &cursor)],
+            )?;
+            users.extend(page.members);
+            match next_cursor(page.response_metadata) {
+                Some(next) => cursor = next,
+                None => break,
+            }
+        }
+        Ok(user_names(users))
+    }
+
+    fn channels(
+        &self,
+        channel_filter: &[String],
+        limit: Option<u32>,
+    ) -> Result<Vec<ChannelData>, SourceError> {
+        let mut channels = Vec::new();
+        let mut cursor = String::new();
+        loop {
+            let page: ChannelsPage = self.call(
+                "conversations.list",
+                &[
+                    ("types", "public_channel,private_channel"),
+                    ("exclude_archived", "true"),
+                    ("limit", API_PAGE_SIZE),
+                    ("cursor", &cursor),
+                ],
+            )?;
+            channels.extend(page.channels);
+            match next_cursor(page.response_metadata) {
+                Some(next) => cursor = next,
+                None => break,
+            }
+        }
+
+        channels
+            .into_iter()
+            .filter(|c| {
+                if channel_filter.is_empty() {
+                    c.is_member.unwrap_or(false)
+                } else {
+                    matches_channel(c, channel_filter)
+                }
+            })
+            .map(|channel| {
+                info!(channel = %channel.name, "fetching channel history");
+                let messages = self.history(&channel.id, limit)?;
+                Ok(ChannelData { channel, messages })
+            })
+            .collect()
+    }
+
+    /// Fetch channel history (newest first) including thread replies.
+    fn history(
+        &self,
+        channel_id: &str,
+        limit: Option<u32>,
+    ) -> Result<Vec<SlackMessage>, SourceError> {
+        let mut messages: Vec<SlackMessage> = Vec::new();
+        let mut cursor = String::new();
+        loop {
+            let page: MessagesPage = self.call(
+                "conversations.history",
+                &[
+                    ("channel", channel_id),
+                    ("limit", API_PAGE_SIZE),
+                    ("cursor", &cursor),
+                ],
+            )?;
+            messages.extend(page.messages);
+
+            if limit.is_some_and(|l| messages.len() >= l as usize) {
+                break;
+            }
+            match next_cursor(page.response_metadata) {
+                Some(next) => cursor = next,
+                None => break,
+            }
+        }
+
+        let parents: Vec<String> = messages
+            .iter()
+            .filter(|m| m.reply_count.unwrap_or(0) > 0)
+            .map(|m| m.ts.clone())
+            .collect();
+
+        for thread_ts in parents {
+            let mut cursor = String::new();
+            loop {
+                let page: MessagesPage = self.call(
+                    "conversations.replies",
+                    &[
+                        ("channel", channel_id),
+                        ("ts", &thread_ts),
+                        ("limit", API_PAGE_SIZE),
+                        ("cursor", &cursor),
+                    ],
+                )?;
+                // The parent is repeated as the first message of every page
+                messages.extend(page.messages.into_iter().filter(|m| m.ts != thread_ts));
+                match next_cursor(page.response_metadata) {
+                    Some(next) => cursor = next,
+                    None => break,
+                }
+            }
+        }
+
+        Ok(messages)
+    }
+}
+
+fn next_cursor(metadata: Option<ResponseMetadata>) -> Option<String> {
+    metadata
+        .and_then(|m| m.next_cursor)
+        .filter(|c| !c.is_empty())
+}
+
+/// Group messages into threads and daily logs of standalone messages,
+/// most recently active first.
+fn group_conversations(messages: Vec<SlackMessage>) -> Vec<Conversation> {
+    let mut threads: BTreeMap<String, Vec<SlackMessage>> = BTreeMap::new();
+    let mut days: BTreeMap<String, Vec<SlackMessage>> = BTreeMap::new();
+
+    for message in messages {
+        if is_noise(&message) {
+            continue;
+        }
+        match message.thread_ts.clone() {
+            Some(thread_ts) => threads.entry(thread_ts).or_default().push(message),
+            None => days.entry(ts_date(&message.ts)).or_default().push(message),
+        }
+    }
+
+    let mut conversations: Vec<Conversation> = threads
+        .into_iter()
+        .map(|(key, messages)| (ConversationKind::Thread, key, messages))
+        .chain(
+            days.into_iter()
+                .map(|(key, messages)| (ConversationKind::Day, key, messages)),
+        )
+        .map(|(kind, key, mut messages)| {
+            messages.sort_by(|a, b| ts_seconds(&a.ts).total_cmp(&ts_seconds(&b.ts)));
+            messages.dedup_by(|a, b| a.ts == b.ts);
+            Conversation {
+                kind,
+                key,
+                messages,
+            }
+        })
+        .collect();
+
+    conversations.sort_by(|a, b| latest_ts(b).total_cmp(&latest_ts(a)));
+    conversations
+}
+
+fn is_noise(message: &SlackMessage) -> bool {
+    message
+        .subtype
+        .as_deref()
+        .is_some_and(|s| s.starts_with("channel_") || s.starts_with("group_"))
+        || message.text.trim().is_empty()
+}
+
+fn latest_ts(conversation: &Conversation) -> f64 {
+    conversation
+        .messages
+        .last()
+        .map_or(0.0, |m| ts_seconds(&m.ts))
+}
+
+fn ts_seconds(ts: &str) -> f64 {
+    ts.parse().unwrap_or(0.0)
+}
+
+fn ts_datetime(ts: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    chrono::DateTime::from_timestamp(ts_seconds(ts) as i64, 0)
+}
+
+fn ts_date(ts: &str) -> String {
+    ts_datetime(ts)
+        .map(|dt| dt.format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
+
+fn permalink(workspace_url: &str, channel_id: &str, message: &SlackMessage) -> String {
+    let mut url = format!(
+        "{}/archives/{}/p{}",
+        workspace_url.trim_end_matches('/'),
+        channel_id,
+        message.ts.replace('.', "")
+    );
+    if let Some(ref thread_ts) = message.thread_ts
+        && thread_ts != &message.ts
+    {
+        url.push_str(&format!("?thread_ts={}&cid={}", thread_ts, channel_id));
+    }
+    url
+}
+
+fn author_name(message: &SlackMessage, users: &HashMap<String, String>) -> String {
+    message
+        .user
+        .as_ref()
+        .and_then(|id| users.get(id).cloned())
+        .or_else(|| message.user_profile.as_ref().and_then(profile_name))
+        .or_else(|| message.username.clone())
+        .or_else(|| message.user.clone())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+static RE_USER_MENTION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<@([A-Z0-9]+)(?:\|[^>]*)?>").unwrap());
+static RE_CHANNEL_MENTION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<#[A-Z0-9]+\|([^>]*)>").unwrap());
+static RE_LINK: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<(https?://[^|>]+)(?:\|([^>]*))?>").unwrap());
+
+/// Convert Slack markup (mentions, links) to plain text.
+fn clean_text(text: &str, users: &HashMap<String, String>) -> String {
+    let text = RE_USER_MENTION.replace_all(text, |caps: &regex::Captures| {
+        let id = &caps[1];
+        format!("@{}", users.get(id).map_or(id, String::as_str))
+    });
+    let text = RE_CHANNEL_MENTION.replace_all(&text, "#$1");
+    let text = RE_LINK.replace_all(&text, |caps: &regex::Captures| match caps.get(2) {
+        Some(label) if !label.as_str().is_empty() => format!("{} ({})", label.as_str(), &caps[1]),
+        _ => caps[1].to_string(),
+    });
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+fn conversation_to_document(
+    conversation: &Conversation,
+    channel: &SlackChannel,
+    users: &HashMap<String, String>,
+    workspace_url: Option<&str>,
+    tags: &[Tag],
+) -> Result<Document, SourceError> {
+    let first = conversation
+        .messages
+        .first()
+        .ok_or_else(|| SourceError::ParseError("empty conversation".to_string()))?;
+
+    let title = match conversation.kind {
+        ConversationKind::Thread => {
+            let first_line = clean_text(&first.text, users)
+                .lines()
+                .next()
+                .unwrap_or("")
+                .to_string();
+            let mut title: String = first_line.chars().take(80).collect();
+            if first_line.chars().count() > 80 {
+                title.push_str("...");
+            }
+            title
+        }
+        ConversationKind::Day => format!("#{} {}", channel.name, conversation.key),
+    };
+
+    let mut participants: Vec<String> = Vec::new();
+    let mut body = String::new();
+    for message in &conversation.messages {
+        let author = author_name(message, users);
+        let time = ts_datetime(&message.ts)
+            .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_default();
+        body.push_str(&format!(
+            "**{}** ({}): {}\n\n",
+            author,
+            time,
+            clean_text(&message.text, users).trim()
+        ));
+        if !participants.contains(&author) {
+            participants.push(author);
+        }
+    }
+
+    let content = format!(
+        "# {}\n\nChannel: #{} | Participants: {}\n\n{}",
+        title,
+        channel.name,
+        participants.join(", "),
+        body.trim_end()
+    );
+
+    if !has_meaningful_content(&content) {
+        return Err(SourceError::ParseError(format!(
+            "conversation {} has no meaningful content",
+            conversation.key
+        )));
+    }
+
+    let location = format!("{}/{}", channel.id, conversation.key);
+    let url = workspace_url.map(|base| permalink(base, &channel.id, first));
+    let source = Source::new(SourceType::Slack, location, url);
+    let checksum = calculate_checksum(&content);
+
+    let metadata = DocumentMetadata {
+        filename: None,
+        extension: None,
+        language: Some("markdown".to_string()),
+        title: Some(title.clone()),
+        path: Some(format!("#{} > {}", channel.name, title)),
+        size_bytes: content.len() as u64,
+    };
+
+    let kind = match conversation.kind {
+        ConversationKind::Thread => "thread",
+        ConversationKind::Day => "daily",
+    };
+    let mut all_tags = tags.to_vec();
+    for tag_str in [
+        "source:slack".to_string(),
+        format!("slack-channel:{}", channel.name.to_lowercase()),
+        format!("slack-type:{}", kind),
+    ] {
+        if let Ok(tag) = tag_str.parse::<Tag>()
+            && !all_tags.contains(&tag)
+        {
+            all_tags.push(tag);
+        }
+    }
+
+    let created = ts_datetime(&first.ts);
+    let updated = conversation
+        .messages
+        .last()
+        .and_then(|message| ts_datetime(&message.ts));
+    Ok(Document::new(content, source, all_tags, checksum, metadata)
+        .with_timestamps(created, updated))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(ts: &str, thread_ts: Option<&str>, user: &str, text: &str) -> SlackMessage {
+        SlackMessage {
+            ts: ts.to_string(),
+            thread_ts: thread_ts.map(String::from),
+            user: Some(user.to_string()),
+            username: None,
+            user_profile: None,
+            text: text.to_string(),
+            subtype: None,
+            reply_count: None,
+        }
+    }
+
+    fn channel() -> SlackChannel {
+        SlackChannel {
+            id: "C123".to_string(),
+            name: "backend".to_string(),
+            is_member: None,
+        }
+    }
+
+    #[test]
+    fn test_slack_source_creation() {
+        let source = SlackSource::new();
+        assert_eq!(source.source_type(), SourceType::Slack);
+        assert_eq!(source.name(), "Slack");
+    }
+
+    #[test]
+    fn test_group_conversations() {
+        let messages = vec![
+            message(
+                "1700000000.000100",
+                Some("1700000000.000100"),
+                "U1",
+                "Deploy failed?",
+            ),
+            message(
+                "1700000100.000200",
+                Some("1700000000.000100"),
+                "U2",
+                "Rolled back",
+            ),
+            message("1700000200.000300", None, "U1", "Lunch?"),
+            message("1700090000.000400", None, "U2", "Standup moved"),
+            SlackMessage {
+                subtype: Some("channel_join".to_string()),
+                ..message("1700000300.000500", None, "U3", "joined")
+            },
+        ];
+
+        let conversations = group_conversations(messages);
+
+        assert_eq!(conversations.len(), 3);
+        // Most recently active first
+        assert_eq!(conversations[0].kind, ConversationKind::Day);
+        assert_eq!(conversations[0].key, "2023-11-15");
+        let thread = conversations
+            .iter()
+            .find(|c| c.kind == ConversationKind::Thread)
+            .unwrap();
+        assert_eq!(thread.messages.len(), 2);
+        assert_eq!(thread.messages[0].text, "Deploy failed?");
+    }
+
+    #[test]
+    fn test_clean_text() {
+        let users = HashMap::from([("U1".to_string(), "alice".to_string())]);
+        let text = "<@U1> see <https://example.com|the doc> in <#C9|ops> &amp; <@U2>";
+        assert_eq!(
+            clean_text(text, &users),
+            "@alice see the doc (https://example.com) in #ops & @U2"
+        );
+    }
+
+    #[test]
+    fn test_conversation_to_document() {
+        let users = HashMap::from([
+            ("U1".to_string(), "alice".to_string()),
+            ("U2".to_string(), "bob".to_string()),
+        ]);
+        let conversation = Conversation {
+            kind: ConversationKind::Thread,
+            key: "1700000000.000100".to_string(),
+            messages: vec![
+                message(
+                    "1700000000.000100",
+                    Some("1700000000.000100"),
+                    "U1",
+                    "Deploy failed?",
+                ),
+                message(
+                    "1700000100.000200",
+                    Some("1700000000.000100"),
+                    "U2",
+                    "Rolled back",
+                ),
+            ],
+        };
+
+        let doc = conversation_to_document(
+            &conversation,
+            &channel(),
+            &users,
+            Some("https://acme.slack.com/"),
+            &[],
+        )
+        .unwrap();
+
+        assert_eq!(doc.source.location, "C123/1700000000.000100");
+        assert_eq!(
+            doc.source.url.as_deref(),
+            Some("https://acme.slack.com/archives/C123/p1700000000000100")
+        );
+        assert!(doc.content.contains("Participants: alice, bob"));
+        let tags: Vec<String> = doc.tags.iter().map(ToString::to_string).collect();
+        assert!(tags.contains(&"slack-channel:backend".to_string()));
+        assert!(tags.contains(&"slack-type:thread".to_string()));
+    }
+}
@@ -0,0 +1,531 @@
+//! Web pages fetched with `curl`, reduced to their readable text.
+
+use std::collections::{HashSet, VecDeque};
+use std::process::Command;
+use std::sync::LazyLock;
+
+use regex::{Captures, Regex};
+use tracing::{info, warn};
+use url::Url;
+
+use crate::error::SourceError;
+use crate::models::{Document, DocumentMetadata, Source, SourceType, Tag};
+use crate::sources::SyncOptions;
+use crate::utils::file::calculate_checksum;
+use crate::utils::has_meaningful_content;
+
+/// Pages fetched per sync when no limit is given.
+pub const DEFAULT_MAX_PAGES: u32 = 100;
+
+const FETCH_TIMEOUT_SECS: &str = "30";
+const MAX_PAGE_BYTES: &str = "10M";
+/// Separates the body from `curl --write-out` metadata.
+const META_MARKER: &str = "\n--ssearch-meta--\n";
+
+/// Elements whose content is never readable text.
+static RE_NON_CONTENT: LazyLock<Vec<Regex>> = LazyLock::new(|| {
+    [
+        "script", "style", "noscript", "template", "svg", "iframe", "form",
+    ]
+    .iter()
+    .map(|tag| Regex::new(&format!(r"(?is)<{tag}\b.*?</{tag}\s*>")).unwrap())
+    .chain([Regex::new(r"(?s)<!--.*?-->").unwrap()])
+    .collect()
+});
+/// Page chrome around the content.
+static RE_BOILERPLATE: LazyLock<Vec<Regex>> = LazyLock::new(|| {
+    ["nav", "header", "footer", "aside"]
+        .iter()
+        .map(|tag| Regex::new(&format!(r"(?is)<{tag}\b.*?</{tag}\s*>")).unwrap())
+        .collect()
+});
+static RE_MAIN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?is)<(article|main)\b[^>]*>(.*?)</(?:article|main)\s*>").unwrap()
+});
+static RE_BODY: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)<body\b[^>]*>(.*)</body\s*>").unwrap());
+static RE_TITLE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)<title\b[^>]*>(.*?)</title\s*>").unwrap());
+static RE_HEADING: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)<h([1-6])\b[^>]*>").unwrap());
+static RE_LIST_ITEM: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)<li\b[^>]*>").unwrap());
+static RE_LINE_BREAK: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)<br\s*/?>").unwrap());
+static RE_BLOCK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)</?(?:p|div|section|article|main|table|tr|ul|ol|dl|dt|dd|pre|blockquote|figure|h[1-6])\b[^>]*>",
+    )
+    .unwrap()
+});
+static RE_LINK_TEXT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)<a\b[^>]*>(.*?)</a\s*>").unwrap());
+static RE_HREF: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?i)<a\b[^>]*?\bhref\s*=\s*(?:"([^"]*)"|'([^']*)')"#).unwrap());
+static RE_TAG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?s)<[^>]*>").unwrap());
+static RE_ENTITY: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"&(#[0-9]+|#[xX][0-9a-fA-F]+|[a-zA-Z]+);").unwrap());
+
+/// A fetched page.
+#[derive(Debug)]
+struct Page {
+    url: Url,
+    content_type: String,
+    body: String,
+}
+
+/// Readable content of a page.
+#[derive(Debug, PartialEq)]
+struct Extracted {
+    title: Option<String>,
+    text: String,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct WebSource;
+
+impl WebSource {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn source_type(&self) -> SourceType {
+        SourceType::Web
+    }
+
+    pub fn name(&self) -> &str {
+        "Web"
+    }
+
+    pub fn check_available(&self) -> Result<bool, SourceError> {
+        Command::new("which")
+            .arg("curl")
+            .output()
+            .map(|o| o.status.success())
+            .map_err(|e| SourceError::ExecutionError(e.to_string()))
+    }
+
+    pub fn install_instructions(&self) -> &str {
+        "Install curl: https://curl.se/download.html"
+    }
+
+    /// Fetch pages as documents located at their URL.
+    ///
+    /// `options.query` holds the start URLs (comma-separated). Links are
+    /// followed `options.depth` levels deep, staying on the start page's host
+    /// and under its directory. `options.limit` caps the pages fetched
+    /// (default [`DEFAULT_MAX_PAGES`]).
+    pub fn sync(&self, options: SyncOptions) -> Result<Vec<Document>, SourceError> {
+        let mut documents = Vec::new();
+        self.sync_streaming(options, |doc| {
+            documents.push(doc);
+            Ok(())
+        })?;
+        Ok(documents)
+    }
+
+    /// Crawl pages, handing each document to `on_document` as soon as it is extracted.
+    pub fn sync_streaming<F>(
+        &self,
+        options: SyncOptions,
+        mut on_document: F,
+    ) -> Result<u64, SourceError>
+    where
+        F: FnMut(Document) -> Result<(), SourceError>,
+    {
+        let starts = options
+            .query
+            .as_deref()
+            .unwrap_or("")
+            .split(',')
+            .map(str::trim)
+            .filter(|u| !u.is_empty())
+            .map(|u| {
+                Url::parse(u)
+                    .ok()
+                    .filter(|url| matches!(url.scheme(), "http" | "https"))
+                    .ok_or_else(|| SourceError::SyncError(format!("invalid page URL: {}", u)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if starts.is_empty() {
+            return Err(SourceError::SyncError(
+                "pass the page URLs to fetch with --query".to_string(),
+            ));
+        }
+
+        let max_pages = options.limit.unwrap_or(DEFAULT_MAX_PAGES) as usize;
+        let scopes: Vec<Url> = starts.iter().map(crawl_scope).collect();
+
+        let mut queue: VecDeque<(Url, u32)> = VecDeque::new();
+        let mut seen: HashSet<String> = HashSet::new();
+        for start in starts {
+            let start = normalize(start);
+            if seen.insert(start.to_string()) {
+                queue.push_back((start, 0));
+            }
+        }
+
+        let mut fetched = 0usize;
+        let mut count = 0u64;
+        while let Some((url, depth)) = queue.pop_front() {
+            if fetched >= max_pages {
+                break;
+            }
+            fetched += 1;
+
+            let page = match fetch(&url) {
+                Ok(page) => page,
+                Err(e) => {
+                    warn!(url = %url, error = %e, "failed to fetch page");
+                    continue;
+                }
+            };
+            info!(url = %page.url, depth, "fetched page");
+            let is_html = page.content_type.contains("html");
+
+            if is_html && depth < options.depth {
+                for link in extract_links(&page.body, &page.url) {
+                    if scopes.iter().any(|scope| in_scope(&link, scope))
+                        && seen.insert(link.to_string())
+                    {
+                        queue.push_back((link, depth + 1));
+                    }
+                }
+            }
+
+            let extracted = if is_html {
+                extract_readable(&page.body)
+            } else if page.content_type.starts_with("text/") || page.content_type.is_empty() {
+                Extracted {
+                    title: None,
+                    text: page.body.trim().to_string(),
+                }
+            } else {
+                info!(url = %page.url, content_type = %page.content_type, "skipping non-text page");
+                continue;
+            };
+
+            if let Some(doc) = page_to_document(&page.url, extracted, &options.tags) {
+                on_document(doc)?;
+                count += 1;
+            }
+        }
+
+        Ok(count)
+    }
+}
+
+impl Default for WebSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Fetch a page, following redirects.
+fn fetch(url: &Url) -> Result<Page, SourceError> {
+    let output = Command::new("curl")
+        .args([
+            "-sSL",
+            "--fail",
+            "--compressed",
+            "--max-time",
+            FETCH_TIMEOUT_SECS,
+            "--max-filesize",
+            MAX_PAGE_BYTES,
+            "-A",
+            concat!("ssearch/", env!("CARGO_PKG_VERSION")),
+            "--write-out",
+        ])
+        .arg(format!(
+            "{}%{{content_type}}\n%{{url_effective}}",
+            META_MARKER
+        ))
+        .arg(url.as_str())
+        .output()
+        .map_err(|e| SourceError::ExecutionError(format!("failed to run curl: {}", e)))?;
+
+    if !output.status.success() {
+        return Err(SourceError::SyncError(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (body, meta) = stdout
+        .rsplit_once(META_MARKER)
+        .ok_or_else(|| SourceError::ParseError("missing curl metadata".to_string()))?;
+    let (content_type, effective) = meta.split_once('\n').unwrap_or((meta, ""));
+    Ok(Page {
+        url: Url::parse(effective.trim()).unwrap_or_else(|_| url.clone()),
+        content_type: content_type.trim().to_lowercase(),
+        body: body.to_string(),
+    })
+}
+
+/// URL without its fragment, which never names a different page.
+fn normalize(mut url: Url) -> Url {
+    url.set_fragment(None);
+    url
+}
+
+/// The directory of a start page; crawled links must stay below it.
+fn crawl_scope(start: &Url) -> Url {
+    let mut scope = start.clone();
+    scope.set_fragment(None);
+    scope.set_query(None);
+    if !scope.path().ends_with('/') {
+        let dir = match scope.path().rfind('/') {
+            Some(i) => scope.path()[..=i].to_string(),
+            None => "/".to_string(),
+        };
+        scope.set_path(&dir);
+    }
+    scope
+}
+
+fn in_scope(url: &Url, scope: &Url) -> bool {
+    url.scheme() == scope.scheme()
+        && url.host_str() == scope.host_str()
+        && url.port_or_known_default() == scope.port_or_known_default()
+        && url.path().starts_with(scope.path())
+}
+
+/// Absolute http(s) links of a page.
+fn extract_links(html: &str, base: &Url) -> Vec<Url> {
+    RE_HREF
+        .captures_iter(html)
+        .filter_map(|caps| caps.get(1).or_else(|| caps.get(2)))
+        .filter_map(|href| base.join(&decode_entities(href.as_str().trim())).ok())
+        .filter(|url| matches!(url.scheme(), "http" | "https"))
+        .map(normalize)
+        .collect()
+}
+
+/// Title and readable text of an HTML page.
+///
+/// Scripts, forms, and page chrome (nav, header, footer, aside) are removed,
+/// the largest `<article>`/`<main>` is preferred over the whole body, and
+/// blocks that are mostly links (menus, tag clouds, "related" lists) are dropped.
+fn extract_readable(html: &str) -> Extracted {
+    let title = RE_TITLE
+        .captures(html)
+        .map(|caps| collapse_whitespace(&decode_entities(&RE_TAG.replace_all(&caps[1], ""))))
+        .filter(|t| !t.is_empty());
+
+    let mut html = html.to_string();
+    for re in RE_NON_CONTENT.iter() {
+        html = re.replace_all(&html, " ").into_owned();
+    }
+
+    let main = RE_MAIN
+        .captures_iter(&html)
+        .map(|caps| caps.get(2).map_or("", |m| m.as_str()).to_string())
+        .max_by_key(String::len)
+        .filter(|m| !RE_TAG.replace_all(m, "").trim().is_empty());
+    let mut content = match main {
+        Some(main) => main,
+        None => RE_BODY
+            .captures(&html)
+            .map_or(html.clone(), |caps| caps[1].to_string()),
+    };
+    for re in RE_BOILERPLATE.iter() {
+        content = re.replace_all(&content, " ").into_owned();
+    }
+
+    let content = RE_HEADING.replace_all(&content, |caps: &Captures| {
+        let level: usize = caps[1].parse().unwrap_or(2);
+        format!("\n\n{} ", "#".repeat(level))
+    });
+    let content = RE_LIST_ITEM.replace_all(&content, "\n\n- ");
+    let content = RE_LINE_BREAK.replace_all(&content, "\n");
+    let content = RE_BLOCK.replace_all(&content, "\n\n");
+
+    let mut blocks = Vec::new();
+    for block in content.split("\n\n") {
+        let link_len: usize = RE_LINK_TEXT
+            .captures_iter(block)
+            .map(|caps| visible_text(&caps[1]).len())
+            .sum();
+        let text = visible_text(block);
+        if text.is_empty() {
+            continue;
+        }
+        let is_heading = text.starts_with('#');
+        if !is_heading && link_len * 2 > text.len() {
+            continue;
+        }
+        blocks.push(text);
+    }
+
+    // Drop headings left without content (e.g., from removed link lists)
+    let mut text = String::new();
+    for (i, block) in blocks.iter().enumerate() {
+        let next_is_heading = blocks.get(i + 1).is_none_or(|b| b.starts_with('#'));
+        if block.starts_with('#') && next_is_heading {
+            continue;
+        }
+        text.push_str(block);
+        text.push_str("\n\n");
+    }
+
+    Extracted {
+        title,
+        text: text.trim_end().to_string(),
+    }
+}
+
+fn visible_text(html: &str) -> String {
+    collapse_whitespace(&decode_entities(&RE_TAG.replace_all(html, " ")))
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn decode_entities(text: &str) -> String {
+    RE_ENTITY
+        .replace_all(text, |caps: &Captures| {
+            let entity = &caps[1];
+            let decoded = if let Some(hex) = entity
+                .strip_prefix("#x")
+                .or_else(|| entity.strip_prefix("#X"))
+            {
+                u32::from_str_radix(hex, 16).ok().and_then(char::from_u32)
+            } else if let Some(dec) = entity.strip_prefix('#') {
+                dec.parse().ok().and_then(char::from_u32)
+            } else {
+                match entity {
+                    "amp" => Some('&'),
+                    "lt" => Some('<'),
+                    "gt" => Some('>'),
+                    "quot" => Some('"'),
+                    "apos" => Some('\''),
+                    "nbsp" => Some(' '),
+                    "mdash" => Some('—'),
+                    "ndash" => Some('–'),
+                    "hellip" => Some('…'),
+                    _ => None,
+                }
+            };
+            decoded.map_or_else(|| caps[0].to_string(), String::from)
+        })
+        .into_owned()
+}
+
+fn page_to_document(url: &Url, extracted: Extracted, tags: &[Tag]) -> Option<Document> {
+    if !has_meaningful_content(&extracted.text) {
+        return None;
+    }
+
+    let title = extracted.title.unwrap_or_else(|| {
+        extracted
+            .text
+            .lines()
+            .find_map(|l| l.strip_prefix('#'))
+            .map(|h| h.trim_start_matches('#').trim().to_string())
+            .unwrap_or_else(|| url.to_string())
+    });
+    let content = format!("# {}\n\n{}", title, extracted.text);
+
+    let location = url.to_string();
+    let source = Source::new(SourceType::Web, location.clone(), Some(location));
+    let checksum = calculate_checksum(&content);
+
+    let metadata = DocumentMetadata {
+        filename: None,
+        extension: None,
+        language: Some("markdown".to_string()),
+        title: Some(title),
+        path: Some(format!("{}{}", url.host_str().unwrap_or(""), url.path())),
+        size_bytes: content.len() as u64,
+    };
+
+    let mut all_tags = tags.to_vec();
+    let mut tag_strs = vec!["source:web".to_string()];
+    if let Some(host) = url.host_str() {
+        tag_strs.push(format!("web-host:{}", host));
+    }
+    for tag_str in tag_strs {
+        if let Ok(tag) = tag_str.parse::<Tag>()
+            && !all_tags.contains(&tag)
+        {
+            all_tags.push(tag);
+        }
+    }
+
+    Some(Document::new(content, source, all_tags, checksum, metadata))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<!DOCTYPE html>
+<html><head><title>Retry Policy &mdash; Handbook</title>
+<style>body { color: red }</style><script>track("view")</script></head>
+<body>
+<header><a href="/">Home</a> <a href="/about">About</a></header>
+<nav><ul><li><a href="/docs/a">A</a></li><li><a href="/docs/b">B</a></li></ul></nav>
+<main>
+<h1>Retry policy</h1>
+<p>Webhook deliveries are retried with exponential backoff &amp; jitter,
+up to <b>five</b> attempts.</p>
+<p>See the <a href="/docs/alerts">alerting guide</a> for what happens after the last attempt fails.</p>
+<h2>Related</h2>
+<ul><li><a href="guides/timeouts.html#top">Timeouts</a></li><li><a href="https://other.example.org/x">Elsewhere</a></li></ul>
+</main>
+<footer>&copy; Example Corp</footer>
+</body></html>"#;
+
+    #[test]
+    fn test_web_source_creation() {
+        let source = WebSource::new();
+        assert_eq!(source.source_type(), SourceType::Web);
+        assert_eq!(source.name(), "Web");
+    }
+
+    #[test]
+    fn test_extract_readable() {
+        let extracted = extract_readable(PAGE);
+        assert_eq!(extracted.title.as_deref(), Some("Retry Policy — Handbook"));
+        assert_eq!(
+            extracted.text,
+            "# Retry policy\n\n\
+             Webhook deliveries are retried with exponential backoff & jitter, up to five attempts.\n\n\
+             See the alerting guide for what happens after the last attempt fails."
+        );
+    }
+
+    #[test]
+    fn test_extract_links_in_scope() {
+        let base = Url::parse("https://handbook.example.com/docs/retry.html").unwrap();
+        let scope = crawl_scope(&base);
+        assert_eq!(scope.as_str(), "https://handbook.example.com/docs/");
+
+        let links: Vec<String> = extract_links(PAGE, &base)
+            .into_iter()
+            .filter(|link| in_scope(link, &scope))
+            .map(String::from)
+            .collect();
+        assert_eq!(
+            links,
+            vec![
+                "https://handbook.example.com/docs/a",
+                "https://handbook.example.com/docs/b",
+                "https://handbook.example.com/docs/alerts",
+                "https://handbook.example.com/docs/guides/timeouts.html",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_page_to_document() {
+        let url = Url::parse("https://handbook.example.com/docs/retry.html").unwrap();
+        let doc = page_to_document(&url, extract_readable(PAGE), &[]).unwrap();
+        assert_eq!(doc.source.location, url.as_str());
+        assert!(
+            doc.content
+                .starts_with("# Retry Policy — Handbook\n\n# Retry policy")
+        );
+        let tags: Vec<String> = doc.tags.iter().map(ToString::to_string).collect();
+        assert!(tags.contains(&"web-host:handbook.example.com".to_string()));
+    }
+}
@@ -0,0 +1,121 @@
+//! Builders for documents, chunks and search results.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::models::{
+    Document, DocumentChunk, DocumentMetadata, SearchResult, Source, SourceType, Tag,
+};
+use crate::services::TextChunker;
+use crate::utils::file::calculate_checksum;
+
+/// Builds a [`Document`] the way a source would, with metadata derived from
+/// its location.
+#[derive(Debug, Clone)]
+pub struct DocumentBuilder {
+    location: String,
+    content: String,
+    source_type: SourceType,
+    url: Option<String>,
+    tags: Vec<Tag>,
+    title: Option<String>,
+    attributes: BTreeMap<String, String>,
+}
+
+impl DocumentBuilder {
+    /// A local file at `location` holding `content`.
+    pub fn new(location: impl Into<String>, content: impl Into<String>) -> Self {
+        Self {
+            location: location.into(),
+            content: content.into(),
+            source_type: SourceType::Local,
+            url: None,
+            tags: Vec::new(),
+            title: None,
+            attributes: BTreeMap::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn source_type(mut self, source_type: SourceType) -> Self {
+        self.source_type = source_type;
+        self
+    }
+
+    #[must_use]
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    /// Add a `key:value` tag.
+    ///
+    /// # Panics
+    ///
+    /// When `tag` isn't a valid tag.
+    #[must_use]
+    pub fn tag(mut self, tag: &str) -> Self {
+        self.tags.push(
+            tag.parse()
+                .unwrap_or_else(|e| panic!("invalid tag {tag}: {e}")),
+        );
+        self
+    }
+
+    #[must_use]
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    #[must_use]
+    pub fn attribute(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.attributes.insert(key.into(), value.into());
+        self
+    }
+
+    pub fn build(self) -> Document {
+        let path = Path::new(&self.location);
+        let metadata = DocumentMetadata {
+            title: self.title,
+            ..DocumentMetadata::for_file(path, &self.content)
+        };
+        let checksum = calculate_checksum(&self.content);
+        let source = Source::new(self.source_type, self.location, self.url);
+        Document::new(self.content, source, self.tags, checksum, metadata)
+            .with_attributes(self.attributes)
+    }
+}
+
+/// A local document at `location` holding `content`.
+pub fn document(location: &str, content: &str) -> Document {
+    DocumentBuilder::new(location, content).build()
+}
+
+/// `document` cut into chunks with the default chunking settings.
+pub fn chunks(document: &Document) -> Vec<DocumentChunk> {
+    TextChunker::with_defaults().chunk(document)
+}
+
+/// A result for the first chunk of a local document at `location`.
+pub fn search_result(location: &str, content: &str, score: f32) -> SearchResult {
+    let document = document(location, content);
+    SearchResult {
+        chunk_id: format!("{}:0", document.id),
+        document_id: document.id,
+        chunk_index: 0,
+        score,
+        content: content.to_string(),
+        location: location.to_string(),
+        source: document.source,
+        tags: document.tags,
+        line_start: Some(1),
+        line_end: Some(content.lines().count().max(1) as u32),
+        attributes: document.attributes,
+        updated_at: None,
+        context_before: Vec::new(),
+        context_after: Vec::new(),
+        explanation: None,
+        keyword_match: false,
+    }
+}
@@ -0,0 +1,343 @@
+//! Backends that stand in for the daemon and the vector store.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+use crate::error::{DaemonError, EmbeddingError, VectorStoreError};
+use crate::models::{DocumentChunk, SearchFilter, SearchResult, SourceType, Tag};
+use crate::services::keyword::sparse_vector;
+use crate::services::{
+    ChunkPage, CollectionInfo, EmbeddingProvider, IndexInventory, IndexedDocument, MemoryBackend,
+    VectorStore,
+};
+
+/// Dimension of [`MockEmbeddingProvider::default`] vectors.
+pub const MOCK_DIMENSION: usize = 64;
+
+/// Take one of the failures queued in `failures`, if any.
+fn take_failure(failures: &AtomicU32) -> bool {
+    failures
+        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+        .is_ok()
+}
+
+/// Embeds text without a model: keyword terms are hashed into a fixed number
+/// of dimensions and the vector normalized, so texts sharing words are close
+/// and the same text always gets the same vector.
+#[derive(Debug)]
+pub struct MockEmbeddingProvider {
+    dimension: usize,
+    calls: AtomicUsize,
+    texts: AtomicUsize,
+    failures: AtomicU32,
+}
+
+impl Default for MockEmbeddingProvider {
+    fn default() -> Self {
+        Self::new(MOCK_DIMENSION)
+    }
+}
+
+impl MockEmbeddingProvider {
+    pub fn new(dimension: usize) -> Self {
+        Self {
+            dimension: dimension.max(1),
+            calls: AtomicUsize::new(0),
+            texts: AtomicUsize::new(0),
+            failures: AtomicU32::new(0),
+        }
+    }
+
+    /// The vector `text` embeds to, as a document or as a query.
+    pub fn embed(&self, text: &str) -> Vec<f32> {
+        let mut vector = vec![0.0; self.dimension];
+        let (indices, values) = sparse_vector(text);
+        for (index, value) in indices.into_iter().zip(values) {
+            vector[index as usize % self.dimension] += value;
+        }
+        let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+        if norm == 0.0 {
+            // Text without keyword terms still gets a unit vector
+            vector[0] = 1.0;
+        } else {
+            vector.iter_mut().for_each(|v| *v /= norm);
+        }
+        vector
+    }
+
+    /// Fail the next `n` calls with a retryable connection error.
+    pub fn fail_next(&self, n: u32) {
+        self.failures.store(n, Ordering::SeqCst);
+    }
+
+    /// Embedding calls made, including failed ones.
+    pub fn calls(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
+    }
+
+    /// Texts embedded by successful calls.
+    pub fn texts_embedded(&self) -> usize {
+        self.texts.load(Ordering::SeqCst)
+    }
+
+    fn embed_all(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        if take_failure(&self.failures) {
+            return Err(DaemonError::ConnectionFailed("mock failure".to_string()).into());
+        }
+        self.texts.fetch_add(texts.len(), Ordering::SeqCst);
+        Ok(texts.iter().map(|text| self.embed(text)).collect())
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for MockEmbeddingProvider {
+    async fn embed_batch(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+        self.embed_all(texts)
+    }
+
+    async fn embed_queries(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+        self.embed_all(texts)
+    }
+
+    /// Cosine similarity of the query and document embeddings.
+    async fn rerank(
+        &self,
+        query: &str,
+        documents: Vec<String>,
+    ) -> Result<Vec<f32>, EmbeddingError> {
+        let query = self.embed(query);
+        Ok(self
+            .embed_all(documents)?
+            .iter()
+            .map(|document| document.iter().zip(&query).map(|(a, b)| a * b).sum())
+            .collect())
+    }
+}
+
+/// A [`MemoryBackend`] that counts upserts and searches and can be told to
+/// fail upserts, e.g. to exercise the pipeline's retries.
+pub struct MockVectorStore {
+    inner: MemoryBackend,
+    upserts: AtomicUsize,
+    searches: AtomicUsize,
+    failures: AtomicU32,
+}
+
+impl Default for MockVectorStore {
+    fn default() -> Self {
+        Self::new("mock")
+    }
+}
+
+impl MockVectorStore {
+    pub fn new(collection: impl Into<String>) -> Self {
+        Self {
+            inner: MemoryBackend::new(collection),
+            upserts: AtomicUsize::new(0),
+            searches: AtomicUsize::new(0),
+            failures: AtomicU32::new(0),
+        }
+    }
+
+    /// Fail the next `n` upserts with a retryable connection error.
+    pub fn fail_next_upserts(&self, n: u32) {
+        self.failures.store(n, Ordering::SeqCst);
+    }
+
+    /// Upsert calls made, including failed ones.
+    pub fn upsert_calls(&self) -> usize {
+        self.upserts.load(Ordering::SeqCst)
+    }
+
+    /// Dense, keyword and hybrid searches made.
+    pub fn search_calls(&self) -> usize {
+        self.searches.load(Ordering::SeqCst)
+    }
+
+    fn count_search(&self) {
+        self.searches.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[async_trait]
+impl VectorStore for MockVectorStore {
+    async fn health_check(&self) -> Result<bool, VectorStoreError> {
+        self.inner.health_check().await
+    }
+
+    async fn get_collection_info(&self) -> Result<Option<CollectionInfo>, VectorStoreError> {
+        self.inner.get_collection_info().await
+    }
+
+    async fn create_collection(&self) -> Result<(), VectorStoreError> {
+        self.inner.create_collection().await
+    }
+
+    async fn upsert_points(&self, chunks: Vec<DocumentChunk>) -> Result<(), VectorStoreError> {
+        self.upserts.fetch_add(1, Ordering::SeqCst);
+        if take_failure(&self.failures) {
+            return Err(VectorStoreError::ConnectionError(
+                "mock failure".to_string(),
+            ));
+        }
+        self.inner.upsert_points(chunks).await
+    }
+
+    async fn search(
+        &self,
+        query_vector: Vec<f32>,
+        limit: u64,
+        offset: u64,
+        filter: &SearchFilter,
+        min_score: Option<f32>,
+    ) -> Result<Vec<SearchResult>, VectorStoreError> {
+        self.count_search();
+        self.inner
+            .search(query_vector, limit, offset, filter, min_score)
+            .await
+    }
+
+    async fn keyword_search(
+        &self,
+        query_text: &str,
+        limit: u64,
+        filter: &SearchFilter,
+    ) -> Result<Vec<SearchResult>, VectorStoreError> {
+        self.count_search();
+        self.inner.keyword_search(query_text, limit, filter).await
+    }
+
+    async fn hybrid_search(
+        &self,
+        query_vector: Vec<f32>,
+        query_text: &str,
+        limit: u64,
+        offset: u64,
+        filter: &SearchFilter,
+        min_score: Option<f32>,
+    ) -> Result<Vec<SearchResult>, VectorStoreError> {
+        self.count_search();
+        self.inner
+            .hybrid_search(query_vector, query_text, limit, offset, filter, min_score)
+            .await
+    }
+
+    async fn delete_by_tags(&self, tags: &[Tag]) -> Result<(), VectorStoreError> {
+        self.inner.delete_by_tags(tags).await
+    }
+
+    async fn delete_by_document_ids(
+        &self,
+        document_ids: &[String],
+    ) -> Result<(), VectorStoreError> {
+        self.inner.delete_by_document_ids(document_ids).await
+    }
+
+    async fn clear_collection(&self) -> Result<(), VectorStoreError> {
+        self.inner.clear_collection().await
+    }
+
+    async fn delete_collection(&self) -> Result<(), VectorStoreError> {
+        self.inner.delete_collection().await
+    }
+
+    async fn create_snapshot(&self, path: &Path) -> Result<(), VectorStoreError> {
+        self.inner.create_snapshot(path).await
+    }
+
+    async fn restore_snapshot(&self, path: &Path) -> Result<(), VectorStoreError> {
+        self.inner.restore_snapshot(path).await
+    }
+
+    fn snapshot_extension(&self) -> &'static str {
+        self.inner.snapshot_extension()
+    }
+
+    async fn delete_by_source_type(&self, source_type: SourceType) -> Result<(), VectorStoreError> {
+        self.inner.delete_by_source_type(source_type).await
+    }
+
+    async fn count_by_filter(&self, filter: &SearchFilter) -> Result<u64, VectorStoreError> {
+        self.inner.count_by_filter(filter).await
+    }
+
+    async fn delete_by_filter(&self, filter: &SearchFilter) -> Result<u64, VectorStoreError> {
+        self.inner.delete_by_filter(filter).await
+    }
+
+    async fn soft_delete_by_filter(
+        &self,
+        filter: &SearchFilter,
+        deleted_at: DateTime<Utc>,
+    ) -> Result<u64, VectorStoreError> {
+        self.inner.soft_delete_by_filter(filter, deleted_at).await
+    }
+
+    async fn restore_by_filter(&self, filter: &SearchFilter) -> Result<u64, VectorStoreError> {
+        self.inner.restore_by_filter(filter).await
+    }
+
+    async fn list_all_tags(&self) -> Result<Vec<(String, u64)>, VectorStoreError> {
+        self.inner.list_all_tags().await
+    }
+
+    async fn list_documents(
+        &self,
+        source_type: &SourceType,
+    ) -> Result<Vec<IndexedDocument>, VectorStoreError> {
+        self.inner.list_documents(source_type).await
+    }
+
+    async fn find_content_hashes(
+        &self,
+        hashes: &[String],
+    ) -> Result<Vec<(String, String)>, VectorStoreError> {
+        self.inner.find_content_hashes(hashes).await
+    }
+
+    async fn get_vectors(
+        &self,
+        chunk_ids: &[String],
+    ) -> Result<HashMap<String, Vec<f32>>, VectorStoreError> {
+        self.inner.get_vectors(chunk_ids).await
+    }
+
+    async fn get_adjacent_chunks(
+        &self,
+        document_id: &str,
+        chunk_index: u32,
+        radius: u32,
+    ) -> Result<Vec<DocumentChunk>, VectorStoreError> {
+        self.inner
+            .get_adjacent_chunks(document_id, chunk_index, radius)
+            .await
+    }
+
+    async fn scroll_by_document(
+        &self,
+        document_id: &str,
+    ) -> Result<Vec<DocumentChunk>, VectorStoreError> {
+        self.inner.scroll_by_document(document_id).await
+    }
+
+    async fn inventory(&self) -> Result<IndexInventory, VectorStoreError> {
+        self.inner.inventory().await
+    }
+
+    async fn scroll_chunks(
+        &self,
+        offset: Option<String>,
+        limit: u32,
+    ) -> Result<ChunkPage, VectorStoreError> {
+        self.inner.scroll_chunks(offset, limit).await
+    }
+
+    fn collection(&self) -> &str {
+        self.inner.collection()
+    }
+}
@@ -0,0 +1,132 @@
+//! Test support, behind the `test-util` feature: in-process stand-ins for
+//! the daemon and the vector store, and fixture builders, so indexing and
+//! search flows run without Qdrant or ONNX.
+//!
+//! ```ignore
+//! let embedder = MockEmbeddingProvider::default();
+//! let store = MockVectorStore::default();
+//! index(&embedder, &store, &[document("src/auth.rs", "fn refresh_token() {}")]).await?;
+//! let results = search(&embedder, &store, "refresh token", 5).await?;
+//! ```
+
+mod fixtures;
+mod mock;
+
+pub use fixtures::{DocumentBuilder, chunks, document, search_result};
+pub use mock::{MOCK_DIMENSION, MockEmbeddingProvider, MockVectorStore};
+
+use anyhow::{Context, Result};
+
+use crate::models::{Document, SearchFilter, SearchResult};
+use crate::services::{EmbeddingProvider, IndexPipeline, PipelineStats, VectorStore};
+
+/// Chunk `documents` with the default settings and run them through the
+/// index pipeline into `store`.
+pub async fn index<V: VectorStore + ?Sized>(
+    embedder: &dyn EmbeddingProvider,
+    store: &V,
+    documents: &[Document],
+) -> Result<PipelineStats> {
+    let (sender, pipeline) = IndexPipeline::new(embedder, store, 16);
+    let produce = async {
+        let sender = sender;
+        for document in documents {
+            for chunk in chunks(document) {
+                sender.send(chunk).await?;
+            }
+        }
+        Ok::<(), anyhow::Error>(())
+    };
+    let (produced, stats) = tokio::join!(produce, pipeline.run());
+    produced?;
+    stats
+}
+
+/// Embed `query` and return the `limit` closest chunks in `store`.
+pub async fn search<V: VectorStore + ?Sized>(
+    embedder: &dyn EmbeddingProvider,
+    store: &V,
+    query: &str,
+    limit: u64,
+) -> Result<Vec<SearchResult>> {
+    let embedding = embedder
+        .embed_queries(vec![query.to_string()])
+        .await?
+        .pop()
+        .context("no query embedding")?;
+    Ok(store
+        .search(embedding, limit, 0, &SearchFilter::new(), None)
+        .await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::parse_tags;
+
+    fn corpus() -> Vec<Document> {
+        vec![
+            DocumentBuilder::new(
+                "src/auth.rs",
+                "Refresh the access token before it expires and retry the request.",
+            )
+            .tag("team:identity")
+            .build(),
+            document(
+                "docs/deploy.md",
+                "# Deploy\n\nRoll out the kubernetes deployment with a canary first.",
+            ),
+            document(
+                "docs/cooking.md",
+                "# Pasta\n\nBoil the water, salt it and cook the pasta.",
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_mock_embeddings() {
+        let embedder = MockEmbeddingProvider::default();
+        let a = embedder.embed("kubernetes deployment");
+        assert_eq!(a.len(), MOCK_DIMENSION);
+        assert_eq!(a, embedder.embed("kubernetes deployment"));
+        let norm: f32 = a.iter().map(|v| v * v).sum::<f32>().sqrt();
+        assert!((norm - 1.0).abs() < 1e-5);
+        assert_eq!(embedder.embed("").len(), MOCK_DIMENSION);
+    }
+
+    #[tokio::test]
+    async fn test_index_and_search() {
+        let embedder = MockEmbeddingProvider::default();
+        let store = MockVectorStore::default();
+        let stats = index(&embedder, &store, &corpus()).await.unwrap();
+        assert_eq!(stats.stored, 3);
+        assert_eq!(embedder.texts_embedded(), 3);
+
+        let results = search(&embedder, &store, "kubernetes deployment canary", 3)
+            .await
+            .unwrap();
+        assert_eq!(results[0].source.location, "docs/deploy.md");
+        assert_eq!(store.search_calls(), 1);
+
+        let filter = SearchFilter::new().with_tags(parse_tags("team:identity").unwrap());
+        assert_eq!(store.count_by_filter(&filter).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_upsert_failure() {
+        let embedder = MockEmbeddingProvider::default();
+        let store = MockVectorStore::default();
+        store.fail_next_upserts(1);
+        let (sender, pipeline) = IndexPipeline::new(&embedder, &store, 16);
+        let pipeline = pipeline.with_max_retries(0);
+        let produce = async {
+            for chunk in chunks(&corpus()[0]) {
+                sender.send(chunk).await.unwrap();
+            }
+            drop(sender);
+        };
+        let ((), stats) = tokio::join!(produce, pipeline.run());
+        assert!(stats.is_err());
+        assert_eq!(store.upsert_calls(), 1);
+    }
+}
@@ -3,7 +3,12 @@
 use sha2::{Digest, Sha256};
 use std::fs;
 use std::io::Read;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+use ignore::WalkBuilder;
+
+/// Per-directory ignore file for indexing, read alongside `.gitignore` and `.ignore`.
+pub const IGNORE_FILENAME: &str = ".ssearchignore";
 
 /// Calculate SHA-256 checksum of content.
 pub fn calculate_checksum(content: &str) -> String {
@@ -49,6 +54,36 @@ pub fn is_text_file(path: &Path) -> bool {
     false
 }
 
+/// List the files under `path` (or `path` itself when it is a file).
+///
+/// With `respect_ignore`, files excluded by `.gitignore`, `.ignore` or
+/// `.ssearchignore` (in `path` or any parent) are skipped, whether or not
+/// `path` is inside a git repository. Hidden files are kept either way.
+pub fn walk_files(path: &Path, respect_ignore: bool) -> Result<Vec<PathBuf>, ignore::Error> {
+    if path.is_file() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+
+    let mut builder = WalkBuilder::new(path);
+    builder
+        .standard_filters(respect_ignore)
+        .hidden(false)
+        .require_git(false)
+        .follow_links(false);
+    if respect_ignore {
+        builder.add_custom_ignore_filename(IGNORE_FILENAME);
+    }
+
+    let mut files = Vec::new();
+    for entry in builder.build() {
+        let entry = entry?;
+        if entry.file_type().is_some_and(|t| t.is_file()) {
+            files.push(entry.into_path());
+        }
+    }
+    Ok(files)
+}
+
 /// Read file content with size limit.
 pub fn read_file_content(path: &Path, max_size: u64) -> std::io::Result<String> {
     let metadata = fs::metadata(path)?;
@@ -67,6 +102,22 @@ pub fn read_file_content(path: &Path, max_size: u64) -> std::io::Result<String>
     fs::read_to_string(path)
 }
 
+/// Read `reader` to the end as text, e.g. content piped on stdin, failing
+/// once it grows past `max_size` bytes. Invalid UTF-8 is replaced.
+pub fn read_text_limited(reader: impl Read, max_size: u64) -> std::io::Result<String> {
+    let mut bytes = Vec::new();
+    reader
+        .take(max_size.saturating_add(1))
+        .read_to_end(&mut bytes)?;
+    if bytes.len() as u64 > max_size {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("input exceeds maximum size of {} bytes", max_size),
+        ));
+    }
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
 /// Check if extension indicates a binary file.
 fn is_binary_extension(ext: &str) -> bool {
     matches!(
@@ -151,6 +202,50 @@ fn is_text_extension(ext: &str) -> bool {
     )
 }
 
+/// Detect programming language from file extension.
+pub fn detect_language(path: &Path) -> Option<String> {
+    let ext = path.extension()?.to_string_lossy().to_lowercase();
+    language_for_extension(&ext).map(String::from)
+}
+
+/// Canonical language name for a user-supplied name or extension
+/// (`rs` → `rust`, `Python` → `python`); unknown names are lowercased.
+pub fn normalize_language(name: &str) -> String {
+    let name = name.trim().to_lowercase();
+    match language_for_extension(&name) {
+        Some(language) => language.to_string(),
+        None => name,
+    }
+}
+
+fn language_for_extension(ext: &str) -> Option<&'static str> {
+    match ext {
+        "rs" => Some("rust"),
+        "py" => Some("python"),
+        "js" | "jsx" => Some("javascript"),
+        "ts" | "tsx" => Some("typescript"),
+        "go" => Some("go"),
+        "java" => Some("java"),
+        "kt" | "kts" => Some("kotlin"),
+        "c" | "h" => Some("c"),
+        "cpp" | "hpp" | "cc" | "cxx" => Some("cpp"),
+        "rb" => Some("ruby"),
+        "php" => Some("php"),
+        "swift" => Some("swift"),
+        "scala" => Some("scala"),
+        "sh" | "bash" => Some("shell"),
+        "sql" => Some("sql"),
+        "html" | "htm" => Some("html"),
+        "css" | "scss" | "sass" => Some("css"),
+        "json" => Some("json"),
+        "yaml" | "yml" => Some("yaml"),
+        "toml" => Some("toml"),
+        "xml" => Some("xml"),
+        "md" | "markdown" => Some("markdown"),
+        _ => None,
+    }
+}
+
 /// Get the relative path from a base directory.
 pub fn get_relative_path(base: &Path, path: &Path) -> Option<String> {
     path.strip_prefix(base)
@@ -177,7 +272,6 @@ pub fn sanitize_filename(name: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::path::PathBuf;
 
     #[test]
     fn test_calculate_checksum() {
@@ -201,6 +295,23 @@ mod tests {
         assert!(!is_text_extension("png"));
     }
 
+    #[test]
+    fn test_detect_language() {
+        assert_eq!(
+            detect_language(Path::new("test.rs")),
+            Some("rust".to_string())
+        );
+        assert_eq!(
+            detect_language(Path::new("Test.PY")),
+            Some("python".to_string())
+        );
+        assert_eq!(detect_language(Path::new("test.unknown")), None);
+
+        assert_eq!(normalize_language("rs"), "rust");
+        assert_eq!(normalize_language(" Python "), "python");
+        assert_eq!(normalize_language("kotlin"), "kotlin");
+    }
+
     #[test]
     fn test_is_text_file() {
         let path = PathBuf::from("test.rs");
@@ -209,4 +320,51 @@ mod tests {
         let path = PathBuf::from("test.png");
         assert!(!is_text_file(&path));
     }
+
+    #[test]
+    fn test_read_text_limited() {
+        let text = read_text_limited("hello\nworld".as_bytes(), 11).unwrap();
+        assert_eq!(text, "hello\nworld");
+        assert!(read_text_limited("hello\nworld".as_bytes(), 10).is_err());
+        assert_eq!(read_text_limited(&b"a\xffb"[..], 10).unwrap(), "a\u{fffd}b");
+    }
+
+    #[test]
+    fn test_walk_files_ignore_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for file in [
+            "src/main.rs",
+            "node_modules/pkg/index.js",
+            "build/out.txt",
+            "notes/draft.md",
+            ".github/ci.yml",
+        ] {
+            let path = root.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, "x").unwrap();
+        }
+        fs::write(root.join(".gitignore"), "node_modules/\n").unwrap();
+        fs::write(root.join(".ignore"), "build/\n").unwrap();
+        fs::write(root.join("notes/.ssearchignore"), "draft.md\n").unwrap();
+
+        let relative = |files: Vec<PathBuf>| {
+            let mut names: Vec<String> = files
+                .iter()
+                .filter_map(|f| get_relative_path(root, f))
+                .filter(|f| !f.ends_with("ignore"))
+                .collect();
+            names.sort();
+            names
+        };
+
+        assert_eq!(
+            relative(walk_files(root, true).unwrap()),
+            vec![".github/ci.yml", "src/main.rs"]
+        );
+        assert_eq!(relative(walk_files(root, false).unwrap()).len(), 5);
+        // An explicitly named file is never filtered
+        let draft = root.join("notes/draft.md");
+        assert_eq!(walk_files(&draft, true).unwrap(), vec![draft]);
+    }
 }
@@ -0,0 +1,178 @@
+//! Git repository helpers for git-aware indexing.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::error::IndexError;
+
+/// Commit metadata for files in a git working tree.
+#[derive(Debug, Clone)]
+pub struct GitRepo {
+    /// Working tree root
+    pub root: PathBuf,
+    /// Current commit hash
+    pub commit: String,
+    /// Current branch (`HEAD` when detached)
+    pub branch: String,
+}
+
+impl GitRepo {
+    /// Discover the repository containing `path`.
+    pub fn discover(path: &Path) -> Result<Self, IndexError> {
+        let dir = if path.is_file() {
+            path.parent().unwrap_or(path)
+        } else {
+            path
+        };
+
+        let root = PathBuf::from(git(dir, &["rev-parse", "--show-toplevel"])?.trim());
+        let commit = git(dir, &["rev-parse", "HEAD"])?.trim().to_string();
+        let branch = git(dir, &["rev-parse", "--abbrev-ref", "HEAD"])?
+            .trim()
+            .to_string();
+
+        Ok(Self {
+            root,
+            commit,
+            branch,
+        })
+    }
+
+    /// List files under `path` that git does not ignore (tracked and untracked).
+    pub fn list_files(&self, path: &Path) -> Result<Vec<PathBuf>, IndexError> {
+        let output = git(
+            &self.root,
+            &[
+                "ls-files",
+                "-z",
+                "--cached",
+                "--others",
+                "--exclude-standard",
+                "--",
+                &path.to_string_lossy(),
+            ],
+        )?;
+
+        let mut files: Vec<PathBuf> = output
+            .split('\0')
+            .filter(|f| !f.is_empty())
+            .map(|f| self.root.join(f))
+            .filter(|f| f.is_file())
+            .collect();
+        files.sort();
+        files.dedup();
+        Ok(files)
+    }
+
+    /// Author of the most recent commit touching each file under `path`.
+    pub fn last_authors(&self, path: &Path) -> Result<HashMap<PathBuf, String>, IndexError> {
+        let output = git(
+            &self.root,
+            &[
+                "log",
+                "-z",
+                "--name-only",
+                "--format=%x00%an",
+                "--",
+                &path.to_string_lossy(),
+            ],
+        )?;
+        Ok(parse_last_authors(&output, &self.root))
+    }
+}
+
+/// Parse `git log -z --name-only --format=%x00%an` output; newest commit wins.
+///
+/// Fields are NUL-separated and file names unquoted: each commit is an empty
+/// field, its author, then its files, the first of them after a newline.
+fn parse_last_authors(output: &str, root: &Path) -> HashMap<PathBuf, String> {
+    let mut authors = HashMap::new();
+    let mut current: Option<&str> = None;
+    let mut fields = output.split('\0');
+
+    while let Some(field) = fields.next() {
+        if field.is_empty() {
+            current = fields.next();
+        } else if let Some(author) = current {
+            let file = field.strip_prefix('\n').unwrap_or(field);
+            authors
+                .entry(root.join(file))
+                .or_insert_with(|| author.to_string());
+        }
+    }
+
+    authors
+}
+
+fn git(dir: &Path, args: &[&str]) -> Result<String, IndexError> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .map_err(|e| IndexError::GitError(format!("failed to run git: {e}")))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(IndexError::GitError(stderr.trim().to_string()));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_last_authors_newest_wins() {
+        let output = "\0Alice\0\nsrc/lib.rs\0README.md\0\0Bob\0\0Bob\0\nsrc/lib.rs\0src/main.rs\0";
+        let authors = parse_last_authors(output, Path::new("/repo"));
+
+        assert_eq!(authors[Path::new("/repo/src/lib.rs")], "Alice");
+        assert_eq!(authors[Path::new("/repo/README.md")], "Alice");
+        assert_eq!(authors[Path::new("/repo/src/main.rs")], "Bob");
+        assert_eq!(authors.len(), 3);
+    }
+
+    #[test]
+    fn test_last_authors_unusual_names() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        let commit = |author: &str, files: &[&str]| {
+            for file in files {
+                std::fs::write(root.join(file), author).unwrap();
+            }
+            git(&root, &["add", "-A"]).unwrap();
+            let name = format!("user.name={author}");
+            git(
+                &root,
+                &[
+                    "-c",
+                    &name,
+                    "-c",
+                    "user.email=dev@example.com",
+                    "commit",
+                    "-q",
+                    "-m",
+                    "update",
+                ],
+            )
+            .unwrap();
+        };
+        git(&root, &["init", "-q"]).unwrap();
+        commit("Alice", &["café.md", "notes with spaces.md"]);
+        commit("Bob", &["café.md", "line\nbreak.md"]);
+
+        let authors = GitRepo::discover(&root)
+            .unwrap()
+            .last_authors(&root)
+            .unwrap();
+        // Quoted and escaped without -z, e.g. "caf\303\251.md"
+        assert_eq!(authors[&root.join("café.md")], "Bob");
+        assert_eq!(authors[&root.join("notes with spaces.md")], "Alice");
+        assert_eq!(authors[&root.join("line\nbreak.md")], "Bob");
+        assert_eq!(authors.len(), 3);
+    }
+}
@@ -1,9 +1,14 @@
 //! Utility modules.
 
 pub mod file;
+pub mod git;
 pub mod retry;
 pub mod text;
 
-pub use file::{calculate_checksum, calculate_file_checksum, is_text_file, read_file_content};
-pub use retry::{RetryConfig, RetryResult, Retryable, retry, with_retry};
-pub use text::has_meaningful_content;
+pub use file::{
+    calculate_checksum, calculate_file_checksum, is_text_file, read_file_content,
+    read_text_limited, walk_files,
+};
+pub use git::GitRepo;
+pub use retry::{RetryConfig, RetryResult, Retryable, retry, with_retry, with_retry_notify};
+pub use text::{has_meaningful_content, meaningful_length, slug};
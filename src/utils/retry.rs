@@ -100,11 +100,28 @@ impl Retryable for anyhow::Error {
 }
 
 /// Execute an async operation with exponential backoff retry.
-pub async fn with_retry<T, E, F, Fut>(config: &RetryConfig, mut operation: F) -> RetryResult<T, E>
+pub async fn with_retry<T, E, F, Fut>(config: &RetryConfig, operation: F) -> RetryResult<T, E>
 where
     E: Retryable + std::fmt::Debug,
     F: FnMut() -> Fut,
     Fut: Future<Output = Result<T, E>>,
+{
+    with_retry_notify(config, operation, |_, _, _| {}).await
+}
+
+/// Execute an async operation with exponential backoff retry, calling
+/// `on_retry` with the error, the failed attempt number and the delay before
+/// the next attempt.
+pub async fn with_retry_notify<T, E, F, Fut, N>(
+    config: &RetryConfig,
+    mut operation: F,
+    mut on_retry: N,
+) -> RetryResult<T, E>
+where
+    E: Retryable + std::fmt::Debug,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    N: FnMut(&E, u32, Duration),
 {
     let mut attempts = 0;
     let mut delay = config.initial_delay;
@@ -126,6 +143,7 @@ where
                 let jitter_ms = rand_jitter(delay.as_millis() as u64 / 4);
                 let actual_delay = delay + Duration::from_millis(jitter_ms);
 
+                on_retry(&error, attempts, actual_delay);
                 sleep(actual_delay).await;
 
                 // Increase delay for next attempt
@@ -249,4 +267,31 @@ mod tests {
         }
         assert_eq!(counter.load(Ordering::SeqCst), 3);
     }
+
+    #[tokio::test]
+    async fn test_retry_notify() {
+        let mut notified = Vec::new();
+        let counter = AtomicU32::new(0);
+        let result = with_retry_notify(
+            &RetryConfig::new(3).with_initial_delay(Duration::from_millis(10)),
+            || async {
+                if counter.fetch_add(1, Ordering::SeqCst) < 2 {
+                    Err(RetryableError("transient error".to_string()))
+                } else {
+                    Ok("success")
+                }
+            },
+            |error: &RetryableError, attempt, _| notified.push((error.0.clone(), attempt)),
+        )
+        .await;
+
+        assert!(matches!(result, RetryResult::Success("success")));
+        assert_eq!(
+            notified,
+            [
+                ("transient error".to_string(), 1),
+                ("transient error".to_string(), 2)
+            ]
+        );
+    }
 }
@@ -5,7 +5,22 @@ pub const MIN_CONTENT_LENGTH: usize = 50;
 
 /// Check if content has meaningful text (not just whitespace/punctuation).
 pub fn has_meaningful_content(content: &str) -> bool {
-    content.chars().filter(|c| !c.is_whitespace()).count() >= MIN_CONTENT_LENGTH
+    meaningful_length(content) >= MIN_CONTENT_LENGTH
+}
+
+/// Number of non-whitespace characters in `content`.
+pub fn meaningful_length(content: &str) -> usize {
+    content.chars().filter(|c| !c.is_whitespace()).count()
+}
+
+/// Lowercase `text` for use in a tag: runs of characters other than letters,
+/// digits, `_` and `.` become a single `-`.
+pub fn slug(text: &str) -> String {
+    text.to_lowercase()
+        .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
 }
 
 #[cfg(test)]
@@ -23,5 +38,14 @@ mod tests {
         assert!(has_meaningful_content(
             "This is a meaningful piece of content with enough characters."
         ));
+        assert_eq!(meaningful_length(" a b\n c "), 3);
+    }
+
+    #[test]
+    fn test_slug() {
+        assert_eq!(slug("Order Management"), "order-management");
+        assert_eq!(slug("  v1.2_beta / API!! "), "v1.2_beta-api");
+        assert_eq!(slug("Café"), "café");
+        assert_eq!(slug("--"), "");
     }
 }